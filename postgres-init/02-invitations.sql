@@ -0,0 +1,24 @@
+-- Create invitations table
+CREATE TABLE IF NOT EXISTS invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL,
+    branch_id UUID,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    invited_by UUID NOT NULL REFERENCES users(id),
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create indexes for better performance
+CREATE INDEX IF NOT EXISTS idx_invitations_account_id ON invitations(account_id);
+CREATE INDEX IF NOT EXISTS idx_invitations_email ON invitations(email);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_invitations_updated_at 
+    BEFORE UPDATE ON invitations 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,36 @@
+-- Create accounts table
+CREATE TABLE IF NOT EXISTS accounts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(200) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'ACTIVE',
+    suspended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_accounts_updated_at 
+    BEFORE UPDATE ON accounts 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Backfill: every account already referenced gets a row before the references are enforced
+INSERT INTO accounts (id, name)
+SELECT account_id, 'Account ' || account_id
+FROM (
+    SELECT account_id FROM users
+    UNION SELECT account_id FROM invitations
+    UNION SELECT account_id FROM audit_events
+) referenced
+ON CONFLICT (id) DO NOTHING;
+
+-- Every tenant-scoped row must point at an existing account
+ALTER TABLE users
+    ADD CONSTRAINT fk_users_account FOREIGN KEY (account_id) REFERENCES accounts(id);
+ALTER TABLE invitations
+    ADD CONSTRAINT fk_invitations_account FOREIGN KEY (account_id) REFERENCES accounts(id);
+ALTER TABLE audit_events
+    ADD CONSTRAINT fk_audit_events_account FOREIGN KEY (account_id) REFERENCES accounts(id);
+
+CREATE INDEX IF NOT EXISTS idx_users_account_id ON users(account_id);
@@ -0,0 +1,38 @@
+-- Create branches table
+CREATE TABLE IF NOT EXISTS branches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    name VARCHAR(200) NOT NULL,
+    address VARCHAR(500),
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC',
+    opening_hours JSONB NOT NULL DEFAULT '{}',
+    currency CHAR(3) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_branches_updated_at 
+    BEFORE UPDATE ON branches 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_branches_account_id ON branches(account_id) WHERE deleted_at IS NULL;
+
+-- Staff working at branches other than their home branch
+CREATE TABLE IF NOT EXISTS user_branches (
+    user_id UUID NOT NULL REFERENCES users(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, branch_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_branches_branch_id ON user_branches(branch_id);
+CREATE INDEX IF NOT EXISTS idx_users_branch_id ON users(branch_id);
+
+-- Home branches must exist
+ALTER TABLE users
+    ADD CONSTRAINT fk_users_branch FOREIGN KEY (branch_id) REFERENCES branches(id);
+ALTER TABLE invitations
+    ADD CONSTRAINT fk_invitations_branch FOREIGN KEY (branch_id) REFERENCES branches(id);
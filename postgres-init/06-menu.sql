@@ -0,0 +1,89 @@
+-- Create menu categories table
+CREATE TABLE IF NOT EXISTS menu_categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(500),
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create menu items table
+CREATE TABLE IF NOT EXISTS menu_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    category_id UUID NOT NULL REFERENCES menu_categories(id),
+    name VARCHAR(200) NOT NULL,
+    description VARCHAR(1000),
+    price NUMERIC(12, 2) NOT NULL CHECK (price >= 0),
+    tax_category VARCHAR(20) NOT NULL DEFAULT 'STANDARD',
+    allergens TEXT[] NOT NULL DEFAULT '{}',
+    dietary_tags TEXT[] NOT NULL DEFAULT '{}',
+    availability JSONB NOT NULL DEFAULT '[]',
+    is_available BOOLEAN NOT NULL DEFAULT TRUE,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Branch-specific prices overriding the base price of an item
+CREATE TABLE IF NOT EXISTS menu_item_prices (
+    item_id UUID NOT NULL REFERENCES menu_items(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    price NUMERIC(12, 2) NOT NULL CHECK (price >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (item_id, branch_id)
+);
+
+-- Create menu modifier groups table
+CREATE TABLE IF NOT EXISTS menu_modifier_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES menu_items(id),
+    name VARCHAR(100) NOT NULL,
+    min_select INTEGER NOT NULL DEFAULT 0 CHECK (min_select >= 0),
+    max_select INTEGER NOT NULL CHECK (max_select >= 1),
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_menu_modifier_groups_selection CHECK (min_select <= max_select)
+);
+
+-- Create menu modifier options table
+CREATE TABLE IF NOT EXISTS menu_modifier_options (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    group_id UUID NOT NULL REFERENCES menu_modifier_groups(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    price_delta NUMERIC(12, 2) NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL DEFAULT 0
+);
+
+-- Create triggers to automatically update updated_at
+CREATE TRIGGER update_menu_categories_updated_at 
+    BEFORE UPDATE ON menu_categories 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_menu_items_updated_at 
+    BEFORE UPDATE ON menu_items 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_menu_item_prices_updated_at 
+    BEFORE UPDATE ON menu_item_prices 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TRIGGER update_menu_modifier_groups_updated_at 
+    BEFORE UPDATE ON menu_modifier_groups 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_menu_categories_account_id ON menu_categories(account_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_menu_items_account_id ON menu_items(account_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_menu_items_category_id ON menu_items(category_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_menu_modifier_groups_item_id ON menu_modifier_groups(item_id);
+CREATE INDEX IF NOT EXISTS idx_menu_modifier_options_group_id ON menu_modifier_options(group_id);
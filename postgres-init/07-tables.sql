@@ -0,0 +1,48 @@
+-- Create floor areas table
+CREATE TABLE IF NOT EXISTS floor_areas (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    name VARCHAR(100) NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_floor_areas_updated_at 
+    BEFORE UPDATE ON floor_areas 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_floor_areas_branch_id ON floor_areas(branch_id) WHERE deleted_at IS NULL;
+
+-- Create dining tables table
+CREATE TABLE IF NOT EXISTS dining_tables (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    area_id UUID REFERENCES floor_areas(id),
+    label VARCHAR(50) NOT NULL,
+    seats INTEGER NOT NULL CHECK (seats > 0),
+    pos_x INTEGER NOT NULL DEFAULT 0,
+    pos_y INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'FREE',
+    waiter_id UUID REFERENCES users(id),
+    -- Bumped to invalidate the table's QR code and guest sessions
+    token_version INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_dining_tables_updated_at 
+    BEFORE UPDATE ON dining_tables 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_dining_tables_branch_label ON dining_tables(branch_id, label) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_dining_tables_area_id ON dining_tables(area_id) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_dining_tables_waiter_id ON dining_tables(waiter_id) WHERE deleted_at IS NULL;
@@ -0,0 +1,53 @@
+-- Create orders table
+CREATE TABLE IF NOT EXISTS orders (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    table_id UUID REFERENCES dining_tables(id),
+    created_by UUID NOT NULL REFERENCES users(id),
+    status VARCHAR(20) NOT NULL DEFAULT 'DRAFT'
+        CHECK (status IN ('DRAFT', 'PLACED', 'IN_PREPARATION', 'READY', 'SERVED', 'PAID', 'CANCELLED')),
+    note VARCHAR(500),
+    subtotal NUMERIC(12, 2) NOT NULL DEFAULT 0,
+    placed_at TIMESTAMPTZ,
+    ready_at TIMESTAMPTZ,
+    served_at TIMESTAMPTZ,
+    paid_at TIMESTAMPTZ,
+    cancelled_at TIMESTAMPTZ,
+    cancel_reason VARCHAR(500),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_orders_updated_at 
+    BEFORE UPDATE ON orders 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_orders_branch_created_at ON orders(branch_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_orders_open_table ON orders(table_id) WHERE status NOT IN ('PAID', 'CANCELLED');
+
+-- Order lines keep the name and price of the item at the time it was ordered
+CREATE TABLE IF NOT EXISTS order_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES orders(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL REFERENCES menu_items(id),
+    name VARCHAR(200) NOT NULL,
+    tax_category VARCHAR(20) NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    unit_price NUMERIC(12, 2) NOT NULL,
+    modifiers JSONB NOT NULL DEFAULT '[]',
+    note VARCHAR(500),
+    line_total NUMERIC(12, 2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_order_lines_updated_at 
+    BEFORE UPDATE ON order_lines 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_order_lines_order_id ON order_lines(order_id);
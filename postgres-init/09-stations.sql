@@ -0,0 +1,37 @@
+-- Create preparation stations table
+CREATE TABLE IF NOT EXISTS prep_stations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    name VARCHAR(100) NOT NULL,
+    roles TEXT[] NOT NULL DEFAULT '{}',
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_prep_stations_updated_at 
+    BEFORE UPDATE ON prep_stations 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_prep_stations_branch_id ON prep_stations(branch_id) WHERE deleted_at IS NULL;
+
+-- Menu items prepared at each station; an item goes to one station per branch
+CREATE TABLE IF NOT EXISTS prep_station_items (
+    station_id UUID NOT NULL REFERENCES prep_stations(id),
+    item_id UUID NOT NULL REFERENCES menu_items(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (station_id, item_id),
+    UNIQUE (branch_id, item_id)
+);
+
+-- Lines are routed to a station when their order is placed
+ALTER TABLE order_lines
+    ADD COLUMN IF NOT EXISTS station_id UUID REFERENCES prep_stations(id),
+    ADD COLUMN IF NOT EXISTS bumped_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_order_lines_station_pending ON order_lines(station_id, created_at) WHERE bumped_at IS NULL;
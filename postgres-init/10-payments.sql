@@ -0,0 +1,55 @@
+-- Create payments table
+CREATE TABLE IF NOT EXISTS payments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    order_id UUID NOT NULL REFERENCES orders(id),
+    created_by UUID NOT NULL REFERENCES users(id),
+    method VARCHAR(20) NOT NULL CHECK (method IN ('CASH', 'CARD', 'ONLINE')),
+    provider VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING'
+        CHECK (status IN ('PENDING', 'CAPTURED', 'FAILED', 'REFUNDED')),
+    amount NUMERIC(12, 2) NOT NULL CHECK (amount > 0),
+    tip NUMERIC(12, 2) NOT NULL DEFAULT 0 CHECK (tip >= 0),
+    refunded NUMERIC(12, 2) NOT NULL DEFAULT 0 CHECK (refunded >= 0 AND refunded <= amount + tip),
+    currency CHAR(3) NOT NULL,
+    idempotency_key VARCHAR(100) NOT NULL,
+    split VARCHAR(10) CHECK (split IN ('ITEMS', 'EQUAL')),
+    line_ids UUID[] NOT NULL DEFAULT '{}',
+    shares INTEGER CHECK (shares >= 2),
+    tendered NUMERIC(12, 2),
+    change NUMERIC(12, 2),
+    provider_reference VARCHAR(255),
+    failure_reason VARCHAR(500),
+    captured_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Retried captures find the original payment instead of charging twice
+    UNIQUE (account_id, idempotency_key)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_payments_updated_at 
+    BEFORE UPDATE ON payments 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_payments_order_id ON payments(order_id);
+CREATE INDEX IF NOT EXISTS idx_payments_branch_created_at ON payments(branch_id, created_at DESC);
+
+-- Refunds are only ever added, keeping a full trail for reconciliation
+CREATE TABLE IF NOT EXISTS payment_refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payment_id UUID NOT NULL REFERENCES payments(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    order_id UUID NOT NULL REFERENCES orders(id),
+    created_by UUID NOT NULL REFERENCES users(id),
+    amount NUMERIC(12, 2) NOT NULL CHECK (amount > 0),
+    reason VARCHAR(500) NOT NULL,
+    provider_reference VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_refunds_payment_id ON payment_refunds(payment_id);
+CREATE INDEX IF NOT EXISTS idx_payment_refunds_branch_created_at ON payment_refunds(branch_id, created_at DESC);
@@ -0,0 +1,55 @@
+-- Create register sessions table
+CREATE TABLE IF NOT EXISTS register_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    device_id VARCHAR(100) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'OPEN' CHECK (status IN ('OPEN', 'CLOSED')),
+    opened_by UUID NOT NULL REFERENCES users(id),
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    opening_float NUMERIC(12, 2) NOT NULL CHECK (opening_float >= 0),
+    closed_by UUID REFERENCES users(id),
+    closed_at TIMESTAMPTZ,
+    expected_cash NUMERIC(12, 2),
+    counted_cash NUMERIC(12, 2) CHECK (counted_cash >= 0),
+    discrepancy NUMERIC(12, 2),
+    z_number INTEGER,
+    close_note VARCHAR(500),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Z reports are numbered without gaps or duplicates per branch
+    UNIQUE (branch_id, z_number),
+    CHECK (status = 'OPEN' OR (closed_at IS NOT NULL AND counted_cash IS NOT NULL AND z_number IS NOT NULL))
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_register_sessions_updated_at 
+    BEFORE UPDATE ON register_sessions 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- A device runs at most one open session at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_register_sessions_open_device
+    ON register_sessions(branch_id, device_id) WHERE status = 'OPEN';
+CREATE INDEX IF NOT EXISTS idx_register_sessions_branch_opened_at ON register_sessions(branch_id, opened_at DESC);
+
+-- Cash put into or taken out of a drawer outside of sales
+CREATE TABLE IF NOT EXISTS register_movements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    session_id UUID NOT NULL REFERENCES register_sessions(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('CASH_IN', 'CASH_OUT')),
+    amount NUMERIC(12, 2) NOT NULL CHECK (amount > 0),
+    reason VARCHAR(500) NOT NULL,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_register_movements_session_id ON register_movements(session_id);
+
+-- Payments and refunds record the drawer they went through
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS register_session_id UUID REFERENCES register_sessions(id);
+ALTER TABLE payment_refunds ADD COLUMN IF NOT EXISTS register_session_id UUID REFERENCES register_sessions(id);
+
+CREATE INDEX IF NOT EXISTS idx_payments_register_session_id ON payments(register_session_id);
+CREATE INDEX IF NOT EXISTS idx_payment_refunds_register_session_id ON payment_refunds(register_session_id);
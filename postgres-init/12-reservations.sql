@@ -0,0 +1,42 @@
+-- Lets the exclusion constraint below combine table equality with time range overlap
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Create reservations table
+CREATE TABLE IF NOT EXISTS reservations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    table_id UUID NOT NULL REFERENCES dining_tables(id),
+    customer_id UUID REFERENCES users(id),
+    guest_name VARCHAR(100) NOT NULL,
+    guest_phone VARCHAR(50),
+    guest_email VARCHAR(255),
+    party_size INTEGER NOT NULL CHECK (party_size > 0),
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'REQUESTED'
+        CHECK (status IN ('REQUESTED', 'CONFIRMED', 'SEATED', 'NO_SHOW', 'CANCELLED')),
+    notes VARCHAR(500),
+    created_by UUID NOT NULL REFERENCES users(id),
+    confirmed_at TIMESTAMPTZ,
+    seated_at TIMESTAMPTZ,
+    cancelled_at TIMESTAMPTZ,
+    cancel_reason VARCHAR(500),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at),
+    -- A table cannot be held by two live reservations at overlapping times
+    CONSTRAINT reservations_no_double_booking EXCLUDE USING gist (
+        table_id WITH =,
+        tstzrange(starts_at, ends_at) WITH &&
+    ) WHERE (status IN ('REQUESTED', 'CONFIRMED', 'SEATED'))
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_reservations_updated_at 
+    BEFORE UPDATE ON reservations 
+    FOR EACH ROW 
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_reservations_branch_starts_at ON reservations(branch_id, starts_at);
+CREATE INDEX IF NOT EXISTS idx_reservations_customer_id ON reservations(customer_id);
@@ -0,0 +1,74 @@
+-- Create stock_items table
+CREATE TABLE IF NOT EXISTS stock_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    name VARCHAR(100) NOT NULL,
+    unit VARCHAR(10) NOT NULL CHECK (unit IN ('G', 'KG', 'ML', 'L', 'PIECE')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_stock_items_updated_at
+    BEFORE UPDATE ON stock_items
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stock_items_account_name ON stock_items(account_id, name) WHERE deleted_at IS NULL;
+
+-- Current level of each stock item per branch, kept in step with the ledger below
+CREATE TABLE IF NOT EXISTS stock_levels (
+    stock_item_id UUID NOT NULL REFERENCES stock_items(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    quantity NUMERIC(14, 3) NOT NULL DEFAULT 0,
+    low_stock_threshold NUMERIC(14, 3) CHECK (low_stock_threshold >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (stock_item_id, branch_id)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_stock_levels_updated_at
+    BEFORE UPDATE ON stock_levels
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Ingredients used by one portion of a menu item
+CREATE TABLE IF NOT EXISTS recipe_lines (
+    menu_item_id UUID NOT NULL REFERENCES menu_items(id),
+    stock_item_id UUID NOT NULL REFERENCES stock_items(id),
+    quantity NUMERIC(14, 3) NOT NULL CHECK (quantity > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (menu_item_id, stock_item_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_lines_stock_item_id ON recipe_lines(stock_item_id);
+
+-- Append-only ledger of every stock change; summing it per item and branch gives the level
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    stock_item_id UUID NOT NULL REFERENCES stock_items(id),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('RECEIPT', 'SALE', 'WASTE', 'ADJUSTMENT', 'COUNT')),
+    quantity NUMERIC(14, 3) NOT NULL,
+    balance_after NUMERIC(14, 3) NOT NULL,
+    order_id UUID REFERENCES orders(id),
+    reason VARCHAR(500),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_branch_created_at ON stock_movements(branch_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_stock_item_id ON stock_movements(stock_item_id);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_order_id ON stock_movements(order_id);
+
+-- Menu items 86'd at a branch because an ingredient ran out
+CREATE TABLE IF NOT EXISTS menu_item_outages (
+    item_id UUID NOT NULL REFERENCES menu_items(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    stock_item_id UUID REFERENCES stock_items(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (item_id, branch_id)
+);
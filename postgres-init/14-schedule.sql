@@ -0,0 +1,80 @@
+-- Create shifts table
+CREATE TABLE IF NOT EXISTS shifts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    user_id UUID REFERENCES users(id),
+    role VARCHAR(50) NOT NULL
+        CHECK (role IN ('GENERAL_MANAGER', 'MANAGER', 'WAITER', 'COOK', 'BARMAN', 'CASH_REGISTER')),
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    break_minutes INTEGER NOT NULL DEFAULT 0 CHECK (break_minutes >= 0),
+    notes VARCHAR(500),
+    published_at TIMESTAMPTZ,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at),
+    -- Nobody works two shifts at overlapping times (btree_gist comes with the reservations schema)
+    CONSTRAINT shifts_no_overlap EXCLUDE USING gist (
+        user_id WITH =,
+        tstzrange(starts_at, ends_at) WITH &&
+    ) WHERE (user_id IS NOT NULL)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_shifts_updated_at
+    BEFORE UPDATE ON shifts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_shifts_branch_starts_at ON shifts(branch_id, starts_at);
+CREATE INDEX IF NOT EXISTS idx_shifts_user_starts_at ON shifts(user_id, starts_at);
+
+-- Create time_entries table
+CREATE TABLE IF NOT EXISTS time_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    user_id UUID NOT NULL REFERENCES users(id),
+    shift_id UUID REFERENCES shifts(id) ON DELETE SET NULL,
+    clock_in_at TIMESTAMPTZ NOT NULL,
+    clock_out_at TIMESTAMPTZ,
+    method VARCHAR(20) NOT NULL CHECK (method IN ('SESSION', 'PIN')),
+    edited_by UUID REFERENCES users(id),
+    note VARCHAR(500),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (clock_out_at IS NULL OR clock_out_at > clock_in_at)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_time_entries_updated_at
+    BEFORE UPDATE ON time_entries
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- A staff member is clocked in at most once at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_open_user ON time_entries(user_id) WHERE clock_out_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_time_entries_branch_clock_in_at ON time_entries(branch_id, clock_in_at);
+
+-- Create time_breaks table
+CREATE TABLE IF NOT EXISTS time_breaks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    time_entry_id UUID NOT NULL REFERENCES time_entries(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+-- At most one running break per time entry
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_breaks_open_entry ON time_breaks(time_entry_id) WHERE ended_at IS NULL;
+
+-- PINs for clocking at a shared terminal
+CREATE TABLE IF NOT EXISTS clock_pins (
+    user_id UUID PRIMARY KEY REFERENCES users(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    pin_hash VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,151 @@
+-- Create loyalty_rules table
+CREATE TABLE IF NOT EXISTS loyalty_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID REFERENCES branches(id),
+    name VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('PER_AMOUNT', 'PER_ORDER')),
+    points NUMERIC(10, 2) NOT NULL CHECK (points > 0),
+    min_order_amount NUMERIC(12, 2) CHECK (min_order_amount >= 0),
+    valid_from TIMESTAMPTZ,
+    valid_until TIMESTAMPTZ,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (valid_until IS NULL OR valid_from IS NULL OR valid_until > valid_from)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_loyalty_rules_updated_at
+    BEFORE UPDATE ON loyalty_rules
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_rules_account_id ON loyalty_rules(account_id);
+
+-- Create loyalty_rewards table
+CREATE TABLE IF NOT EXISTS loyalty_rewards (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(500),
+    points_cost INTEGER NOT NULL CHECK (points_cost > 0),
+    discount_kind VARCHAR(20) NOT NULL CHECK (discount_kind IN ('PERCENT', 'FIXED')),
+    discount_value NUMERIC(12, 2) NOT NULL CHECK (discount_value > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_loyalty_rewards_updated_at
+    BEFORE UPDATE ON loyalty_rewards
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_rewards_account_id ON loyalty_rewards(account_id);
+
+-- Create vouchers table
+CREATE TABLE IF NOT EXISTS vouchers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    code VARCHAR(50) NOT NULL,
+    description VARCHAR(500),
+    discount_kind VARCHAR(20) NOT NULL CHECK (discount_kind IN ('PERCENT', 'FIXED')),
+    discount_value NUMERIC(12, 2) NOT NULL CHECK (discount_value > 0),
+    min_order_amount NUMERIC(12, 2) CHECK (min_order_amount >= 0),
+    valid_from TIMESTAMPTZ,
+    valid_until TIMESTAMPTZ,
+    max_uses INTEGER CHECK (max_uses > 0),
+    max_uses_per_customer INTEGER CHECK (max_uses_per_customer > 0),
+    -- Set on vouchers bought with points; only that customer may use them
+    customer_id UUID REFERENCES users(id),
+    reward_id UUID REFERENCES loyalty_rewards(id),
+    uses INTEGER NOT NULL DEFAULT 0 CHECK (uses >= 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (valid_until IS NULL OR valid_from IS NULL OR valid_until > valid_from),
+    CHECK (max_uses IS NULL OR uses <= max_uses)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_vouchers_updated_at
+    BEFORE UPDATE ON vouchers
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Codes are case-insensitive and stored upper case
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vouchers_account_code ON vouchers(account_id, code);
+CREATE INDEX IF NOT EXISTS idx_vouchers_customer_id ON vouchers(customer_id) WHERE customer_id IS NOT NULL;
+
+-- Customers and discounts on orders
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS customer_id UUID REFERENCES users(id);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS voucher_id UUID REFERENCES vouchers(id);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS discount_rule JSONB;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS discount NUMERIC(12, 2) NOT NULL DEFAULT 0 CHECK (discount >= 0);
+
+CREATE INDEX IF NOT EXISTS idx_orders_customer_id ON orders(customer_id) WHERE customer_id IS NOT NULL;
+
+-- Each use of a voucher on an order; released when the order is cancelled or the voucher removed
+CREATE TABLE IF NOT EXISTS voucher_redemptions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    voucher_id UUID NOT NULL REFERENCES vouchers(id),
+    order_id UUID NOT NULL REFERENCES orders(id),
+    customer_id UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    released_at TIMESTAMPTZ
+);
+
+-- An order carries at most one voucher at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_voucher_redemptions_open_order ON voucher_redemptions(order_id) WHERE released_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_voucher_redemptions_voucher_customer ON voucher_redemptions(voucher_id, customer_id);
+
+-- Points each customer holds, kept in step with the ledger below
+CREATE TABLE IF NOT EXISTS loyalty_balances (
+    customer_id UUID PRIMARY KEY REFERENCES users(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    points INTEGER NOT NULL DEFAULT 0 CHECK (points >= 0),
+    lifetime_points INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_loyalty_balances_updated_at
+    BEFORE UPDATE ON loyalty_balances
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Append-only ledger of every points change; summing it per customer gives the balance
+CREATE TABLE IF NOT EXISTS loyalty_ledger (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    customer_id UUID NOT NULL REFERENCES users(id),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('EARN', 'REDEEM', 'ADJUST')),
+    points INTEGER NOT NULL CHECK (points <> 0),
+    balance_after INTEGER NOT NULL CHECK (balance_after >= 0),
+    order_id UUID REFERENCES orders(id),
+    voucher_id UUID REFERENCES vouchers(id),
+    reason VARCHAR(500),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_ledger_customer_created_at ON loyalty_ledger(customer_id, created_at DESC);
+-- An order earns points once
+CREATE UNIQUE INDEX IF NOT EXISTS idx_loyalty_ledger_order_earn ON loyalty_ledger(order_id) WHERE kind = 'EARN';
+
+-- Corrections are new entries; rows already written never change
+CREATE OR REPLACE FUNCTION reject_loyalty_ledger_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'loyalty_ledger is append-only';
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER loyalty_ledger_append_only
+    BEFORE UPDATE OR DELETE ON loyalty_ledger
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_loyalty_ledger_change();
@@ -0,0 +1,27 @@
+-- Create tax_jurisdictions table; rates hold one entry per tax category of the menu
+CREATE TABLE IF NOT EXISTS tax_jurisdictions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    name VARCHAR(100) NOT NULL,
+    prices_include_tax BOOLEAN NOT NULL DEFAULT TRUE,
+    rounding VARCHAR(20) NOT NULL DEFAULT 'LINE' CHECK (rounding IN ('LINE', 'INVOICE')),
+    rates JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_tax_jurisdictions_updated_at
+    BEFORE UPDATE ON tax_jurisdictions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_tax_jurisdictions_account_id ON tax_jurisdictions(account_id) WHERE deleted_at IS NULL;
+
+-- Branches charge the taxes of their jurisdiction
+ALTER TABLE branches ADD COLUMN IF NOT EXISTS tax_jurisdiction_id UUID REFERENCES tax_jurisdictions(id);
+
+-- Orders keep the tax rules of their branch from when they were opened
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS tax_policy JSONB;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS tax NUMERIC(12, 2) NOT NULL DEFAULT 0 CHECK (tax >= 0);
@@ -0,0 +1,39 @@
+-- Legal details and texts branches print on their receipts
+ALTER TABLE branches ADD COLUMN IF NOT EXISTS branding JSONB NOT NULL DEFAULT '{}';
+
+-- Create receipts table; invoice numbers run without gaps per branch
+CREATE TABLE IF NOT EXISTS receipts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    order_id UUID NOT NULL UNIQUE REFERENCES orders(id),
+    invoice_number INTEGER NOT NULL CHECK (invoice_number > 0),
+    document JSONB NOT NULL,
+    print_count INTEGER NOT NULL DEFAULT 0 CHECK (print_count >= 0),
+    last_printed_at TIMESTAMPTZ,
+    issued_by UUID NOT NULL REFERENCES users(id),
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (branch_id, invoice_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_receipts_branch_issued_at ON receipts(branch_id, issued_at);
+
+-- Issued receipts are kept as they were; only reprints are counted
+CREATE OR REPLACE FUNCTION reject_receipt_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE'
+        OR NEW.invoice_number <> OLD.invoice_number
+        OR NEW.branch_id <> OLD.branch_id
+        OR NEW.order_id <> OLD.order_id
+        OR NEW.document <> OLD.document THEN
+        RAISE EXCEPTION 'Issued receipts cannot be changed or deleted';
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+CREATE TRIGGER receipts_immutable
+    BEFORE UPDATE OR DELETE ON receipts
+    FOR EACH ROW
+    EXECUTE FUNCTION reject_receipt_change();
@@ -0,0 +1,2 @@
+-- Locale branches format amounts in
+ALTER TABLE branches ADD COLUMN IF NOT EXISTS locale VARCHAR(10) NOT NULL DEFAULT 'en-US';
@@ -0,0 +1,32 @@
+-- Create reviews table; customers rate a paid order or a visit they were seated for, once each
+CREATE TABLE IF NOT EXISTS reviews (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    branch_id UUID NOT NULL REFERENCES branches(id),
+    customer_id UUID NOT NULL REFERENCES users(id),
+    order_id UUID UNIQUE REFERENCES orders(id),
+    reservation_id UUID UNIQUE REFERENCES reservations(id),
+    -- Who served the customer: the order's creator or the waiter of the reserved table
+    staff_id UUID REFERENCES users(id),
+    rating INTEGER NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    comment VARCHAR(2000),
+    anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR(20) NOT NULL DEFAULT 'VISIBLE' CHECK (status IN ('VISIBLE', 'FLAGGED', 'HIDDEN')),
+    moderation_note VARCHAR(500),
+    moderated_by UUID REFERENCES users(id),
+    moderated_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(order_id, reservation_id) = 1)
+);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_reviews_updated_at
+    BEFORE UPDATE ON reviews
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_reviews_branch_created_at ON reviews(branch_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reviews_customer_id ON reviews(customer_id);
+CREATE INDEX IF NOT EXISTS idx_reviews_staff_id ON reviews(staff_id) WHERE staff_id IS NOT NULL;
@@ -0,0 +1,30 @@
+-- Create outbox table; domain events written in the same transaction as the change they describe
+CREATE TABLE IF NOT EXISTS outbox (
+    -- Idempotency key of the event, passed on to every subscriber
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    kind VARCHAR(50) NOT NULL,
+    aggregate_type VARCHAR(50) NOT NULL,
+    aggregate_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    -- Who caused the event and from where, for subscribers that audit it
+    metadata JSONB NOT NULL DEFAULT '{}',
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'DISPATCHED', 'FAILED')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    dispatched_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_due ON outbox(next_attempt_at) WHERE status = 'PENDING';
+CREATE INDEX IF NOT EXISTS idx_outbox_aggregate ON outbox(aggregate_type, aggregate_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_outbox_dispatched_at ON outbox(dispatched_at) WHERE status = 'DISPATCHED';
+
+-- Subscribers that have handled an event, so a retried event is not handled twice by the same subscriber
+CREATE TABLE IF NOT EXISTS outbox_handled (
+    event_id UUID NOT NULL REFERENCES outbox(id) ON DELETE CASCADE,
+    subscriber VARCHAR(50) NOT NULL,
+    handled_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, subscriber)
+);
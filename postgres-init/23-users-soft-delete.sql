@@ -0,0 +1,21 @@
+-- Soft-deleted users: status follows deleted_at and emails are unique among live users only
+
+-- Deleted users must not block re-registration of their email
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_email_key;
+
+ALTER TABLE users ALTER COLUMN status SET DEFAULT 'ACTIVE';
+
+-- Backfill: users marked inactive without a deletion time were deleted at their last update,
+-- then status is derived from deleted_at
+UPDATE users SET deleted_at = updated_at
+    WHERE UPPER(status) = 'INACTIVE' AND deleted_at IS NULL;
+UPDATE users SET status = CASE WHEN deleted_at IS NULL THEN 'ACTIVE' ELSE 'INACTIVE' END
+    WHERE status IS DISTINCT FROM CASE WHEN deleted_at IS NULL THEN 'ACTIVE' ELSE 'INACTIVE' END;
+
+-- Soft-deleted users are inactive and inactive users are soft-deleted
+ALTER TABLE users DROP CONSTRAINT IF EXISTS chk_users_status_deleted_at;
+ALTER TABLE users
+    ADD CONSTRAINT chk_users_status_deleted_at CHECK ((status = 'INACTIVE') = (deleted_at IS NOT NULL));
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_users_email_active ON users(email) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at) WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,17 @@
+-- Anonymized users keep their row, without a password
+ALTER TABLE users ALTER COLUMN password_hash DROP NOT NULL;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS anonymized_at TIMESTAMPTZ;
+
+-- Invitations point at the user who accepted them, since their email may later be registered by someone else
+ALTER TABLE invitations ADD COLUMN IF NOT EXISTS accepted_user_id UUID REFERENCES users(id);
+
+-- Backfill: the user of the account created with the invitation's email when it was accepted
+UPDATE invitations i SET accepted_user_id = (
+    SELECT u.id FROM users u
+    WHERE u.account_id = i.account_id AND u.email = i.email AND u.created_at <= i.accepted_at
+    ORDER BY u.created_at DESC
+    LIMIT 1
+)
+WHERE i.status = 'ACCEPTED' AND i.accepted_user_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_invitations_accepted_user_id ON invitations(accepted_user_id) WHERE accepted_user_id IS NOT NULL;
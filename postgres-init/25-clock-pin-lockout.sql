@@ -0,0 +1,3 @@
+-- Lock a clock PIN after too many wrong entries in a row
+ALTER TABLE clock_pins ADD COLUMN IF NOT EXISTS failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE clock_pins ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+-- Currency of an order's amounts, copied from its branch when the order is opened
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS currency CHAR(3);
+UPDATE orders SET currency = branches.currency FROM branches WHERE branches.id = orders.branch_id AND orders.currency IS NULL;
+ALTER TABLE orders ALTER COLUMN currency SET NOT NULL;
@@ -0,0 +1,3 @@
+-- Domain event an audit entry was recorded from, so an event delivered twice is audited once
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS event_id UUID;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_audit_events_event_id ON audit_events(event_id) WHERE event_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- State of the record before the change an event describes, for subscribers recording what changed
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS previous JSONB;
@@ -0,0 +1,4 @@
+-- Refunds are reserved as PENDING before the provider is asked, then CONFIRMED or FAILED with its answer
+ALTER TABLE payment_refunds ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'CONFIRMED'
+    CHECK (status IN ('PENDING', 'CONFIRMED', 'FAILED'));
+ALTER TABLE payment_refunds ADD COLUMN IF NOT EXISTS failure_reason VARCHAR(500);
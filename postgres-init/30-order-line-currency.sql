@@ -0,0 +1,4 @@
+-- Currency of a line's amounts, the currency of its order, so the prices are read as money
+ALTER TABLE order_lines ADD COLUMN IF NOT EXISTS currency CHAR(3);
+UPDATE order_lines SET currency = orders.currency FROM orders WHERE orders.id = order_lines.order_id AND order_lines.currency IS NULL;
+ALTER TABLE order_lines ALTER COLUMN currency SET NOT NULL;
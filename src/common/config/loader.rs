@@ -0,0 +1,405 @@
+use std::{env, path::PathBuf};
+
+use toml::{Table, Value};
+
+/// Built-in defaults, the lowest layer
+pub(super) const DEFAULTS: &str = include_str!("defaults.toml");
+
+/// Every setting with the environment variable overriding it
+const SETTINGS: &[(&str, &str)] = &[
+    ("environment", "APP_ENV"),
+    ("server.host", "HOST"),
+    ("server.port", "PORT"),
+    ("server.trusted_proxies", "TRUSTED_PROXIES"),
+    ("database.host", "DATABASE_HOST"),
+    ("database.port", "DATABASE_PORT"),
+    ("database.database", "DATABASE_NAME"),
+    ("database.username", "DATABASE_USER"),
+    ("database.password", "DATABASE_PASSWORD"),
+    ("database.max_connections", "DATABASE_MAX_CONNECTIONS"),
+    ("database.min_connections", "DATABASE_MIN_CONNECTIONS"),
+    ("database.acquire_timeout_seconds", "DATABASE_ACQUIRE_TIMEOUT"),
+    ("database.idle_timeout_seconds", "DATABASE_IDLE_TIMEOUT"),
+    ("logging.level", "RUST_LOG"),
+    ("session.secret", "SESSION_SECRET"),
+    ("session.redis_url", "REDIS_URL"),
+    ("session.cookie_name", "SESSION_COOKIE_NAME"),
+    ("session.cookie_domain", "SESSION_COOKIE_DOMAIN"),
+    ("session.cookie_secure", "SESSION_COOKIE_SECURE"),
+    ("session.cookie_same_site", "SESSION_COOKIE_SAME_SITE"),
+    ("session.max_age_seconds", "SESSION_MAX_AGE_SECONDS"),
+    ("invitation.ttl_hours", "INVITATION_TTL_HOURS"),
+    ("invitation.accept_url", "INVITATION_ACCEPT_URL"),
+    ("privacy.retention_days", "USER_RETENTION_DAYS"),
+    ("privacy.purge_interval_seconds", "USER_PURGE_INTERVAL_SECONDS"),
+    ("table.qr_url", "TABLE_QR_URL"),
+    ("events.bus", "EVENT_BUS"),
+    ("events.channel", "EVENT_CHANNEL"),
+    ("events.buffer_size", "EVENT_BUFFER_SIZE"),
+    ("payment.card_provider", "PAYMENT_CARD_PROVIDER"),
+    ("payment.terminal_url", "PAYMENT_TERMINAL_URL"),
+    ("payment.terminal_api_key", "PAYMENT_TERMINAL_API_KEY"),
+    ("payment.online_provider", "PAYMENT_ONLINE_PROVIDER"),
+    ("payment.online_url", "PAYMENT_ONLINE_URL"),
+    ("payment.online_api_key", "PAYMENT_ONLINE_API_KEY"),
+    ("payment.timeout_seconds", "PAYMENT_TIMEOUT_SECONDS"),
+    ("reservation.slot_minutes", "RESERVATION_SLOT_MINUTES"),
+    ("reservation.default_duration_minutes", "RESERVATION_DURATION_MINUTES"),
+    ("reservation.max_days_ahead", "RESERVATION_MAX_DAYS_AHEAD"),
+    ("schedule.daily_overtime_hours", "SCHEDULE_DAILY_OVERTIME_HOURS"),
+    ("schedule.weekly_overtime_hours", "SCHEDULE_WEEKLY_OVERTIME_HOURS"),
+    ("schedule.pin_max_attempts", "SCHEDULE_PIN_MAX_ATTEMPTS"),
+    ("schedule.pin_lockout_minutes", "SCHEDULE_PIN_LOCKOUT_MINUTES"),
+    ("mail.provider", "MAIL_PROVIDER"),
+    ("mail.url", "MAIL_URL"),
+    ("mail.api_key", "MAIL_API_KEY"),
+    ("mail.from", "MAIL_FROM"),
+    ("mail.timeout_seconds", "MAIL_TIMEOUT_SECONDS"),
+    ("money.rate_provider", "EXCHANGE_RATE_PROVIDER"),
+    ("money.rates", "EXCHANGE_RATES"),
+    ("money.rate_url", "EXCHANGE_RATE_URL"),
+    ("money.rate_api_key", "EXCHANGE_RATE_API_KEY"),
+    ("money.timeout_seconds", "EXCHANGE_RATE_TIMEOUT_SECONDS"),
+    ("notification.poll_interval_seconds", "NOTIFICATION_POLL_INTERVAL_SECONDS"),
+    ("notification.batch_size", "NOTIFICATION_BATCH_SIZE"),
+    ("notification.max_attempts", "NOTIFICATION_MAX_ATTEMPTS"),
+    ("notification.backoff_seconds", "NOTIFICATION_BACKOFF_SECONDS"),
+    ("notification.max_backoff_seconds", "NOTIFICATION_MAX_BACKOFF_SECONDS"),
+    ("notification.push_provider", "NOTIFICATION_PUSH_PROVIDER"),
+    ("notification.push_url", "NOTIFICATION_PUSH_URL"),
+    ("notification.push_api_key", "NOTIFICATION_PUSH_API_KEY"),
+    ("notification.timeout_seconds", "NOTIFICATION_TIMEOUT_SECONDS"),
+    ("webhook.poll_interval_seconds", "WEBHOOK_POLL_INTERVAL_SECONDS"),
+    ("webhook.batch_size", "WEBHOOK_BATCH_SIZE"),
+    ("webhook.max_attempts", "WEBHOOK_MAX_ATTEMPTS"),
+    ("webhook.backoff_seconds", "WEBHOOK_BACKOFF_SECONDS"),
+    ("webhook.max_backoff_seconds", "WEBHOOK_MAX_BACKOFF_SECONDS"),
+    ("webhook.disable_after_failures", "WEBHOOK_DISABLE_AFTER_FAILURES"),
+    ("webhook.allow_http", "WEBHOOK_ALLOW_HTTP"),
+    ("webhook.timeout_seconds", "WEBHOOK_TIMEOUT_SECONDS"),
+    ("outbox.poll_interval_seconds", "OUTBOX_POLL_INTERVAL_SECONDS"),
+    ("outbox.batch_size", "OUTBOX_BATCH_SIZE"),
+    ("outbox.max_attempts", "OUTBOX_MAX_ATTEMPTS"),
+    ("outbox.backoff_seconds", "OUTBOX_BACKOFF_SECONDS"),
+    ("outbox.max_backoff_seconds", "OUTBOX_MAX_BACKOFF_SECONDS"),
+    ("outbox.lease_seconds", "OUTBOX_LEASE_SECONDS"),
+    ("outbox.retention_days", "OUTBOX_RETENTION_DAYS"),
+];
+
+/// Where settings come from besides the built-in defaults and the environment
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    /// TOML file overriding the defaults
+    pub file: Option<PathBuf>,
+    /// `key=value` pairs from the command line, overriding everything else
+    pub overrides: Vec<(String, String)>,
+}
+
+/// Why the configuration cannot be used
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Cannot read config file {path}: {message}")]
+    File { path: String, message: String },
+
+    #[error("{origin} sets unknown setting {key}")]
+    UnknownSetting { origin: String, key: String },
+
+    #[error("{origin} sets {key} to {value:?}, expected {expected}")]
+    InvalidValue { origin: String, key: String, value: String, expected: &'static str },
+
+    #[error("{key}: {message}")]
+    Invalid { key: String, message: String },
+
+    #[error("{key} must be changed from its default in production")]
+    DefaultSecret { key: String },
+}
+
+/// Every problem found in the configuration, reported together
+#[derive(Debug)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl std::fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid configuration:")?;
+        for error in &self.0 {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Merge the layers into one table: defaults, then the file, then the environment, then the command line
+pub(super) fn layered(sources: &ConfigSources) -> Result<Table, Vec<ConfigError>> {
+    let defaults: Table = DEFAULTS.parse().expect("Built-in configuration defaults are valid TOML");
+    let mut table = defaults.clone();
+    let mut errors = Vec::new();
+
+    if let Some(ref path) = sources.file {
+        let origin = path.display().to_string();
+        let file = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| text.parse::<Table>().map_err(|e| e.to_string()));
+        match file {
+            Ok(file) => {
+                for (key, value) in leaves(&file, "") {
+                    if let Err(e) = set_checked(&mut table, &origin, &key, value) {
+                        errors.push(e);
+                    }
+                }
+            }
+            Err(message) => errors.push(ConfigError::File { path: origin, message }),
+        }
+    }
+
+    for (key, var) in SETTINGS {
+        let Ok(raw) = env::var(var) else { continue };
+        // An empty optional setting, e.g. `SESSION_COOKIE_DOMAIN=`, unsets it
+        if raw.is_empty() && get(&defaults, key).is_none() {
+            remove(&mut table, key);
+            continue;
+        }
+        if let Err(e) = set_raw(&mut table, &format!("environment variable {}", var), key, &raw) {
+            errors.push(e);
+        }
+    }
+
+    for (key, raw) in &sources.overrides {
+        if let Err(e) = set_raw(&mut table, "command line", key, raw) {
+            errors.push(e);
+        }
+    }
+
+    if errors.is_empty() { Ok(table) } else { Err(errors) }
+}
+
+/// The setting an error from deserializing a serialized table is about, from the offending line
+pub(super) fn key_at(text: &str, offset: usize) -> String {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let name = text[line_start..].split('=').next().unwrap_or_default().trim();
+    let section = before
+        .lines()
+        .rev()
+        .find(|line| line.starts_with('['))
+        .map(|line| line.trim_matches(|c| c == '[' || c == ']'));
+
+    match section {
+        Some(section) if !name.starts_with('[') => format!("{}.{}", section, name),
+        _ => name.to_string(),
+    }
+}
+
+/// Set a value parsed from text to the type of the setting's default
+fn set_raw(table: &mut Table, origin: &str, key: &str, raw: &str) -> Result<(), ConfigError> {
+    if !is_known(key) {
+        return Err(ConfigError::UnknownSetting { origin: origin.to_string(), key: key.to_string() });
+    }
+
+    let invalid = |expected| ConfigError::InvalidValue {
+        origin: origin.to_string(),
+        key: key.to_string(),
+        value: raw.to_string(),
+        expected,
+    };
+    let value = match get(table, key) {
+        Some(Value::Integer(_)) => Value::Integer(raw.trim().parse().map_err(|_| invalid("an integer"))?),
+        Some(Value::Float(_)) => Value::Float(raw.trim().parse().map_err(|_| invalid("a number"))?),
+        Some(Value::Boolean(_)) => Value::Boolean(raw.trim().parse().map_err(|_| invalid("true or false"))?),
+        _ => Value::String(raw.to_string()),
+    };
+
+    set(table, key, value);
+    Ok(())
+}
+
+/// Set a value from a file, which must have the type of the setting's default
+fn set_checked(table: &mut Table, origin: &str, key: &str, value: Value) -> Result<(), ConfigError> {
+    if !is_known(key) {
+        return Err(ConfigError::UnknownSetting { origin: origin.to_string(), key: key.to_string() });
+    }
+
+    let expected = match (get(table, key), &value) {
+        (Some(Value::Integer(_)), Value::Integer(_))
+        | (Some(Value::Float(_)), Value::Float(_) | Value::Integer(_))
+        | (Some(Value::Boolean(_)), Value::Boolean(_))
+        | (Some(Value::String(_)) | None, Value::String(_)) => None,
+        (Some(Value::Integer(_)), _) => Some("an integer"),
+        (Some(Value::Float(_)), _) => Some("a number"),
+        (Some(Value::Boolean(_)), _) => Some("true or false"),
+        _ => Some("a string"),
+    };
+    if let Some(expected) = expected {
+        let value = match value {
+            Value::String(text) => text,
+            other => other.to_string(),
+        };
+        return Err(ConfigError::InvalidValue { origin: origin.to_string(), key: key.to_string(), value, expected });
+    }
+
+    set(table, key, value);
+    Ok(())
+}
+
+fn is_known(key: &str) -> bool {
+    SETTINGS.iter().any(|(setting, _)| *setting == key)
+}
+
+/// The settings of a table as dotted keys
+fn leaves(table: &Table, prefix: &str) -> Vec<(String, Value)> {
+    table
+        .iter()
+        .flat_map(|(name, value)| {
+            let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            match value {
+                Value::Table(nested) => leaves(nested, &key),
+                _ => vec![(key, value.clone())],
+            }
+        })
+        .collect()
+}
+
+fn get<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let (section, name) = match key.split_once('.') {
+        Some((section, name)) => (Some(section), name),
+        None => (None, key),
+    };
+    match section {
+        Some(section) => table.get(section)?.as_table()?.get(name),
+        None => table.get(name),
+    }
+}
+
+fn remove(table: &mut Table, key: &str) {
+    match key.split_once('.') {
+        Some((section, name)) => {
+            if let Some(Value::Table(section)) = table.get_mut(section) {
+                section.remove(name);
+            }
+        }
+        None => {
+            table.remove(key);
+        }
+    }
+}
+
+fn set(table: &mut Table, key: &str, value: Value) {
+    match key.split_once('.') {
+        Some((section, name)) => {
+            let section = table
+                .entry(section.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(section) = section {
+                section.insert(name.to_string(), value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config file with the given content, removed when dropped
+    struct File(PathBuf);
+
+    impl File {
+        fn new(content: &str) -> Self {
+            let path = env::temp_dir().join(format!("rust_api_config_{}.toml", uuid::Uuid::new_v4().simple()));
+            std::fs::write(&path, content).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for File {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn sources(file: Option<&File>, overrides: &[(&str, &str)]) -> ConfigSources {
+        ConfigSources {
+            file: file.map(|file| file.0.clone()),
+            overrides: overrides.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    fn integer(table: &Table, key: &str) -> i64 {
+        get(table, key).and_then(Value::as_integer).unwrap()
+    }
+
+    #[test]
+    fn each_layer_overrides_the_one_before() {
+        // Only settings no other test depends on are set in the shared environment
+        env::set_var("OUTBOX_RETENTION_DAYS", "11");
+        env::set_var("MAIL_TIMEOUT_SECONDS", "12");
+        let file = File::new("[invitation]\nttl_hours = 1\n[outbox]\nretention_days = 2\n[mail]\ntimeout_seconds = 3\n");
+
+        let table = layered(&sources(Some(&file), &[("mail.timeout_seconds", "21")]));
+        env::remove_var("OUTBOX_RETENTION_DAYS");
+        env::remove_var("MAIL_TIMEOUT_SECONDS");
+        let table = table.unwrap();
+
+        assert_eq!(integer(&table, "reservation.max_days_ahead"), 60, "default");
+        assert_eq!(integer(&table, "invitation.ttl_hours"), 1, "file over default");
+        assert_eq!(integer(&table, "outbox.retention_days"), 11, "environment over file");
+        assert_eq!(integer(&table, "mail.timeout_seconds"), 21, "command line over environment");
+    }
+
+    #[test]
+    fn values_that_do_not_parse_are_refused_instead_of_defaulted() {
+        let mut table: Table = DEFAULTS.parse().unwrap();
+        let error = set_raw(&mut table, "environment variable PORT", "server.port", "abc").unwrap_err();
+        assert!(
+            matches!(&error, ConfigError::InvalidValue { origin, key, value, expected: "an integer" }
+                if origin == "environment variable PORT" && key == "server.port" && value == "abc"),
+            "{:?}",
+            error
+        );
+        assert_eq!(error.to_string(), r#"environment variable PORT sets server.port to "abc", expected an integer"#);
+        assert_eq!(integer(&table, "server.port"), 3000);
+
+        let file = File::new("[server]\nport = \"3000\"\n[session]\ncookie_secure = 1\n");
+        let errors = layered(&sources(Some(&file), &[("webhook.allow_http", "yes")])).unwrap_err();
+        let refused: Vec<_> = errors
+            .iter()
+            .map(|error| match error {
+                ConfigError::InvalidValue { key, expected, .. } => (key.as_str(), *expected),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            refused,
+            [("server.port", "an integer"), ("session.cookie_secure", "true or false"), ("webhook.allow_http", "true or false")]
+        );
+    }
+
+    #[test]
+    fn unknown_settings_are_refused_wherever_they_are_set() {
+        let file = File::new("[server]\nhots = \"0.0.0.0\"\n");
+        let errors = layered(&sources(Some(&file), &[("events.buss", "memory")])).unwrap_err();
+        let unknown: Vec<_> = errors
+            .iter()
+            .map(|error| match error {
+                ConfigError::UnknownSetting { origin, key } => (origin.clone(), key.as_str()),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(unknown, [(file.0.display().to_string(), "server.hots"), ("command line".to_string(), "events.buss")]);
+    }
+
+    #[test]
+    fn an_unreadable_file_is_reported() {
+        let file = File::new("[server\n");
+        let errors = layered(&sources(Some(&file), &[])).unwrap_err();
+        assert!(matches!(&errors[..], [ConfigError::File { path, .. }] if *path == file.0.display().to_string()), "{:?}", errors);
+
+        let missing = env::temp_dir().join("rust_api_config_missing.toml");
+        let errors = layered(&ConfigSources { file: Some(missing), overrides: Vec::new() }).unwrap_err();
+        assert!(matches!(&errors[..], [ConfigError::File { .. }]), "{:?}", errors);
+    }
+}
@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::context::TrustedProxies;
+
+mod loader;
+mod secret;
+
+pub use loader::{ConfigError, ConfigErrors, ConfigSources};
+pub use secret::Secret;
+
+/// Shortest session secret accepted in production
+const MIN_SESSION_SECRET_LENGTH: usize = 32;
+
+/// Application configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub environment: Environment,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub logging: LoggingConfig,
+    pub session: SessionConfig,
+    pub invitation: InvitationConfig,
+    pub privacy: PrivacyConfig,
+    pub table: TableConfig,
+    pub events: EventsConfig,
+    pub payment: PaymentConfig,
+    pub reservation: ReservationConfig,
+    pub schedule: ScheduleConfig,
+    pub mail: MailConfig,
+    pub money: MoneyConfig,
+    pub notification: NotificationConfig,
+    pub webhook: WebhookConfig,
+    pub outbox: OutboxConfig,
+}
+
+/// Where the API runs; production refuses the default secrets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Development,
+    Production,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Reverse proxies whose `X-Forwarded-For` header is believed, e.g. `10.0.0.0/8,127.0.0.1`
+    pub trusted_proxies: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+    pub database: String,
+    pub username: String,
+    pub password: Secret<String>,
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub acquire_timeout_seconds: u64,
+    pub idle_timeout_seconds: u64,
+}
+
+impl DatabaseConfig {
+    /// Build the database URL from individual components
+    pub fn url(&self) -> String {
+        format!(
+            "postgres://{}:{}@{}:{}/{}",
+            self.username, self.password.expose(), self.host, self.port, self.database
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    pub level: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    pub secret: Secret<String>,
+    /// May carry the Redis password, e.g. `redis://:password@host:6379`
+    pub redis_url: Secret<String>,
+    pub cookie_name: String,
+    #[serde(default)]
+    pub cookie_domain: Option<String>,
+    pub cookie_secure: bool,
+    pub cookie_same_site: String,
+    pub max_age_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationConfig {
+    pub ttl_hours: i64,
+    pub accept_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    pub retention_days: i64,
+    pub purge_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableConfig {
+    pub qr_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventsConfig {
+    /// `redis` to share events between instances, `memory` for a single process
+    pub bus: String,
+    pub channel: String,
+    pub buffer_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentConfig {
+    /// `terminal` to charge cards through the terminal gateway, `fake` to approve everything
+    pub card_provider: String,
+    pub terminal_url: String,
+    pub terminal_api_key: Secret<String>,
+    /// `online` to charge payment tokens through the online gateway, `fake` to approve everything
+    pub online_provider: String,
+    pub online_url: String,
+    pub online_api_key: Secret<String>,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationConfig {
+    /// Minutes between bookable start times
+    pub slot_minutes: i64,
+    pub default_duration_minutes: i64,
+    pub max_days_ahead: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// Hours worked in a day before the rest counts as overtime
+    pub daily_overtime_hours: i64,
+    /// Regular hours worked in a week before the rest counts as overtime
+    pub weekly_overtime_hours: i64,
+    /// Wrong clock PIN entries in a row before the PIN is locked
+    pub pin_max_attempts: i32,
+    /// How long a locked clock PIN stays locked
+    pub pin_lockout_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailConfig {
+    /// `http` to send through the mail gateway, `log` to only log messages
+    pub provider: String,
+    pub url: String,
+    pub api_key: Secret<String>,
+    pub from: String,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoneyConfig {
+    /// `http` to ask the rate service, `static` for the fixed `rates`
+    pub rate_provider: String,
+    /// Fixed exchange rates, e.g. `EUR/USD=1.08,EUR/BAM=1.95583`
+    pub rates: String,
+    pub rate_url: String,
+    pub rate_api_key: Secret<String>,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    /// How often the worker looks for due deliveries
+    pub poll_interval_seconds: u64,
+    pub batch_size: u64,
+    /// Attempts before a delivery is given up on
+    pub max_attempts: i32,
+    /// Wait after the first failed attempt, doubled after every further one
+    pub backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+    /// `http` to send web push through the push gateway, `log` to only log messages
+    pub push_provider: String,
+    pub push_url: String,
+    pub push_api_key: Secret<String>,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// How often the worker looks for due deliveries
+    pub poll_interval_seconds: u64,
+    pub batch_size: u64,
+    /// Attempts before a delivery is given up on
+    pub max_attempts: i32,
+    /// Wait after the first failed attempt, doubled after every further one
+    pub backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+    /// Failed attempts in a row after which a subscription is disabled
+    pub disable_after_failures: i32,
+    /// Accept plain `http://` URLs and loopback or private hosts, e.g. a receiver on localhost during development
+    pub allow_http: bool,
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxConfig {
+    /// How often the dispatcher looks for due domain events
+    pub poll_interval_seconds: u64,
+    pub batch_size: u64,
+    /// Attempts before an event is given up on
+    pub max_attempts: i32,
+    /// Wait after the first failed attempt, doubled after every further one
+    pub backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+    /// How long a claimed batch is hidden from other dispatchers
+    pub lease_seconds: u64,
+    /// Days dispatched events are kept before they are deleted
+    pub retention_days: i64,
+}
+
+impl Config {
+    /// Load the configuration from the defaults, the config file, the environment and command line overrides,
+    /// in that order, and validate it. Every problem found is returned, not just the first.
+    pub fn load(sources: &ConfigSources) -> Result<Self, ConfigErrors> {
+        let table = loader::layered(sources).map_err(ConfigErrors)?;
+        let config = Self::from_table(table).map_err(|e| ConfigErrors(vec![e]))?;
+
+        let errors = config.validate();
+        if errors.is_empty() { Ok(config) } else { Err(ConfigErrors(errors)) }
+    }
+
+    /// Load the configuration from the defaults and the environment only
+    pub fn from_env() -> Result<Self, ConfigErrors> {
+        Self::load(&ConfigSources::default())
+    }
+
+    /// The configuration as TOML, with secrets redacted
+    pub fn to_redacted_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Get the server address
+    pub fn server_address(&self) -> String {
+        format!("{}:{}", self.server.host, self.server.port)
+    }
+
+    /// Settings that are out of range or do not fit together
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        let mut check = |valid: bool, key: &str, message: &str| {
+            if !valid {
+                errors.push(ConfigError::Invalid { key: key.to_string(), message: message.to_string() });
+            }
+        };
+
+        check(self.server.port > 0, "server.port", "must not be 0");
+        check(
+            self.server.trusted_proxies.parse::<TrustedProxies>().is_ok(),
+            "server.trusted_proxies",
+            "must be IP addresses or CIDR ranges separated by commas",
+        );
+        check(self.database.max_connections > 0, "database.max_connections", "must be at least 1");
+        check(
+            self.database.min_connections <= self.database.max_connections,
+            "database.min_connections",
+            "must not exceed database.max_connections",
+        );
+        check(
+            self.logging.level.parse::<tracing_subscriber::filter::Targets>().is_ok(),
+            "logging.level",
+            "must be a level such as info, or directives such as info,rust_api=debug",
+        );
+        check(self.session.max_age_seconds > 0, "session.max_age_seconds", "must be at least 1");
+        check(
+            ["strict", "lax", "none"].contains(&self.session.cookie_same_site.to_lowercase().as_str()),
+            "session.cookie_same_site",
+            "must be strict, lax or none",
+        );
+        check(self.invitation.ttl_hours > 0, "invitation.ttl_hours", "must be at least 1");
+        check(self.privacy.purge_interval_seconds > 0, "privacy.purge_interval_seconds", "must be at least 1");
+        check(["redis", "memory"].contains(&self.events.bus.as_str()), "events.bus", "must be redis or memory");
+        check(self.events.buffer_size > 0, "events.buffer_size", "must be at least 1");
+        check(["terminal", "fake"].contains(&self.payment.card_provider.as_str()), "payment.card_provider", "must be terminal or fake");
+        check(["online", "fake"].contains(&self.payment.online_provider.as_str()), "payment.online_provider", "must be online or fake");
+        check(self.reservation.slot_minutes > 0, "reservation.slot_minutes", "must be at least 1");
+        check(self.reservation.default_duration_minutes > 0, "reservation.default_duration_minutes", "must be at least 1");
+        check(self.schedule.pin_max_attempts > 0, "schedule.pin_max_attempts", "must be at least 1");
+        check(self.schedule.pin_lockout_minutes > 0, "schedule.pin_lockout_minutes", "must be at least 1");
+        check(["http", "log"].contains(&self.mail.provider.as_str()), "mail.provider", "must be http or log");
+        check(["http", "static"].contains(&self.money.rate_provider.as_str()), "money.rate_provider", "must be http or static");
+        check(["http", "log"].contains(&self.notification.push_provider.as_str()), "notification.push_provider", "must be http or log");
+
+        for (section, poll_interval, batch_size, max_attempts, backoff, max_backoff) in [
+            ("notification", self.notification.poll_interval_seconds, self.notification.batch_size, self.notification.max_attempts, self.notification.backoff_seconds, self.notification.max_backoff_seconds),
+            ("webhook", self.webhook.poll_interval_seconds, self.webhook.batch_size, self.webhook.max_attempts, self.webhook.backoff_seconds, self.webhook.max_backoff_seconds),
+            ("outbox", self.outbox.poll_interval_seconds, self.outbox.batch_size, self.outbox.max_attempts, self.outbox.backoff_seconds, self.outbox.max_backoff_seconds),
+        ] {
+            check(poll_interval > 0, &format!("{}.poll_interval_seconds", section), "must be at least 1");
+            check(batch_size > 0, &format!("{}.batch_size", section), "must be at least 1");
+            check(max_attempts > 0, &format!("{}.max_attempts", section), "must be at least 1");
+            check(backoff <= max_backoff, &format!("{}.backoff_seconds", section), "must not exceed max_backoff_seconds");
+        }
+
+        for (key, url) in [
+            ("session.redis_url", self.session.redis_url.expose()),
+            ("invitation.accept_url", &self.invitation.accept_url),
+            ("table.qr_url", &self.table.qr_url),
+            ("payment.terminal_url", &self.payment.terminal_url),
+            ("payment.online_url", &self.payment.online_url),
+            ("mail.url", &self.mail.url),
+            ("money.rate_url", &self.money.rate_url),
+            ("notification.push_url", &self.notification.push_url),
+        ] {
+            check(reqwest::Url::parse(url).is_ok(), key, "must be a valid URL");
+        }
+
+        if self.environment == Environment::Production {
+            errors.extend(self.default_secrets().into_iter().map(|key| ConfigError::DefaultSecret { key: key.to_string() }));
+            if self.session.secret.expose().len() < MIN_SESSION_SECRET_LENGTH {
+                errors.push(ConfigError::Invalid {
+                    key: "session.secret".to_string(),
+                    message: format!("must be at least {} characters in production", MIN_SESSION_SECRET_LENGTH),
+                });
+            }
+            // Signed webhook payloads and their signatures must not travel in clear
+            if self.webhook.allow_http {
+                errors.push(ConfigError::Invalid {
+                    key: "webhook.allow_http".to_string(),
+                    message: "must be false in production".to_string(),
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Secrets still set to their built-in defaults
+    fn default_secrets(&self) -> Vec<&'static str> {
+        let Ok(defaults) = Self::from_table(loader::DEFAULTS.parse().unwrap_or_default()) else {
+            return Vec::new();
+        };
+
+        let mut keys = Vec::new();
+        if self.session.secret == defaults.session.secret {
+            keys.push("session.secret");
+        }
+        if self.database.password == defaults.database.password {
+            keys.push("database.password");
+        }
+        if self.session.redis_url == defaults.session.redis_url {
+            keys.push("session.redis_url");
+        }
+        keys
+    }
+
+    fn from_table(table: toml::Table) -> Result<Self, ConfigError> {
+        // Going through text lets the error point at the setting
+        let text = toml::to_string(&table).unwrap_or_default();
+        toml::from_str(&text).map_err(|e| ConfigError::Invalid {
+            key: e.span().map(|span| loader::key_at(&text, span.start)).unwrap_or_default(),
+            message: e.message().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(overrides: &[(&str, &str)]) -> Result<Config, ConfigErrors> {
+        Config::load(&ConfigSources {
+            file: None,
+            overrides: overrides.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        })
+    }
+
+    fn invalid_keys(errors: ConfigErrors) -> Vec<String> {
+        errors
+            .0
+            .into_iter()
+            .map(|error| match error {
+                ConfigError::DefaultSecret { key } | ConfigError::Invalid { key, .. } => key,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect()
+    }
+
+    const PRODUCTION: &[(&str, &str)] = &[
+        ("environment", "production"),
+        ("session.secret", "a-session-secret-of-at-least-32-characters"),
+        ("database.password", "a-database-password"),
+        ("session.redis_url", "redis://:a-redis-password@redis:6379"),
+    ];
+
+    #[test]
+    fn production_refuses_the_default_secrets() {
+        let errors = load(&[("environment", "production")]).unwrap_err();
+        assert_eq!(invalid_keys(errors), ["session.secret", "database.password", "session.redis_url"]);
+
+        let config = load(PRODUCTION).unwrap();
+        assert_eq!(config.environment, Environment::Production);
+        assert!(load(&[]).is_ok(), "development starts with the defaults");
+    }
+
+    #[test]
+    fn production_refuses_short_session_secrets_and_plain_http_webhooks() {
+        let errors = load(&[PRODUCTION, &[("session.secret", "short"), ("webhook.allow_http", "true")]].concat()).unwrap_err();
+        assert_eq!(invalid_keys(errors), ["session.secret", "webhook.allow_http"]);
+        assert!(load(&[("webhook.allow_http", "true")]).is_ok());
+    }
+
+    #[test]
+    fn settings_that_do_not_fit_together_are_reported_together() {
+        let errors = load(&[
+            ("database.min_connections", "20"),
+            ("events.bus", "kafka"),
+            ("logging.level", "info,rust_api=loud"),
+            ("outbox.backoff_seconds", "7200"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            invalid_keys(errors),
+            ["database.min_connections", "logging.level", "events.bus", "outbox.backoff_seconds"]
+        );
+        assert!(load(&[("logging.level", "warn,rust_api=debug")]).is_ok());
+    }
+
+    #[test]
+    fn secrets_are_redacted_when_printed() {
+        let config = load(PRODUCTION).unwrap();
+        let printed = format!("{:?}\n{}", config, config.to_redacted_toml());
+        for (key, secret) in &PRODUCTION[1..] {
+            assert!(!printed.contains(secret), "{} is printed", key);
+        }
+        assert_eq!(config.session.redis_url.expose(), "redis://:a-redis-password@redis:6379");
+    }
+}
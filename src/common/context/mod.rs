@@ -0,0 +1,150 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+use uuid::Uuid;
+
+use crate::modules::auth::entity::UserInfo;
+
+/// Header carrying the request ID, set by the request ID middleware
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Who is performing a request and from where, for auditing mutations
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    pub user: Option<UserInfo>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl RequestContext {
+    /// Context for work not triggered by a request (background jobs)
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// Attach the user performing the request
+    pub fn with_user(mut self, user: UserInfo) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    /// ID of the user performing the request, if authenticated
+    pub fn actor_id(&self) -> Option<Uuid> {
+        self.user.as_ref().and_then(|user| user.parsed_id().ok())
+    }
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for RequestContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted = parts.extensions.get::<TrustedProxies>();
+
+        Ok(Self {
+            // Set by the authentication middleware on protected routes
+            user: parts.extensions.get::<UserInfo>().cloned(),
+            ip: peer_ip.map(|peer| client_ip(&parts.headers, peer, trusted).to_string()),
+            user_agent: header(&parts.headers, "user-agent"),
+            request_id: header(&parts.headers, REQUEST_ID_HEADER),
+        })
+    }
+}
+
+/// Reverse proxies whose `X-Forwarded-For` header is believed, given as addresses or CIDR ranges
+/// separated by commas, e.g. `10.0.0.0/8,127.0.0.1`
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (address, prefix) = entry.split_once('/').unwrap_or((entry, ""));
+            let address: IpAddr = address
+                .parse()
+                .map_err(|_| format!("{} is not an IP address or CIDR range", entry))?;
+            let max = if address.is_ipv4() { 32 } else { 128 };
+            let prefix = match prefix {
+                "" => max,
+                prefix => prefix
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|prefix| *prefix <= max)
+                    .ok_or_else(|| format!("{} has an invalid prefix length", entry))?,
+            };
+            ranges.push((address, prefix));
+        }
+        Ok(Self(ranges))
+    }
+}
+
+impl TrustedProxies {
+    /// Whether an address is one of the trusted proxies
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|(network, prefix)| match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - *prefix as u32).unwrap_or(0);
+                u32::from(*network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - *prefix as u32).unwrap_or(0);
+                u128::from(*network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        })
+    }
+}
+
+/// The client address: the peer itself, unless it is a trusted proxy, in which case the `X-Forwarded-For`
+/// chain is followed from the right past the trusted proxies. Entries further left are set by the client
+/// and can be forged, so they are never used.
+fn client_ip(headers: &HeaderMap, peer: IpAddr, trusted: Option<&TrustedProxies>) -> IpAddr {
+    let Some(trusted) = trusted.filter(|trusted| trusted.contains(peer)) else {
+        return peer;
+    };
+
+    let chain: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+
+    let mut client = peer;
+    for entry in chain.into_iter().rev() {
+        let Ok(ip) = entry.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !trusted.contains(ip) {
+            break;
+        }
+    }
+    client
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
@@ -16,7 +16,7 @@ impl Database {
     pub async fn new(config: &DatabaseConfig) -> Result<Self> {
         info!("Connecting to database...");
         
-        let mut opt = ConnectOptions::new(&config.url());
+        let mut opt = ConnectOptions::new(config.url());
         opt.max_connections(config.max_connections)
             .min_connections(config.min_connections)
             .acquire_timeout(Duration::from_secs(config.acquire_timeout_seconds))
@@ -11,6 +11,9 @@ pub enum ApiError {
     #[error("User not found")]
     UserNotFound,
     
+    #[error("{0}")]
+    NotFound(String),
+    
     #[error("Invalid input: {0}")]
     InvalidInput(String),
     
@@ -23,9 +26,36 @@ pub enum ApiError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
     
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
     #[error("Invalid credentials")]
     InvalidCredentials,
     
+    #[error("Invalid transition from {from} to {to}")]
+    InvalidTransition { from: String, to: String },
+    
+    #[error("Payment declined: {0}")]
+    PaymentDeclined(String),
+    
+    #[error("Payment provider unavailable: {0}")]
+    ProviderUnavailable(String),
+    
+    #[error("Mail service unavailable: {0}")]
+    MailUnavailable(String),
+    
+    #[error("Currency mismatch: expected {expected}, found {found}")]
+    CurrencyMismatch { expected: String, found: String },
+    
+    #[error("Exchange rates unavailable: {0}")]
+    RatesUnavailable(String),
+    
+    #[error("Too many attempts: {0}")]
+    TooManyAttempts(String),
+    
     #[error("Internal server error")]
     InternalServerError,
 }
@@ -34,6 +64,7 @@ impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             ApiError::UserNotFound => (StatusCode::NOT_FOUND, "User not found".to_string()),
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             ApiError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::UserAlreadyExists => (StatusCode::CONFLICT, "User already exists".to_string()),
             ApiError::DatabaseError(msg) => {
@@ -41,7 +72,29 @@ impl IntoResponse for ApiError {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
             }
             ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
             ApiError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "Invalid credentials".to_string()),
+            ApiError::InvalidTransition { from, to } => {
+                (StatusCode::CONFLICT, format!("Cannot move from {} to {}", from, to))
+            }
+            ApiError::PaymentDeclined(msg) => (StatusCode::PAYMENT_REQUIRED, msg),
+            ApiError::ProviderUnavailable(msg) => {
+                tracing::error!("Payment provider unavailable: {}", msg);
+                (StatusCode::BAD_GATEWAY, "Payment provider unavailable".to_string())
+            }
+            ApiError::MailUnavailable(msg) => {
+                tracing::error!("Mail service unavailable: {}", msg);
+                (StatusCode::BAD_GATEWAY, "Mail service unavailable".to_string())
+            }
+            ApiError::CurrencyMismatch { expected, found } => {
+                (StatusCode::BAD_REQUEST, format!("Cannot combine amounts in {} and {}", expected, found))
+            }
+            ApiError::RatesUnavailable(msg) => {
+                tracing::error!("Exchange rates unavailable: {}", msg);
+                (StatusCode::BAD_GATEWAY, "Exchange rates unavailable".to_string())
+            }
+            ApiError::TooManyAttempts(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
             ApiError::InternalServerError => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string()),
         };
 
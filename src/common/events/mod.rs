@@ -0,0 +1,275 @@
+use std::sync::Arc;
+
+use fred::clients::RedisClient;
+use fred::interfaces::{ClientLike, EventInterface, PubsubInterface};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::common::config::EventsConfig;
+
+/// Something that happened which connected clients may want to see
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// What happened, e.g. `order.status_changed`
+    pub kind: String,
+    /// Topics the event is delivered to, e.g. `branch:<id>`
+    pub topics: Vec<String>,
+    pub payload: Value,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Event {
+    /// Create an event, serializing its payload
+    pub fn new<T: Serialize>(kind: &str, topics: Vec<String>, payload: &T) -> Self {
+        Self {
+            kind: kind.to_string(),
+            topics,
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Topic names clients subscribe to
+pub mod topic {
+    use uuid::Uuid;
+
+    pub fn branch(id: Uuid) -> String {
+        format!("branch:{}", id)
+    }
+
+    pub fn table(id: Uuid) -> String {
+        format!("table:{}", id)
+    }
+
+    pub fn station(id: Uuid) -> String {
+        format!("station:{}", id)
+    }
+}
+
+/// Fans events out to every subscriber of every API instance
+#[async_trait::async_trait]
+pub trait EventBus: Send + Sync + std::fmt::Debug {
+    /// Publish an event; delivery is best effort and never fails the caller
+    async fn publish(&self, event: Event);
+
+    /// Receive every event published from now on
+    fn subscribe(&self) -> broadcast::Receiver<Event>;
+}
+
+/// Event bus shared by the services and the real-time endpoints
+pub type SharedEventBus = Arc<dyn EventBus>;
+
+/// Event bus within a single process, for tests and single-instance deployments
+#[derive(Debug)]
+pub struct InProcessBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl InProcessBus {
+    /// Create a bus buffering up to `capacity` events per slow subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventBus for InProcessBus {
+    async fn publish(&self, event: Event) {
+        // Sending only fails when nobody is listening
+        let _ = self.sender.send(event);
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+/// Event bus over Redis pub/sub, keeping every API instance in sync
+#[derive(Debug)]
+pub struct RedisBus {
+    publisher: RedisClient,
+    channel: String,
+    local: broadcast::Sender<Event>,
+}
+
+impl RedisBus {
+    /// Connect to Redis and forward events published by any instance to local subscribers
+    pub async fn connect(redis_url: &str, channel: &str, capacity: usize) -> Result<Self, fred::error::RedisError> {
+        let publisher = Self::client(redis_url)?;
+        publisher.connect();
+        publisher.wait_for_connect().await?;
+
+        // A connection in subscriber mode cannot run other commands
+        let subscriber = Self::client(redis_url)?;
+        subscriber.connect();
+        subscriber.wait_for_connect().await?;
+        subscriber.subscribe::<(), _>(channel).await?;
+
+        let (local, _) = broadcast::channel(capacity);
+        Self::spawn_forwarder(subscriber, channel.to_string(), local.clone());
+
+        info!("Event bus subscribed to Redis channel {}", channel);
+        Ok(Self {
+            publisher,
+            channel: channel.to_string(),
+            local,
+        })
+    }
+
+    fn client(redis_url: &str) -> Result<RedisClient, fred::error::RedisError> {
+        Ok(RedisClient::new(fred::types::RedisConfig::from_url(redis_url)?, None, None, None))
+    }
+
+    fn spawn_forwarder(subscriber: RedisClient, channel: String, local: broadcast::Sender<Event>) {
+        // Subscriptions are lost when the connection drops
+        let resubscriber = subscriber.clone();
+        let resubscribe_channel = channel.clone();
+        subscriber.on_reconnect(move |_| {
+            let client = resubscriber.clone();
+            let channel = resubscribe_channel.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.subscribe::<(), _>(channel.as_str()).await {
+                    error!("Failed to resubscribe to Redis channel {}: {}", channel, e);
+                }
+            });
+            Ok(())
+        });
+
+        let mut messages = subscriber.on_message();
+        tokio::spawn(async move {
+            loop {
+                match messages.recv().await {
+                    Ok(message) if message.channel == channel.as_str() => {
+                        let Some(text) = message.value.as_string() else {
+                            continue;
+                        };
+                        match serde_json::from_str::<Event>(&text) {
+                            Ok(event) => {
+                                let _ = local.send(event);
+                            }
+                            Err(e) => warn!("Dropped malformed event from Redis: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Event bus fell behind Redis, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+#[async_trait::async_trait]
+impl EventBus for RedisBus {
+    async fn publish(&self, event: Event) {
+        let message = match serde_json::to_string(&event) {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to serialize event {}: {}", event.kind, e);
+                return;
+            }
+        };
+
+        // Redis echoes the message back to this instance, so local subscribers get it too
+        if let Err(e) = self.publisher.publish::<(), _, _>(self.channel.as_str(), message).await {
+            error!("Failed to publish event {} to Redis, delivering locally: {}", event.kind, e);
+            let _ = self.local.send(event);
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.local.subscribe()
+    }
+}
+
+/// Create the configured event bus
+pub async fn create_event_bus(config: &EventsConfig, redis_url: &str) -> SharedEventBus {
+    match config.bus.as_str() {
+        "memory" => Arc::new(InProcessBus::new(config.buffer_size)),
+        _ => Arc::new(
+            RedisBus::connect(redis_url, &config.channel, config.buffer_size)
+                .await
+                .expect("Failed to connect event bus to Redis"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn event(kind: &str) -> Event {
+        Event::new(kind, vec![topic::branch(Uuid::nil())], &json!({ "kind": kind }))
+    }
+
+    #[tokio::test]
+    async fn delivers_to_every_subscriber() {
+        let bus = InProcessBus::new(16);
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        bus.publish(event("order.created")).await;
+
+        for receiver in [&mut first, &mut second] {
+            let received = receiver.recv().await.unwrap();
+            assert_eq!(received.kind, "order.created");
+            assert_eq!(received.topics, vec![format!("branch:{}", Uuid::nil())]);
+            assert_eq!(received.payload, json!({ "kind": "order.created" }));
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_publish_order() {
+        let bus = InProcessBus::new(16);
+        let mut receiver = bus.subscribe();
+
+        for kind in ["order.created", "order.updated", "order.status_changed"] {
+            bus.publish(event(kind)).await;
+        }
+
+        for kind in ["order.created", "order.updated", "order.status_changed"] {
+            assert_eq!(receiver.recv().await.unwrap().kind, kind);
+        }
+    }
+
+    #[tokio::test]
+    async fn only_delivers_events_published_after_subscribing() {
+        let bus = InProcessBus::new(16);
+        bus.publish(event("order.created")).await;
+
+        let mut receiver = bus.subscribe();
+        assert!(matches!(receiver.try_recv(), Err(broadcast::error::TryRecvError::Empty)));
+
+        bus.publish(event("order.updated")).await;
+        assert_eq!(receiver.recv().await.unwrap().kind, "order.updated");
+    }
+
+    #[tokio::test]
+    async fn publishing_without_subscribers_does_not_fail() {
+        let bus = InProcessBus::new(16);
+        bus.publish(event("order.created")).await;
+    }
+
+    #[tokio::test]
+    async fn slow_subscribers_skip_what_overflowed_the_buffer() {
+        let bus = InProcessBus::new(2);
+        let mut receiver = bus.subscribe();
+
+        for kind in ["a", "b", "c", "d"] {
+            bus.publish(event(kind)).await;
+        }
+
+        assert!(matches!(receiver.recv().await, Err(broadcast::error::RecvError::Lagged(2))));
+        assert_eq!(receiver.recv().await.unwrap().kind, "c");
+        assert_eq!(receiver.recv().await.unwrap().kind, "d");
+    }
+}
@@ -0,0 +1,113 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tracing::{error, info};
+
+use crate::common::{config::{MailConfig, Secret}, ApiError};
+
+/// A file sent along with a message
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+/// A plain text email
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+}
+
+/// Delivers email
+#[async_trait::async_trait]
+pub trait Mailer: Send + Sync + std::fmt::Debug {
+    async fn send(&self, mail: &Mail) -> Result<(), ApiError>;
+}
+
+pub type SharedMailer = Arc<dyn Mailer>;
+
+/// Build the configured mailer
+pub fn create_mailer(config: &MailConfig) -> SharedMailer {
+    match config.provider.as_str() {
+        "http" => Arc::new(HttpMailer::new(config)),
+        _ => Arc::new(LogMailer),
+    }
+}
+
+/// Sends through a mail gateway exposing `POST /messages`
+#[derive(Debug)]
+pub struct HttpMailer {
+    client: reqwest::Client,
+    url: String,
+    api_key: Secret<String>,
+    from: String,
+}
+
+impl HttpMailer {
+    pub fn new(config: &MailConfig) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .build()
+                .unwrap_or_default(),
+            url: format!("{}/messages", config.url.trim_end_matches('/')),
+            api_key: config.api_key.clone(),
+            from: config.from.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for HttpMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), ApiError> {
+        info!("Sending \"{}\" through mail gateway {}", mail.subject, self.url);
+
+        let attachments: Vec<serde_json::Value> = mail.attachments
+            .iter()
+            .map(|attachment| serde_json::json!({
+                "filename": attachment.filename,
+                "content_type": attachment.content_type,
+                "content": STANDARD.encode(&attachment.content),
+            }))
+            .collect();
+        let body = serde_json::json!({
+            "from": self.from,
+            "to": mail.to,
+            "subject": mail.subject,
+            "text": mail.text,
+            "attachments": attachments,
+        });
+
+        let response = self.client
+            .post(&self.url)
+            .bearer_auth(self.api_key.expose())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Mail gateway {} unreachable: {}", self.url, e);
+                ApiError::MailUnavailable(e.to_string())
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::MailUnavailable(format!("Gateway answered {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Logs messages instead of sending them, for development and tests
+#[derive(Debug, Default)]
+pub struct LogMailer;
+
+#[async_trait::async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), ApiError> {
+        info!("Mail to {}: \"{}\" with {} attachments (not sent)", mail.to, mail.subject, mail.attachments.len());
+        Ok(())
+    }
+}
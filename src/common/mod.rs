@@ -1,10 +1,20 @@
 pub mod config;
+pub mod context;
 pub mod database;
 pub mod errors;
+pub mod events;
+pub mod mail;
+pub mod money;
+pub mod outbox;
+pub mod pagination;
 pub mod session;
+pub mod signing;
 pub mod state;
+#[cfg(test)]
+pub mod testing;
 
 pub use config::Config;
+pub use context::RequestContext;
 pub use database::Database;
 pub use errors::ApiError;
 pub use state::AppState;
\ No newline at end of file
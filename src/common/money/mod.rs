@@ -0,0 +1,468 @@
+use std::{fmt, str::FromStr};
+
+use rust_decimal::{prelude::ToPrimitive, RoundingStrategy};
+use sea_orm::prelude::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::ApiError;
+
+pub mod rates;
+
+/// Currencies whose minor unit is not the cent, and the symbols of common ones
+const EXPONENTS: &[(&str, u32)] = &[
+    ("BHD", 3), ("CLP", 0), ("ISK", 0), ("JOD", 3), ("JPY", 0), ("KRW", 0),
+    ("KWD", 3), ("OMR", 3), ("TND", 3), ("UGX", 0), ("VND", 0),
+];
+const SYMBOLS: &[(&str, &str)] = &[
+    ("BAM", "KM"), ("CHF", "CHF"), ("CZK", "Kč"), ("EUR", "€"), ("GBP", "£"), ("HUF", "Ft"), ("JPY", "¥"),
+    ("PLN", "zł"), ("RSD", "RSD"), ("SEK", "kr"), ("TRY", "₺"), ("USD", "$"),
+];
+
+/// ISO 4217 currency with the number of decimals of its minor unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Currency {
+    code: [u8; 3],
+    exponent: u32,
+}
+
+impl Currency {
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.code).unwrap_or("XXX")
+    }
+
+    /// Decimals of the minor unit, e.g. 2 for cents
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    /// Symbol printed next to amounts, the code where there is no common symbol
+    pub fn symbol(&self) -> &str {
+        SYMBOLS
+            .iter()
+            .find(|(code, _)| *code == self.code())
+            .map(|(_, symbol)| *symbol)
+            .unwrap_or_else(|| self.code())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code: [u8; 3] = s
+            .as_bytes()
+            .try_into()
+            .ok()
+            .filter(|code: &[u8; 3]| code.iter().all(u8::is_ascii_uppercase))
+            .ok_or_else(|| format!("Invalid currency: {}. Must be a three-letter ISO 4217 code", s))?;
+        let exponent = EXPONENTS.iter().find(|(known, _)| *known == s).map_or(2, |(_, exponent)| *exponent);
+        Ok(Self { code, exponent })
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An amount in the minor units of its currency. Arithmetic refuses to mix currencies
+/// and fails instead of overflowing; the amount is stored in `NUMERIC` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor: i64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(minor: i64, currency: Currency) -> Self {
+        Self { minor, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Money from a decimal amount, refusing more decimals than the currency has
+    pub fn from_decimal(amount: Decimal, currency: Currency) -> Result<Self, ApiError> {
+        if amount.normalize().scale() > currency.exponent {
+            return Err(ApiError::InvalidInput(format!("{} has more decimals than {} allows", amount, currency)));
+        }
+        Self::rounded(amount, currency)
+    }
+
+    /// Money from a decimal amount rounded to the currency's minor unit, halves away from zero
+    pub fn rounded(amount: Decimal, currency: Currency) -> Result<Self, ApiError> {
+        let minor = amount
+            .round_dp_with_strategy(currency.exponent, RoundingStrategy::MidpointAwayFromZero)
+            .checked_mul(Decimal::from(10i64.pow(currency.exponent)))
+            .and_then(|scaled| scaled.to_i64())
+            .ok_or_else(|| ApiError::InvalidInput(format!("{} {} is out of range", amount, currency)))?;
+        Ok(Self::new(minor, currency))
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn amount(&self) -> Decimal {
+        Decimal::new(self.minor, self.currency.exponent)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor < 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Result<Money, ApiError> {
+        self.same_currency(other)?;
+        self.minor.checked_add(other.minor).map(|minor| Self::new(minor, self.currency)).ok_or_else(|| self.overflow())
+    }
+
+    pub fn checked_sub(self, other: Money) -> Result<Money, ApiError> {
+        self.same_currency(other)?;
+        self.minor.checked_sub(other.minor).map(|minor| Self::new(minor, self.currency)).ok_or_else(|| self.overflow())
+    }
+
+    pub fn checked_mul(self, quantity: i64) -> Result<Money, ApiError> {
+        self.minor.checked_mul(quantity).map(|minor| Self::new(minor, self.currency)).ok_or_else(|| self.overflow())
+    }
+
+    /// Sum of amounts that must all be in `currency`
+    pub fn sum(currency: Currency, amounts: impl IntoIterator<Item = Money>) -> Result<Money, ApiError> {
+        amounts.into_iter().try_fold(Self::zero(currency), Money::checked_add)
+    }
+
+    /// The amount in another currency at `rate` units of it per unit of this one
+    pub fn convert(self, to: Currency, rate: Decimal) -> Result<Money, ApiError> {
+        if rate <= Decimal::ZERO {
+            return Err(ApiError::InvalidInput(format!("Exchange rate from {} to {} must be positive", self.currency, to)));
+        }
+        let amount = self.amount().checked_mul(rate).ok_or_else(|| self.overflow())?;
+        Self::rounded(amount, to)
+    }
+
+    /// The amount with the locale's separators and the currency symbol, e.g. `1.234,50 €`
+    pub fn format(&self, locale: Locale) -> String {
+        let number = self.format_number(locale);
+        let (sign, number) = match number.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", number.as_str()),
+        };
+        let space = if locale.symbol_space { "\u{a0}" } else { "" };
+        if locale.symbol_first {
+            format!("{}{}{}{}", sign, self.currency.symbol(), space, number)
+        } else {
+            format!("{}{}{}{}", sign, number, space, self.currency.symbol())
+        }
+    }
+
+    /// The amount with the locale's separators and no currency, e.g. `1.234,50`
+    pub fn format_number(&self, locale: Locale) -> String {
+        let unit = 10u64.pow(self.currency.exponent);
+        let minor = self.minor.unsigned_abs();
+        let digits = (minor / unit).to_string();
+
+        let mut number = String::new();
+        if self.minor < 0 {
+            number.push('-');
+        }
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                number.push(locale.group);
+            }
+            number.push(digit);
+        }
+        if self.currency.exponent > 0 {
+            number.push(locale.decimal);
+            number.push_str(&format!("{:0width$}", minor % unit, width = self.currency.exponent as usize));
+        }
+        number
+    }
+
+    fn same_currency(&self, other: Money) -> Result<(), ApiError> {
+        if self.currency != other.currency {
+            return Err(ApiError::CurrencyMismatch { expected: self.currency.to_string(), found: other.currency.to_string() });
+        }
+        Ok(())
+    }
+
+    fn overflow(&self) -> ApiError {
+        ApiError::InvalidInput(format!("Amount in {} is out of range", self.currency))
+    }
+}
+
+/// Amounts in different currencies are not comparable
+impl PartialOrd for Money {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (self.currency == other.currency).then(|| self.minor.cmp(&other.minor))
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount(), self.currency)
+    }
+}
+
+/// Serialized as `{"amount": "12.50", "currency": "EUR"}` like the other amounts of the API
+#[derive(Serialize, Deserialize)]
+struct MoneyRepr {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MoneyRepr { amount: self.amount(), currency: self.currency }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MoneyRepr::deserialize(deserializer)?;
+        Money::from_decimal(repr.amount, repr.currency).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Stored as the decimal amount; the currency lives with the branch or row the amount belongs to
+impl From<Money> for sea_orm::Value {
+    fn from(money: Money) -> Self {
+        sea_orm::Value::Decimal(Some(Box::new(money.amount())))
+    }
+}
+
+impl sea_orm::sea_query::Nullable for Money {
+    fn null() -> sea_orm::Value {
+        sea_orm::Value::Decimal(None)
+    }
+}
+
+/// Read from the amount column and the `currency` column of the same row
+impl sea_orm::TryGetable for Money {
+    fn try_get_by<I: sea_orm::ColIdx>(res: &sea_orm::QueryResult, index: I) -> Result<Self, sea_orm::TryGetError> {
+        let amount = Decimal::try_get_by(res, index)?;
+        let currency = String::try_get_by(res, "currency")?;
+        Self::from_row(amount, &currency)
+    }
+
+    fn try_get(res: &sea_orm::QueryResult, pre: &str, col: &str) -> Result<Self, sea_orm::TryGetError> {
+        let amount = Decimal::try_get(res, pre, col)?;
+        let currency = String::try_get(res, pre, "currency")?;
+        Self::from_row(amount, &currency)
+    }
+}
+
+/// A bare `NUMERIC` value has no currency, so money is only ever read whole rows at a time
+impl sea_orm::sea_query::ValueType for Money {
+    fn try_from(_: sea_orm::Value) -> Result<Self, sea_orm::sea_query::ValueTypeErr> {
+        Err(sea_orm::sea_query::ValueTypeErr)
+    }
+
+    fn type_name() -> String {
+        "Money".to_string()
+    }
+
+    fn array_type() -> sea_orm::sea_query::ArrayType {
+        sea_orm::sea_query::ArrayType::Decimal
+    }
+
+    fn column_type() -> sea_orm::sea_query::ColumnType {
+        sea_orm::sea_query::ColumnType::Decimal(None)
+    }
+}
+
+impl Money {
+    fn from_row(amount: Decimal, currency: &str) -> Result<Self, sea_orm::TryGetError> {
+        let currency: Currency = currency.trim().parse().map_err(|e| sea_orm::TryGetError::DbErr(sea_orm::DbErr::Type(e)))?;
+        Self::from_decimal(amount, currency).map_err(|e| sea_orm::TryGetError::DbErr(sea_orm::DbErr::Type(e.to_string())))
+    }
+}
+
+/// Serialize money as its bare amount, for responses that give the currency once alongside
+pub fn serialize_amount<S: Serializer>(money: &Money, serializer: S) -> Result<S::Ok, S::Error> {
+    Serialize::serialize(&money.amount(), serializer)
+}
+
+/// How amounts are written in a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    tag: &'static str,
+    decimal: char,
+    group: char,
+    symbol_first: bool,
+    symbol_space: bool,
+}
+
+const LOCALES: &[Locale] = &[
+    Locale { tag: "en-US", decimal: '.', group: ',', symbol_first: true, symbol_space: false },
+    Locale { tag: "en-GB", decimal: '.', group: ',', symbol_first: true, symbol_space: false },
+    Locale { tag: "de-DE", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "de-AT", decimal: ',', group: '.', symbol_first: true, symbol_space: true },
+    Locale { tag: "de-CH", decimal: '.', group: '\'', symbol_first: true, symbol_space: true },
+    Locale { tag: "fr-FR", decimal: ',', group: '\u{a0}', symbol_first: false, symbol_space: true },
+    Locale { tag: "it-IT", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "es-ES", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "nl-NL", decimal: ',', group: '.', symbol_first: true, symbol_space: true },
+    Locale { tag: "pl-PL", decimal: ',', group: '\u{a0}', symbol_first: false, symbol_space: true },
+    Locale { tag: "bs-BA", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "hr-HR", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "sr-RS", decimal: ',', group: '.', symbol_first: false, symbol_space: true },
+    Locale { tag: "ja-JP", decimal: '.', group: ',', symbol_first: true, symbol_space: false },
+];
+
+impl Locale {
+    pub fn tag(&self) -> &'static str {
+        self.tag
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        LOCALES[0]
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LOCALES.iter().find(|locale| locale.tag.eq_ignore_ascii_case(s)).copied().ok_or_else(|| {
+            let tags: Vec<&str> = LOCALES.iter().map(|locale| locale.tag).collect();
+            format!("Invalid locale: {}. Must be one of: {}", s, tags.join(", "))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::testing::{dec, eur, money};
+
+    fn currency(code: &str) -> Currency {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn currencies_know_their_minor_unit_and_symbol() {
+        assert_eq!(currency("EUR").exponent(), 2);
+        assert_eq!(currency("JPY").exponent(), 0);
+        assert_eq!(currency("KWD").exponent(), 3);
+        assert_eq!(currency("EUR").symbol(), "€");
+        assert_eq!(currency("NOK").symbol(), "NOK");
+        for invalid in ["eur", "EU", "EURO", "E1R"] {
+            assert!(invalid.parse::<Currency>().is_err(), "{} parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn from_decimal_refuses_more_decimals_than_the_currency_has() {
+        assert_eq!(Money::from_decimal(dec("12.50"), currency("EUR")).unwrap().minor_units(), 1250);
+        assert_eq!(Money::from_decimal(dec("12.5000"), currency("EUR")).unwrap().minor_units(), 1250);
+        assert_eq!(Money::from_decimal(dec("1000"), currency("JPY")).unwrap().minor_units(), 1000);
+        assert_eq!(Money::from_decimal(dec("1.234"), currency("KWD")).unwrap().minor_units(), 1234);
+
+        assert!(matches!(Money::from_decimal(dec("12.505"), currency("EUR")), Err(ApiError::InvalidInput(_))));
+        assert!(matches!(Money::from_decimal(dec("0.5"), currency("JPY")), Err(ApiError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn rounded_rounds_halves_away_from_zero() {
+        assert_eq!(Money::rounded(dec("12.345"), currency("EUR")).unwrap(), eur("12.35"));
+        assert_eq!(Money::rounded(dec("12.344"), currency("EUR")).unwrap(), eur("12.34"));
+        assert_eq!(Money::rounded(dec("-12.345"), currency("EUR")).unwrap(), eur("-12.35"));
+        assert_eq!(Money::rounded(dec("999.5"), currency("JPY")).unwrap(), money("1000", "JPY"));
+        assert!(matches!(Money::rounded(Decimal::MAX, currency("EUR")), Err(ApiError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn arithmetic_refuses_to_mix_currencies() {
+        let usd = money("1.00", "USD");
+
+        for result in [eur("1.00").checked_add(usd), eur("1.00").checked_sub(usd), Money::sum(currency("EUR"), [eur("1.00"), usd])] {
+            assert!(matches!(result, Err(ApiError::CurrencyMismatch { expected, found }) if expected == "EUR" && found == "USD"));
+        }
+        assert_eq!(eur("1.00").partial_cmp(&usd), None);
+    }
+
+    #[test]
+    fn arithmetic_stays_in_the_minor_unit_and_fails_on_overflow() {
+        assert_eq!(eur("0.10").checked_add(eur("0.20")).unwrap(), eur("0.30"));
+        assert_eq!(eur("0.10").checked_sub(eur("0.30")).unwrap(), eur("-0.20"));
+        assert_eq!(eur("2.50").checked_mul(3).unwrap(), eur("7.50"));
+        assert_eq!(Money::sum(currency("EUR"), [eur("1.10"), eur("2.20")]).unwrap(), eur("3.30"));
+
+        let max = Money::new(i64::MAX, currency("EUR"));
+        assert!(matches!(max.checked_add(eur("0.01")), Err(ApiError::InvalidInput(_))));
+        assert!(matches!(max.checked_mul(2), Err(ApiError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn convert_rounds_to_the_target_minor_unit() {
+        assert_eq!(eur("10.00").convert(currency("USD"), dec("1.08")).unwrap(), money("10.80", "USD"));
+        assert_eq!(eur("10.00").convert(currency("JPY"), dec("161.235")).unwrap(), money("1612", "JPY"));
+        assert!(matches!(eur("10.00").convert(currency("USD"), Decimal::ZERO), Err(ApiError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn formats_amounts_per_locale() {
+        let cases = [
+            ("en-US", eur("1234.50"), "1,234.50", "€1,234.50"),
+            ("de-DE", eur("1234.50"), "1.234,50", "1.234,50\u{a0}€"),
+            ("de-AT", eur("1234.50"), "1.234,50", "€\u{a0}1.234,50"),
+            ("de-CH", money("1234567.05", "CHF"), "1'234'567.05", "CHF\u{a0}1'234'567.05"),
+            ("fr-FR", eur("1234.50"), "1\u{a0}234,50", "1\u{a0}234,50\u{a0}€"),
+            ("ja-JP", money("1234", "JPY"), "1,234", "¥1,234"),
+            ("bs-BA", money("5.00", "BAM"), "5,00", "5,00\u{a0}KM"),
+            ("en-GB", money("-1234.50", "GBP"), "-1,234.50", "-£1,234.50"),
+            ("de-DE", eur("-0.05"), "-0,05", "-0,05\u{a0}€"),
+            ("en-US", eur("999.99"), "999.99", "€999.99"),
+        ];
+
+        for (tag, amount, number, formatted) in cases {
+            let locale: Locale = tag.parse().unwrap();
+            assert_eq!(amount.format_number(locale), number, "{} in {}", amount, tag);
+            assert_eq!(amount.format(locale), formatted, "{} in {}", amount, tag);
+        }
+    }
+
+    #[test]
+    fn locales_parse_case_insensitively_and_default_to_en_us() {
+        assert_eq!("DE-de".parse::<Locale>().unwrap().tag(), "de-DE");
+        assert!("xx-XX".parse::<Locale>().is_err());
+        assert_eq!(Locale::default().tag(), "en-US");
+    }
+
+    #[test]
+    fn serializes_with_its_currency() {
+        let value = serde_json::to_value(eur("12.50")).unwrap();
+        assert_eq!(value, serde_json::json!({ "amount": "12.50", "currency": "EUR" }));
+        assert_eq!(serde_json::from_value::<Money>(value).unwrap(), eur("12.50"));
+        assert!(serde_json::from_value::<Money>(serde_json::json!({ "amount": "0.5", "currency": "JPY" })).is_err());
+    }
+}
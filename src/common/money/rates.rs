@@ -0,0 +1,212 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use sea_orm::prelude::Decimal;
+use tracing::{error, info, warn};
+
+use crate::common::{config::{MoneyConfig, Secret}, money::Currency, ApiError};
+
+/// Supplies exchange rates between currencies
+#[async_trait::async_trait]
+pub trait RateSource: Send + Sync + std::fmt::Debug {
+    /// Units of `to` one unit of `from` buys, `None` if the rate is unknown
+    async fn rate(&self, from: Currency, to: Currency) -> Result<Option<Decimal>, ApiError>;
+}
+
+pub type SharedRateSource = Arc<dyn RateSource>;
+
+/// Build the configured rate source
+pub fn create_rate_source(config: &MoneyConfig) -> SharedRateSource {
+    match config.rate_provider.as_str() {
+        "http" => Arc::new(HttpRates::new(config)),
+        _ => Arc::new(StaticRates::parse(&config.rates)),
+    }
+}
+
+/// Fixed rates from configuration, e.g. `EUR/USD=1.08,EUR/BAM=1.95583`; the reverse rates are derived
+#[derive(Debug, Default)]
+pub struct StaticRates {
+    rates: HashMap<(Currency, Currency), Decimal>,
+}
+
+impl StaticRates {
+    pub fn parse(spec: &str) -> Self {
+        let mut rates = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let parsed = entry.split_once('=').and_then(|(pair, rate)| {
+                let (from, to) = pair.trim().split_once('/')?;
+                let from: Currency = from.trim().parse().ok()?;
+                let to: Currency = to.trim().parse().ok()?;
+                let rate: Decimal = rate.trim().parse().ok().filter(|rate| *rate > Decimal::ZERO)?;
+                Some((from, to, rate))
+            });
+            match parsed {
+                Some((from, to, rate)) => {
+                    rates.insert((from, to), rate);
+                    rates.entry((to, from)).or_insert(Decimal::ONE / rate);
+                }
+                None => warn!("Ignoring invalid exchange rate \"{}\"", entry),
+            }
+        }
+
+        Self { rates }
+    }
+}
+
+#[async_trait::async_trait]
+impl RateSource for StaticRates {
+    async fn rate(&self, from: Currency, to: Currency) -> Result<Option<Decimal>, ApiError> {
+        if from == to {
+            return Ok(Some(Decimal::ONE));
+        }
+        Ok(self.rates.get(&(from, to)).copied())
+    }
+}
+
+/// Asks a rate service exposing `GET /rates?from=EUR&to=USD`, answering `{"rate": "1.08"}`
+#[derive(Debug)]
+pub struct HttpRates {
+    client: reqwest::Client,
+    url: String,
+    api_key: Secret<String>,
+}
+
+impl HttpRates {
+    pub fn new(config: &MoneyConfig) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .build()
+                .unwrap_or_default(),
+            url: format!("{}/rates", config.rate_url.trim_end_matches('/')),
+            api_key: config.rate_api_key.clone(),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RateResponse {
+    rate: Decimal,
+}
+
+#[async_trait::async_trait]
+impl RateSource for HttpRates {
+    async fn rate(&self, from: Currency, to: Currency) -> Result<Option<Decimal>, ApiError> {
+        if from == to {
+            return Ok(Some(Decimal::ONE));
+        }
+        info!("Fetching exchange rate from {} to {}", from, to);
+
+        let response = self.client
+            .get(&self.url)
+            .bearer_auth(self.api_key.expose())
+            .query(&[("from", from.code()), ("to", to.code())])
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Rate service {} unreachable: {}", self.url, e);
+                ApiError::RatesUnavailable(e.to_string())
+            })?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ApiError::RatesUnavailable(format!("Rate service answered {}", response.status())));
+        }
+
+        let body: RateResponse = response
+            .json()
+            .await
+            .map_err(|e| ApiError::RatesUnavailable(format!("Unreadable rate: {}", e)))?;
+        Ok(Some(body.rate).filter(|rate| *rate > Decimal::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        extract::Query,
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::common::testing::{self, dec};
+
+    fn currency(code: &str) -> Currency {
+        code.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn static_rates_derive_the_reverse_and_skip_invalid_entries() {
+        let rates = StaticRates::parse(" EUR/USD=1.25, EUR/BAM=1.95583 ,USD/EUR=0.8,broken,EUR/GBP=0,eur/CHF=1,EUR/JPY=abc");
+
+        assert_eq!(rates.rate(currency("EUR"), currency("USD")).await.unwrap(), Some(dec("1.25")));
+        assert_eq!(rates.rate(currency("USD"), currency("EUR")).await.unwrap(), Some(dec("0.8")));
+        assert_eq!(rates.rate(currency("BAM"), currency("EUR")).await.unwrap(), Some(Decimal::ONE / dec("1.95583")));
+        assert_eq!(rates.rate(currency("EUR"), currency("EUR")).await.unwrap(), Some(Decimal::ONE));
+        for (from, to) in [("EUR", "GBP"), ("EUR", "CHF"), ("EUR", "JPY"), ("USD", "BAM")] {
+            assert_eq!(rates.rate(currency(from), currency(to)).await.unwrap(), None, "{}/{}", from, to);
+        }
+    }
+
+    #[test]
+    fn the_configured_provider_is_used() {
+        let config = testing::config(&[("money.rates", "EUR/USD=1.08")]);
+        assert!(format!("{:?}", create_rate_source(&config.money)).starts_with("StaticRates"));
+
+        let config = testing::config(&[("money.rate_provider", "http")]);
+        assert!(format!("{:?}", create_rate_source(&config.money)).starts_with("HttpRates"));
+    }
+
+    /// Answers EUR/USD, EUR/BAD with a zero rate and EUR/ERR with an error, for the API key `secret`
+    async fn rates(headers: HeaderMap, Query(query): Query<HashMap<String, String>>) -> impl IntoResponse {
+        if headers.get("authorization").and_then(|value| value.to_str().ok()) != Some("Bearer secret") {
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+        match (query["from"].as_str(), query["to"].as_str()) {
+            ("EUR", "USD") => Json(json!({ "rate": "1.08" })).into_response(),
+            ("EUR", "BAD") => Json(json!({ "rate": "0" })).into_response(),
+            ("EUR", "ERR") => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            _ => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    async fn http_rates(api_key: &str) -> HttpRates {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, Router::new().route("/rates", get(rates))).await });
+
+        let config = testing::config(&[("money.rate_provider", "http"), ("money.rate_url", &url), ("money.rate_api_key", api_key)]);
+        HttpRates::new(&config.money)
+    }
+
+    #[tokio::test]
+    async fn http_rates_ask_the_rate_service() {
+        let rates = http_rates("secret").await;
+
+        assert_eq!(rates.rate(currency("EUR"), currency("USD")).await.unwrap(), Some(dec("1.08")));
+        assert_eq!(rates.rate(currency("EUR"), currency("EUR")).await.unwrap(), Some(Decimal::ONE));
+        assert_eq!(rates.rate(currency("EUR"), currency("GBP")).await.unwrap(), None);
+        assert_eq!(rates.rate(currency("EUR"), currency("BAD")).await.unwrap(), None);
+        assert!(matches!(rates.rate(currency("EUR"), currency("ERR")).await, Err(ApiError::RatesUnavailable(_))));
+
+        let unauthorized = http_rates("wrong").await;
+        assert!(matches!(unauthorized.rate(currency("EUR"), currency("USD")).await, Err(ApiError::RatesUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn http_rates_answer_rates_unavailable_when_unreachable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let config = testing::config(&[("money.rate_url", &url), ("money.timeout_seconds", "1")]);
+        let rates = HttpRates::new(&config.money);
+        assert!(matches!(rates.rate(currency("EUR"), currency("USD")).await, Err(ApiError::RatesUnavailable(_))));
+    }
+}
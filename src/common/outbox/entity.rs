@@ -0,0 +1,73 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A recorded domain event waiting for, or done with, dispatch to the subscribers
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "outbox")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub kind: String,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub previous: Option<Json>,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub metadata: Json,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTimeWithTimeZone,
+    pub last_error: Option<String>,
+    pub dispatched_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Subscribers done with an event
+pub mod handled {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+    #[sea_orm(table_name = "outbox_handled")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub event_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub subscriber: String,
+        pub handled_at: DateTimeWithTimeZone,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Pending,
+    /// Handled by every subscriber interested in it
+    Dispatched,
+    /// Given up after the last attempt
+    Failed,
+}
+
+impl std::fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventStatus::Pending => write!(f, "PENDING"),
+            EventStatus::Dispatched => write!(f, "DISPATCHED"),
+            EventStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
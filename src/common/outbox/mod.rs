@@ -0,0 +1,712 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType, OnConflict},
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    common::{config::OutboxConfig, ApiError, RequestContext},
+    modules::auth::entity::UserInfo,
+};
+
+pub mod entity;
+
+use entity::{handled, EventStatus};
+
+/// Kinds of the recorded domain events
+pub mod kind {
+    pub const USER_CREATED: &str = "user.created";
+    pub const USER_UPDATED: &str = "user.updated";
+    pub const USER_DEACTIVATED: &str = "user.deactivated";
+    pub const USER_ACTIVATED: &str = "user.activated";
+    pub const RESERVATION_CREATED: &str = "reservation.created";
+    pub const PAYMENT_CAPTURED: &str = "payment.captured";
+    pub const PAYMENT_FAILED: &str = "payment.failed";
+    pub const PAYMENT_REFUNDED: &str = "payment.refunded";
+
+    /// Start of the kinds of order events
+    pub const ORDER_PREFIX: &str = "order.";
+
+    /// Kind of an order moving to a status, e.g. `order.paid`
+    pub fn order(status: &crate::modules::order::entity::OrderStatus) -> String {
+        format!("{}{}", ORDER_PREFIX, status.to_string().to_lowercase())
+    }
+}
+
+/// Types of the records events are about
+pub mod aggregate {
+    pub const USER: &str = "USER";
+    pub const ORDER: &str = "ORDER";
+    pub const RESERVATION: &str = "RESERVATION";
+    pub const PAYMENT: &str = "PAYMENT";
+}
+
+/// Who caused an event and from where, so subscribers can act on behalf of the original request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventMetadata {
+    pub actor: Option<UserInfo>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl From<&RequestContext> for EventMetadata {
+    fn from(ctx: &RequestContext) -> Self {
+        Self {
+            actor: ctx.user.clone(),
+            ip: ctx.ip.clone(),
+            user_agent: ctx.user_agent.clone(),
+            request_id: ctx.request_id.clone(),
+        }
+    }
+}
+
+impl EventMetadata {
+    /// The context of the request that caused the event
+    pub fn context(&self) -> RequestContext {
+        RequestContext {
+            user: self.actor.clone(),
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+            request_id: self.request_id.clone(),
+        }
+    }
+}
+
+/// An event to record along with the change it describes
+#[derive(Debug, Clone)]
+pub struct NewDomainEvent {
+    pub account_id: Uuid,
+    pub kind: String,
+    pub aggregate_type: &'static str,
+    pub aggregate_id: Uuid,
+    pub payload: Value,
+    pub previous: Option<Value>,
+    pub metadata: EventMetadata,
+}
+
+impl NewDomainEvent {
+    /// Create an event about `aggregate`, serializing its payload
+    pub fn new<T: Serialize>(
+        kind: impl Into<String>,
+        account_id: Uuid,
+        aggregate: (&'static str, Uuid),
+        payload: &T,
+        metadata: EventMetadata,
+    ) -> Self {
+        Self {
+            account_id,
+            kind: kind.into(),
+            aggregate_type: aggregate.0,
+            aggregate_id: aggregate.1,
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            previous: None,
+            metadata,
+        }
+    }
+
+    /// Keep the state of the aggregate before the change, serialized like the payload
+    pub fn with_previous<T: Serialize>(mut self, previous: &T) -> Self {
+        self.previous = serde_json::to_value(previous).ok();
+        self
+    }
+}
+
+/// A recorded event as handed to the subscribers
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEvent {
+    /// Stays the same on every retry; subscribers use it as idempotency key
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub kind: String,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub payload: Value,
+    /// State of the aggregate before the change, for events about changing it
+    pub previous: Option<Value>,
+    pub metadata: EventMetadata,
+    pub created_at: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl DomainEvent {
+    /// Deserialize the payload into the type it was recorded from
+    pub fn payload<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        serde_json::from_value(self.payload.clone())
+            .map_err(|e| ApiError::InvalidInput(format!("Invalid payload of event {}: {}", self.id, e)))
+    }
+}
+
+impl From<entity::Model> for DomainEvent {
+    fn from(event: entity::Model) -> Self {
+        Self {
+            id: event.id,
+            account_id: event.account_id,
+            kind: event.kind,
+            aggregate_type: event.aggregate_type,
+            aggregate_id: event.aggregate_id,
+            payload: event.payload,
+            previous: event.previous,
+            metadata: serde_json::from_value(event.metadata).unwrap_or_default(),
+            created_at: event.created_at,
+        }
+    }
+}
+
+/// Record an event in the outbox. Pass the transaction making the change the event describes, so that either
+/// both are stored or neither is.
+pub async fn record<C: ConnectionTrait>(db: &C, event: NewDomainEvent) -> Result<Uuid, ApiError> {
+    let id = Uuid::new_v4();
+    let now = chrono::Utc::now().fixed_offset();
+    let metadata = serde_json::to_value(&event.metadata).unwrap_or(Value::Null);
+
+    entity::ActiveModel {
+        id: Set(id),
+        account_id: Set(event.account_id),
+        kind: Set(event.kind.clone()),
+        aggregate_type: Set(event.aggregate_type.to_string()),
+        aggregate_id: Set(event.aggregate_id),
+        payload: Set(event.payload),
+        previous: Set(event.previous),
+        metadata: Set(metadata),
+        status: Set(EventStatus::Pending.to_string()),
+        attempts: Set(0),
+        next_attempt_at: Set(now),
+        last_error: Set(None),
+        dispatched_at: Set(None),
+        created_at: Set(now),
+    }
+    .insert(db)
+    .await
+    .map_err(|e| {
+        error!("Failed to record {} event: {}", event.kind, e);
+        ApiError::DatabaseError(e.to_string())
+    })?;
+
+    Ok(id)
+}
+
+/// Events holding personal data of a user: those about the user, those naming them as customer and those they caused
+pub fn about_user(user_id: Uuid) -> Condition {
+    Condition::any().add(naming_user(user_id)).add(caused_by(user_id))
+}
+
+/// Events whose payload is the user or names them as customer
+pub fn naming_user(user_id: Uuid) -> Condition {
+    Condition::any()
+        .add(
+            Condition::all()
+                .add(entity::Column::AggregateType.eq(aggregate::USER))
+                .add(entity::Column::AggregateId.eq(user_id)),
+        )
+        .add(Expr::cust_with_values("outbox.payload->>'customer_id' = ?", [user_id.to_string()]))
+}
+
+/// Events recorded while the user was the actor
+fn caused_by(user_id: Uuid) -> Condition {
+    Condition::all().add(Expr::cust_with_values("outbox.metadata->'actor'->>'id' = ?", [user_id.to_string()]))
+}
+
+/// Get the events holding personal data of a user, oldest first
+pub async fn get_about_user<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<Vec<DomainEvent>, ApiError> {
+    let events = entity::Entity::find()
+        .filter(about_user(user_id))
+        .order_by_asc(entity::Column::CreatedAt)
+        .all(db)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch events about user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+    Ok(events.into_iter().map(DomainEvent::from).collect())
+}
+
+/// Empty the payloads and previous states about or naming a user and drop them as actor, along with the address and browser they
+/// acted from. Subscribers still handling such an event receive the redacted version.
+pub async fn redact_user<C: ConnectionTrait>(db: &C, user_id: Uuid) -> Result<(), ApiError> {
+    let map_err = |e: sea_orm::DbErr| {
+        error!("Failed to redact events about user {}: {}", user_id, e);
+        ApiError::DatabaseError(e.to_string())
+    };
+
+    entity::Entity::update_many()
+        .col_expr(entity::Column::Payload, Expr::cust("'{}'::jsonb"))
+        .col_expr(entity::Column::Previous, Expr::cust("NULL"))
+        .filter(naming_user(user_id))
+        .exec(db)
+        .await
+        .map_err(map_err)?;
+
+    entity::Entity::update_many()
+        .col_expr(entity::Column::Metadata, Expr::cust("outbox.metadata - 'actor' - 'ip' - 'user_agent'"))
+        .filter(caused_by(user_id))
+        .exec(db)
+        .await
+        .map_err(map_err)?;
+
+    Ok(())
+}
+
+/// Reacts to recorded events. Delivery is at least once: an event may reach a subscriber again when the process
+/// stops between handling it and noting that, so handlers should use the event ID to drop duplicates.
+#[async_trait::async_trait]
+pub trait EventSubscriber: Send + Sync + std::fmt::Debug {
+    /// Stable name noted for every event handled; renaming a subscriber hands it the pending events again
+    fn name(&self) -> &'static str;
+
+    /// Whether the subscriber wants events of a kind
+    fn handles(&self, kind: &str) -> bool;
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError>;
+}
+
+/// Subscriber registered with the dispatcher
+pub type SharedSubscriber = Arc<dyn EventSubscriber>;
+
+/// Hands recorded events to the subscribers, retrying those that failed with backoff
+#[derive(Debug, Clone)]
+pub struct OutboxDispatcher {
+    db: DatabaseConnection,
+    subscribers: Vec<SharedSubscriber>,
+    config: OutboxConfig,
+}
+
+impl OutboxDispatcher {
+    /// Create a dispatcher without subscribers
+    pub fn new(db: DatabaseConnection, config: &OutboxConfig) -> Self {
+        Self { db, subscribers: Vec::new(), config: config.clone() }
+    }
+
+    /// Register a subscriber
+    pub fn subscribe(mut self, subscriber: SharedSubscriber) -> Self {
+        info!("Subscribing {} to domain events", subscriber.name());
+        self.subscribers.push(subscriber);
+        self
+    }
+
+    /// Dispatch the due events, returning how many were attempted. An event whose attempt could not be recorded
+    /// does not hold up the rest of the batch; it is due again once its lease runs out.
+    pub async fn process_due(&self) -> Result<usize, ApiError> {
+        let lease = chrono::Duration::seconds(self.config.lease_seconds as i64);
+        let due = self.claim_due(self.config.batch_size, lease).await?;
+
+        for event in &due {
+            if let Err(e) = self.attempt(event).await {
+                error!("Failed to dispatch {} event {}, retrying after its lease: {}", event.kind, event.id, e);
+            }
+        }
+        Ok(due.len())
+    }
+
+    /// Delete dispatched events past the retention period, returning how many were deleted
+    pub async fn purge_dispatched(&self) -> Result<u64, ApiError> {
+        let cutoff = chrono::Utc::now().fixed_offset() - chrono::Duration::days(self.config.retention_days);
+
+        let result = entity::Entity::delete_many()
+            .filter(entity::Column::Status.eq(EventStatus::Dispatched.to_string()))
+            .filter(entity::Column::DispatchedAt.lt(cutoff))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to purge dispatched events: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Run `process_due` periodically in the background, and `purge_dispatched` once an hour
+    pub fn spawn_worker(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut last_purge: Option<tokio::time::Instant> = None;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.process_due().await {
+                    error!("Failed to dispatch domain events: {}", e);
+                }
+
+                if last_purge.is_none_or(|at| at.elapsed() >= Duration::from_secs(3600)) {
+                    last_purge = Some(tokio::time::Instant::now());
+                    match self.purge_dispatched().await {
+                        Ok(0) => {}
+                        Ok(count) => info!("Purged {} dispatched domain events", count),
+                        Err(e) => error!("Failed to purge dispatched domain events: {}", e),
+                    }
+                }
+            }
+        })
+    }
+
+    /// Hand an event to every interested subscriber that has not handled it yet
+    async fn attempt(&self, model: &entity::Model) -> Result<(), ApiError> {
+        let attempts = model.attempts + 1;
+        let handled = self.handled_by(model.id).await?;
+        let event = DomainEvent::from(model.clone());
+
+        let mut errors = Vec::new();
+        for subscriber in &self.subscribers {
+            if !subscriber.handles(&event.kind) || handled.contains(subscriber.name()) {
+                continue;
+            }
+            match subscriber.handle(&event).await {
+                Ok(()) => self.mark_handled(event.id, subscriber.name()).await?,
+                Err(e) => errors.push(format!("{}: {}", subscriber.name(), e)),
+            }
+        }
+
+        if errors.is_empty() {
+            return self.record_attempt(event.id, attempts, None, None).await;
+        }
+
+        let error = errors.join("; ");
+        if attempts >= self.config.max_attempts {
+            warn!("Giving up on {} event {} after {} attempts: {}", event.kind, event.id, attempts, error);
+            return self.record_attempt(event.id, attempts, Some(error), None).await;
+        }
+
+        let retry_at = chrono::Utc::now().fixed_offset() + self.backoff(attempts);
+        warn!("Dispatching {} event {} failed, retrying at {}: {}", event.kind, event.id, retry_at, error);
+        self.record_attempt(event.id, attempts, Some(error), Some(retry_at)).await
+    }
+
+    /// Wait before the next attempt, doubling with every failed one up to the configured maximum
+    fn backoff(&self, attempts: i32) -> chrono::Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1) as u32);
+        let seconds = self.config.backoff_seconds.saturating_mul(factor).min(self.config.max_backoff_seconds);
+        chrono::Duration::seconds(seconds as i64)
+    }
+
+    /// Take up to `limit` due events, pushing their next attempt back by `lease` so other workers skip them
+    /// while they are dispatched
+    async fn claim_due(&self, limit: u64, lease: chrono::Duration) -> Result<Vec<entity::Model>, ApiError> {
+        let txn = self.db.begin().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        let now = chrono::Utc::now().fixed_offset();
+
+        let due = entity::Entity::find()
+            .filter(entity::Column::Status.eq(EventStatus::Pending.to_string()))
+            .filter(entity::Column::NextAttemptAt.lte(now))
+            .order_by_asc(entity::Column::NextAttemptAt)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to claim due events: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if !due.is_empty() {
+            entity::Entity::update_many()
+                .col_expr(entity::Column::NextAttemptAt, Expr::value(now + lease))
+                .filter(entity::Column::Id.is_in(due.iter().map(|event| event.id)))
+                .exec(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to lease due events: {}", e);
+                    ApiError::DatabaseError(e.to_string())
+                })?;
+        }
+
+        txn.commit().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        Ok(due)
+    }
+
+    /// Names of the subscribers done with an event
+    async fn handled_by(&self, event_id: Uuid) -> Result<HashSet<String>, ApiError> {
+        let handled = handled::Entity::find()
+            .filter(handled::Column::EventId.eq(event_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch subscribers done with event {}: {}", event_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(handled.into_iter().map(|row| row.subscriber).collect())
+    }
+
+    async fn mark_handled(&self, event_id: Uuid, subscriber: &str) -> Result<(), ApiError> {
+        let row = handled::ActiveModel {
+            event_id: Set(event_id),
+            subscriber: Set(subscriber.to_string()),
+            handled_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        handled::Entity::insert(row)
+            .on_conflict(
+                OnConflict::columns([handled::Column::EventId, handled::Column::Subscriber])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to note {} handled event {}: {}", subscriber, event_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Record a dispatch attempt: dispatched, due again at `retry_at`, or failed for good without it
+    async fn record_attempt(
+        &self,
+        id: Uuid,
+        attempts: i32,
+        error: Option<String>,
+        retry_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    ) -> Result<(), ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        let status = match (&error, retry_at) {
+            (None, _) => EventStatus::Dispatched,
+            (Some(_), Some(_)) => EventStatus::Pending,
+            (Some(_), None) => EventStatus::Failed,
+        };
+
+        let mut update = entity::Entity::update_many()
+            .col_expr(entity::Column::Status, Expr::value(status.to_string()))
+            .col_expr(entity::Column::Attempts, Expr::value(attempts))
+            .col_expr(entity::Column::LastError, Expr::value(error));
+        if status == EventStatus::Dispatched {
+            update = update.col_expr(entity::Column::DispatchedAt, Expr::value(now));
+        }
+        if let Some(retry_at) = retry_at {
+            update = update.col_expr(entity::Column::NextAttemptAt, Expr::value(retry_at));
+        }
+
+        update
+            .filter(entity::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record dispatch of event {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use sea_orm::ConnectionTrait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::common::testing;
+
+    /// Notes the events it is handed and fails the first `failures` of them
+    #[derive(Debug)]
+    struct Recorder {
+        name: &'static str,
+        failures: Mutex<usize>,
+        seen: Mutex<Vec<Uuid>>,
+    }
+
+    impl Recorder {
+        fn new(name: &'static str, failures: usize) -> Arc<Self> {
+            Arc::new(Self { name, failures: Mutex::new(failures), seen: Mutex::new(Vec::new()) })
+        }
+
+        fn seen(&self) -> Vec<Uuid> {
+            self.seen.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Recorder {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn handles(&self, kind: &str) -> bool {
+            kind.starts_with("test.")
+        }
+
+        async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+            self.seen.lock().unwrap().push(event.id);
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(ApiError::InvalidInput(format!("{} failed", self.name)));
+            }
+            Ok(())
+        }
+    }
+
+    /// Deletes the event it is handed, so noting it as handled fails
+    #[derive(Debug)]
+    struct Deleter {
+        db: DatabaseConnection,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSubscriber for Deleter {
+        fn name(&self) -> &'static str {
+            "deleter"
+        }
+
+        fn handles(&self, kind: &str) -> bool {
+            kind == "test.deleted"
+        }
+
+        async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+            entity::Entity::delete_by_id(event.id).exec(&self.db).await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+            Ok(())
+        }
+    }
+
+    fn dispatcher(db: DatabaseConnection, overrides: &[(&str, &str)]) -> OutboxDispatcher {
+        OutboxDispatcher::new(db, &testing::config(overrides).outbox)
+    }
+
+    async fn database() -> Option<(DatabaseConnection, Uuid)> {
+        let (database, _) = testing::database().await?;
+        let db = database.connection().clone();
+        let account_id = Uuid::new_v4();
+        db.execute_unprepared(&format!("INSERT INTO accounts (id, name) VALUES ('{}', 'Test')", account_id)).await.unwrap();
+        Some((db, account_id))
+    }
+
+    async fn record_test(db: &DatabaseConnection, account_id: Uuid, kind: &str) -> Uuid {
+        let event = NewDomainEvent::new(kind, account_id, ("TEST", Uuid::new_v4()), &json!({}), EventMetadata::default());
+        record(db, event).await.unwrap()
+    }
+
+    async fn get(db: &DatabaseConnection, id: Uuid) -> entity::Model {
+        entity::Entity::find_by_id(id).one(db).await.unwrap().unwrap()
+    }
+
+    /// Make an event due now, as if its backoff or lease had run out
+    async fn make_due(db: &DatabaseConnection, id: Uuid) {
+        entity::Entity::update_many()
+            .col_expr(entity::Column::NextAttemptAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(entity::Column::Id.eq(id))
+            .exec(db)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let dispatcher = dispatcher(
+            DatabaseConnection::Disconnected,
+            &[("outbox.backoff_seconds", "5"), ("outbox.max_backoff_seconds", "60")],
+        );
+
+        let waits: Vec<i64> = [1, 2, 3, 4, 5, 6, 40].iter().map(|attempts| dispatcher.backoff(*attempts).num_seconds()).collect();
+        assert_eq!(waits, vec![5, 10, 20, 40, 60, 60, 60]);
+    }
+
+    #[tokio::test]
+    async fn claimed_events_are_hidden_from_other_workers_until_the_lease_runs_out() {
+        let Some((db, account_id)) = database().await else { return };
+        let first = record_test(&db, account_id, "test.first").await;
+        let second = record_test(&db, account_id, "test.second").await;
+        let dispatcher = dispatcher(db.clone(), &[]);
+        let lease = chrono::Duration::seconds(60);
+
+        let claimed = dispatcher.claim_due(1, lease).await.unwrap();
+        assert_eq!(claimed.iter().map(|event| event.id).collect::<Vec<_>>(), vec![first]);
+        assert!(get(&db, first).await.next_attempt_at > chrono::Utc::now().fixed_offset() + chrono::Duration::seconds(50));
+
+        let claimed = dispatcher.claim_due(10, lease).await.unwrap();
+        assert_eq!(claimed.iter().map(|event| event.id).collect::<Vec<_>>(), vec![second]);
+        assert!(dispatcher.claim_due(10, lease).await.unwrap().is_empty());
+
+        make_due(&db, first).await;
+        assert_eq!(dispatcher.claim_due(10, lease).await.unwrap()[0].id, first);
+    }
+
+    #[tokio::test]
+    async fn failed_subscribers_are_retried_with_backoff_without_repeating_the_others() {
+        let Some((db, account_id)) = database().await else { return };
+        let id = record_test(&db, account_id, "test.retried").await;
+        let steady = Recorder::new("steady", 0);
+        let flaky = Recorder::new("flaky", 1);
+        let dispatcher = dispatcher(db.clone(), &[("outbox.backoff_seconds", "30")])
+            .subscribe(steady.clone())
+            .subscribe(flaky.clone());
+
+        assert_eq!(dispatcher.process_due().await.unwrap(), 1);
+        let event = get(&db, id).await;
+        assert_eq!(event.status, EventStatus::Pending.to_string());
+        assert_eq!(event.attempts, 1);
+        assert_eq!(event.last_error.as_deref(), Some("flaky: Invalid input: flaky failed"));
+        assert!(event.next_attempt_at > chrono::Utc::now().fixed_offset() + chrono::Duration::seconds(20));
+        assert_eq!(dispatcher.handled_by(id).await.unwrap(), HashSet::from(["steady".to_string()]));
+
+        // Not due before the backoff runs out
+        assert_eq!(dispatcher.process_due().await.unwrap(), 0);
+
+        make_due(&db, id).await;
+        dispatcher.process_due().await.unwrap();
+        let event = get(&db, id).await;
+        assert_eq!(event.status, EventStatus::Dispatched.to_string());
+        assert_eq!(event.attempts, 2);
+        assert_eq!(steady.seen(), vec![id]);
+        assert_eq!(flaky.seen(), vec![id, id]);
+    }
+
+    #[tokio::test]
+    async fn events_are_given_up_after_the_last_attempt() {
+        let Some((db, account_id)) = database().await else { return };
+        let id = record_test(&db, account_id, "test.failing").await;
+        let failing = Recorder::new("failing", usize::MAX);
+        let dispatcher = dispatcher(db.clone(), &[("outbox.max_attempts", "2")]).subscribe(failing.clone());
+
+        for _ in 0..3 {
+            make_due(&db, id).await;
+            dispatcher.process_due().await.unwrap();
+        }
+
+        let event = get(&db, id).await;
+        assert_eq!(event.status, EventStatus::Failed.to_string());
+        assert_eq!(event.attempts, 2);
+        assert_eq!(failing.seen().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn events_interrupted_mid_dispatch_are_delivered_again() {
+        let Some((db, account_id)) = database().await else { return };
+        let id = record_test(&db, account_id, "test.interrupted").await;
+        let recorder = Recorder::new("recorder", 0);
+        let dispatcher = dispatcher(db.clone(), &[]).subscribe(recorder.clone());
+
+        // A worker handled the event and stopped before noting it
+        let claimed = dispatcher.claim_due(10, chrono::Duration::seconds(60)).await.unwrap();
+        recorder.handle(&DomainEvent::from(claimed[0].clone())).await.unwrap();
+
+        make_due(&db, id).await;
+        dispatcher.process_due().await.unwrap();
+
+        assert_eq!(recorder.seen(), vec![id, id]);
+        assert_eq!(get(&db, id).await.status, EventStatus::Dispatched.to_string());
+    }
+
+    #[tokio::test]
+    async fn an_event_that_cannot_be_recorded_does_not_hold_up_the_batch() {
+        let Some((db, account_id)) = database().await else { return };
+        let deleted = record_test(&db, account_id, "test.deleted").await;
+        let next = record_test(&db, account_id, "test.next").await;
+        let recorder = Recorder::new("recorder", 0);
+        let dispatcher = dispatcher(db.clone(), &[])
+            .subscribe(Arc::new(Deleter { db: db.clone() }))
+            .subscribe(recorder.clone());
+
+        assert_eq!(dispatcher.process_due().await.unwrap(), 2);
+
+        assert!(entity::Entity::find_by_id(deleted).one(&db).await.unwrap().is_none());
+        assert_eq!(get(&db, next).await.status, EventStatus::Dispatched.to_string());
+        assert!(recorder.seen().contains(&next));
+    }
+}
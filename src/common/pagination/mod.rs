@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// Default number of items per page
+pub const DEFAULT_PER_PAGE: u64 = 50;
+
+/// Upper bound on items per page
+pub const MAX_PER_PAGE: u64 = 200;
+
+/// One page of a paginated listing
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+/// Normalize optional page parameters to a 1-based page and a bounded page size
+pub fn normalize(page: Option<u64>, per_page: Option<u64>) -> (u64, u64) {
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE);
+    (page, per_page)
+}
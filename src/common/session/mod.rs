@@ -1,10 +1,9 @@
 use axum::{
-    extract::{FromRequestParts, State},
+    extract::FromRequestParts,
     http::{request::Parts, StatusCode},
-    response::Response,
 };
 use tower_sessions::{
-    Session, SessionManagerLayer, SessionStore,
+    Session, SessionManagerLayer,
 };
 use tower_sessions_redis_store::RedisStore;
 use fred::clients::RedisClient;
@@ -13,7 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     common::config::SessionConfig,
-    modules::auth::entity::UserInfo,
+    modules::{auth::entity::UserInfo, table::entity::GuestInfo},
 };
 
 /// Session data stored in the session
@@ -28,7 +27,7 @@ pub type SessionStoreType = RedisStore<RedisClient>;
 /// Create session layer with Redis store
 pub async fn create_session_layer(config: &SessionConfig) -> SessionManagerLayer<SessionStoreType> {
     let redis_client = RedisClient::new(
-        fred::types::RedisConfig::from_url(&config.redis_url)
+        fred::types::RedisConfig::from_url(config.redis_url.expose())
             .expect("Failed to parse Redis URL"),
         None,
         None,
@@ -84,6 +83,33 @@ where
     }
 }
 
+/// Extract the guest seated at a table from the session
+#[derive(Debug, Clone)]
+pub struct SessionGuest(pub GuestInfo);
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for SessionGuest
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let session = parts
+            .extensions
+            .get::<Session>()
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        let guest: GuestInfo = session
+            .get("guest")
+            .await
+            .map_err(|_| StatusCode::UNAUTHORIZED)?
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
+        Ok(SessionGuest(guest))
+    }
+}
+
 /// Session management utilities
 pub struct SessionManager;
 
@@ -99,6 +125,15 @@ impl SessionManager {
         session.remove::<SessionData>("user").await
     }
 
+    /// Switch the branch the logged in user is working at
+    pub async fn set_active_branch(session: &Session, branch_id: uuid::Uuid) -> Result<(), tower_sessions::session::Error> {
+        if let Some(mut session_data) = session.get::<SessionData>("user").await? {
+            session_data.user.active_branch_id = Some(branch_id.to_string());
+            session.insert("user", session_data).await?;
+        }
+        Ok(())
+    }
+
     /// Check if user is logged in
     pub async fn is_logged_in(session: &Session) -> bool {
         session.get::<SessionData>("user").await.is_ok()
@@ -112,4 +147,14 @@ impl SessionManager {
             None
         }
     }
+
+    /// Seat a guest at a table by storing the table in the session
+    pub async fn start_guest(session: &Session, guest: GuestInfo) -> Result<(), tower_sessions::session::Error> {
+        session.insert("guest", guest).await
+    }
+
+    /// End a guest session
+    pub async fn end_guest(session: &Session) -> Result<Option<GuestInfo>, tower_sessions::session::Error> {
+        session.remove::<GuestInfo>("guest").await
+    }
 }
@@ -0,0 +1,46 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign a payload with HMAC-SHA256, returning a URL-safe `payload.signature` token
+pub fn sign(secret: &str, payload: &str) -> String {
+    let signature = mac(secret, payload.as_bytes()).finalize().into_bytes();
+
+    format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    )
+}
+
+/// Verify a token produced by `sign` and return its payload if the signature matches
+pub fn verify(secret: &str, token: &str) -> Option<String> {
+    let (payload, signature) = token.split_once('.')?;
+
+    let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+    // Constant-time comparison of the signature
+    mac(secret, &payload).verify_slice(&signature).ok()?;
+
+    String::from_utf8(payload).ok()
+}
+
+/// HMAC-SHA256 of a payload as lowercase hex, e.g. for signatures third parties check
+pub fn hex_signature(secret: &str, payload: &[u8]) -> String {
+    mac(secret, payload)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn mac(secret: &str, payload: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(payload);
+    mac
+}
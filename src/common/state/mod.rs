@@ -1,28 +1,404 @@
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::common::config::Config;
 use crate::common::database::Database;
+use crate::common::events::SharedEventBus;
+use crate::common::mail::create_mailer;
+use crate::common::money::rates::create_rate_source;
+use crate::common::outbox::{self, OutboxDispatcher};
+use crate::modules::account::repository::AccountRepository;
+use crate::modules::account::service::AccountService;
+use crate::modules::audit::repository::AuditRepository;
+use crate::modules::audit::service::AuditService;
+use crate::modules::audit::subscriber::AuditSubscriber;
+use crate::modules::branch::repository::BranchRepository;
+use crate::modules::branch::service::BranchService;
+use crate::modules::inventory::repository::InventoryRepository;
+use crate::modules::inventory::service::InventoryService;
+use crate::modules::inventory::subscriber::InventorySubscriber;
+use crate::modules::loyalty::repository::LoyaltyRepository;
+use crate::modules::loyalty::service::LoyaltyService;
+use crate::modules::menu::repository::MenuRepository;
+use crate::modules::menu::service::MenuService;
+use crate::modules::notification::repository::NotificationRepository;
+use crate::modules::notification::service::NotificationService;
+use crate::modules::table::repository::TableRepository;
+use crate::modules::table::service::TableService;
+use crate::modules::order::repository::OrderRepository;
+use crate::modules::order::service::OrderService;
+use crate::modules::station::repository::StationRepository;
+use crate::modules::station::service::StationService;
 use crate::modules::user::repository::UserRepository;
 use crate::modules::user::service::UserService;
 use crate::modules::auth::repository::AuthRepository;
 use crate::modules::auth::service::AuthService;
+use crate::modules::invitation::repository::InvitationRepository;
+use crate::modules::invitation::service::InvitationService;
+use crate::modules::payment::provider::PaymentProviders;
+use crate::modules::payment::repository::PaymentRepository;
+use crate::modules::payment::service::PaymentService;
+use crate::modules::payment::subscriber::SettlementSubscriber;
+use crate::modules::privacy::{holder::RepositoryHolder, service::PrivacyService};
+use crate::modules::receipt::repository::ReceiptRepository;
+use crate::modules::receipt::service::ReceiptService;
+use crate::modules::register::repository::RegisterRepository;
+use crate::modules::register::service::RegisterService;
+use crate::modules::reservation::repository::ReservationRepository;
+use crate::modules::reservation::service::ReservationService;
+use crate::modules::review::repository::ReviewRepository;
+use crate::modules::review::service::ReviewService;
+use crate::modules::schedule::repository::ScheduleRepository;
+use crate::modules::schedule::service::ScheduleService;
+use crate::modules::tax::repository::TaxRepository;
+use crate::modules::tax::service::TaxService;
+use crate::modules::realtime::service::RealtimeService;
+use crate::modules::webhook::repository::WebhookRepository;
+use crate::modules::webhook::service::WebhookService;
+use crate::modules::webhook::subscriber::WebhookSubscriber;
 
 /// Application state containing shared data
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub user_service: UserService,
     pub auth_service: AuthService,
+    pub invitation_service: InvitationService,
+    pub notification_service: NotificationService,
+    pub privacy_service: PrivacyService,
+    pub audit_service: AuditService,
+    pub account_service: AccountService,
+    pub branch_service: BranchService,
+    pub tax_service: TaxService,
+    pub menu_service: MenuService,
+    pub inventory_service: InventoryService,
+    pub table_service: TableService,
+    pub order_service: OrderService,
+    pub loyalty_service: LoyaltyService,
+    pub station_service: StationService,
+    pub payment_service: PaymentService,
+    pub register_service: RegisterService,
+    pub receipt_service: ReceiptService,
+    pub reservation_service: ReservationService,
+    pub review_service: ReviewService,
+    pub schedule_service: ScheduleService,
+    pub realtime_service: RealtimeService,
+    pub webhook_service: WebhookService,
+    pub outbox_dispatcher: OutboxDispatcher,
 }
 
 impl AppState {
     /// Create a new application state
-    pub fn new(database: Database) -> Self {
+    pub fn new(database: Database, config: &Config, events: SharedEventBus) -> Self {
+        let audit_repository = AuditRepository::new(database.connection().clone());
+        let audit_service = AuditService::new(audit_repository);
+
+        let account_repository = AccountRepository::new(database.connection().clone());
+        let branch_repository = BranchRepository::new(database.connection().clone());
+
         let user_repository = UserRepository::new(database.connection().clone());
-        let user_service = UserService::new(user_repository);
+
+        let mailer = create_mailer(&config.mail);
+        let notification_repository = NotificationRepository::new(database.connection().clone());
+        let notification_service = NotificationService::new(
+            notification_repository.clone(),
+            user_repository.clone(),
+            mailer.clone(),
+            &config.notification,
+        );
+
+        let webhook_repository = WebhookRepository::new(database.connection().clone());
+        let webhook_service = WebhookService::new(
+            webhook_repository.clone(),
+            notification_service.clone(),
+            audit_service.clone(),
+            &config.webhook,
+        );
+
+        let user_service = UserService::new(
+            user_repository.clone(),
+            account_repository.clone(),
+            branch_repository.clone(),
+        );
         
         let auth_repository = AuthRepository::new(database.connection().clone());
-        let auth_service = AuthService::new(auth_repository);
+        let auth_service = AuthService::new(
+            auth_repository,
+            account_repository.clone(),
+            audit_service.clone(),
+        );
+
+        let account_service = AccountService::new(
+            account_repository.clone(),
+            user_service.clone(),
+            audit_service.clone(),
+        );
+
+        let invitation_repository = InvitationRepository::new(database.connection().clone());
+        let invitation_service = InvitationService::new(
+            invitation_repository.clone(),
+            user_service.clone(),
+            account_repository,
+            notification_service.clone(),
+            audit_service.clone(),
+            &config.invitation,
+            config.session.secret.expose().clone(),
+        );
+
+        let branch_service = BranchService::new(
+            branch_repository.clone(),
+            user_repository.clone(),
+            audit_service.clone(),
+        );
+
+        let menu_repository = MenuRepository::new(database.connection().clone());
+        let menu_service = MenuService::new(
+            menu_repository.clone(),
+            branch_repository.clone(),
+            audit_service.clone(),
+        );
+
+        let table_repository = TableRepository::new(database.connection().clone());
+        let table_service = TableService::new(
+            table_repository.clone(),
+            branch_repository.clone(),
+            user_repository.clone(),
+            menu_service.clone(),
+            audit_service.clone(),
+            events.clone(),
+            config,
+        );
+
+        let inventory_repository = InventoryRepository::new(database.connection().clone());
+        let inventory_service = InventoryService::new(
+            inventory_repository,
+            branch_repository.clone(),
+            menu_repository.clone(),
+            notification_service.clone(),
+            audit_service.clone(),
+            events.clone(),
+        );
+
+        let tax_repository = TaxRepository::new(database.connection().clone());
+        let tax_service = TaxService::new(tax_repository, branch_repository.clone(), audit_service.clone());
+
+        let payment_repository = PaymentRepository::new(database.connection().clone());
+        let loyalty_repository = LoyaltyRepository::new(database.connection().clone());
+        let loyalty_service = LoyaltyService::new(
+            loyalty_repository.clone(),
+            user_repository.clone(),
+            payment_repository.clone(),
+            audit_service.clone(),
+            events.clone(),
+        );
+
+        let order_repository = OrderRepository::new(database.connection().clone());
+        let receipt_repository = ReceiptRepository::new(database.connection().clone());
+        let receipt_service = ReceiptService::new(
+            receipt_repository,
+            order_repository.clone(),
+            payment_repository.clone(),
+            branch_repository.clone(),
+            user_repository.clone(),
+            mailer,
+            audit_service.clone(),
+        );
+
+        let station_repository = StationRepository::new(database.connection().clone());
+        let order_service = OrderService::new(
+            order_repository.clone(),
+            branch_repository.clone(),
+            menu_service.clone(),
+            table_service.clone(),
+            station_repository.clone(),
+            loyalty_service.clone(),
+            tax_service.clone(),
+            receipt_service.clone(),
+            notification_service.clone(),
+            audit_service.clone(),
+            events.clone(),
+        );
+
+        let station_service = StationService::new(
+            station_repository.clone(),
+            branch_repository.clone(),
+            menu_repository,
+            order_repository.clone(),
+            audit_service.clone(),
+            events.clone(),
+        );
+
+        let register_repository = RegisterRepository::new(database.connection().clone());
+        let payment_service = PaymentService::new(
+            payment_repository.clone(),
+            register_repository.clone(),
+            branch_repository.clone(),
+            order_service.clone(),
+            PaymentProviders::from_config(&config.payment),
+            create_rate_source(&config.money),
+            events.clone(),
+        );
+
+        let register_service = RegisterService::new(
+            register_repository,
+            branch_repository.clone(),
+            payment_repository,
+            order_repository.clone(),
+            user_repository.clone(),
+            audit_service.clone(),
+        );
+
+        let reservation_repository = ReservationRepository::new(database.connection().clone());
+        let reservation_service = ReservationService::new(
+            reservation_repository.clone(),
+            branch_repository.clone(),
+            table_repository.clone(),
+            table_service.clone(),
+            notification_service.clone(),
+            audit_service.clone(),
+            events.clone(),
+            config,
+        );
+
+        let review_repository = ReviewRepository::new(database.connection().clone());
+        let review_service = ReviewService::new(
+            review_repository.clone(),
+            order_repository.clone(),
+            reservation_repository.clone(),
+            table_repository.clone(),
+            branch_repository.clone(),
+            user_repository.clone(),
+            audit_service.clone(),
+        );
+
+        let schedule_repository = ScheduleRepository::new(database.connection().clone());
+        let schedule_service = ScheduleService::new(
+            schedule_repository.clone(),
+            branch_repository.clone(),
+            user_repository.clone(),
+            audit_service.clone(),
+            events.clone(),
+            config,
+        );
+
+        let realtime_service = RealtimeService::new(
+            branch_repository,
+            table_repository,
+            station_repository,
+            events,
+        );
+
+        let outbox_dispatcher = OutboxDispatcher::new(database.connection().clone(), &config.outbox)
+            .subscribe(Arc::new(AuditSubscriber::new(audit_service.clone())))
+            .subscribe(Arc::new(WebhookSubscriber::new(webhook_service.clone())))
+            .subscribe(Arc::new(InventorySubscriber::new(inventory_service.clone(), order_repository.clone())))
+            .subscribe(Arc::new(SettlementSubscriber::new(payment_service.clone())));
+
+        let privacy_service = PrivacyService::new(
+            user_repository,
+            invitation_repository,
+            audit_service.clone(),
+            &config.privacy,
+        )
+        // Webhook deliveries are found through the recorded events, so they are scrubbed first
+        .register(Arc::new(RepositoryHolder::new(
+            "webhook_deliveries",
+            webhook_repository,
+            |repository, user_id| async move { repository.get_deliveries_about_user(user_id).await },
+            |repository, user_id| async move { repository.redact_deliveries_about_user(user_id).await.map(drop) },
+        )))
+        .register(Arc::new(RepositoryHolder::new(
+            "domain_events",
+            database.connection().clone(),
+            |db, user_id| async move { outbox::get_about_user(&db, user_id).await },
+            |db, user_id| async move { outbox::redact_user(&db, user_id).await },
+        )))
+        // Orders and reviews keep the sale and the rating, detached from the customer
+        .register(Arc::new(RepositoryHolder::new(
+            "orders",
+            order_repository,
+            |repository, user_id| async move { repository.get_by_customer_id(user_id).await },
+            |repository, user_id| async move { repository.unlink_customer(user_id).await.map(drop) },
+        )))
+        .register(Arc::new(RepositoryHolder::new(
+            "reviews",
+            review_repository,
+            |repository, user_id| async move { repository.get_all_by_customer_id(user_id).await },
+            |repository, user_id| async move { repository.anonymize_customer(user_id).await.map(drop) },
+        )))
+        .register(Arc::new(RepositoryHolder::new(
+            "loyalty",
+            loyalty_repository,
+            |repository, user_id| async move {
+                Ok(json!({
+                    "balance": repository.get_balance(user_id).await?,
+                    "ledger": repository.get_ledger(user_id).await?,
+                    "vouchers": repository.get_all_personal_vouchers(user_id).await?,
+                    "redemptions": repository.get_redemptions(user_id).await?,
+                }))
+            },
+            |repository, user_id| async move { repository.anonymize_customer(user_id).await },
+        )))
+        .register(Arc::new(RepositoryHolder::new(
+            "reservations",
+            reservation_repository,
+            |repository, user_id| async move { repository.get_by_customer_id(user_id).await },
+            |repository, user_id| async move { repository.anonymize_customer(user_id).await.map(drop) },
+        )))
+        .register(Arc::new(RepositoryHolder::new(
+            "notifications",
+            notification_repository,
+            |repository, user_id| async move {
+                Ok(json!({
+                    "inbox": repository.get_by_user_id(user_id).await?,
+                    "preferences": repository.get_preferences(user_id).await?,
+                    "targets": repository.get_targets(user_id).await?,
+                }))
+            },
+            |repository, user_id| async move { repository.delete_by_user_id(user_id).await },
+        )))
+        // Time entries keep the hours worked, with the staff member's notes cleared
+        .register(Arc::new(RepositoryHolder::new(
+            "schedule",
+            schedule_repository,
+            |repository, user_id| async move {
+                let time_entries = repository.get_entries_of_user(user_id).await?;
+                let breaks = repository.get_breaks(time_entries.iter().map(|entry| entry.id).collect()).await?;
+
+                Ok(json!({
+                    "shifts": repository.get_shifts_of_user(user_id).await?,
+                    "time_entries": time_entries,
+                    "breaks": breaks,
+                }))
+            },
+            |repository, user_id| async move { repository.anonymize_user(user_id).await },
+        )));
 
         Self {
             user_service,
             auth_service,
+            invitation_service,
+            notification_service,
+            privacy_service,
+            audit_service,
+            account_service,
+            branch_service,
+            tax_service,
+            menu_service,
+            inventory_service,
+            table_service,
+            order_service,
+            loyalty_service,
+            station_service,
+            payment_service,
+            register_service,
+            receipt_service,
+            reservation_service,
+            review_service,
+            schedule_service,
+            realtime_service,
+            webhook_service,
+            outbox_dispatcher,
         }
     }
 }
\ No newline at end of file
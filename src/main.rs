@@ -1,29 +1,106 @@
-mod common;
-mod modules;
-mod routes;
+use std::{net::SocketAddr, path::PathBuf};
 
+use axum::Extension;
 use anyhow::Result;
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use clap::{Parser, Subcommand};
+use tracing::info;
+use tracing_subscriber::{filter::{LevelFilter, Targets}, layer::SubscriberExt, FmtSubscriber};
 use dotenvy::dotenv;
 
-use common::{Config, Database, AppState, session::create_session_layer};
-use routes::create_router;
+use rust_api::common::{config::ConfigSources, context::TrustedProxies, Config, Database, AppState, events::create_event_bus, session::create_session_layer};
+use rust_api::routes::create_router;
+
+/// Restaurant management REST API
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// TOML file overriding the built-in defaults; environment variables and flags override it in turn
+    #[arg(short, long, env = "CONFIG_FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Override a setting, e.g. `--set server.port=8080`; may be repeated
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_override, global = true)]
+    overrides: Vec<(String, String)>,
+
+    /// Address to listen on, overriding `server.host`
+    #[arg(long, global = true)]
+    host: Option<String>,
+
+    /// Port to listen on, overriding `server.port`
+    #[arg(long, global = true)]
+    port: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the API server (the default)
+    Serve,
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Load and validate the configuration, then print it with secrets redacted
+    Check,
+}
+
+impl Cli {
+    /// Configuration layers given on the command line; `--host` and `--port` win over `--set`
+    fn sources(&self) -> ConfigSources {
+        let mut overrides = self.overrides.clone();
+        if let Some(ref host) = self.host {
+            overrides.push(("server.host".to_string(), host.clone()));
+        }
+        if let Some(ref port) = self.port {
+            overrides.push(("server.port".to_string(), port.clone()));
+        }
+        ConfigSources { file: self.config.clone(), overrides }
+    }
+}
+
+fn parse_override(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got {:?}", arg))
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load environment variables from .env file
     dotenv().ok();
+
+    let cli = Cli::parse();
+
+    // Load configuration: defaults, config file, environment, then command line
+    let config = match Config::load(&cli.sources()) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("{}", errors);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(Command::Config { command: ConfigCommand::Check }) = cli.command {
+        print!("{}", config.to_redacted_toml());
+        println!("\n# Configuration is valid");
+        return Ok(());
+    }
     
-    // Load configuration
-    let config = Config::from_env();
-    
-    // Initialize tracing with proper configuration
+    // Initialize tracing at the configured level (`logging.level` or `RUST_LOG`), validated when loading
+    let filter: Targets = config.logging.level.parse()?;
     let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
+        .with_max_level(LevelFilter::TRACE)
         .with_target(false)
         .compact()
-        .finish();
+        .finish()
+        .with(filter);
     
     tracing::subscriber::set_global_default(subscriber)?;
 
@@ -37,15 +114,40 @@ async fn main() -> Result<()> {
     database.health_check().await?;
     info!("✅ Database connection verified");
 
+    // Connect the event bus behind real-time updates
+    let events = create_event_bus(&config.events, config.session.redis_url.expose()).await;
+
     // Create application state
-    let state = AppState::new(database);
+    let state = AppState::new(database, &config, events);
+
+    // Anonymize users past the retention period in the background
+    state.privacy_service.clone().spawn_purge_worker(
+        std::time::Duration::from_secs(config.privacy.purge_interval_seconds),
+    );
+
+    // Send queued notifications and retry failed ones in the background
+    state.notification_service.clone().spawn_worker(
+        std::time::Duration::from_secs(config.notification.poll_interval_seconds),
+    );
+
+    // Post queued webhook deliveries and retry failed ones in the background
+    state.webhook_service.clone().spawn_worker(
+        std::time::Duration::from_secs(config.webhook.poll_interval_seconds),
+    );
+
+    // Hand recorded domain events to their subscribers in the background
+    state.outbox_dispatcher.clone().spawn_worker(
+        std::time::Duration::from_secs(config.outbox.poll_interval_seconds),
+    );
 
     // Create session layer
     let session_layer = create_session_layer(&config.session).await;
     
     // Create the router with session middleware
+    let trusted_proxies: TrustedProxies = config.server.trusted_proxies.parse().unwrap_or_default();
     let app = create_router(state)
-        .layer(session_layer);
+        .layer(session_layer)
+        .layer(Extension(trusted_proxies));
 
     // Start the server
     let address = config.server_address();
@@ -55,7 +157,8 @@ async fn main() -> Result<()> {
     info!("📚 Health check available at http://{}/health", address);
     info!("🗄️  Database connected and migrations applied");
     
-    axum::serve(listener, app).await?;
+    // Keep peer addresses available for audit logging
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::account::entity::{Model as Account, OnboardAccountRequest, OnboardedAccount, UpdateAccountRequest},
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Get all accounts
+pub async fn get_all(State(state): State<AppState>) -> Result<Json<Vec<Account>>, ApiError> {
+    info!("Fetching all accounts");
+    let result = state.account_service.get_all().await?;
+    Ok(Json(result))
+}
+
+/// Get a specific account by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Account>, ApiError> {
+    info!("Fetching account with ID: {}", id);
+    let result = state.account_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Sign up a new account with its first general manager
+pub async fn onboard(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Json(payload): Json<OnboardAccountRequest>,
+) -> Result<(StatusCode, Json<OnboardedAccount>), ApiError> {
+    info!("Onboarding account: {}", payload.account_name);
+    
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+    
+    let result = state.account_service.onboard(&ctx, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing account
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateAccountRequest>,
+) -> Result<Json<Account>, ApiError> {
+    info!("Updating account with ID: {}", id);
+    
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+    
+    let result = state.account_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Suspend an account
+pub async fn suspend(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<Json<Account>, ApiError> {
+    info!("Suspending account with ID: {}", id);
+    let result = state.account_service.suspend(&ctx, id).await?;
+    Ok(Json(result))
+}
+
+/// Reactivate a suspended account
+pub async fn reactivate(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+) -> Result<Json<Account>, ApiError> {
+    info!("Reactivating account with ID: {}", id);
+    let result = state.account_service.reactivate(&ctx, id).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,93 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "accounts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub name: String,
+    pub status: String,
+    pub suspended_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Account", 6)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("suspended_at", &self.suspended_at)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether members of this account may log in
+    pub fn is_active(&self) -> bool {
+        self.status == AccountStatus::Active.to_string() && self.deleted_at.is_none()
+    }
+}
+
+// Enums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountStatus {
+    Active,
+    Suspended,
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStatus::Active => write!(f, "ACTIVE"),
+            AccountStatus::Suspended => write!(f, "SUSPENDED"),
+        }
+    }
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateAccountRequest {
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: Option<String>,
+}
+
+/// Sign-up of a new account together with its first general manager; unknown fields such as `role` are rejected
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct OnboardAccountRequest {
+    #[validate(length(min = 1, max = 200, message = "Account name must be between 1 and 200 characters"))]
+    pub account_name: String,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 8, max = 100, message = "Password must be between 8 and 100 characters"))]
+    pub password: String,
+}
+
+/// Result of onboarding: the account and its general manager
+#[derive(Debug, Serialize)]
+pub struct OnboardedAccount {
+    pub account: Model,
+    pub user: crate::modules::user::entity::Model,
+}
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder, TransactionTrait};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::account::entity::{Entity as AccountEntity, Model as Account, AccountStatus, UpdateAccountRequest, Column, ActiveModel},
+    modules::user::{
+        entity::{CreateUserRequest, Model as User},
+        repository::UserRepository,
+    },
+    common::{ApiError, RequestContext},
+};
+
+/// Account repository for database operations
+#[derive(Debug, Clone)]
+pub struct AccountRepository {
+    db: DatabaseConnection,
+}
+
+impl AccountRepository {
+    /// Create a new account repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get all accounts with ordering
+    pub async fn get_all(&self) -> Result<Vec<Account>, ApiError> {
+        info!("Fetching all accounts from database");
+
+        let accounts = AccountEntity::find()
+            .filter(Column::DeletedAt.is_null())
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch accounts: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(accounts)
+    }
+
+    /// Get an account by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Account, ApiError> {
+        info!("Fetching account with ID: {}", id);
+
+        let account = AccountEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch account with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match account {
+            Some(account) => Ok(account),
+            None => Err(ApiError::NotFound("Account not found".to_string())),
+        }
+    }
+
+    /// Create an account together with its first user in one transaction, recording `user.created` along with them
+    pub async fn create_with_owner(
+        &self,
+        ctx: &RequestContext,
+        name: String,
+        owner: CreateUserRequest,
+        password_hash: String,
+    ) -> Result<(Account, User), ApiError> {
+        info!("Onboarding account: {}", name);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start onboarding transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let account = ActiveModel {
+            id: Set(owner.account_id),
+            name: Set(name),
+            status: Set(AccountStatus::Active.to_string()),
+            suspended_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        let account = account.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create account: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let user = UserRepository::create_in(&txn, ctx, owner, password_hash).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit onboarding of account {}: {}", account.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        info!("Onboarded account {} with user {}", account.id, user.id);
+        Ok((account, user))
+    }
+
+    /// Update an existing account
+    pub async fn update(&self, id: Uuid, request: UpdateAccountRequest) -> Result<Account, ApiError> {
+        info!("Updating account with ID: {}", id);
+
+        let mut account: ActiveModel = self.get_by_id(id).await?.into();
+
+        if let Some(name) = request.name {
+            account.name = Set(name);
+        }
+
+        account.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        account.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update account {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Change the status of an account
+    pub async fn set_status(&self, id: Uuid, status: AccountStatus) -> Result<Account, ApiError> {
+        info!("Setting account {} status to {}", id, status);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut account: ActiveModel = self.get_by_id(id).await?.into();
+        account.suspended_at = Set(match status {
+            AccountStatus::Suspended => Some(now),
+            AccountStatus::Active => None,
+        });
+        account.status = Set(status.to_string());
+        account.updated_at = Set(now);
+
+        account.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update account {} status: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
@@ -0,0 +1,30 @@
+use axum::{
+    routing::{get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create account routes for members of an account
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/accounts/:id", get(get_by_id).put(update))
+}
+
+/// Create account administration routes (root only)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/accounts", get(get_all))
+        .route("/accounts/:id/suspend", post(suspend))
+        .route("/accounts/:id/reactivate", post(reactivate))
+        .layer(middleware::from_fn(authorize(vec!["ROOT"])))
+}
+
+/// Create public account routes
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/accounts/onboard", post(onboard))
+}
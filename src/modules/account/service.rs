@@ -0,0 +1,126 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{ApiError, RequestContext},
+    modules::{
+        account::{
+            entity::{AccountStatus, Model as Account, OnboardAccountRequest, OnboardedAccount, UpdateAccountRequest},
+            repository::AccountRepository,
+        },
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        user::{
+            entity::{CreateUserRequest, UserRole},
+            service::UserService,
+        },
+    },
+};
+
+/// Account service layer for business logic
+#[derive(Debug, Clone)]
+pub struct AccountService {
+    repository: AccountRepository,
+    user_service: UserService,
+    audit_service: AuditService,
+}
+
+impl AccountService {
+    /// Create a new account service
+    pub fn new(repository: AccountRepository, user_service: UserService, audit_service: AuditService) -> Self {
+        Self { repository, user_service, audit_service }
+    }
+
+    /// Get all accounts
+    pub async fn get_all(&self) -> Result<Vec<Account>, ApiError> {
+        self.repository.get_all().await
+    }
+
+    /// Get an account by ID, visible to its members and root
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Account, ApiError> {
+        let role = actor.parsed_role()?;
+        if role != UserRole::Root && actor.parsed_account_id()? != id {
+            return Err(ApiError::NotFound("Account not found".to_string()));
+        }
+
+        self.repository.get_by_id(id).await
+    }
+
+    /// Create a new account together with its first general manager
+    pub async fn onboard(&self, ctx: &RequestContext, data: OnboardAccountRequest) -> Result<OnboardedAccount, ApiError> {
+        info!("Onboarding account: {}", data.account_name);
+
+        if self.user_service.exists_by_email(&data.email).await? {
+            return Err(ApiError::UserAlreadyExists);
+        }
+
+        let password_hash = self.user_service.hash_password(&data.password)?;
+        let owner = CreateUserRequest {
+            account_id: Uuid::new_v4(),
+            branch_id: None,
+            name: data.name,
+            email: data.email,
+            password: data.password,
+            role: UserRole::GeneralManager.to_string(),
+        };
+
+        let (account, user) = self.repository
+            .create_with_owner(ctx, data.account_name, owner, password_hash)
+            .await?;
+
+        let ctx = ctx.clone().with_user(UserInfo::from(user.clone()));
+        self.audit(&ctx, AuditAction::AccountCreated, None, &account).await?;
+
+        Ok(OnboardedAccount { account, user })
+    }
+
+    /// Update an account, allowed for its general manager and root
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateAccountRequest) -> Result<Account, ApiError> {
+        info!("Updating account with ID: {}", id);
+
+        let before = self.get_by_id(actor, id).await?;
+        if !actor.parsed_role()?.is_admin() {
+            return Err(ApiError::Forbidden("Only general managers can update the account".to_string()));
+        }
+
+        let account = self.repository.update(id, data).await?;
+
+        self.audit(ctx, AuditAction::AccountUpdated, Some(&before), &account).await?;
+        Ok(account)
+    }
+
+    /// Suspend an account, blocking logins of its users
+    pub async fn suspend(&self, ctx: &RequestContext, id: Uuid) -> Result<Account, ApiError> {
+        info!("Suspending account with ID: {}", id);
+        self.set_status(ctx, id, AccountStatus::Suspended, AuditAction::AccountSuspended).await
+    }
+
+    /// Lift the suspension of an account
+    pub async fn reactivate(&self, ctx: &RequestContext, id: Uuid) -> Result<Account, ApiError> {
+        info!("Reactivating account with ID: {}", id);
+        self.set_status(ctx, id, AccountStatus::Active, AuditAction::AccountReactivated).await
+    }
+
+    async fn set_status(&self, ctx: &RequestContext, id: Uuid, status: AccountStatus, action: AuditAction) -> Result<Account, ApiError> {
+        let before = self.repository.get_by_id(id).await?;
+        if before.status == status.to_string() {
+            return Err(ApiError::Conflict(format!("Account is already {}", status)));
+        }
+
+        let account = self.repository.set_status(id, status).await?;
+
+        self.audit(ctx, action, Some(&before), &account).await?;
+        Ok(account)
+    }
+
+    /// Record an account mutation in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, before: Option<&Account>, after: &Account) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, after.id, action, (AuditTarget::Account, Some(after.id)), before, Some(after))
+            .await
+    }
+}
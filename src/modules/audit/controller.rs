@@ -0,0 +1,22 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use tracing::info;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::audit::entity::{AuditQuery, Model as AuditEvent},
+    common::{AppState, session::SessionUser},
+};
+
+/// Search the audit log of the caller's account
+pub async fn get_all(
+    Query(query): Query<AuditQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<AuditEvent>>, ApiError> {
+    info!("Fetching audit events for account: {}", user.account_id);
+    let result = state.audit_service.search(&user, query).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,342 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "audit_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<Uuid>,
+    pub before: Option<Json>,
+    pub after: Option<Json>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+    /// Domain event the entry was recorded from, if any
+    pub event_id: Option<Uuid>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AuditEvent", 13)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("actor_id", &self.actor_id)?;
+        state.serialize_field("action", &self.action)?;
+        state.serialize_field("target_type", &self.target_type)?;
+        state.serialize_field("target_id", &self.target_id)?;
+        state.serialize_field("before", &self.before)?;
+        state.serialize_field("after", &self.after)?;
+        state.serialize_field("ip", &self.ip)?;
+        state.serialize_field("user_agent", &self.user_agent)?;
+        state.serialize_field("request_id", &self.request_id)?;
+        state.serialize_field("event_id", &self.event_id)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    UserCreated,
+    UserUpdated,
+    UserDeactivated,
+    UserActivated,
+    UserAnonymized,
+    AuthLogin,
+    AuthLoginFailed,
+    AuthLogout,
+    InvitationCreated,
+    InvitationResent,
+    InvitationRevoked,
+    InvitationAccepted,
+    AccountCreated,
+    AccountUpdated,
+    AccountSuspended,
+    AccountReactivated,
+    BranchCreated,
+    BranchUpdated,
+    BranchDeleted,
+    BranchStaffAssigned,
+    BranchStaffUnassigned,
+    MenuCategoryCreated,
+    MenuCategoryUpdated,
+    MenuCategoryDeleted,
+    MenuItemCreated,
+    MenuItemUpdated,
+    MenuItemDeleted,
+    MenuItemPriceSet,
+    MenuItemPriceRemoved,
+    MenuModifierGroupCreated,
+    MenuModifierGroupUpdated,
+    MenuModifierGroupDeleted,
+    FloorAreaCreated,
+    FloorAreaUpdated,
+    FloorAreaDeleted,
+    TableCreated,
+    TableUpdated,
+    TableDeleted,
+    TableStatusChanged,
+    TableWaiterAssigned,
+    TableQrRotated,
+    OrderCreated,
+    OrderUpdated,
+    OrderStatusChanged,
+    StationCreated,
+    StationUpdated,
+    StationDeleted,
+    StationItemsSet,
+    PaymentCaptured,
+    PaymentFailed,
+    PaymentRefunded,
+    RegisterOpened,
+    RegisterCashMoved,
+    RegisterClosed,
+    ReservationCreated,
+    ReservationUpdated,
+    ReservationStatusChanged,
+    StockItemCreated,
+    StockItemUpdated,
+    StockItemDeleted,
+    StockThresholdSet,
+    StockMoved,
+    StockRebuilt,
+    RecipeSet,
+    ShiftCreated,
+    ShiftUpdated,
+    ShiftDeleted,
+    SchedulePublished,
+    ClockedIn,
+    ClockedOut,
+    TimeEntryUpdated,
+    ClockPinSet,
+    ClockPinFailed,
+    ClockPinLocked,
+    LoyaltyRuleCreated,
+    LoyaltyRuleUpdated,
+    LoyaltyRuleDeleted,
+    LoyaltyRewardCreated,
+    LoyaltyRewardUpdated,
+    LoyaltyRewardDeleted,
+    LoyaltyRewardRedeemed,
+    LoyaltyPointsAdjusted,
+    VoucherCreated,
+    VoucherUpdated,
+    TaxJurisdictionCreated,
+    TaxJurisdictionUpdated,
+    TaxJurisdictionDeleted,
+    ReceiptIssued,
+    ReceiptReprinted,
+    ReceiptEmailed,
+    ReviewCreated,
+    ReviewModerated,
+    WebhookCreated,
+    WebhookUpdated,
+    WebhookDeleted,
+    WebhookSecretRotated,
+    WebhookDeliveryReplayed,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditAction::UserCreated => write!(f, "user.created"),
+            AuditAction::UserUpdated => write!(f, "user.updated"),
+            AuditAction::UserDeactivated => write!(f, "user.deactivated"),
+            AuditAction::UserActivated => write!(f, "user.activated"),
+            AuditAction::UserAnonymized => write!(f, "user.anonymized"),
+            AuditAction::AuthLogin => write!(f, "auth.login"),
+            AuditAction::AuthLoginFailed => write!(f, "auth.login_failed"),
+            AuditAction::AuthLogout => write!(f, "auth.logout"),
+            AuditAction::InvitationCreated => write!(f, "invitation.created"),
+            AuditAction::InvitationResent => write!(f, "invitation.resent"),
+            AuditAction::InvitationRevoked => write!(f, "invitation.revoked"),
+            AuditAction::InvitationAccepted => write!(f, "invitation.accepted"),
+            AuditAction::AccountCreated => write!(f, "account.created"),
+            AuditAction::AccountUpdated => write!(f, "account.updated"),
+            AuditAction::AccountSuspended => write!(f, "account.suspended"),
+            AuditAction::AccountReactivated => write!(f, "account.reactivated"),
+            AuditAction::BranchCreated => write!(f, "branch.created"),
+            AuditAction::BranchUpdated => write!(f, "branch.updated"),
+            AuditAction::BranchDeleted => write!(f, "branch.deleted"),
+            AuditAction::BranchStaffAssigned => write!(f, "branch.staff_assigned"),
+            AuditAction::BranchStaffUnassigned => write!(f, "branch.staff_unassigned"),
+            AuditAction::MenuCategoryCreated => write!(f, "menu.category_created"),
+            AuditAction::MenuCategoryUpdated => write!(f, "menu.category_updated"),
+            AuditAction::MenuCategoryDeleted => write!(f, "menu.category_deleted"),
+            AuditAction::MenuItemCreated => write!(f, "menu.item_created"),
+            AuditAction::MenuItemUpdated => write!(f, "menu.item_updated"),
+            AuditAction::MenuItemDeleted => write!(f, "menu.item_deleted"),
+            AuditAction::MenuItemPriceSet => write!(f, "menu.item_price_set"),
+            AuditAction::MenuItemPriceRemoved => write!(f, "menu.item_price_removed"),
+            AuditAction::MenuModifierGroupCreated => write!(f, "menu.modifier_group_created"),
+            AuditAction::MenuModifierGroupUpdated => write!(f, "menu.modifier_group_updated"),
+            AuditAction::MenuModifierGroupDeleted => write!(f, "menu.modifier_group_deleted"),
+            AuditAction::FloorAreaCreated => write!(f, "floor_area.created"),
+            AuditAction::FloorAreaUpdated => write!(f, "floor_area.updated"),
+            AuditAction::FloorAreaDeleted => write!(f, "floor_area.deleted"),
+            AuditAction::TableCreated => write!(f, "table.created"),
+            AuditAction::TableUpdated => write!(f, "table.updated"),
+            AuditAction::TableDeleted => write!(f, "table.deleted"),
+            AuditAction::TableStatusChanged => write!(f, "table.status_changed"),
+            AuditAction::TableWaiterAssigned => write!(f, "table.waiter_assigned"),
+            AuditAction::TableQrRotated => write!(f, "table.qr_rotated"),
+            AuditAction::OrderCreated => write!(f, "order.created"),
+            AuditAction::OrderUpdated => write!(f, "order.updated"),
+            AuditAction::OrderStatusChanged => write!(f, "order.status_changed"),
+            AuditAction::StationCreated => write!(f, "station.created"),
+            AuditAction::StationUpdated => write!(f, "station.updated"),
+            AuditAction::StationDeleted => write!(f, "station.deleted"),
+            AuditAction::StationItemsSet => write!(f, "station.items_set"),
+            AuditAction::PaymentCaptured => write!(f, "payment.captured"),
+            AuditAction::PaymentFailed => write!(f, "payment.failed"),
+            AuditAction::PaymentRefunded => write!(f, "payment.refunded"),
+            AuditAction::RegisterOpened => write!(f, "register.opened"),
+            AuditAction::RegisterCashMoved => write!(f, "register.cash_moved"),
+            AuditAction::RegisterClosed => write!(f, "register.closed"),
+            AuditAction::ReservationCreated => write!(f, "reservation.created"),
+            AuditAction::ReservationUpdated => write!(f, "reservation.updated"),
+            AuditAction::ReservationStatusChanged => write!(f, "reservation.status_changed"),
+            AuditAction::StockItemCreated => write!(f, "inventory.item_created"),
+            AuditAction::StockItemUpdated => write!(f, "inventory.item_updated"),
+            AuditAction::StockItemDeleted => write!(f, "inventory.item_deleted"),
+            AuditAction::StockThresholdSet => write!(f, "inventory.threshold_set"),
+            AuditAction::StockMoved => write!(f, "inventory.stock_moved"),
+            AuditAction::StockRebuilt => write!(f, "inventory.rebuilt"),
+            AuditAction::RecipeSet => write!(f, "inventory.recipe_set"),
+            AuditAction::ShiftCreated => write!(f, "schedule.shift_created"),
+            AuditAction::ShiftUpdated => write!(f, "schedule.shift_updated"),
+            AuditAction::ShiftDeleted => write!(f, "schedule.shift_deleted"),
+            AuditAction::SchedulePublished => write!(f, "schedule.published"),
+            AuditAction::ClockedIn => write!(f, "time_clock.clocked_in"),
+            AuditAction::ClockedOut => write!(f, "time_clock.clocked_out"),
+            AuditAction::TimeEntryUpdated => write!(f, "time_clock.entry_updated"),
+            AuditAction::ClockPinSet => write!(f, "time_clock.pin_set"),
+            AuditAction::ClockPinFailed => write!(f, "time_clock.pin_failed"),
+            AuditAction::ClockPinLocked => write!(f, "time_clock.pin_locked"),
+            AuditAction::LoyaltyRuleCreated => write!(f, "loyalty.rule_created"),
+            AuditAction::LoyaltyRuleUpdated => write!(f, "loyalty.rule_updated"),
+            AuditAction::LoyaltyRuleDeleted => write!(f, "loyalty.rule_deleted"),
+            AuditAction::LoyaltyRewardCreated => write!(f, "loyalty.reward_created"),
+            AuditAction::LoyaltyRewardUpdated => write!(f, "loyalty.reward_updated"),
+            AuditAction::LoyaltyRewardDeleted => write!(f, "loyalty.reward_deleted"),
+            AuditAction::LoyaltyRewardRedeemed => write!(f, "loyalty.reward_redeemed"),
+            AuditAction::LoyaltyPointsAdjusted => write!(f, "loyalty.points_adjusted"),
+            AuditAction::VoucherCreated => write!(f, "voucher.created"),
+            AuditAction::VoucherUpdated => write!(f, "voucher.updated"),
+            AuditAction::TaxJurisdictionCreated => write!(f, "tax_jurisdiction.created"),
+            AuditAction::TaxJurisdictionUpdated => write!(f, "tax_jurisdiction.updated"),
+            AuditAction::TaxJurisdictionDeleted => write!(f, "tax_jurisdiction.deleted"),
+            AuditAction::ReceiptIssued => write!(f, "receipt.issued"),
+            AuditAction::ReceiptReprinted => write!(f, "receipt.reprinted"),
+            AuditAction::ReceiptEmailed => write!(f, "receipt.emailed"),
+            AuditAction::ReviewCreated => write!(f, "review.created"),
+            AuditAction::ReviewModerated => write!(f, "review.moderated"),
+            AuditAction::WebhookCreated => write!(f, "webhook.created"),
+            AuditAction::WebhookUpdated => write!(f, "webhook.updated"),
+            AuditAction::WebhookDeleted => write!(f, "webhook.deleted"),
+            AuditAction::WebhookSecretRotated => write!(f, "webhook.secret_rotated"),
+            AuditAction::WebhookDeliveryReplayed => write!(f, "webhook.delivery_replayed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditTarget {
+    User,
+    Invitation,
+    Account,
+    Branch,
+    MenuCategory,
+    MenuItem,
+    MenuModifierGroup,
+    FloorArea,
+    Table,
+    Order,
+    Station,
+    Payment,
+    RegisterSession,
+    Reservation,
+    StockItem,
+    Shift,
+    TimeEntry,
+    LoyaltyRule,
+    LoyaltyReward,
+    Voucher,
+    TaxJurisdiction,
+    Receipt,
+    Review,
+    Webhook,
+}
+
+impl std::fmt::Display for AuditTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditTarget::User => write!(f, "USER"),
+            AuditTarget::Invitation => write!(f, "INVITATION"),
+            AuditTarget::Account => write!(f, "ACCOUNT"),
+            AuditTarget::Branch => write!(f, "BRANCH"),
+            AuditTarget::MenuCategory => write!(f, "MENU_CATEGORY"),
+            AuditTarget::MenuItem => write!(f, "MENU_ITEM"),
+            AuditTarget::MenuModifierGroup => write!(f, "MENU_MODIFIER_GROUP"),
+            AuditTarget::FloorArea => write!(f, "FLOOR_AREA"),
+            AuditTarget::Table => write!(f, "TABLE"),
+            AuditTarget::Order => write!(f, "ORDER"),
+            AuditTarget::Station => write!(f, "STATION"),
+            AuditTarget::Payment => write!(f, "PAYMENT"),
+            AuditTarget::RegisterSession => write!(f, "REGISTER_SESSION"),
+            AuditTarget::Reservation => write!(f, "RESERVATION"),
+            AuditTarget::StockItem => write!(f, "STOCK_ITEM"),
+            AuditTarget::Shift => write!(f, "SHIFT"),
+            AuditTarget::TimeEntry => write!(f, "TIME_ENTRY"),
+            AuditTarget::LoyaltyRule => write!(f, "LOYALTY_RULE"),
+            AuditTarget::LoyaltyReward => write!(f, "LOYALTY_REWARD"),
+            AuditTarget::Voucher => write!(f, "VOUCHER"),
+            AuditTarget::TaxJurisdiction => write!(f, "TAX_JURISDICTION"),
+            AuditTarget::Receipt => write!(f, "RECEIPT"),
+            AuditTarget::Review => write!(f, "REVIEW"),
+            AuditTarget::Webhook => write!(f, "WEBHOOK"),
+        }
+    }
+}
+
+/// An audit event about to be recorded
+#[derive(Debug, Clone)]
+pub struct NewAuditEvent {
+    pub account_id: Uuid,
+    pub action: AuditAction,
+    pub target_type: AuditTarget,
+    pub target_id: Option<Uuid>,
+    pub before: Option<Json>,
+    pub after: Option<Json>,
+    pub event_id: Option<Uuid>,
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Default)]
+pub struct AuditQuery {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
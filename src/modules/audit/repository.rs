@@ -0,0 +1,153 @@
+use anyhow::Result;
+use sea_orm::{prelude::Json, sea_query::Expr, Condition, DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder, PaginatorTrait};
+use uuid::Uuid;
+use tracing::error;
+
+use crate::{
+    modules::audit::entity::{Entity as AuditEventEntity, Model as AuditEvent, AuditQuery, AuditTarget, NewAuditEvent, Column, ActiveModel},
+    common::{ApiError, RequestContext},
+};
+
+/// Audit repository for database operations
+#[derive(Debug, Clone)]
+pub struct AuditRepository {
+    db: DatabaseConnection,
+}
+
+impl AuditRepository {
+    /// Create a new audit repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Append an audit event
+    pub async fn create(&self, ctx: &RequestContext, event: NewAuditEvent) -> Result<AuditEvent, ApiError> {
+        let audit_event = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(event.account_id),
+            actor_id: Set(ctx.actor_id()),
+            action: Set(event.action.to_string()),
+            target_type: Set(event.target_type.to_string()),
+            target_id: Set(event.target_id),
+            before: Set(event.before),
+            after: Set(event.after),
+            ip: Set(ctx.ip.clone()),
+            user_agent: Set(ctx.user_agent.clone()),
+            request_id: Set(ctx.request_id.clone()),
+            event_id: Set(event.event_id),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        audit_event.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record audit event {}: {}", event.action, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("Event was already audited".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Whether a domain event has been audited already
+    pub async fn has_event(&self, event_id: Uuid) -> Result<bool, ApiError> {
+        let count = AuditEventEntity::find()
+            .filter(Column::EventId.eq(event_id))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to look up audit events of event {}: {}", event_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// Search audit events of an account, newest first, returning one page and the total count
+    pub async fn search(
+        &self,
+        account_id: Uuid,
+        query: &AuditQuery,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<AuditEvent>, u64), ApiError> {
+        let mut select = AuditEventEntity::find().filter(Column::AccountId.eq(account_id));
+
+        if let Some(actor_id) = query.actor_id {
+            select = select.filter(Column::ActorId.eq(actor_id));
+        }
+        if let Some(ref action) = query.action {
+            select = select.filter(Column::Action.eq(action.as_str()));
+        }
+        if let Some(ref target_type) = query.target_type {
+            select = select.filter(Column::TargetType.eq(target_type.as_str()));
+        }
+        if let Some(target_id) = query.target_id {
+            select = select.filter(Column::TargetId.eq(target_id));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(Column::CreatedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count audit events of account {}: {}", account_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let events = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch audit events of account {}: {}", account_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((events, total))
+    }
+
+    /// Get events performed by or targeting a user
+    pub async fn get_by_subject(&self, user_id: Uuid) -> Result<Vec<AuditEvent>, ApiError> {
+        let events = AuditEventEntity::find()
+            .filter(
+                Condition::any()
+                    .add(Column::ActorId.eq(user_id))
+                    .add(
+                        Condition::all()
+                            .add(Column::TargetType.eq(AuditTarget::User.to_string()))
+                            .add(Column::TargetId.eq(user_id)),
+                    ),
+            )
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch audit events of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(events)
+    }
+
+    /// Drop the recorded field values of every event about a target
+    pub async fn redact_target(&self, target_type: AuditTarget, target_id: Uuid) -> Result<u64, ApiError> {
+        let result = AuditEventEntity::update_many()
+            .col_expr(Column::Before, Expr::value(Option::<Json>::None))
+            .col_expr(Column::After, Expr::value(Option::<Json>::None))
+            .filter(Column::TargetType.eq(target_type.to_string()))
+            .filter(Column::TargetId.eq(target_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to redact audit events of {} {}: {}", target_type, target_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,16 @@
+use axum::{
+    routing::get,
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create audit routes
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/audit", get(get_all))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
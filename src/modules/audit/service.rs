@@ -0,0 +1,130 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{outbox::DomainEvent, pagination::{self, Page}, ApiError, RequestContext},
+    modules::{
+        auth::entity::UserInfo,
+        audit::{
+            entity::{AuditAction, AuditQuery, AuditTarget, Model as AuditEvent, NewAuditEvent},
+            repository::AuditRepository,
+        },
+    },
+};
+
+/// Audit service recording who changed what
+#[derive(Debug, Clone)]
+pub struct AuditService {
+    repository: AuditRepository,
+}
+
+impl AuditService {
+    /// Create a new audit service
+    pub fn new(repository: AuditRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Audit a domain event as whoever caused it, with its payload as the new state and the previous state, if
+    /// recorded, as the old one. An event audited before is
+    /// skipped so a redelivered one is not recorded twice, and a failure is returned for the dispatcher to retry.
+    pub async fn record_event(&self, event: &DomainEvent, action: AuditAction, target: AuditTarget) -> Result<(), ApiError> {
+        if self.repository.has_event(event.id).await? {
+            info!("Event {} was already audited", event.id);
+            return Ok(());
+        }
+
+        let (before, after) = diff(event.previous.clone(), Some(event.payload.clone()));
+        let entry = NewAuditEvent {
+            account_id: event.account_id,
+            action,
+            target_type: target,
+            target_id: Some(event.aggregate_id),
+            before,
+            after,
+            event_id: Some(event.id),
+        };
+
+        // Another dispatcher may have audited it since the check
+        match self.repository.create(&event.metadata.context(), entry).await {
+            Ok(_) | Err(ApiError::Conflict(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record a mutation, storing only the fields that changed between `before` and `after`
+    ///
+    /// The mutation is already committed, so a failure leaves it unaudited; it is returned to fail the request
+    /// rather than let it pass silently. Changes that must never go unaudited are recorded as domain events instead.
+    pub async fn record<T: Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Option<Uuid>),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        let (before, after) = diff(
+            before.and_then(|value| serde_json::to_value(value).ok()),
+            after.and_then(|value| serde_json::to_value(value).ok()),
+        );
+
+        let event = NewAuditEvent {
+            account_id,
+            action,
+            target_type: target.0,
+            target_id: target.1,
+            before,
+            after,
+            event_id: None,
+        };
+
+        self.repository.create(ctx, event).await?;
+        Ok(())
+    }
+
+    /// Search the audit log of the caller's account
+    pub async fn search(&self, actor: &UserInfo, query: AuditQuery) -> Result<Page<AuditEvent>, ApiError> {
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository
+            .search(actor.parsed_account_id()?, &query, page, per_page)
+            .await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Get events performed by or targeting a user
+    pub async fn get_by_subject(&self, user_id: Uuid) -> Result<Vec<AuditEvent>, ApiError> {
+        self.repository.get_by_subject(user_id).await
+    }
+
+    /// Drop the recorded field values of every event about a target
+    pub async fn redact_target(&self, target_type: AuditTarget, target_id: Uuid) -> Result<(), ApiError> {
+        self.repository.redact_target(target_type, target_id).await?;
+        Ok(())
+    }
+}
+
+/// Reduce two JSON objects to the top-level fields that differ
+fn diff(before: Option<Value>, after: Option<Value>) -> (Option<Value>, Option<Value>) {
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let mut changed_before = Map::new();
+            let mut changed_after = Map::new();
+
+            for (key, new_value) in &after {
+                let old_value = before.get(key).unwrap_or(&Value::Null);
+                if old_value != new_value {
+                    changed_before.insert(key.clone(), old_value.clone());
+                    changed_after.insert(key.clone(), new_value.clone());
+                }
+            }
+
+            (Some(Value::Object(changed_before)), Some(Value::Object(changed_after)))
+        }
+        other => other,
+    }
+}
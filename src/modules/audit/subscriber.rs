@@ -0,0 +1,82 @@
+use crate::{
+    common::{
+        outbox::{self, DomainEvent, EventSubscriber},
+        ApiError,
+    },
+    modules::audit::{
+        entity::{AuditAction, AuditTarget},
+        service::AuditService,
+    },
+};
+
+/// Audits the changes recorded as domain events, as whoever caused them
+#[derive(Debug, Clone)]
+pub struct AuditSubscriber {
+    audit_service: AuditService,
+}
+
+impl AuditSubscriber {
+    /// Create a new audit subscriber
+    pub fn new(audit_service: AuditService) -> Self {
+        Self { audit_service }
+    }
+
+    /// What an event of a kind is audited as
+    fn action(kind: &str) -> Option<(AuditAction, AuditTarget)> {
+        match kind {
+            outbox::kind::USER_CREATED => Some((AuditAction::UserCreated, AuditTarget::User)),
+            outbox::kind::USER_UPDATED => Some((AuditAction::UserUpdated, AuditTarget::User)),
+            outbox::kind::USER_DEACTIVATED => Some((AuditAction::UserDeactivated, AuditTarget::User)),
+            outbox::kind::USER_ACTIVATED => Some((AuditAction::UserActivated, AuditTarget::User)),
+            outbox::kind::PAYMENT_CAPTURED => Some((AuditAction::PaymentCaptured, AuditTarget::Payment)),
+            outbox::kind::PAYMENT_FAILED => Some((AuditAction::PaymentFailed, AuditTarget::Payment)),
+            outbox::kind::PAYMENT_REFUNDED => Some((AuditAction::PaymentRefunded, AuditTarget::Payment)),
+            outbox::kind::RESERVATION_CREATED => Some((AuditAction::ReservationCreated, AuditTarget::Reservation)),
+            _ if kind.starts_with(outbox::kind::ORDER_PREFIX) => Some((AuditAction::OrderStatusChanged, AuditTarget::Order)),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSubscriber for AuditSubscriber {
+    fn name(&self) -> &'static str {
+        "audit"
+    }
+
+    fn handles(&self, kind: &str) -> bool {
+        Self::action(kind).is_some()
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+        let Some((action, target)) = Self::action(&event.kind) else {
+            return Ok(());
+        };
+
+        self.audit_service.record_event(event, action, target).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::order::entity::OrderStatus;
+
+    #[test]
+    fn audits_the_recorded_changes_against_their_target() {
+        let cases = [
+            (outbox::kind::USER_UPDATED.to_string(), AuditAction::UserUpdated, AuditTarget::User),
+            (outbox::kind::order(&OrderStatus::Paid), AuditAction::OrderStatusChanged, AuditTarget::Order),
+            (outbox::kind::order(&OrderStatus::InPreparation), AuditAction::OrderStatusChanged, AuditTarget::Order),
+            (outbox::kind::PAYMENT_CAPTURED.to_string(), AuditAction::PaymentCaptured, AuditTarget::Payment),
+            (outbox::kind::PAYMENT_FAILED.to_string(), AuditAction::PaymentFailed, AuditTarget::Payment),
+            (outbox::kind::PAYMENT_REFUNDED.to_string(), AuditAction::PaymentRefunded, AuditTarget::Payment),
+            (outbox::kind::RESERVATION_CREATED.to_string(), AuditAction::ReservationCreated, AuditTarget::Reservation),
+        ];
+
+        for (kind, action, target) in cases {
+            assert_eq!(AuditSubscriber::action(&kind).map(|(a, t)| (a.to_string(), t.to_string())), Some((action.to_string(), target.to_string())), "{}", kind);
+        }
+        assert!(AuditSubscriber::action("webhook.test").is_none());
+    }
+}
@@ -9,14 +9,14 @@ use validator::Validate;
 
 use crate::{
     common::ApiError,
-    modules::auth::entity::{LoginRequest, UserInfo},
-    modules::user::entity::CreateUserRequest,
-    common::{AppState, session::SessionManager},
+    modules::auth::entity::{LoginRequest, RegisterRequest},
+    common::{AppState, RequestContext, session::SessionManager},
 };
 
 /// Login an existing user
 pub async fn login(
     State(state): State<AppState>,
+    ctx: RequestContext,
     session: Session,
     Json(payload): Json<LoginRequest>,
 ) -> Result<StatusCode, ApiError> {
@@ -26,20 +26,21 @@ pub async fn login(
     payload.validate()
         .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
     
-    let user_info = state.auth_service.login(payload).await?;
+    let user_info = state.auth_service.login(&ctx, payload).await?;
     
     // Store user in session (like req.logIn() in Node.js)
     SessionManager::login(&session, user_info).await
-        .map_err(|e| ApiError::InternalServerError)?;
+        .map_err(|_| ApiError::InternalServerError)?;
     
     info!("User logged in successfully");
     Ok(StatusCode::OK)
 }
 
-/// Register a new user
+/// Register a customer of an account; new accounts sign up through `/accounts/onboard` and staff join through invitations
 pub async fn register(
     State(state): State<AppState>,
-    Json(payload): Json<CreateUserRequest>,
+    ctx: RequestContext,
+    Json(payload): Json<RegisterRequest>,
 ) -> Result<StatusCode, ApiError> {
     info!("Registration request for email: {}", payload.email);
     
@@ -47,8 +48,8 @@ pub async fn register(
     payload.validate()
         .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
     
-    // Create the user
-    state.user_service.create(payload).await?;
+    // The role is never taken from the request
+    state.user_service.register(&ctx, payload).await?;
     
     info!("User registered successfully");
     Ok(StatusCode::CREATED)
@@ -56,13 +57,19 @@ pub async fn register(
 
 /// Logout user
 pub async fn logout(
+    State(state): State<AppState>,
+    ctx: RequestContext,
     session: Session,
 ) -> Result<StatusCode, ApiError> {
     info!("User logout request");
     
     // Remove user from session (like req.logout() in Node.js)
-    SessionManager::logout(&session).await
-        .map_err(|e| ApiError::InternalServerError)?;
+    let session_data = SessionManager::logout(&session).await
+        .map_err(|_| ApiError::InternalServerError)?;
+    
+    if let Some(session_data) = session_data {
+        state.auth_service.logout(&ctx, &session_data.user).await?;
+    }
     
     info!("User logged out successfully");
     Ok(StatusCode::OK)
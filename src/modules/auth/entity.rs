@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 
+use crate::{common::ApiError, modules::user::entity::UserRole};
+
 /// Login request DTO
 #[derive(Debug, Deserialize, Serialize, Validate)]
 pub struct LoginRequest {
@@ -11,12 +14,31 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// Customer sign-up with an account; unknown fields such as `role` are rejected
+#[derive(Debug, Deserialize, Serialize, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct RegisterRequest {
+    pub account_id: Uuid,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 8, max = 100, message = "Password must be between 8 and 100 characters"))]
+    pub password: String,
+}
+
 /// User information for session context
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserInfo {
     pub id: String,
     pub account_id: String,
     pub branch_id: Option<String>,
+    /// Branch the session is currently working at, starting at the home branch
+    #[serde(default)]
+    pub active_branch_id: Option<String>,
     pub name: Option<String>,
     pub email: String,
     pub role: String,
@@ -29,6 +51,7 @@ impl From<crate::modules::user::entity::Model> for UserInfo {
             id: user.id.to_string(),
             account_id: user.account_id.to_string(),
             branch_id: user.branch_id.map(|id| id.to_string()),
+            active_branch_id: user.branch_id.map(|id| id.to_string()),
             name: user.name,
             email: user.email,
             role: user.role,
@@ -36,3 +59,33 @@ impl From<crate::modules::user::entity::Model> for UserInfo {
         }
     }
 }
+
+impl UserInfo {
+    /// Parse the session user's ID
+    pub fn parsed_id(&self) -> Result<Uuid, ApiError> {
+        Uuid::parse_str(&self.id)
+            .map_err(|_| ApiError::Unauthorized("Invalid session".to_string()))
+    }
+
+    /// Parse the session user's account ID
+    pub fn parsed_account_id(&self) -> Result<Uuid, ApiError> {
+        Uuid::parse_str(&self.account_id)
+            .map_err(|_| ApiError::Unauthorized("Invalid session".to_string()))
+    }
+
+    /// Parse the branch the session is working at, if one is selected
+    pub fn parsed_active_branch_id(&self) -> Result<Option<Uuid>, ApiError> {
+        self.active_branch_id
+            .as_deref()
+            .map(Uuid::parse_str)
+            .transpose()
+            .map_err(|_| ApiError::Unauthorized("Invalid session".to_string()))
+    }
+
+    /// Parse the session user's role
+    pub fn parsed_role(&self) -> Result<UserRole, ApiError> {
+        self.role
+            .parse()
+            .map_err(|_| ApiError::Unauthorized("Invalid session".to_string()))
+    }
+}
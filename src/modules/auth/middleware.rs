@@ -1,5 +1,5 @@
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::Response,
@@ -7,12 +7,13 @@ use axum::{
 use tower_sessions::Session;
 
 use crate::{
-    common::session::SessionManager,
+    common::{session::SessionManager, AppState},
     modules::auth::entity::UserInfo,
 };
 
-/// Authentication middleware that checks if user is authenticated
+/// Authentication middleware that checks if user is authenticated and their account is not suspended
 pub async fn authenticate(
+    State(state): State<AppState>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -22,6 +23,14 @@ pub async fn authenticate(
     
     // Check if user is logged in via session
     if let Some(user_info) = SessionManager::get_current_user(session).await {
+        // Suspending an account ends the sessions of its users
+        let active = state.auth_service.is_session_active(&user_info).await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if !active {
+            let _ = SessionManager::logout(session).await;
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        
         // Set user context in request extensions (like your Node.js implementation)
         request.extensions_mut().insert(user_info);
         Ok(next.run(request).await)
@@ -31,7 +40,7 @@ pub async fn authenticate(
 }
 
 /// Authorization middleware that checks user roles
-pub fn authorize(roles: Vec<&'static str>) -> impl Fn(Request, Next) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, StatusCode>> + Send + 'static>> {
+pub fn authorize(roles: Vec<&'static str>) -> impl Fn(Request, Next) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, StatusCode>> + Send + 'static>> + Clone {
     move |request: Request, next: Next| {
         let roles = roles.clone();
         Box::pin(async move {
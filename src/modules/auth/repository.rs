@@ -25,6 +25,7 @@ impl AuthRepository {
         
         let user = UserEntity::find()
             .filter(Column::Email.eq(email))
+            .filter(Column::DeletedAt.is_null())
             .one(&self.db)
             .await
             .map_err(|e| {
@@ -41,6 +42,7 @@ impl AuthRepository {
         
         let count = UserEntity::find()
             .filter(Column::Email.eq(email))
+            .filter(Column::DeletedAt.is_null())
             .count(&self.db)
             .await
             .map_err(|e| {
@@ -3,13 +3,18 @@ use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use tracing::info;
 
 use crate::{
-    common::ApiError,
+    common::{ApiError, RequestContext},
     modules::{
+        account::{entity::Model as Account, repository::AccountRepository},
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
         auth::{
             entity::{LoginRequest, UserInfo},
             repository::AuthRepository,
         },
-        user::entity::UserStatus,
+        user::entity::{Model as User, UserStatus},
     },
 };
 
@@ -17,22 +22,65 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct AuthService {
     repository: AuthRepository,
+    account_repository: AccountRepository,
+    audit_service: AuditService,
 }
 
 impl AuthService {
     /// Create a new auth service
-    pub fn new(repository: AuthRepository) -> Self {
-        Self { repository }
+    pub fn new(repository: AuthRepository, account_repository: AccountRepository, audit_service: AuditService) -> Self {
+        Self { repository, account_repository, audit_service }
     }
 
     /// Login an existing user
-    pub async fn login(&self, request: LoginRequest) -> Result<UserInfo, ApiError> {
+    pub async fn login(&self, ctx: &RequestContext, request: LoginRequest) -> Result<UserInfo, ApiError> {
         info!("Attempting login for user: {}", request.email);
         
         // Find user by email
         let user = self.repository.find_user_by_email(&request.email).await?
             .ok_or(ApiError::InvalidCredentials)?;
         
+        let account = self.account_repository.get_by_id(user.account_id).await?;
+        
+        if let Err(e) = self.check_credentials(&user, &account, &request) {
+            self.audit(ctx, AuditAction::AuthLoginFailed, &user).await?;
+            return Err(e);
+        }
+        
+        info!("User logged in successfully: {}", user.email);
+        
+        let user_info = UserInfo::from(user.clone());
+        self.audit(&ctx.clone().with_user(user_info.clone()), AuditAction::AuthLogin, &user).await?;
+        
+        Ok(user_info)
+    }
+
+    /// Record a logout of the session user
+    pub async fn logout(&self, ctx: &RequestContext, user: &UserInfo) -> Result<(), ApiError> {
+        info!("User logged out: {}", user.email);
+        
+        let account_id = user.parsed_account_id()?;
+        let ctx = ctx.clone().with_user(user.clone());
+        self.audit_service
+            .record::<User>(&ctx, account_id, AuditAction::AuthLogout, (AuditTarget::User, Some(user.parsed_id()?)), None, None)
+            .await?;
+        
+        Ok(())
+    }
+
+    /// Whether a signed-in session may still be used; sessions of suspended accounts are cut off
+    pub async fn is_session_active(&self, user: &UserInfo) -> Result<bool, ApiError> {
+        let account = self.account_repository.get_by_id(user.parsed_account_id()?).await?;
+        Ok(account.is_active())
+    }
+
+    /// Check that the user may log in with the given password
+    fn check_credentials(&self, user: &User, account: &Account, request: &LoginRequest) -> Result<(), ApiError> {
+        // Suspended accounts block logins of all their users
+        if !account.is_active() {
+            return Err(ApiError::Unauthorized("Account is suspended".to_string()));
+        }
+        
         // Check if user is active
         if user.status != UserStatus::Active.to_string() {
             return Err(ApiError::Unauthorized("User is not active".to_string()));
@@ -40,14 +88,17 @@ impl AuthService {
         
         // Verify password if it exists (some users might not have passwords)
         if let Some(ref password_hash) = user.password_hash {
-            self.verify_password(&request.password, password_hash)?;
+            self.verify_password(&request.password, password_hash)
         } else {
-            return Err(ApiError::InvalidCredentials);
+            Err(ApiError::InvalidCredentials)
         }
-        
-        info!("User logged in successfully: {}", user.email);
-        
-        Ok(UserInfo::from(user))
+    }
+
+    /// Record a login attempt in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, user: &User) -> Result<(), ApiError> {
+        self.audit_service
+            .record::<User>(ctx, user.account_id, action, (AuditTarget::User, Some(user.id)), None, None)
+            .await
     }
 
     /// Verify a password against its hash
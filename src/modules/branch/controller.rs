@@ -0,0 +1,149 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tower_sessions::Session;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::branch::entity::{user_branch, AssignStaffRequest, CreateBranchRequest, Model as Branch, SwitchBranchRequest, UpdateBranchRequest},
+    modules::user::entity::Model as User,
+    common::{AppState, RequestContext, session::{SessionManager, SessionUser}},
+};
+
+/// Get the branches visible to the caller
+pub async fn get_all(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Branch>>, ApiError> {
+    info!("Fetching branches");
+    let result = state.branch_service.get_all(&user).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific branch by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Branch>, ApiError> {
+    info!("Fetching branch with ID: {}", id);
+    let result = state.branch_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Create a new branch
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateBranchRequest>,
+) -> Result<(StatusCode, Json<Branch>), ApiError> {
+    info!("Creating branch: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.branch_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing branch
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateBranchRequest>,
+) -> Result<Json<Branch>, ApiError> {
+    info!("Updating branch with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.branch_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a branch (soft delete)
+pub async fn delete_branch(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting branch with ID: {}", id);
+    state.branch_service.delete(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the staff working at a branch
+pub async fn get_staff(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<User>>, ApiError> {
+    info!("Fetching staff of branch with ID: {}", id);
+    let result = state.branch_service.get_staff(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Assign a user to a branch
+pub async fn assign_staff(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<AssignStaffRequest>,
+) -> Result<(StatusCode, Json<user_branch::Model>), ApiError> {
+    info!("Assigning user {} to branch {}", payload.user_id, id);
+    let result = state.branch_service.assign_staff(&ctx, &user, id, payload.user_id).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Remove a user from a branch
+pub async fn unassign_staff(
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Removing user {} from branch {}", user_id, id);
+    state.branch_service.unassign_staff(&ctx, &user, id, user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get the branch the session is working at
+pub async fn get_active(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Branch>, ApiError> {
+    info!("Fetching active branch");
+    let id = user
+        .parsed_active_branch_id()?
+        .ok_or_else(|| ApiError::NotFound("No active branch selected".to_string()))?;
+    let result = state.branch_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Switch the branch the session is working at
+pub async fn switch_active(
+    State(state): State<AppState>,
+    session: Session,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SwitchBranchRequest>,
+) -> Result<Json<Branch>, ApiError> {
+    info!("Switching active branch to: {}", payload.branch_id);
+    let branch = state.branch_service.resolve_active(&user, payload.branch_id).await?;
+
+    SessionManager::set_active_branch(&session, branch.id).await
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(Json(branch))
+}
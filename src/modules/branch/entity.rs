@@ -0,0 +1,284 @@
+use chrono::NaiveTime;
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::common::{
+    money::{Currency, Locale, Money},
+    ApiError,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "branches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub name: String,
+    pub address: Option<String>,
+    pub timezone: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub opening_hours: OpeningHours,
+    /// Currency amounts of the branch are in
+    pub currency: String,
+    /// Locale amounts are formatted in, e.g. on receipts
+    pub locale: String,
+    /// Jurisdiction whose taxes orders of the branch are charged
+    pub tax_jurisdiction_id: Option<Uuid>,
+    /// Legal details and texts printed on receipts
+    #[sea_orm(column_type = "JsonBinary")]
+    pub branding: Branding,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Branch", 12)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("address", &self.address)?;
+        state.serialize_field("timezone", &self.timezone)?;
+        state.serialize_field("opening_hours", &self.opening_hours)?;
+        state.serialize_field("currency", &self.currency)?;
+        state.serialize_field("locale", &self.locale)?;
+        state.serialize_field("tax_jurisdiction_id", &self.tax_jurisdiction_id)?;
+        state.serialize_field("branding", &self.branding)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Parsed IANA timezone of the branch
+    pub fn tz(&self) -> chrono_tz::Tz {
+        self.timezone.parse().unwrap_or(chrono_tz::UTC)
+    }
+
+    /// Parsed currency of the branch
+    pub fn currency(&self) -> Result<Currency, ApiError> {
+        self.currency.parse().map_err(ApiError::InvalidInput)
+    }
+
+    /// Parsed locale of the branch
+    pub fn locale(&self) -> Locale {
+        self.locale.parse().unwrap_or_default()
+    }
+
+    /// An amount in the branch's currency, rounded to its minor unit
+    pub fn money(&self, amount: Decimal) -> Result<Money, ApiError> {
+        Money::rounded(amount, self.currency()?)
+    }
+}
+
+/// Staff working at a branch other than their home branch
+pub mod user_branch {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "user_branches")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub user_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub branch_id: Uuid,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("UserBranch", 3)?;
+            state.serialize_field("user_id", &self.user_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Opening window within a day; `close` before `open` runs past midnight
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub open: NaiveTime,
+    pub close: NaiveTime,
+}
+
+/// Weekly opening hours, an empty day meaning closed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct OpeningHours {
+    #[serde(default)]
+    pub monday: Vec<TimeRange>,
+    #[serde(default)]
+    pub tuesday: Vec<TimeRange>,
+    #[serde(default)]
+    pub wednesday: Vec<TimeRange>,
+    #[serde(default)]
+    pub thursday: Vec<TimeRange>,
+    #[serde(default)]
+    pub friday: Vec<TimeRange>,
+    #[serde(default)]
+    pub saturday: Vec<TimeRange>,
+    #[serde(default)]
+    pub sunday: Vec<TimeRange>,
+}
+
+impl OpeningHours {
+    /// Opening windows of a weekday
+    pub fn for_weekday(&self, weekday: chrono::Weekday) -> &[TimeRange] {
+        match weekday {
+            chrono::Weekday::Mon => &self.monday,
+            chrono::Weekday::Tue => &self.tuesday,
+            chrono::Weekday::Wed => &self.wednesday,
+            chrono::Weekday::Thu => &self.thursday,
+            chrono::Weekday::Fri => &self.friday,
+            chrono::Weekday::Sat => &self.saturday,
+            chrono::Weekday::Sun => &self.sunday,
+        }
+    }
+}
+
+/// What a branch prints on its receipts besides its name and address
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult, Validate)]
+pub struct Branding {
+    /// Registered name of the business, if it differs from the branch name
+    #[validate(length(max = 200, message = "Legal name must be at most 200 characters"))]
+    pub legal_name: Option<String>,
+
+    /// VAT or other tax registration number
+    #[validate(length(max = 50, message = "Tax ID must be at most 50 characters"))]
+    pub tax_id: Option<String>,
+
+    /// Put in front of invoice numbers, e.g. `MAIN-`
+    #[validate(length(max = 20, message = "Invoice prefix must be at most 20 characters"))]
+    pub invoice_prefix: Option<String>,
+
+    #[validate(length(max = 500, message = "Header must be at most 500 characters"))]
+    pub header: Option<String>,
+
+    #[validate(length(max = 500, message = "Footer must be at most 500 characters"))]
+    pub footer: Option<String>,
+}
+
+// Validators
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("timezone").with_message("Timezone must be an IANA name such as Europe/Sarajevo".into()))
+}
+
+fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    currency
+        .parse::<Currency>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("currency").with_message("Currency must be a three-letter ISO 4217 code".into()))
+}
+
+fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    locale
+        .parse::<Locale>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("locale").with_message(e.into()))
+}
+
+fn default_locale() -> String {
+    Locale::default().to_string()
+}
+
+fn validate_opening_hours(hours: &OpeningHours) -> Result<(), ValidationError> {
+    let days = [
+        &hours.monday, &hours.tuesday, &hours.wednesday, &hours.thursday,
+        &hours.friday, &hours.saturday, &hours.sunday,
+    ];
+    if days.iter().flat_map(|day| day.iter()).any(|range| range.open == range.close) {
+        return Err(ValidationError::new("opening_hours").with_message("Opening and closing times must differ".into()));
+    }
+    Ok(())
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateBranchRequest {
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 500, message = "Address must be at most 500 characters"))]
+    pub address: Option<String>,
+
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: String,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_opening_hours"))]
+    pub opening_hours: OpeningHours,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: String,
+
+    #[serde(default = "default_locale")]
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: String,
+
+    #[serde(default)]
+    #[validate(nested)]
+    pub branding: Branding,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateBranchRequest {
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 500, message = "Address must be at most 500 characters"))]
+    pub address: Option<String>,
+
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+
+    #[validate(custom(function = "validate_opening_hours"))]
+    pub opening_hours: Option<OpeningHours>,
+
+    #[validate(custom(function = "validate_currency"))]
+    pub currency: Option<String>,
+
+    #[validate(custom(function = "validate_locale"))]
+    pub locale: Option<String>,
+
+    #[validate(nested)]
+    pub branding: Option<Branding>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct AssignStaffRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SwitchBranchRequest {
+    pub branch_id: Uuid,
+}
@@ -0,0 +1,300 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::OnConflict, DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder,
+    PaginatorTrait, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::branch::entity::{
+        user_branch, Entity as BranchEntity, Model as Branch, CreateBranchRequest, UpdateBranchRequest, Column, ActiveModel,
+    },
+    common::ApiError,
+};
+
+/// Branch repository for database operations
+#[derive(Debug, Clone)]
+pub struct BranchRepository {
+    db: DatabaseConnection,
+}
+
+impl BranchRepository {
+    /// Create a new branch repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get all branches of an account
+    pub async fn get_by_account_id(&self, account_id: Uuid) -> Result<Vec<Branch>, ApiError> {
+        info!("Fetching branches by account ID: {}", account_id);
+
+        let branches = BranchEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch branches by account ID {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(branches)
+    }
+
+    /// Get branches by ID
+    pub async fn get_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Branch>, ApiError> {
+        info!("Fetching {} branches by ID", ids.len());
+
+        let branches = BranchEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch branches by ID: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(branches)
+    }
+
+    /// Get a branch by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Branch, ApiError> {
+        info!("Fetching branch with ID: {}", id);
+
+        let branch = BranchEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch branch with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match branch {
+            Some(branch) => Ok(branch),
+            None => Err(ApiError::NotFound("Branch not found".to_string())),
+        }
+    }
+
+    /// Create a new branch
+    pub async fn create(&self, account_id: Uuid, request: CreateBranchRequest) -> Result<Branch, ApiError> {
+        info!("Creating branch: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let branch = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            name: Set(request.name),
+            address: Set(request.address),
+            timezone: Set(request.timezone),
+            opening_hours: Set(request.opening_hours),
+            currency: Set(request.currency),
+            locale: Set(request.locale),
+            tax_jurisdiction_id: Set(None),
+            branding: Set(request.branding),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        let branch = branch.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create branch: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        info!("Created branch with ID: {}", branch.id);
+        Ok(branch)
+    }
+
+    /// Update an existing branch
+    pub async fn update(&self, id: Uuid, request: UpdateBranchRequest) -> Result<Branch, ApiError> {
+        info!("Updating branch with ID: {}", id);
+
+        let mut branch: ActiveModel = self.get_by_id(id).await?.into();
+
+        if let Some(name) = request.name {
+            branch.name = Set(name);
+        }
+
+        if let Some(address) = request.address {
+            branch.address = Set(Some(address));
+        }
+
+        if let Some(timezone) = request.timezone {
+            branch.timezone = Set(timezone);
+        }
+
+        if let Some(opening_hours) = request.opening_hours {
+            branch.opening_hours = Set(opening_hours);
+        }
+
+        if let Some(currency) = request.currency {
+            branch.currency = Set(currency);
+        }
+
+        if let Some(locale) = request.locale {
+            branch.locale = Set(locale);
+        }
+
+        if let Some(branding) = request.branding {
+            branch.branding = Set(branding);
+        }
+
+        branch.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        branch.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update branch {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Set or clear the tax jurisdiction of a branch
+    pub async fn set_tax_jurisdiction(&self, id: Uuid, tax_jurisdiction_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        info!("Setting tax jurisdiction of branch {}", id);
+
+        let mut branch: ActiveModel = self.get_by_id(id).await?.into();
+        branch.tax_jurisdiction_id = Set(tax_jurisdiction_id);
+        branch.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        branch.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set tax jurisdiction of branch {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Count the branches charging the taxes of a jurisdiction
+    pub async fn count_by_tax_jurisdiction(&self, tax_jurisdiction_id: Uuid) -> Result<u64, ApiError> {
+        BranchEntity::find()
+            .filter(Column::TaxJurisdictionId.eq(tax_jurisdiction_id))
+            .filter(Column::DeletedAt.is_null())
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count branches of tax jurisdiction {}: {}", tax_jurisdiction_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a branch and drop its staff assignments
+    pub async fn soft_delete(&self, id: Uuid) -> Result<Branch, ApiError> {
+        info!("Soft deleting branch with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut branch: ActiveModel = self.get_by_id(id).await?.into();
+        branch.deleted_at = Set(Some(now));
+        branch.updated_at = Set(now);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction deleting branch {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        user_branch::Entity::delete_many()
+            .filter(user_branch::Column::BranchId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to drop staff assignments of branch {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let branch = branch.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete branch {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit deletion of branch {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(branch)
+    }
+
+    /// Assign a user to a branch, doing nothing if already assigned
+    pub async fn assign(&self, user_id: Uuid, branch_id: Uuid) -> Result<user_branch::Model, ApiError> {
+        info!("Assigning user {} to branch {}", user_id, branch_id);
+
+        let assignment = user_branch::ActiveModel {
+            user_id: Set(user_id),
+            branch_id: Set(branch_id),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        user_branch::Entity::insert(assignment)
+            .on_conflict(
+                OnConflict::columns([user_branch::Column::UserId, user_branch::Column::BranchId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to assign user {} to branch {}: {}", user_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        self.get_assignment(user_id, branch_id)
+            .await?
+            .ok_or_else(|| ApiError::DatabaseError("Assignment was not stored".to_string()))
+    }
+
+    /// Remove a user from a branch
+    pub async fn unassign(&self, user_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        info!("Removing user {} from branch {}", user_id, branch_id);
+
+        let result = user_branch::Entity::delete_by_id((user_id, branch_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove user {} from branch {}: {}", user_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("Assignment not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Get the assignment of a user to a branch, if any
+    pub async fn get_assignment(&self, user_id: Uuid, branch_id: Uuid) -> Result<Option<user_branch::Model>, ApiError> {
+        user_branch::Entity::find_by_id((user_id, branch_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch assignment of user {} to branch {}: {}", user_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the IDs of the branches a user is assigned to
+    pub async fn get_assigned_branch_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>, ApiError> {
+        info!("Fetching branch assignments of user: {}", user_id);
+
+        let assignments = user_branch::Entity::find()
+            .filter(user_branch::Column::UserId.eq(user_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch branch assignments of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(assignments.into_iter().map(|a| a.branch_id).collect())
+    }
+}
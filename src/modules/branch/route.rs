@@ -0,0 +1,27 @@
+use axum::{
+    routing::{delete, get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create branch routes for members of an account
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/branches", get(get_all))
+        .route("/branches/active", get(get_active).put(switch_active))
+        .route("/branches/:id", get(get_by_id))
+}
+
+/// Create branch administration routes (general manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/branches", post(create))
+        .route("/branches/:id", put(update).delete(delete_branch))
+        .route("/branches/:id/staff", get(get_staff).post(assign_staff))
+        .route("/branches/:id/staff/:user_id", delete(unassign_staff))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER"])))
+}
@@ -0,0 +1,176 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{
+            entity::{user_branch, CreateBranchRequest, Model as Branch, UpdateBranchRequest},
+            repository::BranchRepository,
+        },
+        user::{
+            entity::{Model as User, UserRole},
+            repository::UserRepository,
+        },
+    },
+};
+
+/// Branch service layer for business logic
+#[derive(Debug, Clone)]
+pub struct BranchService {
+    repository: BranchRepository,
+    user_repository: UserRepository,
+    audit_service: AuditService,
+}
+
+impl BranchService {
+    /// Create a new branch service
+    pub fn new(repository: BranchRepository, user_repository: UserRepository, audit_service: AuditService) -> Self {
+        Self { repository, user_repository, audit_service }
+    }
+
+    /// List branches visible to the caller: the whole account for managers, otherwise the ones they work at
+    pub async fn get_all(&self, actor: &UserInfo) -> Result<Vec<Branch>, ApiError> {
+        if actor.parsed_role()?.level() >= UserRole::Manager.level() {
+            return self.repository.get_by_account_id(actor.parsed_account_id()?).await;
+        }
+
+        let home = actor.branch_id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+        self.get_for_user(actor.parsed_id()?, home).await
+    }
+
+    /// Get a branch of the caller's account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Branch, ApiError> {
+        self.get_owned(actor, id).await
+    }
+
+    /// Create a branch in the caller's account
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateBranchRequest) -> Result<Branch, ApiError> {
+        info!("Creating branch: {}", data.name);
+
+        let branch = self.repository.create(actor.parsed_account_id()?, data).await?;
+
+        self.audit(ctx, AuditAction::BranchCreated, None, &branch).await?;
+        Ok(branch)
+    }
+
+    /// Update a branch of the caller's account
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateBranchRequest) -> Result<Branch, ApiError> {
+        info!("Updating branch with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let branch = self.repository.update(id, data).await?;
+
+        self.audit(ctx, AuditAction::BranchUpdated, Some(&before), &branch).await?;
+        Ok(branch)
+    }
+
+    /// Soft delete a branch that is no longer anyone's home branch
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting branch with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+
+        let staff = self.user_repository.get_by_branch_id(id).await?;
+        if staff.iter().any(|user| user.branch_id == Some(id)) {
+            return Err(ApiError::Conflict("Branch is still the home branch of some staff".to_string()));
+        }
+
+        let deleted = self.repository.soft_delete(id).await?;
+
+        self.audit(ctx, AuditAction::BranchDeleted, Some(&before), &deleted).await?;
+        Ok(())
+    }
+
+    /// Staff working at a branch, by home branch or assignment
+    pub async fn get_staff(&self, actor: &UserInfo, id: Uuid) -> Result<Vec<User>, ApiError> {
+        self.get_owned(actor, id).await?;
+        self.user_repository.get_by_branch_id(id).await
+    }
+
+    /// Let a user of the account work at a branch besides their home branch
+    pub async fn assign_staff(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, user_id: Uuid) -> Result<user_branch::Model, ApiError> {
+        info!("Assigning user {} to branch {}", user_id, id);
+
+        let branch = self.get_owned(actor, id).await?;
+        let user = self.user_repository.get_by_id(user_id).await?;
+        if user.account_id != branch.account_id {
+            return Err(ApiError::UserNotFound);
+        }
+
+        if user.branch_id == Some(id) {
+            return Err(ApiError::Conflict("Branch is already the user's home branch".to_string()));
+        }
+
+        let assignment = self.repository.assign(user_id, id).await?;
+
+        self.audit_service
+            .record(ctx, branch.account_id, AuditAction::BranchStaffAssigned, (AuditTarget::Branch, Some(id)), None, Some(&assignment))
+            .await?;
+        Ok(assignment)
+    }
+
+    /// Remove a user's assignment to a branch
+    pub async fn unassign_staff(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+        info!("Removing user {} from branch {}", user_id, id);
+
+        let branch = self.get_owned(actor, id).await?;
+        let assignment = self.repository
+            .get_assignment(user_id, id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Assignment not found".to_string()))?;
+
+        self.repository.unassign(user_id, id).await?;
+
+        self.audit_service
+            .record(ctx, branch.account_id, AuditAction::BranchStaffUnassigned, (AuditTarget::Branch, Some(id)), Some(&assignment), None)
+            .await?;
+        Ok(())
+    }
+
+    /// Branches a user works at: their home branch and any assignments
+    pub async fn get_for_user(&self, user_id: Uuid, home_branch_id: Option<Uuid>) -> Result<Vec<Branch>, ApiError> {
+        let mut ids = self.repository.get_assigned_branch_ids(user_id).await?;
+        ids.extend(home_branch_id);
+        self.repository.get_by_ids(ids).await
+    }
+
+    /// Resolve the branch the caller wants to work at, checking they may
+    pub async fn resolve_active(&self, actor: &UserInfo, id: Uuid) -> Result<Branch, ApiError> {
+        let branch = self.get_owned(actor, id).await?;
+
+        let is_home = actor.branch_id.as_deref() == Some(id.to_string().as_str());
+        if actor.parsed_role()?.is_admin() || is_home {
+            return Ok(branch);
+        }
+
+        match self.repository.get_assignment(actor.parsed_id()?, id).await? {
+            Some(_) => Ok(branch),
+            None => Err(ApiError::Forbidden("Not assigned to this branch".to_string())),
+        }
+    }
+
+    /// Fetch a branch, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Branch, ApiError> {
+        let branch = self.repository.get_by_id(id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record a branch mutation in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, before: Option<&Branch>, after: &Branch) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, after.account_id, action, (AuditTarget::Branch, Some(after.id)), before, Some(after))
+            .await
+    }
+}
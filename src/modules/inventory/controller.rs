@@ -0,0 +1,172 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::inventory::entity::{
+        movement, BranchQuery, CreateStockItemRequest, CreateStockMovementRequest, Model as StockItem, MovementQuery, RebuildReport,
+        RecipeView, SetRecipeRequest, SetThresholdRequest, StockQuery, StockView, UpdateStockItemRequest,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List the stock items of the account
+pub async fn get_items(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<StockItem>>, ApiError> {
+    info!("Fetching stock items");
+    let result = state.inventory_service.get_items(&user).await?;
+    Ok(Json(result))
+}
+
+/// Create a stock item
+pub async fn create_item(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateStockItemRequest>,
+) -> Result<(StatusCode, Json<StockItem>), ApiError> {
+    info!("Creating stock item: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.inventory_service.create_item(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a stock item
+pub async fn update_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateStockItemRequest>,
+) -> Result<Json<StockItem>, ApiError> {
+    info!("Updating stock item with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.inventory_service.update_item(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a stock item
+pub async fn delete_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting stock item with ID: {}", id);
+    state.inventory_service.delete_item(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Set the low stock threshold of an item at a branch
+pub async fn set_threshold(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetThresholdRequest>,
+) -> Result<Json<StockView>, ApiError> {
+    info!("Setting low stock threshold of stock item {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.inventory_service.set_threshold(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Stock levels of a branch
+pub async fn get_stock(
+    Query(query): Query<StockQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<StockView>>, ApiError> {
+    info!("Fetching stock levels");
+    let result = state.inventory_service.get_stock(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Record a delivery, waste, correction or stock take
+pub async fn create_movement(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateStockMovementRequest>,
+) -> Result<(StatusCode, Json<movement::Model>), ApiError> {
+    info!("Recording {} of stock item {}", payload.kind, payload.stock_item_id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.inventory_service.record(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Stock ledger of a branch
+pub async fn get_movements(
+    Query(query): Query<MovementQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<movement::Model>>, ApiError> {
+    info!("Fetching stock movements");
+    let result = state.inventory_service.get_movements(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Recompute the stock levels of a branch from its ledger
+pub async fn rebuild(
+    Query(query): Query<BranchQuery>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<RebuildReport>, ApiError> {
+    info!("Rebuilding stock levels");
+    let result = state.inventory_service.rebuild(&ctx, &user, query.branch_id).await?;
+    Ok(Json(result))
+}
+
+/// Get the recipe of a menu item
+pub async fn get_recipe(
+    Path(menu_item_id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<RecipeView>, ApiError> {
+    info!("Fetching recipe of menu item {}", menu_item_id);
+    let result = state.inventory_service.get_recipe(&user, menu_item_id).await?;
+    Ok(Json(result))
+}
+
+/// Replace the recipe of a menu item
+pub async fn set_recipe(
+    Path(menu_item_id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetRecipeRequest>,
+) -> Result<Json<RecipeView>, ApiError> {
+    info!("Setting recipe of menu item {}", menu_item_id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.inventory_service.set_recipe(&ctx, &user, menu_item_id, payload).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,432 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// An ingredient or supply tracked in stock, shared by the branches of an account
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "stock_items")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub name: String,
+    pub unit: String,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("StockItem", 6)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("unit", &self.unit)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// How much of a stock item a branch holds
+pub mod level {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "stock_levels")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub stock_item_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub branch_id: Uuid,
+        pub account_id: Uuid,
+        /// Running balance of the ledger; may go negative when sales outrun recorded receipts
+        #[sea_orm(column_type = "Decimal(Some((14, 3)))")]
+        pub quantity: Decimal,
+        #[sea_orm(column_type = "Decimal(Some((14, 3)))", nullable)]
+        pub low_stock_threshold: Option<Decimal>,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("StockLevel", 6)?;
+            state.serialize_field("stock_item_id", &self.stock_item_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("quantity", &self.quantity)?;
+            state.serialize_field("low_stock_threshold", &self.low_stock_threshold)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    impl Model {
+        pub fn is_low(&self) -> bool {
+            self.low_stock_threshold.is_some_and(|threshold| self.quantity <= threshold)
+        }
+    }
+}
+
+/// Quantity of a stock item used by one portion of a menu item
+pub mod recipe_line {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "recipe_lines")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub menu_item_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub stock_item_id: Uuid,
+        #[sea_orm(column_type = "Decimal(Some((14, 3)))")]
+        pub quantity: Decimal,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("RecipeLine", 4)?;
+            state.serialize_field("menu_item_id", &self.menu_item_id)?;
+            state.serialize_field("stock_item_id", &self.stock_item_id)?;
+            state.serialize_field("quantity", &self.quantity)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Ledger entry changing a stock level; levels are the sum of their movements
+pub mod movement {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "stock_movements")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub branch_id: Uuid,
+        pub stock_item_id: Uuid,
+        pub kind: String,
+        /// Signed change of the level
+        #[sea_orm(column_type = "Decimal(Some((14, 3)))")]
+        pub quantity: Decimal,
+        /// Level right after this movement
+        #[sea_orm(column_type = "Decimal(Some((14, 3)))")]
+        pub balance_after: Decimal,
+        pub order_id: Option<Uuid>,
+        pub reason: Option<String>,
+        pub created_by: Uuid,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("StockMovement", 11)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("stock_item_id", &self.stock_item_id)?;
+            state.serialize_field("kind", &self.kind)?;
+            state.serialize_field("quantity", &self.quantity)?;
+            state.serialize_field("balance_after", &self.balance_after)?;
+            state.serialize_field("order_id", &self.order_id)?;
+            state.serialize_field("reason", &self.reason)?;
+            state.serialize_field("created_by", &self.created_by)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StockUnit {
+    Gram,
+    Kilogram,
+    Millilitre,
+    Litre,
+    Piece,
+}
+
+impl std::fmt::Display for StockUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StockUnit::Gram => write!(f, "G"),
+            StockUnit::Kilogram => write!(f, "KG"),
+            StockUnit::Millilitre => write!(f, "ML"),
+            StockUnit::Litre => write!(f, "L"),
+            StockUnit::Piece => write!(f, "PIECE"),
+        }
+    }
+}
+
+impl std::str::FromStr for StockUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "G" => Ok(StockUnit::Gram),
+            "KG" => Ok(StockUnit::Kilogram),
+            "ML" => Ok(StockUnit::Millilitre),
+            "L" => Ok(StockUnit::Litre),
+            "PIECE" => Ok(StockUnit::Piece),
+            _ => Err(format!("Unit {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovementKind {
+    /// Delivery from a supplier
+    Receipt,
+    /// Used by a served order
+    Sale,
+    /// Spoiled, dropped or thrown away
+    Waste,
+    /// Correction by a signed amount
+    Adjustment,
+    /// Stock take setting the level to what was counted
+    Count,
+}
+
+impl std::fmt::Display for MovementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovementKind::Receipt => write!(f, "RECEIPT"),
+            MovementKind::Sale => write!(f, "SALE"),
+            MovementKind::Waste => write!(f, "WASTE"),
+            MovementKind::Adjustment => write!(f, "ADJUSTMENT"),
+            MovementKind::Count => write!(f, "COUNT"),
+        }
+    }
+}
+
+impl std::str::FromStr for MovementKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "RECEIPT" => Ok(MovementKind::Receipt),
+            "SALE" => Ok(MovementKind::Sale),
+            "WASTE" => Ok(MovementKind::Waste),
+            "ADJUSTMENT" => Ok(MovementKind::Adjustment),
+            "COUNT" => Ok(MovementKind::Count),
+            _ => Err(format!("Movement kind {} is not valid", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_unit(unit: &str) -> Result<(), ValidationError> {
+    unit.parse::<StockUnit>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("unit").with_message(e.into()))
+}
+
+fn validate_kind(kind: &str) -> Result<(), ValidationError> {
+    match kind.parse::<MovementKind>() {
+        Ok(MovementKind::Sale) => Err(ValidationError::new("kind").with_message("Sales are recorded when orders are served".into())),
+        Ok(_) => Ok(()),
+        Err(e) => Err(ValidationError::new("kind").with_message(e.into())),
+    }
+}
+
+fn validate_quantity(quantity: &Decimal) -> Result<(), ValidationError> {
+    if quantity.scale() > 3 {
+        return Err(ValidationError::new("quantity").with_message("Quantity must have at most three decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_positive_quantity(quantity: &Decimal) -> Result<(), ValidationError> {
+    if quantity.is_sign_negative() || quantity.is_zero() {
+        return Err(ValidationError::new("quantity").with_message("Quantity must be positive".into()));
+    }
+    validate_quantity(quantity)
+}
+
+fn validate_threshold(threshold: &Decimal) -> Result<(), ValidationError> {
+    if threshold.is_sign_negative() {
+        return Err(ValidationError::new("low_stock_threshold").with_message("Threshold must not be negative".into()));
+    }
+    validate_quantity(threshold)
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateStockItemRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(custom(function = "validate_unit"))]
+    pub unit: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateStockItemRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_unit"))]
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetThresholdRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+
+    /// Empty to stop warning about this item
+    #[validate(custom(function = "validate_threshold"))]
+    pub low_stock_threshold: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateStockMovementRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub stock_item_id: Uuid,
+
+    /// `RECEIPT`, `WASTE`, `ADJUSTMENT` or `COUNT`
+    #[validate(custom(function = "validate_kind"))]
+    pub kind: String,
+
+    /// Received or wasted amount, signed change for an adjustment, or the counted level for a count
+    #[validate(custom(function = "validate_quantity"))]
+    pub quantity: Decimal,
+
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct RecipeLineRequest {
+    pub stock_item_id: Uuid,
+
+    #[validate(custom(function = "validate_positive_quantity"))]
+    pub quantity: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetRecipeRequest {
+    #[validate(nested)]
+    pub lines: Vec<RecipeLineRequest>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StockQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    #[serde(default)]
+    pub low_only: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct MovementQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub stock_item_id: Option<Uuid>,
+    pub kind: Option<String>,
+    pub order_id: Option<Uuid>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct BranchQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+}
+
+/// A stock item with its level at a branch
+#[derive(Debug, Clone, Serialize)]
+pub struct StockView {
+    #[serde(flatten)]
+    pub item: Model,
+    pub branch_id: Uuid,
+    pub quantity: Decimal,
+    pub low_stock_threshold: Option<Decimal>,
+    pub low: bool,
+}
+
+/// The ingredients of a menu item
+#[derive(Debug, Clone, Serialize)]
+pub struct RecipeView {
+    pub menu_item_id: Uuid,
+    pub lines: Vec<recipe_line::Model>,
+}
+
+/// A level that no longer matched its ledger when rebuilt
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelCorrection {
+    pub stock_item_id: Uuid,
+    pub recorded: Decimal,
+    pub ledger: Decimal,
+}
+
+/// Result of rebuilding the levels of a branch from the ledger
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildReport {
+    pub branch_id: Uuid,
+    pub levels: usize,
+    pub corrections: Vec<LevelCorrection>,
+}
+
+/// A change to apply to a stock level
+#[derive(Debug, Clone)]
+pub struct NewMovement {
+    pub stock_item_id: Uuid,
+    pub kind: MovementKind,
+    /// Signed change, or the counted level for a count
+    pub quantity: Decimal,
+    pub order_id: Option<Uuid>,
+    pub reason: Option<String>,
+}
@@ -0,0 +1,553 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sea_orm::{
+    prelude::Decimal, sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        inventory::entity::{
+            level, movement, recipe_line, ActiveModel, Column, CreateStockItemRequest, Entity as StockItemEntity, LevelCorrection,
+            Model as StockItem, MovementKind, MovementQuery, NewMovement, RecipeLineRequest, UpdateStockItemRequest,
+        },
+        menu::entity::item_outage,
+    },
+    common::ApiError,
+};
+
+/// Inventory repository for database operations
+#[derive(Debug, Clone)]
+pub struct InventoryRepository {
+    db: DatabaseConnection,
+}
+
+impl InventoryRepository {
+    /// Create a new inventory repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the stock items of an account
+    pub async fn get_items(&self, account_id: Uuid) -> Result<Vec<StockItem>, ApiError> {
+        info!("Fetching stock items of account {}", account_id);
+
+        StockItemEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stock items of account {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get stock items by their IDs
+    pub async fn get_items_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<StockItem>, ApiError> {
+        StockItemEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .filter(Column::DeletedAt.is_null())
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stock items: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a stock item by ID
+    pub async fn get_item(&self, id: Uuid) -> Result<StockItem, ApiError> {
+        info!("Fetching stock item with ID: {}", id);
+
+        let item = StockItemEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stock item with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match item {
+            Some(item) => Ok(item),
+            None => Err(ApiError::NotFound("Stock item not found".to_string())),
+        }
+    }
+
+    /// Create a new stock item
+    pub async fn create_item(&self, account_id: Uuid, request: CreateStockItemRequest) -> Result<StockItem, ApiError> {
+        info!("Creating stock item: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let item = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            name: Set(request.name),
+            unit: Set(request.unit),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        item.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create stock item: {}", e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A stock item with this name already exists".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Update a stock item
+    pub async fn update_item(&self, id: Uuid, request: UpdateStockItemRequest) -> Result<StockItem, ApiError> {
+        info!("Updating stock item with ID: {}", id);
+
+        let mut item: ActiveModel = self.get_item(id).await?.into();
+        if let Some(name) = request.name {
+            item.name = Set(name);
+        }
+        if let Some(unit) = request.unit {
+            item.unit = Set(unit);
+        }
+        item.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        item.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update stock item with ID {}: {}", id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A stock item with this name already exists".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Soft delete a stock item, keeping its ledger
+    pub async fn soft_delete_item(&self, id: Uuid) -> Result<StockItem, ApiError> {
+        info!("Soft deleting stock item with ID: {}", id);
+
+        let mut item: ActiveModel = self.get_item(id).await?.into();
+        let now = chrono::Utc::now().fixed_offset();
+        item.deleted_at = Set(Some(now));
+        item.updated_at = Set(now);
+
+        item.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete stock item {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Count the recipes a stock item is used in
+    pub async fn count_recipes_using(&self, stock_item_id: Uuid) -> Result<u64, ApiError> {
+        recipe_line::Entity::find()
+            .filter(recipe_line::Column::StockItemId.eq(stock_item_id))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count recipes using stock item {}: {}", stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the stock levels of a branch
+    pub async fn get_levels(&self, branch_id: Uuid) -> Result<Vec<level::Model>, ApiError> {
+        level::Entity::find()
+            .filter(level::Column::BranchId.eq(branch_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stock levels of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Set or clear the low stock threshold of an item at a branch
+    pub async fn set_threshold(
+        &self,
+        account_id: Uuid,
+        branch_id: Uuid,
+        stock_item_id: Uuid,
+        threshold: Option<Decimal>,
+    ) -> Result<level::Model, ApiError> {
+        info!("Setting low stock threshold of item {} at branch {}", stock_item_id, branch_id);
+
+        Self::ensure_level(&self.db, account_id, branch_id, stock_item_id).await?;
+
+        let mut level: level::ActiveModel = level::Entity::find_by_id((stock_item_id, branch_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stock level of item {}: {}", stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Stock level not found".to_string()))?
+            .into();
+        level.low_stock_threshold = Set(threshold);
+        level.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        level.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set threshold of item {}: {}", stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the recipe lines of menu items
+    pub async fn get_recipes(&self, menu_item_ids: Vec<Uuid>) -> Result<Vec<recipe_line::Model>, ApiError> {
+        recipe_line::Entity::find()
+            .filter(recipe_line::Column::MenuItemId.is_in(menu_item_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch recipes: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the recipe lines using any of the given stock items
+    pub async fn get_recipes_using(&self, stock_item_ids: Vec<Uuid>) -> Result<Vec<recipe_line::Model>, ApiError> {
+        recipe_line::Entity::find()
+            .filter(recipe_line::Column::StockItemId.is_in(stock_item_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch recipes using stock items: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Replace the recipe of a menu item
+    pub async fn set_recipe(&self, menu_item_id: Uuid, lines: Vec<RecipeLineRequest>) -> Result<Vec<recipe_line::Model>, ApiError> {
+        info!("Setting recipe of menu item {}", menu_item_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        recipe_line::Entity::delete_many()
+            .filter(recipe_line::Column::MenuItemId.eq(menu_item_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to clear recipe of menu item {}: {}", menu_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut saved = Vec::with_capacity(lines.len());
+        for line in lines {
+            let line = recipe_line::ActiveModel {
+                menu_item_id: Set(menu_item_id),
+                stock_item_id: Set(line.stock_item_id),
+                quantity: Set(line.quantity),
+                created_at: Set(now),
+            };
+            saved.push(line.insert(&txn).await.map_err(|e| {
+                error!("Failed to add recipe line to menu item {}: {}", menu_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?);
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit recipe of menu item {}: {}", menu_item_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(saved)
+    }
+
+    /// Search the ledger of a branch, newest first, returning one page and the total count
+    pub async fn search_movements(&self, branch_id: Uuid, query: &MovementQuery, page: u64, per_page: u64) -> Result<(Vec<movement::Model>, u64), ApiError> {
+        let mut select = movement::Entity::find().filter(movement::Column::BranchId.eq(branch_id));
+
+        if let Some(stock_item_id) = query.stock_item_id {
+            select = select.filter(movement::Column::StockItemId.eq(stock_item_id));
+        }
+        if let Some(ref kind) = query.kind {
+            select = select.filter(movement::Column::Kind.eq(kind.as_str()));
+        }
+        if let Some(order_id) = query.order_id {
+            select = select.filter(movement::Column::OrderId.eq(order_id));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(movement::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(movement::Column::CreatedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(movement::Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count stock movements of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let movements = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch stock movements of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((movements, total))
+    }
+
+    /// Whether the stock of an order was already taken off
+    pub async fn has_sales_of_order(&self, order_id: Uuid) -> Result<bool, ApiError> {
+        let count = movement::Entity::find()
+            .filter(movement::Column::OrderId.eq(order_id))
+            .filter(movement::Column::Kind.eq(MovementKind::Sale.to_string()))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to look up sales of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// Append movements to the ledger and apply them to the levels in one transaction.
+    /// Levels are locked in item order so concurrent writers cannot deadlock or lose updates.
+    pub async fn record(
+        &self,
+        account_id: Uuid,
+        branch_id: Uuid,
+        created_by: Uuid,
+        mut movements: Vec<NewMovement>,
+    ) -> Result<Vec<(movement::Model, level::Model)>, ApiError> {
+        info!("Recording {} stock movements at branch {}", movements.len(), branch_id);
+        movements.sort_by_key(|movement| movement.stock_item_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut recorded = Vec::with_capacity(movements.len());
+        for new in movements {
+            Self::ensure_level(&txn, account_id, branch_id, new.stock_item_id).await?;
+
+            let current = level::Entity::find_by_id((new.stock_item_id, branch_id))
+                .lock_exclusive()
+                .one(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to lock stock level of item {}: {}", new.stock_item_id, e);
+                    ApiError::DatabaseError(e.to_string())
+                })?
+                .ok_or_else(|| ApiError::NotFound("Stock level not found".to_string()))?;
+
+            let delta = match new.kind {
+                MovementKind::Count => new.quantity - current.quantity,
+                _ => new.quantity,
+            };
+            let balance = current.quantity + delta;
+
+            let mut level: level::ActiveModel = current.into();
+            level.quantity = Set(balance);
+            level.updated_at = Set(now);
+            let level = level.update(&txn).await.map_err(|e| {
+                error!("Failed to update stock level of item {}: {}", new.stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+            let movement = movement::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                account_id: Set(account_id),
+                branch_id: Set(branch_id),
+                stock_item_id: Set(new.stock_item_id),
+                kind: Set(new.kind.to_string()),
+                quantity: Set(delta),
+                balance_after: Set(balance),
+                order_id: Set(new.order_id),
+                reason: Set(new.reason),
+                created_by: Set(created_by),
+                created_at: Set(now),
+            }
+            .insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to record stock movement of item {}: {}", new.stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+            recorded.push((movement, level));
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit stock movements at branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(recorded)
+    }
+
+    /// Recompute every level of a branch as the sum of its ledger, returning the levels that had drifted
+    pub async fn rebuild(&self, branch_id: Uuid) -> Result<(usize, Vec<LevelCorrection>), ApiError> {
+        info!("Rebuilding stock levels of branch {}", branch_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let levels = level::Entity::find()
+            .filter(level::Column::BranchId.eq(branch_id))
+            .order_by_asc(level::Column::StockItemId)
+            .lock_exclusive()
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock stock levels of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let sums: Vec<(Uuid, Option<Decimal>)> = movement::Entity::find()
+            .select_only()
+            .column(movement::Column::StockItemId)
+            .column_as(movement::Column::Quantity.sum(), "quantity")
+            .filter(movement::Column::BranchId.eq(branch_id))
+            .group_by(movement::Column::StockItemId)
+            .into_tuple()
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to sum stock movements of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let sums: HashMap<Uuid, Decimal> = sums.into_iter().map(|(id, sum)| (id, sum.unwrap_or_default())).collect();
+
+        let now = chrono::Utc::now().fixed_offset();
+        let count = levels.len();
+        let mut corrections = Vec::new();
+        for current in levels {
+            let ledger = sums.get(&current.stock_item_id).copied().unwrap_or_default();
+            if ledger == current.quantity {
+                continue;
+            }
+
+            corrections.push(LevelCorrection { stock_item_id: current.stock_item_id, recorded: current.quantity, ledger });
+
+            let mut level: level::ActiveModel = current.into();
+            level.quantity = Set(ledger);
+            level.updated_at = Set(now);
+            level.update(&txn).await.map_err(|e| {
+                error!("Failed to rebuild stock level: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit rebuilt stock levels of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((count, corrections))
+    }
+
+    /// Get the menu items sold out at a branch
+    pub async fn get_outages(&self, branch_id: Uuid) -> Result<Vec<item_outage::Model>, ApiError> {
+        item_outage::Entity::find()
+            .filter(item_outage::Column::BranchId.eq(branch_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch sold out items of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Mark a menu item sold out at a branch
+    pub async fn add_outage(&self, item_id: Uuid, branch_id: Uuid, stock_item_id: Uuid) -> Result<item_outage::Model, ApiError> {
+        info!("Marking menu item {} sold out at branch {}", item_id, branch_id);
+
+        let outage = item_outage::ActiveModel {
+            item_id: Set(item_id),
+            branch_id: Set(branch_id),
+            stock_item_id: Set(Some(stock_item_id)),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        item_outage::Entity::insert(outage)
+            .on_conflict(
+                OnConflict::columns([item_outage::Column::ItemId, item_outage::Column::BranchId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to mark menu item {} sold out: {}", item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        item_outage::Entity::find_by_id((item_id, branch_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch outage of menu item {}: {}", item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Outage not found".to_string()))
+    }
+
+    /// Make a menu item orderable again at a branch
+    pub async fn remove_outage(&self, item_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        info!("Restoring menu item {} at branch {}", item_id, branch_id);
+
+        item_outage::Entity::delete_by_id((item_id, branch_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to restore menu item {}: {}", item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Create an empty level for an item at a branch unless one exists
+    async fn ensure_level<C: ConnectionTrait>(db: &C, account_id: Uuid, branch_id: Uuid, stock_item_id: Uuid) -> Result<(), ApiError> {
+        let level = level::ActiveModel {
+            stock_item_id: Set(stock_item_id),
+            branch_id: Set(branch_id),
+            account_id: Set(account_id),
+            quantity: Set(Decimal::ZERO),
+            low_stock_threshold: Set(None),
+            updated_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        level::Entity::insert(level)
+            .on_conflict(
+                OnConflict::columns([level::Column::StockItemId, level::Column::BranchId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create stock level of item {}: {}", stock_item_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create stock routes for kitchen and bar staff
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/inventory/items", get(get_items))
+        .route("/inventory/stock", get(get_stock))
+        .route("/inventory/movements", post(create_movement))
+        .route("/inventory/recipes/:menu_item_id", get(get_recipe))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "COOK", "BARMAN"])))
+}
+
+/// Create inventory management routes for managers
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/inventory/items", post(create_item))
+        .route("/inventory/items/:id", put(update_item).delete(delete_item))
+        .route("/inventory/items/:id/threshold", put(set_threshold))
+        .route("/inventory/recipes/:menu_item_id", put(set_recipe))
+        .route("/inventory/movements", get(get_movements))
+        .route("/inventory/rebuild", post(rebuild))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
@@ -0,0 +1,433 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{
+        events::{topic, Event, SharedEventBus},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        inventory::{
+            entity::{
+                level, movement, CreateStockItemRequest, CreateStockMovementRequest, Model as StockItem, MovementKind, MovementQuery,
+                NewMovement, RebuildReport, RecipeView, SetRecipeRequest, SetThresholdRequest, StockQuery, StockView,
+                UpdateStockItemRequest,
+            },
+            repository::InventoryRepository,
+        },
+        menu::{entity::item as menu_item, repository::MenuRepository},
+        notification::{entity::NotificationKind, service::NotificationService},
+        order::entity::{line, Model as Order},
+        user::entity::UserRole,
+    },
+};
+
+/// Inventory service layer for business logic
+#[derive(Debug, Clone)]
+pub struct InventoryService {
+    repository: InventoryRepository,
+    branch_repository: BranchRepository,
+    menu_repository: MenuRepository,
+    notification_service: NotificationService,
+    audit_service: AuditService,
+    events: SharedEventBus,
+}
+
+impl InventoryService {
+    /// Create a new inventory service
+    pub fn new(
+        repository: InventoryRepository,
+        branch_repository: BranchRepository,
+        menu_repository: MenuRepository,
+        notification_service: NotificationService,
+        audit_service: AuditService,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            menu_repository,
+            notification_service,
+            audit_service,
+            events,
+        }
+    }
+
+    /// Get the stock items of the caller's account
+    pub async fn get_items(&self, actor: &UserInfo) -> Result<Vec<StockItem>, ApiError> {
+        self.repository.get_items(actor.parsed_account_id()?).await
+    }
+
+    /// Create a stock item in the caller's account
+    pub async fn create_item(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateStockItemRequest) -> Result<StockItem, ApiError> {
+        info!("Creating stock item: {}", data.name);
+
+        let item = self.repository.create_item(actor.parsed_account_id()?, data).await?;
+
+        self.audit(ctx, item.account_id, AuditAction::StockItemCreated, (AuditTarget::StockItem, item.id), None, Some(&item)).await?;
+        Ok(item)
+    }
+
+    /// Update a stock item of the caller's account
+    pub async fn update_item(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateStockItemRequest) -> Result<StockItem, ApiError> {
+        info!("Updating stock item with ID: {}", id);
+
+        let before = self.get_owned_item(actor, id).await?;
+        let item = self.repository.update_item(id, data).await?;
+
+        self.audit(ctx, item.account_id, AuditAction::StockItemUpdated, (AuditTarget::StockItem, id), Some(&before), Some(&item)).await?;
+        Ok(item)
+    }
+
+    /// Delete a stock item no recipe uses any more
+    pub async fn delete_item(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting stock item with ID: {}", id);
+
+        let before = self.get_owned_item(actor, id).await?;
+        if self.repository.count_recipes_using(id).await? > 0 {
+            return Err(ApiError::Conflict("Stock item is still used in recipes".to_string()));
+        }
+
+        let deleted = self.repository.soft_delete_item(id).await?;
+
+        self.audit(ctx, deleted.account_id, AuditAction::StockItemDeleted, (AuditTarget::StockItem, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Stock items with their levels at a branch, defaulting to the session's active branch
+    pub async fn get_stock(&self, actor: &UserInfo, query: StockQuery) -> Result<Vec<StockView>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        let items = self.repository.get_items(branch.account_id).await?;
+        let mut levels: HashMap<Uuid, level::Model> = self.repository
+            .get_levels(branch.id)
+            .await?
+            .into_iter()
+            .map(|level| (level.stock_item_id, level))
+            .collect();
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let level = levels.remove(&item.id);
+                Self::stock_view(item, branch.id, level.as_ref())
+            })
+            .filter(|view| !query.low_only || view.low)
+            .collect())
+    }
+
+    /// Set the low stock threshold of an item at a branch
+    pub async fn set_threshold(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: SetThresholdRequest) -> Result<StockView, ApiError> {
+        info!("Setting low stock threshold of stock item {}", id);
+
+        let item = self.get_owned_item(actor, id).await?;
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        let level = self.repository.set_threshold(branch.account_id, branch.id, id, data.low_stock_threshold).await?;
+
+        self.audit(ctx, branch.account_id, AuditAction::StockThresholdSet, (AuditTarget::StockItem, id), None, Some(&level)).await?;
+        Ok(Self::stock_view(item, branch.id, Some(&level)))
+    }
+
+    /// Record a delivery, waste, correction or stock take
+    pub async fn record(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateStockMovementRequest) -> Result<movement::Model, ApiError> {
+        info!("Recording {} of stock item {}", data.kind, data.stock_item_id);
+
+        let kind: MovementKind = data.kind.parse().map_err(ApiError::InvalidInput)?;
+        let role = actor.parsed_role()?;
+        if matches!(kind, MovementKind::Adjustment | MovementKind::Count) && role.level() < UserRole::Manager.level() {
+            return Err(ApiError::Forbidden(format!("{} cannot record a {}", role, kind)));
+        }
+
+        let quantity = match kind {
+            MovementKind::Receipt | MovementKind::Waste if data.quantity <= Decimal::ZERO => {
+                return Err(ApiError::InvalidInput("Quantity must be positive".to_string()));
+            }
+            MovementKind::Count if data.quantity.is_sign_negative() => {
+                return Err(ApiError::InvalidInput("Counted quantity must not be negative".to_string()));
+            }
+            MovementKind::Adjustment if data.quantity.is_zero() => {
+                return Err(ApiError::InvalidInput("Adjustment must not be zero".to_string()));
+            }
+            MovementKind::Waste => -data.quantity,
+            _ => data.quantity,
+        };
+
+        self.get_owned_item(actor, data.stock_item_id).await?;
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+
+        let movements = vec![NewMovement {
+            stock_item_id: data.stock_item_id,
+            kind,
+            quantity,
+            order_id: None,
+            reason: data.reason,
+        }];
+        let mut recorded = self.apply(&branch, actor.parsed_id()?, movements).await?;
+        let (movement, _) = recorded.pop().ok_or(ApiError::InternalServerError)?;
+
+        self.audit(ctx, branch.account_id, AuditAction::StockMoved, (AuditTarget::StockItem, movement.stock_item_id), None, Some(&movement)).await?;
+        Ok(movement)
+    }
+
+    /// Use up the ingredients of a served order's lines
+    pub async fn deplete_order(&self, order: &Order, lines: &[line::Model], served_by: Uuid) -> Result<(), ApiError> {
+        let item_ids: Vec<Uuid> = lines.iter().map(|line| line.item_id).collect();
+        let mut recipes: HashMap<Uuid, Vec<(Uuid, Decimal)>> = HashMap::new();
+        for recipe_line in self.repository.get_recipes(item_ids).await? {
+            recipes.entry(recipe_line.menu_item_id).or_default().push((recipe_line.stock_item_id, recipe_line.quantity));
+        }
+
+        let mut used: BTreeMap<Uuid, Decimal> = BTreeMap::new();
+        for line in lines {
+            for (stock_item_id, quantity) in recipes.get(&line.item_id).into_iter().flatten() {
+                *used.entry(*stock_item_id).or_default() += *quantity * Decimal::from(line.quantity);
+            }
+        }
+        if used.is_empty() {
+            return Ok(());
+        }
+
+        // A redelivered `order.served` event must not take the stock off twice
+        if self.repository.has_sales_of_order(order.id).await? {
+            info!("Stock of order {} was already depleted", order.id);
+            return Ok(());
+        }
+
+        info!("Depleting {} stock items for order {}", used.len(), order.id);
+
+        let branch = self.branch_repository.get_by_id(order.branch_id).await?;
+        let movements = used
+            .into_iter()
+            .map(|(stock_item_id, quantity)| NewMovement {
+                stock_item_id,
+                kind: MovementKind::Sale,
+                quantity: -quantity,
+                order_id: Some(order.id),
+                reason: None,
+            })
+            .collect();
+
+        self.apply(&branch, served_by, movements).await?;
+        Ok(())
+    }
+
+    /// Get the recipe of a menu item
+    pub async fn get_recipe(&self, actor: &UserInfo, menu_item_id: Uuid) -> Result<RecipeView, ApiError> {
+        self.get_owned_menu_item(actor, menu_item_id).await?;
+        let lines = self.repository.get_recipes(vec![menu_item_id]).await?;
+        Ok(RecipeView { menu_item_id, lines })
+    }
+
+    /// Replace the recipe of a menu item
+    pub async fn set_recipe(&self, ctx: &RequestContext, actor: &UserInfo, menu_item_id: Uuid, data: SetRecipeRequest) -> Result<RecipeView, ApiError> {
+        info!("Setting recipe of menu item {}", menu_item_id);
+
+        let item = self.get_owned_menu_item(actor, menu_item_id).await?;
+
+        let stock_item_ids: HashSet<Uuid> = data.lines.iter().map(|line| line.stock_item_id).collect();
+        if stock_item_ids.len() != data.lines.len() {
+            return Err(ApiError::InvalidInput("Each stock item may appear only once in a recipe".to_string()));
+        }
+        let found = self.repository.get_items_by_ids(stock_item_ids.iter().copied().collect()).await?;
+        if found.len() != stock_item_ids.len() || found.iter().any(|stock_item| stock_item.account_id != item.account_id) {
+            return Err(ApiError::NotFound("Stock item not found".to_string()));
+        }
+
+        let before = RecipeView { menu_item_id, lines: self.repository.get_recipes(vec![menu_item_id]).await? };
+        let lines = self.repository.set_recipe(menu_item_id, data.lines).await?;
+        let recipe = RecipeView { menu_item_id, lines };
+
+        self.audit(ctx, item.account_id, AuditAction::RecipeSet, (AuditTarget::MenuItem, menu_item_id), Some(&before), Some(&recipe)).await?;
+        Ok(recipe)
+    }
+
+    /// Ledger of a branch
+    pub async fn get_movements(&self, actor: &UserInfo, query: MovementQuery) -> Result<Page<movement::Model>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref kind) = query.kind {
+            kind.parse::<MovementKind>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search_movements(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Recompute the levels of a branch from the ledger
+    pub async fn rebuild(&self, ctx: &RequestContext, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<RebuildReport, ApiError> {
+        let branch = self.resolve_branch(actor, branch_id).await?;
+        let (levels, corrections) = self.repository.rebuild(branch.id).await?;
+        let report = RebuildReport { branch_id: branch.id, levels, corrections };
+
+        if !report.corrections.is_empty() {
+            let stock_item_ids = report.corrections.iter().map(|correction| correction.stock_item_id).collect();
+            self.refresh_outages(&branch, stock_item_ids).await?;
+        }
+
+        self.audit(ctx, branch.account_id, AuditAction::StockRebuilt, (AuditTarget::Branch, branch.id), None, Some(&report)).await?;
+        Ok(report)
+    }
+
+    /// Apply movements, then warn about low stock and update which menu items are sold out
+    async fn apply(&self, branch: &Branch, created_by: Uuid, movements: Vec<NewMovement>) -> Result<Vec<(movement::Model, level::Model)>, ApiError> {
+        let recorded = self.repository.record(branch.account_id, branch.id, created_by, movements).await?;
+
+        let low: Vec<Uuid> = recorded
+            .iter()
+            .filter(|(movement, level)| level.is_low() && movement.quantity.is_sign_negative())
+            .filter(|(movement, level)| level.low_stock_threshold.is_some_and(|t| movement.balance_after - movement.quantity > t))
+            .map(|(movement, _)| movement.stock_item_id)
+            .collect();
+        if !low.is_empty() {
+            let items = self.repository.get_items_by_ids(low).await?;
+            for item in items {
+                if let Some((_, level)) = recorded.iter().find(|(movement, _)| movement.stock_item_id == item.id) {
+                    let view = Self::stock_view(item, branch.id, Some(level));
+                    self.events.publish(Event::new("inventory.low_stock", vec![topic::branch(branch.id)], &view)).await;
+
+                    let data = serde_json::json!({
+                        "stock_item_id": view.item.id,
+                        "item": view.item.name,
+                        "unit": view.item.unit,
+                        "branch_id": branch.id,
+                        "branch": branch.name,
+                        "quantity": view.quantity.normalize(),
+                    });
+                    self.notification_service
+                        .notify_branch_managers(branch.account_id, branch.id, NotificationKind::LowStock, data)
+                        .await;
+                }
+            }
+        }
+
+        let stock_item_ids = recorded.iter().map(|(movement, _)| movement.stock_item_id).collect();
+        self.refresh_outages(branch, stock_item_ids).await?;
+
+        Ok(recorded)
+    }
+
+    /// 86 the menu items that can no longer be made at a branch and bring back those that can again
+    async fn refresh_outages(&self, branch: &Branch, stock_item_ids: Vec<Uuid>) -> Result<(), ApiError> {
+        let menu_item_ids: HashSet<Uuid> = self.repository
+            .get_recipes_using(stock_item_ids)
+            .await?
+            .into_iter()
+            .map(|line| line.menu_item_id)
+            .collect();
+        if menu_item_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut recipes: HashMap<Uuid, Vec<(Uuid, Decimal)>> = HashMap::new();
+        for line in self.repository.get_recipes(menu_item_ids.iter().copied().collect()).await? {
+            recipes.entry(line.menu_item_id).or_default().push((line.stock_item_id, line.quantity));
+        }
+        let levels: HashMap<Uuid, Decimal> = self.repository
+            .get_levels(branch.id)
+            .await?
+            .into_iter()
+            .map(|level| (level.stock_item_id, level.quantity))
+            .collect();
+        let sold_out: HashSet<Uuid> = self.repository
+            .get_outages(branch.id)
+            .await?
+            .into_iter()
+            .map(|outage| outage.item_id)
+            .collect();
+
+        for (menu_item_id, lines) in recipes {
+            // The first ingredient short of one portion
+            let missing = lines
+                .iter()
+                .find(|(stock_item_id, quantity)| levels.get(stock_item_id).copied().unwrap_or_default() < *quantity)
+                .map(|(stock_item_id, _)| *stock_item_id);
+
+            match missing {
+                Some(stock_item_id) if !sold_out.contains(&menu_item_id) => {
+                    let outage = self.repository.add_outage(menu_item_id, branch.id, stock_item_id).await?;
+                    self.events.publish(Event::new("menu.item_sold_out", vec![topic::branch(branch.id)], &outage)).await;
+                }
+                None if sold_out.contains(&menu_item_id) => {
+                    self.repository.remove_outage(menu_item_id, branch.id).await?;
+                    let payload = serde_json::json!({ "item_id": menu_item_id, "branch_id": branch.id });
+                    self.events.publish(Event::new("menu.item_back", vec![topic::branch(branch.id)], &payload)).await;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stock_view(item: StockItem, branch_id: Uuid, level: Option<&level::Model>) -> StockView {
+        StockView {
+            branch_id,
+            quantity: level.map_or(Decimal::ZERO, |level| level.quantity),
+            low_stock_threshold: level.and_then(|level| level.low_stock_threshold),
+            low: level.is_some_and(|level| level.is_low()),
+            item,
+        }
+    }
+
+    /// Fetch a stock item, hiding those of other accounts
+    async fn get_owned_item(&self, actor: &UserInfo, id: Uuid) -> Result<StockItem, ApiError> {
+        let item = self.repository.get_item(id).await?;
+
+        if item.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Stock item not found".to_string()));
+        }
+
+        Ok(item)
+    }
+
+    /// Fetch a menu item, hiding those of other accounts
+    async fn get_owned_menu_item(&self, actor: &UserInfo, id: Uuid) -> Result<menu_item::Model, ApiError> {
+        let item = self.menu_repository.get_item(id).await?;
+
+        if item.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Menu item not found".to_string()));
+        }
+
+        Ok(item)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record an inventory mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Uuid),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (target.0, Some(target.1)), before, after)
+            .await
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    common::{
+        outbox::{self, DomainEvent, EventSubscriber},
+        ApiError,
+    },
+    modules::{
+        inventory::service::InventoryService,
+        order::{entity::OrderStatus, repository::OrderRepository},
+    },
+};
+
+/// Takes the recipes of served orders off the branch's stock, retried by the dispatcher until it succeeds
+#[derive(Debug, Clone)]
+pub struct InventorySubscriber {
+    inventory_service: InventoryService,
+    order_repository: OrderRepository,
+}
+
+impl InventorySubscriber {
+    /// Create a new inventory subscriber
+    pub fn new(inventory_service: InventoryService, order_repository: OrderRepository) -> Self {
+        Self { inventory_service, order_repository }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSubscriber for InventorySubscriber {
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+
+    fn handles(&self, kind: &str) -> bool {
+        kind == outbox::kind::order(&OrderStatus::Served)
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+        // Lines no longer change once an order is served
+        let order = self.order_repository.get_by_id(event.aggregate_id).await?;
+        let lines = self.order_repository.get_lines(order.id).await?;
+        let served_by = match event.metadata.actor {
+            Some(ref actor) => actor.parsed_id()?,
+            None => order.created_by,
+        };
+
+        self.inventory_service.deplete_order(&order, &lines, served_by).await
+    }
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::invitation::entity::{AcceptInvitationRequest, CreateInvitationRequest, Model as Invitation},
+    modules::user::entity::Model as User,
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List invitations of the caller's account
+pub async fn get_all(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Invitation>>, ApiError> {
+    info!("Fetching invitations for account: {}", user.account_id);
+    let result = state.invitation_service.get_all(&user).await?;
+    Ok(Json(result))
+}
+
+/// Invite a new user
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateInvitationRequest>,
+) -> Result<(StatusCode, Json<Invitation>), ApiError> {
+    info!("Creating invitation for: {}", payload.email);
+    
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+    
+    let result = state.invitation_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Resend a pending invitation
+pub async fn resend(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Invitation>, ApiError> {
+    info!("Resending invitation with ID: {}", id);
+    let result = state.invitation_service.resend(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
+
+/// Revoke a pending invitation
+pub async fn revoke(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<(), ApiError> {
+    info!("Revoking invitation with ID: {}", id);
+    state.invitation_service.revoke(&ctx, &user, id).await
+}
+
+/// Accept an invitation and set a password
+pub async fn accept(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    Json(payload): Json<AcceptInvitationRequest>,
+) -> Result<(StatusCode, Json<User>), ApiError> {
+    info!("Accepting invitation");
+    
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+    
+    let result = state.invitation_service.accept(&ctx, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
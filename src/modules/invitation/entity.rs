@@ -0,0 +1,91 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "invitations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Option<Uuid>,
+    pub email: String,
+    pub role: String,
+    pub status: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTimeWithTimeZone,
+    pub accepted_at: Option<DateTimeWithTimeZone>,
+    pub accepted_user_id: Option<Uuid>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Invitation", 12)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("email", &self.email)?;
+        state.serialize_field("role", &self.role)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("invited_by", &self.invited_by)?;
+        state.serialize_field("expires_at", &self.expires_at)?;
+        state.serialize_field("accepted_at", &self.accepted_at)?;
+        state.serialize_field("accepted_user_id", &self.accepted_user_id)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Enums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Revoked,
+}
+
+impl std::fmt::Display for InvitationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvitationStatus::Pending => write!(f, "PENDING"),
+            InvitationStatus::Accepted => write!(f, "ACCEPTED"),
+            InvitationStatus::Revoked => write!(f, "REVOKED"),
+        }
+    }
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateInvitationRequest {
+    pub branch_id: Option<Uuid>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct AcceptInvitationRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 8, max = 100, message = "Password must be between 8 and 100 characters"))]
+    pub password: String,
+}
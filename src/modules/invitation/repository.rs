@@ -0,0 +1,253 @@
+use anyhow::Result;
+use std::{future::Future, pin::Pin};
+use sea_orm::{prelude::DateTimeWithTimeZone, DatabaseConnection, DatabaseTransaction, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder, PaginatorTrait, TransactionTrait};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::invitation::entity::{Entity as InvitationEntity, Model as Invitation, CreateInvitationRequest, InvitationStatus, Column, ActiveModel},
+    modules::user::entity::Model as User,
+    common::ApiError,
+};
+
+/// Invitation repository for database operations
+#[derive(Debug, Clone)]
+pub struct InvitationRepository {
+    db: DatabaseConnection,
+}
+
+impl InvitationRepository {
+    /// Create a new invitation repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get all invitations of an account
+    pub async fn get_by_account_id(&self, account_id: Uuid) -> Result<Vec<Invitation>, ApiError> {
+        info!("Fetching invitations by account ID: {}", account_id);
+
+        let invitations = InvitationEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch invitations by account ID {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(invitations)
+    }
+
+    /// Get an invitation by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Invitation, ApiError> {
+        info!("Fetching invitation with ID: {}", id);
+
+        let invitation = InvitationEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch invitation with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match invitation {
+            Some(invitation) => Ok(invitation),
+            None => Err(ApiError::NotFound("Invitation not found".to_string())),
+        }
+    }
+
+    /// Create a new pending invitation
+    pub async fn create(
+        &self,
+        account_id: Uuid,
+        invited_by: Uuid,
+        request: CreateInvitationRequest,
+        expires_at: DateTimeWithTimeZone,
+    ) -> Result<Invitation, ApiError> {
+        info!("Creating invitation for: {}", request.email);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let invitation = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(request.branch_id),
+            email: Set(request.email),
+            role: Set(request.role),
+            status: Set(InvitationStatus::Pending.to_string()),
+            invited_by: Set(invited_by),
+            expires_at: Set(expires_at),
+            accepted_at: Set(None),
+            accepted_user_id: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let invitation = invitation.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create invitation: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        info!("Created invitation with ID: {}", invitation.id);
+        Ok(invitation)
+    }
+
+    /// Push back the expiry of an invitation, invalidating previously issued tokens
+    pub async fn extend(&self, id: Uuid, expires_at: DateTimeWithTimeZone) -> Result<Invitation, ApiError> {
+        info!("Extending invitation with ID: {}", id);
+
+        let mut invitation: ActiveModel = self.get_by_id(id).await?.into();
+        invitation.expires_at = Set(expires_at);
+        invitation.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        invitation.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to extend invitation {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Change the status of an invitation
+    pub async fn set_status(&self, id: Uuid, status: InvitationStatus) -> Result<Invitation, ApiError> {
+        info!("Setting invitation {} status to {}", id, status);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut invitation: ActiveModel = self.get_by_id(id).await?.into();
+        if status == InvitationStatus::Accepted {
+            invitation.accepted_at = Set(Some(now));
+        }
+        invitation.status = Set(status.to_string());
+        invitation.updated_at = Set(now);
+
+        invitation.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update invitation {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Create the invited user and mark the invitation accepted in one transaction
+    pub async fn accept<F>(&self, id: Uuid, create_user: F) -> Result<(Invitation, User), ApiError>
+    where
+        F: for<'c> FnOnce(&'c DatabaseTransaction) -> Pin<Box<dyn Future<Output = Result<User, ApiError>> + Send + 'c>>,
+    {
+        info!("Accepting invitation with ID: {}", id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start acceptance transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let user = create_user(&txn).await?;
+
+        // Only a still pending invitation may be accepted, so concurrent accepts cannot both succeed
+        let now = chrono::Utc::now().fixed_offset();
+        let result = InvitationEntity::update_many()
+            .col_expr(Column::Status, sea_orm::sea_query::Expr::value(InvitationStatus::Accepted.to_string()))
+            .col_expr(Column::AcceptedAt, sea_orm::sea_query::Expr::value(now))
+            .col_expr(Column::AcceptedUserId, sea_orm::sea_query::Expr::value(user.id))
+            .col_expr(Column::UpdatedAt, sea_orm::sea_query::Expr::value(now))
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(InvitationStatus::Pending.to_string()))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to accept invitation {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        if result.rows_affected == 0 {
+            return Err(ApiError::Conflict("Invitation is no longer pending".to_string()));
+        }
+
+        let invitation = InvitationEntity::find_by_id(id)
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch invitation with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Invitation not found".to_string()))?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit acceptance of invitation {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        info!("Invitation {} accepted by user {}", id, user.id);
+        Ok((invitation, user))
+    }
+
+    /// Check if an account has a live pending invitation for an email
+    pub async fn exists_pending(&self, account_id: Uuid, email: &str) -> Result<bool, ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        let count = InvitationEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::Email.eq(email))
+            .filter(Column::Status.eq(InvitationStatus::Pending.to_string()))
+            .filter(Column::ExpiresAt.gt(now))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to check pending invitations for {}: {}", email, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// Get the invitations a user accepted
+    pub async fn get_by_accepted_user(&self, user_id: Uuid) -> Result<Vec<Invitation>, ApiError> {
+        info!("Fetching invitations accepted by user: {}", user_id);
+
+        let invitations = InvitationEntity::find()
+            .filter(Column::AcceptedUserId.eq(user_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch invitations accepted by user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(invitations)
+    }
+
+    /// Get invitations sent by a user
+    pub async fn get_by_inviter(&self, user_id: Uuid) -> Result<Vec<Invitation>, ApiError> {
+        info!("Fetching invitations sent by user: {}", user_id);
+
+        let invitations = InvitationEntity::find()
+            .filter(Column::InvitedBy.eq(user_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch invitations sent by user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(invitations)
+    }
+
+    /// Replace the email on every invitation a user accepted
+    pub async fn replace_email(&self, user_id: Uuid, replacement: &str) -> Result<u64, ApiError> {
+        info!("Scrubbing email from invitations");
+
+        let result = InvitationEntity::update_many()
+            .col_expr(Column::Email, sea_orm::sea_query::Expr::value(replacement))
+            .col_expr(Column::UpdatedAt, sea_orm::sea_query::Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::AcceptedUserId.eq(user_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to scrub email from invitations: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+}
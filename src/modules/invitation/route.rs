@@ -0,0 +1,24 @@
+use axum::{
+    routing::{delete, get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create invitation management routes
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/invitations", get(get_all).post(create))
+        .route("/invitations/:id", delete(revoke))
+        .route("/invitations/:id/resend", post(resend))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
+
+/// Create public invitation routes
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/invitations/accept", post(accept))
+}
@@ -0,0 +1,268 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::{
+    common::{config::InvitationConfig, signing, ApiError, RequestContext},
+    modules::{
+        account::repository::AccountRepository,
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        invitation::{
+            entity::{AcceptInvitationRequest, CreateInvitationRequest, InvitationStatus, Model as Invitation},
+            repository::InvitationRepository,
+        },
+        notification::{
+            entity::{NewNotification, NotificationKind, Recipient},
+            service::NotificationService,
+        },
+        user::{
+            entity::{CreateUserRequest, Model as User, UserRole},
+            service::UserService,
+        },
+    },
+};
+
+/// Prefix of the signed invitation token payload
+const TOKEN_PREFIX: &str = "invitation";
+
+/// Invitation service layer for business logic
+#[derive(Debug, Clone)]
+pub struct InvitationService {
+    repository: InvitationRepository,
+    user_service: UserService,
+    account_repository: AccountRepository,
+    notification_service: NotificationService,
+    audit_service: AuditService,
+    secret: String,
+    ttl_hours: i64,
+    accept_url: String,
+}
+
+impl InvitationService {
+    /// Create a new invitation service
+    pub fn new(
+        repository: InvitationRepository,
+        user_service: UserService,
+        account_repository: AccountRepository,
+        notification_service: NotificationService,
+        audit_service: AuditService,
+        config: &InvitationConfig,
+        secret: String,
+    ) -> Self {
+        Self {
+            repository,
+            user_service,
+            account_repository,
+            notification_service,
+            audit_service,
+            secret,
+            ttl_hours: config.ttl_hours,
+            accept_url: config.accept_url.clone(),
+        }
+    }
+
+    /// List the invitations of the caller's account
+    pub async fn get_all(&self, actor: &UserInfo) -> Result<Vec<Invitation>, ApiError> {
+        self.repository.get_by_account_id(actor.parsed_account_id()?).await
+    }
+
+    /// Invite an email to join the caller's account with a role
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateInvitationRequest) -> Result<Invitation, ApiError> {
+        info!("Inviting {} as {}", data.email, data.role);
+
+        let account_id = actor.parsed_account_id()?;
+        self.ensure_can_grant(actor, &data.role)?;
+
+        if let Some(branch_id) = data.branch_id {
+            self.user_service.ensure_branch_in_account(branch_id, account_id).await?;
+        }
+
+        if self.user_service.exists_by_email(&data.email).await? {
+            return Err(ApiError::UserAlreadyExists);
+        }
+
+        if self.repository.exists_pending(account_id, &data.email).await? {
+            return Err(ApiError::Conflict("A pending invitation already exists for this email".to_string()));
+        }
+
+        let invitation = self.repository
+            .create(account_id, actor.parsed_id()?, data, self.next_expiry())
+            .await?;
+
+        self.audit(ctx, AuditAction::InvitationCreated, None, &invitation).await?;
+        self.notify(&invitation).await;
+        Ok(invitation)
+    }
+
+    /// Re-issue a pending invitation with a fresh expiry
+    pub async fn resend(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<Invitation, ApiError> {
+        info!("Resending invitation with ID: {}", id);
+
+        let invitation = self.get_owned(actor, id).await?;
+        self.ensure_can_grant(actor, &invitation.role)?;
+
+        if invitation.status != InvitationStatus::Pending.to_string() {
+            return Err(ApiError::Conflict(format!("Invitation is {}", invitation.status)));
+        }
+
+        let extended = self.repository.extend(id, self.next_expiry()).await?;
+
+        self.audit(ctx, AuditAction::InvitationResent, Some(&invitation), &extended).await?;
+        self.notify(&extended).await;
+        Ok(extended)
+    }
+
+    /// Revoke a pending invitation
+    pub async fn revoke(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Revoking invitation with ID: {}", id);
+
+        let invitation = self.get_owned(actor, id).await?;
+        self.ensure_can_grant(actor, &invitation.role)?;
+
+        if invitation.status != InvitationStatus::Pending.to_string() {
+            return Err(ApiError::Conflict(format!("Invitation is {}", invitation.status)));
+        }
+
+        let revoked = self.repository.set_status(id, InvitationStatus::Revoked).await?;
+
+        self.audit(ctx, AuditAction::InvitationRevoked, Some(&invitation), &revoked).await?;
+        Ok(())
+    }
+
+    /// Accept an invitation by choosing a password, creating the user
+    pub async fn accept(&self, ctx: &RequestContext, data: AcceptInvitationRequest) -> Result<User, ApiError> {
+        let invitation = self.verify_token(&data.token).await?;
+        info!("Accepting invitation with ID: {}", invitation.id);
+
+        let request = CreateUserRequest {
+            account_id: invitation.account_id,
+            branch_id: invitation.branch_id,
+            name: data.name,
+            email: invitation.email.clone(),
+            password: data.password,
+            role: invitation.role.clone(),
+        };
+
+        // The user and the accepted status are written together, so a failure leaves neither
+        let user_service = self.user_service.clone();
+        let user_ctx = ctx.clone();
+        let (accepted, user) = self.repository
+            .accept(invitation.id, move |txn| {
+                Box::pin(async move { user_service.create_in(txn, &user_ctx, request).await })
+            })
+            .await?;
+
+        let ctx = ctx.clone().with_user(UserInfo::from(user.clone()));
+        self.audit(&ctx, AuditAction::InvitationAccepted, Some(&invitation), &accepted).await?;
+
+        info!("Invitation {} accepted by user {}", invitation.id, user.id);
+        Ok(user)
+    }
+
+    /// Record an invitation mutation in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, before: Option<&Invitation>, after: &Invitation) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, after.account_id, action, (AuditTarget::Invitation, Some(after.id)), before, Some(after))
+            .await
+    }
+
+    /// Resolve a signed token to its pending, unexpired invitation
+    async fn verify_token(&self, token: &str) -> Result<Invitation, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid or expired invitation".to_string());
+
+        let payload = signing::verify(&self.secret, token).ok_or_else(|| {
+            warn!("Rejected invitation token with bad signature");
+            invalid()
+        })?;
+
+        let mut parts = payload.splitn(3, ':');
+        let (Some(TOKEN_PREFIX), Some(id), Some(expires_at)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        let expires_at: i64 = expires_at.parse().map_err(|_| invalid())?;
+
+        let invitation = self.repository.get_by_id(id).await.map_err(|e| match e {
+            ApiError::NotFound(_) => invalid(),
+            e => e,
+        })?;
+
+        // A resend moves the expiry, so older tokens no longer match
+        if invitation.expires_at.timestamp() != expires_at
+            || invitation.expires_at < chrono::Utc::now().fixed_offset()
+            || invitation.status != InvitationStatus::Pending.to_string()
+        {
+            return Err(invalid());
+        }
+
+        Ok(invitation)
+    }
+
+    /// Fetch an invitation, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Invitation, ApiError> {
+        let invitation = self.repository.get_by_id(id).await?;
+
+        if invitation.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Invitation not found".to_string()));
+        }
+
+        Ok(invitation)
+    }
+
+    /// Only roles strictly below the caller's may be handed out
+    fn ensure_can_grant(&self, actor: &UserInfo, role: &str) -> Result<(), ApiError> {
+        let role: UserRole = role.parse().map_err(ApiError::InvalidInput)?;
+
+        if !actor.parsed_role()?.outranks(&role) {
+            return Err(ApiError::Forbidden(format!("Cannot invite users with role {}", role)));
+        }
+
+        Ok(())
+    }
+
+    fn next_expiry(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        // Truncate to whole seconds so the expiry round-trips through the token
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(self.ttl_hours);
+        chrono::DateTime::from_timestamp(expires_at.timestamp(), 0)
+            .unwrap_or(expires_at)
+            .fixed_offset()
+    }
+
+    /// Email the invitee the link to accept; only the invitee ever sees the token
+    async fn notify(&self, invitation: &Invitation) {
+        let account = match self.account_repository.get_by_id(invitation.account_id).await {
+            Ok(account) => account.name,
+            Err(e) => {
+                warn!("Failed to fetch account {} for invitation email: {}", invitation.account_id, e);
+                return;
+            }
+        };
+
+        let data = serde_json::json!({
+            "invitation_id": invitation.id,
+            "account": account,
+            "role": invitation.role,
+            "expires_at": invitation.expires_at.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M UTC").to_string(),
+            "accept_url": self.accept_url(invitation),
+        });
+        self.notification_service
+            .notify(NewNotification {
+                account_id: invitation.account_id,
+                recipient: Recipient::Email(invitation.email.clone()),
+                kind: NotificationKind::Invitation,
+                data,
+            })
+            .await;
+    }
+
+    /// Link to accept an invitation, carrying its signed token
+    fn accept_url(&self, invitation: &Invitation) -> String {
+        let payload = format!("{}:{}:{}", TOKEN_PREFIX, invitation.id, invitation.expires_at.timestamp());
+        let token = signing::sign(&self.secret, &payload);
+        format!("{}?token={}", self.accept_url, token)
+    }
+}
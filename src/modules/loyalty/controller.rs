@@ -0,0 +1,251 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::loyalty::entity::{
+        reward, rule, voucher, CreateAdjustmentRequest, CreateRewardRequest, CreateRuleRequest, CreateVoucherRequest, LedgerQuery,
+        LoyaltyView, Model as Entry, RedemptionView, UpdateRewardRequest, UpdateRuleRequest, UpdateVoucherRequest, VoucherQuery,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Points and personal vouchers of the current user
+pub async fn get_mine(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<LoyaltyView>, ApiError> {
+    info!("Fetching own loyalty points");
+    let result = state.loyalty_service.get_mine(&user).await?;
+    Ok(Json(result))
+}
+
+/// Points history of the current user
+pub async fn get_my_ledger(
+    Query(query): Query<LedgerQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Entry>>, ApiError> {
+    info!("Fetching own loyalty ledger");
+    let result = state.loyalty_service.get_my_ledger(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// List the rewards of the account
+pub async fn get_rewards(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<reward::Model>>, ApiError> {
+    info!("Fetching loyalty rewards");
+    let result = state.loyalty_service.get_rewards(&user).await?;
+    Ok(Json(result))
+}
+
+/// Spend points on a reward
+pub async fn redeem_reward(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<(StatusCode, Json<RedemptionView>), ApiError> {
+    info!("Redeeming loyalty reward {}", id);
+    let result = state.loyalty_service.redeem_reward(&ctx, &user, id).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// List the earning rules of the account
+pub async fn get_rules(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<rule::Model>>, ApiError> {
+    info!("Fetching loyalty rules");
+    let result = state.loyalty_service.get_rules(&user).await?;
+    Ok(Json(result))
+}
+
+/// Create an earning rule
+pub async fn create_rule(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateRuleRequest>,
+) -> Result<(StatusCode, Json<rule::Model>), ApiError> {
+    info!("Creating loyalty rule: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.create_rule(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an earning rule
+pub async fn update_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateRuleRequest>,
+) -> Result<Json<rule::Model>, ApiError> {
+    info!("Updating loyalty rule with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.update_rule(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete an earning rule
+pub async fn delete_rule(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting loyalty rule with ID: {}", id);
+    state.loyalty_service.delete_rule(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a reward
+pub async fn create_reward(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateRewardRequest>,
+) -> Result<(StatusCode, Json<reward::Model>), ApiError> {
+    info!("Creating loyalty reward: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.create_reward(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a reward
+pub async fn update_reward(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateRewardRequest>,
+) -> Result<Json<reward::Model>, ApiError> {
+    info!("Updating loyalty reward with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.update_reward(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a reward
+pub async fn delete_reward(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting loyalty reward with ID: {}", id);
+    state.loyalty_service.delete_reward(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the vouchers of the account
+pub async fn get_vouchers(
+    Query(query): Query<VoucherQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<voucher::Model>>, ApiError> {
+    info!("Fetching vouchers");
+    let result = state.loyalty_service.get_vouchers(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Create a promo code
+pub async fn create_voucher(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateVoucherRequest>,
+) -> Result<(StatusCode, Json<voucher::Model>), ApiError> {
+    info!("Creating voucher: {}", payload.code);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.create_voucher(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a voucher
+pub async fn update_voucher(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateVoucherRequest>,
+) -> Result<Json<voucher::Model>, ApiError> {
+    info!("Updating voucher with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.update_voucher(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Points of a customer
+pub async fn get_customer(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<LoyaltyView>, ApiError> {
+    info!("Fetching loyalty points of customer {}", id);
+    let result = state.loyalty_service.get_customer(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Points history of a customer
+pub async fn get_customer_ledger(
+    Path(id): Path<Uuid>,
+    Query(query): Query<LedgerQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Entry>>, ApiError> {
+    info!("Fetching loyalty ledger of customer {}", id);
+    let result = state.loyalty_service.get_customer_ledger(&user, id, query).await?;
+    Ok(Json(result))
+}
+
+/// Correct the points of a customer
+pub async fn create_adjustment(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateAdjustmentRequest>,
+) -> Result<(StatusCode, Json<Entry>), ApiError> {
+    info!("Adjusting loyalty points of customer {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.loyalty_service.adjust(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
@@ -0,0 +1,637 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::modules::order::entity::DiscountKind;
+
+/// Ledger entry changing a customer's points; balances are the sum of their entries
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "loyalty_ledger")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub customer_id: Uuid,
+    pub kind: String,
+    /// Signed change of the balance
+    pub points: i32,
+    /// Balance right after this entry
+    pub balance_after: i32,
+    pub order_id: Option<Uuid>,
+    pub voucher_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("LoyaltyEntry", 11)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("customer_id", &self.customer_id)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("points", &self.points)?;
+        state.serialize_field("balance_after", &self.balance_after)?;
+        state.serialize_field("order_id", &self.order_id)?;
+        state.serialize_field("voucher_id", &self.voucher_id)?;
+        state.serialize_field("reason", &self.reason)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Points a customer holds
+pub mod balance {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "loyalty_balances")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub customer_id: Uuid,
+        pub account_id: Uuid,
+        pub points: i32,
+        /// Every point ever earned, for tiers and statistics
+        pub lifetime_points: i32,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("LoyaltyBalance", 5)?;
+            state.serialize_field("customer_id", &self.customer_id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("points", &self.points)?;
+            state.serialize_field("lifetime_points", &self.lifetime_points)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// How paid orders earn points
+pub mod rule {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    use super::RuleKind;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "loyalty_rules")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        /// Only orders of this branch earn by the rule; all branches when empty
+        pub branch_id: Option<Uuid>,
+        pub name: String,
+        pub kind: String,
+        /// Points per currency unit for `PER_AMOUNT`, per order for `PER_ORDER`
+        #[sea_orm(column_type = "Decimal(Some((10, 2)))")]
+        pub points: Decimal,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+        pub min_order_amount: Option<Decimal>,
+        pub valid_from: Option<DateTimeWithTimeZone>,
+        pub valid_until: Option<DateTimeWithTimeZone>,
+        pub active: bool,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("LoyaltyRule", 12)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("kind", &self.kind)?;
+            state.serialize_field("points", &self.points)?;
+            state.serialize_field("min_order_amount", &self.min_order_amount)?;
+            state.serialize_field("valid_from", &self.valid_from)?;
+            state.serialize_field("valid_until", &self.valid_until)?;
+            state.serialize_field("active", &self.active)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    impl Model {
+        /// Points an order of `amount` at `branch_id` earns by this rule at `at`, before rounding
+        pub fn earns(&self, branch_id: Uuid, amount: Decimal, at: DateTimeWithTimeZone) -> Decimal {
+            let applies = self.active
+                && self.branch_id.is_none_or(|id| id == branch_id)
+                && self.valid_from.is_none_or(|from| from <= at)
+                && self.valid_until.is_none_or(|until| at < until)
+                && self.min_order_amount.is_none_or(|min| amount >= min);
+            if !applies {
+                return Decimal::ZERO;
+            }
+
+            match self.kind.parse() {
+                Ok(RuleKind::PerAmount) => amount * self.points,
+                Ok(RuleKind::PerOrder) => self.points,
+                Err(_) => Decimal::ZERO,
+            }
+        }
+    }
+}
+
+/// Something customers can buy with points
+pub mod reward {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "loyalty_rewards")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub name: String,
+        pub description: Option<String>,
+        pub points_cost: i32,
+        /// Discount of the voucher the reward is redeemed as
+        pub discount_kind: String,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub discount_value: Decimal,
+        pub active: bool,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+        pub deleted_at: Option<DateTimeWithTimeZone>,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("LoyaltyReward", 10)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("description", &self.description)?;
+            state.serialize_field("points_cost", &self.points_cost)?;
+            state.serialize_field("discount_kind", &self.discount_kind)?;
+            state.serialize_field("discount_value", &self.discount_value)?;
+            state.serialize_field("active", &self.active)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Promo code taking a discount off an order
+pub mod voucher {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    use crate::modules::order::entity::DiscountRule;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "vouchers")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub code: String,
+        pub description: Option<String>,
+        pub discount_kind: String,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub discount_value: Decimal,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+        pub min_order_amount: Option<Decimal>,
+        pub valid_from: Option<DateTimeWithTimeZone>,
+        pub valid_until: Option<DateTimeWithTimeZone>,
+        pub max_uses: Option<i32>,
+        pub max_uses_per_customer: Option<i32>,
+        /// Only this customer may use the voucher; set on vouchers bought with points
+        pub customer_id: Option<Uuid>,
+        pub reward_id: Option<Uuid>,
+        /// Orders currently carrying the voucher
+        pub uses: i32,
+        pub active: bool,
+        pub created_by: Uuid,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("Voucher", 18)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("code", &self.code)?;
+            state.serialize_field("description", &self.description)?;
+            state.serialize_field("discount_kind", &self.discount_kind)?;
+            state.serialize_field("discount_value", &self.discount_value)?;
+            state.serialize_field("min_order_amount", &self.min_order_amount)?;
+            state.serialize_field("valid_from", &self.valid_from)?;
+            state.serialize_field("valid_until", &self.valid_until)?;
+            state.serialize_field("max_uses", &self.max_uses)?;
+            state.serialize_field("max_uses_per_customer", &self.max_uses_per_customer)?;
+            state.serialize_field("customer_id", &self.customer_id)?;
+            state.serialize_field("reward_id", &self.reward_id)?;
+            state.serialize_field("uses", &self.uses)?;
+            state.serialize_field("active", &self.active)?;
+            state.serialize_field("created_by", &self.created_by)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    impl Model {
+        /// The discount copied onto an order carrying the voucher
+        pub fn rule(&self) -> DiscountRule {
+            DiscountRule {
+                kind: self.discount_kind.clone(),
+                value: self.discount_value,
+                min_order_amount: self.min_order_amount,
+            }
+        }
+
+        /// Whether the voucher may be used at `at`, ignoring its usage limits
+        pub fn is_valid_at(&self, at: DateTimeWithTimeZone) -> bool {
+            self.active
+                && self.valid_from.is_none_or(|from| from <= at)
+                && self.valid_until.is_none_or(|until| at < until)
+        }
+    }
+}
+
+/// One use of a voucher on an order
+pub mod redemption {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "voucher_redemptions")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub voucher_id: Uuid,
+        pub order_id: Uuid,
+        pub customer_id: Option<Uuid>,
+        pub created_at: DateTimeWithTimeZone,
+        /// Set when the order was cancelled or the voucher taken off it
+        pub released_at: Option<DateTimeWithTimeZone>,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("VoucherRedemption", 6)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("voucher_id", &self.voucher_id)?;
+            state.serialize_field("order_id", &self.order_id)?;
+            state.serialize_field("customer_id", &self.customer_id)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("released_at", &self.released_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// Earned by a paid order
+    Earn,
+    /// Spent on a reward
+    Redeem,
+    /// Correction by a manager, e.g. after a refund
+    Adjust,
+}
+
+impl std::fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryKind::Earn => write!(f, "EARN"),
+            EntryKind::Redeem => write!(f, "REDEEM"),
+            EntryKind::Adjust => write!(f, "ADJUST"),
+        }
+    }
+}
+
+impl std::str::FromStr for EntryKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "EARN" => Ok(EntryKind::Earn),
+            "REDEEM" => Ok(EntryKind::Redeem),
+            "ADJUST" => Ok(EntryKind::Adjust),
+            _ => Err(format!("Entry kind {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
+    /// Points for every currency unit paid
+    PerAmount,
+    /// Fixed points for every paid order
+    PerOrder,
+}
+
+impl std::fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleKind::PerAmount => write!(f, "PER_AMOUNT"),
+            RuleKind::PerOrder => write!(f, "PER_ORDER"),
+        }
+    }
+}
+
+impl std::str::FromStr for RuleKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PER_AMOUNT" => Ok(RuleKind::PerAmount),
+            "PER_ORDER" => Ok(RuleKind::PerOrder),
+            _ => Err(format!("Rule kind {} is not valid", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_rule_kind(kind: &str) -> Result<(), ValidationError> {
+    kind.parse::<RuleKind>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("kind").with_message(e.into()))
+}
+
+fn validate_discount_kind(kind: &str) -> Result<(), ValidationError> {
+    kind.parse::<DiscountKind>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("discount_kind").with_message(e.into()))
+}
+
+fn validate_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_sign_negative() || amount.scale() > 2 {
+        return Err(ValidationError::new("amount").with_message("Amount must be positive with at most two decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_positive_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_zero() {
+        return Err(ValidationError::new("amount").with_message("Amount must be positive".into()));
+    }
+    validate_amount(amount)
+}
+
+fn validate_code(code: &str) -> Result<(), ValidationError> {
+    if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(ValidationError::new("code").with_message("Code may only contain letters, digits, '-' and '_'".into()));
+    }
+    Ok(())
+}
+
+fn validate_adjustment(points: i32) -> Result<(), ValidationError> {
+    if points == 0 {
+        return Err(ValidationError::new("points").with_message("Adjustment must not be zero".into()));
+    }
+    Ok(())
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateRuleRequest {
+    /// Limit the rule to one branch
+    pub branch_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    /// `PER_AMOUNT` or `PER_ORDER`
+    #[validate(custom(function = "validate_rule_kind"))]
+    pub kind: String,
+
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub points: Decimal,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub min_order_amount: Option<Decimal>,
+
+    pub valid_from: Option<DateTimeWithTimeZone>,
+    pub valid_until: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateRuleRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub points: Option<Decimal>,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub min_order_amount: Option<Decimal>,
+
+    pub valid_from: Option<DateTimeWithTimeZone>,
+    pub valid_until: Option<DateTimeWithTimeZone>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateRewardRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    #[validate(range(min = 1, message = "Points cost must be positive"))]
+    pub points_cost: i32,
+
+    /// `PERCENT` or `FIXED`
+    #[validate(custom(function = "validate_discount_kind"))]
+    pub discount_kind: String,
+
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub discount_value: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateRewardRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    #[validate(range(min = 1, message = "Points cost must be positive"))]
+    pub points_cost: Option<i32>,
+
+    #[validate(custom(function = "validate_discount_kind"))]
+    pub discount_kind: Option<String>,
+
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub discount_value: Option<Decimal>,
+
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateVoucherRequest {
+    #[validate(length(min = 3, max = 50, message = "Code must be between 3 and 50 characters"), custom(function = "validate_code"))]
+    pub code: String,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    /// `PERCENT` or `FIXED`
+    #[validate(custom(function = "validate_discount_kind"))]
+    pub discount_kind: String,
+
+    #[validate(custom(function = "validate_positive_amount"))]
+    pub discount_value: Decimal,
+
+    #[validate(custom(function = "validate_amount"))]
+    pub min_order_amount: Option<Decimal>,
+
+    pub valid_from: Option<DateTimeWithTimeZone>,
+    pub valid_until: Option<DateTimeWithTimeZone>,
+
+    #[validate(range(min = 1, message = "Maximum uses must be positive"))]
+    pub max_uses: Option<i32>,
+
+    #[validate(range(min = 1, message = "Maximum uses per customer must be positive"))]
+    pub max_uses_per_customer: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateVoucherRequest {
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    pub valid_from: Option<DateTimeWithTimeZone>,
+    pub valid_until: Option<DateTimeWithTimeZone>,
+
+    #[validate(range(min = 1, message = "Maximum uses must be positive"))]
+    pub max_uses: Option<i32>,
+
+    #[validate(range(min = 1, message = "Maximum uses per customer must be positive"))]
+    pub max_uses_per_customer: Option<i32>,
+
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateAdjustmentRequest {
+    /// Signed change of the balance
+    #[validate(custom(function = "validate_adjustment"))]
+    pub points: i32,
+
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1 and 500 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct LedgerQuery {
+    pub kind: Option<String>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct VoucherQuery {
+    /// Include vouchers bought with points by customers
+    #[serde(default)]
+    pub include_personal: bool,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+/// A customer's points with the vouchers they can still use
+#[derive(Debug, Clone, Serialize)]
+pub struct LoyaltyView {
+    pub customer_id: Uuid,
+    pub points: i32,
+    pub lifetime_points: i32,
+    pub vouchers: Vec<voucher::Model>,
+}
+
+/// A reward redeemed for a personal voucher
+#[derive(Debug, Clone, Serialize)]
+pub struct RedemptionView {
+    pub entry: Model,
+    pub voucher: voucher::Model,
+}
+
+/// A change to apply to a customer's points
+#[derive(Debug, Clone)]
+pub struct NewEntry {
+    pub account_id: Uuid,
+    pub customer_id: Uuid,
+    pub kind: EntryKind,
+    pub points: i32,
+    pub order_id: Option<Uuid>,
+    pub voucher_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub created_by: Uuid,
+}
@@ -0,0 +1,718 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        loyalty::entity::{
+            balance, redemption, reward, rule, voucher, ActiveModel, Column, Entity as EntryEntity, EntryKind, LedgerQuery,
+            Model as Entry, NewEntry,
+        },
+        order::{
+            entity::{ActiveModel as OrderActiveModel, Entity as OrderEntity, Model as Order},
+            repository::OrderRepository,
+        },
+    },
+    common::ApiError,
+};
+
+/// Loyalty repository for database operations
+#[derive(Debug, Clone)]
+pub struct LoyaltyRepository {
+    db: DatabaseConnection,
+}
+
+impl LoyaltyRepository {
+    /// Create a new loyalty repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the earning rules of an account
+    pub async fn get_rules(&self, account_id: Uuid) -> Result<Vec<rule::Model>, ApiError> {
+        rule::Entity::find()
+            .filter(rule::Column::AccountId.eq(account_id))
+            .order_by_asc(rule::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty rules of account {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get an earning rule by ID
+    pub async fn get_rule(&self, id: Uuid) -> Result<rule::Model, ApiError> {
+        info!("Fetching loyalty rule with ID: {}", id);
+
+        let rule = rule::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty rule with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match rule {
+            Some(rule) => Ok(rule),
+            None => Err(ApiError::NotFound("Loyalty rule not found".to_string())),
+        }
+    }
+
+    /// Create an earning rule
+    pub async fn create_rule(&self, rule: rule::Model) -> Result<rule::Model, ApiError> {
+        info!("Creating loyalty rule: {}", rule.name);
+
+        rule::ActiveModel::from(rule)
+            .reset_all()
+            .insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create loyalty rule: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Save the changed fields of an earning rule
+    pub async fn update_rule(&self, rule: rule::Model) -> Result<rule::Model, ApiError> {
+        info!("Updating loyalty rule with ID: {}", rule.id);
+
+        let id = rule.id;
+        let mut model = rule::ActiveModel::from(rule).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update loyalty rule with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Delete an earning rule; points already earned by it stay in the ledger
+    pub async fn delete_rule(&self, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting loyalty rule with ID: {}", id);
+
+        rule::Entity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete loyalty rule with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the rewards of an account
+    pub async fn get_rewards(&self, account_id: Uuid, active_only: bool) -> Result<Vec<reward::Model>, ApiError> {
+        let mut select = reward::Entity::find()
+            .filter(reward::Column::AccountId.eq(account_id))
+            .filter(reward::Column::DeletedAt.is_null());
+        if active_only {
+            select = select.filter(reward::Column::Active.eq(true));
+        }
+
+        select
+            .order_by_asc(reward::Column::PointsCost)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty rewards of account {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a reward by ID
+    pub async fn get_reward(&self, id: Uuid) -> Result<reward::Model, ApiError> {
+        info!("Fetching loyalty reward with ID: {}", id);
+
+        let reward = reward::Entity::find_by_id(id)
+            .filter(reward::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty reward with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match reward {
+            Some(reward) => Ok(reward),
+            None => Err(ApiError::NotFound("Reward not found".to_string())),
+        }
+    }
+
+    /// Create a reward
+    pub async fn create_reward(&self, reward: reward::Model) -> Result<reward::Model, ApiError> {
+        info!("Creating loyalty reward: {}", reward.name);
+
+        reward::ActiveModel::from(reward)
+            .reset_all()
+            .insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create loyalty reward: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Save the changed fields of a reward
+    pub async fn update_reward(&self, reward: reward::Model) -> Result<reward::Model, ApiError> {
+        info!("Updating loyalty reward with ID: {}", reward.id);
+
+        let id = reward.id;
+        let mut model = reward::ActiveModel::from(reward).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update loyalty reward with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a reward, keeping the vouchers already redeemed for it
+    pub async fn soft_delete_reward(&self, id: Uuid) -> Result<reward::Model, ApiError> {
+        info!("Soft deleting loyalty reward with ID: {}", id);
+
+        let mut reward: reward::ActiveModel = self.get_reward(id).await?.into();
+        let now = chrono::Utc::now().fixed_offset();
+        reward.active = Set(false);
+        reward.deleted_at = Set(Some(now));
+        reward.updated_at = Set(now);
+
+        reward.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete loyalty reward {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Search the vouchers of an account, newest first, returning one page and the total count
+    pub async fn search_vouchers(&self, account_id: Uuid, include_personal: bool, page: u64, per_page: u64) -> Result<(Vec<voucher::Model>, u64), ApiError> {
+        let mut select = voucher::Entity::find().filter(voucher::Column::AccountId.eq(account_id));
+        if !include_personal {
+            select = select.filter(voucher::Column::CustomerId.is_null());
+        }
+
+        let paginator = select
+            .order_by_desc(voucher::Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count vouchers of account {}: {}", account_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let vouchers = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch vouchers of account {}: {}", account_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((vouchers, total))
+    }
+
+    /// Get a voucher by ID
+    pub async fn get_voucher(&self, id: Uuid) -> Result<voucher::Model, ApiError> {
+        info!("Fetching voucher with ID: {}", id);
+
+        let voucher = voucher::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch voucher with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match voucher {
+            Some(voucher) => Ok(voucher),
+            None => Err(ApiError::NotFound("Voucher not found".to_string())),
+        }
+    }
+
+    /// Get a voucher of an account by its code
+    pub async fn get_voucher_by_code(&self, account_id: Uuid, code: &str) -> Result<voucher::Model, ApiError> {
+        let voucher = voucher::Entity::find()
+            .filter(voucher::Column::AccountId.eq(account_id))
+            .filter(voucher::Column::Code.eq(code))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch voucher {}: {}", code, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match voucher {
+            Some(voucher) => Ok(voucher),
+            None => Err(ApiError::NotFound("Voucher not found".to_string())),
+        }
+    }
+
+    /// Get the active personal vouchers of a customer that still have a use left
+    pub async fn get_personal_vouchers(&self, customer_id: Uuid) -> Result<Vec<voucher::Model>, ApiError> {
+        voucher::Entity::find()
+            .filter(voucher::Column::CustomerId.eq(customer_id))
+            .filter(voucher::Column::Active.eq(true))
+            .filter(
+                voucher::Column::MaxUses
+                    .is_null()
+                    .or(Expr::col(voucher::Column::Uses).lt(Expr::col(voucher::Column::MaxUses))),
+            )
+            .order_by_desc(voucher::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch vouchers of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Create a voucher
+    pub async fn create_voucher(&self, voucher: voucher::Model) -> Result<voucher::Model, ApiError> {
+        info!("Creating voucher: {}", voucher.code);
+
+        Self::insert_voucher(&self.db, voucher).await
+    }
+
+    /// Save the changed fields of a voucher
+    pub async fn update_voucher(&self, voucher: voucher::Model) -> Result<voucher::Model, ApiError> {
+        info!("Updating voucher with ID: {}", voucher.id);
+
+        let id = voucher.id;
+        let mut model = voucher::ActiveModel::from(voucher).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update voucher with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the balance of a customer, if they ever had points
+    pub async fn get_balance(&self, customer_id: Uuid) -> Result<Option<balance::Model>, ApiError> {
+        balance::Entity::find_by_id(customer_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty balance of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Search the ledger of a customer, newest first, returning one page and the total count
+    pub async fn search_ledger(&self, customer_id: Uuid, query: &LedgerQuery, page: u64, per_page: u64) -> Result<(Vec<Entry>, u64), ApiError> {
+        let mut select = EntryEntity::find().filter(Column::CustomerId.eq(customer_id));
+        if let Some(ref kind) = query.kind {
+            select = select.filter(Column::Kind.eq(kind.as_str()));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count loyalty entries of customer {}: {}", customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let entries = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch loyalty entries of customer {}: {}", customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((entries, total))
+    }
+
+    /// Get the whole ledger of a customer, newest first
+    pub async fn get_ledger(&self, customer_id: Uuid) -> Result<Vec<Entry>, ApiError> {
+        EntryEntity::find()
+            .filter(Column::CustomerId.eq(customer_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch loyalty entries of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get every personal voucher of a customer, used up or not
+    pub async fn get_all_personal_vouchers(&self, customer_id: Uuid) -> Result<Vec<voucher::Model>, ApiError> {
+        voucher::Entity::find()
+            .filter(voucher::Column::CustomerId.eq(customer_id))
+            .order_by_desc(voucher::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch vouchers of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the voucher redemptions of a customer
+    pub async fn get_redemptions(&self, customer_id: Uuid) -> Result<Vec<redemption::Model>, ApiError> {
+        redemption::Entity::find()
+            .filter(redemption::Column::CustomerId.eq(customer_id))
+            .order_by_desc(redemption::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch voucher redemptions of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Clear the free text on a customer's ledger and personal vouchers and retire those vouchers,
+    /// keeping the points history
+    pub async fn anonymize_customer(&self, customer_id: Uuid) -> Result<(), ApiError> {
+        info!("Anonymizing loyalty data of customer {}", customer_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start loyalty anonymization transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        EntryEntity::update_many()
+            .col_expr(Column::Reason, Expr::value(Option::<String>::None))
+            .filter(Column::CustomerId.eq(customer_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to anonymize loyalty entries of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        voucher::Entity::update_many()
+            .col_expr(voucher::Column::Description, Expr::value(Option::<String>::None))
+            .col_expr(voucher::Column::Active, Expr::value(false))
+            .col_expr(voucher::Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(voucher::Column::CustomerId.eq(customer_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to anonymize vouchers of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit loyalty anonymization of customer {}: {}", customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Whether an order has already earned points
+    pub async fn has_earned(&self, order_id: Uuid) -> Result<bool, ApiError> {
+        let count = EntryEntity::find()
+            .filter(Column::OrderId.eq(order_id))
+            .filter(Column::Kind.eq(EntryKind::Earn.to_string()))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to check points of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// Append an entry to the ledger and apply it to the balance in one transaction
+    pub async fn append(&self, entry: NewEntry) -> Result<(Entry, balance::Model), ApiError> {
+        info!("Recording {} of {} points for customer {}", entry.kind, entry.points, entry.customer_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let recorded = Self::append_in(&txn, entry).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit loyalty entry: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(recorded)
+    }
+
+    /// Spend points on a reward and issue the personal voucher it is redeemed as, together
+    pub async fn redeem_reward(&self, voucher: voucher::Model, mut entry: NewEntry) -> Result<(Entry, voucher::Model), ApiError> {
+        info!("Redeeming {} points of customer {} for voucher {}", -entry.points, entry.customer_id, voucher.code);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let voucher = Self::insert_voucher(&txn, voucher).await?;
+        entry.voucher_id = Some(voucher.id);
+        let (entry, _) = Self::append_in(&txn, entry).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit redemption of voucher {}: {}", voucher.code, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((entry, voucher))
+    }
+
+    /// Put a voucher on an order and discount it, enforcing the usage limits.
+    /// The order and the voucher stay locked so concurrent checkouts cannot exceed the limits.
+    pub async fn apply_voucher(&self, order_id: Uuid, voucher_id: Uuid) -> Result<(Order, redemption::Model), ApiError> {
+        info!("Applying voucher {} to order {}", voucher_id, order_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let order = OrderEntity::find_by_id(order_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+        if order.voucher_id.is_some() {
+            return Err(ApiError::Conflict("Order already carries a voucher".to_string()));
+        }
+
+        let voucher = voucher::Entity::find_by_id(voucher_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock voucher {}: {}", voucher_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Voucher not found".to_string()))?;
+
+        if voucher.max_uses.is_some_and(|max| voucher.uses >= max) {
+            return Err(ApiError::Conflict("Voucher has been used up".to_string()));
+        }
+        if let Some(max) = voucher.max_uses_per_customer {
+            let customer_id = order.customer_id
+                .ok_or_else(|| ApiError::InvalidInput("Voucher needs a customer on the order".to_string()))?;
+            let used = redemption::Entity::find()
+                .filter(redemption::Column::VoucherId.eq(voucher.id))
+                .filter(redemption::Column::CustomerId.eq(customer_id))
+                .filter(redemption::Column::ReleasedAt.is_null())
+                .count(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to count uses of voucher {}: {}", voucher.id, e);
+                    ApiError::DatabaseError(e.to_string())
+                })?;
+            if used >= max as u64 {
+                return Err(ApiError::Conflict("Customer has already used this voucher".to_string()));
+            }
+        }
+
+        let now = chrono::Utc::now().fixed_offset();
+        let redemption = redemption::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            voucher_id: Set(voucher.id),
+            order_id: Set(order.id),
+            customer_id: Set(order.customer_id),
+            created_at: Set(now),
+            released_at: Set(None),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| {
+            error!("Failed to redeem voucher {} on order {}: {}", voucher.id, order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let uses = voucher.uses + 1;
+        let rule = voucher.rule();
+        let mut voucher: voucher::ActiveModel = voucher.into();
+        voucher.uses = Set(uses);
+        voucher.updated_at = Set(now);
+        voucher.update(&txn).await.map_err(|e| {
+            error!("Failed to count use of voucher {}: {}", voucher_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let mut order: OrderActiveModel = order.into();
+        order.voucher_id = Set(Some(voucher_id));
+        order.discount_rule = Set(Some(rule));
+        order.update(&txn).await.map_err(|e| {
+            error!("Failed to discount order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+        let order = OrderRepository::recalculate(&txn, order_id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit voucher on order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((order, redemption))
+    }
+
+    /// Take the voucher off an order, giving its use back; `None` if the order carried none
+    pub async fn release_voucher(&self, order_id: Uuid) -> Result<Option<redemption::Model>, ApiError> {
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let order = OrderEntity::find_by_id(order_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+
+        let open = redemption::Entity::find()
+            .filter(redemption::Column::OrderId.eq(order_id))
+            .filter(redemption::Column::ReleasedAt.is_null())
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch voucher redemption of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let Some(open) = open else {
+            return Ok(None);
+        };
+        info!("Releasing voucher {} from order {}", open.voucher_id, order_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut released: redemption::ActiveModel = open.clone().into();
+        released.released_at = Set(Some(now));
+        let released = released.update(&txn).await.map_err(|e| {
+            error!("Failed to release voucher redemption {}: {}", open.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        voucher::Entity::update_many()
+            .col_expr(voucher::Column::Uses, Expr::col(voucher::Column::Uses).sub(1))
+            .col_expr(voucher::Column::UpdatedAt, Expr::value(now))
+            .filter(voucher::Column::Id.eq(open.voucher_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to give back use of voucher {}: {}", open.voucher_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let mut order: OrderActiveModel = order.into();
+        order.voucher_id = Set(None);
+        order.discount_rule = Set(None);
+        order.update(&txn).await.map_err(|e| {
+            error!("Failed to remove discount of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+        OrderRepository::recalculate(&txn, order_id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit voucher release of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(Some(released))
+    }
+
+    async fn insert_voucher<C: ConnectionTrait>(db: &C, voucher: voucher::Model) -> Result<voucher::Model, ApiError> {
+        voucher::ActiveModel::from(voucher)
+            .reset_all()
+            .insert(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create voucher: {}", e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A voucher with this code already exists".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Lock the customer's balance, apply the entry to it and append the entry to the ledger.
+    /// Fails without writing anything when the balance would go negative.
+    async fn append_in<C: ConnectionTrait>(db: &C, entry: NewEntry) -> Result<(Entry, balance::Model), ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+
+        balance::Entity::insert(balance::ActiveModel {
+            customer_id: Set(entry.customer_id),
+            account_id: Set(entry.account_id),
+            points: Set(0),
+            lifetime_points: Set(0),
+            updated_at: Set(now),
+        })
+        .on_conflict(OnConflict::column(balance::Column::CustomerId).do_nothing().to_owned())
+        .exec_without_returning(db)
+        .await
+        .map_err(|e| {
+            error!("Failed to create loyalty balance of customer {}: {}", entry.customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let current = balance::Entity::find_by_id(entry.customer_id)
+            .lock_exclusive()
+            .one(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock loyalty balance of customer {}: {}", entry.customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Loyalty balance not found".to_string()))?;
+
+        let points = current.points + entry.points;
+        if points < 0 {
+            return Err(ApiError::Conflict(format!("Not enough points; the balance is {}", current.points)));
+        }
+        let lifetime_points = current.lifetime_points + if entry.kind == EntryKind::Earn { entry.points } else { 0 };
+
+        let mut balance: balance::ActiveModel = current.into();
+        balance.points = Set(points);
+        balance.lifetime_points = Set(lifetime_points);
+        balance.updated_at = Set(now);
+        let balance = balance.update(db).await.map_err(|e| {
+            error!("Failed to update loyalty balance of customer {}: {}", entry.customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let recorded = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(entry.account_id),
+            customer_id: Set(entry.customer_id),
+            kind: Set(entry.kind.to_string()),
+            points: Set(entry.points),
+            balance_after: Set(points),
+            order_id: Set(entry.order_id),
+            voucher_id: Set(entry.voucher_id),
+            reason: Set(entry.reason),
+            created_by: Set(entry.created_by),
+            created_at: Set(now),
+        }
+        .insert(db)
+        .await
+        .map_err(|e| {
+            error!("Failed to record loyalty entry of customer {}: {}", entry.customer_id, e);
+            match e.sql_err() {
+                Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                    ApiError::Conflict("Points were already earned for this order".to_string())
+                }
+                _ => ApiError::DatabaseError(e.to_string()),
+            }
+        })?;
+
+        Ok((recorded, balance))
+    }
+}
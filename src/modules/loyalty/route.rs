@@ -0,0 +1,33 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create loyalty routes for customers; staff see the same rewards but hold no points
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/loyalty/me", get(get_mine))
+        .route("/loyalty/me/ledger", get(get_my_ledger))
+        .route("/loyalty/rewards", get(get_rewards))
+        .route("/loyalty/rewards/:id/redeem", post(redeem_reward))
+}
+
+/// Create loyalty management routes for managers
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/loyalty/rules", get(get_rules).post(create_rule))
+        .route("/loyalty/rules/:id", put(update_rule).delete(delete_rule))
+        .route("/loyalty/rewards", post(create_reward))
+        .route("/loyalty/rewards/:id", put(update_reward).delete(delete_reward))
+        .route("/vouchers", get(get_vouchers).post(create_voucher))
+        .route("/vouchers/:id", put(update_voucher))
+        .route("/loyalty/customers/:id", get(get_customer))
+        .route("/loyalty/customers/:id/ledger", get(get_customer_ledger))
+        .route("/loyalty/customers/:id/adjustments", post(create_adjustment))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
@@ -0,0 +1,593 @@
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{
+        events::{topic, Event, SharedEventBus},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        loyalty::{
+            entity::{
+                reward, rule, voucher, CreateAdjustmentRequest, CreateRewardRequest, CreateRuleRequest, CreateVoucherRequest, EntryKind,
+                LedgerQuery, LoyaltyView, Model as Entry, NewEntry, RedemptionView, UpdateRewardRequest, UpdateRuleRequest,
+                UpdateVoucherRequest, VoucherQuery,
+            },
+            repository::LoyaltyRepository,
+        },
+        order::entity::{Model as Order, SetCustomerRequest},
+        payment::{entity::PaymentStatus, repository::PaymentRepository},
+        user::{
+            entity::{Model as User, UserRole},
+            repository::UserRepository,
+        },
+    },
+};
+
+/// Prefix of the codes of vouchers bought with points
+const REWARD_CODE_PREFIX: &str = "RW-";
+
+/// Loyalty service layer for business logic
+#[derive(Debug, Clone)]
+pub struct LoyaltyService {
+    repository: LoyaltyRepository,
+    user_repository: UserRepository,
+    payment_repository: PaymentRepository,
+    audit_service: AuditService,
+    events: SharedEventBus,
+}
+
+impl LoyaltyService {
+    /// Create a new loyalty service
+    pub fn new(
+        repository: LoyaltyRepository,
+        user_repository: UserRepository,
+        payment_repository: PaymentRepository,
+        audit_service: AuditService,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            repository,
+            user_repository,
+            payment_repository,
+            audit_service,
+            events,
+        }
+    }
+
+    /// The caller's points and the personal vouchers they can still use
+    pub async fn get_mine(&self, actor: &UserInfo) -> Result<LoyaltyView, ApiError> {
+        self.view(actor.parsed_id()?).await
+    }
+
+    /// The caller's points history
+    pub async fn get_my_ledger(&self, actor: &UserInfo, query: LedgerQuery) -> Result<Page<Entry>, ApiError> {
+        self.ledger(actor.parsed_id()?, query).await
+    }
+
+    /// A customer's points, for staff
+    pub async fn get_customer(&self, actor: &UserInfo, customer_id: Uuid) -> Result<LoyaltyView, ApiError> {
+        self.get_owned_customer(actor, customer_id).await?;
+        self.view(customer_id).await
+    }
+
+    /// A customer's points history, for staff
+    pub async fn get_customer_ledger(&self, actor: &UserInfo, customer_id: Uuid, query: LedgerQuery) -> Result<Page<Entry>, ApiError> {
+        self.get_owned_customer(actor, customer_id).await?;
+        self.ledger(customer_id, query).await
+    }
+
+    /// Correct a customer's balance, e.g. after a refund
+    pub async fn adjust(&self, ctx: &RequestContext, actor: &UserInfo, customer_id: Uuid, data: CreateAdjustmentRequest) -> Result<Entry, ApiError> {
+        info!("Adjusting points of customer {} by {}", customer_id, data.points);
+
+        let customer = self.get_owned_customer(actor, customer_id).await?;
+        let (entry, _) = self.repository
+            .append(NewEntry {
+                account_id: customer.account_id,
+                customer_id,
+                kind: EntryKind::Adjust,
+                points: data.points,
+                order_id: None,
+                voucher_id: None,
+                reason: Some(data.reason),
+                created_by: actor.parsed_id()?,
+            })
+            .await?;
+
+        self.audit(ctx, entry.account_id, AuditAction::LoyaltyPointsAdjusted, (AuditTarget::User, customer_id), None, Some(&entry)).await?;
+        Ok(entry)
+    }
+
+    /// Earn points for a paid order by the rules of its account; orders without a customer earn nothing
+    pub async fn earn(&self, order: &Order, paid_by: Uuid) -> Result<Option<Entry>, ApiError> {
+        let Some(customer_id) = order.customer_id else {
+            return Ok(None);
+        };
+        if self.repository.has_earned(order.id).await? {
+            return Ok(None);
+        }
+
+        let at = order.paid_at.unwrap_or_else(|| chrono::Utc::now().fixed_offset());
+        let total = order.total()?.amount();
+        let earned: Decimal = self.repository
+            .get_rules(order.account_id)
+            .await?
+            .iter()
+            .map(|rule| rule.earns(order.branch_id, total, at))
+            .sum();
+        // Whole points only, never rounded up
+        let points = i32::try_from(earned.floor()).unwrap_or(i32::MAX);
+        if points <= 0 {
+            return Ok(None);
+        }
+
+        info!("Order {} earns {} points for customer {}", order.id, points, customer_id);
+
+        let (entry, balance) = self.repository
+            .append(NewEntry {
+                account_id: order.account_id,
+                customer_id,
+                kind: EntryKind::Earn,
+                points,
+                order_id: Some(order.id),
+                voucher_id: None,
+                reason: None,
+                created_by: paid_by,
+            })
+            .await?;
+
+        let payload = serde_json::json!({ "entry": entry, "balance": balance.points });
+        self.events.publish(Event::new("loyalty.points_earned", vec![topic::branch(order.branch_id)], &payload)).await;
+        Ok(Some(entry))
+    }
+
+    /// Find the customer an order should collect points for
+    pub async fn resolve_customer(&self, account_id: Uuid, data: SetCustomerRequest) -> Result<User, ApiError> {
+        let customer = match (data.customer_id, data.email) {
+            (Some(id), None) => self.user_repository.get_by_id(id).await,
+            (None, Some(email)) => self.user_repository.get_by_email(&email.to_lowercase()).await,
+            _ => return Err(ApiError::InvalidInput("Give either customer_id or email".to_string())),
+        }
+        .map_err(|_| ApiError::NotFound("Customer not found".to_string()))?;
+
+        if customer.account_id != account_id || customer.role != UserRole::Customer.to_string() {
+            return Err(ApiError::NotFound("Customer not found".to_string()));
+        }
+
+        Ok(customer)
+    }
+
+    /// Put a voucher on an order by its code; not possible once money was taken for it
+    pub async fn redeem_voucher(&self, order: &Order, code: &str) -> Result<Order, ApiError> {
+        let voucher = self.repository
+            .get_voucher_by_code(order.account_id, &code.trim().to_uppercase())
+            .await?;
+
+        if !voucher.is_valid_at(chrono::Utc::now().fixed_offset()) {
+            return Err(ApiError::Conflict("Voucher is not valid now".to_string()));
+        }
+        if voucher.customer_id.is_some() && voucher.customer_id != order.customer_id {
+            return Err(ApiError::Conflict("Voucher belongs to another customer".to_string()));
+        }
+        self.ensure_unpaid(order).await?;
+
+        let (order, redemption) = self.repository.apply_voucher(order.id, voucher.id).await?;
+        info!("Voucher {} redeemed on order {} as {}", voucher.code, order.id, redemption.id);
+        Ok(order)
+    }
+
+    /// Take the voucher off an order again
+    pub async fn remove_voucher(&self, order: &Order) -> Result<(), ApiError> {
+        if order.voucher_id.is_none() {
+            return Err(ApiError::NotFound("Order carries no voucher".to_string()));
+        }
+        self.ensure_unpaid(order).await?;
+
+        self.repository.release_voucher(order.id).await?;
+        Ok(())
+    }
+
+    /// Give the voucher use of a cancelled order back
+    pub async fn release_voucher(&self, order: &Order) -> Result<(), ApiError> {
+        if order.voucher_id.is_some() {
+            self.repository.release_voucher(order.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Earning rules of the caller's account
+    pub async fn get_rules(&self, actor: &UserInfo) -> Result<Vec<rule::Model>, ApiError> {
+        self.repository.get_rules(actor.parsed_account_id()?).await
+    }
+
+    /// Create an earning rule
+    pub async fn create_rule(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateRuleRequest) -> Result<rule::Model, ApiError> {
+        info!("Creating loyalty rule: {}", data.name);
+
+        Self::check_window(data.valid_from, data.valid_until)?;
+        let now = chrono::Utc::now().fixed_offset();
+        let rule = self.repository
+            .create_rule(rule::Model {
+                id: Uuid::new_v4(),
+                account_id: actor.parsed_account_id()?,
+                branch_id: data.branch_id,
+                name: data.name,
+                kind: data.kind,
+                points: data.points,
+                min_order_amount: data.min_order_amount,
+                valid_from: data.valid_from,
+                valid_until: data.valid_until,
+                active: true,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+
+        self.audit(ctx, rule.account_id, AuditAction::LoyaltyRuleCreated, (AuditTarget::LoyaltyRule, rule.id), None, Some(&rule)).await?;
+        Ok(rule)
+    }
+
+    /// Update an earning rule
+    pub async fn update_rule(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateRuleRequest) -> Result<rule::Model, ApiError> {
+        info!("Updating loyalty rule with ID: {}", id);
+
+        let before = self.get_owned_rule(actor, id).await?;
+        let mut rule = before.clone();
+        if let Some(name) = data.name {
+            rule.name = name;
+        }
+        if let Some(points) = data.points {
+            rule.points = points;
+        }
+        if data.min_order_amount.is_some() {
+            rule.min_order_amount = data.min_order_amount;
+        }
+        if data.valid_from.is_some() {
+            rule.valid_from = data.valid_from;
+        }
+        if data.valid_until.is_some() {
+            rule.valid_until = data.valid_until;
+        }
+        if let Some(active) = data.active {
+            rule.active = active;
+        }
+        Self::check_window(rule.valid_from, rule.valid_until)?;
+
+        let rule = self.repository.update_rule(rule).await?;
+
+        self.audit(ctx, rule.account_id, AuditAction::LoyaltyRuleUpdated, (AuditTarget::LoyaltyRule, id), Some(&before), Some(&rule)).await?;
+        Ok(rule)
+    }
+
+    /// Delete an earning rule
+    pub async fn delete_rule(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting loyalty rule with ID: {}", id);
+
+        let before = self.get_owned_rule(actor, id).await?;
+        self.repository.delete_rule(id).await?;
+
+        self.audit(ctx, before.account_id, AuditAction::LoyaltyRuleDeleted, (AuditTarget::LoyaltyRule, id), Some(&before), None).await?;
+        Ok(())
+    }
+
+    /// Rewards of the caller's account; customers and staff below managers only see active ones
+    pub async fn get_rewards(&self, actor: &UserInfo) -> Result<Vec<reward::Model>, ApiError> {
+        let active_only = actor.parsed_role()?.level() < UserRole::Manager.level();
+        self.repository.get_rewards(actor.parsed_account_id()?, active_only).await
+    }
+
+    /// Create a reward
+    pub async fn create_reward(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateRewardRequest) -> Result<reward::Model, ApiError> {
+        info!("Creating loyalty reward: {}", data.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let reward = self.repository
+            .create_reward(reward::Model {
+                id: Uuid::new_v4(),
+                account_id: actor.parsed_account_id()?,
+                name: data.name,
+                description: data.description,
+                points_cost: data.points_cost,
+                discount_kind: data.discount_kind,
+                discount_value: data.discount_value,
+                active: true,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            })
+            .await?;
+
+        self.audit(ctx, reward.account_id, AuditAction::LoyaltyRewardCreated, (AuditTarget::LoyaltyReward, reward.id), None, Some(&reward)).await?;
+        Ok(reward)
+    }
+
+    /// Update a reward; vouchers already redeemed for it keep their discount
+    pub async fn update_reward(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateRewardRequest) -> Result<reward::Model, ApiError> {
+        info!("Updating loyalty reward with ID: {}", id);
+
+        let before = self.get_owned_reward(actor, id).await?;
+        let mut reward = before.clone();
+        if let Some(name) = data.name {
+            reward.name = name;
+        }
+        if data.description.is_some() {
+            reward.description = data.description;
+        }
+        if let Some(points_cost) = data.points_cost {
+            reward.points_cost = points_cost;
+        }
+        if let Some(discount_kind) = data.discount_kind {
+            reward.discount_kind = discount_kind;
+        }
+        if let Some(discount_value) = data.discount_value {
+            reward.discount_value = discount_value;
+        }
+        if let Some(active) = data.active {
+            reward.active = active;
+        }
+
+        let reward = self.repository.update_reward(reward).await?;
+
+        self.audit(ctx, reward.account_id, AuditAction::LoyaltyRewardUpdated, (AuditTarget::LoyaltyReward, id), Some(&before), Some(&reward)).await?;
+        Ok(reward)
+    }
+
+    /// Delete a reward
+    pub async fn delete_reward(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting loyalty reward with ID: {}", id);
+
+        let before = self.get_owned_reward(actor, id).await?;
+        let deleted = self.repository.soft_delete_reward(id).await?;
+
+        self.audit(ctx, deleted.account_id, AuditAction::LoyaltyRewardDeleted, (AuditTarget::LoyaltyReward, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Spend the caller's points on a reward, receiving a single-use personal voucher
+    pub async fn redeem_reward(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<RedemptionView, ApiError> {
+        info!("Redeeming loyalty reward {}", id);
+
+        let reward = self.get_owned_reward(actor, id).await?;
+        if !reward.active {
+            return Err(ApiError::Conflict("Reward is not available".to_string()));
+        }
+
+        let customer_id = actor.parsed_id()?;
+        let now = chrono::Utc::now().fixed_offset();
+        let code = format!("{}{}", REWARD_CODE_PREFIX, &Uuid::new_v4().simple().to_string()[..10].to_uppercase());
+        let voucher = voucher::Model {
+            id: Uuid::new_v4(),
+            account_id: reward.account_id,
+            code,
+            description: Some(reward.name.clone()),
+            discount_kind: reward.discount_kind.clone(),
+            discount_value: reward.discount_value,
+            min_order_amount: None,
+            valid_from: None,
+            valid_until: None,
+            max_uses: Some(1),
+            max_uses_per_customer: None,
+            customer_id: Some(customer_id),
+            reward_id: Some(reward.id),
+            uses: 0,
+            active: true,
+            created_by: customer_id,
+            created_at: now,
+            updated_at: now,
+        };
+        let entry = NewEntry {
+            account_id: reward.account_id,
+            customer_id,
+            kind: EntryKind::Redeem,
+            points: -reward.points_cost,
+            order_id: None,
+            voucher_id: None,
+            reason: Some(reward.name.clone()),
+            created_by: customer_id,
+        };
+
+        let (entry, voucher) = self.repository.redeem_reward(voucher, entry).await?;
+        let redemption = RedemptionView { entry, voucher };
+
+        self.audit(ctx, reward.account_id, AuditAction::LoyaltyRewardRedeemed, (AuditTarget::LoyaltyReward, id), None, Some(&redemption)).await?;
+        Ok(redemption)
+    }
+
+    /// Vouchers of the caller's account
+    pub async fn get_vouchers(&self, actor: &UserInfo, query: VoucherQuery) -> Result<Page<voucher::Model>, ApiError> {
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository
+            .search_vouchers(actor.parsed_account_id()?, query.include_personal, page, per_page)
+            .await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Create a promo code
+    pub async fn create_voucher(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateVoucherRequest) -> Result<voucher::Model, ApiError> {
+        info!("Creating voucher: {}", data.code);
+
+        Self::check_window(data.valid_from, data.valid_until)?;
+        if data.code.to_uppercase().starts_with(REWARD_CODE_PREFIX) {
+            return Err(ApiError::InvalidInput(format!("Codes starting with {} are kept for rewards", REWARD_CODE_PREFIX)));
+        }
+
+        let now = chrono::Utc::now().fixed_offset();
+        let voucher = self.repository
+            .create_voucher(voucher::Model {
+                id: Uuid::new_v4(),
+                account_id: actor.parsed_account_id()?,
+                code: data.code.to_uppercase(),
+                description: data.description,
+                discount_kind: data.discount_kind,
+                discount_value: data.discount_value,
+                min_order_amount: data.min_order_amount,
+                valid_from: data.valid_from,
+                valid_until: data.valid_until,
+                max_uses: data.max_uses,
+                max_uses_per_customer: data.max_uses_per_customer,
+                customer_id: None,
+                reward_id: None,
+                uses: 0,
+                active: true,
+                created_by: actor.parsed_id()?,
+                created_at: now,
+                updated_at: now,
+            })
+            .await?;
+
+        self.audit(ctx, voucher.account_id, AuditAction::VoucherCreated, (AuditTarget::Voucher, voucher.id), None, Some(&voucher)).await?;
+        Ok(voucher)
+    }
+
+    /// Change the limits or validity of a voucher; its discount is fixed once created
+    pub async fn update_voucher(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateVoucherRequest) -> Result<voucher::Model, ApiError> {
+        info!("Updating voucher with ID: {}", id);
+
+        let before = self.get_owned_voucher(actor, id).await?;
+        let mut voucher = before.clone();
+        if data.description.is_some() {
+            voucher.description = data.description;
+        }
+        if data.valid_from.is_some() {
+            voucher.valid_from = data.valid_from;
+        }
+        if data.valid_until.is_some() {
+            voucher.valid_until = data.valid_until;
+        }
+        if let Some(max_uses) = data.max_uses {
+            if max_uses < voucher.uses {
+                return Err(ApiError::Conflict(format!("Voucher is already used {} times", voucher.uses)));
+            }
+            voucher.max_uses = Some(max_uses);
+        }
+        if data.max_uses_per_customer.is_some() {
+            voucher.max_uses_per_customer = data.max_uses_per_customer;
+        }
+        if let Some(active) = data.active {
+            voucher.active = active;
+        }
+        Self::check_window(voucher.valid_from, voucher.valid_until)?;
+
+        let voucher = self.repository.update_voucher(voucher).await?;
+
+        self.audit(ctx, voucher.account_id, AuditAction::VoucherUpdated, (AuditTarget::Voucher, id), Some(&before), Some(&voucher)).await?;
+        Ok(voucher)
+    }
+
+    async fn view(&self, customer_id: Uuid) -> Result<LoyaltyView, ApiError> {
+        let balance = self.repository.get_balance(customer_id).await?;
+        let vouchers = self.repository.get_personal_vouchers(customer_id).await?;
+
+        Ok(LoyaltyView {
+            customer_id,
+            points: balance.as_ref().map_or(0, |balance| balance.points),
+            lifetime_points: balance.as_ref().map_or(0, |balance| balance.lifetime_points),
+            vouchers,
+        })
+    }
+
+    async fn ledger(&self, customer_id: Uuid, query: LedgerQuery) -> Result<Page<Entry>, ApiError> {
+        if let Some(ref kind) = query.kind {
+            kind.parse::<EntryKind>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search_ledger(customer_id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Discounts are settled before the first payment; changing them afterwards would move the balance under the cashier
+    async fn ensure_unpaid(&self, order: &Order) -> Result<(), ApiError> {
+        let paying = self.payment_repository
+            .get_by_order_id(order.id)
+            .await?
+            .iter()
+            .any(|payment| payment.status == PaymentStatus::Captured.to_string() || payment.status == PaymentStatus::Pending.to_string());
+        if paying {
+            return Err(ApiError::Conflict("Order is already being paid".to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_window(from: Option<chrono::DateTime<chrono::FixedOffset>>, until: Option<chrono::DateTime<chrono::FixedOffset>>) -> Result<(), ApiError> {
+        if let (Some(from), Some(until)) = (from, until) {
+            if until <= from {
+                return Err(ApiError::InvalidInput("valid_until must be after valid_from".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch a customer, hiding those of other accounts
+    async fn get_owned_customer(&self, actor: &UserInfo, id: Uuid) -> Result<User, ApiError> {
+        let customer = self.user_repository
+            .get_by_id(id)
+            .await
+            .map_err(|_| ApiError::NotFound("Customer not found".to_string()))?;
+
+        if customer.account_id != actor.parsed_account_id()? || customer.role != UserRole::Customer.to_string() {
+            return Err(ApiError::NotFound("Customer not found".to_string()));
+        }
+
+        Ok(customer)
+    }
+
+    /// Fetch an earning rule, hiding those of other accounts
+    async fn get_owned_rule(&self, actor: &UserInfo, id: Uuid) -> Result<rule::Model, ApiError> {
+        let rule = self.repository.get_rule(id).await?;
+
+        if rule.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Loyalty rule not found".to_string()));
+        }
+
+        Ok(rule)
+    }
+
+    /// Fetch a reward, hiding those of other accounts
+    async fn get_owned_reward(&self, actor: &UserInfo, id: Uuid) -> Result<reward::Model, ApiError> {
+        let reward = self.repository.get_reward(id).await?;
+
+        if reward.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Reward not found".to_string()));
+        }
+
+        Ok(reward)
+    }
+
+    /// Fetch a voucher, hiding those of other accounts
+    async fn get_owned_voucher(&self, actor: &UserInfo, id: Uuid) -> Result<voucher::Model, ApiError> {
+        let voucher = self.repository.get_voucher(id).await?;
+
+        if voucher.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Voucher not found".to_string()));
+        }
+
+        Ok(voucher)
+    }
+
+    /// Record a loyalty mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Uuid),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (target.0, Some(target.1)), before, after)
+            .await
+    }
+}
@@ -0,0 +1,233 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::menu::entity::{
+        category, item, item_price, CreateCategoryRequest, CreateItemRequest, CreateModifierGroupRequest, ItemQuery, ItemView,
+        MenuQuery, MenuView, ModifierGroupView, SetItemPriceRequest, UpdateCategoryRequest, UpdateItemRequest,
+        UpdateModifierGroupRequest,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Get the full menu priced at a branch
+pub async fn get_menu(
+    Query(query): Query<MenuQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<MenuView>, ApiError> {
+    info!("Fetching menu");
+    let result = state.menu_service.get_menu(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get all menu categories
+pub async fn get_categories(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<category::Model>>, ApiError> {
+    info!("Fetching menu categories");
+    let result = state.menu_service.get_categories(&user).await?;
+    Ok(Json(result))
+}
+
+/// Create a new menu category
+pub async fn create_category(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateCategoryRequest>,
+) -> Result<(StatusCode, Json<category::Model>), ApiError> {
+    info!("Creating menu category: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.create_category(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing menu category
+pub async fn update_category(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateCategoryRequest>,
+) -> Result<Json<category::Model>, ApiError> {
+    info!("Updating menu category with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.update_category(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete an empty menu category
+pub async fn delete_category(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting menu category with ID: {}", id);
+    state.menu_service.delete_category(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get menu items, optionally of one category
+pub async fn get_items(
+    Query(query): Query<ItemQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<item::Model>>, ApiError> {
+    info!("Fetching menu items");
+    let result = state.menu_service.get_items(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get a menu item with its prices and modifiers
+pub async fn get_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<ItemView>, ApiError> {
+    info!("Fetching menu item with ID: {}", id);
+    let result = state.menu_service.get_item(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Create a new menu item
+pub async fn create_item(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateItemRequest>,
+) -> Result<(StatusCode, Json<item::Model>), ApiError> {
+    info!("Creating menu item: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.create_item(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing menu item
+pub async fn update_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateItemRequest>,
+) -> Result<Json<item::Model>, ApiError> {
+    info!("Updating menu item with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.update_item(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a menu item (soft delete)
+pub async fn delete_item(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting menu item with ID: {}", id);
+    state.menu_service.delete_item(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Override the price of a menu item at a branch
+pub async fn set_price(
+    Path((id, branch_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetItemPriceRequest>,
+) -> Result<Json<item_price::Model>, ApiError> {
+    info!("Setting price of menu item {} at branch {}", id, branch_id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.set_price(&ctx, &user, id, branch_id, payload.price).await?;
+    Ok(Json(result))
+}
+
+/// Remove the branch price of a menu item
+pub async fn remove_price(
+    Path((id, branch_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Removing price of menu item {} at branch {}", id, branch_id);
+    state.menu_service.remove_price(&ctx, &user, id, branch_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Attach a modifier group to a menu item
+pub async fn create_modifier_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateModifierGroupRequest>,
+) -> Result<(StatusCode, Json<ModifierGroupView>), ApiError> {
+    info!("Creating modifier group for menu item with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.create_modifier_group(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a modifier group
+pub async fn update_modifier_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateModifierGroupRequest>,
+) -> Result<Json<ModifierGroupView>, ApiError> {
+    info!("Updating modifier group with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.menu_service.update_modifier_group(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a modifier group
+pub async fn delete_modifier_group(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting modifier group with ID: {}", id);
+    state.menu_service.delete_modifier_group(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,586 @@
+use chrono::{NaiveTime, Weekday};
+use sea_orm::prelude::Decimal;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// Menu sections such as starters or drinks
+pub mod category {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_categories")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub name: String,
+        pub description: Option<String>,
+        pub position: i32,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+        pub deleted_at: Option<DateTimeWithTimeZone>,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuCategory", 7)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("description", &self.description)?;
+            state.serialize_field("position", &self.position)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Dishes and drinks that can be ordered
+pub mod item {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    use super::Availability;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_items")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub category_id: Uuid,
+        pub name: String,
+        pub description: Option<String>,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub price: Decimal,
+        pub tax_category: String,
+        pub allergens: Vec<String>,
+        pub dietary_tags: Vec<String>,
+        #[sea_orm(column_type = "JsonBinary")]
+        pub availability: Availability,
+        pub is_available: bool,
+        pub position: i32,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+        pub deleted_at: Option<DateTimeWithTimeZone>,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuItem", 14)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("category_id", &self.category_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("description", &self.description)?;
+            state.serialize_field("price", &self.price)?;
+            state.serialize_field("tax_category", &self.tax_category)?;
+            state.serialize_field("allergens", &self.allergens)?;
+            state.serialize_field("dietary_tags", &self.dietary_tags)?;
+            state.serialize_field("availability", &self.availability)?;
+            state.serialize_field("is_available", &self.is_available)?;
+            state.serialize_field("position", &self.position)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    impl Model {
+        /// Whether the item can be ordered at a branch-local date and time
+        pub fn is_orderable_at(&self, at: chrono::NaiveDateTime) -> bool {
+            self.is_available && self.deleted_at.is_none() && self.availability.contains(at)
+        }
+    }
+}
+
+/// An item sold out at a branch because its ingredients ran out
+pub mod item_outage {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_item_outages")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub item_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub branch_id: Uuid,
+        /// Stock item that ran out
+        pub stock_item_id: Option<Uuid>,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuItemOutage", 4)?;
+            state.serialize_field("item_id", &self.item_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("stock_item_id", &self.stock_item_id)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Price of an item at a specific branch, overriding the base price
+pub mod item_price {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_item_prices")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub item_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub branch_id: Uuid,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub price: Decimal,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuItemPrice", 5)?;
+            state.serialize_field("item_id", &self.item_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("price", &self.price)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Choices attached to an item, such as sides or doneness
+pub mod modifier_group {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_modifier_groups")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub item_id: Uuid,
+        pub name: String,
+        pub min_select: i32,
+        pub max_select: i32,
+        pub position: i32,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuModifierGroup", 8)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("item_id", &self.item_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("min_select", &self.min_select)?;
+            state.serialize_field("max_select", &self.max_select)?;
+            state.serialize_field("position", &self.position)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// A single choice within a modifier group
+pub mod modifier_option {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "menu_modifier_options")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub group_id: Uuid,
+        pub name: String,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub price_delta: Decimal,
+        pub position: i32,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("MenuModifierOption", 5)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("group_id", &self.group_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("price_delta", &self.price_delta)?;
+            state.serialize_field("position", &self.position)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Window of the week in which an item can be ordered, in branch-local time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailabilityWindow {
+    pub weekdays: Vec<Weekday>,
+    pub from: NaiveTime,
+    pub to: NaiveTime,
+}
+
+/// Availability windows of an item; no windows means always available
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct Availability(pub Vec<AvailabilityWindow>);
+
+impl Availability {
+    /// Whether a branch-local date and time falls in any window
+    pub fn contains(&self, at: chrono::NaiveDateTime) -> bool {
+        use chrono::Datelike;
+
+        if self.0.is_empty() {
+            return true;
+        }
+
+        let (weekday, time) = (at.weekday(), at.time());
+        self.0.iter().any(|window| {
+            if window.from <= window.to {
+                window.weekdays.contains(&weekday) && window.from <= time && time < window.to
+            } else {
+                // Runs past midnight into the next day
+                (window.weekdays.contains(&weekday) && time >= window.from)
+                    || (window.weekdays.contains(&weekday.pred()) && time < window.to)
+            }
+        })
+    }
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxCategory {
+    Standard,
+    Reduced,
+    Zero,
+    Exempt,
+}
+
+impl std::fmt::Display for TaxCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaxCategory::Standard => write!(f, "STANDARD"),
+            TaxCategory::Reduced => write!(f, "REDUCED"),
+            TaxCategory::Zero => write!(f, "ZERO"),
+            TaxCategory::Exempt => write!(f, "EXEMPT"),
+        }
+    }
+}
+
+impl std::str::FromStr for TaxCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "STANDARD" => Ok(TaxCategory::Standard),
+            "REDUCED" => Ok(TaxCategory::Reduced),
+            "ZERO" => Ok(TaxCategory::Zero),
+            "EXEMPT" => Ok(TaxCategory::Exempt),
+            _ => Err(format!("Tax category {} is not valid", s)),
+        }
+    }
+}
+
+/// The fourteen allergens that must be declared on EU menus
+pub const ALLERGENS: [&str; 14] = [
+    "CELERY", "CRUSTACEANS", "EGGS", "FISH", "GLUTEN", "LUPIN", "MILK",
+    "MOLLUSCS", "MUSTARD", "NUTS", "PEANUTS", "SESAME", "SOY", "SULPHITES",
+];
+
+/// Dietary tags an item can carry
+pub const DIETARY_TAGS: [&str; 7] = [
+    "VEGETARIAN", "VEGAN", "GLUTEN_FREE", "LACTOSE_FREE", "HALAL", "KOSHER", "SPICY",
+];
+
+// Validators
+fn validate_price(price: &Decimal) -> Result<(), ValidationError> {
+    if price.is_sign_negative() || price.scale() > 2 {
+        return Err(ValidationError::new("price").with_message("Price must be non-negative with at most two decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_price_delta(price: &Decimal) -> Result<(), ValidationError> {
+    if price.scale() > 2 {
+        return Err(ValidationError::new("price_delta").with_message("Price delta must have at most two decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_tax_category(tax_category: &str) -> Result<(), ValidationError> {
+    tax_category
+        .parse::<TaxCategory>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("tax_category").with_message(e.into()))
+}
+
+fn validate_allergens(allergens: &[String]) -> Result<(), ValidationError> {
+    match allergens.iter().find(|a| !ALLERGENS.contains(&a.as_str())) {
+        Some(a) => Err(ValidationError::new("allergens").with_message(format!("Allergen {} is not valid", a).into())),
+        None => Ok(()),
+    }
+}
+
+fn validate_dietary_tags(tags: &[String]) -> Result<(), ValidationError> {
+    match tags.iter().find(|t| !DIETARY_TAGS.contains(&t.as_str())) {
+        Some(t) => Err(ValidationError::new("dietary_tags").with_message(format!("Dietary tag {} is not valid", t).into())),
+        None => Ok(()),
+    }
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateCategoryRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateCategoryRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 500, message = "Description must be at most 500 characters"))]
+    pub description: Option<String>,
+
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateItemRequest {
+    pub category_id: Uuid,
+
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: String,
+
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+
+    #[validate(custom(function = "validate_price"))]
+    pub price: Decimal,
+
+    #[validate(custom(function = "validate_tax_category"))]
+    pub tax_category: String,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_allergens"))]
+    pub allergens: Vec<String>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_dietary_tags"))]
+    pub dietary_tags: Vec<String>,
+
+    #[serde(default)]
+    pub availability: Availability,
+
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateItemRequest {
+    pub category_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 200, message = "Name must be between 1 and 200 characters"))]
+    pub name: Option<String>,
+
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+
+    #[validate(custom(function = "validate_price"))]
+    pub price: Option<Decimal>,
+
+    #[validate(custom(function = "validate_tax_category"))]
+    pub tax_category: Option<String>,
+
+    #[validate(custom(function = "validate_allergens"))]
+    pub allergens: Option<Vec<String>>,
+
+    #[validate(custom(function = "validate_dietary_tags"))]
+    pub dietary_tags: Option<Vec<String>>,
+
+    pub availability: Option<Availability>,
+
+    pub is_available: Option<bool>,
+
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetItemPriceRequest {
+    #[validate(custom(function = "validate_price"))]
+    pub price: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct ModifierOptionRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_price_delta"))]
+    pub price_delta: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateModifierGroupRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[serde(default)]
+    #[validate(range(min = 0, message = "Minimum selection cannot be negative"))]
+    pub min_select: i32,
+
+    #[validate(range(min = 1, message = "Maximum selection must be at least 1"))]
+    pub max_select: i32,
+
+    #[serde(default)]
+    pub position: i32,
+
+    #[validate(length(min = 1, message = "At least one option is required"), nested)]
+    pub options: Vec<ModifierOptionRequest>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateModifierGroupRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(range(min = 0, message = "Minimum selection cannot be negative"))]
+    pub min_select: Option<i32>,
+
+    #[validate(range(min = 1, message = "Maximum selection must be at least 1"))]
+    pub max_select: Option<i32>,
+
+    pub position: Option<i32>,
+
+    /// Replaces every option of the group when present
+    #[validate(length(min = 1, message = "At least one option is required"), nested)]
+    pub options: Option<Vec<ModifierOptionRequest>>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct MenuQuery {
+    /// Branch whose prices apply, defaulting to the session's active branch
+    pub branch_id: Option<Uuid>,
+    #[serde(default)]
+    pub available_only: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ItemQuery {
+    pub category_id: Option<Uuid>,
+}
+
+/// Modifier group together with its options
+#[derive(Debug, Clone, Serialize)]
+pub struct ModifierGroupView {
+    #[serde(flatten)]
+    pub group: modifier_group::Model,
+    pub options: Vec<modifier_option::Model>,
+}
+
+/// Item with its modifiers and the price that applies at a branch
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemView {
+    #[serde(flatten)]
+    pub item: item::Model,
+    /// Branch price if overridden, otherwise the base price
+    pub effective_price: Decimal,
+    pub branch_prices: Vec<item_price::Model>,
+    /// Ingredients ran out at the branch
+    pub sold_out: bool,
+    pub modifier_groups: Vec<ModifierGroupView>,
+}
+
+/// Category with the items listed under it
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryView {
+    #[serde(flatten)]
+    pub category: category::Model,
+    pub items: Vec<ItemView>,
+}
+
+/// Full menu as seen at one branch
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuView {
+    pub branch_id: Option<Uuid>,
+    pub categories: Vec<CategoryView>,
+}
@@ -0,0 +1,581 @@
+use anyhow::Result;
+use sea_orm::{
+    prelude::Decimal, sea_query::OnConflict, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::menu::entity::{
+        category, item, item_outage, item_price, modifier_group, modifier_option, CreateCategoryRequest, CreateItemRequest,
+        CreateModifierGroupRequest, ModifierOptionRequest, UpdateCategoryRequest, UpdateItemRequest, UpdateModifierGroupRequest,
+    },
+    common::ApiError,
+};
+
+/// Menu repository for database operations
+#[derive(Debug, Clone)]
+pub struct MenuRepository {
+    db: DatabaseConnection,
+}
+
+impl MenuRepository {
+    /// Create a new menu repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get all categories of an account in menu order
+    pub async fn get_categories(&self, account_id: Uuid) -> Result<Vec<category::Model>, ApiError> {
+        info!("Fetching menu categories by account ID: {}", account_id);
+
+        category::Entity::find()
+            .filter(category::Column::AccountId.eq(account_id))
+            .filter(category::Column::DeletedAt.is_null())
+            .order_by_asc(category::Column::Position)
+            .order_by_asc(category::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu categories by account ID {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a category by ID
+    pub async fn get_category(&self, id: Uuid) -> Result<category::Model, ApiError> {
+        info!("Fetching menu category with ID: {}", id);
+
+        let category = category::Entity::find_by_id(id)
+            .filter(category::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu category with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match category {
+            Some(category) => Ok(category),
+            None => Err(ApiError::NotFound("Menu category not found".to_string())),
+        }
+    }
+
+    /// Create a new category
+    pub async fn create_category(&self, account_id: Uuid, request: CreateCategoryRequest) -> Result<category::Model, ApiError> {
+        info!("Creating menu category: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let category = category::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            name: Set(request.name),
+            description: Set(request.description),
+            position: Set(request.position),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        category.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create menu category: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update an existing category
+    pub async fn update_category(&self, id: Uuid, request: UpdateCategoryRequest) -> Result<category::Model, ApiError> {
+        info!("Updating menu category with ID: {}", id);
+
+        let mut category: category::ActiveModel = self.get_category(id).await?.into();
+
+        if let Some(name) = request.name {
+            category.name = Set(name);
+        }
+
+        if let Some(description) = request.description {
+            category.description = Set(Some(description));
+        }
+
+        if let Some(position) = request.position {
+            category.position = Set(position);
+        }
+
+        category.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        category.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update menu category {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a category
+    pub async fn soft_delete_category(&self, id: Uuid) -> Result<category::Model, ApiError> {
+        info!("Soft deleting menu category with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut category: category::ActiveModel = self.get_category(id).await?.into();
+        category.deleted_at = Set(Some(now));
+        category.updated_at = Set(now);
+
+        category.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete menu category {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Count the items listed under a category
+    pub async fn count_items_in_category(&self, category_id: Uuid) -> Result<u64, ApiError> {
+        item::Entity::find()
+            .filter(item::Column::CategoryId.eq(category_id))
+            .filter(item::Column::DeletedAt.is_null())
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count items of menu category {}: {}", category_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the items of an account, optionally limited to a category
+    pub async fn get_items(&self, account_id: Uuid, category_id: Option<Uuid>) -> Result<Vec<item::Model>, ApiError> {
+        info!("Fetching menu items by account ID: {}", account_id);
+
+        let mut query = item::Entity::find()
+            .filter(item::Column::AccountId.eq(account_id))
+            .filter(item::Column::DeletedAt.is_null());
+        if let Some(category_id) = category_id {
+            query = query.filter(item::Column::CategoryId.eq(category_id));
+        }
+
+        query
+            .order_by_asc(item::Column::Position)
+            .order_by_asc(item::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu items by account ID {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get items by ID
+    pub async fn get_items_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<item::Model>, ApiError> {
+        info!("Fetching {} menu items by ID", ids.len());
+
+        item::Entity::find()
+            .filter(item::Column::Id.is_in(ids))
+            .filter(item::Column::DeletedAt.is_null())
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu items by ID: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get an item by ID
+    pub async fn get_item(&self, id: Uuid) -> Result<item::Model, ApiError> {
+        info!("Fetching menu item with ID: {}", id);
+
+        let item = item::Entity::find_by_id(id)
+            .filter(item::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu item with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match item {
+            Some(item) => Ok(item),
+            None => Err(ApiError::NotFound("Menu item not found".to_string())),
+        }
+    }
+
+    /// Create a new item
+    pub async fn create_item(&self, account_id: Uuid, request: CreateItemRequest) -> Result<item::Model, ApiError> {
+        info!("Creating menu item: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let item = item::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            category_id: Set(request.category_id),
+            name: Set(request.name),
+            description: Set(request.description),
+            price: Set(request.price),
+            tax_category: Set(request.tax_category),
+            allergens: Set(request.allergens),
+            dietary_tags: Set(request.dietary_tags),
+            availability: Set(request.availability),
+            is_available: Set(true),
+            position: Set(request.position),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        item.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create menu item: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update an existing item
+    pub async fn update_item(&self, id: Uuid, request: UpdateItemRequest) -> Result<item::Model, ApiError> {
+        info!("Updating menu item with ID: {}", id);
+
+        let mut item: item::ActiveModel = self.get_item(id).await?.into();
+
+        if let Some(category_id) = request.category_id {
+            item.category_id = Set(category_id);
+        }
+
+        if let Some(name) = request.name {
+            item.name = Set(name);
+        }
+
+        if let Some(description) = request.description {
+            item.description = Set(Some(description));
+        }
+
+        if let Some(price) = request.price {
+            item.price = Set(price);
+        }
+
+        if let Some(tax_category) = request.tax_category {
+            item.tax_category = Set(tax_category);
+        }
+
+        if let Some(allergens) = request.allergens {
+            item.allergens = Set(allergens);
+        }
+
+        if let Some(dietary_tags) = request.dietary_tags {
+            item.dietary_tags = Set(dietary_tags);
+        }
+
+        if let Some(availability) = request.availability {
+            item.availability = Set(availability);
+        }
+
+        if let Some(is_available) = request.is_available {
+            item.is_available = Set(is_available);
+        }
+
+        if let Some(position) = request.position {
+            item.position = Set(position);
+        }
+
+        item.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        item.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update menu item {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete an item, keeping it for the orders that reference it
+    pub async fn soft_delete_item(&self, id: Uuid) -> Result<item::Model, ApiError> {
+        info!("Soft deleting menu item with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut item: item::ActiveModel = self.get_item(id).await?.into();
+        item.deleted_at = Set(Some(now));
+        item.updated_at = Set(now);
+
+        item.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete menu item {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the branch prices of items
+    pub async fn get_prices(&self, item_ids: Vec<Uuid>) -> Result<Vec<item_price::Model>, ApiError> {
+        item_price::Entity::find()
+            .filter(item_price::Column::ItemId.is_in(item_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu item prices: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the items sold out at a branch
+    pub async fn get_outages(&self, branch_id: Uuid) -> Result<Vec<item_outage::Model>, ApiError> {
+        item_outage::Entity::find()
+            .filter(item_outage::Column::BranchId.eq(branch_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch sold out items of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the price of an item at a branch, if overridden
+    pub async fn get_price(&self, item_id: Uuid, branch_id: Uuid) -> Result<Option<item_price::Model>, ApiError> {
+        item_price::Entity::find_by_id((item_id, branch_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch price of menu item {} at branch {}: {}", item_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Override the price of an item at a branch
+    pub async fn set_price(&self, item_id: Uuid, branch_id: Uuid, price: Decimal) -> Result<item_price::Model, ApiError> {
+        info!("Setting price of menu item {} at branch {}", item_id, branch_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let override_price = item_price::ActiveModel {
+            item_id: Set(item_id),
+            branch_id: Set(branch_id),
+            price: Set(price),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        item_price::Entity::insert(override_price)
+            .on_conflict(
+                OnConflict::columns([item_price::Column::ItemId, item_price::Column::BranchId])
+                    .update_columns([item_price::Column::Price, item_price::Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set price of menu item {} at branch {}: {}", item_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        self.get_price(item_id, branch_id)
+            .await?
+            .ok_or_else(|| ApiError::DatabaseError("Price was not stored".to_string()))
+    }
+
+    /// Remove the price override of an item at a branch
+    pub async fn remove_price(&self, item_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        info!("Removing price of menu item {} at branch {}", item_id, branch_id);
+
+        let result = item_price::Entity::delete_by_id((item_id, branch_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove price of menu item {} at branch {}: {}", item_id, branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("Branch price not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Get the modifier groups of items in menu order
+    pub async fn get_modifier_groups(&self, item_ids: Vec<Uuid>) -> Result<Vec<modifier_group::Model>, ApiError> {
+        modifier_group::Entity::find()
+            .filter(modifier_group::Column::ItemId.is_in(item_ids))
+            .order_by_asc(modifier_group::Column::Position)
+            .order_by_asc(modifier_group::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu modifier groups: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the options of modifier groups in menu order
+    pub async fn get_modifier_options(&self, group_ids: Vec<Uuid>) -> Result<Vec<modifier_option::Model>, ApiError> {
+        modifier_option::Entity::find()
+            .filter(modifier_option::Column::GroupId.is_in(group_ids))
+            .order_by_asc(modifier_option::Column::Position)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu modifier options: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a modifier group by ID
+    pub async fn get_modifier_group(&self, id: Uuid) -> Result<modifier_group::Model, ApiError> {
+        info!("Fetching menu modifier group with ID: {}", id);
+
+        let group = modifier_group::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch menu modifier group with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match group {
+            Some(group) => Ok(group),
+            None => Err(ApiError::NotFound("Modifier group not found".to_string())),
+        }
+    }
+
+    /// Create a modifier group together with its options
+    pub async fn create_modifier_group(&self, item_id: Uuid, request: CreateModifierGroupRequest) -> Result<modifier_group::Model, ApiError> {
+        info!("Creating modifier group {} for menu item {}", request.name, item_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction creating modifier group: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let group = modifier_group::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            item_id: Set(item_id),
+            name: Set(request.name),
+            min_select: Set(request.min_select),
+            max_select: Set(request.max_select),
+            position: Set(request.position),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let group = group.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create modifier group: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Self::insert_options(&txn, group.id, request.options).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit modifier group {}: {}", group.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(group)
+    }
+
+    /// Update a modifier group, replacing its options when given
+    pub async fn update_modifier_group(&self, id: Uuid, request: UpdateModifierGroupRequest) -> Result<modifier_group::Model, ApiError> {
+        info!("Updating modifier group with ID: {}", id);
+
+        let mut group: modifier_group::ActiveModel = self.get_modifier_group(id).await?.into();
+
+        if let Some(name) = request.name {
+            group.name = Set(name);
+        }
+
+        if let Some(min_select) = request.min_select {
+            group.min_select = Set(min_select);
+        }
+
+        if let Some(max_select) = request.max_select {
+            group.max_select = Set(max_select);
+        }
+
+        if let Some(position) = request.position {
+            group.position = Set(position);
+        }
+
+        group.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction updating modifier group {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let group = group.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to update modifier group {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if let Some(options) = request.options {
+            Self::delete_options(&txn, id).await?;
+            Self::insert_options(&txn, id, options).await?;
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit modifier group {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(group)
+    }
+
+    /// Delete a modifier group and its options
+    pub async fn delete_modifier_group(&self, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting modifier group with ID: {}", id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction deleting modifier group {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Self::delete_options(&txn, id).await?;
+
+        modifier_group::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete modifier group {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit deletion of modifier group {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    async fn insert_options<C: ConnectionTrait>(db: &C, group_id: Uuid, options: Vec<ModifierOptionRequest>) -> Result<(), ApiError> {
+        let options = options.into_iter().enumerate().map(|(position, option)| modifier_option::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            group_id: Set(group_id),
+            name: Set(option.name),
+            price_delta: Set(option.price_delta),
+            position: Set(position as i32),
+        });
+
+        modifier_option::Entity::insert_many(options)
+            .exec(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create options of modifier group {}: {}", group_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    async fn delete_options<C: ConnectionTrait>(db: &C, group_id: Uuid) -> Result<(), ApiError> {
+        modifier_option::Entity::delete_many()
+            .filter(modifier_option::Column::GroupId.eq(group_id))
+            .exec(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete options of modifier group {}: {}", group_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+}
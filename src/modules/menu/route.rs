@@ -0,0 +1,31 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create menu routes for members of an account
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/menu", get(get_menu))
+        .route("/menu/categories", get(get_categories))
+        .route("/menu/items", get(get_items))
+        .route("/menu/items/:id", get(get_item))
+}
+
+/// Create menu administration routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/menu/categories", post(create_category))
+        .route("/menu/categories/:id", put(update_category).delete(delete_category))
+        .route("/menu/items", post(create_item))
+        .route("/menu/items/:id", put(update_item).delete(delete_item))
+        .route("/menu/items/:id/prices/:branch_id", put(set_price).delete(remove_price))
+        .route("/menu/items/:id/modifier-groups", post(create_modifier_group))
+        .route("/menu/modifier-groups/:id", put(update_modifier_group).delete(delete_modifier_group))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
@@ -0,0 +1,412 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        menu::{
+            entity::{
+                category, item, item_price, modifier_group, CategoryView, CreateCategoryRequest, CreateItemRequest,
+                CreateModifierGroupRequest, ItemQuery, ItemView, MenuQuery, MenuView, ModifierGroupView, UpdateCategoryRequest,
+                UpdateItemRequest, UpdateModifierGroupRequest,
+            },
+            repository::MenuRepository,
+        },
+    },
+};
+
+/// Menu service layer for business logic
+#[derive(Debug, Clone)]
+pub struct MenuService {
+    repository: MenuRepository,
+    branch_repository: BranchRepository,
+    audit_service: AuditService,
+}
+
+impl MenuService {
+    /// Create a new menu service
+    pub fn new(repository: MenuRepository, branch_repository: BranchRepository, audit_service: AuditService) -> Self {
+        Self { repository, branch_repository, audit_service }
+    }
+
+    /// The full menu with prices of a branch, defaulting to the session's active branch
+    pub async fn get_menu(&self, actor: &UserInfo, query: MenuQuery) -> Result<MenuView, ApiError> {
+        let account_id = actor.parsed_account_id()?;
+        let branch = match query.branch_id.or(actor.parsed_active_branch_id()?) {
+            Some(branch_id) => Some(self.get_branch(account_id, branch_id).await?),
+            None => None,
+        };
+
+        self.build_menu(account_id, branch.as_ref(), query.available_only).await
+    }
+
+    /// Assemble the menu of an account as seen at a branch
+    pub async fn build_menu(&self, account_id: Uuid, branch: Option<&Branch>, available_only: bool) -> Result<MenuView, ApiError> {
+        let categories = self.repository.get_categories(account_id).await?;
+        let mut items = self.repository.get_items(account_id, None).await?;
+
+        if available_only {
+            let now = Self::local_now(branch);
+            items.retain(|item| item.is_orderable_at(now));
+        }
+
+        let mut views = self.build_item_views(items, branch.map(|b| b.id)).await?;
+        if available_only {
+            views.retain(|view| !view.sold_out);
+        }
+        let categories = categories
+            .into_iter()
+            .map(|category| {
+                let (items, rest) = views.drain(..).partition(|view| view.item.category_id == category.id);
+                views = rest;
+                CategoryView { category, items }
+            })
+            .filter(|view| !available_only || !view.items.is_empty())
+            .collect();
+
+        Ok(MenuView {
+            branch_id: branch.map(|b| b.id),
+            categories,
+        })
+    }
+
+    /// Get the categories of the caller's account
+    pub async fn get_categories(&self, actor: &UserInfo) -> Result<Vec<category::Model>, ApiError> {
+        self.repository.get_categories(actor.parsed_account_id()?).await
+    }
+
+    /// Create a category in the caller's account
+    pub async fn create_category(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateCategoryRequest) -> Result<category::Model, ApiError> {
+        info!("Creating menu category: {}", data.name);
+
+        let category = self.repository.create_category(actor.parsed_account_id()?, data).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuCategoryCreated, (AuditTarget::MenuCategory, category.id), None, Some(&category)).await?;
+        Ok(category)
+    }
+
+    /// Update a category of the caller's account
+    pub async fn update_category(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateCategoryRequest) -> Result<category::Model, ApiError> {
+        info!("Updating menu category with ID: {}", id);
+
+        let before = self.get_owned_category(actor, id).await?;
+        let category = self.repository.update_category(id, data).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuCategoryUpdated, (AuditTarget::MenuCategory, id), Some(&before), Some(&category)).await?;
+        Ok(category)
+    }
+
+    /// Delete an empty category
+    pub async fn delete_category(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting menu category with ID: {}", id);
+
+        let before = self.get_owned_category(actor, id).await?;
+        if self.repository.count_items_in_category(id).await? > 0 {
+            return Err(ApiError::Conflict("Menu category still has items".to_string()));
+        }
+
+        let deleted = self.repository.soft_delete_category(id).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuCategoryDeleted, (AuditTarget::MenuCategory, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Get the items of the caller's account
+    pub async fn get_items(&self, actor: &UserInfo, query: ItemQuery) -> Result<Vec<item::Model>, ApiError> {
+        self.repository.get_items(actor.parsed_account_id()?, query.category_id).await
+    }
+
+    /// Get an item with its modifiers, priced at the session's active branch
+    pub async fn get_item(&self, actor: &UserInfo, id: Uuid) -> Result<ItemView, ApiError> {
+        let item = self.get_owned_item(actor, id).await?;
+        let branch_id = actor.parsed_active_branch_id()?;
+
+        let mut views = self.build_item_views(vec![item], branch_id).await?;
+        views.pop().ok_or_else(|| ApiError::NotFound("Menu item not found".to_string()))
+    }
+
+    /// Create an item in the caller's account
+    pub async fn create_item(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateItemRequest) -> Result<item::Model, ApiError> {
+        info!("Creating menu item: {}", data.name);
+
+        self.get_owned_category(actor, data.category_id).await?;
+        let item = self.repository.create_item(actor.parsed_account_id()?, data).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuItemCreated, (AuditTarget::MenuItem, item.id), None, Some(&item)).await?;
+        Ok(item)
+    }
+
+    /// Update an item of the caller's account
+    pub async fn update_item(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateItemRequest) -> Result<item::Model, ApiError> {
+        info!("Updating menu item with ID: {}", id);
+
+        let before = self.get_owned_item(actor, id).await?;
+        if let Some(category_id) = data.category_id {
+            self.get_owned_category(actor, category_id).await?;
+        }
+
+        let item = self.repository.update_item(id, data).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuItemUpdated, (AuditTarget::MenuItem, id), Some(&before), Some(&item)).await?;
+        Ok(item)
+    }
+
+    /// Delete an item (soft delete)
+    pub async fn delete_item(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting menu item with ID: {}", id);
+
+        let before = self.get_owned_item(actor, id).await?;
+        let deleted = self.repository.soft_delete_item(id).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuItemDeleted, (AuditTarget::MenuItem, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Override the price of an item at a branch of the same account
+    pub async fn set_price(&self, ctx: &RequestContext, actor: &UserInfo, item_id: Uuid, branch_id: Uuid, price: Decimal) -> Result<item_price::Model, ApiError> {
+        info!("Setting price of menu item {} at branch {}", item_id, branch_id);
+
+        let item = self.get_owned_item(actor, item_id).await?;
+        self.get_branch(item.account_id, branch_id).await?;
+
+        let before = self.repository.get_price(item_id, branch_id).await?;
+        let price = self.repository.set_price(item_id, branch_id, price).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuItemPriceSet, (AuditTarget::MenuItem, item_id), before.as_ref(), Some(&price)).await?;
+        Ok(price)
+    }
+
+    /// Go back to the base price of an item at a branch
+    pub async fn remove_price(&self, ctx: &RequestContext, actor: &UserInfo, item_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        info!("Removing price of menu item {} at branch {}", item_id, branch_id);
+
+        self.get_owned_item(actor, item_id).await?;
+        let before = self.repository
+            .get_price(item_id, branch_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("Branch price not found".to_string()))?;
+
+        self.repository.remove_price(item_id, branch_id).await?;
+
+        self.audit::<item_price::Model>(ctx, actor.parsed_account_id()?, AuditAction::MenuItemPriceRemoved, (AuditTarget::MenuItem, item_id), Some(&before), None).await?;
+        Ok(())
+    }
+
+    /// Attach a modifier group to an item
+    pub async fn create_modifier_group(&self, ctx: &RequestContext, actor: &UserInfo, item_id: Uuid, data: CreateModifierGroupRequest) -> Result<ModifierGroupView, ApiError> {
+        info!("Creating modifier group {} for menu item {}", data.name, item_id);
+
+        self.get_owned_item(actor, item_id).await?;
+        Self::check_selection(data.min_select, data.max_select, data.options.len())?;
+
+        let group = self.repository.create_modifier_group(item_id, data).await?;
+        let view = self.get_group_view(group).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuModifierGroupCreated, (AuditTarget::MenuModifierGroup, view.group.id), None, Some(&view)).await?;
+        Ok(view)
+    }
+
+    /// Update a modifier group, replacing its options when given
+    pub async fn update_modifier_group(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateModifierGroupRequest) -> Result<ModifierGroupView, ApiError> {
+        info!("Updating modifier group with ID: {}", id);
+
+        let before = self.get_owned_group(actor, id).await?;
+        Self::check_selection(
+            data.min_select.unwrap_or(before.group.min_select),
+            data.max_select.unwrap_or(before.group.max_select),
+            data.options.as_ref().map_or(before.options.len(), Vec::len),
+        )?;
+
+        let group = self.repository.update_modifier_group(id, data).await?;
+        let view = self.get_group_view(group).await?;
+
+        self.audit(ctx, actor.parsed_account_id()?, AuditAction::MenuModifierGroupUpdated, (AuditTarget::MenuModifierGroup, id), Some(&before), Some(&view)).await?;
+        Ok(view)
+    }
+
+    /// Remove a modifier group and its options
+    pub async fn delete_modifier_group(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting modifier group with ID: {}", id);
+
+        let before = self.get_owned_group(actor, id).await?;
+        self.repository.delete_modifier_group(id).await?;
+
+        self.audit::<ModifierGroupView>(ctx, actor.parsed_account_id()?, AuditAction::MenuModifierGroupDeleted, (AuditTarget::MenuModifierGroup, id), Some(&before), None).await?;
+        Ok(())
+    }
+
+    /// Attach branch prices, modifiers and whether they are sold out at the branch to items
+    pub async fn build_item_views(&self, items: Vec<item::Model>, branch_id: Option<Uuid>) -> Result<Vec<ItemView>, ApiError> {
+        let item_ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+
+        let sold_out: HashSet<Uuid> = match branch_id {
+            Some(branch_id) => self.repository.get_outages(branch_id).await?.into_iter().map(|outage| outage.item_id).collect(),
+            None => HashSet::new(),
+        };
+
+        let mut prices: HashMap<Uuid, Vec<item_price::Model>> = HashMap::new();
+        for price in self.repository.get_prices(item_ids.clone()).await? {
+            prices.entry(price.item_id).or_default().push(price);
+        }
+
+        let mut groups: HashMap<Uuid, Vec<ModifierGroupView>> = HashMap::new();
+        for view in self.get_group_views(self.repository.get_modifier_groups(item_ids).await?).await? {
+            groups.entry(view.group.item_id).or_default().push(view);
+        }
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let branch_prices = prices.remove(&item.id).unwrap_or_default();
+                let effective_price = branch_prices
+                    .iter()
+                    .find(|price| Some(price.branch_id) == branch_id)
+                    .map_or(item.price, |price| price.price);
+
+                ItemView {
+                    effective_price,
+                    branch_prices,
+                    sold_out: sold_out.contains(&item.id),
+                    modifier_groups: groups.remove(&item.id).unwrap_or_default(),
+                    item,
+                }
+            })
+            .collect())
+    }
+
+    /// An item of an account that can be ordered at a branch right now, with its price there
+    pub async fn get_orderable_item(&self, account_id: Uuid, branch: &Branch, id: Uuid) -> Result<ItemView, ApiError> {
+        let item = self.repository.get_item(id).await?;
+        if item.account_id != account_id {
+            return Err(ApiError::NotFound("Menu item not found".to_string()));
+        }
+
+        if !item.is_orderable_at(Self::local_now(Some(branch))) {
+            return Err(ApiError::Conflict(format!("{} is not available right now", item.name)));
+        }
+
+        let view = self.build_item_views(vec![item], Some(branch.id))
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::NotFound("Menu item not found".to_string()))?;
+
+        if view.sold_out {
+            return Err(ApiError::Conflict(format!("{} is sold out", view.item.name)));
+        }
+
+        Ok(view)
+    }
+
+    async fn get_group_views(&self, groups: Vec<modifier_group::Model>) -> Result<Vec<ModifierGroupView>, ApiError> {
+        let group_ids = groups.iter().map(|group| group.id).collect();
+
+        let mut options: HashMap<Uuid, Vec<_>> = HashMap::new();
+        for option in self.repository.get_modifier_options(group_ids).await? {
+            options.entry(option.group_id).or_default().push(option);
+        }
+
+        Ok(groups
+            .into_iter()
+            .map(|group| ModifierGroupView {
+                options: options.remove(&group.id).unwrap_or_default(),
+                group,
+            })
+            .collect())
+    }
+
+    async fn get_group_view(&self, group: modifier_group::Model) -> Result<ModifierGroupView, ApiError> {
+        let mut views = self.get_group_views(vec![group]).await?;
+        views.pop().ok_or_else(|| ApiError::NotFound("Modifier group not found".to_string()))
+    }
+
+    /// Fetch a category, hiding those of other accounts
+    async fn get_owned_category(&self, actor: &UserInfo, id: Uuid) -> Result<category::Model, ApiError> {
+        let category = self.repository.get_category(id).await?;
+
+        if category.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Menu category not found".to_string()));
+        }
+
+        Ok(category)
+    }
+
+    /// Fetch an item, hiding those of other accounts
+    async fn get_owned_item(&self, actor: &UserInfo, id: Uuid) -> Result<item::Model, ApiError> {
+        let item = self.repository.get_item(id).await?;
+
+        if item.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Menu item not found".to_string()));
+        }
+
+        Ok(item)
+    }
+
+    /// Fetch a modifier group through its item, hiding those of other accounts
+    async fn get_owned_group(&self, actor: &UserInfo, id: Uuid) -> Result<ModifierGroupView, ApiError> {
+        let group = self.repository.get_modifier_group(id).await?;
+
+        self.get_owned_item(actor, group.item_id).await.map_err(|e| match e {
+            ApiError::NotFound(_) => ApiError::NotFound("Modifier group not found".to_string()),
+            e => e,
+        })?;
+
+        self.get_group_view(group).await
+    }
+
+    /// Fetch a branch, hiding those of other accounts
+    async fn get_branch(&self, account_id: Uuid, branch_id: Uuid) -> Result<Branch, ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != account_id {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    fn check_selection(min_select: i32, max_select: i32, options: usize) -> Result<(), ApiError> {
+        if min_select > max_select {
+            return Err(ApiError::InvalidInput("Minimum selection cannot exceed the maximum".to_string()));
+        }
+
+        if max_select as usize > options {
+            return Err(ApiError::InvalidInput("Maximum selection cannot exceed the number of options".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Current wall-clock time at a branch, UTC without one
+    fn local_now(branch: Option<&Branch>) -> chrono::NaiveDateTime {
+        let now = chrono::Utc::now();
+        match branch {
+            Some(branch) => now.with_timezone(&branch.tz()).naive_local(),
+            None => now.naive_utc(),
+        }
+    }
+
+    /// Record a menu mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Uuid),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (target.0, Some(target.1)), before, after)
+            .await
+    }
+}
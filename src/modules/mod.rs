@@ -1,2 +1,23 @@
 pub mod user;
 pub mod auth;
+pub mod invitation;
+pub mod privacy;
+pub mod audit;
+pub mod account;
+pub mod branch;
+pub mod menu;
+pub mod table;
+pub mod order;
+pub mod station;
+pub mod realtime;
+pub mod payment;
+pub mod register;
+pub mod reservation;
+pub mod inventory;
+pub mod schedule;
+pub mod loyalty;
+pub mod tax;
+pub mod receipt;
+pub mod review;
+pub mod notification;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,262 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use tracing::{error, info};
+
+use crate::{
+    common::{config::{NotificationConfig, Secret}, mail::{Mail, SharedMailer}, ApiError},
+    modules::notification::{
+        entity::{outbox, Address, Channel},
+        repository::NotificationRepository,
+    },
+};
+
+/// Why a delivery did not go out
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum DeliveryError {
+    /// The receiving side could not be reached or failed; worth another attempt
+    #[error("{0}")]
+    Unavailable(String),
+    /// The address no longer exists, e.g. an expired push subscription; retrying will not help
+    #[error("{0}")]
+    Gone(String),
+}
+
+impl From<ApiError> for DeliveryError {
+    fn from(error: ApiError) -> Self {
+        DeliveryError::Unavailable(error.to_string())
+    }
+}
+
+/// Delivers notifications through one channel
+#[async_trait::async_trait]
+pub trait NotificationChannel: Send + Sync + std::fmt::Debug {
+    fn channel(&self) -> Channel;
+
+    /// Send one delivery from the outbox; called again for the same delivery when an earlier attempt failed
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError>;
+}
+
+pub type SharedChannel = Arc<dyn NotificationChannel>;
+
+/// Build the channels the worker delivers through
+pub fn create_channels(
+    repository: NotificationRepository,
+    mailer: SharedMailer,
+    config: &NotificationConfig,
+) -> HashMap<Channel, SharedChannel> {
+    let timeout = Duration::from_secs(config.timeout_seconds);
+    let push: SharedChannel = match config.push_provider.as_str() {
+        "http" => Arc::new(HttpPushChannel::new(&config.push_url, config.push_api_key.expose(), timeout)),
+        _ => Arc::new(LogPushChannel),
+    };
+    let channels: [SharedChannel; 4] = [
+        Arc::new(InAppChannel::new(repository)),
+        Arc::new(EmailChannel::new(mailer)),
+        push,
+        Arc::new(WebhookChannel::new(timeout)),
+    ];
+
+    channels.into_iter().map(|channel| (channel.channel(), channel)).collect()
+}
+
+fn unexpected(delivery: &outbox::Model) -> DeliveryError {
+    DeliveryError::Gone(format!("Address of delivery {} does not fit its channel", delivery.id))
+}
+
+/// Stores notifications in the user's inbox
+#[derive(Debug)]
+pub struct InAppChannel {
+    repository: NotificationRepository,
+}
+
+impl InAppChannel {
+    pub fn new(repository: NotificationRepository) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for InAppChannel {
+    fn channel(&self) -> Channel {
+        Channel::InApp
+    }
+
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError> {
+        let Address::Inbox { user_id } = delivery.address else {
+            return Err(unexpected(delivery));
+        };
+        self.repository.insert_inbox(delivery, user_id).await?;
+        Ok(())
+    }
+}
+
+/// Sends notifications as plain text email through the configured mailer
+#[derive(Debug)]
+pub struct EmailChannel {
+    mailer: SharedMailer,
+}
+
+impl EmailChannel {
+    pub fn new(mailer: SharedMailer) -> Self {
+        Self { mailer }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for EmailChannel {
+    fn channel(&self) -> Channel {
+        Channel::Email
+    }
+
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError> {
+        let Address::Email { to } = &delivery.address else {
+            return Err(unexpected(delivery));
+        };
+
+        let mail = Mail {
+            to: to.clone(),
+            subject: delivery.title.clone(),
+            text: delivery.body.clone(),
+            attachments: Vec::new(),
+        };
+        self.mailer.send(&mail).await?;
+        Ok(())
+    }
+}
+
+/// Sends web push messages through a push gateway exposing `POST /messages`, which holds the VAPID keys
+#[derive(Debug)]
+pub struct HttpPushChannel {
+    client: reqwest::Client,
+    url: String,
+    api_key: Secret<String>,
+}
+
+impl HttpPushChannel {
+    pub fn new(base_url: &str, api_key: &str, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(timeout).build().unwrap_or_default(),
+            url: format!("{}/messages", base_url.trim_end_matches('/')),
+            api_key: Secret::new(api_key.to_string()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for HttpPushChannel {
+    fn channel(&self) -> Channel {
+        Channel::Push
+    }
+
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError> {
+        let Address::Push { endpoint, p256dh, auth } = &delivery.address else {
+            return Err(unexpected(delivery));
+        };
+        info!("Sending push notification {} through push gateway {}", delivery.id, self.url);
+
+        let body = serde_json::json!({
+            "subscription": {
+                "endpoint": endpoint,
+                "keys": { "p256dh": p256dh, "auth": auth },
+            },
+            "payload": {
+                "id": delivery.id,
+                "kind": delivery.kind,
+                "title": delivery.title,
+                "body": delivery.body,
+                "data": delivery.data,
+            },
+        });
+
+        let response = self.client
+            .post(&self.url)
+            .bearer_auth(self.api_key.expose())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Push gateway {} unreachable: {}", self.url, e);
+                DeliveryError::Unavailable(e.to_string())
+            })?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            // The push service forgot the subscription, e.g. the user revoked the permission
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => {
+                Err(DeliveryError::Gone("Push subscription expired".to_string()))
+            }
+            status => Err(DeliveryError::Unavailable(format!("Push gateway answered {}", status))),
+        }
+    }
+}
+
+/// Logs push messages instead of sending them, for development and tests
+#[derive(Debug, Default)]
+pub struct LogPushChannel;
+
+#[async_trait::async_trait]
+impl NotificationChannel for LogPushChannel {
+    fn channel(&self) -> Channel {
+        Channel::Push
+    }
+
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError> {
+        let Address::Push { endpoint, .. } = &delivery.address else {
+            return Err(unexpected(delivery));
+        };
+        info!("Push to {}: \"{}\" (not sent)", endpoint, delivery.title);
+        Ok(())
+    }
+}
+
+/// Posts notifications as JSON to a URL the user registered
+#[derive(Debug)]
+pub struct WebhookChannel {
+    client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(timeout).build().unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn channel(&self) -> Channel {
+        Channel::Webhook
+    }
+
+    async fn deliver(&self, delivery: &outbox::Model) -> Result<(), DeliveryError> {
+        let Address::Webhook { url } = &delivery.address else {
+            return Err(unexpected(delivery));
+        };
+        info!("Posting notification {} to webhook {}", delivery.id, url);
+
+        let body = serde_json::json!({
+            "id": delivery.id,
+            "kind": delivery.kind,
+            "title": delivery.title,
+            "body": delivery.body,
+            "data": delivery.data,
+            "created_at": delivery.created_at,
+        });
+
+        let response = self.client
+            .post(url)
+            // Lets receivers drop a delivery they already got from an earlier attempt
+            .header("Idempotency-Key", delivery.id.to_string())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Unavailable(e.to_string()))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::GONE => Err(DeliveryError::Gone("Webhook answered 410".to_string())),
+            status => Err(DeliveryError::Unavailable(format!("Webhook answered {}", status))),
+        }
+    }
+}
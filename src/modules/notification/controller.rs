@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::notification::entity::{
+        target, CreateTargetRequest, Model as Notification, NotificationQuery, PreferenceView, UnreadCount, UpdatePreferenceRequest,
+    },
+    common::{AppState, session::SessionUser},
+};
+
+/// Inbox of the logged-in user
+pub async fn get_all(
+    Query(query): Query<NotificationQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Notification>>, ApiError> {
+    info!("Fetching notifications of user {}", user.id);
+    let result = state.notification_service.get_inbox(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Number of unread notifications of the logged-in user
+pub async fn get_unread_count(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<UnreadCount>, ApiError> {
+    let result = state.notification_service.unread_count(&user).await?;
+    Ok(Json(result))
+}
+
+/// Mark a notification read
+pub async fn mark_read(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Notification>, ApiError> {
+    info!("Marking notification {} read", id);
+    let result = state.notification_service.mark_read(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Mark every notification of the logged-in user read
+pub async fn mark_all_read(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Marking all notifications of user {} read", user.id);
+    state.notification_service.mark_all_read(&user).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Channels of every kind of notification
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<PreferenceView>>, ApiError> {
+    let result = state.notification_service.get_preferences(&user).await?;
+    Ok(Json(result))
+}
+
+/// Choose the channels of a kind of notification
+pub async fn update_preference(
+    Path(kind): Path<String>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdatePreferenceRequest>,
+) -> Result<Json<PreferenceView>, ApiError> {
+    info!("Updating {} notification channels of user {}", kind, user.id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.notification_service.set_preference(&user, &kind, payload).await?;
+    Ok(Json(result))
+}
+
+/// Push subscriptions and webhooks of the logged-in user
+pub async fn get_targets(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<target::Model>>, ApiError> {
+    let result = state.notification_service.get_targets(&user).await?;
+    Ok(Json(result))
+}
+
+/// Register a push subscription or webhook
+pub async fn create_target(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateTargetRequest>,
+) -> Result<(StatusCode, Json<target::Model>), ApiError> {
+    info!("Registering notification target for user {}", user.id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.notification_service.create_target(&user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Remove a push subscription or webhook
+pub async fn delete_target(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Removing notification target {}", id);
+    state.notification_service.delete_target(&user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
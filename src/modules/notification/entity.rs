@@ -0,0 +1,356 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// A notification in a user's in-app inbox
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "notifications")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    /// Values the notification was rendered from, e.g. the order ID
+    #[sea_orm(column_type = "JsonBinary")]
+    pub data: Json,
+    pub read_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Notification", 9)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("user_id", &self.user_id)?;
+        state.serialize_field("kind", &self.kind)?;
+        state.serialize_field("title", &self.title)?;
+        state.serialize_field("body", &self.body)?;
+        state.serialize_field("data", &self.data)?;
+        state.serialize_field("read_at", &self.read_at)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Channels a user chose for one kind of notification
+pub mod preference {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+    #[sea_orm(table_name = "notification_preferences")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub user_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub kind: String,
+        pub channels: Vec<String>,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// A push subscription or webhook URL of a user
+pub mod target {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "notification_targets")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub user_id: Uuid,
+        pub channel: String,
+        pub endpoint: String,
+        /// Web push subscription keys
+        pub p256dh: Option<String>,
+        pub auth: Option<String>,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            // The subscription keys stay on the server
+            let mut state = serializer.serialize_struct("NotificationTarget", 5)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("user_id", &self.user_id)?;
+            state.serialize_field("channel", &self.channel)?;
+            state.serialize_field("endpoint", &self.endpoint)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// A delivery waiting in the outbox, sent or given up on
+pub mod outbox {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::Address;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+    #[sea_orm(table_name = "notification_outbox")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        /// Empty for messages to an address that is not a user yet, e.g. invitations
+        pub user_id: Option<Uuid>,
+        pub channel: String,
+        #[sea_orm(column_type = "JsonBinary")]
+        pub address: Address,
+        pub kind: String,
+        pub title: String,
+        pub body: String,
+        #[sea_orm(column_type = "JsonBinary")]
+        pub data: Json,
+        pub status: String,
+        pub attempts: i32,
+        pub next_attempt_at: DateTimeWithTimeZone,
+        pub last_error: Option<String>,
+        pub sent_at: Option<DateTimeWithTimeZone>,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Where a delivery goes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Address {
+    /// The inbox of a user
+    Inbox { user_id: Uuid },
+    Email { to: String },
+    Push { endpoint: String, p256dh: Option<String>, auth: Option<String> },
+    Webhook { url: String },
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    /// An order is ready to be served
+    OrderReady,
+    ReservationConfirmed,
+    /// A stock item fell below its threshold
+    LowStock,
+    /// Someone was invited to join an account
+    Invitation,
+    /// A webhook was turned off after failing too often
+    WebhookDisabled,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 5] = [
+        NotificationKind::OrderReady,
+        NotificationKind::ReservationConfirmed,
+        NotificationKind::LowStock,
+        NotificationKind::Invitation,
+        NotificationKind::WebhookDisabled,
+    ];
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationKind::OrderReady => write!(f, "order.ready"),
+            NotificationKind::ReservationConfirmed => write!(f, "reservation.confirmed"),
+            NotificationKind::LowStock => write!(f, "inventory.low_stock"),
+            NotificationKind::Invitation => write!(f, "invitation.created"),
+            NotificationKind::WebhookDisabled => write!(f, "webhook.disabled"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotificationKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| format!("Notification kind {} is not valid", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    InApp,
+    Email,
+    Push,
+    Webhook,
+}
+
+impl std::fmt::Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Channel::InApp => write!(f, "IN_APP"),
+            Channel::Email => write!(f, "EMAIL"),
+            Channel::Push => write!(f, "PUSH"),
+            Channel::Webhook => write!(f, "WEBHOOK"),
+        }
+    }
+}
+
+impl std::str::FromStr for Channel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "IN_APP" => Ok(Channel::InApp),
+            "EMAIL" => Ok(Channel::Email),
+            "PUSH" => Ok(Channel::Push),
+            "WEBHOOK" => Ok(Channel::Webhook),
+            _ => Err(format!("Notification channel {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Sent,
+    /// Given up after the last attempt
+    Failed,
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "PENDING"),
+            DeliveryStatus::Sent => write!(f, "SENT"),
+            DeliveryStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
+
+// Validators
+fn validate_channels(channels: &[String]) -> Result<(), ValidationError> {
+    match channels.iter().find(|c| c.parse::<Channel>().is_err()) {
+        Some(c) => Err(ValidationError::new("channels").with_message(format!("Notification channel {} is not valid", c).into())),
+        None => Ok(()),
+    }
+}
+
+fn validate_target_channel(channel: &str) -> Result<(), ValidationError> {
+    match channel.parse::<Channel>() {
+        Ok(Channel::Push) | Ok(Channel::Webhook) => Ok(()),
+        _ => Err(ValidationError::new("channel").with_message("Channel must be PUSH or WEBHOOK".into())),
+    }
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Default)]
+pub struct NotificationQuery {
+    /// Only notifications not read yet
+    #[serde(default)]
+    pub unread: bool,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreadCount {
+    pub unread: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdatePreferenceRequest {
+    /// Channels to deliver this kind through; empty to mute it
+    #[validate(custom(function = "validate_channels"))]
+    pub channels: Vec<String>,
+}
+
+/// The channels a user receives one kind of notification through
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceView {
+    pub kind: String,
+    pub channels: Vec<String>,
+    /// Whether these are the defaults rather than the user's choice
+    pub default: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateTargetRequest {
+    #[validate(custom(function = "validate_target_channel"))]
+    pub channel: String,
+
+    /// Push service endpoint of the subscription or the webhook URL
+    #[validate(url(message = "Endpoint must be a URL"), length(max = 2000, message = "Endpoint must be at most 2000 characters"))]
+    pub endpoint: String,
+
+    #[validate(length(max = 200, message = "p256dh must be at most 200 characters"))]
+    pub p256dh: Option<String>,
+
+    #[validate(length(max = 100, message = "auth must be at most 100 characters"))]
+    pub auth: Option<String>,
+}
+
+/// Who a notification is for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    /// A user, reached through the channels they prefer
+    User(Uuid),
+    /// An address that is not a user yet, reached by email only
+    Email(String),
+}
+
+/// A notification about to be queued
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub account_id: Uuid,
+    pub recipient: Recipient,
+    pub kind: NotificationKind,
+    /// Values the template is rendered with
+    pub data: serde_json::Value,
+}
+
+/// A delivery about to be queued
+#[derive(Debug, Clone)]
+pub struct NewDelivery {
+    pub account_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub channel: Channel,
+    pub address: Address,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    pub data: serde_json::Value,
+}
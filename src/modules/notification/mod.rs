@@ -0,0 +1,7 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod repository;
+pub mod channel;
+pub mod template;
+pub mod route;
\ No newline at end of file
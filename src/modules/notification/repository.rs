@@ -0,0 +1,403 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType, OnConflict}, ColumnTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::notification::entity::{
+        outbox, preference, target, ActiveModel, Address, Column, CreateTargetRequest, DeliveryStatus, Entity as NotificationEntity,
+        Model as Notification, NewDelivery,
+    },
+    common::ApiError,
+};
+
+/// Notification repository for database operations
+#[derive(Debug, Clone)]
+pub struct NotificationRepository {
+    db: DatabaseConnection,
+}
+
+impl NotificationRepository {
+    /// Create a new notification repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search the inbox of a user, newest first, returning one page and the total count
+    pub async fn search(&self, user_id: Uuid, unread: bool, page: u64, per_page: u64) -> Result<(Vec<Notification>, u64), ApiError> {
+        let mut select = NotificationEntity::find().filter(Column::UserId.eq(user_id));
+        if unread {
+            select = select.filter(Column::ReadAt.is_null());
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count notifications of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let notifications = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch notifications of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((notifications, total))
+    }
+
+    /// Get the whole inbox of a user, newest first
+    pub async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<Notification>, ApiError> {
+        NotificationEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch notifications of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Delete the inbox, preferences, targets and queued deliveries of a user
+    pub async fn delete_by_user_id(&self, user_id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting notification data of user {}", user_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start notification deletion transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let map_err = |e: sea_orm::DbErr| {
+            error!("Failed to delete notification data of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        };
+        NotificationEntity::delete_many().filter(Column::UserId.eq(user_id)).exec(&txn).await.map_err(map_err)?;
+        preference::Entity::delete_many().filter(preference::Column::UserId.eq(user_id)).exec(&txn).await.map_err(map_err)?;
+        target::Entity::delete_many().filter(target::Column::UserId.eq(user_id)).exec(&txn).await.map_err(map_err)?;
+        outbox::Entity::delete_many().filter(outbox::Column::UserId.eq(user_id)).exec(&txn).await.map_err(map_err)?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit deletion of notification data of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Count the notifications a user has not read
+    pub async fn count_unread(&self, user_id: Uuid) -> Result<u64, ApiError> {
+        NotificationEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ReadAt.is_null())
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count unread notifications of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a notification by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Notification, ApiError> {
+        let notification = NotificationEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch notification with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match notification {
+            Some(notification) => Ok(notification),
+            None => Err(ApiError::NotFound("Notification not found".to_string())),
+        }
+    }
+
+    /// Mark notifications of a user read, all of them without `id`; returns how many were unread
+    pub async fn mark_read(&self, user_id: Uuid, id: Option<Uuid>) -> Result<u64, ApiError> {
+        let mut update = NotificationEntity::update_many()
+            .col_expr(Column::ReadAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ReadAt.is_null());
+        if let Some(id) = id {
+            update = update.filter(Column::Id.eq(id));
+        }
+
+        let result = update.exec(&self.db).await.map_err(|e| {
+            error!("Failed to mark notifications of user {} read: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Put a delivery into the user's inbox; retrying the same delivery adds nothing
+    pub async fn insert_inbox(&self, delivery: &outbox::Model, user_id: Uuid) -> Result<(), ApiError> {
+        let model = ActiveModel {
+            id: Set(delivery.id),
+            account_id: Set(delivery.account_id),
+            user_id: Set(user_id),
+            kind: Set(delivery.kind.clone()),
+            title: Set(delivery.title.clone()),
+            body: Set(delivery.body.clone()),
+            data: Set(delivery.data.clone()),
+            read_at: Set(None),
+            created_at: Set(delivery.created_at),
+        };
+
+        NotificationEntity::insert(model)
+            .on_conflict(OnConflict::column(Column::Id).do_nothing().to_owned())
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to store notification {} in the inbox: {}", delivery.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the channels a user chose per kind
+    pub async fn get_preferences(&self, user_id: Uuid) -> Result<Vec<preference::Model>, ApiError> {
+        preference::Entity::find()
+            .filter(preference::Column::UserId.eq(user_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch notification preferences of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Set the channels a user receives a kind of notification through
+    pub async fn set_preference(&self, user_id: Uuid, kind: &str, channels: Vec<String>) -> Result<(), ApiError> {
+        info!("Setting {} notification channels of user {}", kind, user_id);
+
+        let model = preference::ActiveModel {
+            user_id: Set(user_id),
+            kind: Set(kind.to_string()),
+            channels: Set(channels),
+            updated_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        preference::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([preference::Column::UserId, preference::Column::Kind])
+                    .update_columns([preference::Column::Channels, preference::Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set notification preference of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the push subscriptions and webhooks of a user
+    pub async fn get_targets(&self, user_id: Uuid) -> Result<Vec<target::Model>, ApiError> {
+        target::Entity::find()
+            .filter(target::Column::UserId.eq(user_id))
+            .order_by_asc(target::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch notification targets of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Register a push subscription or webhook; registering the same endpoint again keeps the newest keys
+    pub async fn create_target(&self, user_id: Uuid, data: CreateTargetRequest) -> Result<target::Model, ApiError> {
+        info!("Registering {} target for user {}", data.channel, user_id);
+
+        let model = target::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            channel: Set(data.channel.clone()),
+            endpoint: Set(data.endpoint.clone()),
+            p256dh: Set(data.p256dh),
+            auth: Set(data.auth),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        target::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns([target::Column::UserId, target::Column::Channel, target::Column::Endpoint])
+                    .update_columns([target::Column::P256dh, target::Column::Auth])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to register notification target of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        target::Entity::find()
+            .filter(target::Column::UserId.eq(user_id))
+            .filter(target::Column::Channel.eq(data.channel))
+            .filter(target::Column::Endpoint.eq(data.endpoint))
+            .one(&self.db)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| ApiError::DatabaseError("Notification target was not stored".to_string()))
+    }
+
+    /// Remove a push subscription or webhook of a user
+    pub async fn delete_target(&self, user_id: Uuid, id: Uuid) -> Result<(), ApiError> {
+        info!("Removing notification target {} of user {}", id, user_id);
+
+        let result = target::Entity::delete_many()
+            .filter(target::Column::Id.eq(id))
+            .filter(target::Column::UserId.eq(user_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove notification target {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("Notification target not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Queue deliveries for the worker
+    pub async fn enqueue(&self, deliveries: Vec<NewDelivery>) -> Result<(), ApiError> {
+        if deliveries.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now().fixed_offset();
+        let models = deliveries.into_iter().map(|delivery| outbox::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(delivery.account_id),
+            user_id: Set(delivery.user_id),
+            channel: Set(delivery.channel.to_string()),
+            address: Set(delivery.address),
+            kind: Set(delivery.kind.to_string()),
+            title: Set(delivery.title),
+            body: Set(delivery.body),
+            data: Set(delivery.data),
+            status: Set(DeliveryStatus::Pending.to_string()),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            last_error: Set(None),
+            sent_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        });
+
+        outbox::Entity::insert_many(models)
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to queue notifications: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Take up to `limit` due deliveries, pushing their next attempt back by `lease` so other workers skip them
+    /// while they are sent. Rows another worker is claiming at the same moment are skipped, not waited for.
+    pub async fn claim_due(&self, limit: u64, lease: chrono::Duration) -> Result<Vec<outbox::Model>, ApiError> {
+        let txn = self.db.begin().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        let now = chrono::Utc::now().fixed_offset();
+
+        let due = outbox::Entity::find()
+            .filter(outbox::Column::Status.eq(DeliveryStatus::Pending.to_string()))
+            .filter(outbox::Column::NextAttemptAt.lte(now))
+            .order_by_asc(outbox::Column::NextAttemptAt)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to claim due notifications: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if !due.is_empty() {
+            outbox::Entity::update_many()
+                .col_expr(outbox::Column::NextAttemptAt, Expr::value(now + lease))
+                .filter(outbox::Column::Id.is_in(due.iter().map(|delivery| delivery.id)))
+                .exec(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to lease due notifications: {}", e);
+                    ApiError::DatabaseError(e.to_string())
+                })?;
+        }
+
+        txn.commit().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        Ok(due)
+    }
+
+    /// Record a delivery attempt: sent, due again at `retry_at`, or failed for good without it
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        attempts: i32,
+        error: Option<String>,
+        retry_at: Option<DateTimeWithTimeZone>,
+    ) -> Result<(), ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        let status = match (&error, retry_at) {
+            (None, _) => DeliveryStatus::Sent,
+            (Some(_), Some(_)) => DeliveryStatus::Pending,
+            (Some(_), None) => DeliveryStatus::Failed,
+        };
+
+        let mut update = outbox::Entity::update_many()
+            .col_expr(outbox::Column::Status, Expr::value(status.to_string()))
+            .col_expr(outbox::Column::Attempts, Expr::value(attempts))
+            .col_expr(outbox::Column::LastError, Expr::value(error));
+        update = match (status, retry_at) {
+            (DeliveryStatus::Sent, _) => update.col_expr(outbox::Column::SentAt, Expr::value(now)),
+            (_, Some(retry_at)) => update.col_expr(outbox::Column::NextAttemptAt, Expr::value(retry_at)),
+            _ => update,
+        };
+
+        update
+            .filter(outbox::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record attempt of notification {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Forget a push subscription or webhook the receiving side no longer knows
+    pub async fn delete_target_by_address(&self, address: &Address) -> Result<(), ApiError> {
+        let (channel, endpoint) = match address {
+            Address::Push { endpoint, .. } => ("PUSH", endpoint),
+            Address::Webhook { url } => ("WEBHOOK", url),
+            _ => return Ok(()),
+        };
+
+        target::Entity::delete_many()
+            .filter(target::Column::Channel.eq(channel))
+            .filter(target::Column::Endpoint.eq(endpoint.as_str()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove stale notification target: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+
+use crate::common::AppState;
+
+use super::controller::*;
+
+/// Create notification routes for the logged-in user
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/notifications", get(get_all))
+        .route("/notifications/unread-count", get(get_unread_count))
+        .route("/notifications/read-all", post(mark_all_read))
+        .route("/notifications/:id/read", post(mark_read))
+        .route("/notifications/preferences", get(get_preferences))
+        .route("/notifications/preferences/:kind", put(update_preference))
+        .route("/notifications/targets", get(get_targets).post(create_target))
+        .route("/notifications/targets/:id", delete(delete_target))
+}
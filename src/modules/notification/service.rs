@@ -0,0 +1,332 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::{error, info, warn};
+
+use crate::{
+    common::{
+        config::NotificationConfig,
+        mail::SharedMailer,
+        pagination::{self, Page},
+        ApiError,
+    },
+    modules::{
+        auth::entity::UserInfo,
+        notification::{
+            channel::{create_channels, DeliveryError, SharedChannel},
+            entity::{
+                outbox, target, Address, Channel, CreateTargetRequest, Model as Notification, NewDelivery, NewNotification,
+                NotificationKind, NotificationQuery, PreferenceView, Recipient, UnreadCount, UpdatePreferenceRequest,
+            },
+            repository::NotificationRepository,
+            template::{render, template},
+        },
+        user::{
+            entity::{Model as User, UserRole, UserStatus},
+            repository::UserRepository,
+        },
+    },
+};
+
+/// Notification service layer for business logic
+#[derive(Debug, Clone)]
+pub struct NotificationService {
+    repository: NotificationRepository,
+    user_repository: UserRepository,
+    channels: Arc<HashMap<Channel, SharedChannel>>,
+    config: NotificationConfig,
+}
+
+impl NotificationService {
+    /// Create a new notification service
+    pub fn new(
+        repository: NotificationRepository,
+        user_repository: UserRepository,
+        mailer: SharedMailer,
+        config: &NotificationConfig,
+    ) -> Self {
+        let channels = create_channels(repository.clone(), mailer, config);
+        Self { repository, user_repository, channels: Arc::new(channels), config: config.clone() }
+    }
+
+    /// Queue a notification for the worker. Notifying is best effort: a failure is logged and never fails the
+    /// operation that caused it.
+    pub async fn notify(&self, notification: NewNotification) {
+        let kind = notification.kind;
+        if let Err(e) = self.enqueue(notification).await {
+            error!("Failed to queue {} notification: {}", kind, e);
+        }
+    }
+
+    /// Notify the managers working at a branch and the account's administrators
+    pub async fn notify_branch_managers(&self, account_id: Uuid, branch_id: Uuid, kind: NotificationKind, data: serde_json::Value) {
+        let managers = match self.branch_managers(account_id, branch_id).await {
+            Ok(managers) => managers,
+            Err(e) => {
+                error!("Failed to find managers of branch {} to notify: {}", branch_id, e);
+                return;
+            }
+        };
+
+        for manager in managers {
+            self.notify(NewNotification { account_id, recipient: Recipient::User(manager), kind, data: data.clone() }).await;
+        }
+    }
+
+    /// Notify the active administrators of an account
+    pub async fn notify_account_admins(&self, account_id: Uuid, kind: NotificationKind, data: serde_json::Value) {
+        let admins = match self.user_repository.get_by_account_id(account_id).await {
+            Ok(users) => users,
+            Err(e) => {
+                error!("Failed to find administrators of account {} to notify: {}", account_id, e);
+                return;
+            }
+        };
+
+        for admin in admins {
+            if admin.status == UserStatus::Active.to_string() && admin.role.parse::<UserRole>().is_ok_and(|r| r.is_admin()) {
+                self.notify(NewNotification { account_id, recipient: Recipient::User(admin.id), kind, data: data.clone() }).await;
+            }
+        }
+    }
+
+    /// Inbox of the logged-in user, newest first
+    pub async fn get_inbox(&self, actor: &UserInfo, query: NotificationQuery) -> Result<Page<Notification>, ApiError> {
+        let user_id = actor.parsed_id()?;
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(user_id, query.unread, page, per_page).await?;
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Number of unread notifications of the logged-in user
+    pub async fn unread_count(&self, actor: &UserInfo) -> Result<UnreadCount, ApiError> {
+        let unread = self.repository.count_unread(actor.parsed_id()?).await?;
+        Ok(UnreadCount { unread })
+    }
+
+    /// Mark one notification of the logged-in user read
+    pub async fn mark_read(&self, actor: &UserInfo, id: Uuid) -> Result<Notification, ApiError> {
+        let user_id = actor.parsed_id()?;
+        let notification = self.repository.get_by_id(id).await?;
+        if notification.user_id != user_id {
+            return Err(ApiError::NotFound("Notification not found".to_string()));
+        }
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+
+        self.repository.mark_read(user_id, Some(id)).await?;
+        self.repository.get_by_id(id).await
+    }
+
+    /// Mark every notification of the logged-in user read
+    pub async fn mark_all_read(&self, actor: &UserInfo) -> Result<(), ApiError> {
+        let user_id = actor.parsed_id()?;
+        let count = self.repository.mark_read(user_id, None).await?;
+        info!("Marked {} notifications of user {} read", count, user_id);
+        Ok(())
+    }
+
+    /// Channels of every kind of notification for the logged-in user
+    pub async fn get_preferences(&self, actor: &UserInfo) -> Result<Vec<PreferenceView>, ApiError> {
+        let chosen: HashMap<String, Vec<String>> = self.repository
+            .get_preferences(actor.parsed_id()?)
+            .await?
+            .into_iter()
+            .map(|preference| (preference.kind, preference.channels))
+            .collect();
+
+        Ok(NotificationKind::ALL
+            .into_iter()
+            .map(|kind| match chosen.get(&kind.to_string()) {
+                Some(channels) => PreferenceView { kind: kind.to_string(), channels: channels.clone(), default: false },
+                None => PreferenceView {
+                    kind: kind.to_string(),
+                    channels: template(kind).channels.iter().map(|c| c.to_string()).collect(),
+                    default: true,
+                },
+            })
+            .collect())
+    }
+
+    /// Choose the channels the logged-in user receives a kind of notification through
+    pub async fn set_preference(&self, actor: &UserInfo, kind: &str, data: UpdatePreferenceRequest) -> Result<PreferenceView, ApiError> {
+        let kind = kind.parse::<NotificationKind>().map_err(ApiError::InvalidInput)?;
+
+        let mut channels: Vec<String> = Vec::new();
+        for channel in data.channels {
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+
+        self.repository.set_preference(actor.parsed_id()?, &kind.to_string(), channels.clone()).await?;
+        Ok(PreferenceView { kind: kind.to_string(), channels, default: false })
+    }
+
+    /// Push subscriptions and webhooks of the logged-in user
+    pub async fn get_targets(&self, actor: &UserInfo) -> Result<Vec<target::Model>, ApiError> {
+        self.repository.get_targets(actor.parsed_id()?).await
+    }
+
+    /// Register a push subscription or webhook for the logged-in user
+    pub async fn create_target(&self, actor: &UserInfo, data: CreateTargetRequest) -> Result<target::Model, ApiError> {
+        if !data.endpoint.starts_with("https://") {
+            return Err(ApiError::InvalidInput("Endpoint must be an https URL".to_string()));
+        }
+        if data.channel == Channel::Push.to_string() && (data.p256dh.is_none() || data.auth.is_none()) {
+            return Err(ApiError::InvalidInput("Push subscriptions need the p256dh and auth keys".to_string()));
+        }
+        self.repository.create_target(actor.parsed_id()?, data).await
+    }
+
+    /// Remove a push subscription or webhook of the logged-in user
+    pub async fn delete_target(&self, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        self.repository.delete_target(actor.parsed_id()?, id).await
+    }
+
+    /// Send the due deliveries of the outbox, returning how many were attempted
+    pub async fn process_due(&self) -> Result<usize, ApiError> {
+        // Long enough for the whole batch to time out one by one before another worker may pick it up
+        let lease = chrono::Duration::seconds((self.config.timeout_seconds * self.config.batch_size.max(1) + 60) as i64);
+        let due = self.repository.claim_due(self.config.batch_size, lease).await?;
+
+        for delivery in &due {
+            self.attempt(delivery).await?;
+        }
+        Ok(due.len())
+    }
+
+    /// Run `process_due` periodically in the background
+    pub fn spawn_worker(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.process_due().await {
+                    error!("Failed to process notification outbox: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn attempt(&self, delivery: &outbox::Model) -> Result<(), ApiError> {
+        let attempts = delivery.attempts + 1;
+        let result = match delivery.channel.parse::<Channel>().ok().and_then(|channel| self.channels.get(&channel)) {
+            Some(channel) => channel.deliver(delivery).await,
+            None => Err(DeliveryError::Gone(format!("No channel delivers {}", delivery.channel))),
+        };
+
+        match result {
+            Ok(()) => self.repository.record_attempt(delivery.id, attempts, None, None).await,
+            Err(DeliveryError::Gone(reason)) => {
+                warn!("Dropping notification {}: {}", delivery.id, reason);
+                self.repository.delete_target_by_address(&delivery.address).await?;
+                self.repository.record_attempt(delivery.id, attempts, Some(reason), None).await
+            }
+            Err(DeliveryError::Unavailable(reason)) => {
+                let retry_at = (attempts < self.config.max_attempts)
+                    .then(|| chrono::Utc::now().fixed_offset() + self.backoff(attempts));
+                match retry_at {
+                    Some(retry_at) => warn!("Notification {} failed, retrying at {}: {}", delivery.id, retry_at, reason),
+                    None => error!("Notification {} failed after {} attempts: {}", delivery.id, attempts, reason),
+                }
+                self.repository.record_attempt(delivery.id, attempts, Some(reason), retry_at).await
+            }
+        }
+    }
+
+    /// Wait before the next attempt, doubling with every failed one up to the configured maximum
+    fn backoff(&self, attempts: i32) -> chrono::Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1) as u32);
+        let seconds = self.config.backoff_seconds.saturating_mul(factor).min(self.config.max_backoff_seconds);
+        chrono::Duration::seconds(seconds as i64)
+    }
+
+    async fn enqueue(&self, notification: NewNotification) -> Result<(), ApiError> {
+        let template = template(notification.kind);
+        let title = render(template.title, &notification.data);
+        let body = render(template.body, &notification.data);
+        let delivery = |user_id: Option<Uuid>, channel: Channel, address: Address| NewDelivery {
+            account_id: notification.account_id,
+            user_id,
+            channel,
+            address,
+            kind: notification.kind,
+            title: title.clone(),
+            body: body.clone(),
+            data: notification.data.clone(),
+        };
+
+        let user_id = match notification.recipient {
+            Recipient::Email(ref to) => {
+                return self.repository.enqueue(vec![delivery(None, Channel::Email, Address::Email { to: to.clone() })]).await;
+            }
+            Recipient::User(user_id) => user_id,
+        };
+
+        let user = match self.user_repository.get_by_id(user_id).await {
+            Ok(user) => user,
+            Err(ApiError::NotFound(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        // Anonymized users have nobody left to reach
+        if user.anonymized_at.is_some() {
+            return Ok(());
+        }
+
+        let kind = notification.kind.to_string();
+        let channels: Vec<Channel> = match self.repository.get_preferences(user_id).await?.into_iter().find(|p| p.kind == kind) {
+            Some(preference) => preference.channels.iter().filter_map(|c| c.parse().ok()).collect(),
+            None => template.channels.to_vec(),
+        };
+        let needs_targets = channels.iter().any(|c| matches!(c, Channel::Push | Channel::Webhook));
+        let targets = if needs_targets { self.repository.get_targets(user_id).await? } else { Vec::new() };
+
+        let mut deliveries = Vec::new();
+        for channel in channels {
+            match channel {
+                Channel::InApp => deliveries.push(delivery(Some(user_id), channel, Address::Inbox { user_id })),
+                Channel::Email => deliveries.push(delivery(Some(user_id), channel, Address::Email { to: user.email.clone() })),
+                Channel::Push | Channel::Webhook => {
+                    for target in targets.iter().filter(|t| t.channel == channel.to_string()) {
+                        let address = match channel {
+                            Channel::Push => Address::Push {
+                                endpoint: target.endpoint.clone(),
+                                p256dh: target.p256dh.clone(),
+                                auth: target.auth.clone(),
+                            },
+                            _ => Address::Webhook { url: target.endpoint.clone() },
+                        };
+                        deliveries.push(delivery(Some(user_id), channel, address));
+                    }
+                }
+            }
+        }
+
+        info!("Queueing {} deliveries of {} notification for user {}", deliveries.len(), kind, user_id);
+        self.repository.enqueue(deliveries).await
+    }
+
+    /// Active managers of a branch and the account's administrators
+    async fn branch_managers(&self, account_id: Uuid, branch_id: Uuid) -> Result<Vec<Uuid>, ApiError> {
+        let is_active = |user: &User| user.status == UserStatus::Active.to_string();
+        let role = |user: &User| user.role.parse::<UserRole>().ok();
+
+        let mut managers: Vec<Uuid> = self.user_repository
+            .get_by_branch_id(branch_id)
+            .await?
+            .into_iter()
+            .filter(|user| is_active(user) && role(user).is_some_and(|r| r.level() >= UserRole::Manager.level()))
+            .map(|user| user.id)
+            .collect();
+
+        for admin in self.user_repository.get_by_account_id(account_id).await? {
+            if is_active(&admin) && role(&admin).is_some_and(|r| r.is_admin()) && !managers.contains(&admin.id) {
+                managers.push(admin.id);
+            }
+        }
+        Ok(managers)
+    }
+}
@@ -0,0 +1,67 @@
+use serde_json::Value;
+
+use crate::modules::notification::entity::{Channel, NotificationKind};
+
+/// Title and body of a kind of notification, with `{{name}}` placeholders filled from its data
+#[derive(Debug, Clone, Copy)]
+pub struct Template {
+    pub title: &'static str,
+    pub body: &'static str,
+    /// Channels used until the user chooses their own
+    pub channels: &'static [Channel],
+}
+
+/// The template of a kind of notification
+pub fn template(kind: NotificationKind) -> Template {
+    match kind {
+        NotificationKind::OrderReady => Template {
+            title: "Order ready",
+            body: "The order for {{table}} is ready to serve.",
+            channels: &[Channel::InApp, Channel::Push],
+        },
+        NotificationKind::ReservationConfirmed => Template {
+            title: "Reservation confirmed",
+            body: "Your table for {{party_size}} at {{branch}} on {{starts_at}} is confirmed.",
+            channels: &[Channel::InApp, Channel::Email],
+        },
+        NotificationKind::LowStock => Template {
+            title: "Low stock: {{item}}",
+            body: "{{item}} at {{branch}} is down to {{quantity}} {{unit}}.",
+            channels: &[Channel::InApp, Channel::Email],
+        },
+        NotificationKind::Invitation => Template {
+            title: "You are invited to {{account}}",
+            body: "You have been invited to join {{account}} as {{role}}. Accept the invitation before {{expires_at}}:\n\n{{accept_url}}",
+            channels: &[Channel::Email],
+        },
+        NotificationKind::WebhookDisabled => Template {
+            title: "Webhook disabled",
+            body: "The webhook to {{url}} was disabled after {{failures}} failed attempts in a row. Fix the receiver, then set the webhook ACTIVE again to resume deliveries.",
+            channels: &[Channel::InApp, Channel::Email],
+        },
+    }
+}
+
+/// Fill the `{{name}}` placeholders of a text with the values of `data`; unknown names are left empty
+pub fn render(text: &str, data: &Value) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        match data.get(name) {
+            Some(Value::String(value)) => rendered.push_str(value),
+            Some(Value::Null) | None => {}
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
@@ -0,0 +1,183 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::order::entity::{
+        ApplyVoucherRequest, CreateLineRequest, CreateOrderRequest, Model as Order, OrderQuery, OrderView, SetCustomerRequest,
+        UpdateLineRequest, UpdateOrderStatusRequest,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List orders of a branch
+pub async fn get_all(
+    Query(query): Query<OrderQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Order>>, ApiError> {
+    info!("Fetching orders");
+    let result = state.order_service.search(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific order by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Fetching order with ID: {}", id);
+    let result = state.order_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Open a new draft order
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateOrderRequest>,
+) -> Result<(StatusCode, Json<OrderView>), ApiError> {
+    info!("Creating order");
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.order_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Add a line to a draft order
+pub async fn add_line(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateLineRequest>,
+) -> Result<(StatusCode, Json<OrderView>), ApiError> {
+    info!("Adding line to order with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.order_service.add_line(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a line of a draft order
+pub async fn update_line(
+    Path((id, line_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateLineRequest>,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Updating line {} of order {}", line_id, id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.order_service.update_line(&ctx, &user, id, line_id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Remove a line from a draft order
+pub async fn delete_line(
+    Path((id, line_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Removing line {} from order {}", line_id, id);
+    let result = state.order_service.delete_line(&ctx, &user, id, line_id).await?;
+    Ok(Json(result))
+}
+
+/// Move an order to its next status
+pub async fn update_status(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateOrderStatusRequest>,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Setting status of order {} to {}", id, payload.status);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let status = payload.status.parse().map_err(ApiError::InvalidInput)?;
+    let result = state.order_service.transition(&ctx, &user, id, status, payload.reason).await?;
+    Ok(Json(result))
+}
+
+/// Set the customer collecting loyalty points for an order
+pub async fn set_customer(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetCustomerRequest>,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Setting customer of order {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.order_service.set_customer(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Remove the customer from an order
+pub async fn remove_customer(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Removing customer of order {}", id);
+    let result = state.order_service.remove_customer(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
+
+/// Discount an order with a voucher code
+pub async fn apply_voucher(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<ApplyVoucherRequest>,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Applying voucher to order {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.order_service.apply_voucher(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Take the voucher off an order
+pub async fn remove_voucher(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<OrderView>, ApiError> {
+    info!("Removing voucher from order {}", id);
+    let result = state.order_service.remove_voucher(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,439 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    common::{
+        money::{Currency, Money},
+        ApiError,
+    },
+    modules::{
+        tax::entity::{TaxLine, TaxPolicy},
+        user::entity::UserRole,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "orders")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub table_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub status: String,
+    pub note: Option<String>,
+    /// Currency of the branch when the order was opened
+    pub currency: String,
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub subtotal: Money,
+    /// Customer collecting loyalty points for the order
+    pub customer_id: Option<Uuid>,
+    pub voucher_id: Option<Uuid>,
+    /// Discount of the applied voucher, copied when it was applied
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub discount_rule: Option<DiscountRule>,
+    /// Amount taken off the subtotal, kept in step with the lines
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub discount: Money,
+    /// Tax rules of the branch when the order was opened, `None` if it charged no tax
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub tax_policy: Option<TaxPolicy>,
+    /// Tax on the order after its discount, contained in the total or added to it
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub tax: Money,
+    pub placed_at: Option<DateTimeWithTimeZone>,
+    pub ready_at: Option<DateTimeWithTimeZone>,
+    pub served_at: Option<DateTimeWithTimeZone>,
+    pub paid_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        use serde::ser::Error;
+        let mut state = serializer.serialize_struct("Order", 24)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("table_id", &self.table_id)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("note", &self.note)?;
+        state.serialize_field("currency", &self.currency)?;
+        state.serialize_field("subtotal", &self.subtotal.amount())?;
+        state.serialize_field("customer_id", &self.customer_id)?;
+        state.serialize_field("voucher_id", &self.voucher_id)?;
+        state.serialize_field("discount_rule", &self.discount_rule)?;
+        state.serialize_field("discount", &self.discount.amount())?;
+        state.serialize_field("prices_include_tax", &self.prices_include_tax())?;
+        state.serialize_field("tax", &self.tax.amount())?;
+        state.serialize_field("total", &self.total().map_err(S::Error::custom)?.amount())?;
+        state.serialize_field("placed_at", &self.placed_at)?;
+        state.serialize_field("ready_at", &self.ready_at)?;
+        state.serialize_field("served_at", &self.served_at)?;
+        state.serialize_field("paid_at", &self.paid_at)?;
+        state.serialize_field("cancelled_at", &self.cancelled_at)?;
+        state.serialize_field("cancel_reason", &self.cancel_reason)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// What is owed for the order after its discount, with tax added when prices exclude it
+    pub fn total(&self) -> Result<Money, ApiError> {
+        let mut total = self.subtotal.checked_sub(self.discount)?;
+        if total.is_negative() {
+            total = Money::zero(total.currency());
+        }
+        if self.prices_include_tax() {
+            Ok(total)
+        } else {
+            total.checked_add(self.tax)
+        }
+    }
+
+    /// Parsed currency of the order
+    pub fn currency(&self) -> Result<Currency, ApiError> {
+        self.currency.trim().parse().map_err(ApiError::InvalidInput)
+    }
+
+    /// Whether the line prices already contain the tax
+    pub fn prices_include_tax(&self) -> bool {
+        self.tax_policy.as_ref().is_none_or(|policy| policy.prices_include_tax)
+    }
+
+    /// Tax of the order per category, from its lines
+    pub fn taxes(&self, lines: &[line::Model]) -> Vec<TaxLine> {
+        let amounts = lines.iter().map(|line| (line.tax_category.as_str(), line.line_total.amount()));
+
+        // Orders of a branch without a tax jurisdiction are broken down without tax
+        match &self.tax_policy {
+            Some(policy) => policy.breakdown(amounts, self.discount.amount()),
+            None => TaxPolicy { prices_include_tax: true, ..Default::default() }.breakdown(amounts, self.discount.amount()),
+        }
+    }
+}
+
+/// Menu items on an order, with their price fixed when added
+pub mod line {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    use super::LineModifiers;
+    use crate::common::money::Money;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "order_lines")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub order_id: Uuid,
+        pub item_id: Uuid,
+        pub name: String,
+        pub tax_category: String,
+        pub quantity: i32,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub unit_price: Money,
+        #[sea_orm(column_type = "JsonBinary")]
+        pub modifiers: LineModifiers,
+        pub note: Option<String>,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub line_total: Money,
+        /// Currency of the order the line belongs to
+        pub currency: String,
+        /// Preparation station the line was routed to when the order was placed
+        pub station_id: Option<Uuid>,
+        /// Set once the station has finished the line, cleared on recall
+        pub bumped_at: Option<DateTimeWithTimeZone>,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("OrderLine", 14)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("order_id", &self.order_id)?;
+            state.serialize_field("item_id", &self.item_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("tax_category", &self.tax_category)?;
+            state.serialize_field("quantity", &self.quantity)?;
+            state.serialize_field("unit_price", &self.unit_price.amount())?;
+            state.serialize_field("modifiers", &self.modifiers)?;
+            state.serialize_field("note", &self.note)?;
+            state.serialize_field("line_total", &self.line_total.amount())?;
+            state.serialize_field("station_id", &self.station_id)?;
+            state.serialize_field("bumped_at", &self.bumped_at)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Modifier option chosen on a line, copied from the menu when the line was added
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineModifier {
+    pub option_id: Uuid,
+    pub group_id: Uuid,
+    pub name: String,
+    pub price_delta: Decimal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct LineModifiers(pub Vec<LineModifier>);
+
+/// How a voucher reduces an order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct DiscountRule {
+    /// `PERCENT` or `FIXED`
+    pub kind: String,
+    pub value: Decimal,
+    /// Below this subtotal the voucher stays on the order but takes nothing off
+    pub min_order_amount: Option<Decimal>,
+}
+
+impl DiscountRule {
+    /// The discount on a subtotal, never more than the subtotal itself
+    pub fn apply(&self, subtotal: Money) -> Result<Money, ApiError> {
+        let currency = subtotal.currency();
+        if self.min_order_amount.is_some_and(|min| subtotal.amount() < min) {
+            return Ok(Money::zero(currency));
+        }
+
+        let discount = match self.kind.parse() {
+            Ok(DiscountKind::Percent) => Money::rounded(subtotal.amount() * self.value / Decimal::from(100), currency)?,
+            Ok(DiscountKind::Fixed) => Money::rounded(self.value, currency)?,
+            Err(_) => Money::zero(currency),
+        };
+        Ok(if discount > subtotal { subtotal } else { discount })
+    }
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscountKind {
+    /// Percentage of the subtotal
+    Percent,
+    /// Fixed amount in the branch currency
+    Fixed,
+}
+
+impl std::fmt::Display for DiscountKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscountKind::Percent => write!(f, "PERCENT"),
+            DiscountKind::Fixed => write!(f, "FIXED"),
+        }
+    }
+}
+
+impl std::str::FromStr for DiscountKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PERCENT" => Ok(DiscountKind::Percent),
+            "FIXED" => Ok(DiscountKind::Fixed),
+            _ => Err(format!("Discount kind {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Draft,
+    Placed,
+    InPreparation,
+    Ready,
+    Served,
+    Paid,
+    Cancelled,
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderStatus::Draft => write!(f, "DRAFT"),
+            OrderStatus::Placed => write!(f, "PLACED"),
+            OrderStatus::InPreparation => write!(f, "IN_PREPARATION"),
+            OrderStatus::Ready => write!(f, "READY"),
+            OrderStatus::Served => write!(f, "SERVED"),
+            OrderStatus::Paid => write!(f, "PAID"),
+            OrderStatus::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl std::str::FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DRAFT" => Ok(OrderStatus::Draft),
+            "PLACED" => Ok(OrderStatus::Placed),
+            "IN_PREPARATION" => Ok(OrderStatus::InPreparation),
+            "READY" => Ok(OrderStatus::Ready),
+            "SERVED" => Ok(OrderStatus::Served),
+            "PAID" => Ok(OrderStatus::Paid),
+            "CANCELLED" => Ok(OrderStatus::Cancelled),
+            _ => Err(format!("Order status {} is not valid", s)),
+        }
+    }
+}
+
+impl OrderStatus {
+    /// Whether the order is finished and no longer holds its table
+    pub fn is_closed(&self) -> bool {
+        matches!(self, OrderStatus::Paid | OrderStatus::Cancelled)
+    }
+
+    /// Roles allowed to move an order from this status to `to`, or `None` if the transition does not exist
+    /// or is not made by hand. Managers and above may perform any existing transition. `SERVED` → `PAID`
+    /// only happens when a payment settles the balance.
+    pub fn transition_roles(&self, to: OrderStatus) -> Option<&'static [UserRole]> {
+        use OrderStatus::*;
+
+        match (self, to) {
+            (Draft, Placed) => Some(&[UserRole::Waiter]),
+            (Placed, InPreparation) | (InPreparation, Ready) => Some(&[UserRole::Cook, UserRole::Barman]),
+            (Ready, Served) => Some(&[UserRole::Waiter]),
+            (Draft, Cancelled) | (Placed, Cancelled) => Some(&[UserRole::Waiter]),
+            // Food is already being made, so only a manager can void the order
+            (InPreparation, Cancelled) | (Ready, Cancelled) | (Served, Cancelled) => Some(&[]),
+            _ => None,
+        }
+    }
+}
+
+// Validators
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<OrderStatus>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("status").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateLineRequest {
+    pub item_id: Uuid,
+
+    #[validate(range(min = 1, max = 100, message = "Quantity must be between 1 and 100"))]
+    pub quantity: i32,
+
+    /// Chosen modifier options of the item
+    #[serde(default)]
+    pub modifier_option_ids: Vec<Uuid>,
+
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateLineRequest {
+    #[validate(range(min = 1, max = 100, message = "Quantity must be between 1 and 100"))]
+    pub quantity: Option<i32>,
+
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateOrderRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub table_id: Option<Uuid>,
+
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+
+    #[validate(nested)]
+    #[serde(default)]
+    pub lines: Vec<CreateLineRequest>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateOrderStatusRequest {
+    #[validate(custom(function = "validate_status"))]
+    pub status: String,
+
+    /// Why the order was cancelled
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// The customer to collect points for an order, by ID or by email
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetCustomerRequest {
+    pub customer_id: Option<Uuid>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct ApplyVoucherRequest {
+    #[validate(length(min = 1, max = 50, message = "Code must be between 1 and 50 characters"))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct OrderQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub table_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+/// A menu item priced for an order line
+#[derive(Debug, Clone)]
+pub struct PricedLine {
+    pub item_id: Uuid,
+    pub name: String,
+    pub tax_category: String,
+    pub quantity: i32,
+    pub unit_price: Money,
+    pub modifiers: LineModifiers,
+    pub note: Option<String>,
+}
+
+/// An order with its lines and taxes
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderView {
+    #[serde(flatten)]
+    pub order: Model,
+    pub lines: Vec<line::Model>,
+    pub taxes: Vec<TaxLine>,
+}
@@ -0,0 +1,520 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::order::entity::{
+        line, ActiveModel, Column, Entity as OrderEntity, Model as Order, OrderQuery, OrderStatus, OrderView, PricedLine,
+        UpdateLineRequest,
+    },
+    modules::tax::entity::TaxPolicy,
+    common::{
+        money::{Currency, Money},
+        outbox::{self, EventMetadata, NewDomainEvent},
+        ApiError, RequestContext,
+    },
+};
+
+/// Order repository for database operations
+#[derive(Debug, Clone)]
+pub struct OrderRepository {
+    db: DatabaseConnection,
+}
+
+impl OrderRepository {
+    /// Create a new order repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search orders of a branch, newest first, returning one page and the total count
+    pub async fn search(&self, branch_id: Uuid, query: &OrderQuery, page: u64, per_page: u64) -> Result<(Vec<Order>, u64), ApiError> {
+        let mut select = OrderEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some(table_id) = query.table_id {
+            select = select.filter(Column::TableId.eq(table_id));
+        }
+        if let Some(ref status) = query.status {
+            select = select.filter(Column::Status.eq(status.as_str()));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count orders of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let orders = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch orders of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((orders, total))
+    }
+
+    /// Get an order by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Order, ApiError> {
+        info!("Fetching order with ID: {}", id);
+
+        let order = OrderEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch order with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match order {
+            Some(order) => Ok(order),
+            None => Err(ApiError::NotFound("Order not found".to_string())),
+        }
+    }
+
+    /// Get orders by their IDs
+    pub async fn get_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Order>, ApiError> {
+        OrderEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch orders by IDs: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the lines of several orders
+    pub async fn get_lines_by_order_ids(&self, order_ids: Vec<Uuid>) -> Result<Vec<line::Model>, ApiError> {
+        line::Entity::find()
+            .filter(line::Column::OrderId.is_in(order_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch lines of orders: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the lines of an order
+    pub async fn get_lines(&self, order_id: Uuid) -> Result<Vec<line::Model>, ApiError> {
+        line::Entity::find()
+            .filter(line::Column::OrderId.eq(order_id))
+            .order_by_asc(line::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch lines of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a line of an order
+    pub async fn get_line(&self, order_id: Uuid, line_id: Uuid) -> Result<line::Model, ApiError> {
+        let line = line::Entity::find_by_id(line_id)
+            .filter(line::Column::OrderId.eq(order_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch order line {}: {}", line_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match line {
+            Some(line) => Ok(line),
+            None => Err(ApiError::NotFound("Order line not found".to_string())),
+        }
+    }
+
+    /// Count the orders still open at a table
+    pub async fn count_open_by_table(&self, table_id: Uuid) -> Result<u64, ApiError> {
+        OrderEntity::find()
+            .filter(Column::TableId.eq(table_id))
+            .filter(Column::Status.is_not_in([OrderStatus::Paid.to_string(), OrderStatus::Cancelled.to_string()]))
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count open orders of table {}: {}", table_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Create a draft order with its lines
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create(
+        &self,
+        account_id: Uuid,
+        branch_id: Uuid,
+        table_id: Option<Uuid>,
+        created_by: Uuid,
+        note: Option<String>,
+        currency: Currency,
+        tax_policy: Option<TaxPolicy>,
+        lines: Vec<PricedLine>,
+    ) -> Result<Order, ApiError> {
+        info!("Creating order at branch {} with {} lines", branch_id, lines.len());
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let order = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(branch_id),
+            table_id: Set(table_id),
+            created_by: Set(created_by),
+            status: Set(OrderStatus::Draft.to_string()),
+            note: Set(note),
+            currency: Set(currency.to_string()),
+            subtotal: Set(Money::zero(currency)),
+            customer_id: Set(None),
+            voucher_id: Set(None),
+            discount_rule: Set(None),
+            discount: Set(Money::zero(currency)),
+            tax_policy: Set(tax_policy),
+            tax: Set(Money::zero(currency)),
+            placed_at: Set(None),
+            ready_at: Set(None),
+            served_at: Set(None),
+            paid_at: Set(None),
+            cancelled_at: Set(None),
+            cancel_reason: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let order = order.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create order: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        for priced in lines {
+            Self::insert_line(&txn, order.id, priced).await?;
+        }
+        let order = Self::recalculate(&txn, order.id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit order {}: {}", order.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(order)
+    }
+
+    /// Add a line to an order and update its subtotal
+    pub async fn add_line(&self, order_id: Uuid, priced: PricedLine) -> Result<line::Model, ApiError> {
+        info!("Adding item {} to order {}", priced.item_id, order_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let line = Self::insert_line(&txn, order_id, priced).await?;
+        Self::recalculate(&txn, order_id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit line of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(line)
+    }
+
+    /// Change the quantity or note of a line and update the order subtotal
+    pub async fn update_line(&self, order_id: Uuid, line_id: Uuid, request: UpdateLineRequest) -> Result<line::Model, ApiError> {
+        info!("Updating line {} of order {}", line_id, order_id);
+
+        let current = self.get_line(order_id, line_id).await?;
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let mut line: line::ActiveModel = current.clone().into();
+
+        if let Some(quantity) = request.quantity {
+            line.quantity = Set(quantity);
+            line.line_total = Set(current.unit_price.checked_mul(i64::from(quantity))?);
+        }
+
+        if let Some(note) = request.note {
+            line.note = Set(Some(note));
+        }
+
+        line.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        let line = line.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to update order line {}: {}", line_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        Self::recalculate(&txn, order_id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit line of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(line)
+    }
+
+    /// Remove a line from an order and update its subtotal
+    pub async fn delete_line(&self, order_id: Uuid, line_id: Uuid) -> Result<(), ApiError> {
+        info!("Removing line {} from order {}", line_id, order_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let result = line::Entity::delete_many()
+            .filter(line::Column::Id.eq(line_id))
+            .filter(line::Column::OrderId.eq(order_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete order line {}: {}", line_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::NotFound("Order line not found".to_string()));
+        }
+        Self::recalculate(&txn, order_id).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit line of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Move an order from one status to another, failing if it was changed concurrently. The move is recorded
+    /// as an `order.<status>` event, e.g. `order.paid`, in the same transaction.
+    pub async fn transition(
+        &self,
+        ctx: &RequestContext,
+        id: Uuid,
+        from: OrderStatus,
+        to: OrderStatus,
+        reason: Option<String>,
+    ) -> Result<Order, ApiError> {
+        info!("Moving order {} from {} to {}", id, from, to);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transition of order {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let before = OrderEntity::find_by_id(id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock order {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut update = OrderEntity::update_many()
+            .col_expr(Column::Status, Expr::value(to.to_string()))
+            .col_expr(Column::UpdatedAt, Expr::value(now));
+
+        update = match to {
+            OrderStatus::Placed => update.col_expr(Column::PlacedAt, Expr::value(now)),
+            OrderStatus::Ready => update.col_expr(Column::ReadyAt, Expr::value(now)),
+            OrderStatus::Served => update.col_expr(Column::ServedAt, Expr::value(now)),
+            OrderStatus::Paid => update.col_expr(Column::PaidAt, Expr::value(now)),
+            OrderStatus::Cancelled => update
+                .col_expr(Column::CancelledAt, Expr::value(now))
+                .col_expr(Column::CancelReason, Expr::value(reason)),
+            OrderStatus::Draft | OrderStatus::InPreparation => update,
+        };
+
+        let result = update
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(from.to_string()))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to update order {} status: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            let current = self.get_by_id(id).await?;
+            return Err(ApiError::InvalidTransition { from: current.status, to: to.to_string() });
+        }
+
+        let order = OrderEntity::find_by_id(id)
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch order with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+        let lines = line::Entity::find()
+            .filter(line::Column::OrderId.eq(id))
+            .order_by_asc(line::Column::CreatedAt)
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch lines of order {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        // Lines do not change with the status
+        let previous = OrderView { taxes: before.taxes(&lines), order: before, lines: lines.clone() };
+        let taxes = order.taxes(&lines);
+        let view = OrderView { order: order.clone(), lines, taxes };
+
+        let event = NewDomainEvent::new(
+            outbox::kind::order(&to),
+            order.account_id,
+            (outbox::aggregate::ORDER, order.id),
+            &view,
+            EventMetadata::from(ctx),
+        )
+        .with_previous(&previous);
+        outbox::record(&txn, event).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit transition of order {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(order)
+    }
+
+    /// Set or clear the customer collecting points for an order
+    pub async fn set_customer(&self, id: Uuid, customer_id: Option<Uuid>) -> Result<Order, ApiError> {
+        info!("Setting customer of order {}", id);
+
+        let mut order: ActiveModel = self.get_by_id(id).await?.into();
+        order.customer_id = Set(customer_id);
+        order.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        order.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set customer of order {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the orders a customer collected points for, newest first
+    pub async fn get_by_customer_id(&self, customer_id: Uuid) -> Result<Vec<Order>, ApiError> {
+        OrderEntity::find()
+            .filter(Column::CustomerId.eq(customer_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch orders of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Detach a customer from all of their orders, keeping the orders themselves
+    pub async fn unlink_customer(&self, customer_id: Uuid) -> Result<u64, ApiError> {
+        info!("Unlinking customer {} from orders", customer_id);
+
+        let result = OrderEntity::update_many()
+            .col_expr(Column::CustomerId, Expr::value(Option::<Uuid>::None))
+            .col_expr(Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::CustomerId.eq(customer_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to unlink customer {} from orders: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    async fn insert_line<C: ConnectionTrait>(db: &C, order_id: Uuid, priced: PricedLine) -> Result<line::Model, ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        let line = line::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            order_id: Set(order_id),
+            item_id: Set(priced.item_id),
+            name: Set(priced.name),
+            tax_category: Set(priced.tax_category),
+            quantity: Set(priced.quantity),
+            unit_price: Set(priced.unit_price),
+            modifiers: Set(priced.modifiers),
+            note: Set(priced.note),
+            line_total: Set(priced.unit_price.checked_mul(i64::from(priced.quantity))?),
+            currency: Set(priced.unit_price.currency().to_string()),
+            station_id: Set(None),
+            bumped_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        line.insert(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to add line to order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Set the order subtotal to the sum of its lines and reapply its discount and tax.
+    /// The order row is locked so a voucher applied meanwhile is not overwritten.
+    pub(crate) async fn recalculate<C: ConnectionTrait>(db: &C, order_id: Uuid) -> Result<Order, ApiError> {
+        let lines = line::Entity::find()
+            .filter(line::Column::OrderId.eq(order_id))
+            .all(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch lines of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let order = OrderEntity::find_by_id(order_id)
+            .lock_exclusive()
+            .one(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch order with ID {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+        let currency = order.currency()?;
+        let subtotal = Money::sum(currency, lines.iter().map(|line| line.line_total))?;
+        let discount = match &order.discount_rule {
+            Some(rule) => rule.apply(subtotal)?,
+            None => Money::zero(currency),
+        };
+        let tax = Money::rounded(Order { discount, ..order.clone() }.taxes(&lines).iter().map(|line| line.tax).sum(), currency)?;
+
+        let mut order: ActiveModel = order.into();
+        order.subtotal = Set(subtotal);
+        order.discount = Set(discount);
+        order.tax = Set(tax);
+        order.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        order.update(db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update subtotal of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
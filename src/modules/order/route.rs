@@ -0,0 +1,32 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create order routes for staff; who may move an order to which status is checked per transition
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orders", get(get_all).post(create))
+        .route("/orders/:id", get(get_by_id))
+        .route("/orders/:id/lines", post(add_line))
+        .route("/orders/:id/lines/:line_id", put(update_line).delete(delete_line))
+        .route("/orders/:id/status", put(update_status))
+        .layer(middleware::from_fn(authorize(vec![
+            "ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER", "COOK", "BARMAN", "CASH_REGISTER",
+        ])))
+}
+
+/// Create checkout routes for attaching customers and vouchers to orders
+pub fn create_checkout_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orders/:id/customer", put(set_customer).delete(remove_customer))
+        .route("/orders/:id/voucher", post(apply_voucher).delete(remove_voucher))
+        .layer(middleware::from_fn(authorize(vec![
+            "ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER", "CASH_REGISTER",
+        ])))
+}
@@ -0,0 +1,467 @@
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::{error, info, warn};
+
+use crate::{
+    common::{
+        events::{topic, Event, SharedEventBus},
+        money::{Currency, Money},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        loyalty::service::LoyaltyService,
+        menu::service::MenuService,
+        notification::{
+            entity::{NewNotification, NotificationKind, Recipient},
+            service::NotificationService,
+        },
+        order::{
+            entity::{
+                line, ApplyVoucherRequest, CreateLineRequest, CreateOrderRequest, LineModifier, LineModifiers, Model as Order, OrderQuery,
+                OrderStatus, OrderView, PricedLine, SetCustomerRequest, UpdateLineRequest,
+            },
+            repository::OrderRepository,
+        },
+        station::repository::StationRepository,
+        table::service::TableService,
+        receipt::service::ReceiptService,
+        tax::service::TaxService,
+        user::entity::UserRole,
+    },
+};
+
+/// Order service layer for business logic
+#[derive(Debug, Clone)]
+pub struct OrderService {
+    repository: OrderRepository,
+    branch_repository: BranchRepository,
+    menu_service: MenuService,
+    table_service: TableService,
+    station_repository: StationRepository,
+    loyalty_service: LoyaltyService,
+    tax_service: TaxService,
+    receipt_service: ReceiptService,
+    notification_service: NotificationService,
+    audit_service: AuditService,
+    events: SharedEventBus,
+}
+
+impl OrderService {
+    /// Create a new order service
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: OrderRepository,
+        branch_repository: BranchRepository,
+        menu_service: MenuService,
+        table_service: TableService,
+        station_repository: StationRepository,
+        loyalty_service: LoyaltyService,
+        tax_service: TaxService,
+        receipt_service: ReceiptService,
+        notification_service: NotificationService,
+        audit_service: AuditService,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            menu_service,
+            table_service,
+            station_repository,
+            loyalty_service,
+            tax_service,
+            receipt_service,
+            notification_service,
+            audit_service,
+            events,
+        }
+    }
+
+    /// Orders of a branch, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: OrderQuery) -> Result<Page<Order>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref status) = query.status {
+            status.parse::<OrderStatus>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Get an order of the caller's account with its lines
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<OrderView, ApiError> {
+        let order = self.get_owned(actor, id).await?;
+        self.view(order).await
+    }
+
+    /// Open a draft order, pricing its lines from the menu of the branch
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateOrderRequest) -> Result<OrderView, ApiError> {
+        info!("Creating order with {} lines", data.lines.len());
+
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        if let Some(table_id) = data.table_id {
+            let table = self.table_service.get_by_id(actor, table_id).await?;
+            if table.branch_id != branch.id {
+                return Err(ApiError::InvalidInput("Table belongs to another branch".to_string()));
+            }
+        }
+
+        let mut lines = Vec::with_capacity(data.lines.len());
+        for line in data.lines {
+            lines.push(self.price_line(&branch, branch.currency()?, line).await?);
+        }
+
+        let tax_policy = self.tax_service.policy_for(&branch).await?;
+        let order = self.repository
+            .create(branch.account_id, branch.id, data.table_id, actor.parsed_id()?, data.note, branch.currency()?, tax_policy, lines)
+            .await?;
+
+        if let Some(table_id) = order.table_id {
+            self.table_service.occupy(table_id).await?;
+        }
+
+        let view = self.view(order).await?;
+        self.audit(ctx, view.order.account_id, AuditAction::OrderCreated, view.order.id, None, Some(&view)).await?;
+        self.publish("order.created", &view).await;
+        Ok(view)
+    }
+
+    /// Add an item to a draft order
+    pub async fn add_line(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: CreateLineRequest) -> Result<OrderView, ApiError> {
+        info!("Adding item {} to order {}", data.item_id, id);
+
+        let order = self.get_draft(actor, id).await?;
+        let before = self.view(order.clone()).await?;
+
+        let branch = self.branch_repository.get_by_id(order.branch_id).await?;
+        let priced = self.price_line(&branch, order.currency()?, data).await?;
+        self.repository.add_line(id, priced).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Change the quantity or note of a line on a draft order
+    pub async fn update_line(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, line_id: Uuid, data: UpdateLineRequest) -> Result<OrderView, ApiError> {
+        info!("Updating line {} of order {}", line_id, id);
+
+        let order = self.get_draft(actor, id).await?;
+        let before = self.view(order).await?;
+        self.repository.update_line(id, line_id, data).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Remove a line from a draft order
+    pub async fn delete_line(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, line_id: Uuid) -> Result<OrderView, ApiError> {
+        info!("Removing line {} from order {}", line_id, id);
+
+        let order = self.get_draft(actor, id).await?;
+        let before = self.view(order).await?;
+        self.repository.delete_line(id, line_id).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Move an order through its lifecycle, enforcing the allowed transitions and who may perform them
+    pub async fn transition(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, to: OrderStatus, reason: Option<String>) -> Result<OrderView, ApiError> {
+        info!("Moving order {} to {}", id, to);
+
+        let before = self.get_owned(actor, id).await?;
+        let from: OrderStatus = before.status.parse().map_err(ApiError::InvalidInput)?;
+
+        let roles = from.transition_roles(to).ok_or_else(|| ApiError::InvalidTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+        })?;
+
+        let role: UserRole = actor.role.parse().map_err(ApiError::InvalidInput)?;
+        if role.level() < UserRole::Manager.level() && !roles.contains(&role) {
+            warn!("User {} with role {} tried to move order {} to {}", actor.id, role, id, to);
+            return Err(ApiError::Forbidden(format!("{} cannot move an order from {} to {}", role, from, to)));
+        }
+
+        if to == OrderStatus::Placed && self.repository.get_lines(id).await?.is_empty() {
+            return Err(ApiError::InvalidInput("Cannot place an order without lines".to_string()));
+        }
+
+        self.apply_transition(ctx, actor, before, from, to, reason).await
+    }
+
+    /// Close a served order once payments have settled its balance; never reachable through `transition`
+    pub async fn mark_paid(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<OrderView, ApiError> {
+        info!("Order {} is paid", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let from: OrderStatus = before.status.parse().map_err(ApiError::InvalidInput)?;
+        if from != OrderStatus::Served {
+            return Err(ApiError::InvalidTransition { from: from.to_string(), to: OrderStatus::Paid.to_string() });
+        }
+
+        self.apply_transition(ctx, actor, before, from, OrderStatus::Paid, None).await
+    }
+
+    /// Store a checked transition and run what follows from the new status
+    async fn apply_transition(&self, ctx: &RequestContext, actor: &UserInfo, before: Order, from: OrderStatus, to: OrderStatus, reason: Option<String>) -> Result<OrderView, ApiError> {
+        let id = before.id;
+        let order = self.repository.transition(ctx, id, from, to, reason).await?;
+
+        if to == OrderStatus::Placed {
+            self.station_repository.route_order(order.id, order.branch_id).await?;
+        }
+        if to == OrderStatus::Ready {
+            self.notify_ready(actor, &order).await;
+        }
+        if to == OrderStatus::Paid {
+            // Points are a bonus on top of the payment and never hold it up
+            if let Err(e) = self.loyalty_service.earn(&order, actor.parsed_id()?).await {
+                error!("Failed to earn loyalty points for order {}: {}", order.id, e);
+            }
+            // A receipt left unissued here is issued when it is first requested
+            if let Err(e) = self.receipt_service.issue(ctx, &order, actor.parsed_id()?).await {
+                error!("Failed to issue receipt for order {}: {}", order.id, e);
+            }
+        }
+        if to == OrderStatus::Cancelled {
+            if let Err(e) = self.loyalty_service.release_voucher(&order).await {
+                error!("Failed to release voucher of order {}: {}", order.id, e);
+            }
+        }
+        if to.is_closed() {
+            self.release_table(&order).await?;
+        }
+
+        let view = self.view(order).await?;
+        self.publish("order.status_changed", &view).await;
+        Ok(view)
+    }
+
+    /// Set the customer collecting loyalty points for an open order
+    pub async fn set_customer(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: SetCustomerRequest) -> Result<OrderView, ApiError> {
+        info!("Setting customer of order {}", id);
+
+        let order = self.get_open_without_voucher(actor, id).await?;
+        let customer = self.loyalty_service.resolve_customer(order.account_id, data).await?;
+        let before = self.view(order).await?;
+        self.repository.set_customer(id, Some(customer.id)).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Stop collecting loyalty points on an open order
+    pub async fn remove_customer(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<OrderView, ApiError> {
+        info!("Removing customer of order {}", id);
+
+        let order = self.get_open_without_voucher(actor, id).await?;
+        let before = self.view(order).await?;
+        self.repository.set_customer(id, None).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Discount an open order with a voucher code
+    pub async fn apply_voucher(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: ApplyVoucherRequest) -> Result<OrderView, ApiError> {
+        info!("Applying voucher to order {}", id);
+
+        let order = self.get_open(actor, id).await?;
+        let before = self.view(order.clone()).await?;
+        self.loyalty_service.redeem_voucher(&order, &data.code).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Take the voucher off an open order
+    pub async fn remove_voucher(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<OrderView, ApiError> {
+        info!("Removing voucher from order {}", id);
+
+        let order = self.get_open(actor, id).await?;
+        let before = self.view(order.clone()).await?;
+        self.loyalty_service.remove_voucher(&order).await?;
+
+        self.updated(ctx, before).await
+    }
+
+    /// Price a menu item with its chosen modifiers as served at a branch, in the currency of the order
+    async fn price_line(&self, branch: &Branch, currency: Currency, data: CreateLineRequest) -> Result<PricedLine, ApiError> {
+        let view = self.menu_service.get_orderable_item(branch.account_id, branch, data.item_id).await?;
+
+        let mut modifiers = Vec::new();
+        for group in &view.modifier_groups {
+            let chosen: Vec<_> = group.options
+                .iter()
+                .filter(|option| data.modifier_option_ids.contains(&option.id))
+                .collect();
+
+            let count = chosen.len() as i32;
+            if count < group.group.min_select || count > group.group.max_select {
+                return Err(ApiError::InvalidInput(format!(
+                    "Choose between {} and {} options for {}",
+                    group.group.min_select, group.group.max_select, group.group.name
+                )));
+            }
+
+            modifiers.extend(chosen.into_iter().map(|option| LineModifier {
+                option_id: option.id,
+                group_id: option.group_id,
+                name: option.name.clone(),
+                price_delta: option.price_delta,
+            }));
+        }
+
+        // Catches options of other items as well as repeated ones
+        if modifiers.len() != data.modifier_option_ids.len() {
+            return Err(ApiError::InvalidInput(format!("Invalid modifier options for {}", view.item.name)));
+        }
+
+        let unit_price = Money::from_decimal(view.effective_price + modifiers.iter().map(|m| m.price_delta).sum::<Decimal>(), currency)?;
+
+        Ok(PricedLine {
+            item_id: view.item.id,
+            name: view.item.name,
+            tax_category: view.item.tax_category,
+            quantity: data.quantity,
+            unit_price,
+            modifiers: LineModifiers(modifiers),
+            note: data.note,
+        })
+    }
+
+    /// Tell the waiter of the table, or whoever took the order, that it can be served
+    async fn notify_ready(&self, actor: &UserInfo, order: &Order) {
+        let table = match order.table_id {
+            Some(table_id) => self.table_service.get_by_id(actor, table_id).await.ok(),
+            None => None,
+        };
+        let waiter_id = table.as_ref().and_then(|table| table.waiter_id).unwrap_or(order.created_by);
+
+        let data = serde_json::json!({
+            "order_id": order.id,
+            "branch_id": order.branch_id,
+            "table_id": order.table_id,
+            "table": table.map_or_else(|| "the counter".to_string(), |table| format!("table {}", table.label)),
+        });
+        self.notification_service
+            .notify(NewNotification {
+                account_id: order.account_id,
+                recipient: Recipient::User(waiter_id),
+                kind: NotificationKind::OrderReady,
+                data,
+            })
+            .await;
+    }
+
+    /// Free the table once its last open order is closed
+    async fn release_table(&self, order: &Order) -> Result<(), ApiError> {
+        if let Some(table_id) = order.table_id {
+            if self.repository.count_open_by_table(table_id).await? == 0 {
+                self.table_service.release(table_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn updated(&self, ctx: &RequestContext, before: OrderView) -> Result<OrderView, ApiError> {
+        let order = self.repository.get_by_id(before.order.id).await?;
+        let view = self.view(order).await?;
+
+        self.audit(ctx, view.order.account_id, AuditAction::OrderUpdated, view.order.id, Some(&before), Some(&view)).await?;
+        self.publish("order.updated", &view).await;
+        Ok(view)
+    }
+
+    /// Tell the branch, the table and the stations preparing the order what changed
+    async fn publish(&self, kind: &str, view: &OrderView) {
+        let mut topics = vec![topic::branch(view.order.branch_id)];
+        topics.extend(view.order.table_id.map(topic::table));
+
+        let mut stations: Vec<Uuid> = view.lines.iter().filter_map(|line| line.station_id).collect();
+        stations.sort();
+        stations.dedup();
+        topics.extend(stations.into_iter().map(topic::station));
+
+        self.events.publish(Event::new(kind, topics, view)).await;
+    }
+
+    async fn view(&self, order: Order) -> Result<OrderView, ApiError> {
+        let lines: Vec<line::Model> = self.repository.get_lines(order.id).await?;
+        let taxes = order.taxes(&lines);
+        Ok(OrderView { order, lines, taxes })
+    }
+
+    /// Fetch a draft order; lines can only change before it is placed
+    async fn get_draft(&self, actor: &UserInfo, id: Uuid) -> Result<Order, ApiError> {
+        let order = self.get_owned(actor, id).await?;
+        if order.status != OrderStatus::Draft.to_string() {
+            return Err(ApiError::Conflict(format!("Order is {} and can no longer be changed", order.status)));
+        }
+        Ok(order)
+    }
+
+    /// Fetch an order that is neither paid nor cancelled
+    async fn get_open(&self, actor: &UserInfo, id: Uuid) -> Result<Order, ApiError> {
+        let order = self.get_owned(actor, id).await?;
+        let status: OrderStatus = order.status.parse().map_err(ApiError::InvalidInput)?;
+        if status.is_closed() {
+            return Err(ApiError::Conflict(format!("Order is {} and can no longer be changed", order.status)));
+        }
+        Ok(order)
+    }
+
+    /// Fetch an open order without a voucher; vouchers may be tied to the customer, so it must come off first
+    async fn get_open_without_voucher(&self, actor: &UserInfo, id: Uuid) -> Result<Order, ApiError> {
+        let order = self.get_open(actor, id).await?;
+        if order.voucher_id.is_some() {
+            return Err(ApiError::Conflict("Remove the voucher before changing the customer".to_string()));
+        }
+        Ok(order)
+    }
+
+    /// Fetch an order, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Order, ApiError> {
+        let order = self.repository.get_by_id(id).await?;
+
+        if order.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Order not found".to_string()));
+        }
+
+        Ok(order)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record an order mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (AuditTarget::Order, Some(id)), before, after)
+            .await
+    }
+}
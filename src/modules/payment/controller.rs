@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::payment::entity::{
+        Bill, BillQuery, CreatePaymentRequest, CreateRefundRequest, Model as Payment, PaymentQuery, PaymentSummary, PaymentView,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Get the bill of an order
+pub async fn get_bill(
+    Path(id): Path<Uuid>,
+    Query(query): Query<BillQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Bill>, ApiError> {
+    info!("Fetching bill of order {}", id);
+    let result = state.payment_service.get_bill(&user, id, query).await?;
+    Ok(Json(result))
+}
+
+/// Take a payment for an order
+pub async fn create(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreatePaymentRequest>,
+) -> Result<(StatusCode, Json<PaymentView>), ApiError> {
+    info!("Taking payment for order {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.payment_service.pay(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Get a specific payment by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<PaymentView>, ApiError> {
+    info!("Fetching payment with ID: {}", id);
+    let result = state.payment_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// List payments of a branch
+pub async fn get_all(
+    Query(query): Query<PaymentQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Payment>>, ApiError> {
+    info!("Fetching payments");
+    let result = state.payment_service.search(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Totals per payment method for reconciliation
+pub async fn get_summary(
+    Query(query): Query<PaymentQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<PaymentSummary>, ApiError> {
+    info!("Fetching payment summary");
+    let result = state.payment_service.summary(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Refund a payment
+pub async fn refund(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateRefundRequest>,
+) -> Result<(StatusCode, Json<PaymentView>), ApiError> {
+    info!("Refunding payment with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.payment_service.refund(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
@@ -0,0 +1,459 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    common::{
+        money::{serialize_amount, Money},
+        ApiError,
+    },
+    modules::tax::entity::TaxLine,
+};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "payments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub order_id: Uuid,
+    pub created_by: Uuid,
+    /// Register session whose drawer took the payment; required for cash
+    pub register_session_id: Option<Uuid>,
+    pub method: String,
+    /// Provider that handled the payment, e.g. `cash`, `terminal`, `online` or `fake`
+    pub provider: String,
+    pub status: String,
+    /// Part of the bill settled by this payment
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub amount: Money,
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub tip: Money,
+    /// Refunded so far, out of amount and tip
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub refunded: Money,
+    pub currency: String,
+    /// Client supplied key making retries of the same capture return the same payment
+    pub idempotency_key: String,
+    pub split: Option<String>,
+    /// Lines settled when the bill is split by item
+    pub line_ids: Vec<Uuid>,
+    /// Number of equal shares when the bill is split evenly
+    pub shares: Option<i32>,
+    /// Cash handed over and the change given back
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub tendered: Option<Money>,
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub change: Option<Money>,
+    pub provider_reference: Option<String>,
+    pub failure_reason: Option<String>,
+    pub captured_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Payment", 23)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("order_id", &self.order_id)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("register_session_id", &self.register_session_id)?;
+        state.serialize_field("method", &self.method)?;
+        state.serialize_field("provider", &self.provider)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("amount", &self.amount.amount())?;
+        state.serialize_field("tip", &self.tip.amount())?;
+        state.serialize_field("refunded", &self.refunded.amount())?;
+        state.serialize_field("currency", &self.currency)?;
+        state.serialize_field("idempotency_key", &self.idempotency_key)?;
+        state.serialize_field("split", &self.split)?;
+        state.serialize_field("line_ids", &self.line_ids)?;
+        state.serialize_field("shares", &self.shares)?;
+        state.serialize_field("tendered", &self.tendered.map(|tendered| tendered.amount()))?;
+        state.serialize_field("change", &self.change.map(|change| change.amount()))?;
+        state.serialize_field("provider_reference", &self.provider_reference)?;
+        state.serialize_field("failure_reason", &self.failure_reason)?;
+        state.serialize_field("captured_at", &self.captured_at)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Part of the bill still settled once refunds are taken off, refunding the bill before the tip
+    pub fn settled(&self) -> Result<Money, ApiError> {
+        if self.status == PaymentStatus::Captured.to_string() || self.status == PaymentStatus::Refunded.to_string() {
+            if self.refunded > self.amount {
+                Ok(Money::zero(self.amount.currency()))
+            } else {
+                self.amount.checked_sub(self.refunded)
+            }
+        } else {
+            Ok(Money::zero(self.amount.currency()))
+        }
+    }
+
+    /// What can still be refunded
+    pub fn refundable(&self) -> Result<Money, ApiError> {
+        self.amount.checked_add(self.tip)?.checked_sub(self.refunded)
+    }
+}
+
+/// Money given back out of a captured payment
+pub mod refund {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "payment_refunds")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub payment_id: Uuid,
+        pub account_id: Uuid,
+        pub branch_id: Uuid,
+        pub order_id: Uuid,
+        pub created_by: Uuid,
+        /// Register session whose drawer paid out a cash refund
+        pub register_session_id: Option<Uuid>,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub amount: Decimal,
+        pub reason: String,
+        /// PENDING while the provider is asked, then CONFIRMED or FAILED; only confirmed refunds count
+        pub status: String,
+        pub provider_reference: Option<String>,
+        pub failure_reason: Option<String>,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("PaymentRefund", 13)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("payment_id", &self.payment_id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("order_id", &self.order_id)?;
+            state.serialize_field("created_by", &self.created_by)?;
+            state.serialize_field("register_session_id", &self.register_session_id)?;
+            state.serialize_field("amount", &self.amount)?;
+            state.serialize_field("reason", &self.reason)?;
+            state.serialize_field("status", &self.status)?;
+            state.serialize_field("provider_reference", &self.provider_reference)?;
+            state.serialize_field("failure_reason", &self.failure_reason)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+
+    /// Lifecycle of a refund; a refund is PENDING only while its provider is being asked
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RefundStatus {
+        Pending,
+        Confirmed,
+        Failed,
+    }
+
+    impl std::fmt::Display for RefundStatus {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                RefundStatus::Pending => write!(f, "PENDING"),
+                RefundStatus::Confirmed => write!(f, "CONFIRMED"),
+                RefundStatus::Failed => write!(f, "FAILED"),
+            }
+        }
+    }
+}
+
+/// How a payment is made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    Online,
+}
+
+impl std::fmt::Display for PaymentMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaymentMethod::Cash => write!(f, "CASH"),
+            PaymentMethod::Card => write!(f, "CARD"),
+            PaymentMethod::Online => write!(f, "ONLINE"),
+        }
+    }
+}
+
+impl std::str::FromStr for PaymentMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CASH" => Ok(PaymentMethod::Cash),
+            "CARD" => Ok(PaymentMethod::Card),
+            "ONLINE" => Ok(PaymentMethod::Online),
+            _ => Err(format!("Invalid payment method: {}", s)),
+        }
+    }
+}
+
+/// Lifecycle of a payment; a payment is PENDING only while its provider is being asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentStatus {
+    Pending,
+    Captured,
+    Failed,
+    /// Everything captured has been given back
+    Refunded,
+}
+
+impl std::fmt::Display for PaymentStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaymentStatus::Pending => write!(f, "PENDING"),
+            PaymentStatus::Captured => write!(f, "CAPTURED"),
+            PaymentStatus::Failed => write!(f, "FAILED"),
+            PaymentStatus::Refunded => write!(f, "REFUNDED"),
+        }
+    }
+}
+
+impl std::str::FromStr for PaymentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(PaymentStatus::Pending),
+            "CAPTURED" => Ok(PaymentStatus::Captured),
+            "FAILED" => Ok(PaymentStatus::Failed),
+            "REFUNDED" => Ok(PaymentStatus::Refunded),
+            _ => Err(format!("Invalid payment status: {}", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_method(method: &str) -> Result<(), ValidationError> {
+    method
+        .parse::<PaymentMethod>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("method").with_message(e.into()))
+}
+
+fn validate_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_sign_negative() || amount.is_zero() || amount.scale() > 2 {
+        return Err(ValidationError::new("amount").with_message("Amount must be positive with at most two decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_tip(tip: &Decimal) -> Result<(), ValidationError> {
+    if tip.is_sign_negative() || tip.scale() > 2 {
+        return Err(ValidationError::new("tip").with_message("Tip must be non-negative with at most two decimals".into()));
+    }
+    Ok(())
+}
+
+// Request/Response DTOs
+/// Which part of the bill a payment settles
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub enum BillSplit {
+    /// The given lines, none of them settled by another payment
+    Items { line_ids: Vec<Uuid> },
+    /// One of `shares` equal parts of the bill; the last share absorbs rounding
+    Equal { shares: i32 },
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreatePaymentRequest {
+    #[validate(custom(function = "validate_method"))]
+    pub method: String,
+
+    /// Retrying with the same key returns the original payment instead of charging again
+    #[validate(length(min = 1, max = 100, message = "Idempotency key must be between 1 and 100 characters"))]
+    pub idempotency_key: String,
+
+    /// Settle part of the bill; without it or `split` the whole balance is paid
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: Option<Decimal>,
+
+    pub split: Option<BillSplit>,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_tip"))]
+    pub tip: Decimal,
+
+    /// Cash handed over, to work out the change
+    #[validate(custom(function = "validate_amount"))]
+    pub tendered: Option<Decimal>,
+
+    /// Open register session taking the payment; required for cash
+    pub register_session_id: Option<Uuid>,
+
+    /// Card terminal to charge
+    #[validate(length(min = 1, max = 100, message = "Terminal ID must be between 1 and 100 characters"))]
+    pub terminal_id: Option<String>,
+
+    /// Payment token obtained by the client from the online gateway
+    #[validate(length(min = 1, max = 500, message = "Token must be between 1 and 500 characters"))]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateRefundRequest {
+    /// Defaults to everything still refundable
+    #[validate(custom(function = "validate_amount"))]
+    pub amount: Option<Decimal>,
+
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1 and 500 characters"))]
+    pub reason: String,
+
+    /// Open register session paying out the cash; required when refunding cash
+    pub register_session_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct BillQuery {
+    /// Also show the total and balance in this currency
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct PaymentQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub order_id: Option<Uuid>,
+    pub method: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+/// A payment with its refunds
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentView {
+    #[serde(flatten)]
+    pub payment: Model,
+    pub refunds: Vec<refund::Model>,
+}
+
+/// A line of a bill and whether a payment by item already settled it
+#[derive(Debug, Clone, Serialize)]
+pub struct BillLine {
+    pub line_id: Uuid,
+    pub name: String,
+    pub tax_category: String,
+    pub quantity: i32,
+    #[serde(serialize_with = "serialize_amount")]
+    pub line_total: Money,
+    pub settled: bool,
+}
+
+/// What is owed on an order and what has been paid
+#[derive(Debug, Clone, Serialize)]
+pub struct Bill {
+    pub order_id: Uuid,
+    pub order_status: String,
+    pub currency: String,
+    #[serde(serialize_with = "serialize_amount")]
+    pub subtotal: Money,
+    /// Taken off by the voucher on the order
+    #[serde(serialize_with = "serialize_amount")]
+    pub discount: Money,
+    /// Whether the line prices contain the tax or it is added to the total
+    pub prices_include_tax: bool,
+    #[serde(serialize_with = "serialize_amount")]
+    pub tax: Money,
+    #[serde(serialize_with = "serialize_amount")]
+    pub total: Money,
+    #[serde(serialize_with = "serialize_amount")]
+    pub paid: Money,
+    #[serde(serialize_with = "serialize_amount")]
+    pub tips: Money,
+    #[serde(serialize_with = "serialize_amount")]
+    pub refunded: Money,
+    #[serde(serialize_with = "serialize_amount")]
+    pub balance: Money,
+    pub lines: Vec<BillLine>,
+    pub taxes: Vec<TaxLine>,
+    pub payments: Vec<Model>,
+    /// Total and balance in the currency asked for, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exchange: Option<Exchange>,
+}
+
+/// A bill's amounts in another currency, for guests paying in it
+#[derive(Debug, Clone, Serialize)]
+pub struct Exchange {
+    pub currency: String,
+    /// Units of `currency` per unit of the branch's currency
+    pub rate: Decimal,
+    pub total: Decimal,
+    pub balance: Decimal,
+}
+
+/// Totals of one payment method over a period, for reconciliation
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MethodTotals {
+    pub method: String,
+    pub count: u64,
+    pub amount: Decimal,
+    pub tips: Decimal,
+    pub refunded: Decimal,
+    pub net: Decimal,
+}
+
+/// Captured payments of a branch over a period, per method
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentSummary {
+    pub branch_id: Uuid,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub methods: Vec<MethodTotals>,
+    pub failed: u64,
+}
+
+/// A payment about to be handed to its provider, with the amount already worked out
+#[derive(Debug, Clone)]
+pub struct NewPayment {
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub order_id: Uuid,
+    pub created_by: Uuid,
+    pub register_session_id: Option<Uuid>,
+    pub method: PaymentMethod,
+    pub provider: String,
+    pub amount: Money,
+    pub tip: Money,
+    pub currency: String,
+    pub idempotency_key: String,
+    pub split: Option<BillSplit>,
+    pub tendered: Option<Money>,
+}
@@ -0,0 +1,7 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod repository;
+pub mod provider;
+pub mod route;
+pub mod subscriber;
@@ -0,0 +1,336 @@
+use std::{collections::HashMap, sync::{Arc, Mutex}, time::Duration};
+
+use sea_orm::prelude::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use tracing::{error, info};
+
+use crate::{
+    common::{config::{PaymentConfig, Secret}, ApiError},
+    modules::payment::entity::PaymentMethod,
+};
+
+/// A charge handed to a provider
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureRequest {
+    pub payment_id: Uuid,
+    /// Providers must not charge twice for the same key
+    pub idempotency_key: String,
+    /// Amount and tip together
+    pub amount: Decimal,
+    pub currency: String,
+    pub tendered: Option<Decimal>,
+    pub terminal_id: Option<String>,
+    pub token: Option<String>,
+}
+
+/// Money to give back out of an earlier capture
+#[derive(Debug, Clone, Serialize)]
+pub struct RefundRequest {
+    pub refund_id: Uuid,
+    pub payment_id: Uuid,
+    /// Reference the provider returned for the capture
+    pub capture_reference: Option<String>,
+    pub amount: Decimal,
+    pub currency: String,
+}
+
+/// Why a provider did not move the money
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProviderError {
+    /// The charge was refused; retrying will not help
+    #[error("{0}")]
+    Declined(String),
+    /// The provider could not be reached or failed
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl From<ProviderError> for ApiError {
+    fn from(error: ProviderError) -> Self {
+        match error {
+            ProviderError::Declined(reason) => ApiError::PaymentDeclined(reason),
+            ProviderError::Unavailable(reason) => ApiError::ProviderUnavailable(reason),
+        }
+    }
+}
+
+/// Moves money for one payment method
+#[async_trait::async_trait]
+pub trait PaymentProvider: Send + Sync + std::fmt::Debug {
+    /// Name recorded on payments for reconciliation
+    fn name(&self) -> &'static str;
+
+    /// Charge the amount, returning the provider's reference
+    async fn capture(&self, request: &CaptureRequest) -> Result<String, ProviderError>;
+
+    /// Give back part of a capture, returning the provider's reference
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError>;
+}
+
+/// Cash counted at the register; nothing leaves the building
+#[derive(Debug, Default)]
+pub struct CashProvider;
+
+#[async_trait::async_trait]
+impl PaymentProvider for CashProvider {
+    fn name(&self) -> &'static str {
+        "cash"
+    }
+
+    async fn capture(&self, request: &CaptureRequest) -> Result<String, ProviderError> {
+        if request.tendered.is_some_and(|tendered| tendered < request.amount) {
+            return Err(ProviderError::Declined("Not enough cash tendered".to_string()));
+        }
+        Ok(format!("cash-{}", request.payment_id))
+    }
+
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError> {
+        Ok(format!("cash-refund-{}", request.refund_id))
+    }
+}
+
+/// JSON answer of a payment gateway
+#[derive(Debug, Deserialize)]
+struct GatewayResponse {
+    id: String,
+    status: String,
+    message: Option<String>,
+}
+
+/// HTTP client for a payment gateway exposing `/captures` and `/refunds`
+#[derive(Debug)]
+struct Gateway {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Secret<String>,
+}
+
+impl Gateway {
+    fn new(base_url: &str, api_key: &str, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder().timeout(timeout).build().unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: Secret::new(api_key.to_string()),
+        }
+    }
+
+    async fn post<T: Serialize>(&self, path: &str, idempotency_key: &str, body: &T) -> Result<String, ProviderError> {
+        let url = format!("{}{}", self.base_url, path);
+        info!("Calling payment gateway {}", url);
+
+        let response = self.client
+            .post(&url)
+            .bearer_auth(self.api_key.expose())
+            .header("Idempotency-Key", idempotency_key)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Payment gateway {} unreachable: {}", url, e);
+                ProviderError::Unavailable(e.to_string())
+            })?;
+
+        let status = response.status();
+        if status.is_server_error() {
+            return Err(ProviderError::Unavailable(format!("Gateway answered {}", status)));
+        }
+
+        let body: GatewayResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::Unavailable(format!("Invalid gateway response: {}", e)))?;
+
+        if !status.is_success() || body.status != "succeeded" {
+            return Err(ProviderError::Declined(body.message.unwrap_or(body.status)));
+        }
+
+        Ok(body.id)
+    }
+
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError> {
+        let capture = request
+            .capture_reference
+            .as_deref()
+            .ok_or_else(|| ProviderError::Declined("Payment has no capture to refund".to_string()))?;
+
+        let body = serde_json::json!({
+            "capture_id": capture,
+            "amount": request.amount,
+            "currency": request.currency,
+        });
+        self.post("/refunds", &request.refund_id.to_string(), &body).await
+    }
+}
+
+/// Card terminals driven through the terminal gateway
+#[derive(Debug)]
+pub struct CardTerminalProvider {
+    gateway: Gateway,
+}
+
+impl CardTerminalProvider {
+    pub fn new(base_url: &str, api_key: &str, timeout: Duration) -> Self {
+        Self { gateway: Gateway::new(base_url, api_key, timeout) }
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProvider for CardTerminalProvider {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    async fn capture(&self, request: &CaptureRequest) -> Result<String, ProviderError> {
+        let terminal_id = request
+            .terminal_id
+            .as_deref()
+            .ok_or_else(|| ProviderError::Declined("terminal_id is required for card payments".to_string()))?;
+
+        let body = serde_json::json!({
+            "terminal_id": terminal_id,
+            "amount": request.amount,
+            "currency": request.currency,
+            "reference": request.payment_id,
+        });
+        self.gateway.post("/captures", &request.idempotency_key, &body).await
+    }
+
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError> {
+        self.gateway.refund(request).await
+    }
+}
+
+/// Online payments charged with a token from the gateway's client SDK
+#[derive(Debug)]
+pub struct OnlineProvider {
+    gateway: Gateway,
+}
+
+impl OnlineProvider {
+    pub fn new(base_url: &str, api_key: &str, timeout: Duration) -> Self {
+        Self { gateway: Gateway::new(base_url, api_key, timeout) }
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProvider for OnlineProvider {
+    fn name(&self) -> &'static str {
+        "online"
+    }
+
+    async fn capture(&self, request: &CaptureRequest) -> Result<String, ProviderError> {
+        let token = request
+            .token
+            .as_deref()
+            .ok_or_else(|| ProviderError::Declined("token is required for online payments".to_string()))?;
+
+        let body = serde_json::json!({
+            "token": token,
+            "amount": request.amount,
+            "currency": request.currency,
+            "reference": request.payment_id,
+        });
+        self.gateway.post("/captures", &request.idempotency_key, &body).await
+    }
+
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError> {
+        self.gateway.refund(request).await
+    }
+}
+
+/// Provider for tests and development that approves or declines everything without moving money
+#[derive(Debug)]
+pub struct FakeProvider {
+    decline: Option<String>,
+    captures: Mutex<HashMap<String, String>>,
+}
+
+impl FakeProvider {
+    /// A provider approving every charge
+    pub fn new() -> Self {
+        Self { decline: None, captures: Mutex::new(HashMap::new()) }
+    }
+
+    /// A provider declining every charge with the given reason
+    pub fn declining(reason: &str) -> Self {
+        Self { decline: Some(reason.to_string()), captures: Mutex::new(HashMap::new()) }
+    }
+
+    /// Number of distinct charges made, retries with the same key counting once
+    pub fn capture_count(&self) -> usize {
+        self.captures.lock().map(|captures| captures.len()).unwrap_or_default()
+    }
+}
+
+impl Default for FakeProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentProvider for FakeProvider {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    async fn capture(&self, request: &CaptureRequest) -> Result<String, ProviderError> {
+        if let Some(ref reason) = self.decline {
+            return Err(ProviderError::Declined(reason.clone()));
+        }
+
+        let mut captures = self.captures
+            .lock()
+            .map_err(|_| ProviderError::Unavailable("Fake provider poisoned".to_string()))?;
+        let reference = captures
+            .entry(request.idempotency_key.clone())
+            .or_insert_with(|| format!("fake-{}", Uuid::new_v4()));
+
+        Ok(reference.clone())
+    }
+
+    async fn refund(&self, request: &RefundRequest) -> Result<String, ProviderError> {
+        Ok(format!("fake-refund-{}", request.refund_id))
+    }
+}
+
+/// The provider behind each payment method
+#[derive(Debug, Clone)]
+pub struct PaymentProviders {
+    cash: Arc<dyn PaymentProvider>,
+    card: Arc<dyn PaymentProvider>,
+    online: Arc<dyn PaymentProvider>,
+}
+
+impl PaymentProviders {
+    /// Use the given providers, e.g. fakes in tests
+    pub fn new(cash: Arc<dyn PaymentProvider>, card: Arc<dyn PaymentProvider>, online: Arc<dyn PaymentProvider>) -> Self {
+        Self { cash, card, online }
+    }
+
+    /// Build the configured providers
+    pub fn from_config(config: &PaymentConfig) -> Self {
+        let timeout = Duration::from_secs(config.timeout_seconds);
+
+        let card: Arc<dyn PaymentProvider> = match config.card_provider.as_str() {
+            "fake" => Arc::new(FakeProvider::new()),
+            _ => Arc::new(CardTerminalProvider::new(&config.terminal_url, config.terminal_api_key.expose(), timeout)),
+        };
+        let online: Arc<dyn PaymentProvider> = match config.online_provider.as_str() {
+            "fake" => Arc::new(FakeProvider::new()),
+            _ => Arc::new(OnlineProvider::new(&config.online_url, config.online_api_key.expose(), timeout)),
+        };
+
+        Self::new(Arc::new(CashProvider), card, online)
+    }
+
+    /// The provider handling a payment method
+    pub fn for_method(&self, method: PaymentMethod) -> &Arc<dyn PaymentProvider> {
+        match method {
+            PaymentMethod::Cash => &self.cash,
+            PaymentMethod::Card => &self.card,
+            PaymentMethod::Online => &self.online,
+        }
+    }
+}
@@ -0,0 +1,510 @@
+use anyhow::Result;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        order::entity::Entity as OrderEntity,
+        payment::entity::{
+            refund::{self, RefundStatus}, ActiveModel, BillSplit, Column, Entity as PaymentEntity, Model as Payment, NewPayment,
+            PaymentQuery, PaymentStatus,
+        },
+    },
+    common::{
+        money::Money,
+        outbox::{self, EventMetadata, NewDomainEvent},
+        ApiError, RequestContext,
+    },
+};
+
+/// Payment repository for database operations
+#[derive(Debug, Clone)]
+pub struct PaymentRepository {
+    db: DatabaseConnection,
+}
+
+impl PaymentRepository {
+    /// Create a new payment repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search payments of a branch, newest first, returning one page and the total count
+    pub async fn search(&self, branch_id: Uuid, query: &PaymentQuery, page: u64, per_page: u64) -> Result<(Vec<Payment>, u64), ApiError> {
+        let mut select = PaymentEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some(order_id) = query.order_id {
+            select = select.filter(Column::OrderId.eq(order_id));
+        }
+        if let Some(ref method) = query.method {
+            select = select.filter(Column::Method.eq(method.as_str()));
+        }
+        if let Some(ref status) = query.status {
+            select = select.filter(Column::Status.eq(status.as_str()));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(Column::CreatedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count payments of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let payments = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch payments of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((payments, total))
+    }
+
+    /// Get a payment by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Payment, ApiError> {
+        info!("Fetching payment with ID: {}", id);
+
+        let payment = PaymentEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payment with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match payment {
+            Some(payment) => Ok(payment),
+            None => Err(ApiError::NotFound("Payment not found".to_string())),
+        }
+    }
+
+    /// Get the payment made with an idempotency key, if any
+    pub async fn get_by_key(&self, account_id: Uuid, idempotency_key: &str) -> Result<Option<Payment>, ApiError> {
+        PaymentEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::IdempotencyKey.eq(idempotency_key))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payment by idempotency key: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the payments of an order, oldest first
+    pub async fn get_by_order_id(&self, order_id: Uuid) -> Result<Vec<Payment>, ApiError> {
+        PaymentEntity::find()
+            .filter(Column::OrderId.eq(order_id))
+            .order_by_asc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payments of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get payments by their IDs
+    pub async fn get_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<Payment>, ApiError> {
+        PaymentEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payments by IDs: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Payments taken at a register session, captured or failed
+    pub async fn get_by_register_session_id(&self, session_id: Uuid) -> Result<Vec<Payment>, ApiError> {
+        PaymentEntity::find()
+            .filter(Column::RegisterSessionId.eq(session_id))
+            .filter(Column::Status.ne(PaymentStatus::Pending.to_string()))
+            .order_by_asc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payments of register session {}: {}", session_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Confirmed refunds paid out of a register session's drawer
+    pub async fn get_refunds_by_register_session_id(&self, session_id: Uuid) -> Result<Vec<refund::Model>, ApiError> {
+        refund::Entity::find()
+            .filter(refund::Column::RegisterSessionId.eq(session_id))
+            .filter(refund::Column::Status.eq(RefundStatus::Confirmed.to_string()))
+            .order_by_asc(refund::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch refunds of register session {}: {}", session_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Payments of a branch captured or failed within a period
+    pub async fn get_in_period(
+        &self,
+        branch_id: Uuid,
+        from: Option<DateTimeWithTimeZone>,
+        to: Option<DateTimeWithTimeZone>,
+    ) -> Result<Vec<Payment>, ApiError> {
+        let mut select = PaymentEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::Status.ne(PaymentStatus::Pending.to_string()));
+
+        if let Some(from) = from {
+            select = select.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(Column::CreatedAt.lt(to));
+        }
+
+        select
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payments of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Confirmed refunds of a branch made within a period
+    pub async fn get_refunds_in_period(
+        &self,
+        branch_id: Uuid,
+        from: Option<DateTimeWithTimeZone>,
+        to: Option<DateTimeWithTimeZone>,
+    ) -> Result<Vec<refund::Model>, ApiError> {
+        let mut select = refund::Entity::find()
+            .filter(refund::Column::BranchId.eq(branch_id))
+            .filter(refund::Column::Status.eq(RefundStatus::Confirmed.to_string()));
+
+        if let Some(from) = from {
+            select = select.filter(refund::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(refund::Column::CreatedAt.lt(to));
+        }
+
+        select
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch refunds of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the refunds of the given payments whatever their status, oldest first
+    pub async fn get_refunds(&self, payment_ids: Vec<Uuid>) -> Result<Vec<refund::Model>, ApiError> {
+        refund::Entity::find()
+            .filter(refund::Column::PaymentId.is_in(payment_ids))
+            .order_by_asc(refund::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch refunds: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Record a payment as pending, making sure it fits the balance of the order.
+    /// The order row stays locked while checking so concurrent payments cannot overpay.
+    pub async fn create_pending(&self, data: NewPayment, total: Money) -> Result<Payment, ApiError> {
+        info!("Recording pending {} payment for order {}", data.method, data.order_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        OrderEntity::find_by_id(data.order_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock order {}: {}", data.order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+
+        let existing = PaymentEntity::find()
+            .filter(Column::OrderId.eq(data.order_id))
+            .filter(Column::Status.is_in([PaymentStatus::Pending.to_string(), PaymentStatus::Captured.to_string()]))
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch payments of order {}: {}", data.order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let reserved = existing
+            .iter()
+            .map(|payment| if payment.status == PaymentStatus::Pending.to_string() { Ok(payment.amount) } else { payment.settled() })
+            .collect::<Result<Vec<_>, _>>()?;
+        let balance = total.checked_sub(Money::sum(total.currency(), reserved)?)?;
+        if data.amount > balance {
+            return Err(ApiError::Conflict(format!("Payment exceeds the balance of {}", balance.amount())));
+        }
+        let change = data.tendered.map(|tendered| tendered.checked_sub(data.amount)?.checked_sub(data.tip)).transpose()?;
+
+        let (split, line_ids, shares) = match data.split {
+            Some(BillSplit::Items { line_ids }) => {
+                if existing.iter().any(|payment| payment.line_ids.iter().any(|id| line_ids.contains(id))) {
+                    return Err(ApiError::Conflict("A line is already paid".to_string()));
+                }
+                (Some("ITEMS".to_string()), line_ids, None)
+            }
+            Some(BillSplit::Equal { shares }) => (Some("EQUAL".to_string()), Vec::new(), Some(shares)),
+            None => (None, Vec::new(), None),
+        };
+
+        let now = chrono::Utc::now().fixed_offset();
+        let payment = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(data.account_id),
+            branch_id: Set(data.branch_id),
+            order_id: Set(data.order_id),
+            created_by: Set(data.created_by),
+            register_session_id: Set(data.register_session_id),
+            method: Set(data.method.to_string()),
+            provider: Set(data.provider),
+            status: Set(PaymentStatus::Pending.to_string()),
+            amount: Set(data.amount),
+            tip: Set(data.tip),
+            refunded: Set(Money::zero(data.amount.currency())),
+            currency: Set(data.currency),
+            idempotency_key: Set(data.idempotency_key),
+            split: Set(split),
+            line_ids: Set(line_ids),
+            shares: Set(shares),
+            tendered: Set(data.tendered),
+            change: Set(change),
+            provider_reference: Set(None),
+            failure_reason: Set(None),
+            captured_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let payment = payment.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to record payment for order {}: {}", data.order_id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A payment with this idempotency key is already being processed".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit payment for order {}: {}", data.order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(payment)
+    }
+
+    /// Mark a pending payment as captured by its provider, recording `payment.captured` with it
+    pub async fn capture(&self, ctx: &RequestContext, payment: Payment, reference: String) -> Result<Payment, ApiError> {
+        info!("Payment {} captured as {}", payment.id, reference);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let before = payment.clone();
+        let mut payment: ActiveModel = payment.into();
+        payment.status = Set(PaymentStatus::Captured.to_string());
+        payment.provider_reference = Set(Some(reference));
+        payment.captured_at = Set(Some(now));
+        payment.updated_at = Set(now);
+
+        self.finish(ctx, outbox::kind::PAYMENT_CAPTURED, before, payment).await
+    }
+
+    /// Mark a pending payment as failed, releasing its part of the balance and recording `payment.failed` with it
+    pub async fn fail(&self, ctx: &RequestContext, payment: Payment, reason: String) -> Result<Payment, ApiError> {
+        info!("Payment {} failed: {}", payment.id, reason);
+
+        let before = payment.clone();
+        let mut payment: ActiveModel = payment.into();
+        payment.status = Set(PaymentStatus::Failed.to_string());
+        payment.failure_reason = Set(Some(reason));
+        payment.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        self.finish(ctx, outbox::kind::PAYMENT_FAILED, before, payment).await
+    }
+
+    /// Reserve a refund as pending before the provider is asked. The payment row stays locked while checking
+    /// that the refund fits what is left once the confirmed and pending refunds are taken off, so concurrent
+    /// refunds cannot give back more than was paid.
+    pub async fn reserve_refund(&self, refund: refund::Model) -> Result<refund::Model, ApiError> {
+        info!("Reserving refund of {} for payment {}", refund.amount, refund.payment_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let payment = Self::lock(&txn, refund.payment_id).await?;
+        if payment.status != PaymentStatus::Captured.to_string() {
+            return Err(ApiError::Conflict(format!("Payment is {}", payment.status)));
+        }
+
+        let pending = refund::Entity::find()
+            .filter(refund::Column::PaymentId.eq(payment.id))
+            .filter(refund::Column::Status.eq(RefundStatus::Pending.to_string()))
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch pending refunds of payment {}: {}", payment.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let currency = payment.amount.currency();
+        let pending = pending
+            .iter()
+            .map(|refund| Money::from_decimal(refund.amount, currency))
+            .collect::<Result<Vec<_>, _>>()?;
+        let refundable = payment.refundable()?.checked_sub(Money::sum(currency, pending)?)?;
+        if Money::from_decimal(refund.amount, currency)? > refundable {
+            return Err(ApiError::Conflict(format!("At most {} can be refunded", refundable.amount())));
+        }
+
+        let mut refund = refund::ActiveModel::from(refund).reset_all();
+        refund.status = Set(RefundStatus::Pending.to_string());
+        refund.provider_reference = Set(None);
+        refund.failure_reason = Set(None);
+        let refund = refund.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to reserve refund: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit refund of payment {}: {}", payment.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(refund)
+    }
+
+    /// Confirm a pending refund the provider has made, taking it off the payment and recording `payment.refunded` with it
+    pub async fn confirm_refund(&self, ctx: &RequestContext, refund: refund::Model, reference: String) -> Result<(Payment, refund::Model), ApiError> {
+        info!("Refund {} of payment {} made as {}", refund.id, refund.payment_id, reference);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let before = Self::lock(&txn, refund.payment_id).await?;
+        let refunded = before.refunded.checked_add(Money::from_decimal(refund.amount, before.amount.currency())?)?;
+        let fully = refunded == before.amount.checked_add(before.tip)?;
+        let mut payment: ActiveModel = before.clone().into();
+        payment.refunded = Set(refunded);
+        if fully {
+            payment.status = Set(PaymentStatus::Refunded.to_string());
+        }
+        payment.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        let payment = payment.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to update refunded amount of payment {}: {}", refund.payment_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let mut refund: refund::ActiveModel = refund.into();
+        refund.status = Set(RefundStatus::Confirmed.to_string());
+        refund.provider_reference = Set(Some(reference));
+        let refund = refund.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to confirm refund of payment {}: {}", payment.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        outbox::record(&txn, Self::event(ctx, outbox::kind::PAYMENT_REFUNDED, &before, &payment)).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit refund of payment {}: {}", payment.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((payment, refund))
+    }
+
+    /// Mark a pending refund the provider refused as failed, releasing its part of the payment
+    pub async fn fail_refund(&self, refund: refund::Model, reason: String) -> Result<refund::Model, ApiError> {
+        info!("Refund {} of payment {} failed: {}", refund.id, refund.payment_id, reason);
+
+        let id = refund.id;
+        let mut refund: refund::ActiveModel = refund.into();
+        refund.status = Set(RefundStatus::Failed.to_string());
+        refund.failure_reason = Set(Some(reason));
+
+        refund.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to mark refund {} as failed: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Store the outcome of a capture along with the event describing it
+    async fn finish(&self, ctx: &RequestContext, kind: &str, before: Payment, payment: ActiveModel) -> Result<Payment, ApiError> {
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let payment = payment.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to mark payment {} as {}: {}", before.id, kind, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        outbox::record(&txn, Self::event(ctx, kind, &before, &payment)).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit payment {}: {}", payment.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(payment)
+    }
+
+    async fn lock(txn: &DatabaseTransaction, id: Uuid) -> Result<Payment, ApiError> {
+        PaymentEntity::find_by_id(id)
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock payment {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .ok_or_else(|| ApiError::NotFound("Payment not found".to_string()))
+    }
+
+    fn event(ctx: &RequestContext, kind: &str, before: &Payment, after: &Payment) -> NewDomainEvent {
+        NewDomainEvent::new(kind, after.account_id, (outbox::aggregate::PAYMENT, after.id), after, EventMetadata::from(ctx))
+            .with_previous(before)
+    }
+}
@@ -0,0 +1,27 @@
+use axum::{
+    routing::{get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create payment routes for the register
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orders/:id/bill", get(get_bill))
+        .route("/orders/:id/payments", post(create))
+        .route("/payments/:id", get(get_by_id))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "CASH_REGISTER"])))
+}
+
+/// Create payment reconciliation and refund routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/payments", get(get_all))
+        .route("/payments/summary", get(get_summary))
+        .route("/payments/:id/refunds", post(refund))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
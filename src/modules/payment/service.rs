@@ -0,0 +1,834 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::{error, info, warn};
+
+use crate::{
+    common::{
+        events::{topic, Event, SharedEventBus},
+        money::{rates::SharedRateSource, Currency, Money},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        order::{
+            entity::{line, Model as Order, OrderStatus},
+            service::OrderService,
+        },
+        payment::{
+            entity::{
+                refund::{self, RefundStatus}, Bill, BillLine, BillQuery, BillSplit, CreatePaymentRequest, CreateRefundRequest, MethodTotals, Model as Payment,
+                Exchange, NewPayment, PaymentMethod, PaymentQuery, PaymentStatus, PaymentSummary, PaymentView,
+            },
+            provider::{CaptureRequest, PaymentProviders, RefundRequest},
+            repository::PaymentRepository,
+        },
+        register::repository::RegisterRepository,
+    },
+};
+
+/// Payment service layer for business logic
+#[derive(Debug, Clone)]
+pub struct PaymentService {
+    repository: PaymentRepository,
+    register_repository: RegisterRepository,
+    branch_repository: BranchRepository,
+    order_service: OrderService,
+    providers: PaymentProviders,
+    rates: SharedRateSource,
+    events: SharedEventBus,
+}
+
+impl PaymentService {
+    /// Create a new payment service
+    pub fn new(
+        repository: PaymentRepository,
+        register_repository: RegisterRepository,
+        branch_repository: BranchRepository,
+        order_service: OrderService,
+        providers: PaymentProviders,
+        rates: SharedRateSource,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            repository,
+            register_repository,
+            branch_repository,
+            order_service,
+            providers,
+            rates,
+            events,
+        }
+    }
+
+    /// What is owed on an order, what has been paid and which lines are settled
+    pub async fn get_bill(&self, actor: &UserInfo, order_id: Uuid, query: BillQuery) -> Result<Bill, ApiError> {
+        let view = self.order_service.get_by_id(actor, order_id).await?;
+        let payments = self.repository.get_by_order_id(view.order.id).await?;
+
+        let mut bill = Self::bill(view.order, view.lines, payments)?;
+        if let Some(currency) = query.currency {
+            bill.exchange = Some(self.exchange(&bill, currency.parse().map_err(ApiError::InvalidInput)?).await?);
+        }
+        Ok(bill)
+    }
+
+    /// Get a payment of the caller's account with its refunds
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<PaymentView, ApiError> {
+        let payment = self.get_owned(actor, id).await?;
+        self.view(payment).await
+    }
+
+    /// Payments of a branch for reconciliation, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: PaymentQuery) -> Result<Page<Payment>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref method) = query.method {
+            method.parse::<PaymentMethod>().map_err(ApiError::InvalidInput)?;
+        }
+        if let Some(ref status) = query.status {
+            status.parse::<PaymentStatus>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Totals per payment method over a period, refunds counted when they were made
+    pub async fn summary(&self, actor: &UserInfo, query: PaymentQuery) -> Result<PaymentSummary, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+
+        let payments = self.repository.get_in_period(branch.id, query.from, query.to).await?;
+        let refunds = self.repository.get_refunds_in_period(branch.id, query.from, query.to).await?;
+
+        // Refunds may belong to payments taken before the period
+        let known: HashSet<Uuid> = payments.iter().map(|payment| payment.id).collect();
+        let missing: Vec<Uuid> = refunds.iter().map(|r| r.payment_id).filter(|id| !known.contains(id)).collect();
+        let mut methods: HashMap<Uuid, String> = payments.iter().map(|payment| (payment.id, payment.method.clone())).collect();
+        if !missing.is_empty() {
+            for payment in self.repository.get_by_ids(missing).await? {
+                methods.insert(payment.id, payment.method);
+            }
+        }
+
+        let mut totals: BTreeMap<String, MethodTotals> = BTreeMap::new();
+        let mut failed = 0;
+        for payment in &payments {
+            if payment.status == PaymentStatus::Failed.to_string() {
+                failed += 1;
+                continue;
+            }
+            let entry = totals.entry(payment.method.clone()).or_default();
+            entry.count += 1;
+            entry.amount += payment.amount.amount();
+            entry.tips += payment.tip.amount();
+        }
+        for refund in &refunds {
+            if let Some(method) = methods.get(&refund.payment_id) {
+                totals.entry(method.clone()).or_default().refunded += refund.amount;
+            }
+        }
+
+        let methods = totals
+            .into_iter()
+            .map(|(method, mut entry)| {
+                entry.method = method;
+                entry.net = entry.amount + entry.tips - entry.refunded;
+                entry
+            })
+            .collect();
+
+        Ok(PaymentSummary { branch_id: branch.id, from: query.from, to: query.to, methods, failed })
+    }
+
+    /// Take a payment for a served order, settling the whole balance, an amount, some lines or an equal share.
+    /// Retrying with the same idempotency key returns the original payment without charging again.
+    pub async fn pay(&self, ctx: &RequestContext, actor: &UserInfo, order_id: Uuid, data: CreatePaymentRequest) -> Result<PaymentView, ApiError> {
+        info!("Taking {} payment for order {}", data.method, order_id);
+
+        let account_id = actor.parsed_account_id()?;
+        if let Some(existing) = self.repository.get_by_key(account_id, &data.idempotency_key).await? {
+            return self.replay(existing, order_id).await;
+        }
+
+        let view = self.order_service.get_by_id(actor, order_id).await?;
+        let order = view.order;
+        if order.status != OrderStatus::Served.to_string() {
+            return Err(ApiError::Conflict(format!("Order is {}; bills are settled once served", order.status)));
+        }
+
+        let method: PaymentMethod = data.method.parse().map_err(ApiError::InvalidInput)?;
+        let provider = self.providers.for_method(method).clone();
+        let payments = self.repository.get_by_order_id(order.id).await?;
+
+        let currency = order.currency()?;
+        let bill = Self::bill(order.clone(), view.lines, payments)?;
+        let amount = Self::amount(&bill, &data)?;
+        let tip = Money::from_decimal(data.tip, currency)?;
+        if data.tendered.is_some() && method != PaymentMethod::Cash {
+            return Err(ApiError::InvalidInput("tendered only applies to cash payments".to_string()));
+        }
+        let tendered = data.tendered.map(|tendered| Money::from_decimal(tendered, currency)).transpose()?;
+        let register_session_id = self.get_drawer(method, order.branch_id, data.register_session_id).await?;
+
+        let pending = self.repository
+            .create_pending(
+                NewPayment {
+                    account_id: order.account_id,
+                    branch_id: order.branch_id,
+                    order_id: order.id,
+                    created_by: actor.parsed_id()?,
+                    register_session_id,
+                    method,
+                    provider: provider.name().to_string(),
+                    amount,
+                    tip,
+                    currency: order.currency.clone(),
+                    idempotency_key: data.idempotency_key.clone(),
+                    split: data.split,
+                    tendered,
+                },
+                bill.total,
+            )
+            .await?;
+
+        let request = CaptureRequest {
+            payment_id: pending.id,
+            idempotency_key: data.idempotency_key,
+            amount: amount.checked_add(tip)?.amount(),
+            currency: order.currency.clone(),
+            tendered: data.tendered,
+            terminal_id: data.terminal_id,
+            token: data.token,
+        };
+
+        let payment = match provider.capture(&request).await {
+            Ok(reference) => self.repository.capture(ctx, pending, reference).await?,
+            Err(e) => {
+                warn!("{} declined payment {}: {}", provider.name(), pending.id, e);
+                self.repository.fail(ctx, pending, e.to_string()).await?;
+                return Err(e.into());
+            }
+        };
+
+        // The money is taken: from here on nothing fails the request. An order left open is closed by the
+        // settlement subscriber from the `payment.captured` event.
+        self.publish("payment.captured", &order, &payment).await;
+        if let Err(e) = self.settle_order(ctx, actor, order.id).await {
+            error!("Failed to close order {} after payment {}, leaving it to the settlement subscriber: {}", order.id, payment.id, e);
+        }
+
+        Ok(PaymentView { payment, refunds: Vec::new() })
+    }
+
+    /// Close a served order once its captured payments cover the total; does nothing otherwise, so it is
+    /// safe to repeat
+    pub async fn settle_order(&self, ctx: &RequestContext, actor: &UserInfo, order_id: Uuid) -> Result<(), ApiError> {
+        let view = self.order_service.get_by_id(actor, order_id).await?;
+        if view.order.status != OrderStatus::Served.to_string() {
+            return Ok(());
+        }
+
+        let payments = self.repository.get_by_order_id(view.order.id).await?;
+        let bill = Self::bill(view.order, view.lines, payments)?;
+        if bill.paid < bill.total {
+            return Ok(());
+        }
+
+        self.order_service.mark_paid(ctx, actor, order_id).await?;
+        Ok(())
+    }
+
+    /// Give money back out of a captured payment; the order stays closed once paid.
+    /// The refund is reserved before the provider is asked, so concurrent refunds cannot exceed the payment.
+    pub async fn refund(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: CreateRefundRequest) -> Result<PaymentView, ApiError> {
+        info!("Refunding payment {}", id);
+
+        let payment = self.get_owned(actor, id).await?;
+        if payment.status != PaymentStatus::Captured.to_string() {
+            return Err(ApiError::Conflict(format!("Payment is {}", payment.status)));
+        }
+
+        let refundable = payment.refundable()?;
+        let amount = match data.amount {
+            Some(amount) => Money::from_decimal(amount, refundable.currency())?,
+            None => refundable,
+        };
+        if amount > refundable {
+            return Err(ApiError::Conflict(format!("At most {} can be refunded", refundable.amount())));
+        }
+
+        let method: PaymentMethod = payment.method.parse().map_err(ApiError::InvalidInput)?;
+        let provider = self.providers.for_method(method);
+        let register_session_id = self.get_drawer(method, payment.branch_id, data.register_session_id).await?;
+
+        let pending = self.repository
+            .reserve_refund(refund::Model {
+                id: Uuid::new_v4(),
+                payment_id: payment.id,
+                account_id: payment.account_id,
+                branch_id: payment.branch_id,
+                order_id: payment.order_id,
+                created_by: actor.parsed_id()?,
+                register_session_id,
+                amount: amount.amount(),
+                reason: data.reason,
+                status: RefundStatus::Pending.to_string(),
+                provider_reference: None,
+                failure_reason: None,
+                created_at: chrono::Utc::now().fixed_offset(),
+            })
+            .await?;
+
+        let request = RefundRequest {
+            refund_id: pending.id,
+            payment_id: payment.id,
+            capture_reference: payment.provider_reference.clone(),
+            amount: amount.amount(),
+            currency: payment.currency.clone(),
+        };
+
+        let (payment, _) = match provider.refund(&request).await {
+            Ok(reference) => self.repository.confirm_refund(ctx, pending, reference).await?,
+            Err(e) => {
+                warn!("{} refused refund {} of payment {}: {}", provider.name(), pending.id, id, e);
+                self.repository.fail_refund(pending, e.to_string()).await?;
+                return Err(e.into());
+            }
+        };
+
+        let order = self.order_service.get_by_id(actor, payment.order_id).await?.order;
+        self.publish("payment.refunded", &order, &payment).await;
+
+        self.view(payment).await
+    }
+
+    /// Answer a retried capture with the payment the key was first used for
+    async fn replay(&self, existing: Payment, order_id: Uuid) -> Result<PaymentView, ApiError> {
+        if existing.order_id != order_id {
+            return Err(ApiError::Conflict("Idempotency key was already used for another order".to_string()));
+        }
+
+        match existing.status.parse().map_err(ApiError::InvalidInput)? {
+            PaymentStatus::Pending => Err(ApiError::Conflict("Payment is still being processed".to_string())),
+            PaymentStatus::Failed => Err(ApiError::PaymentDeclined(existing.failure_reason.unwrap_or_default())),
+            PaymentStatus::Captured | PaymentStatus::Refunded => {
+                info!("Replaying payment {} for its idempotency key", existing.id);
+                self.view(existing).await
+            }
+        }
+    }
+
+    /// Work out what a payment settles of the bill
+    fn amount(bill: &Bill, data: &CreatePaymentRequest) -> Result<Money, ApiError> {
+        let currency = bill.total.currency();
+        let amount = match (&data.split, data.amount) {
+            (Some(_), Some(_)) => {
+                return Err(ApiError::InvalidInput("Give either amount or split, not both".to_string()));
+            }
+            (None, Some(amount)) => Money::from_decimal(amount, currency)?,
+            (None, None) => bill.balance,
+            (Some(BillSplit::Items { line_ids }), None) => {
+                let mut seen = HashSet::new();
+                let mut amount = Money::zero(currency);
+                for id in line_ids {
+                    let line = bill.lines
+                        .iter()
+                        .find(|line| line.line_id == *id)
+                        .ok_or_else(|| ApiError::NotFound("Order line not found".to_string()))?;
+                    if line.settled || !seen.insert(id) {
+                        return Err(ApiError::Conflict(format!("{} is already paid", line.name)));
+                    }
+                    // Tax added on top of the prices is paid with the lines it falls on
+                    let due = if bill.prices_include_tax {
+                        line.line_total
+                    } else {
+                        let rate = bill.taxes
+                            .iter()
+                            .find(|tax| tax.tax_category == line.tax_category)
+                            .map_or(Decimal::ZERO, |tax| tax.rate);
+                        Money::rounded(line.line_total.amount() * (Decimal::from(100) + rate) / Decimal::from(100), currency)?
+                    };
+                    amount = amount.checked_add(due)?;
+                }
+                // A discount on the order comes off whichever lines are paid last
+                if amount > bill.balance { bill.balance } else { amount }
+            }
+            (Some(BillSplit::Equal { shares }), None) => {
+                if *shares < 2 {
+                    return Err(ApiError::InvalidInput("A bill is split into at least 2 shares".to_string()));
+                }
+                let shares = i64::from(*shares);
+                let share = Money::new(bill.total.minor_units().div_euclid(shares), currency);
+
+                // Less than a minor unit per share left means this is the last share
+                if bill.balance.checked_sub(share)?.minor_units() < shares {
+                    bill.balance
+                } else {
+                    share
+                }
+            }
+        };
+
+        if amount.is_negative() || amount.is_zero() {
+            return Err(ApiError::Conflict("Nothing left to pay".to_string()));
+        }
+        if amount > bill.balance {
+            return Err(ApiError::Conflict(format!("Payment exceeds the balance of {}", bill.balance.amount())));
+        }
+
+        Ok(amount)
+    }
+
+    fn bill(order: Order, lines: Vec<line::Model>, payments: Vec<Payment>) -> Result<Bill, ApiError> {
+        let currency = order.currency()?;
+        let live: Vec<&Payment> = payments
+            .iter()
+            .filter(|payment| payment.status == PaymentStatus::Captured.to_string() || payment.status == PaymentStatus::Pending.to_string())
+            .collect();
+        let settled_lines: HashSet<Uuid> = live.iter().flat_map(|payment| payment.line_ids.iter().copied()).collect();
+
+        let settled = payments.iter().map(Payment::settled).collect::<Result<Vec<_>, _>>()?;
+        let paid = Money::sum(currency, settled.iter().copied())?;
+        let pending = Money::sum(
+            currency,
+            live.iter().filter(|payment| payment.status == PaymentStatus::Pending.to_string()).map(|payment| payment.amount),
+        )?;
+        let captured: Vec<&Payment> = payments
+            .iter()
+            .zip(&settled)
+            .filter(|(payment, settled)| !settled.is_zero() || !payment.refunded.is_zero())
+            .map(|(payment, _)| payment)
+            .collect();
+        let total = order.total()?;
+        let mut balance = total.checked_sub(paid)?.checked_sub(pending)?;
+        if balance.is_negative() {
+            balance = Money::zero(currency);
+        }
+        let taxes = order.taxes(&lines);
+        let prices_include_tax = order.prices_include_tax();
+
+        Ok(Bill {
+            order_id: order.id,
+            order_status: order.status,
+            currency: currency.to_string(),
+            subtotal: order.subtotal,
+            discount: order.discount,
+            prices_include_tax,
+            tax: order.tax,
+            total,
+            paid,
+            tips: Money::sum(currency, captured.iter().map(|payment| payment.tip))?,
+            refunded: Money::sum(currency, captured.iter().map(|payment| payment.refunded))?,
+            balance,
+            lines: lines
+                .into_iter()
+                .map(|line| BillLine {
+                    settled: settled_lines.contains(&line.id),
+                    line_id: line.id,
+                    name: line.name,
+                    tax_category: line.tax_category,
+                    quantity: line.quantity,
+                    line_total: line.line_total,
+                })
+                .collect(),
+            taxes,
+            payments,
+            exchange: None,
+        })
+    }
+
+    /// The total and balance of a bill converted at the current rate
+    async fn exchange(&self, bill: &Bill, to: Currency) -> Result<Exchange, ApiError> {
+        let from = bill.total.currency();
+        let rate = self.rates
+            .rate(from, to)
+            .await?
+            .ok_or_else(|| ApiError::InvalidInput(format!("No exchange rate from {} to {}", from, to)))?;
+
+        Ok(Exchange {
+            currency: to.to_string(),
+            rate,
+            total: bill.total.convert(to, rate)?.amount(),
+            balance: bill.balance.convert(to, rate)?.amount(),
+        })
+    }
+
+    async fn view(&self, payment: Payment) -> Result<PaymentView, ApiError> {
+        let refunds = self.repository.get_refunds(vec![payment.id]).await?;
+        Ok(PaymentView { payment, refunds })
+    }
+
+    /// Tell the branch and the table that money came in or went out
+    async fn publish(&self, kind: &str, order: &Order, payment: &Payment) {
+        let mut topics = vec![topic::branch(order.branch_id)];
+        topics.extend(order.table_id.map(topic::table));
+        self.events.publish(Event::new(kind, topics, payment)).await;
+    }
+
+    /// Check the register session cash goes through is open at the branch; other methods may name one too
+    async fn get_drawer(&self, method: PaymentMethod, branch_id: Uuid, session_id: Option<Uuid>) -> Result<Option<Uuid>, ApiError> {
+        let Some(session_id) = session_id else {
+            if method == PaymentMethod::Cash {
+                return Err(ApiError::InvalidInput("register_session_id is required for cash".to_string()));
+            }
+            return Ok(None);
+        };
+
+        let session = self.register_repository.get_by_id(session_id).await?;
+        if session.branch_id != branch_id {
+            return Err(ApiError::NotFound("Register session not found".to_string()));
+        }
+        if !session.is_open() {
+            return Err(ApiError::Conflict("Register session is closed".to_string()));
+        }
+
+        Ok(Some(session.id))
+    }
+
+    /// Fetch a payment, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Payment, ApiError> {
+        let payment = self.repository.get_by_id(id).await?;
+
+        if payment.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Payment not found".to_string()));
+        }
+
+        Ok(payment)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        common::{
+            events::create_event_bus,
+            money::rates::create_rate_source,
+            testing::{self, dec, eur, line, now, order},
+            AppState,
+        },
+        modules::{
+            order::entity::OrderView,
+            payment::provider::{CashProvider, FakeProvider, ProviderError},
+            tax::entity::{TaxPolicy, TaxRate},
+        },
+    };
+
+    fn request(key: &str, amount: Option<&str>, split: Option<BillSplit>) -> CreatePaymentRequest {
+        CreatePaymentRequest {
+            method: PaymentMethod::Card.to_string(),
+            idempotency_key: key.to_string(),
+            amount: amount.map(dec),
+            split,
+            tip: Decimal::ZERO,
+            tendered: None,
+            register_session_id: None,
+            terminal_id: Some("T1".to_string()),
+            token: None,
+        }
+    }
+
+    /// A captured card payment of an order, settling the given lines
+    fn captured(order: &Order, amount: Money, line_ids: Vec<Uuid>) -> Payment {
+        Payment {
+            id: Uuid::new_v4(),
+            account_id: order.account_id,
+            branch_id: order.branch_id,
+            order_id: order.id,
+            created_by: Uuid::new_v4(),
+            register_session_id: None,
+            method: PaymentMethod::Card.to_string(),
+            provider: "fake".to_string(),
+            status: PaymentStatus::Captured.to_string(),
+            amount,
+            tip: Money::zero(amount.currency()),
+            refunded: Money::zero(amount.currency()),
+            currency: order.currency.clone(),
+            idempotency_key: Uuid::new_v4().to_string(),
+            split: None,
+            line_ids,
+            shares: None,
+            tendered: None,
+            change: None,
+            provider_reference: Some("fake-capture".to_string()),
+            failure_reason: None,
+            captured_at: Some(now()),
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    /// A served order of a 30.00 pizza in a newly onboarded account, with a payment service taking cards through `card`
+    struct Served {
+        service: PaymentService,
+        order_service: OrderService,
+        ctx: RequestContext,
+        actor: UserInfo,
+        order_id: Uuid,
+    }
+
+    async fn served(card: Arc<FakeProvider>) -> Option<Served> {
+        let (database, config) = testing::database().await?;
+        let events = create_event_bus(&config.events, "").await;
+        let state = AppState::new(database.clone(), &config, events.clone());
+        fn from<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> T {
+            serde_json::from_value(value).unwrap()
+        }
+
+        let onboarded = state.account_service
+            .onboard(
+                &RequestContext::default(),
+                from(json!({ "account_name": "Trattoria", "email": "owner@example.com", "password": "correct-horse" })),
+            )
+            .await
+            .unwrap();
+        let mut actor = UserInfo::from(onboarded.user);
+        let ctx = RequestContext::default().with_user(actor.clone());
+
+        let branch = state.branch_service
+            .create(&ctx, &actor, from(json!({ "name": "Centre", "timezone": "Europe/Berlin", "currency": "EUR" })))
+            .await
+            .unwrap();
+        actor.active_branch_id = Some(branch.id.to_string());
+        let category = state.menu_service.create_category(&ctx, &actor, from(json!({ "name": "Mains" }))).await.unwrap();
+        let item = state.menu_service
+            .create_item(&ctx, &actor, from(json!({ "category_id": category.id, "name": "Pizza", "price": "30.00", "tax_category": "STANDARD" })))
+            .await
+            .unwrap();
+
+        let view = state.order_service
+            .create(&ctx, &actor, from(json!({ "branch_id": branch.id, "lines": [{ "item_id": item.id, "quantity": 1 }] })))
+            .await
+            .unwrap();
+        for to in [OrderStatus::Placed, OrderStatus::InPreparation, OrderStatus::Ready, OrderStatus::Served] {
+            state.order_service.transition(&ctx, &actor, view.order.id, to, None).await.unwrap();
+        }
+
+        let db = database.connection().clone();
+        let service = PaymentService::new(
+            PaymentRepository::new(db.clone()),
+            RegisterRepository::new(db.clone()),
+            BranchRepository::new(db),
+            state.order_service.clone(),
+            PaymentProviders::new(Arc::new(CashProvider), card.clone(), card),
+            create_rate_source(&config.money),
+            events,
+        );
+
+        Some(Served { service, order_service: state.order_service, ctx, actor, order_id: view.order.id })
+    }
+
+    impl Served {
+        async fn pay(&self, key: &str) -> Result<PaymentView, ApiError> {
+            self.service.pay(&self.ctx, &self.actor, self.order_id, request(key, None, None)).await
+        }
+
+        async fn order(&self) -> OrderView {
+            self.order_service.get_by_id(&self.actor, self.order_id).await.unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn paying_twice_with_the_same_key_charges_once() {
+        let card = Arc::new(FakeProvider::new());
+        let Some(served) = served(card.clone()).await else { return };
+
+        let first = served.pay("key-1").await.unwrap();
+        let retry = served.pay("key-1").await.unwrap();
+
+        assert_eq!(retry.payment.id, first.payment.id);
+        assert_eq!(retry.payment.provider_reference, first.payment.provider_reference);
+        assert_eq!(first.payment.amount, eur("30.00"));
+        assert_eq!(card.capture_count(), 1);
+        assert_eq!(served.order().await.order.status, OrderStatus::Paid.to_string());
+    }
+
+    #[tokio::test]
+    async fn declined_captures_answer_payment_declined_and_release_the_balance() {
+        let card = Arc::new(FakeProvider::declining("Insufficient funds"));
+        let Some(served) = served(card.clone()).await else { return };
+
+        for _ in 0..2 {
+            let result = served.pay("key-1").await;
+            assert!(matches!(result, Err(ApiError::PaymentDeclined(reason)) if reason == "Insufficient funds"));
+        }
+
+        assert_eq!(card.capture_count(), 0);
+        let bill = served.service.get_bill(&served.actor, served.order_id, BillQuery::default()).await.unwrap();
+        assert_eq!(bill.payments[0].status, PaymentStatus::Failed.to_string());
+        assert_eq!(bill.balance, eur("30.00"));
+        assert_eq!(served.order().await.order.status, OrderStatus::Served.to_string());
+    }
+
+    #[tokio::test]
+    async fn refunds_are_confirmed_with_the_provider_reference_and_never_exceed_the_payment() {
+        let card = Arc::new(FakeProvider::new());
+        let Some(served) = served(card).await else { return };
+        let paid = served.pay("key-1").await.unwrap().payment;
+        let refund = |amount: &str| CreateRefundRequest { amount: Some(dec(amount)), reason: "Cold pizza".to_string(), register_session_id: None };
+
+        let view = served.service.refund(&served.ctx, &served.actor, paid.id, refund("10.00")).await.unwrap();
+        assert_eq!(view.payment.refunded, eur("10.00"));
+        assert_eq!(view.refunds.len(), 1);
+        assert_eq!(view.refunds[0].status, RefundStatus::Confirmed.to_string());
+        assert_eq!(view.refunds[0].provider_reference, Some(format!("fake-refund-{}", view.refunds[0].id)));
+
+        let result = served.service.refund(&served.ctx, &served.actor, paid.id, refund("20.01")).await;
+        assert!(matches!(result, Err(ApiError::Conflict(_))));
+
+        let view = served.service.refund(&served.ctx, &served.actor, paid.id, refund("20.00")).await.unwrap();
+        assert_eq!(view.payment.status, PaymentStatus::Refunded.to_string());
+    }
+
+    #[test]
+    fn equal_shares_settle_the_bill_with_the_last_absorbing_rounding() {
+        let order = order("100.00", None, "0");
+        let lines = vec![line(&order, "Menu", "FOOD", "100.00")];
+        let equal = || Some(BillSplit::Equal { shares: 3 });
+
+        let mut payments = Vec::new();
+        for (key, expected) in [("share-1", "33.33"), ("share-2", "33.33"), ("share-3", "33.34")] {
+            let bill = PaymentService::bill(order.clone(), lines.clone(), payments.clone()).unwrap();
+            let amount = PaymentService::amount(&bill, &request(key, None, equal())).unwrap();
+            assert_eq!(amount, eur(expected));
+            payments.push(captured(&order, amount, Vec::new()));
+        }
+
+        let bill = PaymentService::bill(order, lines, payments).unwrap();
+        assert_eq!(bill.paid, eur("100.00"));
+        assert_eq!(bill.balance, eur("0"));
+        assert!(matches!(PaymentService::amount(&bill, &request("share-4", None, equal())), Err(ApiError::Conflict(_))));
+    }
+
+    #[test]
+    fn equal_shares_split_currencies_without_minor_unit_into_whole_amounts() {
+        let yen: Currency = "JPY".parse().unwrap();
+        let mut order = order("0", None, "0");
+        order.currency = yen.to_string();
+        order.subtotal = testing::money("1000", "JPY");
+        order.discount = Money::zero(yen);
+        order.tax = Money::zero(yen);
+        let lines = vec![line(&order, "Teishoku", "FOOD", "1000")];
+        let equal = || Some(BillSplit::Equal { shares: 3 });
+
+        let mut payments = Vec::new();
+        for (key, expected) in [("share-1", "333"), ("share-2", "333"), ("share-3", "334")] {
+            let bill = PaymentService::bill(order.clone(), lines.clone(), payments.clone()).unwrap();
+            let amount = PaymentService::amount(&bill, &request(key, None, equal())).unwrap();
+            assert_eq!(amount, testing::money(expected, "JPY"));
+            payments.push(captured(&order, amount, Vec::new()));
+        }
+
+        let bill = PaymentService::bill(order, lines, payments).unwrap();
+        assert!(bill.balance.is_zero());
+        assert!(matches!(PaymentService::amount(&bill, &request("fractional", Some("0.50"), None)), Err(ApiError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn paying_by_items_settles_each_line_once() {
+        let order = order("30.00", None, "0");
+        let lines = vec![line(&order, "Pizza", "FOOD", "10.00"), line(&order, "Pasta", "FOOD", "20.00")];
+        let items = |line: &line::Model| Some(BillSplit::Items { line_ids: vec![line.id] });
+
+        let bill = PaymentService::bill(order.clone(), lines.clone(), Vec::new()).unwrap();
+        let amount = PaymentService::amount(&bill, &request("pizza", None, items(&lines[0]))).unwrap();
+        assert_eq!(amount, eur("10.00"));
+        let pizza = captured(&order, amount, vec![lines[0].id]);
+
+        let bill = PaymentService::bill(order.clone(), lines.clone(), vec![pizza.clone()]).unwrap();
+        assert!(bill.lines[0].settled);
+        assert!(matches!(PaymentService::amount(&bill, &request("pizza-again", None, items(&lines[0]))), Err(ApiError::Conflict(_))));
+
+        let amount = PaymentService::amount(&bill, &request("pasta", None, items(&lines[1]))).unwrap();
+        assert_eq!(amount, eur("20.00"));
+        let pasta = captured(&order, amount, vec![lines[1].id]);
+
+        let bill = PaymentService::bill(order, lines, vec![pizza, pasta]).unwrap();
+        assert_eq!(bill.balance, eur("0"));
+    }
+
+    #[test]
+    fn paying_by_items_adds_tax_charged_on_top() {
+        let policy = TaxPolicy {
+            name: "Test".to_string(),
+            prices_include_tax: false,
+            rounding: "LINE".to_string(),
+            rates: vec![TaxRate { tax_category: "FOOD".to_string(), rate: dec("10"), label: None }],
+            exponent: 2,
+        };
+        let order = order("30.00", Some(policy), "3.00");
+        let lines = vec![line(&order, "Pizza", "FOOD", "10.00"), line(&order, "Pasta", "FOOD", "20.00")];
+        let bill = PaymentService::bill(order, lines.clone(), Vec::new()).unwrap();
+        assert_eq!(bill.total, eur("33.00"));
+
+        let split = Some(BillSplit::Items { line_ids: vec![lines[0].id] });
+        assert_eq!(PaymentService::amount(&bill, &request("pizza", None, split)).unwrap(), eur("11.00"));
+    }
+
+    #[test]
+    fn payments_beyond_the_balance_are_rejected() {
+        let order = order("30.00", None, "0");
+        let lines = vec![line(&order, "Pizza", "FOOD", "30.00")];
+
+        let bill = PaymentService::bill(order.clone(), lines.clone(), Vec::new()).unwrap();
+        assert!(matches!(PaymentService::amount(&bill, &request("too-much", Some("30.01"), None)), Err(ApiError::Conflict(_))));
+        assert!(matches!(
+            PaymentService::amount(&bill, &request("both", Some("10.00"), Some(BillSplit::Equal { shares: 2 }))),
+            Err(ApiError::InvalidInput(_))
+        ));
+
+        let partial = captured(&order, PaymentService::amount(&bill, &request("partial", Some("25.00"), None)).unwrap(), Vec::new());
+        let bill = PaymentService::bill(order, lines, vec![partial]).unwrap();
+        assert_eq!(bill.balance, eur("5.00"));
+        assert!(matches!(PaymentService::amount(&bill, &request("rest-and-more", Some("5.01"), None)), Err(ApiError::Conflict(_))));
+        assert_eq!(PaymentService::amount(&bill, &request("rest", None, None)).unwrap(), eur("5.00"));
+    }
+
+    #[test]
+    fn refunds_reopen_the_balance_they_give_back() {
+        let order = order("50.00", None, "0");
+        let lines = vec![line(&order, "Menu", "FOOD", "50.00")];
+
+        let mut payment = captured(&order, eur("50.00"), Vec::new());
+        payment.tip = eur("5.00");
+        assert_eq!(payment.refundable().unwrap(), eur("55.00"));
+
+        payment.refunded = eur("20.00");
+        assert_eq!(payment.refundable().unwrap(), eur("35.00"));
+        assert_eq!(payment.settled().unwrap(), eur("30.00"));
+
+        let bill = PaymentService::bill(order, lines, vec![payment]).unwrap();
+        assert_eq!(bill.refunded, eur("20.00"));
+        assert_eq!(bill.balance, eur("20.00"));
+    }
+
+    #[test]
+    fn provider_errors_map_to_api_errors() {
+        assert!(matches!(ApiError::from(ProviderError::Declined("no".to_string())), ApiError::PaymentDeclined(_)));
+        assert!(matches!(ApiError::from(ProviderError::Unavailable("down".to_string())), ApiError::ProviderUnavailable(_)));
+    }
+}
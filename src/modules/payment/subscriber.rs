@@ -0,0 +1,51 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    common::{
+        outbox::{self, DomainEvent, EventSubscriber},
+        ApiError,
+    },
+    modules::payment::service::PaymentService,
+};
+
+/// Part of the captured payment the subscriber needs
+#[derive(Debug, Deserialize)]
+struct Captured {
+    order_id: Uuid,
+}
+
+/// Closes orders whose balance a captured payment settled, retried by the dispatcher when closing right after
+/// the capture failed
+#[derive(Debug, Clone)]
+pub struct SettlementSubscriber {
+    payment_service: PaymentService,
+}
+
+impl SettlementSubscriber {
+    /// Create a new settlement subscriber
+    pub fn new(payment_service: PaymentService) -> Self {
+        Self { payment_service }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSubscriber for SettlementSubscriber {
+    fn name(&self) -> &'static str {
+        "settlement"
+    }
+
+    fn handles(&self, kind: &str) -> bool {
+        kind == outbox::kind::PAYMENT_CAPTURED
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+        let payment: Captured = event.payload()?;
+        // Payments are always taken by a signed-in user
+        let Some(ref actor) = event.metadata.actor else {
+            return Err(ApiError::InvalidInput(format!("Event {} has no actor", event.id)));
+        };
+
+        self.payment_service.settle_order(&event.metadata.context(), actor, payment.order_id).await
+    }
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    common::ApiError,
+    modules::privacy::entity::{ExportFormat, ExportQuery},
+    modules::user::entity::Model as User,
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Export everything held about a user as JSON or a ZIP archive
+pub async fn export_user(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Exporting data of user with ID: {}", id);
+    let export = state.privacy_service.export(&user, id).await?;
+
+    match query.format {
+        ExportFormat::Json => Ok(Json(export).into_response()),
+        ExportFormat::Zip => {
+            let archive = state.privacy_service.export_archive(&export)?;
+            let disposition = format!("attachment; filename=\"user-{}.zip\"", id);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                archive,
+            )
+                .into_response())
+        }
+    }
+}
+
+/// Irreversibly anonymize a user
+pub async fn anonymize_user(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<User>, ApiError> {
+    info!("Anonymizing user with ID: {}", id);
+    let result = state.privacy_service.anonymize(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::{
+    audit::entity::Model as AuditEvent,
+    invitation::entity::Model as Invitation,
+    user::entity::Model as User,
+};
+
+/// Everything held about a user, as handed out for a data access request
+#[derive(Debug, Serialize)]
+pub struct UserExport {
+    pub generated_at: DateTimeWithTimeZone,
+    pub user: User,
+    pub invitations_received: Vec<Invitation>,
+    pub invitations_sent: Vec<Invitation>,
+    pub audit_events: Vec<AuditEvent>,
+    /// Data kept by the other modules, by section
+    #[serde(flatten)]
+    pub sections: BTreeMap<&'static str, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Zip,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
@@ -0,0 +1,144 @@
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{common::ApiError, modules::user::entity::Model as User};
+
+/// A module keeping personal data about users, taking part in data access and erasure requests
+#[async_trait::async_trait]
+pub trait PersonalDataHolder: Send + Sync + std::fmt::Debug {
+    /// Section of the export, also the name of its file in the archive
+    fn section(&self) -> &'static str;
+
+    /// Everything the module keeps about a user
+    async fn export(&self, user: &User) -> Result<Value, ApiError>;
+
+    /// Remove or anonymize what the module keeps about a user, keeping the records others refer to.
+    /// Called again when a purge is retried, so it must be safe to repeat.
+    async fn scrub(&self, user: &User) -> Result<(), ApiError>;
+}
+
+/// Holder registered with the privacy service
+pub type SharedHolder = Arc<dyn PersonalDataHolder>;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, ApiError>> + Send>>;
+
+/// Holder reading and scrubbing a user's data through a module's repository
+pub struct RepositoryHolder {
+    section: &'static str,
+    export: Box<dyn Fn(Uuid) -> BoxFuture<Value> + Send + Sync>,
+    scrub: Box<dyn Fn(Uuid) -> BoxFuture<()> + Send + Sync>,
+}
+
+impl RepositoryHolder {
+    /// Create a holder from the repository queries exporting and scrubbing a user, by user ID
+    pub fn new<R, T, E, EF, S, SF>(section: &'static str, repository: R, export: E, scrub: S) -> Self
+    where
+        R: Clone + Send + Sync + 'static,
+        T: Serialize,
+        E: Fn(R, Uuid) -> EF + Send + Sync + 'static,
+        EF: Future<Output = Result<T, ApiError>> + Send + 'static,
+        S: Fn(R, Uuid) -> SF + Send + Sync + 'static,
+        SF: Future<Output = Result<(), ApiError>> + Send + 'static,
+    {
+        let exported = repository.clone();
+        Self {
+            section,
+            export: Box::new(move |user_id| {
+                let data = export(exported.clone(), user_id);
+                Box::pin(async move {
+                    serde_json::to_value(data.await?).map_err(|e| {
+                        error!("Failed to serialize {} of user {}: {}", section, user_id, e);
+                        ApiError::InternalServerError
+                    })
+                })
+            }),
+            scrub: Box::new(move |user_id| Box::pin(scrub(repository.clone(), user_id))),
+        }
+    }
+}
+
+impl fmt::Debug for RepositoryHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RepositoryHolder").field("section", &self.section).finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl PersonalDataHolder for RepositoryHolder {
+    fn section(&self) -> &'static str {
+        self.section
+    }
+
+    async fn export(&self, user: &User) -> Result<Value, ApiError> {
+        (self.export)(user.id).await
+    }
+
+    async fn scrub(&self, user: &User) -> Result<(), ApiError> {
+        (self.scrub)(user.id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    fn user() -> User {
+        let now = chrono::Utc::now().fixed_offset();
+        User {
+            id: Uuid::new_v4(),
+            account_id: Uuid::new_v4(),
+            branch_id: None,
+            name: Some("Ada".to_string()),
+            email: "ada@example.com".to_string(),
+            password_hash: None,
+            role: "CUSTOMER".to_string(),
+            status: "ACTIVE".to_string(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            anonymized_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_and_scrubs_through_the_repository() {
+        let scrubbed = Arc::new(Mutex::new(Vec::new()));
+        let holder = RepositoryHolder::new(
+            "notes",
+            scrubbed.clone(),
+            |_, user_id| async move { Ok(vec![user_id]) },
+            |scrubbed, user_id| async move {
+                scrubbed.lock().unwrap().push(user_id);
+                Ok(())
+            },
+        );
+        let user = user();
+
+        assert_eq!(holder.section(), "notes");
+        assert_eq!(holder.export(&user).await.unwrap(), serde_json::json!([user.id]));
+        holder.scrub(&user).await.unwrap();
+        assert_eq!(*scrubbed.lock().unwrap(), vec![user.id]);
+    }
+
+    #[tokio::test]
+    async fn data_that_cannot_be_serialized_fails_the_export() {
+        let holder = RepositoryHolder::new(
+            "notes",
+            (),
+            // JSON objects only have string keys
+            |_, _| async move { Ok(BTreeMap::from([((1, 2), "note")])) },
+            |_, _| async move { Ok(()) },
+        );
+
+        assert!(matches!(holder.export(&user()).await, Err(ApiError::InternalServerError)));
+    }
+}
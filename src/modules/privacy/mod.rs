@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod route;
+pub mod holder;
@@ -0,0 +1,15 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::common::AppState;
+
+use super::controller::*;
+
+/// Create privacy routes
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users/:id/export", get(export_user))
+        .route("/users/:id/anonymize", post(anonymize_user))
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::{info, error, warn};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+    common::{config::PrivacyConfig, ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        invitation::repository::InvitationRepository,
+        privacy::{entity::UserExport, holder::SharedHolder},
+        user::{
+            entity::{Model as User, UserRole},
+            repository::{anonymized_email, UserRepository},
+        },
+    },
+};
+
+/// Privacy service handling data access and erasure requests
+#[derive(Debug, Clone)]
+pub struct PrivacyService {
+    user_repository: UserRepository,
+    invitation_repository: InvitationRepository,
+    audit_service: AuditService,
+    holders: Vec<SharedHolder>,
+    retention_days: i64,
+}
+
+impl PrivacyService {
+    /// Create a new privacy service
+    pub fn new(
+        user_repository: UserRepository,
+        invitation_repository: InvitationRepository,
+        audit_service: AuditService,
+        config: &PrivacyConfig,
+    ) -> Self {
+        Self {
+            user_repository,
+            invitation_repository,
+            audit_service,
+            holders: Vec::new(),
+            retention_days: config.retention_days,
+        }
+    }
+
+    /// Include a module's personal data in exports and erasure; holders scrub in the order they are registered
+    pub fn register(mut self, holder: SharedHolder) -> Self {
+        info!("Registering {} as personal data holder", holder.section());
+        self.holders.push(holder);
+        self
+    }
+
+    /// Collect everything held about a user
+    pub async fn export(&self, actor: &UserInfo, id: Uuid) -> Result<UserExport, ApiError> {
+        info!("Exporting data of user with ID: {}", id);
+
+        let user = self.user_repository.get_by_id_with_deleted(id).await?;
+        let is_self = actor.parsed_id()? == user.id;
+        if !is_self {
+            self.ensure_admin_of(actor, &user)?;
+        }
+
+        let invitations_received = self.invitation_repository.get_by_accepted_user(user.id).await?;
+        let invitations_sent = self.invitation_repository.get_by_inviter(user.id).await?;
+        let audit_events = self.audit_service.get_by_subject(user.id).await?;
+
+        let mut sections = BTreeMap::new();
+        for holder in &self.holders {
+            sections.insert(holder.section(), holder.export(&user).await?);
+        }
+
+        Ok(UserExport {
+            generated_at: chrono::Utc::now().fixed_offset(),
+            user,
+            invitations_received,
+            invitations_sent,
+            audit_events,
+            sections,
+        })
+    }
+
+    /// Package an export as a ZIP archive with one JSON file per section
+    pub fn export_archive(&self, export: &UserExport) -> Result<Vec<u8>, ApiError> {
+        let mut files = vec![
+            ("export.json".to_string(), serde_json::to_vec_pretty(export)),
+            ("user.json".to_string(), serde_json::to_vec_pretty(&export.user)),
+            ("invitations_received.json".to_string(), serde_json::to_vec_pretty(&export.invitations_received)),
+            ("invitations_sent.json".to_string(), serde_json::to_vec_pretty(&export.invitations_sent)),
+            ("audit_events.json".to_string(), serde_json::to_vec_pretty(&export.audit_events)),
+        ];
+        for (section, data) in &export.sections {
+            files.push((format!("{}.json", section), serde_json::to_vec_pretty(data)));
+        }
+
+        let mut archive = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            let contents = contents.map_err(|e| {
+                error!("Failed to serialize {} for export: {}", name, e);
+                ApiError::InternalServerError
+            })?;
+
+            archive
+                .start_file(name.as_str(), SimpleFileOptions::default())
+                .and_then(|_| archive.write_all(&contents).map_err(Into::into))
+                .map_err(|e| {
+                    error!("Failed to write {} to export archive: {}", name, e);
+                    ApiError::InternalServerError
+                })?;
+        }
+
+        let cursor = archive.finish().map_err(|e| {
+            error!("Failed to finish export archive: {}", e);
+            ApiError::InternalServerError
+        })?;
+
+        Ok(cursor.into_inner())
+    }
+
+    /// Scrub a user's personal data, keeping the row for referential integrity
+    pub async fn anonymize(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<User, ApiError> {
+        info!("Anonymization requested for user with ID: {}", id);
+
+        let user = self.user_repository.get_by_id_with_deleted(id).await?;
+        self.ensure_admin_of(actor, &user)?;
+
+        if user.role == UserRole::Root.to_string() {
+            return Err(ApiError::Forbidden("Cannot anonymize root users".to_string()));
+        }
+
+        self.scrub(ctx, user).await
+    }
+
+    /// Anonymize users that have been soft-deleted longer than the retention period, returning how many were.
+    /// A user that fails is logged and retried on the next run without holding up the others.
+    pub async fn purge_expired(&self) -> Result<usize, ApiError> {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(self.retention_days)).fixed_offset();
+        let users = self.user_repository.get_deleted_before(cutoff).await?;
+
+        let mut count = 0;
+        for user in users {
+            let id = user.id;
+            match self.scrub(&RequestContext::system(), user).await {
+                Ok(_) => count += 1,
+                Err(e) => warn!("Failed to purge user {}, retrying on the next run: {}", id, e),
+            }
+        }
+
+        if count > 0 {
+            info!("Purged {} users soft-deleted before {}", count, cutoff);
+        }
+        Ok(count)
+    }
+
+    /// Run `purge_expired` periodically in the background
+    pub fn spawn_purge_worker(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.purge_expired().await {
+                    error!("Failed to purge expired users: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn scrub(&self, ctx: &RequestContext, user: User) -> Result<User, ApiError> {
+        if user.anonymized_at.is_some() {
+            return Ok(user);
+        }
+
+        // Scrub everything else first: the user row is the only place the original email survives,
+        // and until it is anonymized a failed run is picked up again by the next purge
+        for holder in &self.holders {
+            holder.scrub(&user).await?;
+        }
+        for invitation in self.invitation_repository.get_by_accepted_user(user.id).await? {
+            self.audit_service.redact_target(AuditTarget::Invitation, invitation.id).await?;
+        }
+        self.invitation_repository
+            .replace_email(user.id, &anonymized_email(user.id))
+            .await?;
+        self.audit_service.redact_target(AuditTarget::User, user.id).await?;
+
+        let anonymized = self.user_repository.anonymize(user.id).await?;
+
+        // Field values are deliberately left out so the log holds no personal data
+        self.audit_service
+            .record::<User>(ctx, anonymized.account_id, AuditAction::UserAnonymized, (AuditTarget::User, Some(anonymized.id)), None, None)
+            .await?;
+
+        Ok(anonymized)
+    }
+
+    /// Admins may act on users of their own account, root on anyone
+    fn ensure_admin_of(&self, actor: &UserInfo, user: &User) -> Result<(), ApiError> {
+        let role = actor.parsed_role()?;
+
+        if role == UserRole::Root || (role.is_admin() && actor.parsed_account_id()? == user.account_id) {
+            return Ok(());
+        }
+
+        Err(ApiError::Forbidden("Not allowed to access this user's data".to_string()))
+    }
+}
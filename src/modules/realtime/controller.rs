@@ -0,0 +1,135 @@
+use std::{collections::HashSet, convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        Response,
+    },
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use tracing::{info, warn};
+
+use crate::{
+    common::ApiError,
+    modules::{
+        auth::entity::UserInfo,
+        realtime::{
+            entity::{ClientMessage, ServerMessage, SubscribeQuery},
+            service::RealtimeService,
+        },
+    },
+    common::{AppState, session::SessionUser},
+};
+
+/// How often idle streams are pinged so proxies keep them open
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Open a WebSocket receiving the events of the requested topics
+pub async fn websocket(
+    Query(query): Query<SubscribeQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    info!("Opening event WebSocket for user {}", user.id);
+
+    // Reject unauthorized topics before upgrading so the client gets a proper status
+    let topics = state.realtime_service.authorize_topics(&user, query.topics()).await?;
+    let service = state.realtime_service.clone();
+
+    Ok(upgrade.on_upgrade(move |socket| stream_socket(socket, service, user, topics)))
+}
+
+/// Stream the events of the requested topics as server-sent events
+pub async fn sse(
+    Query(query): Query<SubscribeQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, ApiError> {
+    info!("Opening event stream for user {}", user.id);
+
+    let topics = state.realtime_service.authorize_topics(&user, query.topics()).await?;
+    let stream = BroadcastStream::new(state.realtime_service.subscribe()).filter_map(move |event| {
+        // A lagging client skips what it missed rather than being disconnected
+        let event = event.ok()?;
+        if !event.topics.iter().any(|topic| topics.contains(topic)) {
+            return None;
+        }
+        SseEvent::default().event(event.kind.clone()).json_data(&event).ok().map(Ok)
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(KEEP_ALIVE)))
+}
+
+async fn stream_socket(mut socket: WebSocket, service: RealtimeService, user: UserInfo, mut topics: HashSet<String>) {
+    let mut events = service.subscribe();
+    let mut ping = tokio::time::interval(KEEP_ALIVE);
+
+    for topic in topics.clone() {
+        if send(&mut socket, &ServerMessage::Subscribed { topic }).await.is_err() {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let reply = match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(ClientMessage::Subscribe { topic }) => match service.authorize_topic(&user, &topic).await {
+                        Ok(topic) => {
+                            topics.insert(topic.clone());
+                            ServerMessage::Subscribed { topic }
+                        }
+                        Err(e) => ServerMessage::Error { message: e.to_string() },
+                    },
+                    Ok(ClientMessage::Unsubscribe { topic }) => {
+                        topics.remove(&topic);
+                        ServerMessage::Unsubscribed { topic }
+                    }
+                    Err(e) => ServerMessage::Error { message: format!("Invalid message: {}", e) },
+                };
+
+                if send(&mut socket, &reply).await.is_err() {
+                    break;
+                }
+            }
+            event = events.recv() => {
+                match event {
+                    Ok(event) if event.topics.iter().any(|topic| topics.contains(topic)) => {
+                        if send(&mut socket, &ServerMessage::Event { event: &event }).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Event WebSocket of user {} skipped {} events", user.id, skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            _ = ping.tick() => {
+                if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    info!("Closed event WebSocket for user {}", user.id);
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage<'_>) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
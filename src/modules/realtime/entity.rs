@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::events::Event;
+
+/// Topics to subscribe to when a stream is opened
+#[derive(Debug, Deserialize, Default)]
+pub struct SubscribeQuery {
+    /// Comma separated, e.g. `branch:<id>,station:<id>`
+    pub topics: Option<String>,
+}
+
+impl SubscribeQuery {
+    /// The requested topics, without blanks
+    pub fn topics(&self) -> Vec<String> {
+        self.topics
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Messages a WebSocket client sends to change its subscriptions
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { topic: String },
+    Unsubscribe { topic: String },
+}
+
+/// Messages sent to a WebSocket client
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage<'a> {
+    Event { event: &'a Event },
+    Subscribed { topic: String },
+    Unsubscribed { topic: String },
+    Error { message: String },
+}
+
+/// Kinds of topic a client can follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicKind {
+    Branch,
+    Table,
+    Station,
+}
+
+impl std::str::FromStr for TopicKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "branch" => Ok(TopicKind::Branch),
+            "table" => Ok(TopicKind::Table),
+            "station" => Ok(TopicKind::Station),
+            _ => Err(format!("Unknown topic kind: {}", s)),
+        }
+    }
+}
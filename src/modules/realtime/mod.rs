@@ -0,0 +1,4 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod route;
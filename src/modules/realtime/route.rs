@@ -0,0 +1,19 @@
+use axum::{
+    routing::get,
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create real-time event routes for staff; each topic is checked when subscribed
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/events/ws", get(websocket))
+        .route("/events/sse", get(sse))
+        .layer(middleware::from_fn(authorize(vec![
+            "ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER", "COOK", "BARMAN", "CASH_REGISTER",
+        ])))
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+use tracing::warn;
+
+use crate::{
+    common::{events::{Event, SharedEventBus}, ApiError},
+    modules::{
+        auth::entity::UserInfo,
+        branch::repository::BranchRepository,
+        realtime::entity::TopicKind,
+        station::repository::StationRepository,
+        table::repository::TableRepository,
+        user::entity::UserRole,
+    },
+};
+
+/// Real-time service deciding who may follow which topics
+#[derive(Debug, Clone)]
+pub struct RealtimeService {
+    branch_repository: BranchRepository,
+    table_repository: TableRepository,
+    station_repository: StationRepository,
+    events: SharedEventBus,
+}
+
+impl RealtimeService {
+    /// Create a new real-time service
+    pub fn new(
+        branch_repository: BranchRepository,
+        table_repository: TableRepository,
+        station_repository: StationRepository,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            branch_repository,
+            table_repository,
+            station_repository,
+            events,
+        }
+    }
+
+    /// Check the caller may follow every topic, defaulting to the session's active branch
+    pub async fn authorize_topics(&self, actor: &UserInfo, topics: Vec<String>) -> Result<HashSet<String>, ApiError> {
+        let topics = if topics.is_empty() {
+            let branch_id = actor
+                .parsed_active_branch_id()?
+                .ok_or_else(|| ApiError::InvalidInput("topics are required without an active branch".to_string()))?;
+            vec![crate::common::events::topic::branch(branch_id)]
+        } else {
+            topics
+        };
+
+        let mut authorized = HashSet::with_capacity(topics.len());
+        for topic in topics {
+            authorized.insert(self.authorize_topic(actor, &topic).await?);
+        }
+
+        Ok(authorized)
+    }
+
+    /// Check the caller may follow a topic of the form `<kind>:<id>`
+    pub async fn authorize_topic(&self, actor: &UserInfo, topic: &str) -> Result<String, ApiError> {
+        let (kind, id) = topic
+            .split_once(':')
+            .ok_or_else(|| ApiError::InvalidInput(format!("Invalid topic: {}", topic)))?;
+        let kind: TopicKind = kind.parse().map_err(ApiError::InvalidInput)?;
+        let id = Uuid::parse_str(id).map_err(|_| ApiError::InvalidInput(format!("Invalid topic: {}", topic)))?;
+
+        let account_id = actor.parsed_account_id()?;
+        let owner = match kind {
+            TopicKind::Branch => self.branch_repository.get_by_id(id).await?.account_id,
+            TopicKind::Table => self.table_repository.get_by_id(id).await?.account_id,
+            TopicKind::Station => {
+                let station = self.station_repository.get_by_id(id).await?;
+                let role: UserRole = actor.role.parse().map_err(ApiError::InvalidInput)?;
+                if station.account_id == account_id && !station.admits(&role) {
+                    warn!("User {} with role {} denied events of station {}", actor.id, role, id);
+                    return Err(ApiError::Forbidden(format!("{} does not work at this station", role)));
+                }
+                station.account_id
+            }
+        };
+
+        if owner != account_id {
+            return Err(ApiError::NotFound("Topic not found".to_string()));
+        }
+
+        Ok(topic.to_string())
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::receipt::entity::{EmailReceiptRequest, Model as Receipt, ReceiptFormat, ReceiptFormatQuery, ReceiptQuery},
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Respond with a receipt as JSON or as a rendered file
+fn respond(state: &AppState, receipt: Receipt, format: ReceiptFormat, copy: bool) -> Response {
+    if format == ReceiptFormat::Json {
+        return Json(receipt).into_response();
+    }
+
+    let rendered = state.receipt_service.render(&receipt, format, copy);
+    let disposition = format!("attachment; filename=\"{}\"", rendered.filename);
+    (
+        [
+            (header::CONTENT_TYPE, rendered.content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        rendered.content,
+    )
+        .into_response()
+}
+
+/// Receipt of a paid order, issued on first request
+pub async fn get_for_order(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReceiptFormatQuery>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Fetching receipt of order {}", id);
+    let receipt = state.receipt_service.get_for_order(&ctx, &user, id).await?;
+    Ok(respond(&state, receipt, query.format, false))
+}
+
+/// Get a specific receipt by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReceiptFormatQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Fetching receipt with ID: {}", id);
+    let receipt = state.receipt_service.get_by_id(&user, id).await?;
+    Ok(respond(&state, receipt, query.format, false))
+}
+
+/// Print a copy of a receipt
+pub async fn reprint(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReceiptFormatQuery>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Reprinting receipt {}", id);
+    let receipt = state.receipt_service.reprint(&ctx, &user, id).await?;
+    Ok(respond(&state, receipt, query.format, true))
+}
+
+/// Email a receipt as PDF
+pub async fn email(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<EmailReceiptRequest>,
+) -> Result<StatusCode, ApiError> {
+    info!("Emailing receipt {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    state.receipt_service.email(&ctx, &user, id, payload).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// List receipts of a branch
+pub async fn get_all(
+    Query(query): Query<ReceiptQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Receipt>>, ApiError> {
+    info!("Fetching receipts");
+    let result = state.receipt_service.search(&user, query).await?;
+    Ok(Json(result))
+}
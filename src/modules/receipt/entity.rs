@@ -0,0 +1,146 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::modules::tax::entity::TaxLine;
+
+/// Receipt of a paid order with its invoice number; the document is fixed when issued
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "receipts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub order_id: Uuid,
+    /// Sequential per branch without gaps
+    pub invoice_number: i32,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub document: ReceiptDocument,
+    /// Copies printed after the original
+    pub print_count: i32,
+    pub last_printed_at: Option<DateTimeWithTimeZone>,
+    pub issued_by: Uuid,
+    pub issued_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Receipt", 10)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("order_id", &self.order_id)?;
+        state.serialize_field("invoice_number", &self.invoice_number)?;
+        state.serialize_field("document", &self.document)?;
+        state.serialize_field("print_count", &self.print_count)?;
+        state.serialize_field("last_printed_at", &self.last_printed_at)?;
+        state.serialize_field("issued_by", &self.issued_by)?;
+        state.serialize_field("issued_at", &self.issued_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Everything printed on a receipt, copied from the branch, order and payments when it is issued
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct ReceiptDocument {
+    /// Invoice number with the branch's prefix
+    pub invoice: String,
+    /// Issue time in the branch's timezone
+    pub issued_at: String,
+    pub branch_name: String,
+    pub address: Option<String>,
+    pub legal_name: Option<String>,
+    pub tax_id: Option<String>,
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    pub currency: String,
+    /// Locale the amounts are printed in
+    #[serde(default)]
+    pub locale: String,
+    pub lines: Vec<ReceiptLine>,
+    pub subtotal: Decimal,
+    pub discount: Decimal,
+    pub prices_include_tax: bool,
+    pub taxes: Vec<TaxLine>,
+    pub tax: Decimal,
+    pub total: Decimal,
+    pub payments: Vec<ReceiptPayment>,
+    pub tips: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptLine {
+    pub name: String,
+    /// Chosen modifier options
+    pub modifiers: Vec<String>,
+    pub quantity: i32,
+    pub unit_price: Decimal,
+    pub line_total: Decimal,
+    pub tax_category: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptPayment {
+    pub method: String,
+    pub amount: Decimal,
+    pub tip: Decimal,
+    pub tendered: Option<Decimal>,
+    pub change: Option<Decimal>,
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptFormat {
+    #[default]
+    Json,
+    /// Plain text laid out for an 80 mm printer
+    Text,
+    Pdf,
+    /// Byte stream for ESC/POS thermal printers
+    Escpos,
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Default)]
+pub struct ReceiptFormatQuery {
+    #[serde(default)]
+    pub format: ReceiptFormat,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReceiptQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct EmailReceiptRequest {
+    /// Defaults to the loyalty customer of the order
+    #[validate(email(message = "Invalid email format"))]
+    pub email: Option<String>,
+}
+
+/// A receipt rendered for printing or sending
+#[derive(Debug, Clone)]
+pub struct RenderedReceipt {
+    pub content_type: &'static str,
+    pub filename: String,
+    pub content: Vec<u8>,
+}
@@ -0,0 +1,277 @@
+use sea_orm::prelude::Decimal;
+
+use crate::{
+    common::money::{Locale, Money},
+    modules::receipt::entity::{Model as Receipt, ReceiptDocument},
+};
+
+/// Characters per line of an 80 mm printer with its standard font
+const WIDTH: usize = 42;
+
+/// PDF layout: Courier at 8pt is 4.8pt per character
+const FONT_SIZE: f32 = 8.0;
+const CHAR_WIDTH: f32 = 4.8;
+const LINE_HEIGHT: f32 = 10.0;
+const MARGIN: f32 = 14.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+}
+
+/// One printed line of a receipt
+struct Row {
+    text: String,
+    align: Align,
+    bold: bool,
+}
+
+impl Row {
+    fn left(text: impl Into<String>) -> Self {
+        Self { text: text.into(), align: Align::Left, bold: false }
+    }
+
+    fn center(text: impl Into<String>) -> Self {
+        Self { text: text.into(), align: Align::Center, bold: false }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Text and amount at both ends of a line, cutting the text if both do not fit
+    fn columns(text: &str, amount: &str) -> Self {
+        let room = WIDTH.saturating_sub(amount.chars().count() + 1);
+        let text: String = text.chars().take(room).collect();
+        let padding = WIDTH.saturating_sub(text.chars().count() + amount.chars().count());
+        Self::left(format!("{}{}{}", text, " ".repeat(padding), amount))
+    }
+
+    fn separator() -> Self {
+        Self::left("-".repeat(WIDTH))
+    }
+
+    /// The row padded to the full width, for printers and pages without alignment commands
+    fn padded(&self) -> String {
+        let gap = WIDTH.saturating_sub(self.text.chars().count());
+        match self.align {
+            Align::Left => self.text.clone(),
+            Align::Center => format!("{}{}", " ".repeat(gap / 2), self.text),
+        }
+    }
+}
+
+/// An amount in the receipt's currency and locale, without the currency
+fn money(doc: &ReceiptDocument, amount: Decimal) -> String {
+    let locale: Locale = doc.locale.parse().unwrap_or_default();
+    doc.currency
+        .parse()
+        .and_then(|currency| Money::rounded(amount, currency).map_err(|e| e.to_string()))
+        .map(|money| money.format_number(locale))
+        .unwrap_or_else(|_| format!("{:.2}", amount.round_dp(2)))
+}
+
+/// Break text into lines of at most `WIDTH` characters at spaces where possible
+fn wrap(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > WIDTH {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..WIDTH).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > WIDTH {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lay out a receipt; copies are marked so they cannot pass for the original
+fn rows(receipt: &Receipt, copy: bool) -> Vec<Row> {
+    let doc: &ReceiptDocument = &receipt.document;
+    let mut rows = Vec::new();
+
+    match &doc.legal_name {
+        Some(legal_name) => {
+            rows.extend(wrap(legal_name).into_iter().map(|line| Row::center(line).bold()));
+            rows.extend(wrap(&doc.branch_name).into_iter().map(Row::center));
+        }
+        None => rows.extend(wrap(&doc.branch_name).into_iter().map(|line| Row::center(line).bold())),
+    }
+    if let Some(address) = &doc.address {
+        rows.extend(wrap(address).into_iter().map(Row::center));
+    }
+    if let Some(tax_id) = &doc.tax_id {
+        rows.push(Row::center(format!("Tax ID: {}", tax_id)));
+    }
+    if let Some(header) = &doc.header {
+        rows.push(Row::left(""));
+        rows.extend(wrap(header).into_iter().map(Row::center));
+    }
+
+    rows.push(Row::left(""));
+    if copy {
+        rows.push(Row::center("*** COPY ***").bold());
+    }
+    rows.push(Row::columns("Invoice", &doc.invoice));
+    rows.push(Row::columns("Date", &doc.issued_at));
+    rows.push(Row::separator());
+
+    for line in &doc.lines {
+        rows.push(Row::columns(&format!("{} x {}", line.quantity, line.name), &money(doc, line.line_total)));
+        for modifier in &line.modifiers {
+            rows.push(Row::left(format!("    + {}", modifier)));
+        }
+        if line.quantity > 1 {
+            rows.push(Row::left(format!("    @ {}", money(doc, line.unit_price))));
+        }
+    }
+
+    rows.push(Row::separator());
+    rows.push(Row::columns("Subtotal", &money(doc, doc.subtotal)));
+    if doc.discount > Decimal::ZERO {
+        rows.push(Row::columns("Discount", &format!("-{}", money(doc, doc.discount))));
+    }
+    for tax in &doc.taxes {
+        let name = tax.label.as_deref().unwrap_or(&tax.tax_category);
+        let label = if doc.prices_include_tax {
+            format!("incl. {} {}% of {}", name, tax.rate.normalize(), money(doc, tax.net))
+        } else {
+            format!("{} {}% of {}", name, tax.rate.normalize(), money(doc, tax.net))
+        };
+        rows.push(Row::columns(&label, &money(doc, tax.tax)));
+    }
+    rows.push(Row::columns(&format!("TOTAL {}", doc.currency), &money(doc, doc.total)).bold());
+
+    if !doc.payments.is_empty() {
+        rows.push(Row::separator());
+        for payment in &doc.payments {
+            rows.push(Row::columns(&payment.method, &money(doc, payment.amount + payment.tip)));
+            if let (Some(tendered), Some(change)) = (payment.tendered, payment.change) {
+                rows.push(Row::columns("    Tendered", &money(doc, tendered)));
+                rows.push(Row::columns("    Change", &money(doc, change)));
+            }
+        }
+        if doc.tips > Decimal::ZERO {
+            rows.push(Row::columns("incl. tips", &money(doc, doc.tips)));
+        }
+    }
+
+    if let Some(footer) = &doc.footer {
+        rows.push(Row::left(""));
+        rows.extend(wrap(footer).into_iter().map(Row::center));
+    }
+
+    rows
+}
+
+/// Plain text receipt
+pub fn text(receipt: &Receipt, copy: bool) -> String {
+    let mut text: String = rows(receipt, copy)
+        .iter()
+        .map(|row| row.padded().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.push('\n');
+    text
+}
+
+/// Windows-1252 bytes of the text, which both ESC/POS code page 16 and the PDF fonts use
+fn cp1252(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u8,
+            '€' => 0x80,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Receipt as ESC/POS commands for thermal printers, cut at the end
+pub fn escpos(receipt: &Receipt, copy: bool) -> Vec<u8> {
+    // Initialize, then select the Windows-1252 code page
+    let mut bytes = vec![0x1b, b'@', 0x1b, b't', 16];
+
+    for row in rows(receipt, copy) {
+        let align = match row.align {
+            Align::Left => 0,
+            Align::Center => 1,
+        };
+        bytes.extend_from_slice(&[0x1b, b'a', align, 0x1b, b'E', u8::from(row.bold)]);
+        bytes.extend(cp1252(&row.text));
+        bytes.push(b'\n');
+    }
+
+    // Feed past the cutter and cut
+    bytes.extend_from_slice(&[0x1b, b'E', 0, 0x1b, b'd', 4, 0x1d, b'V', 66, 0]);
+    bytes
+}
+
+/// Receipt as a single narrow PDF page set in Courier
+pub fn pdf(receipt: &Receipt, copy: bool) -> Vec<u8> {
+    let rows = rows(receipt, copy);
+    let width = 2.0 * MARGIN + WIDTH as f32 * CHAR_WIDTH;
+    let height = 2.0 * MARGIN + rows.len() as f32 * LINE_HEIGHT;
+
+    let mut content = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let y = height - MARGIN - (i as f32 + 1.0) * LINE_HEIGHT + 2.0;
+        let font = if row.bold { "F2" } else { "F1" };
+        content.extend(format!("BT /{} {} Tf {:.1} {:.1} Td (", font, FONT_SIZE, MARGIN, y).into_bytes());
+        for byte in cp1252(&row.padded()) {
+            match byte {
+                b'(' | b')' | b'\\' => content.extend_from_slice(&[b'\\', byte]),
+                0x20..=0x7e => content.push(byte),
+                _ => content.extend(format!("\\{:03o}", byte).into_bytes()),
+            }
+        }
+        content.extend_from_slice(b") Tj ET\n");
+    }
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.1} {:.1}] /Resources << /Font << /F1 4 0 R /F2 5 0 R >> >> /Contents 6 0 R >>",
+            width, height
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend(content);
+    stream.extend_from_slice(b"\nendstream");
+    objects.push(stream);
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).into_bytes());
+    pdf
+}
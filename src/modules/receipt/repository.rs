@@ -0,0 +1,181 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        branch::entity::Entity as BranchEntity,
+        receipt::entity::{ActiveModel, Column, Entity as ReceiptEntity, Model as Receipt, ReceiptDocument, ReceiptQuery},
+    },
+    common::ApiError,
+};
+
+/// Receipt repository for database operations
+#[derive(Debug, Clone)]
+pub struct ReceiptRepository {
+    db: DatabaseConnection,
+}
+
+impl ReceiptRepository {
+    /// Create a new receipt repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search receipts of a branch, newest first, returning one page and the total count
+    pub async fn search(&self, branch_id: Uuid, query: &ReceiptQuery, page: u64, per_page: u64) -> Result<(Vec<Receipt>, u64), ApiError> {
+        let mut select = ReceiptEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some(from) = query.from {
+            select = select.filter(Column::IssuedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(Column::IssuedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::InvoiceNumber)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count receipts of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let receipts = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch receipts of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((receipts, total))
+    }
+
+    /// Get a receipt by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Receipt, ApiError> {
+        info!("Fetching receipt with ID: {}", id);
+
+        let receipt = ReceiptEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch receipt with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match receipt {
+            Some(receipt) => Ok(receipt),
+            None => Err(ApiError::NotFound("Receipt not found".to_string())),
+        }
+    }
+
+    /// Get the receipt of an order, if one was issued
+    pub async fn get_by_order_id(&self, order_id: Uuid) -> Result<Option<Receipt>, ApiError> {
+        ReceiptEntity::find()
+            .filter(Column::OrderId.eq(order_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch receipt of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Issue the receipt of an order under the branch's next invoice number.
+    /// The branch row stays locked while numbering so invoice numbers have no gaps or duplicates;
+    /// an order that already has a receipt gets it back unchanged, flagged as not created.
+    pub async fn create(
+        &self,
+        account_id: Uuid,
+        branch_id: Uuid,
+        order_id: Uuid,
+        issued_by: Uuid,
+        document: impl FnOnce(i32) -> ReceiptDocument,
+    ) -> Result<(Receipt, bool), ApiError> {
+        info!("Issuing receipt for order {}", order_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        BranchEntity::find_by_id(branch_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let existing = ReceiptEntity::find()
+            .filter(Column::OrderId.eq(order_id))
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch receipt of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        if let Some(receipt) = existing {
+            return Ok((receipt, false));
+        }
+
+        let last: Option<Option<i32>> = ReceiptEntity::find()
+            .select_only()
+            .column_as(Column::InvoiceNumber.max(), "invoice_number")
+            .filter(Column::BranchId.eq(branch_id))
+            .into_tuple()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch last invoice number of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let invoice_number = last.flatten().unwrap_or(0) + 1;
+
+        let receipt = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(branch_id),
+            order_id: Set(order_id),
+            invoice_number: Set(invoice_number),
+            document: Set(document(invoice_number)),
+            print_count: Set(0),
+            last_printed_at: Set(None),
+            issued_by: Set(issued_by),
+            issued_at: Set(chrono::Utc::now().fixed_offset()),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| {
+            error!("Failed to issue receipt for order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit receipt of order {}: {}", order_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((receipt, true))
+    }
+
+    /// Count a printed copy of a receipt
+    pub async fn mark_printed(&self, id: Uuid) -> Result<Receipt, ApiError> {
+        ReceiptEntity::update_many()
+            .col_expr(Column::PrintCount, Expr::col(Column::PrintCount).add(1))
+            .col_expr(Column::LastPrintedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count reprint of receipt {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        self.get_by_id(id).await
+    }
+}
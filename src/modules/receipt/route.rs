@@ -0,0 +1,26 @@
+use axum::{
+    routing::{get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create receipt routes for the cash desk
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/orders/:id/receipt", get(get_for_order))
+        .route("/receipts/:id", get(get_by_id))
+        .route("/receipts/:id/reprint", post(reprint))
+        .route("/receipts/:id/email", post(email))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "CASH_REGISTER"])))
+}
+
+/// Create receipt history routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/receipts", get(get_all))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
@@ -0,0 +1,274 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{
+        mail::{Attachment, Mail, SharedMailer},
+        money::Money,
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        order::{
+            entity::{line, Model as Order, OrderStatus},
+            repository::OrderRepository,
+        },
+        payment::{
+            entity::{Model as Payment, PaymentStatus},
+            repository::PaymentRepository,
+        },
+        receipt::{
+            entity::{
+                EmailReceiptRequest, Model as Receipt, ReceiptDocument, ReceiptFormat, ReceiptLine, ReceiptPayment, ReceiptQuery,
+                RenderedReceipt,
+            },
+            render,
+            repository::ReceiptRepository,
+        },
+        user::repository::UserRepository,
+    },
+};
+
+/// Receipt service layer for business logic
+#[derive(Debug, Clone)]
+pub struct ReceiptService {
+    repository: ReceiptRepository,
+    order_repository: OrderRepository,
+    payment_repository: PaymentRepository,
+    branch_repository: BranchRepository,
+    user_repository: UserRepository,
+    mailer: SharedMailer,
+    audit_service: AuditService,
+}
+
+impl ReceiptService {
+    /// Create a new receipt service
+    pub fn new(
+        repository: ReceiptRepository,
+        order_repository: OrderRepository,
+        payment_repository: PaymentRepository,
+        branch_repository: BranchRepository,
+        user_repository: UserRepository,
+        mailer: SharedMailer,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            repository,
+            order_repository,
+            payment_repository,
+            branch_repository,
+            user_repository,
+            mailer,
+            audit_service,
+        }
+    }
+
+    /// Receipts of a branch by invoice number, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: ReceiptQuery) -> Result<Page<Receipt>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Get a receipt of the caller's account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Receipt, ApiError> {
+        self.get_owned(actor, id).await
+    }
+
+    /// The receipt of a paid order, issuing it if the order has none yet
+    pub async fn get_for_order(&self, ctx: &RequestContext, actor: &UserInfo, order_id: Uuid) -> Result<Receipt, ApiError> {
+        let order = self.order_repository.get_by_id(order_id).await?;
+        if order.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Order not found".to_string()));
+        }
+
+        match self.repository.get_by_order_id(order.id).await? {
+            Some(receipt) => Ok(receipt),
+            None => self.issue(ctx, &order, actor.parsed_id()?).await,
+        }
+    }
+
+    /// Issue the receipt of a paid order under the branch's next invoice number.
+    /// Issuing twice returns the first receipt, so invoice numbers are never skipped or reused.
+    pub async fn issue(&self, ctx: &RequestContext, order: &Order, issued_by: Uuid) -> Result<Receipt, ApiError> {
+        if order.status != OrderStatus::Paid.to_string() {
+            return Err(ApiError::Conflict("Receipts are issued once the order is paid".to_string()));
+        }
+
+        let branch = self.branch_repository.get_by_id(order.branch_id).await?;
+        let lines = self.order_repository.get_lines(order.id).await?;
+        let payments = self.payment_repository.get_by_order_id(order.id).await?;
+        let total = order.total()?;
+
+        let (receipt, created) = self.repository
+            .create(order.account_id, branch.id, order.id, issued_by, |number| document(&branch, order, total, &lines, &payments, number))
+            .await?;
+
+        if created {
+            info!("Issued invoice {} for order {}", receipt.document.invoice, order.id);
+            self.audit(ctx, AuditAction::ReceiptIssued, &receipt, &receipt).await?;
+        }
+        Ok(receipt)
+    }
+
+    /// Count a printed copy of a receipt
+    pub async fn reprint(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<Receipt, ApiError> {
+        info!("Reprinting receipt {}", id);
+
+        let receipt = self.get_owned(actor, id).await?;
+        let reprinted = self.repository.mark_printed(receipt.id).await?;
+
+        self.audit(ctx, AuditAction::ReceiptReprinted, &reprinted, &serde_json::json!({ "print_count": reprinted.print_count }))
+            .await?;
+        Ok(reprinted)
+    }
+
+    /// Email a receipt as PDF, by default to the loyalty customer of its order
+    pub async fn email(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: EmailReceiptRequest) -> Result<(), ApiError> {
+        info!("Emailing receipt {}", id);
+
+        let receipt = self.get_owned(actor, id).await?;
+        let to = match data.email {
+            Some(email) => email,
+            None => {
+                let order = self.order_repository.get_by_id(receipt.order_id).await?;
+                let customer_id = order
+                    .customer_id
+                    .ok_or_else(|| ApiError::InvalidInput("email is required for orders without a customer".to_string()))?;
+                self.user_repository.get_by_id(customer_id).await?.email
+            }
+        };
+
+        let pdf = self.render(&receipt, ReceiptFormat::Pdf, false);
+        let mail = Mail {
+            to: to.clone(),
+            subject: format!("Receipt {} from {}", receipt.document.invoice, receipt.document.branch_name),
+            text: render::text(&receipt, false),
+            attachments: vec![Attachment { filename: pdf.filename, content_type: pdf.content_type.to_string(), content: pdf.content }],
+        };
+        self.mailer.send(&mail).await?;
+
+        self.audit(ctx, AuditAction::ReceiptEmailed, &receipt, &serde_json::json!({ "email": to })).await?;
+        Ok(())
+    }
+
+    /// Render a receipt for printing or download; copies are marked as such
+    pub fn render(&self, receipt: &Receipt, format: ReceiptFormat, copy: bool) -> RenderedReceipt {
+        let name = format!("receipt-{}", receipt.document.invoice);
+        match format {
+            ReceiptFormat::Json => RenderedReceipt {
+                content_type: "application/json",
+                filename: format!("{}.json", name),
+                content: serde_json::to_vec(receipt).unwrap_or_default(),
+            },
+            ReceiptFormat::Text => RenderedReceipt {
+                content_type: "text/plain; charset=utf-8",
+                filename: format!("{}.txt", name),
+                content: render::text(receipt, copy).into_bytes(),
+            },
+            ReceiptFormat::Pdf => RenderedReceipt {
+                content_type: "application/pdf",
+                filename: format!("{}.pdf", name),
+                content: render::pdf(receipt, copy),
+            },
+            ReceiptFormat::Escpos => RenderedReceipt {
+                content_type: "application/octet-stream",
+                filename: format!("{}.bin", name),
+                content: render::escpos(receipt, copy),
+            },
+        }
+    }
+
+    /// Fetch a receipt, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Receipt, ApiError> {
+        let receipt = self.repository.get_by_id(id).await?;
+
+        if receipt.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Receipt not found".to_string()));
+        }
+
+        Ok(receipt)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record a receipt event in the audit log
+    async fn audit<T: serde::Serialize>(&self, ctx: &RequestContext, action: AuditAction, receipt: &Receipt, after: &T) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, receipt.account_id, action, (AuditTarget::Receipt, Some(receipt.id)), None::<&T>, Some(after))
+            .await
+    }
+}
+
+/// What the receipt of an order shows, fixed at the time it is issued
+fn document(branch: &Branch, order: &Order, total: Money, lines: &[line::Model], payments: &[Payment], invoice_number: i32) -> ReceiptDocument {
+    let paid_at = order.paid_at.unwrap_or_else(|| chrono::Utc::now().fixed_offset());
+    let payments: Vec<ReceiptPayment> = payments
+        .iter()
+        .filter(|payment| {
+            payment.status == PaymentStatus::Captured.to_string() || payment.status == PaymentStatus::Refunded.to_string()
+        })
+        .map(|payment| ReceiptPayment {
+            method: payment.method.clone(),
+            amount: payment.amount.amount(),
+            tip: payment.tip.amount(),
+            tendered: payment.tendered.map(|tendered| tendered.amount()),
+            change: payment.change.map(|change| change.amount()),
+        })
+        .collect();
+    let taxes = order.taxes(lines);
+
+    ReceiptDocument {
+        invoice: format!("{}{:06}", branch.branding.invoice_prefix.as_deref().unwrap_or_default(), invoice_number),
+        issued_at: paid_at.with_timezone(&branch.tz()).format("%Y-%m-%d %H:%M").to_string(),
+        branch_name: branch.name.clone(),
+        address: branch.address.clone(),
+        legal_name: branch.branding.legal_name.clone(),
+        tax_id: branch.branding.tax_id.clone(),
+        header: branch.branding.header.clone(),
+        footer: branch.branding.footer.clone(),
+        currency: branch.currency.clone(),
+        locale: branch.locale.clone(),
+        lines: lines
+            .iter()
+            .map(|line| ReceiptLine {
+                name: line.name.clone(),
+                modifiers: line.modifiers.0.iter().map(|modifier| modifier.name.clone()).collect(),
+                quantity: line.quantity,
+                unit_price: line.unit_price.amount(),
+                line_total: line.line_total.amount(),
+                tax_category: line.tax_category.clone(),
+            })
+            .collect(),
+        subtotal: order.subtotal.amount(),
+        discount: order.discount.amount(),
+        prices_include_tax: order.prices_include_tax(),
+        taxes,
+        tax: order.tax.amount(),
+        total: total.amount(),
+        tips: payments.iter().map(|payment| payment.tip).sum(),
+        payments,
+    }
+}
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::register::entity::{
+        CloseRegisterRequest, CreateMovementRequest, Model as RegisterSession, OpenRegisterRequest, RegisterQuery, RegisterReport,
+        RegisterView, ReportFormat, ReportQuery,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Open a register session on a device
+pub async fn open(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<OpenRegisterRequest>,
+) -> Result<(StatusCode, Json<RegisterView>), ApiError> {
+    info!("Opening register session on device {}", payload.device_id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.register_service.open(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Get the session open on a device
+pub async fn get_current(
+    Query(query): Query<RegisterQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<RegisterView>, ApiError> {
+    info!("Fetching current register session");
+    let result = state.register_service.get_current(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific register session by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<RegisterView>, ApiError> {
+    info!("Fetching register session with ID: {}", id);
+    let result = state.register_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Put cash into or take cash out of a drawer
+pub async fn add_movement(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateMovementRequest>,
+) -> Result<(StatusCode, Json<RegisterView>), ApiError> {
+    info!("Recording cash movement on register session {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.register_service.add_movement(&ctx, &user, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Close a register session and get its Z report
+pub async fn close(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CloseRegisterRequest>,
+) -> Result<Json<RegisterReport>, ApiError> {
+    info!("Closing register session {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.register_service.close(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// X or Z report of a register session as JSON or CSV
+pub async fn get_report(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReportQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Fetching report of register session {}", id);
+    let report = state.register_service.report(&user, id).await?;
+
+    match query.format {
+        ReportFormat::Json => Ok(Json(report).into_response()),
+        ReportFormat::Csv => {
+            let csv = state.register_service.report_csv(&report);
+            let name = match report.z_number {
+                Some(z_number) => format!("z-report-{}", z_number),
+                None => format!("x-report-{}", id),
+            };
+            let disposition = format!("attachment; filename=\"{}.csv\"", name);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                csv,
+            )
+                .into_response())
+        }
+    }
+}
+
+/// List register sessions of a branch
+pub async fn get_all(
+    Query(query): Query<RegisterQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<RegisterSession>>, ApiError> {
+    info!("Fetching register sessions");
+    let result = state.register_service.search(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Closed sessions whose counted cash did not match
+pub async fn get_discrepancies(
+    Query(query): Query<RegisterQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<RegisterSession>>, ApiError> {
+    info!("Fetching register discrepancies");
+    let result = state.register_service.get_discrepancies(&user, query).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,315 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::modules::payment::entity::MethodTotals;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "register_sessions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    /// Register or drawer the session runs on; one open session per device
+    pub device_id: String,
+    pub status: String,
+    pub opened_by: Uuid,
+    pub opened_at: DateTimeWithTimeZone,
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+    pub opening_float: Decimal,
+    pub closed_by: Option<Uuid>,
+    pub closed_at: Option<DateTimeWithTimeZone>,
+    /// Cash that should be in the drawer at close
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub expected_cash: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub counted_cash: Option<Decimal>,
+    /// Counted minus expected; negative when cash is missing
+    #[sea_orm(column_type = "Decimal(Some((12, 2)))", nullable)]
+    pub discrepancy: Option<Decimal>,
+    /// Sequence number of the Z report, gap-free per branch
+    pub z_number: Option<i32>,
+    pub close_note: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("RegisterSession", 17)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("device_id", &self.device_id)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("opened_by", &self.opened_by)?;
+        state.serialize_field("opened_at", &self.opened_at)?;
+        state.serialize_field("opening_float", &self.opening_float)?;
+        state.serialize_field("closed_by", &self.closed_by)?;
+        state.serialize_field("closed_at", &self.closed_at)?;
+        state.serialize_field("expected_cash", &self.expected_cash)?;
+        state.serialize_field("counted_cash", &self.counted_cash)?;
+        state.serialize_field("discrepancy", &self.discrepancy)?;
+        state.serialize_field("z_number", &self.z_number)?;
+        state.serialize_field("close_note", &self.close_note)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn is_open(&self) -> bool {
+        self.status == RegisterStatus::Open.to_string()
+    }
+}
+
+/// Cash put into or taken out of a drawer outside of sales
+pub mod movement {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "register_movements")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub session_id: Uuid,
+        pub account_id: Uuid,
+        pub kind: String,
+        #[sea_orm(column_type = "Decimal(Some((12, 2)))")]
+        pub amount: Decimal,
+        pub reason: String,
+        pub created_by: Uuid,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("RegisterMovement", 8)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("session_id", &self.session_id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("kind", &self.kind)?;
+            state.serialize_field("amount", &self.amount)?;
+            state.serialize_field("reason", &self.reason)?;
+            state.serialize_field("created_by", &self.created_by)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterStatus {
+    Open,
+    Closed,
+}
+
+impl std::fmt::Display for RegisterStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegisterStatus::Open => write!(f, "OPEN"),
+            RegisterStatus::Closed => write!(f, "CLOSED"),
+        }
+    }
+}
+
+impl std::str::FromStr for RegisterStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "OPEN" => Ok(RegisterStatus::Open),
+            "CLOSED" => Ok(RegisterStatus::Closed),
+            _ => Err(format!("Invalid register status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovementKind {
+    CashIn,
+    CashOut,
+}
+
+impl std::fmt::Display for MovementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovementKind::CashIn => write!(f, "CASH_IN"),
+            MovementKind::CashOut => write!(f, "CASH_OUT"),
+        }
+    }
+}
+
+impl std::str::FromStr for MovementKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CASH_IN" => Ok(MovementKind::CashIn),
+            "CASH_OUT" => Ok(MovementKind::CashOut),
+            _ => Err(format!("Invalid movement kind: {}", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_cash(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_sign_negative() || amount.scale() > 2 {
+        return Err(ValidationError::new("amount").with_message("Amount must be non-negative with at most two decimals".into()));
+    }
+    Ok(())
+}
+
+fn validate_movement_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_zero() {
+        return Err(ValidationError::new("amount").with_message("Amount must be positive".into()));
+    }
+    validate_cash(amount)
+}
+
+fn validate_kind(kind: &str) -> Result<(), ValidationError> {
+    kind.parse::<MovementKind>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("kind").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct OpenRegisterRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 100, message = "Device ID must be between 1 and 100 characters"))]
+    pub device_id: String,
+
+    #[validate(custom(function = "validate_cash"))]
+    pub opening_float: Decimal,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateMovementRequest {
+    #[validate(custom(function = "validate_kind"))]
+    pub kind: String,
+
+    #[validate(custom(function = "validate_movement_amount"))]
+    pub amount: Decimal,
+
+    #[validate(length(min = 1, max = 500, message = "Reason must be between 1 and 500 characters"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CloseRegisterRequest {
+    #[validate(custom(function = "validate_cash"))]
+    pub counted_cash: Decimal,
+
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct RegisterQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub device_id: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReportQuery {
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// A session with its cash movements
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterView {
+    #[serde(flatten)]
+    pub session: Model,
+    pub movements: Vec<movement::Model>,
+}
+
+/// How the cash in a drawer adds up
+#[derive(Debug, Clone, Serialize)]
+pub struct CashSummary {
+    pub opening_float: Decimal,
+    /// Cash payments including tips
+    pub cash_sales: Decimal,
+    pub cash_refunds: Decimal,
+    pub cash_in: Decimal,
+    pub cash_out: Decimal,
+    pub expected: Decimal,
+    pub counted: Option<Decimal>,
+    pub discrepancy: Option<Decimal>,
+}
+
+/// Sales of one tax category and rate, allocated from the payments in proportion to the orders' taxes
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaxTotals {
+    pub tax_category: String,
+    pub rate: Decimal,
+    pub net: Decimal,
+    pub tax: Decimal,
+    /// Payments falling on the category, tax included
+    pub amount: Decimal,
+}
+
+/// Payments taken by one staff member
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StaffTotals {
+    pub user_id: Uuid,
+    pub name: Option<String>,
+    pub count: u64,
+    pub amount: Decimal,
+    pub tips: Decimal,
+}
+
+/// X report while the session is open, Z report once it is closed
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisterReport {
+    pub kind: String,
+    pub z_number: Option<i32>,
+    pub generated_at: DateTimeWithTimeZone,
+    pub session: Model,
+    pub cash: CashSummary,
+    pub by_method: Vec<MethodTotals>,
+    pub by_tax: Vec<TaxTotals>,
+    pub by_staff: Vec<StaffTotals>,
+    pub failed_payments: u64,
+}
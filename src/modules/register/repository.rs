@@ -0,0 +1,279 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, TransactionTrait,
+};
+use sea_orm::prelude::{Decimal, DateTimeWithTimeZone};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        branch::entity::Entity as BranchEntity,
+        register::entity::{
+            movement, ActiveModel, Column, CreateMovementRequest, Entity as RegisterEntity, Model as RegisterSession, RegisterQuery,
+            RegisterStatus,
+        },
+    },
+    common::ApiError,
+};
+
+/// Register session repository for database operations
+#[derive(Debug, Clone)]
+pub struct RegisterRepository {
+    db: DatabaseConnection,
+}
+
+impl RegisterRepository {
+    /// Create a new register repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search register sessions of a branch, newest first, returning one page and the total count
+    pub async fn search(&self, branch_id: Uuid, query: &RegisterQuery, page: u64, per_page: u64) -> Result<(Vec<RegisterSession>, u64), ApiError> {
+        let mut select = RegisterEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some(ref device_id) = query.device_id {
+            select = select.filter(Column::DeviceId.eq(device_id.as_str()));
+        }
+        if let Some(ref status) = query.status {
+            select = select.filter(Column::Status.eq(status.as_str()));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(Column::OpenedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(Column::OpenedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::OpenedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count register sessions of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let sessions = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch register sessions of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((sessions, total))
+    }
+
+    /// Get a register session by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<RegisterSession, ApiError> {
+        info!("Fetching register session with ID: {}", id);
+
+        let session = RegisterEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch register session with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match session {
+            Some(session) => Ok(session),
+            None => Err(ApiError::NotFound("Register session not found".to_string())),
+        }
+    }
+
+    /// Get the session open on a device, if any
+    pub async fn get_open(&self, branch_id: Uuid, device_id: &str) -> Result<Option<RegisterSession>, ApiError> {
+        RegisterEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::DeviceId.eq(device_id))
+            .filter(Column::Status.eq(RegisterStatus::Open.to_string()))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch open register session of device {}: {}", device_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Closed sessions of a branch whose counted cash did not match, newest first
+    pub async fn get_discrepancies(
+        &self,
+        branch_id: Uuid,
+        from: Option<DateTimeWithTimeZone>,
+        to: Option<DateTimeWithTimeZone>,
+    ) -> Result<Vec<RegisterSession>, ApiError> {
+        let mut select = RegisterEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::Status.eq(RegisterStatus::Closed.to_string()))
+            .filter(Column::Discrepancy.ne(Decimal::ZERO));
+
+        if let Some(from) = from {
+            select = select.filter(Column::ClosedAt.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(Column::ClosedAt.lt(to));
+        }
+
+        select
+            .order_by_desc(Column::ClosedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch register discrepancies of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Open a session on a device
+    pub async fn open(
+        &self,
+        account_id: Uuid,
+        branch_id: Uuid,
+        device_id: String,
+        opened_by: Uuid,
+        opening_float: Decimal,
+    ) -> Result<RegisterSession, ApiError> {
+        info!("Opening register session on device {} of branch {}", device_id, branch_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let session = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(branch_id),
+            device_id: Set(device_id),
+            status: Set(RegisterStatus::Open.to_string()),
+            opened_by: Set(opened_by),
+            opened_at: Set(now),
+            opening_float: Set(opening_float),
+            closed_by: Set(None),
+            closed_at: Set(None),
+            expected_cash: Set(None),
+            counted_cash: Set(None),
+            discrepancy: Set(None),
+            z_number: Set(None),
+            close_note: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        session.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to open register session: {}", e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A register session is already open on this device".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Get the cash movements of a session, oldest first
+    pub async fn get_movements(&self, session_id: Uuid) -> Result<Vec<movement::Model>, ApiError> {
+        movement::Entity::find()
+            .filter(movement::Column::SessionId.eq(session_id))
+            .order_by_asc(movement::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch movements of register session {}: {}", session_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Record cash put into or taken out of a drawer
+    pub async fn add_movement(&self, session: &RegisterSession, created_by: Uuid, request: CreateMovementRequest) -> Result<movement::Model, ApiError> {
+        info!("Recording {} of {} on register session {}", request.kind, request.amount, session.id);
+
+        let movement = movement::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            session_id: Set(session.id),
+            account_id: Set(session.account_id),
+            kind: Set(request.kind),
+            amount: Set(request.amount),
+            reason: Set(request.reason),
+            created_by: Set(created_by),
+            created_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        movement.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record movement on register session {}: {}", session.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Close a session with the counted cash, numbering its Z report.
+    /// The branch row stays locked while numbering so Z numbers have no gaps or duplicates.
+    pub async fn close(
+        &self,
+        session: RegisterSession,
+        closed_by: Uuid,
+        expected: Decimal,
+        counted: Decimal,
+        note: Option<String>,
+    ) -> Result<RegisterSession, ApiError> {
+        info!("Closing register session {}", session.id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        BranchEntity::find_by_id(session.branch_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to lock branch {}: {}", session.branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let last: Option<Option<i32>> = RegisterEntity::find()
+            .select_only()
+            .column_as(Column::ZNumber.max(), "z_number")
+            .filter(Column::BranchId.eq(session.branch_id))
+            .into_tuple()
+            .one(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch last Z number of branch {}: {}", session.branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let z_number = last.flatten().unwrap_or(0) + 1;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let result = RegisterEntity::update_many()
+            .col_expr(Column::Status, Expr::value(RegisterStatus::Closed.to_string()))
+            .col_expr(Column::ClosedBy, Expr::value(closed_by))
+            .col_expr(Column::ClosedAt, Expr::value(now))
+            .col_expr(Column::ExpectedCash, Expr::value(expected))
+            .col_expr(Column::CountedCash, Expr::value(counted))
+            .col_expr(Column::Discrepancy, Expr::value(counted - expected))
+            .col_expr(Column::ZNumber, Expr::value(z_number))
+            .col_expr(Column::CloseNote, Expr::value(note))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(Column::Id.eq(session.id))
+            .filter(Column::Status.eq(RegisterStatus::Open.to_string()))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to close register session {}: {}", session.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::Conflict("Register session is already closed".to_string()));
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit closing of register session {}: {}", session.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        self.get_by_id(session.id).await
+    }
+}
@@ -0,0 +1,29 @@
+use axum::{
+    routing::{get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create register session routes for the cash desk
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/register-sessions", post(open))
+        .route("/register-sessions/current", get(get_current))
+        .route("/register-sessions/:id", get(get_by_id))
+        .route("/register-sessions/:id/movements", post(add_movement))
+        .route("/register-sessions/:id/close", post(close))
+        .route("/register-sessions/:id/report", get(get_report))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "CASH_REGISTER"])))
+}
+
+/// Create register history and discrepancy routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/register-sessions", get(get_all))
+        .route("/register-sessions/discrepancies", get(get_discrepancies))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
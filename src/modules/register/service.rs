@@ -0,0 +1,488 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{money::Money, pagination::{self, Page}, ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        order::{entity::line, repository::OrderRepository},
+        payment::{
+            entity::{refund, MethodTotals, Model as Payment, PaymentMethod, PaymentStatus},
+            repository::PaymentRepository,
+        },
+        register::{
+            entity::{
+                movement, CashSummary, CloseRegisterRequest, CreateMovementRequest, Model as RegisterSession, MovementKind,
+                OpenRegisterRequest, RegisterQuery, RegisterReport, RegisterStatus, RegisterView, StaffTotals, TaxTotals,
+            },
+            repository::RegisterRepository,
+        },
+        tax::entity::TaxLine,
+        user::repository::UserRepository,
+    },
+};
+
+/// Register service layer for business logic
+#[derive(Debug, Clone)]
+pub struct RegisterService {
+    repository: RegisterRepository,
+    branch_repository: BranchRepository,
+    payment_repository: PaymentRepository,
+    order_repository: OrderRepository,
+    user_repository: UserRepository,
+    audit_service: AuditService,
+}
+
+/// What went through a drawer during a session
+struct Activity {
+    movements: Vec<movement::Model>,
+    payments: Vec<Payment>,
+    refunds: Vec<refund::Model>,
+    /// Method of every payment the refunds belong to
+    methods: HashMap<Uuid, String>,
+}
+
+impl RegisterService {
+    /// Create a new register service
+    pub fn new(
+        repository: RegisterRepository,
+        branch_repository: BranchRepository,
+        payment_repository: PaymentRepository,
+        order_repository: OrderRepository,
+        user_repository: UserRepository,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            payment_repository,
+            order_repository,
+            user_repository,
+            audit_service,
+        }
+    }
+
+    /// Register sessions of a branch, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: RegisterQuery) -> Result<Page<RegisterSession>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref status) = query.status {
+            status.parse::<RegisterStatus>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// The session open on a device
+    pub async fn get_current(&self, actor: &UserInfo, query: RegisterQuery) -> Result<RegisterView, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        let device_id = query
+            .device_id
+            .ok_or_else(|| ApiError::InvalidInput("device_id is required".to_string()))?;
+
+        let session = self.repository
+            .get_open(branch.id, &device_id)
+            .await?
+            .ok_or_else(|| ApiError::NotFound("No register session is open on this device".to_string()))?;
+
+        self.view(session).await
+    }
+
+    /// Get a register session of the caller's account with its movements
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<RegisterView, ApiError> {
+        let session = self.get_owned(actor, id).await?;
+        self.view(session).await
+    }
+
+    /// Closed sessions whose counted cash did not match what was expected
+    pub async fn get_discrepancies(&self, actor: &UserInfo, query: RegisterQuery) -> Result<Vec<RegisterSession>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        self.repository.get_discrepancies(branch.id, query.from, query.to).await
+    }
+
+    /// Open a drawer with its starting float
+    pub async fn open(&self, ctx: &RequestContext, actor: &UserInfo, data: OpenRegisterRequest) -> Result<RegisterView, ApiError> {
+        info!("Opening register on device {}", data.device_id);
+
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        let session = self.repository
+            .open(branch.account_id, branch.id, data.device_id, actor.parsed_id()?, data.opening_float)
+            .await?;
+
+        self.audit(ctx, session.account_id, AuditAction::RegisterOpened, session.id, None, Some(&session)).await?;
+        Ok(RegisterView { session, movements: Vec::new() })
+    }
+
+    /// Put cash into or take cash out of an open drawer
+    pub async fn add_movement(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: CreateMovementRequest) -> Result<RegisterView, ApiError> {
+        info!("Recording {} on register session {}", data.kind, id);
+
+        let session = self.get_open(actor, id).await?;
+        let kind: MovementKind = data.kind.parse().map_err(ApiError::InvalidInput)?;
+
+        if kind == MovementKind::CashOut {
+            let activity = self.activity(&session).await?;
+            let cash = Self::cash(&session, &activity);
+            if data.amount > cash.expected {
+                return Err(ApiError::Conflict(format!("Only {} should be in the drawer", cash.expected)));
+            }
+        }
+
+        let movement = self.repository.add_movement(&session, actor.parsed_id()?, data).await?;
+        self.audit(ctx, session.account_id, AuditAction::RegisterCashMoved, id, None, Some(&movement)).await?;
+
+        self.view(session).await
+    }
+
+    /// Count the drawer and close the session, producing its Z report
+    pub async fn close(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: CloseRegisterRequest) -> Result<RegisterReport, ApiError> {
+        info!("Closing register session {}", id);
+
+        let before = self.get_open(actor, id).await?;
+        let activity = self.activity(&before).await?;
+        let cash = Self::cash(&before, &activity);
+
+        let session = self.repository
+            .close(before.clone(), actor.parsed_id()?, cash.expected, data.counted_cash, data.note)
+            .await?;
+
+        self.audit(ctx, session.account_id, AuditAction::RegisterClosed, id, Some(&before), Some(&session)).await?;
+        self.report_for(session, activity).await
+    }
+
+    /// X report of an open session or Z report of a closed one
+    pub async fn report(&self, actor: &UserInfo, id: Uuid) -> Result<RegisterReport, ApiError> {
+        let session = self.get_owned(actor, id).await?;
+        let activity = self.activity(&session).await?;
+        self.report_for(session, activity).await
+    }
+
+    /// Render a report as CSV, one row per figure
+    pub fn report_csv(&self, report: &RegisterReport) -> String {
+        let mut rows: Vec<Vec<String>> = vec![
+            vec!["section".into(), "name".into(), "count".into(), "amount".into(), "tips".into(), "refunded".into(), "net".into(), "tax".into()],
+        ];
+        let mut meta = |name: &str, value: String| rows.push(vec!["report".into(), name.into(), String::new(), value]);
+
+        meta("kind", report.kind.clone());
+        meta("z_number", report.z_number.map(|n| n.to_string()).unwrap_or_default());
+        meta("session_id", report.session.id.to_string());
+        meta("device_id", report.session.device_id.clone());
+        meta("opened_at", report.session.opened_at.to_rfc3339());
+        meta("closed_at", report.session.closed_at.map(|at| at.to_rfc3339()).unwrap_or_default());
+        meta("generated_at", report.generated_at.to_rfc3339());
+        meta("failed_payments", report.failed_payments.to_string());
+
+        let cash = &report.cash;
+        for (name, value) in [
+            ("opening_float", Some(cash.opening_float)),
+            ("cash_sales", Some(cash.cash_sales)),
+            ("cash_refunds", Some(cash.cash_refunds)),
+            ("cash_in", Some(cash.cash_in)),
+            ("cash_out", Some(cash.cash_out)),
+            ("expected", Some(cash.expected)),
+            ("counted", cash.counted),
+            ("discrepancy", cash.discrepancy),
+        ] {
+            rows.push(vec!["cash".into(), name.into(), String::new(), value.map(|v| v.to_string()).unwrap_or_default()]);
+        }
+
+        for totals in &report.by_method {
+            rows.push(vec![
+                "method".into(),
+                totals.method.clone(),
+                totals.count.to_string(),
+                totals.amount.to_string(),
+                totals.tips.to_string(),
+                totals.refunded.to_string(),
+                totals.net.to_string(),
+            ]);
+        }
+        for totals in &report.by_tax {
+            rows.push(vec![
+                "tax".into(),
+                format!("{} {}%", totals.tax_category, totals.rate.normalize()),
+                String::new(),
+                totals.amount.to_string(),
+                String::new(),
+                String::new(),
+                totals.net.to_string(),
+                totals.tax.to_string(),
+            ]);
+        }
+        for totals in &report.by_staff {
+            rows.push(vec![
+                "staff".into(),
+                totals.name.clone().unwrap_or_else(|| totals.user_id.to_string()),
+                totals.count.to_string(),
+                totals.amount.to_string(),
+                totals.tips.to_string(),
+            ]);
+        }
+
+        rows.iter()
+            .map(|row| {
+                let mut cells: Vec<String> = row.iter().map(|cell| Self::csv_cell(cell)).collect();
+                cells.resize(8, String::new());
+                cells.join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+
+    async fn report_for(&self, session: RegisterSession, activity: Activity) -> Result<RegisterReport, ApiError> {
+        let cash = Self::cash(&session, &activity);
+        let captured: Vec<&Payment> = activity.payments
+            .iter()
+            .filter(|payment| payment.status != PaymentStatus::Failed.to_string())
+            .collect();
+
+        // Per method
+        let mut by_method: BTreeMap<String, MethodTotals> = BTreeMap::new();
+        for payment in &captured {
+            let entry = by_method.entry(payment.method.clone()).or_default();
+            entry.count += 1;
+            entry.amount += payment.amount.amount();
+            entry.tips += payment.tip.amount();
+        }
+        for refund in &activity.refunds {
+            if let Some(method) = activity.methods.get(&refund.payment_id) {
+                by_method.entry(method.clone()).or_default().refunded += refund.amount;
+            }
+        }
+        let by_method = by_method
+            .into_iter()
+            .map(|(method, mut entry)| {
+                entry.method = method;
+                entry.net = entry.amount + entry.tips - entry.refunded;
+                entry
+            })
+            .collect();
+
+        // Per staff member
+        let names: HashMap<Uuid, Option<String>> = self.user_repository
+            .get_by_account_id(session.account_id)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user.name))
+            .collect();
+        let mut by_staff: BTreeMap<Uuid, StaffTotals> = BTreeMap::new();
+        for payment in &captured {
+            let entry = by_staff.entry(payment.created_by).or_insert_with(|| StaffTotals {
+                user_id: payment.created_by,
+                name: names.get(&payment.created_by).cloned().flatten(),
+                ..Default::default()
+            });
+            entry.count += 1;
+            entry.amount += payment.amount.amount();
+            entry.tips += payment.tip.amount();
+        }
+
+        Ok(RegisterReport {
+            kind: if session.is_open() { "X" } else { "Z" }.to_string(),
+            z_number: session.z_number,
+            generated_at: chrono::Utc::now().fixed_offset(),
+            by_tax: self.tax_totals(&captured).await?,
+            by_method,
+            by_staff: by_staff.into_values().collect(),
+            failed_payments: (activity.payments.len() - captured.len()) as u64,
+            cash,
+            session,
+        })
+    }
+
+    /// Split each payment across the tax categories of its order in proportion to their share of the total,
+    /// taking the tax out of each part at the order's rate
+    async fn tax_totals(&self, payments: &[&Payment]) -> Result<Vec<TaxTotals>, ApiError> {
+        let mut order_ids: Vec<Uuid> = payments.iter().map(|payment| payment.order_id).collect();
+        order_ids.sort();
+        order_ids.dedup();
+
+        let mut lines: HashMap<Uuid, Vec<line::Model>> = HashMap::new();
+        for line in self.order_repository.get_lines_by_order_ids(order_ids.clone()).await? {
+            lines.entry(line.order_id).or_default().push(line);
+        }
+        let orders: HashMap<Uuid, (Money, Vec<TaxLine>)> = self.order_repository
+            .get_by_ids(order_ids)
+            .await?
+            .into_iter()
+            .map(|order| {
+                let taxes = order.taxes(lines.get(&order.id).map_or(&[], Vec::as_slice));
+                Ok((order.id, (order.total()?, taxes)))
+            })
+            .collect::<Result<_, ApiError>>()?;
+
+        let mut totals: BTreeMap<(String, Decimal), TaxTotals> = BTreeMap::new();
+        for payment in payments {
+            let Some((total, taxes)) = orders.get(&payment.order_id) else {
+                continue;
+            };
+            if total.is_zero() {
+                continue;
+            }
+
+            // The last category takes what rounding left over
+            let currency = payment.amount.currency();
+            let mut allocated = Money::zero(currency);
+            let count = taxes.len();
+            for (i, share) in taxes.iter().enumerate() {
+                let amount = if i + 1 == count {
+                    payment.amount.checked_sub(allocated)?
+                } else {
+                    Money::rounded(payment.amount.amount() * share.gross / total.amount(), currency)?
+                };
+                allocated = allocated.checked_add(amount)?;
+
+                let tax = if share.gross.is_zero() {
+                    Money::zero(currency)
+                } else {
+                    Money::rounded(amount.amount() * share.tax / share.gross, currency)?
+                };
+                let (amount, tax) = (amount.amount(), tax.amount());
+                let entry = totals.entry((share.tax_category.clone(), share.rate)).or_insert_with(|| TaxTotals {
+                    tax_category: share.tax_category.clone(),
+                    rate: share.rate,
+                    ..Default::default()
+                });
+                entry.net += amount - tax;
+                entry.tax += tax;
+                entry.amount += amount;
+            }
+        }
+
+        Ok(totals.into_values().collect())
+    }
+
+    /// How much cash should be in the drawer
+    fn cash(session: &RegisterSession, activity: &Activity) -> CashSummary {
+        let cash = PaymentMethod::Cash.to_string();
+
+        let cash_sales: Decimal = activity.payments
+            .iter()
+            .filter(|payment| payment.method == cash && payment.status != PaymentStatus::Failed.to_string())
+            .map(|payment| payment.amount.amount() + payment.tip.amount())
+            .sum();
+        let cash_refunds: Decimal = activity.refunds
+            .iter()
+            .filter(|refund| activity.methods.get(&refund.payment_id) == Some(&cash))
+            .map(|refund| refund.amount)
+            .sum();
+
+        let moved = |kind: MovementKind| -> Decimal {
+            activity.movements
+                .iter()
+                .filter(|movement| movement.kind == kind.to_string())
+                .map(|movement| movement.amount)
+                .sum()
+        };
+        let cash_in = moved(MovementKind::CashIn);
+        let cash_out = moved(MovementKind::CashOut);
+
+        CashSummary {
+            opening_float: session.opening_float,
+            cash_sales,
+            cash_refunds,
+            cash_in,
+            cash_out,
+            expected: session
+                .expected_cash
+                .unwrap_or(session.opening_float + cash_sales - cash_refunds + cash_in - cash_out),
+            counted: session.counted_cash,
+            discrepancy: session.discrepancy,
+        }
+    }
+
+    async fn activity(&self, session: &RegisterSession) -> Result<Activity, ApiError> {
+        let movements = self.repository.get_movements(session.id).await?;
+        let payments = self.payment_repository.get_by_register_session_id(session.id).await?;
+        let refunds = self.payment_repository.get_refunds_by_register_session_id(session.id).await?;
+
+        let mut methods: HashMap<Uuid, String> = payments.iter().map(|payment| (payment.id, payment.method.clone())).collect();
+        let missing: Vec<Uuid> = refunds
+            .iter()
+            .map(|refund| refund.payment_id)
+            .filter(|id| !methods.contains_key(id))
+            .collect();
+        if !missing.is_empty() {
+            for payment in self.payment_repository.get_by_ids(missing).await? {
+                methods.insert(payment.id, payment.method);
+            }
+        }
+
+        Ok(Activity { movements, payments, refunds, methods })
+    }
+
+    async fn view(&self, session: RegisterSession) -> Result<RegisterView, ApiError> {
+        let movements = self.repository.get_movements(session.id).await?;
+        Ok(RegisterView { session, movements })
+    }
+
+    fn csv_cell(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Fetch an open session of the caller's account
+    async fn get_open(&self, actor: &UserInfo, id: Uuid) -> Result<RegisterSession, ApiError> {
+        let session = self.get_owned(actor, id).await?;
+        if !session.is_open() {
+            return Err(ApiError::Conflict("Register session is closed".to_string()));
+        }
+        Ok(session)
+    }
+
+    /// Fetch a session, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<RegisterSession, ApiError> {
+        let session = self.repository.get_by_id(id).await?;
+
+        if session.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Register session not found".to_string()));
+        }
+
+        Ok(session)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record a register session change in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (AuditTarget::RegisterSession, Some(id)), before, after)
+            .await
+    }
+}
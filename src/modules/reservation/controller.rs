@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::reservation::entity::{
+        Availability, AvailabilityQuery, CancelReservationRequest, CreateReservationRequest, Model as Reservation, ReservationQuery,
+        ReservationStatus, UpdateReservationRequest, UpdateReservationStatusRequest,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Bookable start times of a branch on one day
+pub async fn get_availability(
+    Path(id): Path<Uuid>,
+    Query(query): Query<AvailabilityQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Availability>, ApiError> {
+    info!("Fetching availability of branch {} on {}", id, query.date);
+    let result = state.reservation_service.availability(id, query).await?;
+    Ok(Json(result))
+}
+
+/// Book a table
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateReservationRequest>,
+) -> Result<(StatusCode, Json<Reservation>), ApiError> {
+    info!("Creating reservation at {}", payload.starts_at);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.reservation_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Get the bookings of the logged-in customer
+pub async fn get_mine(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Reservation>>, ApiError> {
+    info!("Fetching reservations of user {}", user.id);
+    let result = state.reservation_service.get_mine(&user).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific reservation by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Reservation>, ApiError> {
+    info!("Fetching reservation with ID: {}", id);
+    let result = state.reservation_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Cancel a reservation
+pub async fn cancel(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    payload: Option<Json<CancelReservationRequest>>,
+) -> Result<Json<Reservation>, ApiError> {
+    info!("Cancelling reservation {}", id);
+    let Json(payload) = payload.unwrap_or_default();
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.reservation_service
+        .transition(&ctx, &user, id, ReservationStatus::Cancelled, payload.reason)
+        .await?;
+    Ok(Json(result))
+}
+
+/// List reservations of a branch
+pub async fn get_all(
+    Query(query): Query<ReservationQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<Reservation>>, ApiError> {
+    info!("Fetching reservations");
+    let result = state.reservation_service.search(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Change the time, party or table of a reservation
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateReservationRequest>,
+) -> Result<Json<Reservation>, ApiError> {
+    info!("Updating reservation with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.reservation_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Move a reservation to another status
+pub async fn update_status(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateReservationStatusRequest>,
+) -> Result<Json<Reservation>, ApiError> {
+    info!("Setting status of reservation {} to {}", id, payload.status);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let status = payload.status.parse().map_err(ApiError::InvalidInput)?;
+    let result = state.reservation_service.transition(&ctx, &user, id, status, payload.reason).await?;
+    Ok(Json(result))
+}
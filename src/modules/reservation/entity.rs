@@ -0,0 +1,289 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::modules::user::entity::UserRole;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "reservations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub table_id: Uuid,
+    /// Customer who booked for themselves; empty for bookings taken by staff
+    pub customer_id: Option<Uuid>,
+    pub guest_name: String,
+    pub guest_phone: Option<String>,
+    pub guest_email: Option<String>,
+    pub party_size: i32,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+    pub status: String,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+    pub confirmed_at: Option<DateTimeWithTimeZone>,
+    pub seated_at: Option<DateTimeWithTimeZone>,
+    pub cancelled_at: Option<DateTimeWithTimeZone>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Reservation", 20)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("table_id", &self.table_id)?;
+        state.serialize_field("customer_id", &self.customer_id)?;
+        state.serialize_field("guest_name", &self.guest_name)?;
+        state.serialize_field("guest_phone", &self.guest_phone)?;
+        state.serialize_field("guest_email", &self.guest_email)?;
+        state.serialize_field("party_size", &self.party_size)?;
+        state.serialize_field("starts_at", &self.starts_at)?;
+        state.serialize_field("ends_at", &self.ends_at)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("notes", &self.notes)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("confirmed_at", &self.confirmed_at)?;
+        state.serialize_field("seated_at", &self.seated_at)?;
+        state.serialize_field("cancelled_at", &self.cancelled_at)?;
+        state.serialize_field("cancel_reason", &self.cancel_reason)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn duration_minutes(&self) -> i64 {
+        (self.ends_at - self.starts_at).num_minutes()
+    }
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationStatus {
+    Requested,
+    Confirmed,
+    Seated,
+    NoShow,
+    Cancelled,
+}
+
+impl std::fmt::Display for ReservationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservationStatus::Requested => write!(f, "REQUESTED"),
+            ReservationStatus::Confirmed => write!(f, "CONFIRMED"),
+            ReservationStatus::Seated => write!(f, "SEATED"),
+            ReservationStatus::NoShow => write!(f, "NO_SHOW"),
+            ReservationStatus::Cancelled => write!(f, "CANCELLED"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReservationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "REQUESTED" => Ok(ReservationStatus::Requested),
+            "CONFIRMED" => Ok(ReservationStatus::Confirmed),
+            "SEATED" => Ok(ReservationStatus::Seated),
+            "NO_SHOW" => Ok(ReservationStatus::NoShow),
+            "CANCELLED" => Ok(ReservationStatus::Cancelled),
+            _ => Err(format!("Reservation status {} is not valid", s)),
+        }
+    }
+}
+
+impl ReservationStatus {
+    /// Whether the reservation still holds its table
+    pub fn holds_table(&self) -> bool {
+        matches!(self, ReservationStatus::Requested | ReservationStatus::Confirmed | ReservationStatus::Seated)
+    }
+
+    /// Roles allowed to move a reservation from this status to `to`, or `None` if the transition does not exist.
+    /// Managers and above may perform any existing transition; customers only on their own bookings.
+    pub fn transition_roles(&self, to: ReservationStatus) -> Option<&'static [UserRole]> {
+        use ReservationStatus::*;
+
+        match (self, to) {
+            (Requested, Confirmed) => Some(&[UserRole::Waiter, UserRole::CashRegister]),
+            (Confirmed, Seated) | (Confirmed, NoShow) => Some(&[UserRole::Waiter]),
+            (Requested, Cancelled) | (Confirmed, Cancelled) => {
+                Some(&[UserRole::Customer, UserRole::Waiter, UserRole::CashRegister])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Statuses holding a table, as stored
+pub fn active_statuses() -> Vec<String> {
+    [ReservationStatus::Requested, ReservationStatus::Confirmed, ReservationStatus::Seated]
+        .iter()
+        .map(|status| status.to_string())
+        .collect()
+}
+
+// Validators
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<ReservationStatus>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("status").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateReservationRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// Staff may pick the table; otherwise the smallest free table that fits is assigned
+    pub table_id: Option<Uuid>,
+
+    #[validate(range(min = 1, max = 100, message = "Party size must be between 1 and 100"))]
+    pub party_size: i32,
+
+    pub starts_at: DateTimeWithTimeZone,
+
+    /// Defaults to `RESERVATION_DURATION_MINUTES`
+    #[validate(range(min = 15, max = 720, message = "Duration must be between 15 and 720 minutes"))]
+    pub duration_minutes: Option<i64>,
+
+    /// Defaults to the customer's name when booking for themselves
+    #[validate(length(min = 1, max = 100, message = "Guest name must be between 1 and 100 characters"))]
+    pub guest_name: Option<String>,
+
+    #[validate(length(min = 3, max = 50, message = "Guest phone must be between 3 and 50 characters"))]
+    pub guest_phone: Option<String>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub guest_email: Option<String>,
+
+    #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
+    pub notes: Option<String>,
+
+    /// Ignore opening hours, table capacity and booking horizon (MANAGER and above)
+    #[serde(default, rename = "override")]
+    pub override_rules: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateReservationRequest {
+    pub table_id: Option<Uuid>,
+
+    #[validate(range(min = 1, max = 100, message = "Party size must be between 1 and 100"))]
+    pub party_size: Option<i32>,
+
+    pub starts_at: Option<DateTimeWithTimeZone>,
+
+    #[validate(range(min = 15, max = 720, message = "Duration must be between 15 and 720 minutes"))]
+    pub duration_minutes: Option<i64>,
+
+    #[validate(length(min = 1, max = 100, message = "Guest name must be between 1 and 100 characters"))]
+    pub guest_name: Option<String>,
+
+    #[validate(length(min = 3, max = 50, message = "Guest phone must be between 3 and 50 characters"))]
+    pub guest_phone: Option<String>,
+
+    #[validate(email(message = "Invalid email format"))]
+    pub guest_email: Option<String>,
+
+    #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
+    pub notes: Option<String>,
+
+    /// Ignore opening hours and table capacity (MANAGER and above)
+    #[serde(default, rename = "override")]
+    pub override_rules: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateReservationStatusRequest {
+    #[validate(custom(function = "validate_status"))]
+    pub status: String,
+
+    /// Why the reservation was cancelled
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, Default)]
+pub struct CancelReservationRequest {
+    #[validate(length(max = 500, message = "Reason must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReservationQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// Local day at the branch
+    pub date: Option<NaiveDate>,
+    pub table_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AvailabilityQuery {
+    /// Local day at the branch
+    pub date: NaiveDate,
+    pub party_size: i32,
+    /// Defaults to `RESERVATION_DURATION_MINUTES`
+    pub duration_minutes: Option<i64>,
+}
+
+/// A bookable start time
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailableSlot {
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+    /// Tables large enough for the party that are free for the whole slot
+    pub tables: usize,
+}
+
+/// Bookable slots of a branch on one day
+#[derive(Debug, Clone, Serialize)]
+pub struct Availability {
+    pub branch_id: Uuid,
+    pub date: NaiveDate,
+    pub party_size: i32,
+    pub duration_minutes: i64,
+    pub slots: Vec<AvailableSlot>,
+}
+
+/// A reservation about to be stored
+#[derive(Debug, Clone)]
+pub struct NewReservation {
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub table_id: Uuid,
+    pub customer_id: Option<Uuid>,
+    pub guest_name: String,
+    pub guest_phone: Option<String>,
+    pub guest_email: Option<String>,
+    pub party_size: i32,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+    pub status: ReservationStatus,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+}
@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod repository;
+pub mod route;
@@ -0,0 +1,269 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::reservation::entity::{
+        active_statuses, ActiveModel, Column, Entity as ReservationEntity, Model as Reservation, NewReservation, ReservationQuery,
+        ReservationStatus,
+    },
+    common::{
+        outbox::{self, EventMetadata, NewDomainEvent},
+        ApiError, RequestContext,
+    },
+};
+
+/// Name of the exclusion constraint keeping a table from being booked twice at once
+const NO_DOUBLE_BOOKING: &str = "reservations_no_double_booking";
+
+/// Guest name left on the bookings of an erased customer
+const ANONYMIZED_GUEST: &str = "Anonymized guest";
+
+/// Reservation repository for database operations
+#[derive(Debug, Clone)]
+pub struct ReservationRepository {
+    db: DatabaseConnection,
+}
+
+impl ReservationRepository {
+    /// Create a new reservation repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search reservations of a branch by start time, returning one page and the total count
+    pub async fn search(
+        &self,
+        branch_id: Uuid,
+        range: Option<(DateTimeWithTimeZone, DateTimeWithTimeZone)>,
+        query: &ReservationQuery,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Reservation>, u64), ApiError> {
+        let mut select = ReservationEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some((from, to)) = range {
+            select = select.filter(Column::StartsAt.gte(from)).filter(Column::StartsAt.lt(to));
+        }
+        if let Some(table_id) = query.table_id {
+            select = select.filter(Column::TableId.eq(table_id));
+        }
+        if let Some(ref status) = query.status {
+            select = select.filter(Column::Status.eq(status.as_str()));
+        }
+
+        let paginator = select
+            .order_by_asc(Column::StartsAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count reservations of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let reservations = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch reservations of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((reservations, total))
+    }
+
+    /// Get a reservation by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Reservation, ApiError> {
+        info!("Fetching reservation with ID: {}", id);
+
+        let reservation = ReservationEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch reservation with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match reservation {
+            Some(reservation) => Ok(reservation),
+            None => Err(ApiError::NotFound("Reservation not found".to_string())),
+        }
+    }
+
+    /// Get the bookings a customer made, latest first
+    pub async fn get_by_customer_id(&self, customer_id: Uuid) -> Result<Vec<Reservation>, ApiError> {
+        info!("Fetching reservations of customer {}", customer_id);
+
+        ReservationEntity::find()
+            .filter(Column::CustomerId.eq(customer_id))
+            .order_by_desc(Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch reservations of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Replace the guest details of a customer's bookings, keeping the bookings themselves
+    pub async fn anonymize_customer(&self, customer_id: Uuid) -> Result<u64, ApiError> {
+        info!("Anonymizing reservations of customer {}", customer_id);
+
+        let result = ReservationEntity::update_many()
+            .col_expr(Column::GuestName, Expr::value(ANONYMIZED_GUEST))
+            .col_expr(Column::GuestPhone, Expr::value(Option::<String>::None))
+            .col_expr(Column::GuestEmail, Expr::value(Option::<String>::None))
+            .col_expr(Column::Notes, Expr::value(Option::<String>::None))
+            .col_expr(Column::CancelReason, Expr::value(Option::<String>::None))
+            .col_expr(Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::CustomerId.eq(customer_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to anonymize reservations of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Get the reservations of a branch holding a table at some point between `from` and `to`
+    pub async fn get_active_between(
+        &self,
+        branch_id: Uuid,
+        from: DateTimeWithTimeZone,
+        to: DateTimeWithTimeZone,
+    ) -> Result<Vec<Reservation>, ApiError> {
+        ReservationEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::Status.is_in(active_statuses()))
+            .filter(Column::StartsAt.lt(to))
+            .filter(Column::EndsAt.gt(from))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch reservations of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Create a reservation, recording `reservation.created` in the same transaction; the database refuses it if
+    /// the table is already booked for an overlapping time
+    pub async fn create(&self, ctx: &RequestContext, reservation: NewReservation) -> Result<Reservation, ApiError> {
+        info!("Booking table {} for {} at {}", reservation.table_id, reservation.party_size, reservation.starts_at);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let confirmed_at = (reservation.status == ReservationStatus::Confirmed).then_some(now);
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(reservation.account_id),
+            branch_id: Set(reservation.branch_id),
+            table_id: Set(reservation.table_id),
+            customer_id: Set(reservation.customer_id),
+            guest_name: Set(reservation.guest_name),
+            guest_phone: Set(reservation.guest_phone),
+            guest_email: Set(reservation.guest_email),
+            party_size: Set(reservation.party_size),
+            starts_at: Set(reservation.starts_at),
+            ends_at: Set(reservation.ends_at),
+            status: Set(reservation.status.to_string()),
+            notes: Set(reservation.notes),
+            created_by: Set(reservation.created_by),
+            confirmed_at: Set(confirmed_at),
+            seated_at: Set(None),
+            cancelled_at: Set(None),
+            cancel_reason: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start reservation transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let reservation = model.insert(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to create reservation: {}", e);
+                Self::booking_error(e)
+            })?;
+
+        let event = NewDomainEvent::new(
+            outbox::kind::RESERVATION_CREATED,
+            reservation.account_id,
+            (outbox::aggregate::RESERVATION, reservation.id),
+            &reservation,
+            EventMetadata::from(ctx),
+        );
+        outbox::record(&txn, event).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit reservation {}: {}", reservation.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(reservation)
+    }
+
+    /// Save changed booking details; the database refuses overlaps here too
+    pub async fn update(&self, reservation: Reservation) -> Result<Reservation, ApiError> {
+        info!("Updating reservation with ID: {}", reservation.id);
+
+        let id = reservation.id;
+        let mut model = ActiveModel::from(reservation).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update reservation with ID {}: {}", id, e);
+                Self::booking_error(e)
+            })
+    }
+
+    /// Move a reservation from one status to another, stamping when it happened
+    pub async fn transition(&self, id: Uuid, from: ReservationStatus, to: ReservationStatus, reason: Option<String>) -> Result<Reservation, ApiError> {
+        info!("Moving reservation {} from {} to {}", id, from, to);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut update = ReservationEntity::update_many()
+            .col_expr(Column::Status, Expr::value(to.to_string()))
+            .col_expr(Column::UpdatedAt, Expr::value(now));
+
+        update = match to {
+            ReservationStatus::Confirmed => update.col_expr(Column::ConfirmedAt, Expr::value(now)),
+            ReservationStatus::Seated => update.col_expr(Column::SeatedAt, Expr::value(now)),
+            ReservationStatus::Cancelled => update
+                .col_expr(Column::CancelledAt, Expr::value(now))
+                .col_expr(Column::CancelReason, Expr::value(reason)),
+            ReservationStatus::Requested | ReservationStatus::NoShow => update,
+        };
+
+        let result = update
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(from.to_string()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update reservation {} status: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            let current = self.get_by_id(id).await?;
+            return Err(ApiError::InvalidTransition { from: current.status, to: to.to_string() });
+        }
+
+        self.get_by_id(id).await
+    }
+
+    fn booking_error(e: DbErr) -> ApiError {
+        if e.to_string().contains(NO_DOUBLE_BOOKING) {
+            return ApiError::Conflict("Table is already booked for that time".to_string());
+        }
+        ApiError::DatabaseError(e.to_string())
+    }
+}
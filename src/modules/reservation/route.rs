@@ -0,0 +1,33 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create booking routes for customers and staff of an account
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/reservations", post(create))
+        .route("/reservations/mine", get(get_mine))
+        .route("/reservations/:id", get(get_by_id))
+        .route("/reservations/:id/cancel", post(cancel))
+}
+
+/// Create host stand routes for front-of-house staff
+pub fn create_staff_routes() -> Router<AppState> {
+    Router::new()
+        .route("/reservations", get(get_all))
+        .route("/reservations/:id", put(update))
+        .route("/reservations/:id/status", put(update_status))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER", "CASH_REGISTER"])))
+}
+
+/// Create the public availability route
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/branches/:id/availability", get(get_availability))
+}
@@ -0,0 +1,497 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, TimeZone};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::{
+    common::{
+        config::{Config, ReservationConfig},
+        events::{topic, Event, SharedEventBus},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        notification::{
+            entity::{NewNotification, NotificationKind, Recipient},
+            service::NotificationService,
+        },
+        reservation::{
+            entity::{
+                Availability, AvailabilityQuery, AvailableSlot, CreateReservationRequest, Model as Reservation, NewReservation,
+                ReservationQuery, ReservationStatus, UpdateReservationRequest,
+            },
+            repository::ReservationRepository,
+        },
+        table::{entity::Model as Table, repository::TableRepository, service::TableService},
+        user::entity::UserRole,
+    },
+};
+
+/// Reservation service layer for business logic
+#[derive(Debug, Clone)]
+pub struct ReservationService {
+    repository: ReservationRepository,
+    branch_repository: BranchRepository,
+    table_repository: TableRepository,
+    table_service: TableService,
+    notification_service: NotificationService,
+    audit_service: AuditService,
+    events: SharedEventBus,
+    config: ReservationConfig,
+}
+
+impl ReservationService {
+    /// Create a new reservation service
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repository: ReservationRepository,
+        branch_repository: BranchRepository,
+        table_repository: TableRepository,
+        table_service: TableService,
+        notification_service: NotificationService,
+        audit_service: AuditService,
+        events: SharedEventBus,
+        config: &Config,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            table_repository,
+            table_service,
+            notification_service,
+            audit_service,
+            events,
+            config: config.reservation.clone(),
+        }
+    }
+
+    /// Bookable start times of a branch on one day, from its opening hours and free tables
+    pub async fn availability(&self, branch_id: Uuid, query: AvailabilityQuery) -> Result<Availability, ApiError> {
+        if !(1..=100).contains(&query.party_size) {
+            return Err(ApiError::InvalidInput("Party size must be between 1 and 100".to_string()));
+        }
+        let duration = self.duration(query.duration_minutes)?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        let windows = Self::opening_windows(&branch, query.date);
+        let (Some(first), Some(last)) = (windows.iter().map(|w| w.0).min(), windows.iter().map(|w| w.1).max()) else {
+            return Ok(Availability { branch_id, date: query.date, party_size: query.party_size, duration_minutes: duration, slots: Vec::new() });
+        };
+
+        let tables = self.candidate_tables(&branch, query.party_size).await?;
+        let booked = self.repository.get_active_between(branch.id, first, last).await?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let horizon = now + Duration::days(self.config.max_days_ahead);
+        let step = Duration::minutes(self.config.slot_minutes);
+        let length = Duration::minutes(duration);
+
+        let mut slots = Vec::new();
+        for (open, close) in windows {
+            let mut starts_at = open;
+            while starts_at + length <= close {
+                let ends_at = starts_at + length;
+                if starts_at > now && starts_at <= horizon {
+                    let free = tables
+                        .iter()
+                        .filter(|table| {
+                            !booked.iter().any(|r| r.table_id == table.id && r.starts_at < ends_at && r.ends_at > starts_at)
+                        })
+                        .count();
+                    if free > 0 {
+                        slots.push(AvailableSlot { starts_at, ends_at, tables: free });
+                    }
+                }
+                starts_at += step;
+            }
+        }
+
+        Ok(Availability { branch_id, date: query.date, party_size: query.party_size, duration_minutes: duration, slots })
+    }
+
+    /// Reservations of a branch, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: ReservationQuery) -> Result<Page<Reservation>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref status) = query.status {
+            status.parse::<ReservationStatus>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let range = match query.date {
+            Some(date) => Some(Self::local_day(&branch, date)?),
+            None => None,
+        };
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search(branch.id, range, &query, page, per_page).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// The bookings a customer made for themselves
+    pub async fn get_mine(&self, actor: &UserInfo) -> Result<Vec<Reservation>, ApiError> {
+        self.repository.get_by_customer_id(actor.parsed_id()?).await
+    }
+
+    /// Get a reservation; customers only see their own
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Reservation, ApiError> {
+        self.get_visible(actor, id).await
+    }
+
+    /// Book a table. Customers book for themselves and wait for confirmation; staff bookings are confirmed at once.
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateReservationRequest) -> Result<Reservation, ApiError> {
+        info!("Booking a table for {} at {}", data.party_size, data.starts_at);
+
+        let role = actor.parsed_role()?;
+        let customer = role == UserRole::Customer;
+        Self::check_override(&role, data.override_rules)?;
+        if customer && data.table_id.is_some() {
+            return Err(ApiError::Forbidden("Customers cannot pick a table".to_string()));
+        }
+
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        let starts_at = data.starts_at;
+        let ends_at = starts_at + Duration::minutes(self.duration(data.duration_minutes)?);
+        if !data.override_rules {
+            self.check_time(&branch, starts_at, ends_at)?;
+        }
+
+        let guest_name = match data.guest_name {
+            Some(name) => name,
+            None if customer => actor.name.clone().unwrap_or_else(|| actor.email.clone()),
+            None => return Err(ApiError::InvalidInput("guest_name is required".to_string())),
+        };
+        let guest_email = data.guest_email.or_else(|| customer.then(|| actor.email.clone()));
+
+        let new = NewReservation {
+            account_id: branch.account_id,
+            branch_id: branch.id,
+            table_id: Uuid::nil(),
+            customer_id: if customer { Some(actor.parsed_id()?) } else { None },
+            guest_name,
+            guest_phone: data.guest_phone,
+            guest_email,
+            party_size: data.party_size,
+            starts_at,
+            ends_at,
+            status: if customer { ReservationStatus::Requested } else { ReservationStatus::Confirmed },
+            notes: data.notes,
+            created_by: actor.parsed_id()?,
+        };
+
+        let reservation = match data.table_id {
+            Some(table_id) => {
+                let table = self.get_table(&branch, table_id).await?;
+                if !data.override_rules {
+                    Self::check_seats(&table, new.party_size)?;
+                }
+                self.repository.create(ctx, NewReservation { table_id, ..new }).await?
+            }
+            None => self.book_any_table(ctx, &branch, new).await?,
+        };
+
+        self.publish("reservation.created", &reservation).await;
+        Ok(reservation)
+    }
+
+    /// Change the time, party or table of a booking that has not been seated yet
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateReservationRequest) -> Result<Reservation, ApiError> {
+        info!("Updating reservation with ID: {}", id);
+
+        Self::check_override(&actor.parsed_role()?, data.override_rules)?;
+
+        let before = self.get_owned(actor, id).await?;
+        let status: ReservationStatus = before.status.parse().map_err(ApiError::InvalidInput)?;
+        if !matches!(status, ReservationStatus::Requested | ReservationStatus::Confirmed) {
+            return Err(ApiError::Conflict(format!("Reservation is {}", before.status)));
+        }
+        let branch = self.branch_repository.get_by_id(before.branch_id).await?;
+
+        let mut reservation = before.clone();
+        if data.starts_at.is_some() || data.duration_minutes.is_some() {
+            let duration = match data.duration_minutes {
+                Some(minutes) => self.duration(Some(minutes))?,
+                None => before.duration_minutes(),
+            };
+            reservation.starts_at = data.starts_at.unwrap_or(before.starts_at);
+            reservation.ends_at = reservation.starts_at + Duration::minutes(duration);
+            if !data.override_rules {
+                self.check_time(&branch, reservation.starts_at, reservation.ends_at)?;
+            }
+        }
+        if let Some(party_size) = data.party_size {
+            reservation.party_size = party_size;
+        }
+        if let Some(table_id) = data.table_id {
+            reservation.table_id = table_id;
+        }
+        if !data.override_rules && (data.table_id.is_some() || data.party_size.is_some()) {
+            let table = self.get_table(&branch, reservation.table_id).await?;
+            Self::check_seats(&table, reservation.party_size)?;
+        } else if data.table_id.is_some() {
+            self.get_table(&branch, reservation.table_id).await?;
+        }
+
+        if let Some(guest_name) = data.guest_name {
+            reservation.guest_name = guest_name;
+        }
+        if data.guest_phone.is_some() {
+            reservation.guest_phone = data.guest_phone;
+        }
+        if data.guest_email.is_some() {
+            reservation.guest_email = data.guest_email;
+        }
+        if data.notes.is_some() {
+            reservation.notes = data.notes;
+        }
+
+        let reservation = self.repository.update(reservation).await?;
+
+        self.audit(ctx, reservation.account_id, AuditAction::ReservationUpdated, id, Some(&before), Some(&reservation)).await?;
+        self.publish("reservation.updated", &reservation).await;
+        Ok(reservation)
+    }
+
+    /// Move a reservation through its lifecycle, checking the actor's role may perform the step
+    pub async fn transition(
+        &self,
+        ctx: &RequestContext,
+        actor: &UserInfo,
+        id: Uuid,
+        to: ReservationStatus,
+        reason: Option<String>,
+    ) -> Result<Reservation, ApiError> {
+        info!("Moving reservation {} to {}", id, to);
+
+        let before = self.get_visible(actor, id).await?;
+        let from: ReservationStatus = before.status.parse().map_err(ApiError::InvalidInput)?;
+
+        let roles = from.transition_roles(to).ok_or_else(|| ApiError::InvalidTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+        })?;
+
+        let role = actor.parsed_role()?;
+        if role.level() < UserRole::Manager.level() && !roles.contains(&role) {
+            warn!("User {} with role {} tried to move reservation {} to {}", actor.id, role, id, to);
+            return Err(ApiError::Forbidden(format!("{} cannot move a reservation from {} to {}", role, from, to)));
+        }
+
+        let reservation = self.repository.transition(id, from, to, reason).await?;
+
+        if to == ReservationStatus::Seated {
+            self.table_service.occupy(reservation.table_id).await?;
+        }
+        if to == ReservationStatus::Confirmed {
+            self.notify_confirmed(&reservation).await;
+        }
+
+        self.audit(ctx, reservation.account_id, AuditAction::ReservationStatusChanged, id, Some(&before), Some(&reservation)).await?;
+        self.publish("reservation.status_changed", &reservation).await;
+        Ok(reservation)
+    }
+
+    /// Tell the customer, or the guest's email for bookings taken by staff, that the table is theirs
+    async fn notify_confirmed(&self, reservation: &Reservation) {
+        let recipient = match (reservation.customer_id, &reservation.guest_email) {
+            (Some(customer_id), _) => Recipient::User(customer_id),
+            (None, Some(email)) => Recipient::Email(email.clone()),
+            (None, None) => return,
+        };
+        let Ok(branch) = self.branch_repository.get_by_id(reservation.branch_id).await else {
+            return;
+        };
+
+        let data = serde_json::json!({
+            "reservation_id": reservation.id,
+            "branch_id": branch.id,
+            "branch": branch.name,
+            "party_size": reservation.party_size,
+            "starts_at": reservation.starts_at.with_timezone(&branch.tz()).format("%Y-%m-%d %H:%M").to_string(),
+        });
+        self.notification_service
+            .notify(NewNotification {
+                account_id: reservation.account_id,
+                recipient,
+                kind: NotificationKind::ReservationConfirmed,
+                data,
+            })
+            .await;
+    }
+
+    /// Try the smallest free tables that fit until one can be booked
+    async fn book_any_table(&self, ctx: &RequestContext, branch: &Branch, new: NewReservation) -> Result<Reservation, ApiError> {
+        let booked = self.repository.get_active_between(branch.id, new.starts_at, new.ends_at).await?;
+        let tables = self.candidate_tables(branch, new.party_size).await?;
+
+        for table in tables.iter().filter(|table| !booked.iter().any(|r| r.table_id == table.id)) {
+            match self.repository.create(ctx, NewReservation { table_id: table.id, ..new.clone() }).await {
+                // Someone else took it in the meantime
+                Err(ApiError::Conflict(_)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(ApiError::Conflict("No table is free for that time".to_string()))
+    }
+
+    /// Tables of a branch seating the party, smallest first
+    async fn candidate_tables(&self, branch: &Branch, party_size: i32) -> Result<Vec<Table>, ApiError> {
+        let mut tables: Vec<Table> = self.table_repository
+            .get_by_branch_id(branch.id)
+            .await?
+            .into_iter()
+            .filter(|table| table.seats >= party_size)
+            .collect();
+        tables.sort_by_key(|table| table.seats);
+        Ok(tables)
+    }
+
+    /// Opening windows starting on a local day at the branch; a window closing before it opens runs past midnight
+    fn opening_windows(branch: &Branch, date: NaiveDate) -> Vec<(DateTimeWithTimeZone, DateTimeWithTimeZone)> {
+        let tz = branch.tz();
+
+        branch.opening_hours
+            .for_weekday(date.weekday())
+            .iter()
+            .filter_map(|range| {
+                let close_date = if range.close <= range.open { date.succ_opt()? } else { date };
+                let open = tz.from_local_datetime(&date.and_time(range.open)).earliest()?;
+                let close = tz.from_local_datetime(&close_date.and_time(range.close)).earliest()?;
+                Some((open.fixed_offset(), close.fixed_offset()))
+            })
+            .collect()
+    }
+
+    /// Start and end of a local day at the branch
+    fn local_day(branch: &Branch, date: NaiveDate) -> Result<(DateTimeWithTimeZone, DateTimeWithTimeZone), ApiError> {
+        let tz = branch.tz();
+        let midnight = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|time| tz.from_local_datetime(&time).earliest())
+                .map(|time| time.fixed_offset())
+        };
+
+        match (midnight(date), date.succ_opt().and_then(midnight)) {
+            (Some(from), Some(to)) => Ok((from, to)),
+            _ => Err(ApiError::InvalidInput(format!("Invalid date {}", date))),
+        }
+    }
+
+    /// Refuse bookings in the past, beyond the horizon or outside opening hours
+    fn check_time(&self, branch: &Branch, starts_at: DateTimeWithTimeZone, ends_at: DateTimeWithTimeZone) -> Result<(), ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        if starts_at <= now {
+            return Err(ApiError::InvalidInput("Reservations must start in the future".to_string()));
+        }
+        if starts_at > now + Duration::days(self.config.max_days_ahead) {
+            return Err(ApiError::InvalidInput(format!("Reservations can be made at most {} days ahead", self.config.max_days_ahead)));
+        }
+
+        // A window opened the day before may still be running past midnight
+        let date = starts_at.with_timezone(&branch.tz()).date_naive();
+        let within = [date.pred_opt(), Some(date)]
+            .into_iter()
+            .flatten()
+            .flat_map(|date| Self::opening_windows(branch, date))
+            .any(|(open, close)| open <= starts_at && ends_at <= close);
+
+        if !within {
+            return Err(ApiError::InvalidInput("The branch is not open for the whole reservation".to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_seats(table: &Table, party_size: i32) -> Result<(), ApiError> {
+        if table.seats < party_size {
+            return Err(ApiError::InvalidInput(format!("Table {} seats only {}", table.label, table.seats)));
+        }
+        Ok(())
+    }
+
+    fn check_override(role: &UserRole, override_rules: bool) -> Result<(), ApiError> {
+        if override_rules && role.level() < UserRole::Manager.level() {
+            return Err(ApiError::Forbidden("Only managers can override booking rules".to_string()));
+        }
+        Ok(())
+    }
+
+    fn duration(&self, minutes: Option<i64>) -> Result<i64, ApiError> {
+        let minutes = minutes.unwrap_or(self.config.default_duration_minutes);
+        if !(15..=720).contains(&minutes) {
+            return Err(ApiError::InvalidInput("Duration must be between 15 and 720 minutes".to_string()));
+        }
+        Ok(minutes)
+    }
+
+    /// Fetch a table of the branch
+    async fn get_table(&self, branch: &Branch, table_id: Uuid) -> Result<Table, ApiError> {
+        let table = self.table_repository.get_by_id(table_id).await?;
+        if table.branch_id != branch.id {
+            return Err(ApiError::NotFound("Table not found".to_string()));
+        }
+        Ok(table)
+    }
+
+    /// Fetch a reservation the caller may see: customers only their own bookings
+    async fn get_visible(&self, actor: &UserInfo, id: Uuid) -> Result<Reservation, ApiError> {
+        let reservation = self.get_owned(actor, id).await?;
+
+        if actor.parsed_role()? == UserRole::Customer && reservation.customer_id != Some(actor.parsed_id()?) {
+            return Err(ApiError::NotFound("Reservation not found".to_string()));
+        }
+
+        Ok(reservation)
+    }
+
+    /// Fetch a reservation, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Reservation, ApiError> {
+        let reservation = self.repository.get_by_id(id).await?;
+
+        if reservation.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Reservation not found".to_string()));
+        }
+
+        Ok(reservation)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    async fn publish(&self, kind: &str, reservation: &Reservation) {
+        let topics = vec![topic::branch(reservation.branch_id)];
+        self.events.publish(Event::new(kind, topics, reservation)).await;
+    }
+
+    /// Record a reservation change in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (AuditTarget::Reservation, Some(id)), before, after)
+            .await
+    }
+}
@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::review::entity::{
+        CreateReviewRequest, ModerateReviewRequest, ReviewPageQuery, ReviewQuery, ReviewSummary, ReviewSummaryQuery, ReviewView,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Visible reviews of a branch
+pub async fn get_public(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ReviewPageQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Page<ReviewView>>, ApiError> {
+    info!("Fetching reviews of branch {}", id);
+    let result = state.review_service.get_public(id, query).await?;
+    Ok(Json(result))
+}
+
+/// Review a paid order or a visit
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateReviewRequest>,
+) -> Result<(StatusCode, Json<ReviewView>), ApiError> {
+    info!("Creating review by user {}", user.id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.review_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Get the reviews of the logged-in customer
+pub async fn get_mine(
+    Query(query): Query<ReviewPageQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<ReviewView>>, ApiError> {
+    info!("Fetching reviews of user {}", user.id);
+    let result = state.review_service.get_mine(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// List reviews of a branch
+pub async fn get_all(
+    Query(query): Query<ReviewQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<ReviewView>>, ApiError> {
+    info!("Fetching reviews");
+    let result = state.review_service.search(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Ratings of a branch and its staff
+pub async fn get_summary(
+    Query(query): Query<ReviewSummaryQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<ReviewSummary>, ApiError> {
+    info!("Fetching review summary");
+    let result = state.review_service.summary(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific review by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<ReviewView>, ApiError> {
+    info!("Fetching review with ID: {}", id);
+    let result = state.review_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Show, flag or hide a review
+pub async fn moderate(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<ModerateReviewRequest>,
+) -> Result<Json<ReviewView>, ApiError> {
+    info!("Setting status of review {} to {}", id, payload.status);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.review_service.moderate(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// A customer's rating of a paid order or a visit
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "reviews")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub customer_id: Uuid,
+    /// Exactly one of the order and the reservation is set; each is reviewed at most once
+    pub order_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    /// Who served the customer: the order's creator or the waiter of the reserved table
+    pub staff_id: Option<Uuid>,
+    /// Stars from 1 to 5
+    pub rating: i32,
+    pub tags: Vec<String>,
+    pub comment: Option<String>,
+    /// Hide the customer from everyone but themselves
+    pub anonymous: bool,
+    pub status: String,
+    pub moderation_note: Option<String>,
+    pub moderated_by: Option<Uuid>,
+    pub moderated_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Review", 17)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("customer_id", &self.customer_id)?;
+        state.serialize_field("order_id", &self.order_id)?;
+        state.serialize_field("reservation_id", &self.reservation_id)?;
+        state.serialize_field("staff_id", &self.staff_id)?;
+        state.serialize_field("rating", &self.rating)?;
+        state.serialize_field("tags", &self.tags)?;
+        state.serialize_field("comment", &self.comment)?;
+        state.serialize_field("anonymous", &self.anonymous)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("moderation_note", &self.moderation_note)?;
+        state.serialize_field("moderated_by", &self.moderated_by)?;
+        state.serialize_field("moderated_at", &self.moderated_at)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewStatus {
+    /// Shown publicly and counted in ratings
+    Visible,
+    /// Under review by a manager; counted but not shown publicly
+    Flagged,
+    /// Removed from public view and ratings
+    Hidden,
+}
+
+impl std::fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReviewStatus::Visible => write!(f, "VISIBLE"),
+            ReviewStatus::Flagged => write!(f, "FLAGGED"),
+            ReviewStatus::Hidden => write!(f, "HIDDEN"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "VISIBLE" => Ok(ReviewStatus::Visible),
+            "FLAGGED" => Ok(ReviewStatus::Flagged),
+            "HIDDEN" => Ok(ReviewStatus::Hidden),
+            _ => Err(format!("Review status {} is not valid", s)),
+        }
+    }
+}
+
+/// Aspects of a visit a review can be tagged with
+pub const REVIEW_TAGS: [&str; 8] = [
+    "FOOD", "DRINKS", "SERVICE", "SPEED", "AMBIENCE", "CLEANLINESS", "VALUE", "MUSIC",
+];
+
+// Validators
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    match tags.iter().find(|t| !REVIEW_TAGS.contains(&t.as_str())) {
+        Some(t) => Err(ValidationError::new("tags").with_message(format!("Review tag {} is not valid", t).into())),
+        None => Ok(()),
+    }
+}
+
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<ReviewStatus>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("status").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateReviewRequest {
+    /// Paid order of the customer; give either this or `reservation_id`
+    pub order_id: Option<Uuid>,
+    /// Reservation of the customer that was seated
+    pub reservation_id: Option<Uuid>,
+
+    #[validate(range(min = 1, max = 5, message = "Rating must be between 1 and 5"))]
+    pub rating: i32,
+
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Vec<String>,
+
+    #[validate(length(max = 2000, message = "Comment must be at most 2000 characters"))]
+    pub comment: Option<String>,
+
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct ModerateReviewRequest {
+    #[validate(custom(function = "validate_status"))]
+    pub status: String,
+
+    #[validate(length(max = 500, message = "Note must be at most 500 characters"))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReviewQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub staff_id: Option<Uuid>,
+    pub status: Option<String>,
+    pub rating: Option<i32>,
+    pub tag: Option<String>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReviewPageQuery {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ReviewSummaryQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+}
+
+/// A review as its reader may see it: anonymous reviews name their author only to the author,
+/// and moderation details are left out for customers and the public
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewView {
+    pub id: Uuid,
+    pub branch_id: Uuid,
+    pub customer_id: Option<Uuid>,
+    /// Name of the customer
+    pub author: Option<String>,
+    pub order_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub staff_id: Option<Uuid>,
+    pub rating: i32,
+    pub tags: Vec<String>,
+    pub comment: Option<String>,
+    pub anonymous: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderation_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moderated_at: Option<DateTimeWithTimeZone>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+/// Ratings of a set of reviews
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RatingSummary {
+    pub count: u64,
+    /// Average stars, rounded to two decimals; `None` without reviews
+    pub average: Option<Decimal>,
+    /// Number of reviews per star count
+    pub stars: BTreeMap<i32, u64>,
+    /// Number of reviews per tag
+    pub tags: BTreeMap<String, u64>,
+}
+
+/// Ratings of the reviews naming one staff member
+#[derive(Debug, Clone, Serialize)]
+pub struct StaffRating {
+    pub user_id: Uuid,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub ratings: RatingSummary,
+}
+
+/// Ratings of a branch and of its staff over a period; hidden reviews are not counted
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewSummary {
+    pub branch_id: Uuid,
+    pub from: Option<DateTimeWithTimeZone>,
+    pub to: Option<DateTimeWithTimeZone>,
+    pub overall: RatingSummary,
+    pub by_staff: Vec<StaffRating>,
+}
+
+/// A review about to be stored
+#[derive(Debug, Clone)]
+pub struct NewReview {
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub customer_id: Uuid,
+    pub order_id: Option<Uuid>,
+    pub reservation_id: Option<Uuid>,
+    pub staff_id: Option<Uuid>,
+    pub rating: i32,
+    pub tags: Vec<String>,
+    pub comment: Option<String>,
+    pub anonymous: bool,
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::review::entity::{
+        ActiveModel, Column, Entity as ReviewEntity, Model as Review, NewReview, ReviewQuery, ReviewStatus,
+    },
+    common::ApiError,
+};
+
+/// Review repository for database operations
+#[derive(Debug, Clone)]
+pub struct ReviewRepository {
+    db: DatabaseConnection,
+}
+
+impl ReviewRepository {
+    /// Create a new review repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Search reviews of a branch, newest first, returning one page and the total count
+    pub async fn search(&self, branch_id: Uuid, query: &ReviewQuery, page: u64, per_page: u64) -> Result<(Vec<Review>, u64), ApiError> {
+        let mut select = ReviewEntity::find().filter(Column::BranchId.eq(branch_id));
+
+        if let Some(staff_id) = query.staff_id {
+            select = select.filter(Column::StaffId.eq(staff_id));
+        }
+        if let Some(ref status) = query.status {
+            select = select.filter(Column::Status.eq(status.as_str()));
+        }
+        if let Some(rating) = query.rating {
+            select = select.filter(Column::Rating.eq(rating));
+        }
+        if let Some(ref tag) = query.tag {
+            select = select.filter(Expr::cust_with_values("? = ANY(tags)", [tag.clone()]));
+        }
+        if let Some(from) = query.from {
+            select = select.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = query.to {
+            select = select.filter(Column::CreatedAt.lt(to));
+        }
+
+        let paginator = select
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count reviews of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let reviews = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch reviews of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((reviews, total))
+    }
+
+    /// Get the visible reviews of a branch, newest first, returning one page and the total count
+    pub async fn get_visible(&self, branch_id: Uuid, page: u64, per_page: u64) -> Result<(Vec<Review>, u64), ApiError> {
+        let query = ReviewQuery { status: Some(ReviewStatus::Visible.to_string()), ..Default::default() };
+        self.search(branch_id, &query, page, per_page).await
+    }
+
+    /// Get the reviews a customer wrote, newest first, returning one page and the total count
+    pub async fn get_by_customer_id(&self, customer_id: Uuid, page: u64, per_page: u64) -> Result<(Vec<Review>, u64), ApiError> {
+        info!("Fetching reviews of customer {}", customer_id);
+
+        let paginator = ReviewEntity::find()
+            .filter(Column::CustomerId.eq(customer_id))
+            .order_by_desc(Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count reviews of customer {}: {}", customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let reviews = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch reviews of customer {}: {}", customer_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((reviews, total))
+    }
+
+    /// Get every review a customer wrote, newest first
+    pub async fn get_all_by_customer_id(&self, customer_id: Uuid) -> Result<Vec<Review>, ApiError> {
+        ReviewEntity::find()
+            .filter(Column::CustomerId.eq(customer_id))
+            .order_by_desc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch reviews of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Drop the comments of a customer's reviews and hide their name, keeping the ratings
+    pub async fn anonymize_customer(&self, customer_id: Uuid) -> Result<u64, ApiError> {
+        info!("Anonymizing reviews of customer {}", customer_id);
+
+        let result = ReviewEntity::update_many()
+            .col_expr(Column::Comment, Expr::value(Option::<String>::None))
+            .col_expr(Column::Anonymous, Expr::value(true))
+            .col_expr(Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(Column::CustomerId.eq(customer_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to anonymize reviews of customer {}: {}", customer_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Get the reviews of a branch counted in its ratings over a period
+    pub async fn get_rated(
+        &self,
+        branch_id: Uuid,
+        from: Option<DateTimeWithTimeZone>,
+        to: Option<DateTimeWithTimeZone>,
+    ) -> Result<Vec<Review>, ApiError> {
+        let mut select = ReviewEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::Status.ne(ReviewStatus::Hidden.to_string()));
+
+        if let Some(from) = from {
+            select = select.filter(Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(Column::CreatedAt.lt(to));
+        }
+
+        select.all(&self.db).await.map_err(|e| {
+            error!("Failed to fetch ratings of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Get a review by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Review, ApiError> {
+        info!("Fetching review with ID: {}", id);
+
+        let review = ReviewEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch review with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match review {
+            Some(review) => Ok(review),
+            None => Err(ApiError::NotFound("Review not found".to_string())),
+        }
+    }
+
+    /// Create a review; the database refuses a second review of the same order or visit
+    pub async fn create(&self, review: NewReview) -> Result<Review, ApiError> {
+        info!("Recording a {} star review at branch {}", review.rating, review.branch_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(review.account_id),
+            branch_id: Set(review.branch_id),
+            customer_id: Set(review.customer_id),
+            order_id: Set(review.order_id),
+            reservation_id: Set(review.reservation_id),
+            staff_id: Set(review.staff_id),
+            rating: Set(review.rating),
+            tags: Set(review.tags),
+            comment: Set(review.comment),
+            anonymous: Set(review.anonymous),
+            status: Set(ReviewStatus::Visible.to_string()),
+            moderation_note: Set(None),
+            moderated_by: Set(None),
+            moderated_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        model.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create review: {}", e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("This visit has already been reviewed".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Set the moderation status of a review
+    pub async fn moderate(&self, id: Uuid, status: ReviewStatus, note: Option<String>, moderated_by: Uuid) -> Result<Review, ApiError> {
+        info!("Setting review {} to {}", id, status);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = ActiveModel {
+            id: Set(id),
+            status: Set(status.to_string()),
+            moderation_note: Set(note),
+            moderated_by: Set(Some(moderated_by)),
+            moderated_at: Set(Some(now)),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to moderate review {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
@@ -0,0 +1,32 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create review routes for customers
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/reviews", post(create))
+        .route("/reviews/mine", get(get_mine))
+}
+
+/// Create review management routes for managers
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/reviews", get(get_all))
+        .route("/reviews/summary", get(get_summary))
+        .route("/reviews/:id", get(get_by_id))
+        .route("/reviews/:id/moderation", put(moderate))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
+
+/// Create the public branch reviews route
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/branches/:id/reviews", get(get_public))
+}
@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use sea_orm::prelude::Decimal;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        order::{entity::OrderStatus, repository::OrderRepository},
+        reservation::{entity::ReservationStatus, repository::ReservationRepository},
+        review::{
+            entity::{
+                CreateReviewRequest, ModerateReviewRequest, Model as Review, NewReview, RatingSummary, ReviewPageQuery, ReviewQuery,
+                ReviewStatus, ReviewSummary, ReviewSummaryQuery, ReviewView, StaffRating, REVIEW_TAGS,
+            },
+            repository::ReviewRepository,
+        },
+        table::repository::TableRepository,
+        user::{entity::UserRole, repository::UserRepository},
+    },
+};
+
+/// Who is reading a review, deciding what it shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reader {
+    Public,
+    Customer(Uuid),
+    Staff,
+}
+
+/// Review service layer for business logic
+#[derive(Debug, Clone)]
+pub struct ReviewService {
+    repository: ReviewRepository,
+    order_repository: OrderRepository,
+    reservation_repository: ReservationRepository,
+    table_repository: TableRepository,
+    branch_repository: BranchRepository,
+    user_repository: UserRepository,
+    audit_service: AuditService,
+}
+
+impl ReviewService {
+    /// Create a new review service
+    pub fn new(
+        repository: ReviewRepository,
+        order_repository: OrderRepository,
+        reservation_repository: ReservationRepository,
+        table_repository: TableRepository,
+        branch_repository: BranchRepository,
+        user_repository: UserRepository,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            repository,
+            order_repository,
+            reservation_repository,
+            table_repository,
+            branch_repository,
+            user_repository,
+            audit_service,
+        }
+    }
+
+    /// Visible reviews of a branch, for anyone
+    pub async fn get_public(&self, branch_id: Uuid, query: ReviewPageQuery) -> Result<Page<ReviewView>, ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (reviews, total) = self.repository.get_visible(branch.id, page, per_page).await?;
+
+        Ok(Page { items: self.views(reviews, Reader::Public).await?, page, per_page, total })
+    }
+
+    /// The reviews the logged-in customer wrote
+    pub async fn get_mine(&self, actor: &UserInfo, query: ReviewPageQuery) -> Result<Page<ReviewView>, ApiError> {
+        let customer_id = actor.parsed_id()?;
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (reviews, total) = self.repository.get_by_customer_id(customer_id, page, per_page).await?;
+
+        Ok(Page { items: self.views(reviews, Reader::Customer(customer_id)).await?, page, per_page, total })
+    }
+
+    /// Reviews of a branch in any status, defaulting to the session's active branch
+    pub async fn search(&self, actor: &UserInfo, query: ReviewQuery) -> Result<Page<ReviewView>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref status) = query.status {
+            status.parse::<ReviewStatus>().map_err(ApiError::InvalidInput)?;
+        }
+        if let Some(ref tag) = query.tag {
+            if !REVIEW_TAGS.contains(&tag.as_str()) {
+                return Err(ApiError::InvalidInput(format!("Review tag {} is not valid", tag)));
+            }
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (reviews, total) = self.repository.search(branch.id, &query, page, per_page).await?;
+
+        Ok(Page { items: self.views(reviews, Reader::Staff).await?, page, per_page, total })
+    }
+
+    /// Get a review of the account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<ReviewView, ApiError> {
+        let review = self.get_owned(actor, id).await?;
+        self.view(review, Reader::Staff).await
+    }
+
+    /// Rate a paid order or a seated visit of the customer; each can be reviewed once
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateReviewRequest) -> Result<ReviewView, ApiError> {
+        if actor.parsed_role()? != UserRole::Customer {
+            return Err(ApiError::Forbidden("Only customers can write reviews".to_string()));
+        }
+        let customer_id = actor.parsed_id()?;
+        let account_id = actor.parsed_account_id()?;
+
+        let (branch_id, staff_id) = match (data.order_id, data.reservation_id) {
+            (Some(order_id), None) => {
+                let order = self.order_repository.get_by_id(order_id).await?;
+                if order.account_id != account_id || order.customer_id != Some(customer_id) {
+                    return Err(ApiError::NotFound("Order not found".to_string()));
+                }
+                if order.status != OrderStatus::Paid.to_string() {
+                    return Err(ApiError::Conflict("Only paid orders can be reviewed".to_string()));
+                }
+                (order.branch_id, Some(order.created_by))
+            }
+            (None, Some(reservation_id)) => {
+                let reservation = self.reservation_repository.get_by_id(reservation_id).await?;
+                if reservation.account_id != account_id || reservation.customer_id != Some(customer_id) {
+                    return Err(ApiError::NotFound("Reservation not found".to_string()));
+                }
+                if reservation.status != ReservationStatus::Seated.to_string() {
+                    return Err(ApiError::Conflict("Only visits the guests were seated for can be reviewed".to_string()));
+                }
+                let table = self.table_repository.get_by_id(reservation.table_id).await?;
+                (reservation.branch_id, table.waiter_id)
+            }
+            _ => return Err(ApiError::InvalidInput("Give either order_id or reservation_id".to_string())),
+        };
+        info!("Customer {} rates branch {} with {} stars", customer_id, branch_id, data.rating);
+
+        let mut tags = Vec::new();
+        for tag in data.tags {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let review = self.repository
+            .create(NewReview {
+                account_id,
+                branch_id,
+                customer_id,
+                order_id: data.order_id,
+                reservation_id: data.reservation_id,
+                staff_id,
+                rating: data.rating,
+                tags,
+                comment: data.comment.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+                anonymous: data.anonymous,
+            })
+            .await?;
+
+        self.audit(ctx, account_id, AuditAction::ReviewCreated, review.id, None, Some(&review)).await?;
+        self.view(review, Reader::Customer(customer_id)).await
+    }
+
+    /// Show, flag or hide a review
+    pub async fn moderate(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: ModerateReviewRequest) -> Result<ReviewView, ApiError> {
+        let status: ReviewStatus = data.status.parse().map_err(ApiError::InvalidInput)?;
+        let before = self.get_owned(actor, id).await?;
+        info!("Moderating review {}: {} -> {}", id, before.status, status);
+
+        let review = self.repository.moderate(id, status, data.note, actor.parsed_id()?).await?;
+
+        self.audit(ctx, review.account_id, AuditAction::ReviewModerated, id, Some(&before), Some(&review)).await?;
+        self.view(review, Reader::Staff).await
+    }
+
+    /// Ratings of a branch and of each staff member named in its reviews
+    pub async fn summary(&self, actor: &UserInfo, query: ReviewSummaryQuery) -> Result<ReviewSummary, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        let reviews = self.repository.get_rated(branch.id, query.from, query.to).await?;
+
+        let mut by_staff: BTreeMap<Uuid, Vec<&Review>> = BTreeMap::new();
+        for review in &reviews {
+            if let Some(staff_id) = review.staff_id {
+                by_staff.entry(staff_id).or_default().push(review);
+            }
+        }
+        let names = self.names(by_staff.keys().copied().collect()).await?;
+
+        Ok(ReviewSummary {
+            branch_id: branch.id,
+            from: query.from,
+            to: query.to,
+            overall: Self::ratings(reviews.iter()),
+            by_staff: by_staff
+                .into_iter()
+                .map(|(user_id, reviews)| StaffRating {
+                    user_id,
+                    name: names.get(&user_id).cloned().flatten(),
+                    ratings: Self::ratings(reviews.into_iter()),
+                })
+                .collect(),
+        })
+    }
+
+    /// Count, average and spread of stars and tags
+    fn ratings<'a>(reviews: impl Iterator<Item = &'a Review>) -> RatingSummary {
+        let mut summary = RatingSummary {
+            stars: (1..=5).map(|stars| (stars, 0)).collect(),
+            ..Default::default()
+        };
+        let mut total = 0i64;
+
+        for review in reviews {
+            summary.count += 1;
+            total += review.rating as i64;
+            *summary.stars.entry(review.rating).or_default() += 1;
+            for tag in &review.tags {
+                *summary.tags.entry(tag.clone()).or_default() += 1;
+            }
+        }
+
+        if summary.count > 0 {
+            summary.average = Some((Decimal::from(total) / Decimal::from(summary.count)).round_dp(2));
+        }
+        summary
+    }
+
+    async fn view(&self, review: Review, reader: Reader) -> Result<ReviewView, ApiError> {
+        let mut views = self.views(vec![review], reader).await?;
+        views.pop().ok_or_else(|| ApiError::NotFound("Review not found".to_string()))
+    }
+
+    /// Present reviews to a reader, naming the authors that may be named
+    async fn views(&self, reviews: Vec<Review>, reader: Reader) -> Result<Vec<ReviewView>, ApiError> {
+        let named = |review: &Review| !review.anonymous || reader == Reader::Customer(review.customer_id);
+
+        let ids: HashSet<Uuid> = reviews.iter().filter(|r| named(r)).map(|r| r.customer_id).collect();
+        let names = self.names(ids.into_iter().collect()).await?;
+
+        Ok(reviews
+            .into_iter()
+            .map(|review| {
+                let (customer_id, author) = if named(&review) {
+                    (Some(review.customer_id), names.get(&review.customer_id).cloned().flatten())
+                } else {
+                    (None, None)
+                };
+                let staff = reader == Reader::Staff;
+
+                ReviewView {
+                    id: review.id,
+                    branch_id: review.branch_id,
+                    customer_id,
+                    author,
+                    order_id: review.order_id,
+                    reservation_id: review.reservation_id,
+                    staff_id: review.staff_id,
+                    rating: review.rating,
+                    tags: review.tags,
+                    comment: review.comment,
+                    anonymous: review.anonymous,
+                    status: (reader != Reader::Public).then_some(review.status),
+                    moderation_note: review.moderation_note.filter(|_| staff),
+                    moderated_at: review.moderated_at.filter(|_| staff),
+                    created_at: review.created_at,
+                }
+            })
+            .collect())
+    }
+
+    /// Names of users by ID
+    async fn names(&self, ids: Vec<Uuid>) -> Result<HashMap<Uuid, Option<String>>, ApiError> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(self.user_repository
+            .get_by_ids(ids)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user.name))
+            .collect())
+    }
+
+    /// Fetch a review, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Review, ApiError> {
+        let review = self.repository.get_by_id(id).await?;
+
+        if review.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Review not found".to_string()));
+        }
+
+        Ok(review)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Record a review change in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (AuditTarget::Review, Some(id)), before, after)
+            .await
+    }
+}
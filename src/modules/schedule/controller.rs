@@ -0,0 +1,245 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::schedule::entity::{
+        ClockAction, ClockInRequest, CreateShiftRequest, Model as Shift, MyShiftsQuery, PinClockRequest, PublishScheduleRequest,
+        SetPinRequest, ShiftQuery, TimeEntryQuery, TimeEntryView, TimesheetFormat, TimesheetQuery, UpdateShiftRequest,
+        UpdateTimeEntryRequest,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// Published shifts of the logged-in staff member
+pub async fn get_my_shifts(
+    Query(query): Query<MyShiftsQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Shift>>, ApiError> {
+    info!("Fetching shifts of user {}", user.id);
+    let result = state.schedule_service.get_my_shifts(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// List the shifts of a branch
+pub async fn get_shifts(
+    Query(query): Query<ShiftQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Shift>>, ApiError> {
+    info!("Fetching shifts");
+    let result = state.schedule_service.get_shifts(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Plan a shift
+pub async fn create_shift(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateShiftRequest>,
+) -> Result<(StatusCode, Json<Shift>), ApiError> {
+    info!("Creating {} shift from {}", payload.role, payload.starts_at);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.schedule_service.create_shift(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Change a shift
+pub async fn update_shift(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateShiftRequest>,
+) -> Result<Json<Shift>, ApiError> {
+    info!("Updating shift with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.schedule_service.update_shift(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a shift
+pub async fn delete_shift(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting shift with ID: {}", id);
+    state.schedule_service.delete_shift(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Publish the schedule of a week
+pub async fn publish(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<PublishScheduleRequest>,
+) -> Result<Json<Vec<Shift>>, ApiError> {
+    info!("Publishing schedule of the week starting {}", payload.week_start);
+    let result = state.schedule_service.publish_week(&ctx, &user, payload).await?;
+    Ok(Json(result))
+}
+
+/// The time entry the logged-in staff member is clocked in on
+pub async fn get_my_clock(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Option<TimeEntryView>>, ApiError> {
+    info!("Fetching time clock of user {}", user.id);
+    let result = state.schedule_service.get_my_clock(&user).await?;
+    Ok(Json(result))
+}
+
+/// Clock in at a branch
+pub async fn clock_in(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    payload: Option<Json<ClockInRequest>>,
+) -> Result<(StatusCode, Json<TimeEntryView>), ApiError> {
+    info!("Clocking in user {}", user.id);
+    let Json(payload) = payload.unwrap_or_default();
+    let result = state.schedule_service.clock(&ctx, &user, ClockAction::ClockIn, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Clock out
+pub async fn clock_out(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<TimeEntryView>, ApiError> {
+    info!("Clocking out user {}", user.id);
+    let result = state.schedule_service.clock(&ctx, &user, ClockAction::ClockOut, ClockInRequest::default()).await?;
+    Ok(Json(result))
+}
+
+/// Start a break
+pub async fn start_break(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<TimeEntryView>, ApiError> {
+    info!("Starting break of user {}", user.id);
+    let result = state.schedule_service.clock(&ctx, &user, ClockAction::BreakStart, ClockInRequest::default()).await?;
+    Ok(Json(result))
+}
+
+/// End the running break
+pub async fn end_break(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<TimeEntryView>, ApiError> {
+    info!("Ending break of user {}", user.id);
+    let result = state.schedule_service.clock(&ctx, &user, ClockAction::BreakEnd, ClockInRequest::default()).await?;
+    Ok(Json(result))
+}
+
+/// Clock a staff member with their PIN at a shared terminal
+pub async fn punch(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<PinClockRequest>,
+) -> Result<Json<TimeEntryView>, ApiError> {
+    info!("Clocking user {} at the terminal of user {}", payload.user_id, user.id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.schedule_service.clock_with_pin(&ctx, &user, payload).await?;
+    Ok(Json(result))
+}
+
+/// Set the clock PIN of the logged-in staff member
+pub async fn set_pin(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetPinRequest>,
+) -> Result<StatusCode, ApiError> {
+    info!("Setting clock PIN of user {}", user.id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    state.schedule_service.set_pin(&ctx, &user, payload).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the time entries of a branch
+pub async fn get_entries(
+    Query(query): Query<TimeEntryQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<TimeEntryView>>, ApiError> {
+    info!("Fetching time entries");
+    let result = state.schedule_service.search_entries(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Correct the times of a time entry
+pub async fn update_entry(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateTimeEntryRequest>,
+) -> Result<Json<TimeEntryView>, ApiError> {
+    info!("Correcting time entry with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.schedule_service.update_entry(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Timesheet of a branch as JSON or CSV
+pub async fn get_timesheet(
+    Query(query): Query<TimesheetQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Response, ApiError> {
+    info!("Fetching timesheet from {} to {}", query.from, query.to);
+    let timesheet = state.schedule_service.timesheet(&user, &query).await?;
+
+    match query.format {
+        TimesheetFormat::Json => Ok(Json(timesheet).into_response()),
+        TimesheetFormat::Csv => {
+            let csv = state.schedule_service.timesheet_csv(&timesheet);
+            let disposition = format!("attachment; filename=\"timesheet-{}-{}.csv\"", timesheet.from, timesheet.to);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                csv,
+            )
+                .into_response())
+        }
+    }
+}
@@ -0,0 +1,477 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::modules::user::entity::UserRole;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "shifts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    /// Empty for an open shift still looking for someone to work it
+    pub user_id: Option<Uuid>,
+    pub role: String,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+    /// Planned unpaid break
+    pub break_minutes: i32,
+    pub notes: Option<String>,
+    /// When the week was published; staff only see published shifts
+    pub published_at: Option<DateTimeWithTimeZone>,
+    pub created_by: Uuid,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Shift", 13)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("user_id", &self.user_id)?;
+        state.serialize_field("role", &self.role)?;
+        state.serialize_field("starts_at", &self.starts_at)?;
+        state.serialize_field("ends_at", &self.ends_at)?;
+        state.serialize_field("break_minutes", &self.break_minutes)?;
+        state.serialize_field("notes", &self.notes)?;
+        state.serialize_field("published_at", &self.published_at)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Planned working time, without the break
+    pub fn scheduled_minutes(&self) -> i64 {
+        (self.ends_at - self.starts_at).num_minutes() - i64::from(self.break_minutes)
+    }
+}
+
+/// Time actually worked, from clock-in to clock-out
+pub mod time_entry {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "time_entries")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub branch_id: Uuid,
+        pub user_id: Uuid,
+        /// Published shift this entry was worked against, if any
+        pub shift_id: Option<Uuid>,
+        pub clock_in_at: DateTimeWithTimeZone,
+        /// Empty while still clocked in
+        pub clock_out_at: Option<DateTimeWithTimeZone>,
+        pub method: String,
+        /// Manager who last corrected the times
+        pub edited_by: Option<Uuid>,
+        pub note: Option<String>,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("TimeEntry", 12)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("user_id", &self.user_id)?;
+            state.serialize_field("shift_id", &self.shift_id)?;
+            state.serialize_field("clock_in_at", &self.clock_in_at)?;
+            state.serialize_field("clock_out_at", &self.clock_out_at)?;
+            state.serialize_field("method", &self.method)?;
+            state.serialize_field("edited_by", &self.edited_by)?;
+            state.serialize_field("note", &self.note)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Breaks taken during a time entry
+pub mod time_break {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "time_breaks")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub time_entry_id: Uuid,
+        pub started_at: DateTimeWithTimeZone,
+        /// Empty while the break is running
+        pub ended_at: Option<DateTimeWithTimeZone>,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("TimeBreak", 5)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("time_entry_id", &self.time_entry_id)?;
+            state.serialize_field("started_at", &self.started_at)?;
+            state.serialize_field("ended_at", &self.ended_at)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// PINs staff use to clock in and out at a shared terminal
+pub mod clock_pin {
+    use sea_orm::entity::prelude::*;
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+    #[sea_orm(table_name = "clock_pins")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub user_id: Uuid,
+        pub account_id: Uuid,
+        pub pin_hash: String,
+        /// Wrong entries since the last correct one or lockout
+        pub failed_attempts: i32,
+        /// No entry is accepted before this time
+        pub locked_until: Option<DateTimeWithTimeZone>,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    impl Model {
+        /// Whether the PIN is locked after too many wrong entries
+        pub fn is_locked(&self) -> bool {
+            self.locked_until.is_some_and(|until| until > chrono::Utc::now())
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockMethod {
+    /// Clocked by the staff member from their own session
+    Session,
+    /// Clocked with a PIN at a shared terminal
+    Pin,
+}
+
+impl std::fmt::Display for ClockMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockMethod::Session => write!(f, "SESSION"),
+            ClockMethod::Pin => write!(f, "PIN"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockAction {
+    ClockIn,
+    ClockOut,
+    BreakStart,
+    BreakEnd,
+}
+
+impl std::fmt::Display for ClockAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClockAction::ClockIn => write!(f, "CLOCK_IN"),
+            ClockAction::ClockOut => write!(f, "CLOCK_OUT"),
+            ClockAction::BreakStart => write!(f, "BREAK_START"),
+            ClockAction::BreakEnd => write!(f, "BREAK_END"),
+        }
+    }
+}
+
+impl std::str::FromStr for ClockAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CLOCK_IN" => Ok(ClockAction::ClockIn),
+            "CLOCK_OUT" => Ok(ClockAction::ClockOut),
+            "BREAK_START" => Ok(ClockAction::BreakStart),
+            "BREAK_END" => Ok(ClockAction::BreakEnd),
+            _ => Err(format!("Clock action {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimesheetFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+// Validators
+fn validate_role(role: &str) -> Result<(), ValidationError> {
+    let role: UserRole = role
+        .parse()
+        .map_err(|e: String| ValidationError::new("role").with_message(e.into()))?;
+
+    if matches!(role, UserRole::Root | UserRole::Customer) {
+        return Err(ValidationError::new("role").with_message(format!("{} cannot be scheduled", role).into()));
+    }
+    Ok(())
+}
+
+fn validate_action(action: &str) -> Result<(), ValidationError> {
+    action
+        .parse::<ClockAction>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("action").with_message(e.into()))
+}
+
+fn validate_pin(pin: &str) -> Result<(), ValidationError> {
+    if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ValidationError::new("pin").with_message("PIN must be 4 to 8 digits".into()));
+    }
+    Ok(())
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateShiftRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// Leave empty for an open shift
+    pub user_id: Option<Uuid>,
+
+    #[validate(custom(function = "validate_role"))]
+    pub role: String,
+
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+
+    #[validate(range(min = 0, max = 240, message = "Break must be between 0 and 240 minutes"))]
+    pub break_minutes: Option<i32>,
+
+    #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateShiftRequest {
+    pub user_id: Option<Uuid>,
+
+    /// Turn the shift into an open shift again
+    #[serde(default)]
+    pub unassign: bool,
+
+    #[validate(custom(function = "validate_role"))]
+    pub role: Option<String>,
+
+    pub starts_at: Option<DateTimeWithTimeZone>,
+    pub ends_at: Option<DateTimeWithTimeZone>,
+
+    #[validate(range(min = 0, max = 240, message = "Break must be between 0 and 240 minutes"))]
+    pub break_minutes: Option<i32>,
+
+    #[validate(length(max = 500, message = "Notes must be at most 500 characters"))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct PublishScheduleRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// Monday of the week, in branch-local time
+    pub week_start: NaiveDate,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, Default)]
+pub struct ClockInRequest {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct PinClockRequest {
+    /// Staff member clocking at the terminal
+    pub user_id: Uuid,
+
+    #[validate(custom(function = "validate_pin"))]
+    pub pin: String,
+
+    /// `CLOCK_IN`, `CLOCK_OUT`, `BREAK_START` or `BREAK_END`
+    #[validate(custom(function = "validate_action"))]
+    pub action: String,
+
+    /// Defaults to the terminal session's active branch
+    pub branch_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetPinRequest {
+    #[validate(custom(function = "validate_pin"))]
+    pub pin: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateTimeEntryRequest {
+    pub clock_in_at: Option<DateTimeWithTimeZone>,
+    pub clock_out_at: Option<DateTimeWithTimeZone>,
+
+    /// Why the times were corrected
+    #[validate(length(min = 1, max = 500, message = "Note must be between 1 and 500 characters"))]
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ShiftQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// First local day, defaulting to today
+    pub from: Option<NaiveDate>,
+    /// Last local day, defaulting to a week after `from`
+    pub to: Option<NaiveDate>,
+    pub user_id: Option<Uuid>,
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct MyShiftsQuery {
+    /// First day, defaulting to today
+    pub from: Option<NaiveDate>,
+    /// Last day, defaulting to two weeks after `from`
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TimeEntryQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    /// First local day
+    pub from: Option<NaiveDate>,
+    /// Last local day
+    pub to: Option<NaiveDate>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimesheetQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+    /// First local day
+    pub from: NaiveDate,
+    /// Last local day
+    pub to: NaiveDate,
+    pub user_id: Option<Uuid>,
+    #[serde(default)]
+    pub format: TimesheetFormat,
+}
+
+/// A time entry with its breaks
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeEntryView {
+    #[serde(flatten)]
+    pub entry: time_entry::Model,
+    pub breaks: Vec<time_break::Model>,
+    pub on_break: bool,
+    /// Minutes worked so far, without breaks
+    pub worked_minutes: i64,
+    pub break_minutes: i64,
+}
+
+/// Hours worked by one staff member on one local day
+#[derive(Debug, Clone, Serialize)]
+pub struct TimesheetDay {
+    pub date: NaiveDate,
+    pub worked_minutes: i64,
+    pub break_minutes: i64,
+    /// Worked beyond the daily limit
+    pub overtime_minutes: i64,
+}
+
+/// Hours worked by one staff member over the period
+#[derive(Debug, Clone, Serialize)]
+pub struct StaffTimesheet {
+    pub user_id: Uuid,
+    pub name: Option<String>,
+    pub email: String,
+    pub days: Vec<TimesheetDay>,
+    pub scheduled_minutes: i64,
+    pub worked_minutes: i64,
+    pub regular_minutes: i64,
+    pub daily_overtime_minutes: i64,
+    /// Regular time beyond the weekly limit
+    pub weekly_overtime_minutes: i64,
+    pub overtime_minutes: i64,
+    /// Entries still clocked in, left out of the totals
+    pub open_entries: usize,
+}
+
+/// Timesheet of a branch
+#[derive(Debug, Clone, Serialize)]
+pub struct Timesheet {
+    pub branch_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub daily_overtime_after_minutes: i64,
+    pub weekly_overtime_after_minutes: i64,
+    pub staff: Vec<StaffTimesheet>,
+}
+
+/// A shift about to be stored
+#[derive(Debug, Clone)]
+pub struct NewShift {
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub role: String,
+    pub starts_at: DateTimeWithTimeZone,
+    pub ends_at: DateTimeWithTimeZone,
+    pub break_minutes: i32,
+    pub notes: Option<String>,
+    pub created_by: Uuid,
+}
@@ -0,0 +1,642 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::{Expr, OnConflict}, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::schedule::entity::{
+        clock_pin, time_break, time_entry, ActiveModel, ClockMethod, Column, Entity as ShiftEntity, Model as Shift, NewShift,
+    },
+    common::ApiError,
+};
+
+/// Name of the exclusion constraint keeping a staff member from being scheduled twice at once
+const NO_OVERLAP: &str = "shifts_no_overlap";
+
+/// How long before the start of a shift clocking in counts towards it
+const EARLY_CLOCK_IN_MINUTES: i64 = 60;
+
+/// Schedule repository for database operations
+#[derive(Debug, Clone)]
+pub struct ScheduleRepository {
+    db: DatabaseConnection,
+}
+
+impl ScheduleRepository {
+    /// Create a new schedule repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the shifts of a branch starting between `from` and `to`
+    pub async fn get_shifts(
+        &self,
+        branch_id: Uuid,
+        (from, to): (DateTimeWithTimeZone, DateTimeWithTimeZone),
+        user_id: Option<Uuid>,
+        role: Option<&str>,
+    ) -> Result<Vec<Shift>, ApiError> {
+        let mut select = ShiftEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::StartsAt.gte(from))
+            .filter(Column::StartsAt.lt(to));
+
+        if let Some(user_id) = user_id {
+            select = select.filter(Column::UserId.eq(user_id));
+        }
+        if let Some(role) = role {
+            select = select.filter(Column::Role.eq(role));
+        }
+
+        select
+            .order_by_asc(Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch shifts of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the published shifts of a staff member starting between `from` and `to`
+    pub async fn get_published_shifts_of_user(
+        &self,
+        user_id: Uuid,
+        from: DateTimeWithTimeZone,
+        to: DateTimeWithTimeZone,
+    ) -> Result<Vec<Shift>, ApiError> {
+        ShiftEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::PublishedAt.is_not_null())
+            .filter(Column::StartsAt.gte(from))
+            .filter(Column::StartsAt.lt(to))
+            .order_by_asc(Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch shifts of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the published shift of a staff member at a branch that clocking in at `at` belongs to
+    pub async fn get_current_shift(&self, user_id: Uuid, branch_id: Uuid, at: DateTimeWithTimeZone) -> Result<Option<Shift>, ApiError> {
+        ShiftEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::PublishedAt.is_not_null())
+            .filter(Column::StartsAt.lte(at + chrono::Duration::minutes(EARLY_CLOCK_IN_MINUTES)))
+            .filter(Column::EndsAt.gt(at))
+            .order_by_asc(Column::StartsAt)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch current shift of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a shift by ID
+    pub async fn get_shift(&self, id: Uuid) -> Result<Shift, ApiError> {
+        info!("Fetching shift with ID: {}", id);
+
+        let shift = ShiftEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch shift with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match shift {
+            Some(shift) => Ok(shift),
+            None => Err(ApiError::NotFound("Shift not found".to_string())),
+        }
+    }
+
+    /// Create a shift; the database refuses it if the staff member already works at an overlapping time
+    pub async fn create_shift(&self, shift: NewShift) -> Result<Shift, ApiError> {
+        info!("Scheduling {} shift at branch {} from {}", shift.role, shift.branch_id, shift.starts_at);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(shift.account_id),
+            branch_id: Set(shift.branch_id),
+            user_id: Set(shift.user_id),
+            role: Set(shift.role),
+            starts_at: Set(shift.starts_at),
+            ends_at: Set(shift.ends_at),
+            break_minutes: Set(shift.break_minutes),
+            notes: Set(shift.notes),
+            published_at: Set(None),
+            created_by: Set(shift.created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        model.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create shift: {}", e);
+                Self::shift_error(e)
+            })
+    }
+
+    /// Save a changed shift; the database refuses overlaps here too
+    pub async fn update_shift(&self, shift: Shift) -> Result<Shift, ApiError> {
+        info!("Updating shift with ID: {}", shift.id);
+
+        let id = shift.id;
+        let mut model = ActiveModel::from(shift).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update shift with ID {}: {}", id, e);
+                Self::shift_error(e)
+            })
+    }
+
+    /// Delete a shift; time entries worked against it keep their times
+    pub async fn delete_shift(&self, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting shift with ID: {}", id);
+
+        ShiftEntity::delete_by_id(id)
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete shift with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Publish the draft shifts of a branch starting between `from` and `to`, returning those just published
+    pub async fn publish(&self, branch_id: Uuid, from: DateTimeWithTimeZone, to: DateTimeWithTimeZone) -> Result<Vec<Shift>, ApiError> {
+        info!("Publishing shifts of branch {} from {} to {}", branch_id, from, to);
+
+        let drafts: Vec<Uuid> = ShiftEntity::find()
+            .select_only()
+            .column(Column::Id)
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::StartsAt.gte(from))
+            .filter(Column::StartsAt.lt(to))
+            .filter(Column::PublishedAt.is_null())
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch draft shifts of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        ShiftEntity::update_many()
+            .col_expr(Column::PublishedAt, Expr::value(now))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(Column::Id.is_in(drafts.clone()))
+            .filter(Column::PublishedAt.is_null())
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to publish shifts of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        ShiftEntity::find()
+            .filter(Column::Id.is_in(drafts))
+            .order_by_asc(Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch published shifts of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the entry a staff member is still clocked in on
+    pub async fn get_open_entry(&self, user_id: Uuid) -> Result<Option<time_entry::Model>, ApiError> {
+        time_entry::Entity::find()
+            .filter(time_entry::Column::UserId.eq(user_id))
+            .filter(time_entry::Column::ClockOutAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch open time entry of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a time entry by ID
+    pub async fn get_entry(&self, id: Uuid) -> Result<time_entry::Model, ApiError> {
+        info!("Fetching time entry with ID: {}", id);
+
+        let entry = time_entry::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch time entry with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match entry {
+            Some(entry) => Ok(entry),
+            None => Err(ApiError::NotFound("Time entry not found".to_string())),
+        }
+    }
+
+    /// Search the time entries of a branch by clock-in time, returning one page and the total count
+    pub async fn search_entries(
+        &self,
+        branch_id: Uuid,
+        (from, to): (Option<DateTimeWithTimeZone>, Option<DateTimeWithTimeZone>),
+        user_id: Option<Uuid>,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<time_entry::Model>, u64), ApiError> {
+        let mut select = time_entry::Entity::find().filter(time_entry::Column::BranchId.eq(branch_id));
+
+        if let Some(from) = from {
+            select = select.filter(time_entry::Column::ClockInAt.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(time_entry::Column::ClockInAt.lt(to));
+        }
+        if let Some(user_id) = user_id {
+            select = select.filter(time_entry::Column::UserId.eq(user_id));
+        }
+
+        let paginator = select
+            .order_by_desc(time_entry::Column::ClockInAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count time entries of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let entries = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch time entries of branch {}: {}", branch_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((entries, total))
+    }
+
+    /// Get every time entry of a branch clocked in between `from` and `to`
+    pub async fn get_entries_between(
+        &self,
+        branch_id: Uuid,
+        from: DateTimeWithTimeZone,
+        to: DateTimeWithTimeZone,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<time_entry::Model>, ApiError> {
+        let mut select = time_entry::Entity::find()
+            .filter(time_entry::Column::BranchId.eq(branch_id))
+            .filter(time_entry::Column::ClockInAt.gte(from))
+            .filter(time_entry::Column::ClockInAt.lt(to));
+
+        if let Some(user_id) = user_id {
+            select = select.filter(time_entry::Column::UserId.eq(user_id));
+        }
+
+        select
+            .order_by_asc(time_entry::Column::ClockInAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch time entries of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the breaks of time entries
+    pub async fn get_breaks(&self, entry_ids: Vec<Uuid>) -> Result<Vec<time_break::Model>, ApiError> {
+        time_break::Entity::find()
+            .filter(time_break::Column::TimeEntryId.is_in(entry_ids))
+            .order_by_asc(time_break::Column::StartedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch breaks: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Clock a staff member in; the database refuses a second open entry
+    pub async fn clock_in(
+        &self,
+        (account_id, branch_id, user_id): (Uuid, Uuid, Uuid),
+        shift_id: Option<Uuid>,
+        method: ClockMethod,
+    ) -> Result<time_entry::Model, ApiError> {
+        info!("Clocking in user {} at branch {}", user_id, branch_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = time_entry::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(branch_id),
+            user_id: Set(user_id),
+            shift_id: Set(shift_id),
+            clock_in_at: Set(now),
+            clock_out_at: Set(None),
+            method: Set(method.to_string()),
+            edited_by: Set(None),
+            note: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        model.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to clock in user {}: {}", user_id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => ApiError::Conflict("Already clocked in".to_string()),
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Clock out of an entry, ending a running break with it
+    pub async fn clock_out(&self, id: Uuid) -> Result<time_entry::Model, ApiError> {
+        info!("Clocking out of time entry {}", id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        time_break::Entity::update_many()
+            .col_expr(time_break::Column::EndedAt, Expr::value(now))
+            .filter(time_break::Column::TimeEntryId.eq(id))
+            .filter(time_break::Column::EndedAt.is_null())
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to end break of time entry {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let result = time_entry::Entity::update_many()
+            .col_expr(time_entry::Column::ClockOutAt, Expr::value(now))
+            .col_expr(time_entry::Column::UpdatedAt, Expr::value(now))
+            .filter(time_entry::Column::Id.eq(id))
+            .filter(time_entry::Column::ClockOutAt.is_null())
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to clock out of time entry {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::Conflict("Not clocked in".to_string()));
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit clock out of time entry {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        self.get_entry(id).await
+    }
+
+    /// Start a break; the database refuses a second running break
+    pub async fn start_break(&self, time_entry_id: Uuid) -> Result<time_break::Model, ApiError> {
+        info!("Starting break of time entry {}", time_entry_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let model = time_break::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            time_entry_id: Set(time_entry_id),
+            started_at: Set(now),
+            ended_at: Set(None),
+            created_at: Set(now),
+        };
+
+        model.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to start break of time entry {}: {}", time_entry_id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => ApiError::Conflict("Already on a break".to_string()),
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// End the running break of an entry
+    pub async fn end_break(&self, time_entry_id: Uuid) -> Result<(), ApiError> {
+        info!("Ending break of time entry {}", time_entry_id);
+
+        let result = time_break::Entity::update_many()
+            .col_expr(time_break::Column::EndedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(time_break::Column::TimeEntryId.eq(time_entry_id))
+            .filter(time_break::Column::EndedAt.is_null())
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to end break of time entry {}: {}", time_entry_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if result.rows_affected == 0 {
+            return Err(ApiError::Conflict("Not on a break".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Save corrected times of an entry
+    pub async fn update_entry(&self, entry: time_entry::Model) -> Result<time_entry::Model, ApiError> {
+        info!("Updating time entry with ID: {}", entry.id);
+
+        let id = entry.id;
+        let mut model = time_entry::ActiveModel::from(entry).reset_all();
+        model.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        model.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update time entry with ID {}: {}", id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => ApiError::Conflict("Already clocked in".to_string()),
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Get every shift a staff member was given, latest first
+    pub async fn get_shifts_of_user(&self, user_id: Uuid) -> Result<Vec<Shift>, ApiError> {
+        ShiftEntity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::StartsAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch shifts of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get every time entry of a staff member, latest first
+    pub async fn get_entries_of_user(&self, user_id: Uuid) -> Result<Vec<time_entry::Model>, ApiError> {
+        time_entry::Entity::find()
+            .filter(time_entry::Column::UserId.eq(user_id))
+            .order_by_desc(time_entry::Column::ClockInAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch time entries of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Clear the notes on a staff member's shifts and time entries and drop their clock PIN, keeping the hours
+    pub async fn anonymize_user(&self, user_id: Uuid) -> Result<(), ApiError> {
+        info!("Anonymizing schedule of user {}", user_id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start schedule anonymization transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let map_err = |e: DbErr| {
+            error!("Failed to anonymize schedule of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        };
+        let now = chrono::Utc::now().fixed_offset();
+        ShiftEntity::update_many()
+            .col_expr(Column::Notes, Expr::value(Option::<String>::None))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await
+            .map_err(map_err)?;
+        time_entry::Entity::update_many()
+            .col_expr(time_entry::Column::Note, Expr::value(Option::<String>::None))
+            .col_expr(time_entry::Column::UpdatedAt, Expr::value(now))
+            .filter(time_entry::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await
+            .map_err(map_err)?;
+        clock_pin::Entity::delete_by_id(user_id).exec(&txn).await.map_err(map_err)?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit schedule anonymization of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Get the clock PIN of a staff member
+    pub async fn get_pin(&self, user_id: Uuid) -> Result<Option<clock_pin::Model>, ApiError> {
+        clock_pin::Entity::find_by_id(user_id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch clock PIN of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Set or replace the clock PIN of a staff member
+    pub async fn set_pin(&self, user_id: Uuid, account_id: Uuid, pin_hash: String) -> Result<(), ApiError> {
+        info!("Setting clock PIN of user {}", user_id);
+
+        let model = clock_pin::ActiveModel {
+            user_id: Set(user_id),
+            account_id: Set(account_id),
+            pin_hash: Set(pin_hash),
+            failed_attempts: Set(0),
+            locked_until: Set(None),
+            updated_at: Set(chrono::Utc::now().fixed_offset()),
+        };
+
+        // A new PIN starts without failed attempts or lockout
+        clock_pin::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(clock_pin::Column::UserId)
+                    .update_columns([
+                        clock_pin::Column::PinHash,
+                        clock_pin::Column::FailedAttempts,
+                        clock_pin::Column::LockedUntil,
+                        clock_pin::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to set clock PIN of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Count a wrong PIN entry, locking the PIN for `lockout` once `max_attempts` are reached in a row
+    pub async fn record_pin_failure(&self, user_id: Uuid, max_attempts: i32, lockout: chrono::Duration) -> Result<clock_pin::Model, ApiError> {
+        let map_err = |e: DbErr| {
+            error!("Failed to count wrong clock PIN of user {}: {}", user_id, e);
+            ApiError::DatabaseError(e.to_string())
+        };
+
+        let txn = self.db.begin().await.map_err(map_err)?;
+
+        // Terminals entering PINs at the same time must not lose a count
+        let pin = clock_pin::Entity::find_by_id(user_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(map_err)?
+            .ok_or_else(|| ApiError::NotFound("Clock PIN not found".to_string()))?;
+
+        let attempts = pin.failed_attempts + 1;
+        let mut model: clock_pin::ActiveModel = pin.into();
+        if attempts >= max_attempts {
+            info!("Locking clock PIN of user {} after {} wrong entries", user_id, attempts);
+            model.failed_attempts = Set(0);
+            model.locked_until = Set(Some((chrono::Utc::now() + lockout).fixed_offset()));
+        } else {
+            model.failed_attempts = Set(attempts);
+        }
+
+        let pin = model.update(&txn).await.map_err(map_err)?;
+        txn.commit().await.map_err(map_err)?;
+
+        Ok(pin)
+    }
+
+    /// Forget the wrong PIN entries of a staff member after a correct one
+    pub async fn reset_pin_failures(&self, user_id: Uuid) -> Result<(), ApiError> {
+        clock_pin::Entity::update_many()
+            .col_expr(clock_pin::Column::FailedAttempts, Expr::value(0))
+            .col_expr(clock_pin::Column::LockedUntil, Expr::value(Option::<DateTimeWithTimeZone>::None))
+            .filter(clock_pin::Column::UserId.eq(user_id))
+            .filter(clock_pin::Column::FailedAttempts.gt(0))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to reset wrong clock PIN entries of user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    fn shift_error(e: DbErr) -> ApiError {
+        if e.to_string().contains(NO_OVERLAP) {
+            return ApiError::Conflict("Staff member already has a shift at that time".to_string());
+        }
+        ApiError::DatabaseError(e.to_string())
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create shift and time clock routes for staff
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/shifts/mine", get(get_my_shifts))
+        .route("/time-clock", get(get_my_clock))
+        .route("/time-clock/clock-in", post(clock_in))
+        .route("/time-clock/clock-out", post(clock_out))
+        .route("/time-clock/break/start", post(start_break))
+        .route("/time-clock/break/end", post(end_break))
+        .route("/time-clock/punch", post(punch))
+        .route("/time-clock/pin", put(set_pin))
+        .layer(middleware::from_fn(authorize(vec![
+            "ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER", "COOK", "BARMAN", "CASH_REGISTER",
+        ])))
+}
+
+/// Create scheduling and timesheet routes for managers
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/shifts", get(get_shifts).post(create_shift))
+        .route("/shifts/:id", put(update_shift).delete(delete_shift))
+        .route("/shifts/publish", post(publish))
+        .route("/time-entries", get(get_entries))
+        .route("/time-entries/:id", put(update_entry))
+        .route("/timesheets", get(get_timesheet))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
@@ -0,0 +1,678 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use argon2::password_hash::{SaltString, rand_core::OsRng};
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Weekday};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::{
+    common::{
+        config::{Config, ScheduleConfig},
+        events::{topic, Event, SharedEventBus},
+        pagination::{self, Page},
+        ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        schedule::{
+            entity::{
+                clock_pin, time_break, time_entry, ClockAction, ClockInRequest, ClockMethod, CreateShiftRequest, Model as Shift, MyShiftsQuery,
+                NewShift, PinClockRequest, PublishScheduleRequest, SetPinRequest, ShiftQuery, StaffTimesheet, TimeEntryQuery,
+                TimeEntryView, Timesheet, TimesheetDay, TimesheetQuery, UpdateShiftRequest, UpdateTimeEntryRequest,
+            },
+            repository::ScheduleRepository,
+        },
+        user::{entity::{Model as User, UserRole}, repository::UserRepository},
+    },
+};
+
+/// Longest period a shift listing or timesheet may cover
+const MAX_RANGE_DAYS: i64 = 62;
+
+/// Longest shift that can be scheduled
+const MAX_SHIFT_HOURS: i64 = 16;
+
+/// Schedule service layer for business logic
+#[derive(Debug, Clone)]
+pub struct ScheduleService {
+    repository: ScheduleRepository,
+    branch_repository: BranchRepository,
+    user_repository: UserRepository,
+    audit_service: AuditService,
+    events: SharedEventBus,
+    config: ScheduleConfig,
+}
+
+impl ScheduleService {
+    /// Create a new schedule service
+    pub fn new(
+        repository: ScheduleRepository,
+        branch_repository: BranchRepository,
+        user_repository: UserRepository,
+        audit_service: AuditService,
+        events: SharedEventBus,
+        config: &Config,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            user_repository,
+            audit_service,
+            events,
+            config: config.schedule.clone(),
+        }
+    }
+
+    /// Shifts of a branch, drafts included, defaulting to the coming week at the session's active branch
+    pub async fn get_shifts(&self, actor: &UserInfo, query: ShiftQuery) -> Result<Vec<Shift>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        if let Some(ref role) = query.role {
+            role.parse::<UserRole>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let from = query.from.unwrap_or_else(|| chrono::Utc::now().with_timezone(&branch.tz()).date_naive());
+        let to = query.to.unwrap_or(from + Duration::days(6));
+        let range = Self::local_days(&branch, from, to)?;
+
+        self.repository.get_shifts(branch.id, range, query.user_id, query.role.as_deref()).await
+    }
+
+    /// Published shifts of the logged-in staff member at any branch, defaulting to the next two weeks
+    pub async fn get_my_shifts(&self, actor: &UserInfo, query: MyShiftsQuery) -> Result<Vec<Shift>, ApiError> {
+        let from = query.from.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let to = query.to.unwrap_or(from + Duration::days(13));
+        Self::check_range(from, to)?;
+
+        let utc_midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|time| time.and_utc().fixed_offset());
+        let (Some(from), Some(to)) = (utc_midnight(from), to.succ_opt().and_then(utc_midnight)) else {
+            return Err(ApiError::InvalidInput("Invalid date range".to_string()));
+        };
+
+        self.repository.get_published_shifts_of_user(actor.parsed_id()?, from, to).await
+    }
+
+    /// Plan a shift at a branch, assigned to a staff member or left open
+    pub async fn create_shift(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateShiftRequest) -> Result<Shift, ApiError> {
+        info!("Scheduling {} shift from {}", data.role, data.starts_at);
+
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        let break_minutes = data.break_minutes.unwrap_or(0);
+        Self::check_shift(data.starts_at, data.ends_at, break_minutes)?;
+        if let Some(user_id) = data.user_id {
+            self.get_staff(branch.account_id, user_id).await?;
+        }
+
+        let shift = self.repository
+            .create_shift(NewShift {
+                account_id: branch.account_id,
+                branch_id: branch.id,
+                user_id: data.user_id,
+                role: data.role,
+                starts_at: data.starts_at,
+                ends_at: data.ends_at,
+                break_minutes,
+                notes: data.notes,
+                created_by: actor.parsed_id()?,
+            })
+            .await?;
+
+        self.audit(ctx, shift.account_id, AuditAction::ShiftCreated, (AuditTarget::Shift, shift.id), None, Some(&shift)).await?;
+        Ok(shift)
+    }
+
+    /// Change a shift; staff are told when a published shift changes
+    pub async fn update_shift(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateShiftRequest) -> Result<Shift, ApiError> {
+        info!("Updating shift with ID: {}", id);
+
+        let before = self.get_owned_shift(actor, id).await?;
+        if data.unassign && data.user_id.is_some() {
+            return Err(ApiError::InvalidInput("Either assign or unassign the shift".to_string()));
+        }
+
+        let mut shift = before.clone();
+        if let Some(user_id) = data.user_id {
+            self.get_staff(shift.account_id, user_id).await?;
+            shift.user_id = Some(user_id);
+        }
+        if data.unassign {
+            shift.user_id = None;
+        }
+        if let Some(role) = data.role {
+            shift.role = role;
+        }
+        if let Some(starts_at) = data.starts_at {
+            shift.starts_at = starts_at;
+        }
+        if let Some(ends_at) = data.ends_at {
+            shift.ends_at = ends_at;
+        }
+        if let Some(break_minutes) = data.break_minutes {
+            shift.break_minutes = break_minutes;
+        }
+        if data.notes.is_some() {
+            shift.notes = data.notes;
+        }
+        Self::check_shift(shift.starts_at, shift.ends_at, shift.break_minutes)?;
+
+        let shift = self.repository.update_shift(shift).await?;
+
+        self.audit(ctx, shift.account_id, AuditAction::ShiftUpdated, (AuditTarget::Shift, id), Some(&before), Some(&shift)).await?;
+        if shift.published_at.is_some() {
+            self.publish_event("schedule.shift_updated", shift.branch_id, &shift).await;
+        }
+        Ok(shift)
+    }
+
+    /// Remove a shift from the schedule
+    pub async fn delete_shift(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting shift with ID: {}", id);
+
+        let shift = self.get_owned_shift(actor, id).await?;
+        self.repository.delete_shift(id).await?;
+
+        self.audit(ctx, shift.account_id, AuditAction::ShiftDeleted, (AuditTarget::Shift, id), Some(&shift), None).await?;
+        if shift.published_at.is_some() {
+            self.publish_event("schedule.shift_deleted", shift.branch_id, &shift).await;
+        }
+        Ok(())
+    }
+
+    /// Publish the draft shifts of a branch-local week so staff can see them
+    pub async fn publish_week(&self, ctx: &RequestContext, actor: &UserInfo, data: PublishScheduleRequest) -> Result<Vec<Shift>, ApiError> {
+        info!("Publishing schedule of the week starting {}", data.week_start);
+
+        if data.week_start.weekday() != Weekday::Mon {
+            return Err(ApiError::InvalidInput("week_start must be a Monday".to_string()));
+        }
+        let branch = self.resolve_branch(actor, data.branch_id).await?;
+        let (from, to) = Self::local_days(&branch, data.week_start, data.week_start + Duration::days(6))?;
+
+        let shifts = self.repository.publish(branch.id, from, to).await?;
+
+        let payload = serde_json::json!({
+            "branch_id": branch.id,
+            "week_start": data.week_start,
+            "shifts": shifts,
+        });
+        self.audit(ctx, branch.account_id, AuditAction::SchedulePublished, (AuditTarget::Branch, branch.id), None, Some(&payload)).await?;
+        self.publish_event("schedule.published", branch.id, &payload).await;
+        Ok(shifts)
+    }
+
+    /// The entry the logged-in staff member is clocked in on, if any
+    pub async fn get_my_clock(&self, actor: &UserInfo) -> Result<Option<TimeEntryView>, ApiError> {
+        match self.repository.get_open_entry(actor.parsed_id()?).await? {
+            Some(entry) => Ok(Some(self.view(entry).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Clock the logged-in staff member in, out, or on or off a break
+    pub async fn clock(
+        &self,
+        ctx: &RequestContext,
+        actor: &UserInfo,
+        action: ClockAction,
+        data: ClockInRequest,
+    ) -> Result<TimeEntryView, ApiError> {
+        let user = self.get_staff(actor.parsed_account_id()?, actor.parsed_id()?).await?;
+        self.punch(ctx, actor, &user, action, data.branch_id, ClockMethod::Session).await
+    }
+
+    /// Clock a staff member at a shared terminal, identified by their PIN
+    pub async fn clock_with_pin(&self, ctx: &RequestContext, actor: &UserInfo, data: PinClockRequest) -> Result<TimeEntryView, ApiError> {
+        let action: ClockAction = data.action.parse().map_err(ApiError::InvalidInput)?;
+        let user = self.get_staff(actor.parsed_account_id()?, data.user_id).await?;
+
+        let pin = self.repository
+            .get_pin(user.id)
+            .await?
+            .ok_or_else(|| ApiError::InvalidInput("No clock PIN has been set".to_string()))?;
+
+        // A locked PIN is not even checked, so guessing on gains nothing
+        if pin.is_locked() {
+            warn!("Locked clock PIN for user {} entered at a terminal of user {}", user.id, actor.id);
+            let attempt = serde_json::json!({ "locked_until": pin.locked_until });
+            self.audit(ctx, user.account_id, AuditAction::ClockPinFailed, (AuditTarget::User, user.id), None, Some(&attempt)).await?;
+            return Err(Self::pin_locked(&pin));
+        }
+
+        let verified = PasswordHash::new(&pin.pin_hash)
+            .map(|hash| Argon2::default().verify_password(data.pin.as_bytes(), &hash).is_ok())
+            .unwrap_or(false);
+        if !verified {
+            warn!("Wrong clock PIN for user {} entered at a terminal of user {}", user.id, actor.id);
+            let lockout = chrono::Duration::minutes(self.config.pin_lockout_minutes);
+            let pin = self.repository.record_pin_failure(user.id, self.config.pin_max_attempts, lockout).await?;
+
+            let attempt = serde_json::json!({ "failed_attempts": pin.failed_attempts, "locked_until": pin.locked_until });
+            self.audit(ctx, user.account_id, AuditAction::ClockPinFailed, (AuditTarget::User, user.id), None, Some(&attempt)).await?;
+            if pin.is_locked() {
+                self.audit(ctx, user.account_id, AuditAction::ClockPinLocked, (AuditTarget::User, user.id), None, Some(&attempt)).await?;
+                return Err(Self::pin_locked(&pin));
+            }
+            return Err(ApiError::InvalidCredentials);
+        }
+        if pin.failed_attempts > 0 {
+            self.repository.reset_pin_failures(user.id).await?;
+        }
+
+        self.punch(ctx, actor, &user, action, data.branch_id, ClockMethod::Pin).await
+    }
+
+    fn pin_locked(pin: &clock_pin::Model) -> ApiError {
+        let until = pin.locked_until.map(|until| until.to_rfc3339()).unwrap_or_default();
+        ApiError::TooManyAttempts(format!("Clock PIN is locked after too many wrong entries until {}", until))
+    }
+
+    /// Set the PIN the logged-in staff member clocks with at shared terminals
+    pub async fn set_pin(&self, ctx: &RequestContext, actor: &UserInfo, data: SetPinRequest) -> Result<(), ApiError> {
+        let user = self.get_staff(actor.parsed_account_id()?, actor.parsed_id()?).await?;
+        info!("Setting clock PIN of user {}", user.id);
+
+        let salt = SaltString::generate(&mut OsRng);
+        let pin_hash = Argon2::default()
+            .hash_password(data.pin.as_bytes(), &salt)
+            .map_err(|_e| ApiError::InternalServerError)?
+            .to_string();
+        self.repository.set_pin(user.id, user.account_id, pin_hash).await?;
+
+        self.audit::<()>(ctx, user.account_id, AuditAction::ClockPinSet, (AuditTarget::User, user.id), None, None).await?;
+        Ok(())
+    }
+
+    /// Time entries of a branch
+    pub async fn search_entries(&self, actor: &UserInfo, query: TimeEntryQuery) -> Result<Page<TimeEntryView>, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        let from = query.from.map(|from| Self::local_midnight(&branch, from)).transpose()?;
+        let to = query.to.and_then(|to| to.succ_opt()).map(|to| Self::local_midnight(&branch, to)).transpose()?;
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (entries, total) = self.repository.search_entries(branch.id, (from, to), query.user_id, page, per_page).await?;
+        let items = self.views(entries).await?;
+
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Correct the times of a time entry, e.g. after a forgotten clock-out
+    pub async fn update_entry(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateTimeEntryRequest) -> Result<TimeEntryView, ApiError> {
+        info!("Correcting time entry with ID: {}", id);
+
+        let before = self.repository.get_entry(id).await?;
+        if before.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Time entry not found".to_string()));
+        }
+
+        let mut entry = before.clone();
+        if let Some(clock_in_at) = data.clock_in_at {
+            entry.clock_in_at = clock_in_at;
+        }
+        if data.clock_out_at.is_some() {
+            entry.clock_out_at = data.clock_out_at;
+        }
+        let now = chrono::Utc::now().fixed_offset();
+        if entry.clock_in_at > now || entry.clock_out_at.is_some_and(|clock_out_at| clock_out_at > now) {
+            return Err(ApiError::InvalidInput("Times must not be in the future".to_string()));
+        }
+        if entry.clock_out_at.is_some_and(|clock_out_at| clock_out_at <= entry.clock_in_at) {
+            return Err(ApiError::InvalidInput("Clock-out must be after clock-in".to_string()));
+        }
+        entry.edited_by = Some(actor.parsed_id()?);
+        entry.note = Some(data.note);
+
+        let entry = self.repository.update_entry(entry).await?;
+
+        self.audit(ctx, entry.account_id, AuditAction::TimeEntryUpdated, (AuditTarget::TimeEntry, id), Some(&before), Some(&entry)).await?;
+        self.view(entry).await
+    }
+
+    /// Hours worked per staff member and local day at a branch, with overtime
+    pub async fn timesheet(&self, actor: &UserInfo, query: &TimesheetQuery) -> Result<Timesheet, ApiError> {
+        let branch = self.resolve_branch(actor, query.branch_id).await?;
+        let (from, to) = Self::local_days(&branch, query.from, query.to)?;
+
+        let entries = self.repository.get_entries_between(branch.id, from, to, query.user_id).await?;
+        let breaks = self.repository.get_breaks(entries.iter().map(|entry| entry.id).collect()).await?;
+        let shifts = self.repository.get_shifts(branch.id, (from, to), query.user_id, None).await?;
+        let users: HashMap<Uuid, User> = self.user_repository
+            .get_by_account_id(branch.account_id)
+            .await?
+            .into_iter()
+            .map(|user| (user.id, user))
+            .collect();
+
+        let mut scheduled: HashMap<Uuid, i64> = HashMap::new();
+        for shift in &shifts {
+            if let Some(user_id) = shift.user_id {
+                *scheduled.entry(user_id).or_default() += shift.scheduled_minutes();
+            }
+        }
+
+        // Worked and break minutes per staff member and local day of clocking in
+        let mut days: BTreeMap<Uuid, BTreeMap<NaiveDate, (i64, i64)>> = BTreeMap::new();
+        let mut open: HashMap<Uuid, usize> = HashMap::new();
+        for entry in &entries {
+            let Some(clock_out_at) = entry.clock_out_at else {
+                *open.entry(entry.user_id).or_default() += 1;
+                continue;
+            };
+            let break_minutes: i64 = breaks
+                .iter()
+                .filter(|b| b.time_entry_id == entry.id)
+                .map(|b| Self::break_minutes(b, clock_out_at))
+                .sum();
+            let worked = (clock_out_at - entry.clock_in_at).num_minutes() - break_minutes;
+
+            let date = entry.clock_in_at.with_timezone(&branch.tz()).date_naive();
+            let day = days.entry(entry.user_id).or_default().entry(date).or_default();
+            day.0 += worked.max(0);
+            day.1 += break_minutes;
+        }
+        for user_id in open.keys().chain(scheduled.keys()) {
+            days.entry(*user_id).or_default();
+        }
+
+        let daily_limit = self.config.daily_overtime_hours * 60;
+        let weekly_limit = self.config.weekly_overtime_hours * 60;
+        let staff = days
+            .into_iter()
+            .map(|(user_id, days)| {
+                let days: Vec<TimesheetDay> = days
+                    .into_iter()
+                    .map(|(date, (worked_minutes, break_minutes))| TimesheetDay {
+                        date,
+                        worked_minutes,
+                        break_minutes,
+                        overtime_minutes: (worked_minutes - daily_limit).max(0),
+                    })
+                    .collect();
+
+                // Regular time beyond the weekly limit is overtime too, counted per ISO week
+                let mut weeks: BTreeMap<(i32, u32), i64> = BTreeMap::new();
+                for day in &days {
+                    let week = day.date.iso_week();
+                    *weeks.entry((week.year(), week.week())).or_default() += day.worked_minutes - day.overtime_minutes;
+                }
+
+                let worked_minutes: i64 = days.iter().map(|day| day.worked_minutes).sum();
+                let daily_overtime_minutes: i64 = days.iter().map(|day| day.overtime_minutes).sum();
+                let weekly_overtime_minutes: i64 = weeks.values().map(|regular| (regular - weekly_limit).max(0)).sum();
+                let overtime_minutes = daily_overtime_minutes + weekly_overtime_minutes;
+                let user = users.get(&user_id);
+
+                StaffTimesheet {
+                    user_id,
+                    name: user.and_then(|user| user.name.clone()),
+                    email: user.map(|user| user.email.clone()).unwrap_or_default(),
+                    days,
+                    scheduled_minutes: scheduled.get(&user_id).copied().unwrap_or(0),
+                    worked_minutes,
+                    regular_minutes: worked_minutes - overtime_minutes,
+                    daily_overtime_minutes,
+                    weekly_overtime_minutes,
+                    overtime_minutes,
+                    open_entries: open.get(&user_id).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        Ok(Timesheet {
+            branch_id: branch.id,
+            from: query.from,
+            to: query.to,
+            daily_overtime_after_minutes: daily_limit,
+            weekly_overtime_after_minutes: weekly_limit,
+            staff,
+        })
+    }
+
+    /// Render a timesheet as CSV with one row per staff member and day, followed by their totals
+    pub fn timesheet_csv(&self, timesheet: &Timesheet) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "user_id".into(),
+            "name".into(),
+            "email".into(),
+            "date".into(),
+            "scheduled_minutes".into(),
+            "worked_minutes".into(),
+            "break_minutes".into(),
+            "regular_minutes".into(),
+            "overtime_minutes".into(),
+        ]];
+
+        for staff in &timesheet.staff {
+            let who = vec![staff.user_id.to_string(), staff.name.clone().unwrap_or_default(), staff.email.clone()];
+            for day in &staff.days {
+                let mut row = who.clone();
+                row.extend([
+                    day.date.to_string(),
+                    String::new(),
+                    day.worked_minutes.to_string(),
+                    day.break_minutes.to_string(),
+                    (day.worked_minutes - day.overtime_minutes).to_string(),
+                    day.overtime_minutes.to_string(),
+                ]);
+                rows.push(row);
+            }
+
+            let mut total = who;
+            total.extend([
+                "total".into(),
+                staff.scheduled_minutes.to_string(),
+                staff.worked_minutes.to_string(),
+                staff.days.iter().map(|day| day.break_minutes).sum::<i64>().to_string(),
+                staff.regular_minutes.to_string(),
+                staff.overtime_minutes.to_string(),
+            ]);
+            rows.push(total);
+        }
+
+        rows.iter()
+            .map(|row| row.iter().map(|cell| Self::csv_cell(cell)).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+
+    /// Perform a clock action for a staff member
+    async fn punch(
+        &self,
+        ctx: &RequestContext,
+        actor: &UserInfo,
+        user: &User,
+        action: ClockAction,
+        branch_id: Option<Uuid>,
+        method: ClockMethod,
+    ) -> Result<TimeEntryView, ApiError> {
+        info!("{} of user {} by {}", action, user.id, method);
+
+        if action == ClockAction::ClockIn {
+            let branch = self.resolve_branch(actor, branch_id).await?;
+            let shift = self.repository.get_current_shift(user.id, branch.id, chrono::Utc::now().fixed_offset()).await?;
+            let entry = self.repository
+                .clock_in((branch.account_id, branch.id, user.id), shift.map(|shift| shift.id), method)
+                .await?;
+
+            self.audit(ctx, entry.account_id, AuditAction::ClockedIn, (AuditTarget::TimeEntry, entry.id), None, Some(&entry)).await?;
+            let view = self.view(entry).await?;
+            self.publish_event("time_clock.clocked_in", view.entry.branch_id, &view).await;
+            return Ok(view);
+        }
+
+        let open = self.repository
+            .get_open_entry(user.id)
+            .await?
+            .ok_or_else(|| ApiError::Conflict("Not clocked in".to_string()))?;
+
+        let kind = match action {
+            ClockAction::ClockOut => {
+                let entry = self.repository.clock_out(open.id).await?;
+                self.audit(ctx, entry.account_id, AuditAction::ClockedOut, (AuditTarget::TimeEntry, entry.id), Some(&open), Some(&entry)).await?;
+                "time_clock.clocked_out"
+            }
+            ClockAction::BreakStart => {
+                self.repository.start_break(open.id).await?;
+                "time_clock.break_started"
+            }
+            ClockAction::BreakEnd => {
+                self.repository.end_break(open.id).await?;
+                "time_clock.break_ended"
+            }
+            ClockAction::ClockIn => unreachable!("clocking in is handled above"),
+        };
+
+        let view = self.view(self.repository.get_entry(open.id).await?).await?;
+        self.publish_event(kind, view.entry.branch_id, &view).await;
+        Ok(view)
+    }
+
+    async fn view(&self, entry: time_entry::Model) -> Result<TimeEntryView, ApiError> {
+        let mut views = self.views(vec![entry]).await?;
+        views.pop().ok_or(ApiError::InternalServerError)
+    }
+
+    /// Attach breaks and worked time to time entries
+    async fn views(&self, entries: Vec<time_entry::Model>) -> Result<Vec<TimeEntryView>, ApiError> {
+        let mut breaks: HashMap<Uuid, Vec<time_break::Model>> = HashMap::new();
+        for b in self.repository.get_breaks(entries.iter().map(|entry| entry.id).collect()).await? {
+            breaks.entry(b.time_entry_id).or_default().push(b);
+        }
+
+        let now = chrono::Utc::now().fixed_offset();
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                let breaks = breaks.remove(&entry.id).unwrap_or_default();
+                let until = entry.clock_out_at.unwrap_or(now);
+                let break_minutes: i64 = breaks.iter().map(|b| Self::break_minutes(b, until)).sum();
+                TimeEntryView {
+                    on_break: entry.clock_out_at.is_none() && breaks.iter().any(|b| b.ended_at.is_none()),
+                    worked_minutes: ((until - entry.clock_in_at).num_minutes() - break_minutes).max(0),
+                    break_minutes,
+                    breaks,
+                    entry,
+                }
+            })
+            .collect())
+    }
+
+    /// Length of a break, counting a running one up to `until`
+    fn break_minutes(b: &time_break::Model, until: DateTimeWithTimeZone) -> i64 {
+        (b.ended_at.unwrap_or(until) - b.started_at).num_minutes().max(0)
+    }
+
+    fn check_shift(starts_at: DateTimeWithTimeZone, ends_at: DateTimeWithTimeZone, break_minutes: i32) -> Result<(), ApiError> {
+        let length = ends_at - starts_at;
+        if length <= Duration::zero() {
+            return Err(ApiError::InvalidInput("Shift must end after it starts".to_string()));
+        }
+        if length > Duration::hours(MAX_SHIFT_HOURS) {
+            return Err(ApiError::InvalidInput(format!("Shifts can be at most {} hours long", MAX_SHIFT_HOURS)));
+        }
+        if Duration::minutes(i64::from(break_minutes)) >= length {
+            return Err(ApiError::InvalidInput("Break must be shorter than the shift".to_string()));
+        }
+        Ok(())
+    }
+
+    fn check_range(from: NaiveDate, to: NaiveDate) -> Result<(), ApiError> {
+        if to < from {
+            return Err(ApiError::InvalidInput("to must not be before from".to_string()));
+        }
+        if (to - from).num_days() >= MAX_RANGE_DAYS {
+            return Err(ApiError::InvalidInput(format!("The range can cover at most {} days", MAX_RANGE_DAYS)));
+        }
+        Ok(())
+    }
+
+    /// Start of the local day `from` and end of the local day `to` at the branch
+    fn local_days(branch: &Branch, from: NaiveDate, to: NaiveDate) -> Result<(DateTimeWithTimeZone, DateTimeWithTimeZone), ApiError> {
+        Self::check_range(from, to)?;
+
+        let to = to.succ_opt().ok_or_else(|| ApiError::InvalidInput("Invalid date range".to_string()))?;
+        Ok((Self::local_midnight(branch, from)?, Self::local_midnight(branch, to)?))
+    }
+
+    /// Start of a local day at the branch
+    fn local_midnight(branch: &Branch, date: NaiveDate) -> Result<DateTimeWithTimeZone, ApiError> {
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|time| branch.tz().from_local_datetime(&time).earliest())
+            .map(|time| time.fixed_offset())
+            .ok_or_else(|| ApiError::InvalidInput(format!("Invalid date {}", date)))
+    }
+
+    fn csv_cell(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Fetch an active staff member of the account
+    async fn get_staff(&self, account_id: Uuid, user_id: Uuid) -> Result<User, ApiError> {
+        let user = self.user_repository.get_by_id(user_id).await?;
+
+        if user.account_id != account_id {
+            return Err(ApiError::NotFound("User not found".to_string()));
+        }
+        let role: UserRole = user.role.parse().map_err(ApiError::InvalidInput)?;
+        if role == UserRole::Customer {
+            return Err(ApiError::InvalidInput("Customers do not work shifts".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    /// Fetch a shift, hiding those of other accounts
+    async fn get_owned_shift(&self, actor: &UserInfo, id: Uuid) -> Result<Shift, ApiError> {
+        let shift = self.repository.get_shift(id).await?;
+
+        if shift.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Shift not found".to_string()));
+        }
+
+        Ok(shift)
+    }
+
+    /// Fetch the given branch or the session's active one, hiding those of other accounts
+    async fn resolve_branch(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<Branch, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    async fn publish_event<T: serde::Serialize>(&self, kind: &str, branch_id: Uuid, payload: &T) {
+        self.events.publish(Event::new(kind, vec![topic::branch(branch_id)], payload)).await;
+    }
+
+    /// Record a schedule or time clock mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Uuid),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (target.0, Some(target.1)), before, after)
+            .await
+    }
+}
@@ -0,0 +1,126 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::{
+        order::entity::line,
+        station::entity::{CreateStationRequest, SetStationItemsRequest, StationQuery, StationQueue, StationView, UpdateStationRequest},
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List the stations of a branch the caller works at
+pub async fn get_all(
+    Query(query): Query<StationQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<StationView>>, ApiError> {
+    info!("Fetching stations");
+    let result = state.station_service.get_all(&user, query).await?;
+    Ok(Json(result))
+}
+
+/// Get the pending lines of a station
+pub async fn get_queue(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<StationQueue>, ApiError> {
+    info!("Fetching queue of station with ID: {}", id);
+    let result = state.station_service.get_queue(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Mark a line as done
+pub async fn bump_line(
+    Path((id, line_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<line::Model>, ApiError> {
+    info!("Bumping line {} at station {}", line_id, id);
+    let result = state.station_service.bump(&user, id, line_id).await?;
+    Ok(Json(result))
+}
+
+/// Put a bumped line back into the queue
+pub async fn recall_line(
+    Path((id, line_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<line::Model>, ApiError> {
+    info!("Recalling line {} at station {}", line_id, id);
+    let result = state.station_service.recall(&user, id, line_id).await?;
+    Ok(Json(result))
+}
+
+/// Create a new station
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateStationRequest>,
+) -> Result<(StatusCode, Json<StationView>), ApiError> {
+    info!("Creating station: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.station_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing station
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateStationRequest>,
+) -> Result<Json<StationView>, ApiError> {
+    info!("Updating station with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.station_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a station (soft delete)
+pub async fn delete_station(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting station with ID: {}", id);
+    state.station_service.delete(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replace the menu items routed to a station
+pub async fn set_items(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetStationItemsRequest>,
+) -> Result<Json<StationView>, ApiError> {
+    info!("Setting items of station with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.station_service.set_items(&ctx, &user, id, payload.item_ids).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,167 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::modules::{order::entity::line, user::entity::UserRole};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "prep_stations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub name: String,
+    /// Roles that work at this station; managers always have access
+    pub roles: Vec<String>,
+    pub position: i32,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("PrepStation", 8)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("roles", &self.roles)?;
+        state.serialize_field("position", &self.position)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Whether a role may see the queue of this station and bump its lines
+    pub fn admits(&self, role: &UserRole) -> bool {
+        role.level() >= UserRole::Manager.level() || self.roles.contains(&role.to_string())
+    }
+}
+
+/// Menu items prepared at a station; an item goes to at most one station per branch
+pub mod station_item {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "prep_station_items")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub station_id: Uuid,
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub item_id: Uuid,
+        pub branch_id: Uuid,
+        pub created_at: DateTimeWithTimeZone,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("PrepStationItem", 4)?;
+            state.serialize_field("station_id", &self.station_id)?;
+            state.serialize_field("item_id", &self.item_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Roles that can be put to work at a station
+pub const STATION_ROLES: [&str; 4] = ["COOK", "BARMAN", "WAITER", "CASH_REGISTER"];
+
+// Validators
+fn validate_roles(roles: &[String]) -> Result<(), ValidationError> {
+    match roles.iter().find(|r| !STATION_ROLES.contains(&r.as_str())) {
+        Some(r) => Err(ValidationError::new("roles").with_message(format!("Role {} cannot work at a station", r).into())),
+        None => Ok(()),
+    }
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateStationRequest {
+    pub branch_id: Uuid,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(custom(function = "validate_roles"))]
+    pub roles: Vec<String>,
+
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateStationRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    #[validate(custom(function = "validate_roles"))]
+    pub roles: Option<Vec<String>>,
+
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetStationItemsRequest {
+    /// Replaces the items routed to the station
+    #[validate(length(max = 500, message = "At most 500 items can be routed to a station"))]
+    pub item_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StationQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+}
+
+/// A station with the menu items routed to it
+#[derive(Debug, Clone, Serialize)]
+pub struct StationView {
+    #[serde(flatten)]
+    pub station: Model,
+    pub item_ids: Vec<Uuid>,
+}
+
+/// A line waiting at a station, with how long it has been waiting
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueLine {
+    #[serde(flatten)]
+    pub line: line::Model,
+    pub table_id: Option<Uuid>,
+    pub order_status: String,
+    pub placed_at: Option<DateTimeWithTimeZone>,
+    pub waiting_seconds: i64,
+}
+
+/// Pending lines of a station, oldest first
+#[derive(Debug, Clone, Serialize)]
+pub struct StationQueue {
+    pub station_id: Uuid,
+    pub lines: Vec<QueueLine>,
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use sea_orm::{
+    sea_query::{Expr, Query}, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::{
+        order::entity::{self as order, line, OrderStatus},
+        station::entity::{
+            station_item, ActiveModel, Column, CreateStationRequest, Entity as StationEntity, Model as Station, UpdateStationRequest,
+        },
+    },
+    common::ApiError,
+};
+
+/// Station repository for database operations
+#[derive(Debug, Clone)]
+pub struct StationRepository {
+    db: DatabaseConnection,
+}
+
+impl StationRepository {
+    /// Create a new station repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the stations of a branch
+    pub async fn get_by_branch_id(&self, branch_id: Uuid) -> Result<Vec<Station>, ApiError> {
+        info!("Fetching stations by branch ID: {}", branch_id);
+
+        StationEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Position)
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch stations by branch ID {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a station by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Station, ApiError> {
+        info!("Fetching station with ID: {}", id);
+
+        let station = StationEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch station with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match station {
+            Some(station) => Ok(station),
+            None => Err(ApiError::NotFound("Station not found".to_string())),
+        }
+    }
+
+    /// Create a new station
+    pub async fn create(&self, account_id: Uuid, request: CreateStationRequest) -> Result<Station, ApiError> {
+        info!("Creating station: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let station = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(request.branch_id),
+            name: Set(request.name),
+            roles: Set(request.roles),
+            position: Set(request.position),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        station.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create station: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update an existing station
+    pub async fn update(&self, id: Uuid, request: UpdateStationRequest) -> Result<Station, ApiError> {
+        info!("Updating station with ID: {}", id);
+
+        let mut station: ActiveModel = self.get_by_id(id).await?.into();
+
+        if let Some(name) = request.name {
+            station.name = Set(name);
+        }
+
+        if let Some(roles) = request.roles {
+            station.roles = Set(roles);
+        }
+
+        if let Some(position) = request.position {
+            station.position = Set(position);
+        }
+
+        station.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        station.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update station {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a station and stop routing items to it
+    pub async fn soft_delete(&self, id: Uuid) -> Result<Station, ApiError> {
+        info!("Soft deleting station with ID: {}", id);
+
+        let current = self.get_by_id(id).await?;
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        station_item::Entity::delete_many()
+            .filter(station_item::Column::StationId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove items of station {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut station: ActiveModel = current.into();
+        station.deleted_at = Set(Some(now));
+        station.updated_at = Set(now);
+
+        let station = station.update(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete station {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit deletion of station {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(station)
+    }
+
+    /// Get the items routed to the given stations
+    pub async fn get_items(&self, station_ids: Vec<Uuid>) -> Result<Vec<station_item::Model>, ApiError> {
+        station_item::Entity::find()
+            .filter(station_item::Column::StationId.is_in(station_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch station items: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Replace the items routed to a station
+    pub async fn set_items(&self, station: &Station, item_ids: Vec<Uuid>) -> Result<(), ApiError> {
+        info!("Routing {} items to station {}", item_ids.len(), station.id);
+
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        station_item::Entity::delete_many()
+            .filter(station_item::Column::StationId.eq(station.id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to remove items of station {}: {}", station.id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if !item_ids.is_empty() {
+            let now = chrono::Utc::now().fixed_offset();
+            let items = item_ids.into_iter().map(|item_id| station_item::ActiveModel {
+                station_id: Set(station.id),
+                item_id: Set(item_id),
+                branch_id: Set(station.branch_id),
+                created_at: Set(now),
+            });
+
+            station_item::Entity::insert_many(items)
+                .exec(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to route items to station {}: {}", station.id, e);
+                    match e.sql_err() {
+                        Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                            ApiError::Conflict("An item is already routed to another station of the branch".to_string())
+                        }
+                        _ => ApiError::DatabaseError(e.to_string()),
+                    }
+                })?;
+        }
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit items of station {}: {}", station.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Send the lines of an order to the stations their items are routed to at the branch
+    pub async fn route_order(&self, order_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        info!("Routing lines of order {} to stations", order_id);
+
+        let lines = line::Entity::find()
+            .filter(line::Column::OrderId.eq(order_id))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch lines of order {}: {}", order_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+        let item_ids: Vec<Uuid> = lines.iter().map(|line| line.item_id).collect();
+
+        let routes = station_item::Entity::find()
+            .filter(station_item::Column::BranchId.eq(branch_id))
+            .filter(station_item::Column::ItemId.is_in(item_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch station routes of branch {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        for route in routes {
+            line::Entity::update_many()
+                .col_expr(line::Column::StationId, Expr::value(route.station_id))
+                .filter(line::Column::OrderId.eq(order_id))
+                .filter(line::Column::ItemId.eq(route.item_id))
+                .exec(&self.db)
+                .await
+                .map_err(|e| {
+                    error!("Failed to route lines of order {}: {}", order_id, e);
+                    ApiError::DatabaseError(e.to_string())
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Lines of a station not yet bumped, oldest first, with the orders they belong to
+    pub async fn get_queue(&self, station_id: Uuid) -> Result<Vec<(line::Model, order::Model)>, ApiError> {
+        info!("Fetching queue of station {}", station_id);
+
+        let open = [OrderStatus::Placed.to_string(), OrderStatus::InPreparation.to_string()];
+        let open_orders = Query::select()
+            .column(order::Column::Id)
+            .from(order::Entity)
+            .and_where(order::Column::Status.is_in(open))
+            .to_owned();
+
+        let lines = line::Entity::find()
+            .filter(line::Column::StationId.eq(station_id))
+            .filter(line::Column::BumpedAt.is_null())
+            .filter(line::Column::OrderId.in_subquery(open_orders))
+            .order_by_asc(line::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch queue of station {}: {}", station_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let order_ids: Vec<Uuid> = lines.iter().map(|line| line.order_id).collect();
+        let orders: HashMap<Uuid, order::Model> = order::Entity::find()
+            .filter(order::Column::Id.is_in(order_ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch orders of station {}: {}", station_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?
+            .into_iter()
+            .map(|order| (order.id, order))
+            .collect();
+
+        let mut queue: Vec<_> = lines
+            .into_iter()
+            .filter_map(|line| orders.get(&line.order_id).cloned().map(|order| (line, order)))
+            .collect();
+        queue.sort_by_key(|(line, order)| (order.placed_at, line.created_at));
+
+        Ok(queue)
+    }
+
+    /// Get a line routed to a station
+    pub async fn get_line(&self, station_id: Uuid, line_id: Uuid) -> Result<line::Model, ApiError> {
+        let line = line::Entity::find_by_id(line_id)
+            .filter(line::Column::StationId.eq(station_id))
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch line {} of station {}: {}", line_id, station_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match line {
+            Some(line) => Ok(line),
+            None => Err(ApiError::NotFound("Order line not found".to_string())),
+        }
+    }
+
+    /// Mark a line as done at its station, or put it back in the queue
+    pub async fn set_bumped(&self, line: line::Model, bumped: bool) -> Result<line::Model, ApiError> {
+        info!("Setting line {} bumped: {}", line.id, bumped);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let id = line.id;
+        let mut line: line::ActiveModel = line.into();
+        line.bumped_at = Set(bumped.then_some(now));
+        line.updated_at = Set(now);
+
+        line.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update line {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
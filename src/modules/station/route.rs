@@ -0,0 +1,27 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create station routes for staff; each station admits its own roles
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/stations", get(get_all))
+        .route("/stations/:id/queue", get(get_queue))
+        .route("/stations/:id/lines/:line_id/bump", post(bump_line))
+        .route("/stations/:id/lines/:line_id/recall", post(recall_line))
+}
+
+/// Create station administration routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/stations", post(create))
+        .route("/stations/:id", put(update).delete(delete_station))
+        .route("/stations/:id/items", put(set_items))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
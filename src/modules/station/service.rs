@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::{
+    common::{events::{topic, Event, SharedEventBus}, ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        menu::repository::MenuRepository,
+        order::{
+            entity::{line, OrderStatus},
+            repository::OrderRepository,
+        },
+        station::{
+            entity::{
+                CreateStationRequest, Model as Station, QueueLine, StationQuery, StationQueue, StationView, UpdateStationRequest,
+            },
+            repository::StationRepository,
+        },
+        user::entity::UserRole,
+    },
+};
+
+/// Station service layer for business logic
+#[derive(Debug, Clone)]
+pub struct StationService {
+    repository: StationRepository,
+    branch_repository: BranchRepository,
+    menu_repository: MenuRepository,
+    order_repository: OrderRepository,
+    audit_service: AuditService,
+    events: SharedEventBus,
+}
+
+impl StationService {
+    /// Create a new station service
+    pub fn new(
+        repository: StationRepository,
+        branch_repository: BranchRepository,
+        menu_repository: MenuRepository,
+        order_repository: OrderRepository,
+        audit_service: AuditService,
+        events: SharedEventBus,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            menu_repository,
+            order_repository,
+            audit_service,
+            events,
+        }
+    }
+
+    /// Stations of a branch the caller may work at, defaulting to the session's active branch
+    pub async fn get_all(&self, actor: &UserInfo, query: StationQuery) -> Result<Vec<StationView>, ApiError> {
+        let branch_id = query.branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.get_branch(actor, branch_id).await?;
+
+        let role = Self::role(actor)?;
+        let stations = self.repository
+            .get_by_branch_id(branch.id)
+            .await?
+            .into_iter()
+            .filter(|station| station.admits(&role))
+            .collect();
+
+        self.views(stations).await
+    }
+
+    /// Create a station in a branch of the caller's account
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateStationRequest) -> Result<StationView, ApiError> {
+        info!("Creating station: {}", data.name);
+
+        let branch = self.get_branch(actor, data.branch_id).await?;
+        let station = self.repository.create(branch.account_id, data).await?;
+
+        self.audit(ctx, station.account_id, AuditAction::StationCreated, station.id, None, Some(&station)).await?;
+        Ok(StationView { station, item_ids: Vec::new() })
+    }
+
+    /// Update a station's name, roles or position
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateStationRequest) -> Result<StationView, ApiError> {
+        info!("Updating station with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let station = self.repository.update(id, data).await?;
+
+        self.audit(ctx, station.account_id, AuditAction::StationUpdated, id, Some(&before), Some(&station)).await?;
+        self.view(station).await
+    }
+
+    /// Delete a station; its items are no longer routed anywhere
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting station with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let deleted = self.repository.soft_delete(id).await?;
+
+        self.audit(ctx, deleted.account_id, AuditAction::StationDeleted, id, Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Replace the menu items prepared at a station
+    pub async fn set_items(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, mut item_ids: Vec<Uuid>) -> Result<StationView, ApiError> {
+        info!("Setting items of station with ID: {}", id);
+
+        let station = self.get_owned(actor, id).await?;
+        let before = self.view(station.clone()).await?;
+
+        item_ids.sort();
+        item_ids.dedup();
+
+        let items = self.menu_repository.get_items_by_ids(item_ids.clone()).await?;
+        if items.len() != item_ids.len() || items.iter().any(|item| item.account_id != station.account_id) {
+            return Err(ApiError::NotFound("Menu item not found".to_string()));
+        }
+
+        self.repository.set_items(&station, item_ids).await?;
+        let view = self.view(station).await?;
+
+        self.audit(ctx, view.station.account_id, AuditAction::StationItemsSet, id, Some(&before), Some(&view)).await?;
+        Ok(view)
+    }
+
+    /// Lines waiting at a station, oldest order first
+    pub async fn get_queue(&self, actor: &UserInfo, id: Uuid) -> Result<StationQueue, ApiError> {
+        let station = self.get_workable(actor, id).await?;
+        let now = chrono::Utc::now().fixed_offset();
+
+        let lines = self.repository
+            .get_queue(station.id)
+            .await?
+            .into_iter()
+            .map(|(line, order)| {
+                let since = order.placed_at.unwrap_or(line.created_at);
+                QueueLine {
+                    waiting_seconds: (now - since).num_seconds().max(0),
+                    table_id: order.table_id,
+                    order_status: order.status,
+                    placed_at: order.placed_at,
+                    line,
+                }
+            })
+            .collect();
+
+        Ok(StationQueue { station_id: station.id, lines })
+    }
+
+    /// Mark a line as done at its station
+    pub async fn bump(&self, actor: &UserInfo, id: Uuid, line_id: Uuid) -> Result<line::Model, ApiError> {
+        info!("Bumping line {} at station {}", line_id, id);
+
+        let (station, line) = self.get_open_line(actor, id, line_id).await?;
+        if line.bumped_at.is_some() {
+            return Err(ApiError::Conflict("Line is already bumped".to_string()));
+        }
+
+        let line = self.repository.set_bumped(line, true).await?;
+        self.publish("station.line_bumped", &station, &line).await;
+        Ok(line)
+    }
+
+    /// Put a bumped line back into the station's queue
+    pub async fn recall(&self, actor: &UserInfo, id: Uuid, line_id: Uuid) -> Result<line::Model, ApiError> {
+        info!("Recalling line {} at station {}", line_id, id);
+
+        let (station, line) = self.get_open_line(actor, id, line_id).await?;
+        if line.bumped_at.is_none() {
+            return Err(ApiError::Conflict("Line is not bumped".to_string()));
+        }
+
+        let line = self.repository.set_bumped(line, false).await?;
+        self.publish("station.line_recalled", &station, &line).await;
+        Ok(line)
+    }
+
+    /// Fetch a line of a station whose order is still being prepared
+    async fn get_open_line(&self, actor: &UserInfo, id: Uuid, line_id: Uuid) -> Result<(Station, line::Model), ApiError> {
+        let station = self.get_workable(actor, id).await?;
+        let line = self.repository.get_line(station.id, line_id).await?;
+
+        let order = self.order_repository.get_by_id(line.order_id).await?;
+        let status: OrderStatus = order.status.parse().map_err(ApiError::InvalidInput)?;
+        if !matches!(status, OrderStatus::Placed | OrderStatus::InPreparation) {
+            return Err(ApiError::Conflict(format!("Order is {}", order.status)));
+        }
+
+        Ok((station, line))
+    }
+
+    /// Tell the station and the branch floor about a line's progress
+    async fn publish(&self, kind: &str, station: &Station, line: &line::Model) {
+        let topics = vec![topic::station(station.id), topic::branch(station.branch_id)];
+        self.events.publish(Event::new(kind, topics, line)).await;
+    }
+
+    async fn views(&self, stations: Vec<Station>) -> Result<Vec<StationView>, ApiError> {
+        let station_ids = stations.iter().map(|station| station.id).collect();
+
+        let mut items: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for item in self.repository.get_items(station_ids).await? {
+            items.entry(item.station_id).or_default().push(item.item_id);
+        }
+
+        Ok(stations
+            .into_iter()
+            .map(|station| StationView {
+                item_ids: items.remove(&station.id).unwrap_or_default(),
+                station,
+            })
+            .collect())
+    }
+
+    async fn view(&self, station: Station) -> Result<StationView, ApiError> {
+        let mut views = self.views(vec![station]).await?;
+        views.pop().ok_or_else(|| ApiError::NotFound("Station not found".to_string()))
+    }
+
+    /// Fetch a station the caller's role may work at
+    async fn get_workable(&self, actor: &UserInfo, id: Uuid) -> Result<Station, ApiError> {
+        let station = self.get_owned(actor, id).await?;
+
+        let role = Self::role(actor)?;
+        if !station.admits(&role) {
+            warn!("User {} with role {} denied access to station {}", actor.id, role, id);
+            return Err(ApiError::Forbidden(format!("{} does not work at this station", role)));
+        }
+
+        Ok(station)
+    }
+
+    /// Fetch a station, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Station, ApiError> {
+        let station = self.repository.get_by_id(id).await?;
+
+        if station.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Station not found".to_string()));
+        }
+
+        Ok(station)
+    }
+
+    /// Fetch a branch, hiding those of other accounts
+    async fn get_branch(&self, actor: &UserInfo, branch_id: Uuid) -> Result<Branch, ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    fn role(actor: &UserInfo) -> Result<UserRole, ApiError> {
+        actor.role.parse().map_err(ApiError::InvalidInput)
+    }
+
+    /// Record a station mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (AuditTarget::Station, Some(id)), before, after)
+            .await
+    }
+}
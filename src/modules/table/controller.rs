@@ -0,0 +1,250 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tower_sessions::Session;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::menu::entity::MenuView,
+    modules::table::entity::{
+        area, AssignWaiterRequest, CreateAreaRequest, CreateTableRequest, FloorPlan, GuestSessionView, Model as Table,
+        StartGuestSessionRequest, TableQrCode, TableQuery, UpdateAreaRequest, UpdateTableRequest, UpdateTableStatusRequest,
+    },
+    common::{AppState, RequestContext, session::{SessionGuest, SessionManager, SessionUser}},
+};
+
+/// Get the floor plan of a branch
+pub async fn get_floor_plan(
+    Query(query): Query<TableQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<FloorPlan>, ApiError> {
+    info!("Fetching floor plan");
+    let result = state.table_service.get_floor_plan(&user, query.branch_id).await?;
+    Ok(Json(result))
+}
+
+/// Get the tables assigned to the caller
+pub async fn get_mine(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Table>>, ApiError> {
+    info!("Fetching tables of waiter: {}", user.id);
+    let result = state.table_service.get_mine(&user).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific table by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Table>, ApiError> {
+    info!("Fetching table with ID: {}", id);
+    let result = state.table_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Set the status of a table
+pub async fn update_status(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateTableStatusRequest>,
+) -> Result<Json<Table>, ApiError> {
+    info!("Setting status of table {} to {}", id, payload.status);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let status = payload.status.parse().map_err(ApiError::InvalidInput)?;
+    let result = state.table_service.set_status(&ctx, &user, id, status).await?;
+    Ok(Json(result))
+}
+
+/// Create a new floor area
+pub async fn create_area(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateAreaRequest>,
+) -> Result<(StatusCode, Json<area::Model>), ApiError> {
+    info!("Creating floor area: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.table_service.create_area(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing floor area
+pub async fn update_area(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateAreaRequest>,
+) -> Result<Json<area::Model>, ApiError> {
+    info!("Updating floor area with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.table_service.update_area(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete an empty floor area
+pub async fn delete_area(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting floor area with ID: {}", id);
+    state.table_service.delete_area(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a new table
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateTableRequest>,
+) -> Result<(StatusCode, Json<Table>), ApiError> {
+    info!("Creating table: {}", payload.label);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.table_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing table
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateTableRequest>,
+) -> Result<Json<Table>, ApiError> {
+    info!("Updating table with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.table_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a table (soft delete)
+pub async fn delete_table(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting table with ID: {}", id);
+    state.table_service.delete(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Assign a waiter to a table
+pub async fn assign_waiter(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<AssignWaiterRequest>,
+) -> Result<Json<Table>, ApiError> {
+    info!("Assigning waiter to table with ID: {}", id);
+    let result = state.table_service.assign_waiter(&ctx, &user, id, payload.waiter_id).await?;
+    Ok(Json(result))
+}
+
+/// Get the QR code of a table
+pub async fn get_qr_code(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<TableQrCode>, ApiError> {
+    info!("Fetching QR code of table with ID: {}", id);
+    let result = state.table_service.qr_code(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Replace the QR code of a table
+pub async fn rotate_qr_code(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<TableQrCode>, ApiError> {
+    info!("Rotating QR code of table with ID: {}", id);
+    let result = state.table_service.rotate_qr_code(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
+
+/// Start a guest session from a scanned table QR code
+pub async fn start_guest_session(
+    State(state): State<AppState>,
+    session: Session,
+    Json(payload): Json<StartGuestSessionRequest>,
+) -> Result<(StatusCode, Json<GuestSessionView>), ApiError> {
+    info!("Guest session request");
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let (guest, view) = state.table_service.start_guest_session(&payload.token).await?;
+
+    SessionManager::start_guest(&session, guest).await
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+/// Get the table of the current guest session
+pub async fn get_guest_session(
+    State(state): State<AppState>,
+    SessionGuest(guest): SessionGuest,
+) -> Result<Json<GuestSessionView>, ApiError> {
+    info!("Fetching guest session at table: {}", guest.table_id);
+    let result = state.table_service.guest_view(&guest).await?;
+    Ok(Json(result))
+}
+
+/// Get the menu available to the current guest
+pub async fn get_guest_menu(
+    State(state): State<AppState>,
+    SessionGuest(guest): SessionGuest,
+) -> Result<Json<MenuView>, ApiError> {
+    info!("Fetching guest menu at table: {}", guest.table_id);
+    let result = state.table_service.guest_menu(&guest).await?;
+    Ok(Json(result))
+}
+
+/// End the current guest session
+pub async fn end_guest_session(session: Session) -> Result<StatusCode, ApiError> {
+    info!("Ending guest session");
+
+    SessionManager::end_guest(&session).await
+        .map_err(|_| ApiError::InternalServerError)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
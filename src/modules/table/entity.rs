@@ -0,0 +1,251 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "dining_tables")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub branch_id: Uuid,
+    pub area_id: Option<Uuid>,
+    pub label: String,
+    pub seats: i32,
+    pub pos_x: i32,
+    pub pos_y: i32,
+    pub status: String,
+    pub waiter_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub token_version: i32,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("DiningTable", 12)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("branch_id", &self.branch_id)?;
+        state.serialize_field("area_id", &self.area_id)?;
+        state.serialize_field("label", &self.label)?;
+        state.serialize_field("seats", &self.seats)?;
+        state.serialize_field("pos_x", &self.pos_x)?;
+        state.serialize_field("pos_y", &self.pos_y)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("waiter_id", &self.waiter_id)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Sections of a branch floor plan, such as terrace or bar
+pub mod area {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize, Serializer};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+    #[sea_orm(table_name = "floor_areas")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub branch_id: Uuid,
+        pub name: String,
+        pub position: i32,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+        pub deleted_at: Option<DateTimeWithTimeZone>,
+    }
+
+    impl Serialize for Model {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            use serde::ser::SerializeStruct;
+            let mut state = serializer.serialize_struct("FloorArea", 7)?;
+            state.serialize_field("id", &self.id)?;
+            state.serialize_field("account_id", &self.account_id)?;
+            state.serialize_field("branch_id", &self.branch_id)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("position", &self.position)?;
+            state.serialize_field("created_at", &self.created_at)?;
+            state.serialize_field("updated_at", &self.updated_at)?;
+            state.end()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStatus {
+    Free,
+    Occupied,
+    Reserved,
+    NeedsCleaning,
+}
+
+impl std::fmt::Display for TableStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableStatus::Free => write!(f, "FREE"),
+            TableStatus::Occupied => write!(f, "OCCUPIED"),
+            TableStatus::Reserved => write!(f, "RESERVED"),
+            TableStatus::NeedsCleaning => write!(f, "NEEDS_CLEANING"),
+        }
+    }
+}
+
+impl std::str::FromStr for TableStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "FREE" => Ok(TableStatus::Free),
+            "OCCUPIED" => Ok(TableStatus::Occupied),
+            "RESERVED" => Ok(TableStatus::Reserved),
+            "NEEDS_CLEANING" => Ok(TableStatus::NeedsCleaning),
+            _ => Err(format!("Table status {} is not valid", s)),
+        }
+    }
+}
+
+/// Guest seated at a table through its QR code, held in the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestInfo {
+    pub table_id: Uuid,
+    pub branch_id: Uuid,
+    pub account_id: Uuid,
+    pub token_version: i32,
+    pub started_at: DateTimeWithTimeZone,
+}
+
+// Validators
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<TableStatus>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("status").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateAreaRequest {
+    pub branch_id: Uuid,
+
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateAreaRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateTableRequest {
+    pub branch_id: Uuid,
+    pub area_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 50, message = "Label must be between 1 and 50 characters"))]
+    pub label: String,
+
+    #[validate(range(min = 1, max = 100, message = "Seats must be between 1 and 100"))]
+    pub seats: i32,
+
+    #[serde(default)]
+    pub pos_x: i32,
+
+    #[serde(default)]
+    pub pos_y: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateTableRequest {
+    pub area_id: Option<Uuid>,
+
+    #[validate(length(min = 1, max = 50, message = "Label must be between 1 and 50 characters"))]
+    pub label: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "Seats must be between 1 and 100"))]
+    pub seats: Option<i32>,
+
+    pub pos_x: Option<i32>,
+    pub pos_y: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateTableStatusRequest {
+    #[validate(custom(function = "validate_status"))]
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct AssignWaiterRequest {
+    /// `null` removes the assignment
+    pub waiter_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct StartGuestSessionRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TableQuery {
+    /// Defaults to the session's active branch
+    pub branch_id: Option<Uuid>,
+}
+
+/// Signed table token and the URL to encode in its QR code
+#[derive(Debug, Serialize)]
+pub struct TableQrCode {
+    pub table_id: Uuid,
+    pub token: String,
+    pub url: String,
+}
+
+/// A branch floor plan: its areas and tables
+#[derive(Debug, Serialize)]
+pub struct FloorPlan {
+    pub branch_id: Uuid,
+    pub areas: Vec<area::Model>,
+    pub tables: Vec<Model>,
+}
+
+/// What a guest sees about the table they are seated at
+#[derive(Debug, Serialize)]
+pub struct GuestSessionView {
+    pub table_id: Uuid,
+    pub table_label: String,
+    pub branch_id: Uuid,
+    pub branch_name: String,
+    pub started_at: DateTimeWithTimeZone,
+}
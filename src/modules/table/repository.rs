@@ -0,0 +1,334 @@
+use anyhow::Result;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder, PaginatorTrait};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::table::entity::{
+        area, ActiveModel, Column, CreateAreaRequest, CreateTableRequest, Entity as TableEntity, Model as Table, TableStatus,
+        UpdateAreaRequest, UpdateTableRequest,
+    },
+    common::ApiError,
+};
+
+/// Table repository for database operations
+#[derive(Debug, Clone)]
+pub struct TableRepository {
+    db: DatabaseConnection,
+}
+
+impl TableRepository {
+    /// Create a new table repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the floor areas of a branch
+    pub async fn get_areas(&self, branch_id: Uuid) -> Result<Vec<area::Model>, ApiError> {
+        info!("Fetching floor areas by branch ID: {}", branch_id);
+
+        area::Entity::find()
+            .filter(area::Column::BranchId.eq(branch_id))
+            .filter(area::Column::DeletedAt.is_null())
+            .order_by_asc(area::Column::Position)
+            .order_by_asc(area::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch floor areas by branch ID {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a floor area by ID
+    pub async fn get_area(&self, id: Uuid) -> Result<area::Model, ApiError> {
+        info!("Fetching floor area with ID: {}", id);
+
+        let area = area::Entity::find_by_id(id)
+            .filter(area::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch floor area with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match area {
+            Some(area) => Ok(area),
+            None => Err(ApiError::NotFound("Floor area not found".to_string())),
+        }
+    }
+
+    /// Create a new floor area
+    pub async fn create_area(&self, account_id: Uuid, request: CreateAreaRequest) -> Result<area::Model, ApiError> {
+        info!("Creating floor area: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let area = area::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(request.branch_id),
+            name: Set(request.name),
+            position: Set(request.position),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        area.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create floor area: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update an existing floor area
+    pub async fn update_area(&self, id: Uuid, request: UpdateAreaRequest) -> Result<area::Model, ApiError> {
+        info!("Updating floor area with ID: {}", id);
+
+        let mut area: area::ActiveModel = self.get_area(id).await?.into();
+
+        if let Some(name) = request.name {
+            area.name = Set(name);
+        }
+
+        if let Some(position) = request.position {
+            area.position = Set(position);
+        }
+
+        area.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        area.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update floor area {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a floor area
+    pub async fn soft_delete_area(&self, id: Uuid) -> Result<area::Model, ApiError> {
+        info!("Soft deleting floor area with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut area: area::ActiveModel = self.get_area(id).await?.into();
+        area.deleted_at = Set(Some(now));
+        area.updated_at = Set(now);
+
+        area.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete floor area {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Count the tables placed in a floor area
+    pub async fn count_tables_in_area(&self, area_id: Uuid) -> Result<u64, ApiError> {
+        TableEntity::find()
+            .filter(Column::AreaId.eq(area_id))
+            .filter(Column::DeletedAt.is_null())
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to count tables of floor area {}: {}", area_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the tables of a branch
+    pub async fn get_by_branch_id(&self, branch_id: Uuid) -> Result<Vec<Table>, ApiError> {
+        info!("Fetching tables by branch ID: {}", branch_id);
+
+        TableEntity::find()
+            .filter(Column::BranchId.eq(branch_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Label)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch tables by branch ID {}: {}", branch_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get the tables assigned to a waiter
+    pub async fn get_by_waiter_id(&self, waiter_id: Uuid) -> Result<Vec<Table>, ApiError> {
+        info!("Fetching tables by waiter ID: {}", waiter_id);
+
+        TableEntity::find()
+            .filter(Column::WaiterId.eq(waiter_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Label)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch tables by waiter ID {}: {}", waiter_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a table by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Table, ApiError> {
+        info!("Fetching table with ID: {}", id);
+
+        let table = TableEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch table with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match table {
+            Some(table) => Ok(table),
+            None => Err(ApiError::NotFound("Table not found".to_string())),
+        }
+    }
+
+    /// Create a new, free table
+    pub async fn create(&self, account_id: Uuid, request: CreateTableRequest) -> Result<Table, ApiError> {
+        info!("Creating table: {}", request.label);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let table = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            branch_id: Set(request.branch_id),
+            area_id: Set(request.area_id),
+            label: Set(request.label),
+            seats: Set(request.seats),
+            pos_x: Set(request.pos_x),
+            pos_y: Set(request.pos_y),
+            status: Set(TableStatus::Free.to_string()),
+            waiter_id: Set(None),
+            token_version: Set(1),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        table.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create table: {}", e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A table with this label already exists in the branch".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Update an existing table
+    pub async fn update(&self, id: Uuid, request: UpdateTableRequest) -> Result<Table, ApiError> {
+        info!("Updating table with ID: {}", id);
+
+        let mut table: ActiveModel = self.get_by_id(id).await?.into();
+
+        if let Some(area_id) = request.area_id {
+            table.area_id = Set(Some(area_id));
+        }
+
+        if let Some(label) = request.label {
+            table.label = Set(label);
+        }
+
+        if let Some(seats) = request.seats {
+            table.seats = Set(seats);
+        }
+
+        if let Some(pos_x) = request.pos_x {
+            table.pos_x = Set(pos_x);
+        }
+
+        if let Some(pos_y) = request.pos_y {
+            table.pos_y = Set(pos_y);
+        }
+
+        table.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        table.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update table {}: {}", id, e);
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => {
+                        ApiError::Conflict("A table with this label already exists in the branch".to_string())
+                    }
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
+            })
+    }
+
+    /// Change the status of a table
+    pub async fn set_status(&self, id: Uuid, status: TableStatus) -> Result<Table, ApiError> {
+        info!("Setting table {} status to {}", id, status);
+
+        let mut table: ActiveModel = self.get_by_id(id).await?.into();
+        table.status = Set(status.to_string());
+        table.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        table.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update table {} status: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Assign a waiter to a table, or clear the assignment
+    pub async fn set_waiter(&self, id: Uuid, waiter_id: Option<Uuid>) -> Result<Table, ApiError> {
+        info!("Setting waiter of table {}", id);
+
+        let mut table: ActiveModel = self.get_by_id(id).await?.into();
+        table.waiter_id = Set(waiter_id);
+        table.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        table.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update waiter of table {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Bump the token version of a table, invalidating its QR code and guest sessions
+    pub async fn rotate_token(&self, id: Uuid) -> Result<Table, ApiError> {
+        info!("Rotating token of table {}", id);
+
+        let table = self.get_by_id(id).await?;
+        let version = table.token_version + 1;
+
+        let mut table: ActiveModel = table.into();
+        table.token_version = Set(version);
+        table.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        table.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to rotate token of table {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a table
+    pub async fn soft_delete(&self, id: Uuid) -> Result<Table, ApiError> {
+        info!("Soft deleting table with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let mut table: ActiveModel = self.get_by_id(id).await?.into();
+        table.deleted_at = Set(Some(now));
+        table.updated_at = Set(now);
+
+        table.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete table {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
@@ -0,0 +1,44 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create table routes for staff of an account
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/floor-plan", get(get_floor_plan))
+        .route("/tables/mine", get(get_mine))
+        .route("/tables/:id", get(get_by_id))
+}
+
+/// Create table routes for floor staff (waiter, manager and above)
+pub fn create_floor_routes() -> Router<AppState> {
+    Router::new()
+        .route("/tables/:id/status", put(update_status))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER", "WAITER"])))
+}
+
+/// Create floor plan administration routes (manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/floor-areas", post(create_area))
+        .route("/floor-areas/:id", put(update_area).delete(delete_area))
+        .route("/tables", post(create))
+        .route("/tables/:id", put(update).delete(delete_table))
+        .route("/tables/:id/waiter", put(assign_waiter))
+        .route("/tables/:id/qr", get(get_qr_code))
+        .route("/tables/:id/qr/rotate", post(rotate_qr_code))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
+
+/// Create guest routes for customers seated through a table QR code
+pub fn create_public_routes() -> Router<AppState> {
+    Router::new()
+        .route("/guest/session", post(start_guest_session).get(get_guest_session).delete(end_guest_session))
+        .route("/guest/menu", get(get_guest_menu))
+}
@@ -0,0 +1,401 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::{info, warn};
+
+use crate::{
+    common::{config::Config, events::{topic, Event, SharedEventBus}, signing, ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        menu::{entity::MenuView, service::MenuService},
+        table::{
+            entity::{
+                area, CreateAreaRequest, CreateTableRequest, FloorPlan, GuestInfo, GuestSessionView, Model as Table, TableQrCode,
+                TableStatus, UpdateAreaRequest, UpdateTableRequest,
+            },
+            repository::TableRepository,
+        },
+        user::{entity::UserRole, repository::UserRepository},
+    },
+};
+
+/// Prefix of the signed table token payload
+const TOKEN_PREFIX: &str = "table";
+
+/// Table service layer for business logic
+#[derive(Debug, Clone)]
+pub struct TableService {
+    repository: TableRepository,
+    branch_repository: BranchRepository,
+    user_repository: UserRepository,
+    menu_service: MenuService,
+    audit_service: AuditService,
+    events: SharedEventBus,
+    secret: String,
+    qr_url: String,
+}
+
+impl TableService {
+    /// Create a new table service
+    pub fn new(
+        repository: TableRepository,
+        branch_repository: BranchRepository,
+        user_repository: UserRepository,
+        menu_service: MenuService,
+        audit_service: AuditService,
+        events: SharedEventBus,
+        config: &Config,
+    ) -> Self {
+        Self {
+            repository,
+            branch_repository,
+            user_repository,
+            menu_service,
+            audit_service,
+            events,
+            secret: config.session.secret.expose().clone(),
+            qr_url: config.table.qr_url.clone(),
+        }
+    }
+
+    /// Areas and tables of a branch, defaulting to the session's active branch
+    pub async fn get_floor_plan(&self, actor: &UserInfo, branch_id: Option<Uuid>) -> Result<FloorPlan, ApiError> {
+        let branch_id = branch_id
+            .or(actor.parsed_active_branch_id()?)
+            .ok_or_else(|| ApiError::InvalidInput("branch_id is required without an active branch".to_string()))?;
+        let branch = self.get_branch(actor, branch_id).await?;
+
+        Ok(FloorPlan {
+            branch_id: branch.id,
+            areas: self.repository.get_areas(branch.id).await?,
+            tables: self.repository.get_by_branch_id(branch.id).await?,
+        })
+    }
+
+    /// Tables the caller is assigned to as waiter
+    pub async fn get_mine(&self, actor: &UserInfo) -> Result<Vec<Table>, ApiError> {
+        self.repository.get_by_waiter_id(actor.parsed_id()?).await
+    }
+
+    /// Get a table of the caller's account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Table, ApiError> {
+        self.get_owned(actor, id).await
+    }
+
+    /// Create a floor area in a branch of the caller's account
+    pub async fn create_area(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateAreaRequest) -> Result<area::Model, ApiError> {
+        info!("Creating floor area: {}", data.name);
+
+        let branch = self.get_branch(actor, data.branch_id).await?;
+        let area = self.repository.create_area(branch.account_id, data).await?;
+
+        self.audit(ctx, area.account_id, AuditAction::FloorAreaCreated, (AuditTarget::FloorArea, area.id), None, Some(&area)).await?;
+        Ok(area)
+    }
+
+    /// Update a floor area
+    pub async fn update_area(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateAreaRequest) -> Result<area::Model, ApiError> {
+        info!("Updating floor area with ID: {}", id);
+
+        let before = self.get_owned_area(actor, id).await?;
+        let area = self.repository.update_area(id, data).await?;
+
+        self.audit(ctx, area.account_id, AuditAction::FloorAreaUpdated, (AuditTarget::FloorArea, id), Some(&before), Some(&area)).await?;
+        Ok(area)
+    }
+
+    /// Delete a floor area without tables
+    pub async fn delete_area(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting floor area with ID: {}", id);
+
+        let before = self.get_owned_area(actor, id).await?;
+        if self.repository.count_tables_in_area(id).await? > 0 {
+            return Err(ApiError::Conflict("Floor area still has tables".to_string()));
+        }
+
+        let deleted = self.repository.soft_delete_area(id).await?;
+
+        self.audit(ctx, deleted.account_id, AuditAction::FloorAreaDeleted, (AuditTarget::FloorArea, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Create a table in a branch of the caller's account
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateTableRequest) -> Result<Table, ApiError> {
+        info!("Creating table: {}", data.label);
+
+        let branch = self.get_branch(actor, data.branch_id).await?;
+        if let Some(area_id) = data.area_id {
+            self.ensure_area_in_branch(actor, area_id, branch.id).await?;
+        }
+
+        let table = self.repository.create(branch.account_id, data).await?;
+
+        self.audit(ctx, table.account_id, AuditAction::TableCreated, (AuditTarget::Table, table.id), None, Some(&table)).await?;
+        Ok(table)
+    }
+
+    /// Update a table's label, seats or position on the floor plan
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateTableRequest) -> Result<Table, ApiError> {
+        info!("Updating table with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        if let Some(area_id) = data.area_id {
+            self.ensure_area_in_branch(actor, area_id, before.branch_id).await?;
+        }
+
+        let table = self.repository.update(id, data).await?;
+
+        self.audit(ctx, table.account_id, AuditAction::TableUpdated, (AuditTarget::Table, id), Some(&before), Some(&table)).await?;
+        Ok(table)
+    }
+
+    /// Delete a free table (soft delete)
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting table with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        if before.status != TableStatus::Free.to_string() {
+            return Err(ApiError::Conflict(format!("Table is {}", before.status)));
+        }
+
+        let deleted = self.repository.soft_delete(id).await?;
+
+        self.audit(ctx, deleted.account_id, AuditAction::TableDeleted, (AuditTarget::Table, id), Some(&before), Some(&deleted)).await?;
+        Ok(())
+    }
+
+    /// Set the status of a table by hand, e.g. after cleaning it
+    pub async fn set_status(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, status: TableStatus) -> Result<Table, ApiError> {
+        info!("Setting table {} status to {}", id, status);
+
+        let before = self.get_owned(actor, id).await?;
+        let table = self.repository.set_status(id, status).await?;
+
+        self.audit(ctx, table.account_id, AuditAction::TableStatusChanged, (AuditTarget::Table, id), Some(&before), Some(&table)).await?;
+        self.publish_status(&table).await;
+        Ok(table)
+    }
+
+    /// Mark a table occupied when an order is opened at it
+    pub async fn occupy(&self, id: Uuid) -> Result<Table, ApiError> {
+        let table = self.repository.get_by_id(id).await?;
+        if table.status == TableStatus::Occupied.to_string() {
+            return Ok(table);
+        }
+
+        let table = self.repository.set_status(id, TableStatus::Occupied).await?;
+        self.publish_status(&table).await;
+        Ok(table)
+    }
+
+    /// Flag a table for cleaning once its last order is closed
+    pub async fn release(&self, id: Uuid) -> Result<Table, ApiError> {
+        let table = self.repository.get_by_id(id).await?;
+        if table.status != TableStatus::Occupied.to_string() {
+            return Ok(table);
+        }
+
+        let table = self.repository.set_status(id, TableStatus::NeedsCleaning).await?;
+        self.publish_status(&table).await;
+        Ok(table)
+    }
+
+    /// Assign a waiter of the account to a table, or clear the assignment
+    pub async fn assign_waiter(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, waiter_id: Option<Uuid>) -> Result<Table, ApiError> {
+        info!("Assigning waiter to table with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        if let Some(waiter_id) = waiter_id {
+            let waiter = self.user_repository.get_by_id(waiter_id).await?;
+            if waiter.account_id != before.account_id {
+                return Err(ApiError::UserNotFound);
+            }
+
+            let role: UserRole = waiter.role.parse().map_err(ApiError::InvalidInput)?;
+            if !matches!(role, UserRole::Waiter | UserRole::Manager) {
+                return Err(ApiError::InvalidInput("Only waiters and managers can be assigned to tables".to_string()));
+            }
+        }
+
+        let table = self.repository.set_waiter(id, waiter_id).await?;
+
+        self.audit(ctx, table.account_id, AuditAction::TableWaiterAssigned, (AuditTarget::Table, id), Some(&before), Some(&table)).await?;
+        Ok(table)
+    }
+
+    /// The signed token of a table and the URL to print as its QR code
+    pub async fn qr_code(&self, actor: &UserInfo, id: Uuid) -> Result<TableQrCode, ApiError> {
+        let table = self.get_owned(actor, id).await?;
+        Ok(self.issue(&table))
+    }
+
+    /// Issue a new QR code, invalidating the printed one and any guest sessions started from it
+    pub async fn rotate_qr_code(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<TableQrCode, ApiError> {
+        info!("Rotating QR code of table with ID: {}", id);
+
+        self.get_owned(actor, id).await?;
+        let table = self.repository.rotate_token(id).await?;
+
+        self.audit::<Table>(ctx, table.account_id, AuditAction::TableQrRotated, (AuditTarget::Table, id), None, None).await?;
+        Ok(self.issue(&table))
+    }
+
+    /// Seat a guest at the table a scanned token points to
+    pub async fn start_guest_session(&self, token: &str) -> Result<(GuestInfo, GuestSessionView), ApiError> {
+        let table = self.verify_token(token).await?;
+        info!("Starting guest session at table {}", table.id);
+
+        let guest = GuestInfo {
+            table_id: table.id,
+            branch_id: table.branch_id,
+            account_id: table.account_id,
+            token_version: table.token_version,
+            started_at: chrono::Utc::now().fixed_offset(),
+        };
+
+        let view = self.guest_view(&guest).await?;
+        Ok((guest, view))
+    }
+
+    /// Describe a guest's table, rejecting sessions whose QR code was rotated
+    pub async fn guest_view(&self, guest: &GuestInfo) -> Result<GuestSessionView, ApiError> {
+        let (table, branch) = self.resolve_guest(guest).await?;
+
+        Ok(GuestSessionView {
+            table_id: table.id,
+            table_label: table.label,
+            branch_id: branch.id,
+            branch_name: branch.name,
+            started_at: guest.started_at,
+        })
+    }
+
+    /// The menu a guest can order from right now
+    pub async fn guest_menu(&self, guest: &GuestInfo) -> Result<MenuView, ApiError> {
+        let (_, branch) = self.resolve_guest(guest).await?;
+        self.menu_service.build_menu(branch.account_id, Some(&branch), true).await
+    }
+
+    /// Resolve a guest session to its table and branch while its QR code is still valid
+    pub async fn resolve_guest(&self, guest: &GuestInfo) -> Result<(Table, Branch), ApiError> {
+        let expired = || ApiError::Unauthorized("Guest session expired, scan the table again".to_string());
+
+        let table = self.repository.get_by_id(guest.table_id).await.map_err(|e| match e {
+            ApiError::NotFound(_) => expired(),
+            e => e,
+        })?;
+        if table.token_version != guest.token_version {
+            return Err(expired());
+        }
+
+        let branch = self.branch_repository.get_by_id(table.branch_id).await?;
+        Ok((table, branch))
+    }
+
+    /// Resolve a signed token to its table
+    async fn verify_token(&self, token: &str) -> Result<Table, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid table code".to_string());
+
+        let payload = signing::verify(&self.secret, token).ok_or_else(|| {
+            warn!("Rejected table token with bad signature");
+            invalid()
+        })?;
+
+        let mut parts = payload.splitn(3, ':');
+        let (Some(TOKEN_PREFIX), Some(id), Some(version)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        let version: i32 = version.parse().map_err(|_| invalid())?;
+
+        let table = self.repository.get_by_id(id).await.map_err(|e| match e {
+            ApiError::NotFound(_) => invalid(),
+            e => e,
+        })?;
+
+        // Rotating the code bumps the version, so older codes no longer match
+        if table.token_version != version {
+            return Err(invalid());
+        }
+
+        Ok(table)
+    }
+
+    fn issue(&self, table: &Table) -> TableQrCode {
+        let payload = format!("{}:{}:{}", TOKEN_PREFIX, table.id, table.token_version);
+        let token = signing::sign(&self.secret, &payload);
+        let url = format!("{}?token={}", self.qr_url, token);
+
+        TableQrCode {
+            table_id: table.id,
+            token,
+            url,
+        }
+    }
+
+    /// Fetch a table, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Table, ApiError> {
+        let table = self.repository.get_by_id(id).await?;
+
+        if table.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Table not found".to_string()));
+        }
+
+        Ok(table)
+    }
+
+    /// Fetch a floor area, hiding those of other accounts
+    async fn get_owned_area(&self, actor: &UserInfo, id: Uuid) -> Result<area::Model, ApiError> {
+        let area = self.repository.get_area(id).await?;
+
+        if area.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Floor area not found".to_string()));
+        }
+
+        Ok(area)
+    }
+
+    async fn ensure_area_in_branch(&self, actor: &UserInfo, area_id: Uuid, branch_id: Uuid) -> Result<(), ApiError> {
+        let area = self.get_owned_area(actor, area_id).await?;
+        if area.branch_id != branch_id {
+            return Err(ApiError::InvalidInput("Floor area belongs to another branch".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Fetch a branch, hiding those of other accounts
+    async fn get_branch(&self, actor: &UserInfo, branch_id: Uuid) -> Result<Branch, ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+
+        if branch.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+
+        Ok(branch)
+    }
+
+    /// Tell floor staff about a table's new status
+    async fn publish_status(&self, table: &Table) {
+        let topics = vec![topic::branch(table.branch_id), topic::table(table.id)];
+        self.events.publish(Event::new("table.status_changed", topics, table)).await;
+    }
+
+    /// Record a floor plan mutation in the audit log
+    async fn audit<T: serde::Serialize>(
+        &self,
+        ctx: &RequestContext,
+        account_id: Uuid,
+        action: AuditAction,
+        target: (AuditTarget, Uuid),
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, account_id, action, (target.0, Some(target.1)), before, after)
+            .await
+    }
+}
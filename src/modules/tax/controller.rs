@@ -0,0 +1,96 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::ApiError,
+    modules::branch::entity::Model as Branch,
+    modules::tax::entity::{CreateJurisdictionRequest, Model as Jurisdiction, SetBranchJurisdictionRequest, UpdateJurisdictionRequest},
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List the tax jurisdictions of the account
+pub async fn get_all(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Jurisdiction>>, ApiError> {
+    info!("Fetching tax jurisdictions");
+    let result = state.tax_service.get_all(&user).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific tax jurisdiction by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Jurisdiction>, ApiError> {
+    info!("Fetching tax jurisdiction with ID: {}", id);
+    let result = state.tax_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Create a new tax jurisdiction
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateJurisdictionRequest>,
+) -> Result<(StatusCode, Json<Jurisdiction>), ApiError> {
+    info!("Creating tax jurisdiction: {}", payload.name);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.tax_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update an existing tax jurisdiction
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateJurisdictionRequest>,
+) -> Result<Json<Jurisdiction>, ApiError> {
+    info!("Updating tax jurisdiction with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.tax_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a tax jurisdiction (soft delete)
+pub async fn delete_jurisdiction(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting tax jurisdiction with ID: {}", id);
+    state.tax_service.delete(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Choose the tax jurisdiction of a branch
+pub async fn set_branch_jurisdiction(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<SetBranchJurisdictionRequest>,
+) -> Result<Json<Branch>, ApiError> {
+    info!("Setting tax jurisdiction of branch {}", id);
+    let result = state.tax_service.set_branch_jurisdiction(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
@@ -0,0 +1,558 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::RoundingStrategy;
+use sea_orm::entity::prelude::*;
+use sea_orm::FromJsonQueryResult;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{common::money::Currency, modules::menu::entity::TaxCategory};
+
+/// Tax rules shared by the branches of a country or region
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "tax_jurisdictions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub name: String,
+    /// Whether menu prices already contain the tax or have it added on top
+    pub prices_include_tax: bool,
+    pub rounding: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub rates: TaxRates,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("TaxJurisdiction", 8)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("prices_include_tax", &self.prices_include_tax)?;
+        state.serialize_field("rounding", &self.rounding)?;
+        state.serialize_field("rates", &self.rates)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The rules to copy onto an order opened under this jurisdiction at a branch using `currency`
+    pub fn policy(&self, currency: Currency) -> TaxPolicy {
+        TaxPolicy {
+            name: self.name.clone(),
+            prices_include_tax: self.prices_include_tax,
+            rounding: self.rounding.clone(),
+            rates: self.rates.0.clone(),
+            exponent: currency.exponent(),
+        }
+    }
+}
+
+/// Rate charged on one tax category of the menu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxRate {
+    pub tax_category: String,
+    /// Percentage, e.g. `19` or `7.7`
+    pub rate: Decimal,
+    /// Name printed on receipts, e.g. `VAT A`
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+#[serde(transparent)]
+pub struct TaxRates(pub Vec<TaxRate>);
+
+/// Tax rules of a jurisdiction, copied onto an order when it is opened so later rate changes leave it alone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
+pub struct TaxPolicy {
+    /// Name of the jurisdiction
+    pub name: String,
+    pub prices_include_tax: bool,
+    /// `LINE` or `INVOICE`
+    pub rounding: String,
+    pub rates: Vec<TaxRate>,
+    /// Decimals of the branch currency amounts are rounded to; policies copied before it was kept were in cents
+    #[serde(default = "default_exponent")]
+    pub exponent: u32,
+}
+
+fn default_exponent() -> u32 {
+    2
+}
+
+impl Default for TaxPolicy {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            prices_include_tax: false,
+            rounding: String::new(),
+            rates: Vec::new(),
+            exponent: default_exponent(),
+        }
+    }
+}
+
+impl TaxPolicy {
+    /// Rate of a tax category; categories without one are not taxed
+    pub fn rate(&self, tax_category: &str) -> Option<&TaxRate> {
+        self.rates.iter().find(|rate| rate.tax_category == tax_category)
+    }
+
+    /// Taxes of an order per category, from its line totals and discount.
+    /// The discount is spread over the lines in proportion to their totals before any tax is worked out.
+    pub fn breakdown<'a>(&self, lines: impl IntoIterator<Item = (&'a str, Decimal)>, discount: Decimal) -> Vec<TaxLine> {
+        let lines: Vec<(&str, Decimal)> = lines.into_iter().collect();
+        let subtotal: Decimal = lines.iter().map(|(_, amount)| *amount).sum();
+        let discount = discount.min(subtotal).max(Decimal::ZERO);
+        let per_line = self.rounding.parse::<TaxRounding>() == Ok(TaxRounding::Line);
+
+        // Discount share of each line, the largest line taking what rounding left over
+        let mut shares: Vec<Decimal> = lines
+            .iter()
+            .map(|(_, amount)| if subtotal.is_zero() { Decimal::ZERO } else { self.round(discount * amount / subtotal) })
+            .collect();
+        if let Some(largest) = lines.iter().enumerate().max_by_key(|(_, (_, amount))| *amount).map(|(i, _)| i) {
+            let allocated: Decimal = shares.iter().sum();
+            shares[largest] += discount - allocated;
+        }
+
+        // Taxable amount and, when rounding per line, tax of each category
+        let mut totals: BTreeMap<&str, (Decimal, Decimal)> = BTreeMap::new();
+        for ((category, amount), share) in lines.iter().zip(shares) {
+            let base = amount - share;
+            let (taxable, tax) = totals.entry(category).or_default();
+            *taxable += base;
+            if per_line {
+                *tax += self.tax_on(category, base);
+            }
+        }
+
+        totals
+            .into_iter()
+            .map(|(category, (taxable, tax))| {
+                let tax = if per_line { tax } else { self.tax_on(category, taxable) };
+                let (net, gross) = if self.prices_include_tax { (taxable - tax, taxable) } else { (taxable, taxable + tax) };
+                let rate = self.rate(category);
+                TaxLine {
+                    tax_category: category.to_string(),
+                    label: rate.and_then(|rate| rate.label.clone()),
+                    rate: rate.map_or(Decimal::ZERO, |rate| rate.rate),
+                    net,
+                    tax,
+                    gross,
+                }
+            })
+            .collect()
+    }
+
+    /// Tax contained in or added to an amount of one category
+    fn tax_on(&self, tax_category: &str, amount: Decimal) -> Decimal {
+        let Some(rate) = self.rate(tax_category).map(|rate| rate.rate) else {
+            return Decimal::ZERO;
+        };
+        let hundred = Decimal::from(100);
+
+        if self.prices_include_tax {
+            self.round(amount * rate / (hundred + rate))
+        } else {
+            self.round(amount * rate / hundred)
+        }
+    }
+
+    /// Round to the minor unit of the currency, halves away from zero as tax authorities expect
+    fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.exponent, RoundingStrategy::MidpointAwayFromZero)
+    }
+}
+
+/// Tax of one category on an order, bill or report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxLine {
+    pub tax_category: String,
+    pub label: Option<String>,
+    pub rate: Decimal,
+    pub net: Decimal,
+    pub tax: Decimal,
+    pub gross: Decimal,
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaxRounding {
+    /// Tax is rounded on every line and the rounded amounts added up
+    Line,
+    /// Tax is rounded once per category over the whole order
+    Invoice,
+}
+
+impl std::fmt::Display for TaxRounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaxRounding::Line => write!(f, "LINE"),
+            TaxRounding::Invoice => write!(f, "INVOICE"),
+        }
+    }
+}
+
+impl std::str::FromStr for TaxRounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LINE" => Ok(TaxRounding::Line),
+            "INVOICE" => Ok(TaxRounding::Invoice),
+            _ => Err(format!("Tax rounding {} is not valid", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_rounding(rounding: &str) -> Result<(), ValidationError> {
+    rounding
+        .parse::<TaxRounding>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("rounding").with_message(e.into()))
+}
+
+fn validate_rates(rates: &[TaxRate]) -> Result<(), ValidationError> {
+    let error = |message: String| Err(ValidationError::new("rates").with_message(message.into()));
+
+    for (i, rate) in rates.iter().enumerate() {
+        let category = match rate.tax_category.parse::<TaxCategory>() {
+            Ok(category) => category,
+            Err(e) => return error(e),
+        };
+        if rates[..i].iter().any(|other| other.tax_category == rate.tax_category) {
+            return error(format!("Tax category {} is listed twice", rate.tax_category));
+        }
+        if rate.rate.is_sign_negative() || rate.rate >= Decimal::from(100) || rate.rate.scale() > 3 {
+            return error("Rate must be a percentage below 100 with at most three decimals".to_string());
+        }
+        if matches!(category, TaxCategory::Zero | TaxCategory::Exempt) && !rate.rate.is_zero() {
+            return error(format!("Tax category {} must have a rate of 0", rate.tax_category));
+        }
+        if rate.label.as_ref().is_some_and(|label| label.len() > 20) {
+            return error("Label must be at most 20 characters".to_string());
+        }
+    }
+
+    for category in [TaxCategory::Standard, TaxCategory::Reduced] {
+        if !rates.iter().any(|rate| rate.tax_category == category.to_string()) {
+            return error(format!("A rate for tax category {} is required", category));
+        }
+    }
+    Ok(())
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateJurisdictionRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    /// Whether menu prices already contain the tax
+    pub prices_include_tax: bool,
+
+    /// `LINE` or `INVOICE`
+    #[validate(custom(function = "validate_rounding"))]
+    pub rounding: String,
+
+    #[validate(custom(function = "validate_rates"))]
+    pub rates: Vec<TaxRate>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateJurisdictionRequest {
+    #[validate(length(min = 1, max = 100, message = "Name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    pub prices_include_tax: Option<bool>,
+
+    #[validate(custom(function = "validate_rounding"))]
+    pub rounding: Option<String>,
+
+    /// Replaces all rates of the jurisdiction
+    #[validate(custom(function = "validate_rates"))]
+    pub rates: Option<Vec<TaxRate>>,
+}
+
+/// The jurisdiction whose taxes a branch charges, `null` to charge none
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct SetBranchJurisdictionRequest {
+    pub tax_jurisdiction_id: Option<Uuid>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::testing::dec;
+
+    fn policy(prices_include_tax: bool, rounding: TaxRounding, exponent: u32) -> TaxPolicy {
+        TaxPolicy {
+            name: "Test".to_string(),
+            prices_include_tax,
+            rounding: rounding.to_string(),
+            rates: vec![
+                TaxRate { tax_category: "STANDARD".to_string(), rate: dec("19"), label: Some("A".to_string()) },
+                TaxRate { tax_category: "REDUCED".to_string(), rate: dec("7"), label: Some("B".to_string()) },
+            ],
+            exponent,
+        }
+    }
+
+    struct Case {
+        name: &'static str,
+        prices_include_tax: bool,
+        rounding: TaxRounding,
+        exponent: u32,
+        lines: &'static [(&'static str, &'static str)],
+        discount: &'static str,
+        /// Category, net, tax and gross
+        expected: &'static [(&'static str, &'static str, &'static str, &'static str)],
+    }
+
+    const CASES: &[Case] = &[
+        Case {
+            name: "exclusive invoice adds the tax on top",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("STANDARD", "10.00"), ("STANDARD", "10.00")],
+            discount: "0",
+            expected: &[("STANDARD", "20.00", "3.80", "23.80")],
+        },
+        Case {
+            name: "exclusive line rounds every line",
+            prices_include_tax: false,
+            rounding: TaxRounding::Line,
+            exponent: 2,
+            lines: &[("REDUCED", "1.05"), ("REDUCED", "1.05")],
+            discount: "0",
+            expected: &[("REDUCED", "2.10", "0.14", "2.24")],
+        },
+        Case {
+            name: "exclusive invoice rounds the category once",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("REDUCED", "1.05"), ("REDUCED", "1.05")],
+            discount: "0",
+            expected: &[("REDUCED", "2.10", "0.15", "2.25")],
+        },
+        Case {
+            name: "inclusive invoice takes the tax out of the price",
+            prices_include_tax: true,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("STANDARD", "11.90")],
+            discount: "0",
+            expected: &[("STANDARD", "10.00", "1.90", "11.90")],
+        },
+        Case {
+            name: "inclusive line rounds every line",
+            prices_include_tax: true,
+            rounding: TaxRounding::Line,
+            exponent: 2,
+            lines: &[("REDUCED", "0.50"), ("REDUCED", "0.50"), ("REDUCED", "0.50")],
+            discount: "0",
+            expected: &[("REDUCED", "1.41", "0.09", "1.50")],
+        },
+        Case {
+            name: "inclusive invoice rounds the category once",
+            prices_include_tax: true,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("REDUCED", "0.50"), ("REDUCED", "0.50"), ("REDUCED", "0.50")],
+            discount: "0",
+            expected: &[("REDUCED", "1.40", "0.10", "1.50")],
+        },
+        Case {
+            name: "exclusive discount is spread in proportion to the lines",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("STANDARD", "30.00"), ("REDUCED", "10.00")],
+            discount: "4.00",
+            expected: &[("REDUCED", "9.00", "0.63", "9.63"), ("STANDARD", "27.00", "5.13", "32.13")],
+        },
+        Case {
+            name: "inclusive discount is spread in proportion to the lines",
+            prices_include_tax: true,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("STANDARD", "11.90"), ("REDUCED", "10.70")],
+            discount: "2.26",
+            expected: &[("REDUCED", "9.00", "0.63", "9.63"), ("STANDARD", "9.00", "1.71", "10.71")],
+        },
+        Case {
+            name: "largest line absorbs what rounding the discount shares left over",
+            prices_include_tax: false,
+            rounding: TaxRounding::Line,
+            exponent: 2,
+            lines: &[("STANDARD", "20.00"), ("REDUCED", "5.00"), ("ZERO", "5.00")],
+            discount: "1.00",
+            expected: &[
+                ("REDUCED", "4.83", "0.34", "5.17"),
+                ("STANDARD", "19.34", "3.67", "23.01"),
+                ("ZERO", "4.83", "0", "4.83"),
+            ],
+        },
+        Case {
+            name: "discount beyond the subtotal leaves nothing to tax",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 2,
+            lines: &[("STANDARD", "10.00")],
+            discount: "15.00",
+            expected: &[("STANDARD", "0", "0", "0")],
+        },
+        Case {
+            name: "currencies without minor unit round to whole amounts",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 0,
+            lines: &[("STANDARD", "1050")],
+            discount: "0",
+            expected: &[("STANDARD", "1050", "200", "1250")],
+        },
+        Case {
+            name: "inclusive tax in a currency without minor unit",
+            prices_include_tax: true,
+            rounding: TaxRounding::Line,
+            exponent: 0,
+            lines: &[("REDUCED", "1000")],
+            discount: "0",
+            expected: &[("REDUCED", "935", "65", "1000")],
+        },
+        Case {
+            name: "currencies with three decimals round to the mill",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 3,
+            lines: &[("STANDARD", "1.255")],
+            discount: "0",
+            expected: &[("STANDARD", "1.255", "0.238", "1.493")],
+        },
+        Case {
+            name: "discount shares are rounded to whole amounts too",
+            prices_include_tax: false,
+            rounding: TaxRounding::Invoice,
+            exponent: 0,
+            lines: &[("STANDARD", "200"), ("REDUCED", "100")],
+            discount: "100",
+            expected: &[("REDUCED", "67", "5", "72"), ("STANDARD", "133", "25", "158")],
+        },
+    ];
+
+    #[test]
+    fn breakdown() {
+        for case in CASES {
+            let taxes = policy(case.prices_include_tax, case.rounding, case.exponent)
+                .breakdown(case.lines.iter().map(|(category, amount)| (*category, dec(amount))), dec(case.discount));
+
+            let actual: Vec<(&str, Decimal, Decimal, Decimal)> =
+                taxes.iter().map(|line| (line.tax_category.as_str(), line.net, line.tax, line.gross)).collect();
+            let expected: Vec<(&str, Decimal, Decimal, Decimal)> = case
+                .expected
+                .iter()
+                .map(|(category, net, tax, gross)| (*category, dec(net), dec(tax), dec(gross)))
+                .collect();
+            assert_eq!(actual, expected, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn breakdown_labels_the_rates() {
+        let taxes = policy(false, TaxRounding::Invoice, 2).breakdown([("STANDARD", dec("1.00")), ("EXEMPT", dec("1.00"))], Decimal::ZERO);
+
+        assert_eq!(taxes[0].tax_category, "EXEMPT");
+        assert_eq!((taxes[0].rate, taxes[0].label.as_deref()), (Decimal::ZERO, None));
+        assert_eq!((taxes[1].rate, taxes[1].label.as_deref()), (dec("19"), Some("A")));
+    }
+
+    #[test]
+    fn tax_on() {
+        let cases = [
+            // inclusive, category, exponent, amount, tax
+            (false, "STANDARD", 2, "100.00", "19.00"),
+            (false, "STANDARD", 2, "0.05", "0.01"),
+            (false, "REDUCED", 2, "0.07", "0.00"),
+            (false, "STANDARD", 0, "5", "1"),
+            (false, "STANDARD", 3, "0.050", "0.010"),
+            (true, "STANDARD", 2, "119.00", "19.00"),
+            (true, "REDUCED", 2, "1.07", "0.07"),
+            (true, "REDUCED", 0, "107", "7"),
+            (true, "STANDARD", 3, "1.000", "0.160"),
+            (false, "ZERO", 2, "10.00", "0"),
+            (true, "EXEMPT", 2, "10.00", "0"),
+        ];
+
+        for (inclusive, category, exponent, amount, expected) in cases {
+            let tax = policy(inclusive, TaxRounding::Line, exponent).tax_on(category, dec(amount));
+            assert_eq!(tax, dec(expected), "{} {} of {} at exponent {}", if inclusive { "inclusive" } else { "exclusive" }, category, amount, exponent);
+        }
+    }
+
+    #[test]
+    fn round() {
+        let cases = [
+            // exponent, amount, rounded
+            (2, "0.125", "0.13"),
+            (2, "0.124", "0.12"),
+            (2, "-0.125", "-0.13"),
+            (2, "1.005", "1.01"),
+            (0, "2.5", "3"),
+            (0, "2.49", "2"),
+            (0, "-2.5", "-3"),
+            (3, "0.0005", "0.001"),
+            (3, "1.2344", "1.234"),
+        ];
+
+        for (exponent, amount, expected) in cases {
+            let rounded = policy(false, TaxRounding::Line, exponent).round(dec(amount));
+            assert_eq!(rounded, dec(expected), "{} at exponent {}", amount, exponent);
+        }
+    }
+
+    #[test]
+    fn policy_takes_the_exponent_of_the_branch_currency() {
+        let jurisdiction = Model {
+            id: Uuid::nil(),
+            account_id: Uuid::nil(),
+            name: "Japan".to_string(),
+            prices_include_tax: true,
+            rounding: TaxRounding::Invoice.to_string(),
+            rates: TaxRates(Vec::new()),
+            created_at: chrono::Utc::now().fixed_offset(),
+            updated_at: chrono::Utc::now().fixed_offset(),
+            deleted_at: None,
+        };
+
+        for (currency, exponent) in [("JPY", 0), ("EUR", 2), ("KWD", 3)] {
+            assert_eq!(jurisdiction.policy(currency.parse().unwrap()).exponent, exponent, "{}", currency);
+        }
+    }
+
+    #[test]
+    fn policies_copied_without_exponent_round_to_cents() {
+        let policy: TaxPolicy =
+            serde_json::from_str(r#"{"name":"Old","prices_include_tax":false,"rounding":"LINE","rates":[]}"#).unwrap();
+        assert_eq!(policy.exponent, 2);
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::tax::entity::{
+        ActiveModel, Column, CreateJurisdictionRequest, Entity as JurisdictionEntity, Model as Jurisdiction, TaxRates,
+        UpdateJurisdictionRequest,
+    },
+    common::ApiError,
+};
+
+/// Tax repository for database operations
+#[derive(Debug, Clone)]
+pub struct TaxRepository {
+    db: DatabaseConnection,
+}
+
+impl TaxRepository {
+    /// Create a new tax repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the tax jurisdictions of an account
+    pub async fn get_all(&self, account_id: Uuid) -> Result<Vec<Jurisdiction>, ApiError> {
+        info!("Fetching tax jurisdictions of account {}", account_id);
+
+        JurisdictionEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch tax jurisdictions of account {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a tax jurisdiction by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Jurisdiction, ApiError> {
+        info!("Fetching tax jurisdiction with ID: {}", id);
+
+        let jurisdiction = JurisdictionEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch tax jurisdiction with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match jurisdiction {
+            Some(jurisdiction) => Ok(jurisdiction),
+            None => Err(ApiError::NotFound("Tax jurisdiction not found".to_string())),
+        }
+    }
+
+    /// Create a new tax jurisdiction
+    pub async fn create(&self, account_id: Uuid, request: CreateJurisdictionRequest) -> Result<Jurisdiction, ApiError> {
+        info!("Creating tax jurisdiction: {}", request.name);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let jurisdiction = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            name: Set(request.name),
+            prices_include_tax: Set(request.prices_include_tax),
+            rounding: Set(request.rounding),
+            rates: Set(TaxRates(request.rates)),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        jurisdiction.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create tax jurisdiction: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update a tax jurisdiction
+    pub async fn update(&self, id: Uuid, request: UpdateJurisdictionRequest) -> Result<Jurisdiction, ApiError> {
+        info!("Updating tax jurisdiction with ID: {}", id);
+
+        let mut jurisdiction: ActiveModel = self.get_by_id(id).await?.into();
+        if let Some(name) = request.name {
+            jurisdiction.name = Set(name);
+        }
+        if let Some(prices_include_tax) = request.prices_include_tax {
+            jurisdiction.prices_include_tax = Set(prices_include_tax);
+        }
+        if let Some(rounding) = request.rounding {
+            jurisdiction.rounding = Set(rounding);
+        }
+        if let Some(rates) = request.rates {
+            jurisdiction.rates = Set(TaxRates(rates));
+        }
+        jurisdiction.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        jurisdiction.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update tax jurisdiction with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a tax jurisdiction; orders keep the rules they were opened with
+    pub async fn soft_delete(&self, id: Uuid) -> Result<Jurisdiction, ApiError> {
+        info!("Soft deleting tax jurisdiction with ID: {}", id);
+
+        let mut jurisdiction: ActiveModel = self.get_by_id(id).await?.into();
+        let now = chrono::Utc::now().fixed_offset();
+        jurisdiction.deleted_at = Set(Some(now));
+        jurisdiction.updated_at = Set(now);
+
+        jurisdiction.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete tax jurisdiction {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    routing::{get, post, put},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create tax routes for managers
+pub fn create_routes() -> Router<AppState> {
+    Router::new()
+        .route("/tax-jurisdictions", get(get_all))
+        .route("/tax-jurisdictions/:id", get(get_by_id))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
+
+/// Create tax administration routes (general manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/tax-jurisdictions", post(create))
+        .route("/tax-jurisdictions/:id", put(update).delete(delete_jurisdiction))
+        .route("/branches/:id/tax-jurisdiction", put(set_branch_jurisdiction))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER"])))
+}
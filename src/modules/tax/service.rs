@@ -0,0 +1,133 @@
+use anyhow::Result;
+use uuid::Uuid;
+use tracing::info;
+
+use crate::{
+    common::{ApiError, RequestContext},
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        branch::{entity::Model as Branch, repository::BranchRepository},
+        tax::{
+            entity::{CreateJurisdictionRequest, Model as Jurisdiction, SetBranchJurisdictionRequest, TaxPolicy, UpdateJurisdictionRequest},
+            repository::TaxRepository,
+        },
+    },
+};
+
+/// Tax service layer for business logic
+#[derive(Debug, Clone)]
+pub struct TaxService {
+    repository: TaxRepository,
+    branch_repository: BranchRepository,
+    audit_service: AuditService,
+}
+
+impl TaxService {
+    /// Create a new tax service
+    pub fn new(repository: TaxRepository, branch_repository: BranchRepository, audit_service: AuditService) -> Self {
+        Self { repository, branch_repository, audit_service }
+    }
+
+    /// Get the tax jurisdictions of the caller's account
+    pub async fn get_all(&self, actor: &UserInfo) -> Result<Vec<Jurisdiction>, ApiError> {
+        self.repository.get_all(actor.parsed_account_id()?).await
+    }
+
+    /// Get a tax jurisdiction of the caller's account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Jurisdiction, ApiError> {
+        self.get_owned(actor, id).await
+    }
+
+    /// Create a tax jurisdiction in the caller's account
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateJurisdictionRequest) -> Result<Jurisdiction, ApiError> {
+        info!("Creating tax jurisdiction: {}", data.name);
+
+        let jurisdiction = self.repository.create(actor.parsed_account_id()?, data).await?;
+
+        self.audit(ctx, AuditAction::TaxJurisdictionCreated, None, &jurisdiction).await?;
+        Ok(jurisdiction)
+    }
+
+    /// Update a tax jurisdiction; open orders keep the rules they were opened with
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateJurisdictionRequest) -> Result<Jurisdiction, ApiError> {
+        info!("Updating tax jurisdiction with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let jurisdiction = self.repository.update(id, data).await?;
+
+        self.audit(ctx, AuditAction::TaxJurisdictionUpdated, Some(&before), &jurisdiction).await?;
+        Ok(jurisdiction)
+    }
+
+    /// Delete a tax jurisdiction no branch charges any more
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting tax jurisdiction with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        if self.branch_repository.count_by_tax_jurisdiction(id).await? > 0 {
+            return Err(ApiError::Conflict("Tax jurisdiction is still used by branches".to_string()));
+        }
+
+        let deleted = self.repository.soft_delete(id).await?;
+
+        self.audit(ctx, AuditAction::TaxJurisdictionDeleted, Some(&before), &deleted).await?;
+        Ok(())
+    }
+
+    /// Set or clear the jurisdiction whose taxes a branch charges on new orders
+    pub async fn set_branch_jurisdiction(
+        &self,
+        ctx: &RequestContext,
+        actor: &UserInfo,
+        branch_id: Uuid,
+        data: SetBranchJurisdictionRequest,
+    ) -> Result<Branch, ApiError> {
+        info!("Setting tax jurisdiction of branch {}", branch_id);
+
+        let before = self.branch_repository.get_by_id(branch_id).await?;
+        if before.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
+        if let Some(id) = data.tax_jurisdiction_id {
+            self.get_owned(actor, id).await?;
+        }
+
+        let branch = self.branch_repository.set_tax_jurisdiction(branch_id, data.tax_jurisdiction_id).await?;
+
+        self.audit_service
+            .record(ctx, branch.account_id, AuditAction::BranchUpdated, (AuditTarget::Branch, Some(branch.id)), Some(&before), Some(&branch))
+            .await?;
+        Ok(branch)
+    }
+
+    /// Tax rules to copy onto a new order of a branch; `None` if the branch charges no tax
+    pub async fn policy_for(&self, branch: &Branch) -> Result<Option<TaxPolicy>, ApiError> {
+        let Some(id) = branch.tax_jurisdiction_id else {
+            return Ok(None);
+        };
+        let jurisdiction = self.repository.get_by_id(id).await?;
+        Ok(Some(jurisdiction.policy(branch.currency()?)))
+    }
+
+    /// Fetch a tax jurisdiction, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Jurisdiction, ApiError> {
+        let jurisdiction = self.repository.get_by_id(id).await?;
+
+        if jurisdiction.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Tax jurisdiction not found".to_string()));
+        }
+
+        Ok(jurisdiction)
+    }
+
+    /// Record a tax jurisdiction mutation in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, before: Option<&Jurisdiction>, after: &Jurisdiction) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, after.account_id, action, (AuditTarget::TaxJurisdiction, Some(after.id)), before, Some(after))
+            .await
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use tracing::info;
@@ -8,30 +8,56 @@ use validator::Validate;
 
 use crate::{
     common::ApiError,
-    modules::user::entity::{CreateUserRequest, UpdateUserRequest, Model as User},
-    common::AppState,
+    modules::auth::entity::UserInfo,
+    modules::user::{
+        entity::{CreateUserRequest, UpdateUserRequest, UserQuery, Model as User},
+        service::UserService,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
 };
 
 /// Get all users
-pub async fn get_all(State(state): State<AppState>) -> Result<Json<Vec<User>>, ApiError> {
+pub async fn get_all(
+    Query(query): Query<UserQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<User>>, ApiError> {
     info!("Fetching all users");
-    let result = state.user_service.get_all().await?;
+    ensure_can_include_deleted(&user, &query)?;
+    let result = state.user_service.get_all(UserService::account_scope(&user)?, query.include_deleted).await?;
     Ok(Json(result))
 }
 
 /// Get a specific user by ID
 pub async fn get_by_id(
     Path(id): Path<Uuid>,
+    Query(query): Query<UserQuery>,
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
 ) -> Result<Json<User>, ApiError> {
     info!("Fetching user with ID: {}", id);
-    let result = state.user_service.get_by_id(id).await?;
+    ensure_can_include_deleted(&user, &query)?;
+    let result = if query.include_deleted {
+        state.user_service.get_by_id_with_deleted(&user, id).await?
+    } else {
+        state.user_service.get_by_id(&user, id).await?
+    };
     Ok(Json(result))
 }
 
+/// Only admins may look at soft-deleted users
+fn ensure_can_include_deleted(user: &UserInfo, query: &UserQuery) -> Result<(), ApiError> {
+    if query.include_deleted && !user.parsed_role()?.is_admin() {
+        return Err(ApiError::Forbidden("Only admins can include deleted users".to_string()));
+    }
+    Ok(())
+}
+
 /// Create a new user
 pub async fn create(
     State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
     Json(payload): Json<CreateUserRequest>,
 ) -> Result<Json<User>, ApiError> {
     info!("Creating new user: {}", payload.email);
@@ -40,7 +66,7 @@ pub async fn create(
     payload.validate()
         .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
     
-    let result = state.user_service.create(payload).await?;
+    let result = state.user_service.create(&ctx, &user, payload).await?;
     Ok(Json(result))
 }
 
@@ -48,6 +74,8 @@ pub async fn create(
 pub async fn update(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
     Json(payload): Json<UpdateUserRequest>,
 ) -> Result<Json<User>, ApiError> {
     info!("Updating user with ID: {}", id);
@@ -56,7 +84,7 @@ pub async fn update(
     payload.validate()
         .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
     
-    let result = state.user_service.update(id, payload).await?;
+    let result = state.user_service.update(&ctx, &user, id, payload).await?;
     Ok(Json(result))
 }
 
@@ -64,35 +92,45 @@ pub async fn update(
 pub async fn delete_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
 ) -> Result<(), ApiError> {
     info!("Deleting user with ID: {}", id);
-    state.user_service.delete(id).await
+    state.user_service.delete(&ctx, &user, id).await
 }
 
 /// Deactivate a user (soft delete)
 pub async fn deactivate_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
 ) -> Result<(), ApiError> {
     info!("Deactivating user with ID: {}", id);
-    state.user_service.deactivate(id).await
+    state.user_service.deactivate(&ctx, &user, id).await
 }
 
 /// Activate a user (restore from soft delete)
 pub async fn activate_user(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
 ) -> Result<(), ApiError> {
     info!("Activating user with ID: {}", id);
-    state.user_service.activate(id).await
+    state.user_service.activate(&ctx, &user, id).await
 }
 
 /// Get users by account ID
 pub async fn get_by_account_id(
     Path(account_id): Path<Uuid>,
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
 ) -> Result<Json<Vec<User>>, ApiError> {
     info!("Fetching users by account ID: {}", account_id);
+    if UserService::account_scope(&user)?.is_some_and(|own| own != account_id) {
+        return Err(ApiError::NotFound("Account not found".to_string()));
+    }
     let result = state.user_service.get_by_account_id(account_id).await?;
     Ok(Json(result))
 }
@@ -101,9 +139,10 @@ pub async fn get_by_account_id(
 pub async fn get_by_branch_id(
     Path(branch_id): Path<Uuid>,
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
 ) -> Result<Json<Vec<User>>, ApiError> {
     info!("Fetching users by branch ID: {}", branch_id);
-    let result = state.user_service.get_by_branch_id(branch_id).await?;
+    let result = state.user_service.get_by_branch_id(&user, branch_id).await?;
     Ok(Json(result))
 }
 
@@ -111,8 +150,9 @@ pub async fn get_by_branch_id(
 pub async fn get_by_role(
     Path(role): Path<String>,
     State(state): State<AppState>,
+    SessionUser(user): SessionUser,
 ) -> Result<Json<Vec<User>>, ApiError> {
     info!("Fetching users by role: {}", role);
-    let result = state.user_service.get_by_role(&role).await?;
+    let result = state.user_service.get_by_role(&user, &role).await?;
     Ok(Json(result))
 }
\ No newline at end of file
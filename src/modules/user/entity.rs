@@ -19,6 +19,7 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
     pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub anonymized_at: Option<DateTimeWithTimeZone>,
 }
 
 impl Serialize for Model {
@@ -27,7 +28,7 @@ impl Serialize for Model {
         S: Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("User", 8)?;
+        let mut state = serializer.serialize_struct("User", 11)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("account_id", &self.account_id)?;
         state.serialize_field("branch_id", &self.branch_id)?;
@@ -37,6 +38,8 @@ impl Serialize for Model {
         state.serialize_field("status", &self.status)?;
         state.serialize_field("created_at", &self.created_at)?;
         state.serialize_field("updated_at", &self.updated_at)?;
+        state.serialize_field("deleted_at", &self.deleted_at)?;
+        state.serialize_field("anonymized_at", &self.anonymized_at)?;
         state.end()
     }
 }
@@ -89,6 +92,47 @@ impl std::fmt::Display for UserRole {
     }
 }
 
+impl std::str::FromStr for UserRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ROOT" => Ok(UserRole::Root),
+            "GENERAL_MANAGER" => Ok(UserRole::GeneralManager),
+            "MANAGER" => Ok(UserRole::Manager),
+            "CUSTOMER" => Ok(UserRole::Customer),
+            "WAITER" => Ok(UserRole::Waiter),
+            "COOK" => Ok(UserRole::Cook),
+            "BARMAN" => Ok(UserRole::Barman),
+            "CASH_REGISTER" => Ok(UserRole::CashRegister),
+            _ => Err(format!("Role {} is not valid", s)),
+        }
+    }
+}
+
+impl UserRole {
+    /// Position in the management hierarchy (higher outranks lower)
+    pub fn level(&self) -> u8 {
+        match self {
+            UserRole::Root => 4,
+            UserRole::GeneralManager => 3,
+            UserRole::Manager => 2,
+            UserRole::Waiter | UserRole::Cook | UserRole::Barman | UserRole::CashRegister => 1,
+            UserRole::Customer => 0,
+        }
+    }
+
+    /// Whether this role administers the whole account
+    pub fn is_admin(&self) -> bool {
+        matches!(self, UserRole::Root | UserRole::GeneralManager)
+    }
+
+    /// Whether this role may grant `other` to someone else
+    pub fn outranks(&self, other: &UserRole) -> bool {
+        self.level() > other.level()
+    }
+}
+
 // Request/Response DTOs
 #[derive(Debug, Deserialize, Serialize, Validate)]
 pub struct CreateUserRequest {
@@ -122,4 +166,10 @@ pub struct UpdateUserRequest {
     pub status: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct UserQuery {
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
 // UserResponse is now just Model with custom serialization that excludes password_hash
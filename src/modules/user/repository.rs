@@ -1,11 +1,16 @@
 use anyhow::Result;
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, QueryOrder, PaginatorTrait};
+use sea_orm::{prelude::DateTimeWithTimeZone, sea_query::Query, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait, QueryFilter, ColumnTrait, Set, ActiveModelTrait, ConnectionTrait, QueryOrder, PaginatorTrait, TransactionTrait};
 use uuid::Uuid;
 use tracing::{info, error};
 
 use crate::{
-    modules::user::entity::{Entity as UserEntity, Model as User, CreateUserRequest, UpdateUserRequest, Column, ActiveModel},
-    common::ApiError,
+    modules::auth::entity::UserInfo,
+    modules::branch::entity::user_branch,
+    modules::user::entity::{Entity as UserEntity, Model as User, CreateUserRequest, UpdateUserRequest, UserStatus, Column, ActiveModel},
+    common::{
+        outbox::{self, EventMetadata, NewDomainEvent},
+        ApiError, RequestContext,
+    },
 };
 
 /// User repository for database operations
@@ -20,11 +25,19 @@ impl UserRepository {
         Self { db }
     }
 
-    /// Get all users with ordering
-    pub async fn get_all(&self) -> Result<Vec<User>, ApiError> {
+    /// Get all users with ordering, optionally limited to an account and including soft-deleted ones
+    pub async fn get_all(&self, account_id: Option<Uuid>, include_deleted: bool) -> Result<Vec<User>, ApiError> {
         info!("Fetching all users from database");
         
-        let users = UserEntity::find()
+        let mut query = UserEntity::find();
+        if let Some(account_id) = account_id {
+            query = query.filter(Column::AccountId.eq(account_id));
+        }
+        if !include_deleted {
+            query = query.filter(Column::DeletedAt.is_null());
+        }
+
+        let users = query
             .order_by_desc(Column::CreatedAt)
             .all(&self.db)
             .await
@@ -36,11 +49,12 @@ impl UserRepository {
         Ok(users)
     }
 
-    /// Get a user by ID
+    /// Get a user by ID, excluding soft-deleted users
     pub async fn get_by_id(&self, id: Uuid) -> Result<User, ApiError> {
         info!("Fetching user with ID: {}", id);
 
         let user = UserEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
             .one(&self.db)
             .await
             .map_err(|e| {
@@ -54,12 +68,113 @@ impl UserRepository {
         }
     }
 
-    /// Create a new user
-    pub async fn create(&self, request: CreateUserRequest, password_hash: String) -> Result<User, ApiError> {
+    /// Get a user by ID, including soft-deleted users
+    pub async fn get_by_id_with_deleted(&self, id: Uuid) -> Result<User, ApiError> {
+        info!("Fetching user with ID (including deleted): {}", id);
+
+        let user = UserEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch user with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match user {
+            Some(user) => Ok(user),
+            None => Err(ApiError::UserNotFound),
+        }
+    }
+
+    /// Get users by their IDs, including soft-deleted users
+    pub async fn get_by_ids(&self, ids: Vec<Uuid>) -> Result<Vec<User>, ApiError> {
+        UserEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch users by IDs: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Create a new user, recording `user.created` in the same transaction
+    pub async fn create(&self, ctx: &RequestContext, request: CreateUserRequest, password_hash: String) -> Result<User, ApiError> {
         info!("Creating new user: {}", request.email);
 
+        let txn = self.db.begin().await.map_err(|e| {
+            error!("Failed to start user creation transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let user = Self::create_in(&txn, ctx, request, password_hash).await?;
+
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit creation of user {}: {}", user.id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        info!("Created user with ID: {}", user.id);
+        Ok(user)
+    }
+
+    /// Create a user inside the caller's transaction, recording `user.created` along with it
+    ///
+    /// Without an authenticated actor the user signed up on their own, so they are the actor of their creation.
+    pub async fn create_in<C: ConnectionTrait>(
+        db: &C,
+        ctx: &RequestContext,
+        request: CreateUserRequest,
+        password_hash: String,
+    ) -> Result<User, ApiError> {
+        let user = Self::new_active_model(request, password_hash)
+            .insert(db)
+            .await
+            .map_err(Self::map_insert_error)?;
+
+        let ctx = match ctx.user {
+            Some(_) => ctx.clone(),
+            None => ctx.clone().with_user(UserInfo::from(user.clone())),
+        };
+        outbox::record(db, Self::created_event(&ctx, &user)).await?;
+        Ok(user)
+    }
+
+    /// The `user.created` event of a new user
+    fn created_event(ctx: &RequestContext, user: &User) -> NewDomainEvent {
+        NewDomainEvent::new(
+            outbox::kind::USER_CREATED,
+            user.account_id,
+            (outbox::aggregate::USER, user.id),
+            user,
+            EventMetadata::from(ctx),
+        )
+    }
+
+    /// The event of a change to a user, keeping the user before it
+    fn changed_event(ctx: &RequestContext, kind: &str, before: &User, after: &User) -> NewDomainEvent {
+        NewDomainEvent::new(kind, after.account_id, (outbox::aggregate::USER, after.id), after, EventMetadata::from(ctx))
+            .with_previous(before)
+    }
+
+    async fn begin(&self) -> Result<DatabaseTransaction, ApiError> {
+        self.db.begin().await.map_err(|e| {
+            error!("Failed to start user transaction: {}", e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    async fn commit(txn: DatabaseTransaction, id: Uuid) -> Result<(), ApiError> {
+        txn.commit().await.map_err(|e| {
+            error!("Failed to commit change of user {}: {}", id, e);
+            ApiError::DatabaseError(e.to_string())
+        })
+    }
+
+    /// Build the active model of a new, active user
+    fn new_active_model(request: CreateUserRequest, password_hash: String) -> ActiveModel {
         let now = chrono::Utc::now().fixed_offset();
-        let user = ActiveModel {
+        ActiveModel {
             id: Set(uuid::Uuid::new_v4()),
             account_id: Set(request.account_id),
             branch_id: Set(request.branch_id),
@@ -67,37 +182,41 @@ impl UserRepository {
             email: Set(request.email),
             password_hash: Set(Some(password_hash)),
             role: Set(request.role),
-            status: Set("ACTIVE".to_string()),
+            status: Set(UserStatus::Active.to_string()),
             created_at: Set(now),
             updated_at: Set(now),
             deleted_at: Set(None),
-        };
-
-        let user = user.insert(&self.db)
-            .await
-            .map_err(|e| {
-                error!("Failed to create user: {}", e);
-                match e {
-                    sea_orm::error::DbErr::RecordNotInserted => ApiError::UserAlreadyExists,
-                    _ => ApiError::DatabaseError(e.to_string()),
-                }
-            })?;
+            anonymized_at: Set(None),
+        }
+    }
 
-        info!("Created user with ID: {}", user.id);
-        Ok(user)
+    /// Map a failed user insert, reporting duplicate emails as such
+    fn map_insert_error(e: sea_orm::DbErr) -> ApiError {
+        error!("Failed to create user: {}", e);
+        match e.sql_err() {
+            Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => ApiError::UserAlreadyExists,
+            _ => match e {
+                sea_orm::error::DbErr::RecordNotInserted => ApiError::UserAlreadyExists,
+                _ => ApiError::DatabaseError(e.to_string()),
+            },
+        }
     }
 
     /// Update an existing user
-    pub async fn update(&self, id: Uuid, request: UpdateUserRequest, password_hash: Option<String>) -> Result<User, ApiError> {
+    pub async fn update(&self, ctx: &RequestContext, id: Uuid, request: UpdateUserRequest, password_hash: Option<String>) -> Result<User, ApiError> {
         info!("Updating user with ID: {}", id);
 
         // First, get the existing user
-        let user = self.get_by_id(id).await?;
+        let before = self.get_by_id(id).await?;
 
         // Create active model for update
-        let mut user: ActiveModel = user.into();
+        let mut user: ActiveModel = before.clone().into();
 
         // Update fields if provided
+        if let Some(branch_id) = request.branch_id {
+            user.branch_id = Set(Some(branch_id));
+        }
+
         if let Some(name) = request.name {
             user.name = Set(Some(name));
         }
@@ -113,7 +232,8 @@ impl UserRepository {
         // Update timestamp
         user.updated_at = Set(chrono::Utc::now().fixed_offset());
 
-        let user = user.update(&self.db)
+        let txn = self.begin().await?;
+        let user = user.update(&txn)
             .await
             .map_err(|e| {
                 error!("Failed to update user {}: {}", id, e);
@@ -122,6 +242,8 @@ impl UserRepository {
                     _ => ApiError::DatabaseError(e.to_string()),
                 }
             })?;
+        outbox::record(&txn, Self::changed_event(ctx, outbox::kind::USER_UPDATED, &before, &user)).await?;
+        Self::commit(txn, id).await?;
 
         info!("Updated user with ID: {}", id);
         Ok(user)
@@ -165,44 +287,122 @@ impl UserRepository {
         Ok(count > 0)
     }
 
-    /// Soft delete a user
-    pub async fn soft_delete(&self, id: Uuid) -> Result<(), ApiError> {
+    /// Get a live user by email
+    pub async fn get_by_email(&self, email: &str) -> Result<User, ApiError> {
+        let user = UserEntity::find()
+            .filter(Column::Email.eq(email))
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch user with email {}: {}", email, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match user {
+            Some(user) => Ok(user),
+            None => Err(ApiError::UserNotFound),
+        }
+    }
+
+    /// Soft delete a user, marking it inactive
+    pub async fn soft_delete(&self, ctx: &RequestContext, id: Uuid) -> Result<User, ApiError> {
         info!("Soft deleting user with ID: {}", id);
         
         let now = chrono::Utc::now().fixed_offset();
-        let mut user: ActiveModel = self.get_by_id(id).await?.into();
+        let before = self.get_by_id(id).await?;
+        let mut user: ActiveModel = before.clone().into();
+        user.status = Set(UserStatus::Inactive.to_string());
         user.deleted_at = Set(Some(now));
         user.updated_at = Set(now);
         
-        user.update(&self.db)
+        let txn = self.begin().await?;
+        let user = user.update(&txn)
             .await
             .map_err(|e| {
                 error!("Failed to soft delete user with ID {}: {}", id, e);
                 ApiError::DatabaseError(e.to_string())
             })?;
+        outbox::record(&txn, Self::changed_event(ctx, outbox::kind::USER_DEACTIVATED, &before, &user)).await?;
+        Self::commit(txn, id).await?;
 
         info!("Soft deleted user with ID: {}", id);
-        Ok(())
+        Ok(user)
     }
 
-    /// Restore a soft-deleted user
-    pub async fn restore(&self, id: Uuid) -> Result<(), ApiError> {
+    /// Restore a soft-deleted user, marking it active
+    pub async fn restore(&self, ctx: &RequestContext, id: Uuid) -> Result<User, ApiError> {
         info!("Restoring user with ID: {}", id);
         
         let now = chrono::Utc::now().fixed_offset();
-        let mut user: ActiveModel = self.get_by_id(id).await?.into();
+        let before = self.get_by_id_with_deleted(id).await?;
+        let mut user: ActiveModel = before.clone().into();
+        user.status = Set(UserStatus::Active.to_string());
         user.deleted_at = Set(None);
         user.updated_at = Set(now);
         
-        user.update(&self.db)
+        let txn = self.begin().await?;
+        let user = user.update(&txn)
             .await
             .map_err(|e| {
                 error!("Failed to restore user with ID {}: {}", id, e);
-                ApiError::DatabaseError(e.to_string())
+                // The partial unique index rejects restoring over a live email
+                match e.sql_err() {
+                    Some(sea_orm::SqlErr::UniqueConstraintViolation(_)) => ApiError::UserAlreadyExists,
+                    _ => ApiError::DatabaseError(e.to_string()),
+                }
             })?;
+        outbox::record(&txn, Self::changed_event(ctx, outbox::kind::USER_ACTIVATED, &before, &user)).await?;
+        Self::commit(txn, id).await?;
 
         info!("Restored user with ID: {}", id);
-        Ok(())
+        Ok(user)
+    }
+
+    /// Irreversibly scrub personal data while keeping the row for referential integrity
+    pub async fn anonymize(&self, id: Uuid) -> Result<User, ApiError> {
+        info!("Anonymizing user with ID: {}", id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let existing = self.get_by_id_with_deleted(id).await?;
+        let deleted_at = existing.deleted_at.unwrap_or(now);
+
+        let mut user: ActiveModel = existing.into();
+        user.name = Set(None);
+        user.email = Set(anonymized_email(id));
+        user.password_hash = Set(None);
+        user.status = Set(UserStatus::Inactive.to_string());
+        user.deleted_at = Set(Some(deleted_at));
+        user.anonymized_at = Set(Some(now));
+        user.updated_at = Set(now);
+
+        let user = user.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to anonymize user with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        info!("Anonymized user with ID: {}", id);
+        Ok(user)
+    }
+
+    /// Get users soft-deleted before a cutoff that still hold personal data
+    pub async fn get_deleted_before(&self, cutoff: DateTimeWithTimeZone) -> Result<Vec<User>, ApiError> {
+        info!("Fetching users soft-deleted before: {}", cutoff);
+
+        let users = UserEntity::find()
+            .filter(Column::DeletedAt.lt(cutoff))
+            .filter(Column::AnonymizedAt.is_null())
+            .order_by_asc(Column::DeletedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch users deleted before {}: {}", cutoff, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(users)
     }
 
     /// Get users by account ID
@@ -223,12 +423,22 @@ impl UserRepository {
         Ok(users)
     }
 
-    /// Get users by branch ID
+    /// Get users working at a branch, by home branch or assignment
     pub async fn get_by_branch_id(&self, branch_id: Uuid) -> Result<Vec<User>, ApiError> {
         info!("Fetching users by branch ID: {}", branch_id);
         
+        let assigned = Query::select()
+            .column(user_branch::Column::UserId)
+            .from(user_branch::Entity)
+            .and_where(user_branch::Column::BranchId.eq(branch_id))
+            .to_owned();
+        
         let users = UserEntity::find()
-            .filter(Column::BranchId.eq(branch_id))
+            .filter(
+                Condition::any()
+                    .add(Column::BranchId.eq(branch_id))
+                    .add(Column::Id.in_subquery(assigned)),
+            )
             .filter(Column::DeletedAt.is_null())
             .order_by_desc(Column::CreatedAt)
             .all(&self.db)
@@ -241,13 +451,18 @@ impl UserRepository {
         Ok(users)
     }
 
-    /// Get users by role
-    pub async fn get_by_role(&self, role: &str) -> Result<Vec<User>, ApiError> {
+    /// Get users by role, of one account or of every account when `None`
+    pub async fn get_by_role(&self, account_id: Option<Uuid>, role: &str) -> Result<Vec<User>, ApiError> {
         info!("Fetching users by role: {}", role);
         
-        let users = UserEntity::find()
+        let mut query = UserEntity::find()
             .filter(Column::Role.eq(role))
-            .filter(Column::DeletedAt.is_null())
+            .filter(Column::DeletedAt.is_null());
+        if let Some(account_id) = account_id {
+            query = query.filter(Column::AccountId.eq(account_id));
+        }
+
+        let users = query
             .order_by_desc(Column::CreatedAt)
             .all(&self.db)
             .await
@@ -259,4 +474,9 @@ impl UserRepository {
         Ok(users)
     }
 
-}
\ No newline at end of file
+}
+
+/// Placeholder email that keeps the row unique without identifying anyone
+pub fn anonymized_email(id: Uuid) -> String {
+    format!("anonymized-{}@anonymized.invalid", id)
+}
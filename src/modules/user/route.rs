@@ -1,20 +1,29 @@
 use axum::{
     routing::{get, post},
-    Router,
+    Router, middleware,
 };
 
 use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
 
 use super::controller::*;
 
-/// Create user routes
+/// Create user routes for managers and above
 pub fn create_routes() -> Router<AppState> {
     Router::new()
-        .route("/users", get(get_all).post(create))
+        .route("/users", get(get_all))
         .route("/users/:id", get(get_by_id).put(update).delete(delete_user))
         .route("/users/:id/deactivate", post(deactivate_user))
         .route("/users/:id/activate", post(activate_user))
         .route("/users/account/:account_id", get(get_by_account_id))
         .route("/users/branch/:branch_id", get(get_by_branch_id))
         .route("/users/role/:role", get(get_by_role))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER", "MANAGER"])))
+}
+
+/// Create user administration routes (root only); staff join through invitations
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users", post(create))
+        .layer(middleware::from_fn(authorize(vec!["ROOT"])))
 }
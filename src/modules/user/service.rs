@@ -1,14 +1,20 @@
 use anyhow::Result;
+use sea_orm::ConnectionTrait;
 use uuid::Uuid;
 use argon2::{Argon2, PasswordHasher};
 use argon2::password_hash::{SaltString, rand_core::OsRng};
 use tracing::info;
 
 use crate::{
-    common::ApiError,
-    modules::user::{
-        entity::{CreateUserRequest, UpdateUserRequest, Model as User, UserRole},
-        repository::UserRepository,
+    common::{ApiError, RequestContext},
+    modules::{
+        account::repository::AccountRepository,
+        auth::entity::{RegisterRequest, UserInfo},
+        branch::repository::BranchRepository,
+        user::{
+            entity::{CreateUserRequest, UpdateUserRequest, Model as User, UserRole},
+            repository::UserRepository,
+        },
     },
 };
 
@@ -16,49 +22,132 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct UserService {
     repository: UserRepository,
+    account_repository: AccountRepository,
+    branch_repository: BranchRepository,
 }
 
 impl UserService {
     /// Create a new user service
-    pub fn new(repository: UserRepository) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: UserRepository,
+        account_repository: AccountRepository,
+        branch_repository: BranchRepository,
+    ) -> Self {
+        Self { repository, account_repository, branch_repository }
     }
 
-    /// Get all users
-    pub async fn get_all(&self) -> Result<Vec<User>, ApiError> {
-        self.repository.get_all().await
+    /// Get all users of an account (or of every account when `None`), optionally including soft-deleted ones
+    pub async fn get_all(&self, account_id: Option<Uuid>, include_deleted: bool) -> Result<Vec<User>, ApiError> {
+        self.repository.get_all(account_id, include_deleted).await
     }
 
-    /// Get a user by ID
-    pub async fn get_by_id(&self, id: Uuid) -> Result<User, ApiError> {
-        self.repository.get_by_id(id).await
+    /// Get a user by ID, hiding users of other accounts
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<User, ApiError> {
+        self.get_owned(actor, id, false).await
+    }
+
+    /// Get a user by ID, including soft-deleted users
+    pub async fn get_by_id_with_deleted(&self, actor: &UserInfo, id: Uuid) -> Result<User, ApiError> {
+        self.get_owned(actor, id, true).await
     }
 
     /// Create a new user
-    pub async fn create(&self, data: CreateUserRequest) -> Result<User, ApiError> {
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateUserRequest) -> Result<User, ApiError> {
         info!("Creating new user: {}", data.email);
         
+        // Users are created in the caller's own account unless the caller is root
+        if actor.parsed_role()? != UserRole::Root && actor.parsed_account_id()? != data.account_id {
+            return Err(ApiError::Forbidden("Cannot create users in another account".to_string()));
+        }
+        
+        self.ensure_can_create(&data).await?;
+        self.ensure_can_grant(actor, &data.role)?;
+        
+        // Hash the password
+        let password_hash = self.hash_password(&data.password)?;
+        
+        // Create the user
+        // Auditing and webhooks follow from the recorded `user.created` event
+        let user = self.repository.create(ctx, data, password_hash).await?;
+        Ok(user)
+    }
+
+    /// Sign up a customer of an account; the new customer is the actor of their own creation
+    pub async fn register(&self, ctx: &RequestContext, data: RegisterRequest) -> Result<User, ApiError> {
+        info!("Registering customer: {}", data.email);
+
+        let data = CreateUserRequest {
+            account_id: data.account_id,
+            branch_id: None,
+            name: data.name,
+            email: data.email,
+            password: data.password,
+            role: UserRole::Customer.to_string(),
+        };
+        self.ensure_can_create(&data).await?;
+        let password_hash = self.hash_password(&data.password)?;
+
+        self.repository.create(ctx, data, password_hash).await
+    }
+
+    /// Create a new user inside the caller's transaction, without an actor to check grants against
+    pub async fn create_in<C: ConnectionTrait>(&self, db: &C, ctx: &RequestContext, data: CreateUserRequest) -> Result<User, ApiError> {
+        info!("Creating new user: {}", data.email);
+
+        self.ensure_can_create(&data).await?;
+        let password_hash = self.hash_password(&data.password)?;
+
+        UserRepository::create_in(db, ctx, data, password_hash).await
+    }
+
+    /// Check that a new user has a valid role, an open account, a branch of that account and a free email
+    pub async fn ensure_can_create(&self, data: &CreateUserRequest) -> Result<(), ApiError> {
         // Validate role
         if !self.is_valid_role(&data.role) {
             return Err(ApiError::InvalidInput(format!("Role {} is not valid", data.role)));
         }
         
+        // The account must exist and accept new users
+        let account = self.account_repository.get_by_id(data.account_id).await?;
+        if !account.is_active() {
+            return Err(ApiError::Forbidden("Account is suspended".to_string()));
+        }
+        
+        // The home branch must belong to the same account
+        if let Some(branch_id) = data.branch_id {
+            self.ensure_branch_in_account(branch_id, data.account_id).await?;
+        }
+        
         // Check if user already exists
         if self.repository.exists_by_email(&data.email).await? {
             return Err(ApiError::UserAlreadyExists);
         }
         
-        // Hash the password
-        let password_hash = self.hash_password(&data.password)?;
-        
-        // Create the user
-        self.repository.create(data, password_hash).await
+        Ok(())
     }
 
     /// Update an existing user
-    pub async fn update(&self, id: Uuid, data: UpdateUserRequest) -> Result<User, ApiError> {
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateUserRequest) -> Result<User, ApiError> {
         info!("Updating user with ID: {}", id);
         
+        // Status follows deleted_at and is only changed through deactivate/activate
+        if data.status.is_some() {
+            return Err(ApiError::InvalidInput(
+                "Status cannot be updated directly, use deactivate or activate".to_string(),
+            ));
+        }
+        
+        let before = self.get_owned(actor, id, false).await?;
+        self.ensure_can_manage(actor, &before)?;
+        if let Some(ref role) = data.role {
+            self.ensure_can_grant(actor, role)?;
+        }
+        
+        // The home branch must belong to the user's account
+        if let Some(branch_id) = data.branch_id {
+            self.ensure_branch_in_account(branch_id, before.account_id).await?;
+        }
+        
         // Check if email is being updated and if it already exists
         if let Some(ref email) = data.email {
             if self.repository.exists_by_email(email).await? {
@@ -74,53 +163,133 @@ impl UserService {
         };
         
         // Update the user
-        self.repository.update(id, data, password_hash).await
+        // Auditing follows from the recorded `user.updated` event
+        self.repository.update(ctx, id, data, password_hash).await
     }
 
-    /// Delete a user
-    pub async fn delete(&self, id: Uuid) -> Result<(), ApiError> {
+    /// Delete a user (soft delete, same as deactivation)
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
         info!("Deleting user with ID: {}", id);
-        self.repository.delete(id).await
+        self.deactivate(ctx, actor, id).await
     }
 
     /// Deactivate a user (soft delete)
-    pub async fn deactivate(&self, id: Uuid) -> Result<(), ApiError> {
+    pub async fn deactivate(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
         info!("Deactivating user with ID: {}", id);
         
         // Check if user exists
-        let user = self.repository.get_by_id(id).await?;
+        let user = self.get_owned(actor, id, false).await?;
         
         // Don't allow deactivating root users
         if user.role == UserRole::Root.to_string() {
             return Err(ApiError::Unauthorized("Cannot deactivate root users".to_string()));
         }
+        self.ensure_can_manage(actor, &user)?;
         
-        self.repository.soft_delete(id).await
+        self.repository.soft_delete(ctx, id).await?;
+        Ok(())
     }
 
     /// Activate a user (restore from soft delete)
-    pub async fn activate(&self, id: Uuid) -> Result<(), ApiError> {
+    pub async fn activate(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
         info!("Activating user with ID: {}", id);
-        self.repository.restore(id).await
+        
+        let user = self.get_owned(actor, id, true).await?;
+        self.ensure_can_manage(actor, &user)?;
+        
+        // Another user may have registered the email in the meantime
+        if user.deleted_at.is_some() && self.repository.exists_by_email(&user.email).await? {
+            return Err(ApiError::UserAlreadyExists);
+        }
+        
+        self.repository.restore(ctx, id).await?;
+        Ok(())
     }
 
     /// Get users by account ID
     pub async fn get_by_account_id(&self, account_id: Uuid) -> Result<Vec<User>, ApiError> {
+        // Make sure the tenant exists rather than returning an empty list
+        self.account_repository.get_by_id(account_id).await?;
         self.repository.get_by_account_id(account_id).await
     }
 
-    /// Get users by branch ID
-    pub async fn get_by_branch_id(&self, branch_id: Uuid) -> Result<Vec<User>, ApiError> {
+    /// Get users working at a branch, by home branch or assignment
+    pub async fn get_by_branch_id(&self, actor: &UserInfo, branch_id: Uuid) -> Result<Vec<User>, ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+        if Self::account_scope(actor)?.is_some_and(|own| own != branch.account_id) {
+            return Err(ApiError::NotFound("Branch not found".to_string()));
+        }
         self.repository.get_by_branch_id(branch_id).await
     }
 
-    /// Get users by role
-    pub async fn get_by_role(&self, role: &str) -> Result<Vec<User>, ApiError> {
-        self.repository.get_by_role(role).await
+    /// Get users by role within the caller's account (every account for root)
+    pub async fn get_by_role(&self, actor: &UserInfo, role: &str) -> Result<Vec<User>, ApiError> {
+        self.repository.get_by_role(Self::account_scope(actor)?, role).await
+    }
+
+    /// Root sees every account, everyone else only their own
+    pub fn account_scope(actor: &UserInfo) -> Result<Option<Uuid>, ApiError> {
+        if actor.parsed_role()? == UserRole::Root {
+            Ok(None)
+        } else {
+            actor.parsed_account_id().map(Some)
+        }
+    }
+
+    /// Fetch a user, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid, include_deleted: bool) -> Result<User, ApiError> {
+        let user = if include_deleted {
+            self.repository.get_by_id_with_deleted(id).await?
+        } else {
+            self.repository.get_by_id(id).await?
+        };
+
+        if Self::account_scope(actor)?.is_some_and(|own| own != user.account_id) {
+            return Err(ApiError::UserNotFound);
+        }
+
+        Ok(user)
+    }
+
+    /// Only root and callers outranking a user may change them
+    fn ensure_can_manage(&self, actor: &UserInfo, user: &User) -> Result<(), ApiError> {
+        let role = actor.parsed_role()?;
+        let target: UserRole = user.role.parse().map_err(ApiError::InvalidInput)?;
+
+        if role != UserRole::Root && !role.outranks(&target) {
+            return Err(ApiError::Forbidden(format!("Cannot manage users with role {}", target)));
+        }
+
+        Ok(())
+    }
+
+    /// Only roles strictly below the caller's may be handed out
+    fn ensure_can_grant(&self, actor: &UserInfo, role: &str) -> Result<(), ApiError> {
+        let role: UserRole = role.parse().map_err(ApiError::InvalidInput)?;
+
+        if !actor.parsed_role()?.outranks(&role) {
+            return Err(ApiError::Forbidden(format!("Cannot grant role {}", role)));
+        }
+
+        Ok(())
+    }
+
+    /// Check if a user exists by email
+    pub async fn exists_by_email(&self, email: &str) -> Result<bool, ApiError> {
+        self.repository.exists_by_email(email).await
+    }
+
+    /// Reject branches that do not exist or belong to another account
+    pub async fn ensure_branch_in_account(&self, branch_id: Uuid, account_id: Uuid) -> Result<(), ApiError> {
+        let branch = self.branch_repository.get_by_id(branch_id).await?;
+        if branch.account_id != account_id {
+            return Err(ApiError::InvalidInput("Branch does not belong to the user's account".to_string()));
+        }
+        Ok(())
     }
 
     /// Hash a password using Argon2
-    fn hash_password(&self, password: &str) -> Result<String, ApiError> {
+    pub fn hash_password(&self, password: &str) -> Result<String, ApiError> {
         let salt = SaltString::generate(&mut OsRng);
         let argon2 = Argon2::default();
         
@@ -0,0 +1,141 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    common::{pagination::Page, ApiError},
+    modules::webhook::entity::{
+        delivery, CreateWebhookRequest, DeliveryQuery, Model as Webhook, UpdateWebhookRequest, WebhookWithSecret,
+    },
+    common::{AppState, RequestContext, session::SessionUser},
+};
+
+/// List webhook subscriptions of the account
+pub async fn get_all(
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    info!("Fetching webhooks");
+    let result = state.webhook_service.get_all(&user).await?;
+    Ok(Json(result))
+}
+
+/// Get a specific webhook subscription by ID
+pub async fn get_by_id(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Webhook>, ApiError> {
+    info!("Fetching webhook with ID: {}", id);
+    let result = state.webhook_service.get_by_id(&user, id).await?;
+    Ok(Json(result))
+}
+
+/// Subscribe a URL to events of the account
+pub async fn create(
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookWithSecret>), ApiError> {
+    info!("Creating webhook");
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.webhook_service.create(&ctx, &user, payload).await?;
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+/// Update a webhook subscription
+pub async fn update(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<Webhook>, ApiError> {
+    info!("Updating webhook with ID: {}", id);
+
+    // Validate the request
+    payload.validate()
+        .map_err(|e| ApiError::InvalidInput(format!("Validation error: {}", e)))?;
+
+    let result = state.webhook_service.update(&ctx, &user, id, payload).await?;
+    Ok(Json(result))
+}
+
+/// Delete a webhook subscription
+pub async fn delete_webhook(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<StatusCode, ApiError> {
+    info!("Deleting webhook with ID: {}", id);
+    state.webhook_service.delete(&ctx, &user, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Replace the signing secret of a webhook subscription
+pub async fn rotate_secret(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<Json<WebhookWithSecret>, ApiError> {
+    info!("Rotating secret of webhook {}", id);
+    let result = state.webhook_service.rotate_secret(&ctx, &user, id).await?;
+    Ok(Json(result))
+}
+
+/// Send a test event to a webhook subscription
+pub async fn test(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<(StatusCode, Json<delivery::Model>), ApiError> {
+    info!("Testing webhook {}", id);
+    let result = state.webhook_service.test(&user, id).await?;
+    Ok((StatusCode::ACCEPTED, Json(result)))
+}
+
+/// Delivery log of a webhook subscription
+pub async fn get_deliveries(
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeliveryQuery>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<Page<delivery::Model>>, ApiError> {
+    info!("Fetching deliveries of webhook {}", id);
+    let result = state.webhook_service.get_deliveries(&user, id, query).await?;
+    Ok(Json(result))
+}
+
+/// Get one delivery of a webhook subscription
+pub async fn get_delivery(
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    SessionUser(user): SessionUser,
+) -> Result<Json<delivery::Model>, ApiError> {
+    let result = state.webhook_service.get_delivery(&user, id, delivery_id).await?;
+    Ok(Json(result))
+}
+
+/// Send a delivery again
+pub async fn replay(
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    ctx: RequestContext,
+    SessionUser(user): SessionUser,
+) -> Result<(StatusCode, Json<delivery::Model>), ApiError> {
+    info!("Replaying delivery {} of webhook {}", delivery_id, id);
+    let result = state.webhook_service.replay(&ctx, &user, id, delivery_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(result)))
+}
@@ -0,0 +1,244 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// A URL of an account receiving its events
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Deserialize)]
+#[sea_orm(table_name = "webhook_subscriptions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub url: String,
+    pub description: Option<String>,
+    pub events: Vec<String>,
+    /// Key the payloads are signed with
+    pub secret: String,
+    pub status: String,
+    /// Failed attempts since the last successful one
+    pub consecutive_failures: i32,
+    pub disabled_at: Option<DateTimeWithTimeZone>,
+    pub disabled_reason: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTimeWithTimeZone,
+    pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Serialize for Model {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        // The secret is only shown when it is created or rotated
+        let mut state = serializer.serialize_struct("WebhookSubscription", 12)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("account_id", &self.account_id)?;
+        state.serialize_field("url", &self.url)?;
+        state.serialize_field("description", &self.description)?;
+        state.serialize_field("events", &self.events)?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("consecutive_failures", &self.consecutive_failures)?;
+        state.serialize_field("disabled_at", &self.disabled_at)?;
+        state.serialize_field("disabled_reason", &self.disabled_reason)?;
+        state.serialize_field("created_by", &self.created_by)?;
+        state.serialize_field("created_at", &self.created_at)?;
+        state.serialize_field("updated_at", &self.updated_at)?;
+        state.end()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// One event sent, or to be sent, to one subscription
+pub mod delivery {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+    #[sea_orm(table_name = "webhook_deliveries")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub subscription_id: Uuid,
+        /// Shared by every delivery of the same event, including replays
+        pub event_id: Uuid,
+        pub event_type: String,
+        /// The JSON body posted to the subscription
+        #[sea_orm(column_type = "JsonBinary")]
+        pub payload: Json,
+        pub status: String,
+        pub attempts: i32,
+        pub next_attempt_at: DateTimeWithTimeZone,
+        /// HTTP status and start of the body of the last answer
+        pub response_status: Option<i32>,
+        pub response_body: Option<String>,
+        pub duration_ms: Option<i32>,
+        pub last_error: Option<String>,
+        pub replay_of: Option<Uuid>,
+        pub delivered_at: Option<DateTimeWithTimeZone>,
+        pub created_at: DateTimeWithTimeZone,
+        pub updated_at: DateTimeWithTimeZone,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+/// Events accounts can subscribe to
+pub const WEBHOOK_EVENTS: [&str; 3] = ["user.created", "order.paid", "reservation.created"];
+
+/// Event sent by `POST /webhooks/{id}/test`, whatever the subscription's events
+pub const TEST_EVENT: &str = "webhook.test";
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionStatus {
+    Active,
+    /// Turned off by hand or after too many failures; receives nothing
+    Disabled,
+}
+
+impl std::fmt::Display for SubscriptionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SubscriptionStatus::Active => write!(f, "ACTIVE"),
+            SubscriptionStatus::Disabled => write!(f, "DISABLED"),
+        }
+    }
+}
+
+impl std::str::FromStr for SubscriptionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ACTIVE" => Ok(SubscriptionStatus::Active),
+            "DISABLED" => Ok(SubscriptionStatus::Disabled),
+            _ => Err(format!("Webhook status {} is not valid", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Succeeded,
+    /// Given up after the last attempt
+    Failed,
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryStatus::Pending => write!(f, "PENDING"),
+            DeliveryStatus::Succeeded => write!(f, "SUCCEEDED"),
+            DeliveryStatus::Failed => write!(f, "FAILED"),
+        }
+    }
+}
+
+impl std::str::FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(DeliveryStatus::Pending),
+            "SUCCEEDED" => Ok(DeliveryStatus::Succeeded),
+            "FAILED" => Ok(DeliveryStatus::Failed),
+            _ => Err(format!("Delivery status {} is not valid", s)),
+        }
+    }
+}
+
+// Validators
+fn validate_events(events: &[String]) -> Result<(), ValidationError> {
+    if events.is_empty() {
+        return Err(ValidationError::new("events").with_message("Subscribe to at least one event".into()));
+    }
+    match events.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+        Some(e) => Err(ValidationError::new("events").with_message(format!("Event {} is not valid", e).into())),
+        None => Ok(()),
+    }
+}
+
+fn validate_status(status: &str) -> Result<(), ValidationError> {
+    status
+        .parse::<SubscriptionStatus>()
+        .map(|_| ())
+        .map_err(|e| ValidationError::new("status").with_message(e.into()))
+}
+
+// Request/Response DTOs
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct CreateWebhookRequest {
+    #[validate(url(message = "URL must be valid"), length(max = 2000, message = "URL must be at most 2000 characters"))]
+    pub url: String,
+
+    #[validate(length(max = 200, message = "Description must be at most 200 characters"))]
+    pub description: Option<String>,
+
+    #[validate(custom(function = "validate_events"))]
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct UpdateWebhookRequest {
+    #[validate(url(message = "URL must be valid"), length(max = 2000, message = "URL must be at most 2000 characters"))]
+    pub url: Option<String>,
+
+    #[validate(length(max = 200, message = "Description must be at most 200 characters"))]
+    pub description: Option<String>,
+
+    #[validate(custom(function = "validate_events"))]
+    pub events: Option<Vec<String>>,
+
+    /// `ACTIVE` re-enables a disabled subscription and clears its failures
+    #[validate(custom(function = "validate_status"))]
+    pub status: Option<String>,
+}
+
+/// A subscription with its signing secret, shown once
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookWithSecret {
+    #[serde(flatten)]
+    pub subscription: Model,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct DeliveryQuery {
+    pub status: Option<String>,
+    pub event_type: Option<String>,
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+}
+
+/// A delivery about to be queued
+#[derive(Debug, Clone)]
+pub struct NewDelivery {
+    pub account_id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub replay_of: Option<Uuid>,
+}
+
+/// What came back from one attempt
+#[derive(Debug, Clone)]
+pub struct AttemptResult {
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub duration_ms: i32,
+    pub error: Option<String>,
+}
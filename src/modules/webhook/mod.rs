@@ -0,0 +1,6 @@
+pub mod entity;
+pub mod controller;
+pub mod service;
+pub mod repository;
+pub mod route;
+pub mod subscriber;
@@ -0,0 +1,452 @@
+use anyhow::Result;
+use sea_orm::{
+    sea_query::{Expr, LockBehavior, LockType, Query}, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{info, error};
+
+use crate::{
+    modules::webhook::entity::{
+        delivery, ActiveModel, AttemptResult, Column, CreateWebhookRequest, DeliveryQuery, DeliveryStatus, Entity as WebhookEntity,
+        Model as Webhook, NewDelivery, SubscriptionStatus, UpdateWebhookRequest,
+    },
+    common::{outbox, ApiError},
+};
+
+/// Webhook repository for database operations
+#[derive(Debug, Clone)]
+pub struct WebhookRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookRepository {
+    /// Create a new webhook repository
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Get the webhook subscriptions of an account
+    pub async fn get_by_account_id(&self, account_id: Uuid) -> Result<Vec<Webhook>, ApiError> {
+        WebhookEntity::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::DeletedAt.is_null())
+            .order_by_asc(Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch webhooks of account {}: {}", account_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Get a webhook subscription by ID
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Webhook, ApiError> {
+        let webhook = WebhookEntity::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch webhook with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match webhook {
+            Some(webhook) => Ok(webhook),
+            None => Err(ApiError::NotFound("Webhook not found".to_string())),
+        }
+    }
+
+    /// Create a webhook subscription
+    pub async fn create(&self, account_id: Uuid, created_by: Uuid, request: CreateWebhookRequest, secret: String) -> Result<Webhook, ApiError> {
+        info!("Creating webhook for account {}", account_id);
+
+        let now = chrono::Utc::now().fixed_offset();
+        let webhook = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(account_id),
+            url: Set(request.url),
+            description: Set(request.description),
+            events: Set(request.events),
+            secret: Set(secret),
+            status: Set(SubscriptionStatus::Active.to_string()),
+            consecutive_failures: Set(0),
+            disabled_at: Set(None),
+            disabled_reason: Set(None),
+            created_by: Set(created_by),
+            created_at: Set(now),
+            updated_at: Set(now),
+            deleted_at: Set(None),
+        };
+
+        webhook.insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to create webhook: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Update a webhook subscription; re-enabling it clears its failures
+    pub async fn update(&self, id: Uuid, request: UpdateWebhookRequest) -> Result<Webhook, ApiError> {
+        info!("Updating webhook with ID: {}", id);
+
+        let mut webhook: ActiveModel = self.get_by_id(id).await?.into();
+        if let Some(url) = request.url {
+            webhook.url = Set(url);
+        }
+        if let Some(description) = request.description {
+            webhook.description = Set(Some(description));
+        }
+        if let Some(events) = request.events {
+            webhook.events = Set(events);
+        }
+        match request.status.as_deref().map(str::parse::<SubscriptionStatus>) {
+            Some(Ok(SubscriptionStatus::Active)) => {
+                webhook.status = Set(SubscriptionStatus::Active.to_string());
+                webhook.consecutive_failures = Set(0);
+                webhook.disabled_at = Set(None);
+                webhook.disabled_reason = Set(None);
+            }
+            Some(Ok(SubscriptionStatus::Disabled)) => {
+                webhook.status = Set(SubscriptionStatus::Disabled.to_string());
+                webhook.disabled_at = Set(Some(chrono::Utc::now().fixed_offset()));
+                webhook.disabled_reason = Set(Some("Disabled by hand".to_string()));
+            }
+            _ => {}
+        }
+        webhook.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        webhook.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to update webhook with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Replace the signing secret of a webhook subscription
+    pub async fn rotate_secret(&self, id: Uuid, secret: String) -> Result<Webhook, ApiError> {
+        info!("Rotating secret of webhook {}", id);
+
+        let mut webhook: ActiveModel = self.get_by_id(id).await?.into();
+        webhook.secret = Set(secret);
+        webhook.updated_at = Set(chrono::Utc::now().fixed_offset());
+
+        webhook.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to rotate secret of webhook {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Soft delete a webhook subscription; its delivery log is kept
+    pub async fn soft_delete(&self, id: Uuid) -> Result<Webhook, ApiError> {
+        info!("Soft deleting webhook with ID: {}", id);
+
+        let mut webhook: ActiveModel = self.get_by_id(id).await?.into();
+        let now = chrono::Utc::now().fixed_offset();
+        webhook.deleted_at = Set(Some(now));
+        webhook.updated_at = Set(now);
+
+        webhook.update(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to soft delete webhook {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Count a successful attempt of a subscription, or a failed one; returns the failures in a row
+    pub async fn record_outcome(&self, id: Uuid, succeeded: bool) -> Result<i32, ApiError> {
+        let failures = if succeeded {
+            Expr::value(0)
+        } else {
+            Expr::col(Column::ConsecutiveFailures).add(1)
+        };
+
+        WebhookEntity::update_many()
+            .col_expr(Column::ConsecutiveFailures, failures)
+            .filter(Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record outcome of webhook {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        let webhook = WebhookEntity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        Ok(webhook.map_or(0, |webhook| webhook.consecutive_failures))
+    }
+
+    /// Disable an active subscription; returns whether this call disabled it
+    pub async fn disable(&self, id: Uuid, reason: String) -> Result<bool, ApiError> {
+        let result = WebhookEntity::update_many()
+            .col_expr(Column::Status, Expr::value(SubscriptionStatus::Disabled.to_string()))
+            .col_expr(Column::DisabledAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .col_expr(Column::DisabledReason, Expr::value(reason))
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(SubscriptionStatus::Active.to_string()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to disable webhook {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Search the deliveries of a subscription, newest first, returning one page and the total count
+    pub async fn search_deliveries(
+        &self,
+        subscription_id: Uuid,
+        query: &DeliveryQuery,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<delivery::Model>, u64), ApiError> {
+        let mut select = delivery::Entity::find().filter(delivery::Column::SubscriptionId.eq(subscription_id));
+        if let Some(ref status) = query.status {
+            select = select.filter(delivery::Column::Status.eq(status.as_str()));
+        }
+        if let Some(ref event_type) = query.event_type {
+            select = select.filter(delivery::Column::EventType.eq(event_type.as_str()));
+        }
+
+        let paginator = select
+            .order_by_desc(delivery::Column::CreatedAt)
+            .paginate(&self.db, per_page);
+
+        let total = paginator.num_items().await.map_err(|e| {
+            error!("Failed to count deliveries of webhook {}: {}", subscription_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        let deliveries = paginator.fetch_page(page - 1).await.map_err(|e| {
+            error!("Failed to fetch deliveries of webhook {}: {}", subscription_id, e);
+            ApiError::DatabaseError(e.to_string())
+        })?;
+
+        Ok((deliveries, total))
+    }
+
+    /// Get a delivery by ID
+    pub async fn get_delivery(&self, id: Uuid) -> Result<delivery::Model, ApiError> {
+        let delivery = delivery::Entity::find_by_id(id)
+            .one(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch webhook delivery with ID {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        match delivery {
+            Some(delivery) => Ok(delivery),
+            None => Err(ApiError::NotFound("Webhook delivery not found".to_string())),
+        }
+    }
+
+    /// Queue a delivery for the worker
+    pub async fn create_delivery(&self, new: NewDelivery) -> Result<delivery::Model, ApiError> {
+        Self::delivery_model(new)
+            .insert(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to queue webhook delivery: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Whether deliveries of an event were queued, not counting replays
+    pub async fn has_deliveries(&self, event_id: Uuid) -> Result<bool, ApiError> {
+        let count = delivery::Entity::find()
+            .filter(delivery::Column::EventId.eq(event_id))
+            .filter(delivery::Column::ReplayOf.is_null())
+            .count(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to look up deliveries of event {}: {}", event_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(count > 0)
+    }
+
+    /// Get the deliveries of events that are about a user or name them as customer, oldest first
+    pub async fn get_deliveries_about_user(&self, user_id: Uuid) -> Result<Vec<delivery::Model>, ApiError> {
+        delivery::Entity::find()
+            .filter(delivery::Column::EventId.in_subquery(Self::events_naming(user_id)))
+            .order_by_asc(delivery::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch deliveries about user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// Empty the event data and drop the receiver's response on deliveries about a user
+    pub async fn redact_deliveries_about_user(&self, user_id: Uuid) -> Result<u64, ApiError> {
+        info!("Redacting webhook deliveries about user {}", user_id);
+
+        let result = delivery::Entity::update_many()
+            .col_expr(delivery::Column::Payload, Expr::cust("jsonb_set(payload, '{data}', '{}'::jsonb)"))
+            .col_expr(delivery::Column::ResponseBody, Expr::value(Option::<String>::None))
+            .col_expr(delivery::Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+            .filter(delivery::Column::EventId.in_subquery(Self::events_naming(user_id)))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to redact deliveries about user {}: {}", user_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// IDs of the recorded events about a user or naming them as customer
+    fn events_naming(user_id: Uuid) -> sea_orm::sea_query::SelectStatement {
+        Query::select()
+            .column(outbox::entity::Column::Id)
+            .from(outbox::entity::Entity)
+            .cond_where(outbox::naming_user(user_id))
+            .to_owned()
+    }
+
+    /// Queue deliveries for the worker
+    pub async fn enqueue(&self, deliveries: Vec<NewDelivery>) -> Result<(), ApiError> {
+        if deliveries.is_empty() {
+            return Ok(());
+        }
+
+        delivery::Entity::insert_many(deliveries.into_iter().map(Self::delivery_model))
+            .exec_without_returning(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to queue webhook deliveries: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Take up to `limit` due deliveries, pushing their next attempt back by `lease` so other workers skip them
+    /// while they are sent. Rows another worker is claiming at the same moment are skipped, not waited for.
+    pub async fn claim_due(&self, limit: u64, lease: chrono::Duration) -> Result<Vec<delivery::Model>, ApiError> {
+        let txn = self.db.begin().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        let now = chrono::Utc::now().fixed_offset();
+
+        let due = delivery::Entity::find()
+            .filter(delivery::Column::Status.eq(DeliveryStatus::Pending.to_string()))
+            .filter(delivery::Column::NextAttemptAt.lte(now))
+            .order_by_asc(delivery::Column::NextAttemptAt)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                error!("Failed to claim due webhook deliveries: {}", e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        if !due.is_empty() {
+            delivery::Entity::update_many()
+                .col_expr(delivery::Column::NextAttemptAt, Expr::value(now + lease))
+                .filter(delivery::Column::Id.is_in(due.iter().map(|delivery| delivery.id)))
+                .exec(&txn)
+                .await
+                .map_err(|e| {
+                    error!("Failed to lease due webhook deliveries: {}", e);
+                    ApiError::DatabaseError(e.to_string())
+                })?;
+        }
+
+        txn.commit().await.map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        Ok(due)
+    }
+
+    /// Record an attempt: succeeded without an error, due again at `retry_at`, or failed for good without it
+    pub async fn record_attempt(
+        &self,
+        id: Uuid,
+        attempts: i32,
+        result: AttemptResult,
+        retry_at: Option<DateTimeWithTimeZone>,
+    ) -> Result<(), ApiError> {
+        let now = chrono::Utc::now().fixed_offset();
+        let status = match (&result.error, retry_at) {
+            (None, _) => DeliveryStatus::Succeeded,
+            (Some(_), Some(_)) => DeliveryStatus::Pending,
+            (Some(_), None) => DeliveryStatus::Failed,
+        };
+
+        let mut update = delivery::Entity::update_many()
+            .col_expr(delivery::Column::Status, Expr::value(status.to_string()))
+            .col_expr(delivery::Column::Attempts, Expr::value(attempts))
+            .col_expr(delivery::Column::ResponseStatus, Expr::value(result.response_status))
+            .col_expr(delivery::Column::ResponseBody, Expr::value(result.response_body))
+            .col_expr(delivery::Column::DurationMs, Expr::value(result.duration_ms))
+            .col_expr(delivery::Column::LastError, Expr::value(result.error));
+        update = match (status, retry_at) {
+            (DeliveryStatus::Succeeded, _) => update.col_expr(delivery::Column::DeliveredAt, Expr::value(now)),
+            (_, Some(retry_at)) => update.col_expr(delivery::Column::NextAttemptAt, Expr::value(retry_at)),
+            _ => update,
+        };
+
+        update
+            .filter(delivery::Column::Id.eq(id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to record attempt of webhook delivery {}: {}", id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Give up the pending deliveries of a subscription that no longer receives anything
+    pub async fn fail_pending(&self, subscription_id: Uuid, reason: &str) -> Result<u64, ApiError> {
+        let result = delivery::Entity::update_many()
+            .col_expr(delivery::Column::Status, Expr::value(DeliveryStatus::Failed.to_string()))
+            .col_expr(delivery::Column::LastError, Expr::value(reason))
+            .filter(delivery::Column::SubscriptionId.eq(subscription_id))
+            .filter(delivery::Column::Status.eq(DeliveryStatus::Pending.to_string()))
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to give up deliveries of webhook {}: {}", subscription_id, e);
+                ApiError::DatabaseError(e.to_string())
+            })?;
+
+        Ok(result.rows_affected)
+    }
+
+    fn delivery_model(new: NewDelivery) -> delivery::ActiveModel {
+        let now = chrono::Utc::now().fixed_offset();
+        delivery::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            account_id: Set(new.account_id),
+            subscription_id: Set(new.subscription_id),
+            event_id: Set(new.event_id),
+            event_type: Set(new.event_type),
+            payload: Set(new.payload),
+            status: Set(DeliveryStatus::Pending.to_string()),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            response_status: Set(None),
+            response_body: Set(None),
+            duration_ms: Set(None),
+            last_error: Set(None),
+            replay_of: Set(new.replay_of),
+            delivered_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+    }
+}
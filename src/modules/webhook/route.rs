@@ -0,0 +1,22 @@
+use axum::{
+    routing::{get, post},
+    Router, middleware,
+};
+
+use crate::common::AppState;
+use crate::modules::auth::middleware::authorize;
+
+use super::controller::*;
+
+/// Create webhook administration routes (general manager and above)
+pub fn create_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/webhooks", get(get_all).post(create))
+        .route("/webhooks/:id", get(get_by_id).put(update).delete(delete_webhook))
+        .route("/webhooks/:id/rotate-secret", post(rotate_secret))
+        .route("/webhooks/:id/test", post(test))
+        .route("/webhooks/:id/deliveries", get(get_deliveries))
+        .route("/webhooks/:id/deliveries/:delivery_id", get(get_delivery))
+        .route("/webhooks/:id/deliveries/:delivery_id/replay", post(replay))
+        .layer(middleware::from_fn(authorize(vec!["ROOT", "GENERAL_MANAGER"])))
+}
@@ -0,0 +1,678 @@
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use uuid::Uuid;
+use tracing::{error, info, warn};
+
+use crate::{
+    common::{
+        config::WebhookConfig,
+        outbox::DomainEvent,
+        pagination::{self, Page},
+        signing, ApiError, RequestContext,
+    },
+    modules::{
+        audit::{
+            entity::{AuditAction, AuditTarget},
+            service::AuditService,
+        },
+        auth::entity::UserInfo,
+        notification::{entity::NotificationKind, service::NotificationService},
+        webhook::{
+            entity::{
+                delivery, AttemptResult, CreateWebhookRequest, DeliveryQuery, DeliveryStatus, Model as Webhook, NewDelivery,
+                SubscriptionStatus, UpdateWebhookRequest, WebhookWithSecret, TEST_EVENT,
+            },
+            repository::WebhookRepository,
+        },
+    },
+};
+
+/// Prefix of generated signing secrets
+const SECRET_PREFIX: &str = "whsec_";
+
+/// Characters of a receiver's answer kept in the delivery log
+const RESPONSE_EXCERPT_CHARS: usize = 1000;
+
+/// Webhook service layer for business logic
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    repository: WebhookRepository,
+    notification_service: NotificationService,
+    audit_service: AuditService,
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookService {
+    /// Create a new webhook service
+    pub fn new(
+        repository: WebhookRepository,
+        notification_service: NotificationService,
+        audit_service: AuditService,
+        config: &WebhookConfig,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            // A redirect could send the signed payload somewhere the account never registered
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+
+        Self { repository, notification_service, audit_service, client, config: config.clone() }
+    }
+
+    /// Queue a domain event for every active subscription of its account listening to it. The webhook event takes
+    /// the domain event's ID, and an event queued before is skipped so a redelivered one is not sent twice.
+    pub async fn publish(&self, event: &DomainEvent) -> Result<(), ApiError> {
+        if self.repository.has_deliveries(event.id).await? {
+            info!("Webhooks of event {} were already queued", event.id);
+            return Ok(());
+        }
+
+        self.enqueue(event.account_id, &event.kind, event.id, event.created_at, event.payload.clone()).await
+    }
+
+    /// List the webhook subscriptions of the caller's account
+    pub async fn get_all(&self, actor: &UserInfo) -> Result<Vec<Webhook>, ApiError> {
+        self.repository.get_by_account_id(actor.parsed_account_id()?).await
+    }
+
+    /// Get a webhook subscription of the caller's account
+    pub async fn get_by_id(&self, actor: &UserInfo, id: Uuid) -> Result<Webhook, ApiError> {
+        self.get_owned(actor, id).await
+    }
+
+    /// Subscribe a URL to events of the caller's account, returning the signing secret once
+    pub async fn create(&self, ctx: &RequestContext, actor: &UserInfo, data: CreateWebhookRequest) -> Result<WebhookWithSecret, ApiError> {
+        info!("Creating webhook to {}", data.url);
+        self.ensure_url(&data.url).await?;
+
+        let secret = Self::generate_secret();
+        let webhook = self.repository
+            .create(actor.parsed_account_id()?, actor.parsed_id()?, data, secret.clone())
+            .await?;
+
+        self.audit(ctx, AuditAction::WebhookCreated, None, &webhook).await?;
+        Ok(WebhookWithSecret { subscription: webhook, secret })
+    }
+
+    /// Update a webhook subscription; setting it `ACTIVE` re-enables it after it was disabled
+    pub async fn update(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, data: UpdateWebhookRequest) -> Result<Webhook, ApiError> {
+        info!("Updating webhook with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        if let Some(ref url) = data.url {
+            self.ensure_url(url).await?;
+        }
+
+        let webhook = self.repository.update(id, data).await?;
+        if webhook.status == SubscriptionStatus::Disabled.to_string() {
+            self.repository.fail_pending(id, "Webhook disabled").await?;
+        }
+
+        self.audit(ctx, AuditAction::WebhookUpdated, Some(&before), &webhook).await?;
+        Ok(webhook)
+    }
+
+    /// Delete a webhook subscription, giving up its pending deliveries
+    pub async fn delete(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<(), ApiError> {
+        info!("Deleting webhook with ID: {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let webhook = self.repository.soft_delete(id).await?;
+        self.repository.fail_pending(id, "Webhook deleted").await?;
+
+        self.audit(ctx, AuditAction::WebhookDeleted, Some(&before), &webhook).await?;
+        Ok(())
+    }
+
+    /// Replace the signing secret, returning the new one once; deliveries sent from now on use it
+    pub async fn rotate_secret(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid) -> Result<WebhookWithSecret, ApiError> {
+        info!("Rotating secret of webhook {}", id);
+
+        let before = self.get_owned(actor, id).await?;
+        let secret = Self::generate_secret();
+        let webhook = self.repository.rotate_secret(id, secret.clone()).await?;
+
+        self.audit(ctx, AuditAction::WebhookSecretRotated, Some(&before), &webhook).await?;
+        Ok(WebhookWithSecret { subscription: webhook, secret })
+    }
+
+    /// Queue a `webhook.test` event for a subscription, whatever events it listens to
+    pub async fn test(&self, actor: &UserInfo, id: Uuid) -> Result<delivery::Model, ApiError> {
+        let webhook = self.get_active(actor, id).await?;
+
+        let event_id = Uuid::new_v4();
+        let data = serde_json::json!({ "webhook_id": webhook.id });
+        self.repository
+            .create_delivery(NewDelivery {
+                account_id: webhook.account_id,
+                subscription_id: webhook.id,
+                event_id,
+                event_type: TEST_EVENT.to_string(),
+                payload: Self::envelope(event_id, webhook.account_id, TEST_EVENT, chrono::Utc::now().fixed_offset(), data),
+                replay_of: None,
+            })
+            .await
+    }
+
+    /// Delivery log of a subscription, newest first
+    pub async fn get_deliveries(&self, actor: &UserInfo, id: Uuid, query: DeliveryQuery) -> Result<Page<delivery::Model>, ApiError> {
+        let webhook = self.get_owned(actor, id).await?;
+        if let Some(ref status) = query.status {
+            status.parse::<DeliveryStatus>().map_err(ApiError::InvalidInput)?;
+        }
+
+        let (page, per_page) = pagination::normalize(query.page, query.per_page);
+        let (items, total) = self.repository.search_deliveries(webhook.id, &query, page, per_page).await?;
+        Ok(Page { items, page, per_page, total })
+    }
+
+    /// Get one delivery of a subscription
+    pub async fn get_delivery(&self, actor: &UserInfo, id: Uuid, delivery_id: Uuid) -> Result<delivery::Model, ApiError> {
+        let webhook = self.get_owned(actor, id).await?;
+        self.get_owned_delivery(&webhook, delivery_id).await
+    }
+
+    /// Send a delivery again as a new delivery with the same event ID and payload
+    pub async fn replay(&self, ctx: &RequestContext, actor: &UserInfo, id: Uuid, delivery_id: Uuid) -> Result<delivery::Model, ApiError> {
+        info!("Replaying webhook delivery {}", delivery_id);
+
+        let webhook = self.get_active(actor, id).await?;
+        let original = self.get_owned_delivery(&webhook, delivery_id).await?;
+        if original.status == DeliveryStatus::Pending.to_string() {
+            return Err(ApiError::Conflict("Delivery is still being attempted".to_string()));
+        }
+
+        let replay = self.repository
+            .create_delivery(NewDelivery {
+                account_id: original.account_id,
+                subscription_id: original.subscription_id,
+                event_id: original.event_id,
+                event_type: original.event_type.clone(),
+                payload: original.payload.clone(),
+                replay_of: Some(original.id),
+            })
+            .await?;
+
+        self.audit_service
+            .record(ctx, webhook.account_id, AuditAction::WebhookDeliveryReplayed, (AuditTarget::Webhook, Some(webhook.id)), Some(&original), Some(&replay))
+            .await?;
+        Ok(replay)
+    }
+
+    /// Send the due deliveries, returning how many were attempted
+    pub async fn process_due(&self) -> Result<usize, ApiError> {
+        // Long enough for the whole batch to time out one by one before another worker may pick it up
+        let lease = chrono::Duration::seconds((self.config.timeout_seconds * self.config.batch_size.max(1) + 60) as i64);
+        let due = self.repository.claim_due(self.config.batch_size, lease).await?;
+
+        for delivery in &due {
+            self.attempt(delivery).await?;
+        }
+        Ok(due.len())
+    }
+
+    /// Run `process_due` periodically in the background
+    pub fn spawn_worker(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.process_due().await {
+                    error!("Failed to process webhook deliveries: {}", e);
+                }
+            }
+        })
+    }
+
+    async fn attempt(&self, delivery: &delivery::Model) -> Result<(), ApiError> {
+        let attempts = delivery.attempts + 1;
+
+        let webhook = match self.repository.get_by_id(delivery.subscription_id).await {
+            Ok(webhook) if webhook.status == SubscriptionStatus::Active.to_string() => webhook,
+            Ok(_) => return self.give_up(delivery, attempts, "Webhook disabled").await,
+            Err(ApiError::NotFound(_)) => return self.give_up(delivery, attempts, "Webhook deleted").await,
+            Err(e) => return Err(e),
+        };
+
+        let result = self.send(&webhook, delivery).await;
+        let succeeded = result.error.is_none();
+
+        let retry_at = match result.error {
+            None => None,
+            Some(ref reason) => {
+                let retry_at = (attempts < self.config.max_attempts)
+                    .then(|| chrono::Utc::now().fixed_offset() + self.backoff(attempts));
+                match retry_at {
+                    Some(retry_at) => warn!("Webhook delivery {} failed, retrying at {}: {}", delivery.id, retry_at, reason),
+                    None => error!("Webhook delivery {} failed after {} attempts: {}", delivery.id, attempts, reason),
+                }
+                retry_at
+            }
+        };
+        self.repository.record_attempt(delivery.id, attempts, result, retry_at).await?;
+
+        let failures = self.repository.record_outcome(webhook.id, succeeded).await?;
+        if !succeeded && failures >= self.config.disable_after_failures {
+            self.disable(&webhook, failures).await?;
+        }
+        Ok(())
+    }
+
+    /// Post a delivery to its subscription, signed with the subscription's secret
+    async fn send(&self, webhook: &Webhook, delivery: &delivery::Model) -> AttemptResult {
+        // The host may have been pointed somewhere internal since the subscription was made
+        if let Err(e) = self.ensure_url(&webhook.url).await {
+            return AttemptResult { response_status: None, response_body: None, duration_ms: 0, error: Some(e.to_string()) };
+        }
+
+        let body = delivery.payload.to_string();
+        let timestamp = chrono::Utc::now().timestamp();
+        let signature = signing::hex_signature(&webhook.secret, format!("{}.{}", timestamp, body).as_bytes());
+        info!("Posting webhook delivery {} to {}", delivery.id, webhook.url);
+
+        let started = Instant::now();
+        let response = self.client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("Webhook-Id", delivery.event_id.to_string())
+            .header("Webhook-Event", &delivery.event_type)
+            .header("Webhook-Timestamp", timestamp.to_string())
+            .header("Webhook-Signature", format!("v1={}", signature))
+            .body(body)
+            .send()
+            .await;
+
+        let (response_status, response_body, error) = match response {
+            Ok(response) => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                let excerpt: String = text.chars().take(RESPONSE_EXCERPT_CHARS).collect();
+                let error = (!status.is_success()).then(|| format!("Receiver answered {}", status));
+                (Some(status.as_u16() as i32), Some(excerpt), error)
+            }
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+
+        AttemptResult {
+            response_status,
+            response_body,
+            duration_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+            error,
+        }
+    }
+
+    /// Turn a subscription off after too many failures in a row and tell the account's administrators
+    async fn disable(&self, webhook: &Webhook, failures: i32) -> Result<(), ApiError> {
+        let reason = format!("Disabled after {} failed attempts in a row", failures);
+        if !self.repository.disable(webhook.id, reason.clone()).await? {
+            return Ok(());
+        }
+        warn!("Webhook {} to {}: {}", webhook.id, webhook.url, reason);
+        self.repository.fail_pending(webhook.id, "Webhook disabled").await?;
+
+        let data = serde_json::json!({ "webhook_id": webhook.id, "url": webhook.url, "failures": failures });
+        self.notification_service
+            .notify_account_admins(webhook.account_id, NotificationKind::WebhookDisabled, data)
+            .await;
+        Ok(())
+    }
+
+    async fn give_up(&self, delivery: &delivery::Model, attempts: i32, reason: &str) -> Result<(), ApiError> {
+        let result = AttemptResult { response_status: None, response_body: None, duration_ms: 0, error: Some(reason.to_string()) };
+        self.repository.record_attempt(delivery.id, attempts, result, None).await
+    }
+
+    /// Wait before the next attempt, doubling with every failed one up to the configured maximum
+    fn backoff(&self, attempts: i32) -> chrono::Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1) as u32);
+        let seconds = self.config.backoff_seconds.saturating_mul(factor).min(self.config.max_backoff_seconds);
+        chrono::Duration::seconds(seconds as i64)
+    }
+
+    async fn enqueue(
+        &self,
+        account_id: Uuid,
+        event_type: &str,
+        event_id: Uuid,
+        created_at: DateTimeWithTimeZone,
+        data: serde_json::Value,
+    ) -> Result<(), ApiError> {
+        let subscribed: Vec<Webhook> = self.repository
+            .get_by_account_id(account_id)
+            .await?
+            .into_iter()
+            .filter(|webhook| webhook.status == SubscriptionStatus::Active.to_string())
+            .filter(|webhook| webhook.events.iter().any(|event| event == event_type))
+            .collect();
+        if subscribed.is_empty() {
+            return Ok(());
+        }
+
+        let payload = Self::envelope(event_id, account_id, event_type, created_at, data);
+
+        info!("Queueing {} event for {} webhooks of account {}", event_type, subscribed.len(), account_id);
+        let deliveries = subscribed
+            .into_iter()
+            .map(|webhook| NewDelivery {
+                account_id,
+                subscription_id: webhook.id,
+                event_id,
+                event_type: event_type.to_string(),
+                payload: payload.clone(),
+                replay_of: None,
+            })
+            .collect();
+        self.repository.enqueue(deliveries).await
+    }
+
+    /// The JSON body posted for an event
+    fn envelope(
+        event_id: Uuid,
+        account_id: Uuid,
+        event_type: &str,
+        created_at: DateTimeWithTimeZone,
+        data: serde_json::Value,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "id": event_id,
+            "type": event_type,
+            "account_id": account_id,
+            "created_at": created_at,
+            "data": data,
+        })
+    }
+
+    fn generate_secret() -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        format!("{}{}", SECRET_PREFIX, hex)
+    }
+
+    /// Accept https URLs of public hosts only; the development flag also lets in plain http and local receivers
+    async fn ensure_url(&self, url: &str) -> Result<(), ApiError> {
+        let invalid = |message: &str| ApiError::InvalidInput(message.to_string());
+
+        let parsed = reqwest::Url::parse(url).map_err(|_| invalid("Webhook URL is not a valid URL"))?;
+        if parsed.scheme() != "https" && !(self.config.allow_http && parsed.scheme() == "http") {
+            return Err(invalid("Webhook URL must be an https URL"));
+        }
+        if self.config.allow_http {
+            return Ok(());
+        }
+
+        let host = parsed.host_str().ok_or_else(|| invalid("Webhook URL has no host"))?;
+        let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+            Ok(ip) => vec![ip],
+            Err(_) => tokio::net::lookup_host((host, parsed.port_or_known_default().unwrap_or(443)))
+                .await
+                .map_err(|_| invalid("Webhook URL host cannot be resolved"))?
+                .map(|address| address.ip())
+                .collect(),
+        };
+
+        // Every address counts, the client may connect to any of them
+        if addresses.is_empty() || !addresses.into_iter().all(is_public) {
+            return Err(invalid("Webhook URL must not point at a loopback, link-local or private address"));
+        }
+        Ok(())
+    }
+
+    /// Fetch a subscription, hiding those of other accounts
+    async fn get_owned(&self, actor: &UserInfo, id: Uuid) -> Result<Webhook, ApiError> {
+        let webhook = self.repository.get_by_id(id).await?;
+
+        if webhook.account_id != actor.parsed_account_id()? {
+            return Err(ApiError::NotFound("Webhook not found".to_string()));
+        }
+
+        Ok(webhook)
+    }
+
+    async fn get_active(&self, actor: &UserInfo, id: Uuid) -> Result<Webhook, ApiError> {
+        let webhook = self.get_owned(actor, id).await?;
+        if webhook.status != SubscriptionStatus::Active.to_string() {
+            return Err(ApiError::Conflict("Webhook is disabled".to_string()));
+        }
+        Ok(webhook)
+    }
+
+    async fn get_owned_delivery(&self, webhook: &Webhook, delivery_id: Uuid) -> Result<delivery::Model, ApiError> {
+        let delivery = self.repository.get_delivery(delivery_id).await?;
+        if delivery.subscription_id != webhook.id {
+            return Err(ApiError::NotFound("Webhook delivery not found".to_string()));
+        }
+        Ok(delivery)
+    }
+
+    /// Record a webhook mutation in the audit log
+    async fn audit(&self, ctx: &RequestContext, action: AuditAction, before: Option<&Webhook>, after: &Webhook) -> Result<(), ApiError> {
+        self.audit_service
+            .record(ctx, after.account_id, action, (AuditTarget::Webhook, Some(after.id)), before, Some(after))
+            .await
+    }
+}
+
+/// Whether an address is reachable on the internet, not loopback, link-local, private or otherwise reserved
+fn is_public(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            // 0.0.0.0/8 is "this network" and 100.64.0.0/10 is shared carrier-grade NAT space
+            let reserved = first == 0 || (first == 100 && second & 0xc0 == 64);
+            !(reserved
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast())
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            // fc00::/7 is unique local, fe80::/10 link-local
+            let reserved = first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80;
+            !(reserved || ip.is_loopback() || ip.is_unspecified() || ip.is_multicast())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use hmac::{Hmac, Mac};
+    use sea_orm::DatabaseConnection;
+    use sha2::Sha256;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        common::{
+            config::{Config, ConfigSources},
+            mail::LogMailer,
+        },
+        modules::{
+            audit::repository::AuditRepository, notification::repository::NotificationRepository,
+            user::repository::UserRepository,
+        },
+    };
+
+    type Received = (HeaderMap, String);
+
+    fn service(allow_http: bool) -> WebhookService {
+        let sources = ConfigSources {
+            overrides: vec![("webhook.allow_http".to_string(), allow_http.to_string())],
+            ..Default::default()
+        };
+        let config = Config::load(&sources).unwrap();
+        let db = DatabaseConnection::Disconnected;
+        let notification_service = NotificationService::new(
+            NotificationRepository::new(db.clone()),
+            UserRepository::new(db.clone()),
+            Arc::new(LogMailer),
+            &config.notification,
+        );
+        WebhookService::new(WebhookRepository::new(db.clone()), notification_service, AuditService::new(AuditRepository::new(db)), &config.webhook)
+    }
+
+    fn webhook(url: String) -> Webhook {
+        let now = chrono::Utc::now().fixed_offset();
+        Webhook {
+            id: Uuid::new_v4(),
+            account_id: Uuid::new_v4(),
+            url,
+            description: None,
+            events: vec!["order.served".to_string()],
+            secret: WebhookService::generate_secret(),
+            status: SubscriptionStatus::Active.to_string(),
+            consecutive_failures: 0,
+            disabled_at: None,
+            disabled_reason: None,
+            created_by: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    fn delivery(webhook: &Webhook) -> delivery::Model {
+        let now = chrono::Utc::now().fixed_offset();
+        let event_id = Uuid::new_v4();
+        delivery::Model {
+            id: Uuid::new_v4(),
+            account_id: webhook.account_id,
+            subscription_id: webhook.id,
+            event_id,
+            event_type: "order.served".to_string(),
+            payload: WebhookService::envelope(event_id, webhook.account_id, "order.served", now, serde_json::json!({ "total": "12.50" })),
+            status: DeliveryStatus::Pending.to_string(),
+            attempts: 0,
+            next_attempt_at: now,
+            response_status: None,
+            response_body: None,
+            duration_ms: None,
+            last_error: None,
+            replay_of: None,
+            delivered_at: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// A receiver on a free local port passing on the headers and body of every request it gets
+    async fn receiver() -> (String, mpsc::UnboundedReceiver<Received>) {
+        let (sender, received) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route(
+                "/hooks",
+                post(|State(sender): State<mpsc::UnboundedSender<Received>>, headers: HeaderMap, body: String| async move {
+                    sender.send((headers, body)).ok();
+                    StatusCode::NO_CONTENT
+                }),
+            )
+            .with_state(sender);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.ok() });
+        (url, received)
+    }
+
+    /// Check a signature the way a receiver would, with the shared secret over the timestamp and raw body
+    fn verifies(secret: &str, timestamp: &str, body: &str, signature: &str) -> bool {
+        let Some(hex) = signature.strip_prefix("v1=") else {
+            return false;
+        };
+        let Ok(bytes) = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16)).collect::<Result<Vec<u8>, _>>() else {
+            return false;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", timestamp, body).as_bytes());
+        mac.verify_slice(&bytes).is_ok()
+    }
+
+    #[tokio::test]
+    async fn deliveries_carry_a_signature_the_receiver_can_verify() {
+        let (url, mut received) = receiver().await;
+        let webhook = webhook(url);
+        let delivery = delivery(&webhook);
+
+        let result = service(true).send(&webhook, &delivery).await;
+        assert_eq!(result.error, None);
+        assert_eq!(result.response_status, Some(204));
+
+        let (headers, body) = received.recv().await.unwrap();
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default().to_string();
+        assert_eq!(header("webhook-id"), delivery.event_id.to_string());
+        assert_eq!(header("webhook-event"), "order.served");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&body).unwrap(), delivery.payload);
+
+        let (timestamp, signature) = (header("webhook-timestamp"), header("webhook-signature"));
+        assert!(verifies(&webhook.secret, &timestamp, &body, &signature));
+        assert!(!verifies("whsec_someone-else", &timestamp, &body, &signature));
+        assert!(!verifies(&webhook.secret, &timestamp, &body.replace("12.50", "0.01"), &signature));
+        assert!(!verifies(&webhook.secret, "0", &body, &signature));
+    }
+
+    #[tokio::test]
+    async fn local_receivers_get_nothing_without_the_development_flag() {
+        let (url, mut received) = receiver().await;
+        let webhook = webhook(url);
+
+        let result = service(false).send(&webhook, &delivery(&webhook)).await;
+
+        assert!(result.error.is_some());
+        assert_eq!(result.response_status, None);
+        assert!(received.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn urls_must_be_https_and_public() {
+        let service = service(false);
+        for url in [
+            "http://93.184.215.14/hooks",
+            "https://127.0.0.1/hooks",
+            "https://localhost:8443/hooks",
+            "https://10.1.2.3/hooks",
+            "https://172.16.0.1/hooks",
+            "https://192.168.1.20/hooks",
+            "https://169.254.169.254/latest/meta-data",
+            "https://100.64.0.1/hooks",
+            "https://0.0.0.0/hooks",
+            "https://[::1]/hooks",
+            "https://[fd12:3456::1]/hooks",
+            "https://[fe80::1]/hooks",
+            "https://[::ffff:127.0.0.1]/hooks",
+            "not a url",
+        ] {
+            assert!(matches!(service.ensure_url(url).await, Err(ApiError::InvalidInput(_))), "{}", url);
+        }
+
+        assert!(service.ensure_url("https://93.184.215.14/hooks").await.is_ok());
+        assert!(service.ensure_url("https://[2606:2800:21f:cb07:6820:80da:af6b:8b2c]/hooks").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn the_development_flag_allows_plain_http_to_local_receivers() {
+        let service = service(true);
+
+        assert!(service.ensure_url("http://localhost:9000/hooks").await.is_ok());
+        assert!(service.ensure_url("http://192.168.1.20/hooks").await.is_ok());
+        assert!(matches!(service.ensure_url("ftp://localhost/hooks").await, Err(ApiError::InvalidInput(_))));
+    }
+}
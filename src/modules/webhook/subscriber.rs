@@ -0,0 +1,35 @@
+use crate::{
+    common::{
+        outbox::{DomainEvent, EventSubscriber},
+        ApiError,
+    },
+    modules::webhook::{entity::WEBHOOK_EVENTS, service::WebhookService},
+};
+
+/// Queues webhook deliveries for the domain events accounts can subscribe to
+#[derive(Debug, Clone)]
+pub struct WebhookSubscriber {
+    webhook_service: WebhookService,
+}
+
+impl WebhookSubscriber {
+    /// Create a new webhook subscriber
+    pub fn new(webhook_service: WebhookService) -> Self {
+        Self { webhook_service }
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSubscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn handles(&self, kind: &str) -> bool {
+        WEBHOOK_EVENTS.contains(&kind)
+    }
+
+    async fn handle(&self, event: &DomainEvent) -> Result<(), ApiError> {
+        self.webhook_service.publish(event).await
+    }
+}
@@ -2,10 +2,88 @@ use axum::{
     routing::get,
     Router, middleware,
 };
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 
 use crate::common::AppState;
-use crate::modules::user::route::create_routes as create_user_routes;
+use crate::modules::user::route::{
+    create_routes as create_user_routes,
+    create_admin_routes as create_user_admin_routes,
+};
 use crate::modules::auth::route::create_routes as create_auth_routes;
+use crate::modules::invitation::route::{
+    create_routes as create_invitation_routes,
+    create_public_routes as create_public_invitation_routes,
+};
+use crate::modules::privacy::route::create_routes as create_privacy_routes;
+use crate::modules::audit::route::create_routes as create_audit_routes;
+use crate::modules::account::route::{
+    create_routes as create_account_routes,
+    create_admin_routes as create_account_admin_routes,
+    create_public_routes as create_public_account_routes,
+};
+use crate::modules::branch::route::{
+    create_routes as create_branch_routes,
+    create_admin_routes as create_branch_admin_routes,
+};
+use crate::modules::menu::route::{
+    create_routes as create_menu_routes,
+    create_admin_routes as create_menu_admin_routes,
+};
+use crate::modules::table::route::{
+    create_routes as create_table_routes,
+    create_admin_routes as create_table_admin_routes,
+    create_floor_routes as create_table_floor_routes,
+    create_public_routes as create_public_table_routes,
+};
+use crate::modules::order::route::{
+    create_routes as create_order_routes,
+    create_checkout_routes as create_order_checkout_routes,
+};
+use crate::modules::station::route::{
+    create_routes as create_station_routes,
+    create_admin_routes as create_station_admin_routes,
+};
+use crate::modules::payment::route::{
+    create_routes as create_payment_routes,
+    create_admin_routes as create_payment_admin_routes,
+};
+use crate::modules::register::route::{
+    create_routes as create_register_routes,
+    create_admin_routes as create_register_admin_routes,
+};
+use crate::modules::reservation::route::{
+    create_routes as create_reservation_routes,
+    create_staff_routes as create_reservation_staff_routes,
+    create_public_routes as create_public_reservation_routes,
+};
+use crate::modules::review::route::{
+    create_routes as create_review_routes,
+    create_admin_routes as create_review_admin_routes,
+    create_public_routes as create_public_review_routes,
+};
+use crate::modules::inventory::route::{
+    create_routes as create_inventory_routes,
+    create_admin_routes as create_inventory_admin_routes,
+};
+use crate::modules::schedule::route::{
+    create_routes as create_schedule_routes,
+    create_admin_routes as create_schedule_admin_routes,
+};
+use crate::modules::receipt::route::{
+    create_routes as create_receipt_routes,
+    create_admin_routes as create_receipt_admin_routes,
+};
+use crate::modules::tax::route::{
+    create_routes as create_tax_routes,
+    create_admin_routes as create_tax_admin_routes,
+};
+use crate::modules::loyalty::route::{
+    create_routes as create_loyalty_routes,
+    create_admin_routes as create_loyalty_admin_routes,
+};
+use crate::modules::notification::route::create_routes as create_notification_routes;
+use crate::modules::realtime::route::create_routes as create_realtime_routes;
+use crate::modules::webhook::route::create_admin_routes as create_webhook_admin_routes;
 use crate::modules::auth::middleware::authenticate;
 
 /// Create the main application router
@@ -13,8 +91,54 @@ pub fn create_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health_check))
         .nest("/", create_auth_routes())
-        .nest("/", create_user_routes().layer(middleware::from_fn(authenticate)))
+        .nest("/", create_public_invitation_routes())
+        .nest("/", create_public_account_routes())
+        .nest("/", create_public_table_routes())
+        .nest("/", create_public_reservation_routes())
+        .nest("/", create_public_review_routes())
+        .nest("/", create_user_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_user_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_invitation_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_privacy_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_audit_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_account_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_account_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_branch_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_branch_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_menu_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_menu_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_table_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_table_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_table_floor_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_order_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_order_checkout_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_station_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_station_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_payment_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_payment_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_register_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_register_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_receipt_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_receipt_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_reservation_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_reservation_staff_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_review_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_review_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_inventory_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_inventory_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_schedule_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_schedule_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_tax_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_tax_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_loyalty_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_loyalty_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_notification_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_webhook_admin_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
+        .nest("/", create_realtime_routes().layer(middleware::from_fn_with_state(state.clone(), authenticate)))
         .with_state(state)
+        // Tag every request with an ID (kept if the client sent one) and echo it back
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
 }
 
 /// Health check endpoint